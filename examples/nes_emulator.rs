@@ -4,6 +4,7 @@ use egui_ui::gl_game::GlGameRenderer;
 use egui_ui::CheatToolUi;
 use nes_emulator::audio_ring::SpscRingBuffer;
 use nes_emulator::hud_toast::{draw_hud_toast_rgb24, show_hud_toast, HudToast};
+use nes_emulator::rom_search::{find_roms, rom_dirs};
 use nes_emulator::Nes;
 use sdl2::audio::{AudioCallback, AudioSpecDesired};
 use sdl2::event::Event;
@@ -268,24 +269,7 @@ fn main() -> Result<(), String> {
         }
 
         // Apply cheats every frame
-        {
-            let ram_len = nes.ram().len();
-            let mgr = &cheat_ui.cheat_search_ui.manager;
-            for entry in &mgr.entries {
-                if !entry.enabled {
-                    continue;
-                }
-                let addr = entry.address as usize;
-                if addr < ram_len {
-                    nes.ram_mut()[addr] = entry.value;
-                } else if let Some(sram) = nes.prg_ram_mut() {
-                    let sram_addr = addr - ram_len;
-                    if sram_addr < sram.len() {
-                        sram[sram_addr] = entry.value;
-                    }
-                }
-            }
-        }
+        nes.apply_cheats(&cheat_ui.cheat_search_ui.manager);
 
         // Safety net: discard only on extreme drift (OS clock vs audio hardware clock).
         // With NTSC-accurate frame timing, this should essentially never fire.
@@ -432,27 +416,15 @@ fn cheat_file_path(rom_path: &str) -> PathBuf {
 }
 
 fn select_rom() -> Result<String, Box<dyn std::error::Error>> {
-    use std::fs;
     use std::io::{self, Write};
 
-    let roms_path = Path::new("roms");
-    let mut rom_files = Vec::new();
-
-    if roms_path.exists() && roms_path.is_dir() {
-        for entry in fs::read_dir(roms_path)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_file() {
-                if let Some(ext) = path.extension() {
-                    if ext == "nes" {
-                        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                            rom_files.push((name.to_string(), path.to_string_lossy().to_string()));
-                        }
-                    }
-                }
-            }
-        }
-    }
+    let mut rom_files: Vec<(String, String)> = find_roms(&rom_dirs(&[]))
+        .into_iter()
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?.to_string();
+            Some((name, path.to_string_lossy().to_string()))
+        })
+        .collect();
 
     if rom_files.is_empty() {
         return Err("No ROM files found in 'roms' directory".into());
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use nes_emulator::cpu::reference::diff_against_reference;
+
+// The input is the program at $8000; memory and registers are seeded from it
+fuzz_target!(|program: &[u8]| {
+    if let Err(mismatch) = diff_against_reference(program) {
+        panic!("CPU differs from reference: {}", mismatch);
+    }
+});
@@ -0,0 +1,187 @@
+// Accuracy profiles: named bundles of the emulation toggles, so a player can
+// pick "accuracy", "balanced" or "fast" instead of learning each flag, and a
+// game that needs one can get it from the game DB (`gamedb.txt` beside the
+// ROM, keyed by ROM CRC32). CPU dummy reads and open bus are always exact and
+// not part of any profile.
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::Nes;
+
+pub const GAME_DB_FILE: &str = "gamedb.txt";
+
+/// Where the game DB for `rom` lives: `gamedb.txt` in the ROM's own
+/// directory, so it travels with the ROM set whatever the working
+/// directory is.
+pub fn game_db_path(rom: &str) -> PathBuf {
+    Path::new(rom)
+        .parent()
+        .unwrap_or(Path::new(""))
+        .join(GAME_DB_FILE)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AccuracyProfile {
+    /// Every hardware quirk the core emulates, OAM decay included.
+    Accuracy,
+    /// The defaults: hardware behaviour games rely on, without OAM decay.
+    #[default]
+    Balanced,
+    /// No sprite limit (and so no flicker), and scanlines converted on a
+    /// worker thread. Only speed-for-accuracy trades: PPU warm-up, which
+    /// games rely on at power-on, stays on as in every profile.
+    Fast,
+}
+
+impl AccuracyProfile {
+    pub const ALL: [AccuracyProfile; 3] = [
+        AccuracyProfile::Accuracy,
+        AccuracyProfile::Balanced,
+        AccuracyProfile::Fast,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|profile| profile.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AccuracyProfile::Accuracy => "accuracy",
+            AccuracyProfile::Balanced => "balanced",
+            AccuracyProfile::Fast => "fast",
+        }
+    }
+
+    pub fn settings(self) -> AccuracySettings {
+        match self {
+            AccuracyProfile::Accuracy => AccuracySettings {
+                sprite_limit: true,
+                oam_quirks: true,
+                ppu_warmup: true,
+                threaded_rendering: false,
+            },
+            AccuracyProfile::Balanced => AccuracySettings {
+                sprite_limit: true,
+                oam_quirks: false,
+                ppu_warmup: true,
+                threaded_rendering: false,
+            },
+            AccuracyProfile::Fast => AccuracySettings {
+                sprite_limit: false,
+                oam_quirks: false,
+                ppu_warmup: true,
+                threaded_rendering: true,
+            },
+        }
+    }
+}
+
+/// The toggles a profile sets. Individual command-line flags still
+/// override them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccuracySettings {
+    pub sprite_limit: bool,
+    pub oam_quirks: bool,
+    pub ppu_warmup: bool,
+    pub threaded_rendering: bool,
+}
+
+impl AccuracySettings {
+    /// Apply to `nes`; call before `load_rom` so the warm-up setting
+    /// covers power-on.
+    pub fn apply(&self, nes: &mut Nes) {
+        nes.set_sprite_limit(self.sprite_limit);
+        nes.set_oam_quirks(self.oam_quirks);
+        nes.set_ppu_warmup(self.ppu_warmup);
+        nes.set_threaded_rendering(self.threaded_rendering);
+    }
+}
+
+/// Per-game profiles: one `<CRC32> <profile>` per line, hex CRC32 of the
+/// PRG+CHR data as in `Nes::rom_crc32`. Anything after `#` is a comment.
+#[derive(Debug, Clone, Default)]
+pub struct GameDb {
+    profiles: HashMap<u32, AccuracyProfile>,
+}
+
+impl GameDb {
+    /// Load `path`; a missing file is an empty DB.
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        std::fs::read_to_string(path)
+            .map(|text| Self::parse(&text))
+            .unwrap_or_default()
+    }
+
+    /// The DB beside `rom`, from `game_db_path`.
+    pub fn for_rom(rom: &str) -> Self {
+        Self::load(game_db_path(rom))
+    }
+
+    /// Lines that do not parse are skipped with a warning.
+    pub fn parse(text: &str) -> Self {
+        let mut profiles = HashMap::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let crc32 = fields
+                .next()
+                .and_then(|crc| u32::from_str_radix(crc, 16).ok());
+            let profile = fields.next().and_then(AccuracyProfile::from_name);
+            match (crc32, profile) {
+                (Some(crc32), Some(profile)) => {
+                    profiles.insert(crc32, profile);
+                }
+                _ => log::warn!("gamedb line {}: expected <CRC32> <profile>", number + 1),
+            }
+        }
+        GameDb { profiles }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.profiles.is_empty()
+    }
+
+    pub fn profile_for(&self, crc32: u32) -> Option<AccuracyProfile> {
+        self.profiles.get(&crc32).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_profiles_and_the_game_db() {
+        assert_eq!(
+            AccuracyProfile::from_name("FAST"),
+            Some(AccuracyProfile::Fast)
+        );
+        assert!(AccuracyProfile::from_name("turbo").is_none());
+        assert!(!AccuracyProfile::Fast.settings().sprite_limit);
+        assert!(AccuracyProfile::Accuracy.settings().oam_quirks);
+        assert!(AccuracyProfile::ALL
+            .iter()
+            .all(|profile| profile.settings().ppu_warmup));
+
+        let db = GameDb::parse(
+            "# CRC32   profile\n\
+             1A2B3C4D  accuracy  # needs OAM decay\n\
+             deadbeef  fast\n\
+             nonsense\n",
+        );
+        assert_eq!(db.profile_for(0x1A2B_3C4D), Some(AccuracyProfile::Accuracy));
+        assert_eq!(db.profile_for(0xDEAD_BEEF), Some(AccuracyProfile::Fast));
+        assert_eq!(db.profile_for(0), None);
+
+        assert_eq!(
+            game_db_path("roms/nes/Game (USA).nes"),
+            Path::new("roms/nes/gamedb.txt")
+        );
+        assert_eq!(game_db_path("game.nes"), Path::new("gamedb.txt"));
+    }
+}
@@ -1,6 +1,20 @@
+use crate::region::Region;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+/// Where the APU's output samples go. The APU runs the same whichever is
+/// picked, so frame IRQs and DMC fetches keep their timing without sound.
+#[derive(Clone, Default)]
+pub enum AudioSink {
+    /// Collected until `get_audio_buffer` drains them.
+    #[default]
+    Buffer,
+    /// Pushed into a ring the audio callback reads, as generated.
+    Ring(Arc<crate::audio_ring::SpscRingBuffer>),
+    /// Dropped, for headless runs with no audio output.
+    Null,
+}
+
 #[derive(Debug, Clone, Copy, Default)]
 pub struct AudioDiagFull {
     pub pulse1_enabled: bool,
@@ -151,13 +165,15 @@ pub struct Apu {
 
     // Audio output — samples are pushed directly to ring buffer when available,
     // or fall back to the Vec buffer.
-    audio_ring: Option<Arc<crate::audio_ring::SpscRingBuffer>>,
+    sink: AudioSink,
     output_buffer: Vec<f32>,
     sample_rate: f32,
     cpu_clock_rate: f32,
+    region: Region,
 
     // Fractional sample accumulator
     sample_counter: f32,
+    samples_produced: u64, // Running count for frame stats, not saved
 
     // Oversampling anti-aliasing: accumulate raw mixer output every CPU cycle,
     // then average when producing an output sample (~40x oversampling).
@@ -176,6 +192,21 @@ pub struct Apu {
 
     // Expansion audio (e.g. Sunsoft 5B) — set by bus each CPU cycle
     expansion_audio: f32,
+
+    // Output stage, not part of save states: a DC blocker for whatever
+    // offset the hardware chain leaves, and a short gain ramp that hides
+    // jumps in level at power-on, resume and state load.
+    dc_blocker: HighPassFilter,
+    ramp_remaining: u32,
+    ramp_from: f32,
+    last_sample: f32,
+    // Holds output to the real-time rate while fast-forwarding
+    condenser: crate::fast_forward::AudioCondenser,
+    // Mixing was paused by fast-forward `Skip`; restart with a fade-in
+    synthesis_skipped: bool,
+
+    // Per-channel level capture for the oscilloscope overlay (off by default)
+    scope: Option<crate::audio_scope::AudioScope>,
 }
 
 struct PulseChannel {
@@ -244,6 +275,9 @@ struct DmcChannel {
     pending_dma_stall_cycles: u8,
 }
 
+/// Output samples (~6 ms at 44.1 kHz) a level change is ramped over.
+const RAMP_SAMPLES: u32 = 256;
+
 // High-quality audio filters
 struct HighPassFilter {
     prev_input: f32,
@@ -340,12 +374,14 @@ impl Apu {
             noise_enabled: false,
             dmc_enabled: false,
 
-            audio_ring: None,
+            sink: AudioSink::default(),
             output_buffer: Vec::new(),
             sample_rate: 44100.0,
             cpu_clock_rate: 1789773.0,
+            region: Region::Ntsc,
 
             sample_counter: 0.0,
+            samples_produced: 0,
 
             sample_accumulator: 0.0,
             sample_accumulator_count: 0,
@@ -358,9 +394,41 @@ impl Apu {
             low_pass_14khz: LowPassFilter::new(44100.0, 14000.0),
 
             expansion_audio: 0.0,
+
+            dc_blocker: HighPassFilter::new(44100.0, 10.0),
+            ramp_remaining: RAMP_SAMPLES,
+            ramp_from: 0.0,
+            last_sample: 0.0,
+            condenser: crate::fast_forward::AudioCondenser::new(),
+            synthesis_skipped: false,
+
+            scope: None,
         }
     }
 
+    /// CPU cycles the APU has run since power-on.
+    pub fn cycle_count(&self) -> u64 {
+        self.cycle_count
+    }
+
+    /// Output samples produced since power-on.
+    pub fn samples_produced(&self) -> u64 {
+        self.samples_produced
+    }
+
+    /// Switch frame sequencer timing, noise/DMC tables and the resampler
+    /// to the given console timing.
+    pub fn set_region(&mut self, region: Region) {
+        self.region = region;
+        self.cpu_clock_rate = region.cpu_clock_rate();
+        self.aa_filter1 = LowPassFilter::new(self.cpu_clock_rate, 18000.0);
+        self.aa_filter2 = LowPassFilter::new(self.cpu_clock_rate, 18000.0);
+    }
+
+    pub fn region(&self) -> Region {
+        self.region
+    }
+
     pub fn set_expansion_audio(&mut self, value: f32) {
         self.expansion_audio = value;
     }
@@ -440,16 +508,53 @@ impl Apu {
         self.low_pass_14khz.restore_state(&state.low_pass_14khz);
         self.output_buffer.clear();
         self.expansion_audio = 0.0;
+        self.start_ramp(self.last_sample);
     }
 
     pub fn restore_legacy_state(&mut self, frame_counter: u8, frame_irq: bool) {
-        let ring = self.audio_ring.clone();
-        *self = Apu::new();
-        self.audio_ring = ring;
+        self.power_cycle();
         self.frame_counter = frame_counter as u16;
         self.frame_irq = frame_irq;
     }
 
+    /// Start over from power-on, keeping the region and the frontend's
+    /// audio ring and scope.
+    pub fn power_cycle(&mut self) {
+        let sink = self.sink.clone();
+        let scope = self.scope.take();
+        let region = self.region;
+        let last_sample = self.last_sample;
+        let condenser = self.condenser.clone();
+        *self = Apu::new();
+        self.set_region(region);
+        self.sink = sink;
+        self.scope = scope;
+        self.condenser = condenser;
+        self.start_ramp(last_sample);
+    }
+
+    /// Emulated frames per real frame; the output is held to the real-time
+    /// sample rate as set by `set_fast_forward_audio`.
+    pub fn set_speed(&mut self, speed: usize) {
+        self.condenser.set_speed(speed);
+    }
+
+    pub fn set_fast_forward_audio(&mut self, mode: crate::fast_forward::FastForwardAudio) {
+        self.condenser.set_mode(mode);
+    }
+
+    /// Fade the output in from silence, for when playback restarts after
+    /// the frontend stopped feeding samples (pause, menus, breakpoints).
+    pub fn ramp_in(&mut self) {
+        self.start_ramp(0.0);
+    }
+
+    /// Crossfade from `from` to the live output over `RAMP_SAMPLES`.
+    fn start_ramp(&mut self, from: f32) {
+        self.ramp_from = from;
+        self.ramp_remaining = RAMP_SAMPLES;
+    }
+
     pub fn step(&mut self) {
         self.cycle_count += 1;
         self.frame_counter += 1;
@@ -468,14 +573,14 @@ impl Apu {
 
         // Frame sequencer with proper 4-step/5-step timing
         // Values are in CPU cycles (APU cycle * 2, since step() is called per CPU cycle)
-        // APU 3728.5 = CPU 7457, APU 7456.5 = CPU 14913, etc.
+        // NTSC: APU 3728.5 = CPU 7457, APU 7456.5 = CPU 14913, etc.
+        let [quarter1, half1, quarter2, step4, step5] = self.region.frame_counter_steps();
         if !self.frame_mode {
             // 4-step mode
             match self.frame_counter {
-                7457 => self.clock_quarter_frame(),
-                14913 => self.clock_half_frame(),
-                22371 => self.clock_quarter_frame(),
-                29829 => {
+                c if c == quarter1 || c == quarter2 => self.clock_quarter_frame(),
+                c if c == half1 => self.clock_half_frame(),
+                c if c == step4 => {
                     self.clock_half_frame();
                     if !self.irq_disable {
                         self.frame_irq = true;
@@ -485,13 +590,11 @@ impl Apu {
                 _ => {}
             }
         } else {
-            // 5-step mode (no IRQ)
+            // 5-step mode (no IRQ); the 4th step does nothing
             match self.frame_counter {
-                7457 => self.clock_quarter_frame(),
-                14913 => self.clock_half_frame(),
-                22371 => self.clock_quarter_frame(),
-                29829 => {} // nothing
-                37281 => {
+                c if c == quarter1 || c == quarter2 => self.clock_quarter_frame(),
+                c if c == half1 => self.clock_half_frame(),
+                c if c == step5 => {
                     self.clock_half_frame();
                     self.frame_counter = 0;
                 }
@@ -499,6 +602,19 @@ impl Apu {
             }
         }
 
+        if self.condenser.skips_synthesis() {
+            self.skip_sample();
+            return;
+        }
+        if self.synthesis_skipped {
+            // Back from fast-forward: drop the stale accumulation and fade
+            // the filters' output in from silence
+            self.synthesis_skipped = false;
+            self.sample_accumulator = 0.0;
+            self.sample_accumulator_count = 0;
+            self.start_ramp(0.0);
+        }
+
         // Anti-aliasing: filter raw mixer output at CPU rate, then accumulate.
         let raw = self.raw_mix() + self.expansion_audio;
         let aa = self.aa_filter1.process(raw);
@@ -511,13 +627,38 @@ impl Apu {
         if self.sample_counter >= self.cpu_clock_rate {
             self.sample_counter -= self.cpu_clock_rate;
             let sample = self.produce_sample();
-            // Push directly to ring buffer for jitter-free delivery,
-            // fall back to Vec when no ring buffer is attached.
-            if let Some(ref ring) = self.audio_ring {
+            self.samples_produced += 1;
+            if self.scope.is_some() {
+                self.capture_scope();
+            }
+            self.output_sample(sample);
+        }
+    }
+
+    /// Sample clock while synthesis is skipped: the condenser still gets
+    /// silence at the real-time rate, so the audio queue keeps its level.
+    fn skip_sample(&mut self) {
+        self.synthesis_skipped = true;
+        self.sample_counter += self.sample_rate;
+        if self.sample_counter >= self.cpu_clock_rate {
+            self.sample_counter -= self.cpu_clock_rate;
+            self.samples_produced += 1;
+            self.output_sample(0.0);
+        }
+    }
+
+    fn output_sample(&mut self, sample: f32) {
+        let Some(sample) = self.condenser.process(sample) else {
+            return;
+        };
+        // Push directly to ring buffer for jitter-free delivery,
+        // fall back to Vec when no ring buffer is attached.
+        match &self.sink {
+            AudioSink::Buffer => self.output_buffer.push(sample),
+            AudioSink::Ring(ring) => {
                 ring.push_one(sample);
-            } else {
-                self.output_buffer.push(sample);
             }
+            AudioSink::Null => {}
         }
     }
 
@@ -544,6 +685,31 @@ impl Apu {
     /// for oversampling accumulation.
     #[inline]
     fn raw_mix(&self) -> f32 {
+        let (pulse1_out, pulse2_out, triangle_out, noise_out, dmc_out) = self.channel_outputs();
+
+        // Non-linear mixer (nesdev wiki) - models the NES resistor DAC
+        // Channel outputs are 0.0-15.0. Mixer naturally outputs 0.0-~1.0.
+        let pulse_sum = pulse1_out + pulse2_out;
+        let pulse_out = if pulse_sum > 0.0 {
+            95.88 / (8128.0 / pulse_sum + 100.0)
+        } else {
+            0.0
+        };
+
+        let tnd_sum = triangle_out / 8227.0 + noise_out / 12241.0 + dmc_out / 22638.0;
+        let tnd_out = if tnd_sum > 0.0 {
+            159.79 / (1.0 / tnd_sum + 100.0)
+        } else {
+            0.0
+        };
+
+        pulse_out + tnd_out
+    }
+
+    /// Raw DAC inputs of the five 2A03 channels (pulse1, pulse2, triangle,
+    /// noise, dmc), with silenced channels reported as 0.
+    #[inline]
+    fn channel_outputs(&self) -> (f32, f32, f32, f32, f32) {
         let pulse1_out = if self.pulse1_enabled && self.pulse1.length_counter > 0 {
             self.pulse1.output()
         } else {
@@ -566,23 +732,36 @@ impl Apu {
         };
         let dmc_out = self.dmc.output();
 
-        // Non-linear mixer (nesdev wiki) - models the NES resistor DAC
-        // Channel outputs are 0.0-15.0. Mixer naturally outputs 0.0-~1.0.
-        let pulse_sum = pulse1_out + pulse2_out;
-        let pulse_out = if pulse_sum > 0.0 {
-            95.88 / (8128.0 / pulse_sum + 100.0)
-        } else {
-            0.0
-        };
+        (pulse1_out, pulse2_out, triangle_out, noise_out, dmc_out)
+    }
 
-        let tnd_sum = triangle_out / 8227.0 + noise_out / 12241.0 + dmc_out / 22638.0;
-        let tnd_out = if tnd_sum > 0.0 {
-            159.79 / (1.0 / tnd_sum + 100.0)
-        } else {
-            0.0
-        };
+    fn capture_scope(&mut self) {
+        let (pulse1, pulse2, triangle, noise, dmc) = self.channel_outputs();
+        let levels = [
+            pulse1 / 15.0,
+            pulse2 / 15.0,
+            triangle / 15.0,
+            noise / 15.0,
+            dmc / 127.0,
+            // Expansion audio is already mixer-scaled; center it in the lane
+            0.5 + self.expansion_audio,
+        ];
+        if let Some(scope) = self.scope.as_mut() {
+            scope.push(levels);
+        }
+    }
 
-        pulse_out + tnd_out
+    /// Start or stop capturing per-channel levels for the oscilloscope overlay.
+    pub fn set_scope_enabled(&mut self, enabled: bool) {
+        if !enabled {
+            self.scope = None;
+        } else if self.scope.is_none() {
+            self.scope = Some(crate::audio_scope::AudioScope::new());
+        }
+    }
+
+    pub fn scope(&self) -> Option<&crate::audio_scope::AudioScope> {
+        self.scope.as_ref()
     }
 
     /// Average accumulated raw mix, apply hardware filters, produce final sample.
@@ -601,12 +780,25 @@ impl Apu {
         let filtered = self.low_pass_14khz.process(filtered);
 
         // Scale to fill audio output range (HP filters center the signal around 0)
-        (filtered * 1.8).clamp(-1.0, 1.0)
+        let mut sample = self.dc_blocker.process(filtered * 1.8);
+        if self.ramp_remaining > 0 {
+            let gain = 1.0 - self.ramp_remaining as f32 / RAMP_SAMPLES as f32;
+            sample = self.ramp_from + (sample - self.ramp_from) * gain;
+            self.ramp_remaining -= 1;
+        }
+        let sample = sample.clamp(-1.0, 1.0);
+        self.last_sample = sample;
+        sample
     }
 
     /// Attach a ring buffer for direct sample delivery (bypasses output_buffer).
     pub fn set_audio_ring(&mut self, ring: Arc<crate::audio_ring::SpscRingBuffer>) {
-        self.audio_ring = Some(ring);
+        self.set_audio_sink(AudioSink::Ring(ring));
+    }
+
+    pub fn set_audio_sink(&mut self, sink: AudioSink) {
+        self.output_buffer.clear();
+        self.sink = sink;
     }
 
     pub fn get_audio_buffer(&mut self) -> Vec<f32> {
@@ -626,6 +818,10 @@ impl Apu {
         self.frame_irq && !self.irq_disable
     }
 
+    pub fn dmc_irq_pending(&self) -> bool {
+        self.dmc.irq_pending
+    }
+
     pub fn irq_pending(&self) -> bool {
         self.frame_irq_pending() || self.dmc.irq_pending
     }
@@ -634,6 +830,16 @@ impl Apu {
         self.frame_irq = false;
     }
 
+    /// Reset button: channels silenced as by a $4015 write of 0, frame
+    /// counter restarted in its current mode, DMC output keeps only bit 0.
+    pub fn reset(&mut self) {
+        self.write_register(0x4015, 0x00);
+        self.frame_irq = false;
+        self.frame_counter = 0;
+        self.dmc.output_level &= 0x01;
+        self.start_ramp(self.last_sample);
+    }
+
     pub fn read_register(&mut self, addr: u16) -> u8 {
         match addr {
             0x4015 => {
@@ -694,11 +900,11 @@ impl Apu {
             // Noise
             0x400C => self.noise.write_control(data),
             0x400D => {}
-            0x400E => self.noise.write_period(data),
+            0x400E => self.noise.write_period(data, self.region.noise_periods()),
             0x400F => self.noise.write_length(data, self.noise_enabled),
 
             // DMC
-            0x4010 => self.dmc.write_control(data),
+            0x4010 => self.dmc.write_control(data, self.region.dmc_rates()),
             0x4011 => self.dmc.write_direct_load(data),
             0x4012 => self.dmc.write_sample_address(data),
             0x4013 => self.dmc.write_sample_length(data),
@@ -755,20 +961,11 @@ impl Apu {
 }
 
 // Length counter lookup table
-const LENGTH_TABLE: [u8; 32] = [
+pub(crate) const LENGTH_TABLE: [u8; 32] = [
     10, 254, 20, 2, 40, 4, 80, 6, 160, 8, 60, 10, 14, 12, 26, 14, 12, 16, 24, 18, 48, 20, 96, 22,
     192, 24, 72, 26, 16, 28, 32, 30,
 ];
 
-// Noise period lookup table
-const NOISE_PERIOD_TABLE: [u16; 16] = [
-    4, 8, 16, 32, 64, 96, 128, 160, 202, 254, 380, 508, 762, 1016, 2034, 4068,
-];
-
-const DMC_RATE_TABLE: [u16; 16] = [
-    428, 380, 340, 320, 286, 254, 226, 214, 190, 160, 142, 128, 106, 85, 72, 54,
-];
-
 impl PulseChannel {
     fn new(is_pulse1: bool) -> Self {
         PulseChannel {
@@ -1105,9 +1302,9 @@ impl NoiseChannel {
         // Only writing to $400F (4th register) sets envelope_start.
     }
 
-    fn write_period(&mut self, data: u8) {
+    fn write_period(&mut self, data: u8, periods: &[u16; 16]) {
         self.mode = (data & 0x80) != 0;
-        self.timer_reload = NOISE_PERIOD_TABLE[(data & 0x0F) as usize];
+        self.timer_reload = periods[(data & 0x0F) as usize];
     }
 
     fn write_length(&mut self, data: u8, enabled: bool) {
@@ -1213,8 +1410,8 @@ impl DmcChannel {
             irq_enabled: false,
             irq_pending: false,
             loop_flag: false,
-            timer: DMC_RATE_TABLE[0],
-            timer_reload: DMC_RATE_TABLE[0],
+            timer: Region::Ntsc.dmc_rates()[0],
+            timer_reload: Region::Ntsc.dmc_rates()[0],
             output_level: 0,
             sample_address: 0xC000,
             sample_length: 1,
@@ -1229,10 +1426,10 @@ impl DmcChannel {
         }
     }
 
-    fn write_control(&mut self, data: u8) {
+    fn write_control(&mut self, data: u8, rates: &[u16; 16]) {
         self.irq_enabled = (data & 0x80) != 0;
         self.loop_flag = (data & 0x40) != 0;
-        self.timer_reload = DMC_RATE_TABLE[(data & 0x0F) as usize];
+        self.timer_reload = rates[(data & 0x0F) as usize];
         if !self.irq_enabled {
             self.irq_pending = false;
         }
@@ -1431,13 +1628,121 @@ mod tests {
         assert_eq!(apu.pull_dmc_sample_request(), Some((0xC000, 3)));
         apu.push_dmc_sample(0xFF);
 
-        let cycles = apu.dmc.timer as usize + (DMC_RATE_TABLE[15] as usize + 1) * 20;
+        let cycles = apu.dmc.timer as usize + (Region::Ntsc.dmc_rates()[15] as usize + 1) * 20;
         step_dmc(&mut apu, cycles, 0xFF);
 
         assert!(apu.dmc.output_level > 64);
         assert_eq!(apu.read_register(0x4015) & 0x10, 0);
     }
 
+    #[test]
+    fn skipped_synthesis_keeps_channels_and_sample_rate() {
+        let mut apu = Apu::new();
+        apu.set_fast_forward_audio(crate::fast_forward::FastForwardAudio::Skip);
+        apu.set_speed(4);
+        apu.write_register(0x4015, 0x01);
+        apu.write_register(0x4000, 0x1F);
+        apu.write_register(0x4003, 0x08); // length index 1: 254 half frames
+
+        // A quarter second: length counter clocked ~30 times
+        let cycles = Region::Ntsc.cpu_clock_rate() as usize / 4;
+        for _ in 0..cycles {
+            apu.step();
+        }
+        assert!(apu.condenser.skips_synthesis());
+        assert!(apu.pulse1.length_counter < 254 - 25);
+        let produced = apu.samples_produced();
+        assert!((11_000..11_100).contains(&produced), "{}", produced);
+        // Played at the real-time rate (whole grains of 512), ending in
+        // silence
+        let output = apu.get_audio_buffer();
+        assert_eq!(output.len(), 3072);
+        assert_eq!(output.last(), Some(&0.0));
+    }
+
+    #[test]
+    fn length_table_loads_on_every_channel() {
+        // Odd indices are linear; even ones are note lengths at two tempos
+        let note_lengths = [
+            10, 20, 40, 80, 160, 60, 14, 26, 12, 24, 48, 96, 192, 72, 16, 32,
+        ];
+        for (index, &length) in LENGTH_TABLE.iter().enumerate() {
+            let expected = match index {
+                1 => 254,
+                index if index % 2 == 1 => index as u8 - 1,
+                index => note_lengths[index / 2],
+            };
+            assert_eq!(length, expected, "index {}", index);
+
+            let mut apu = Apu::new();
+            apu.write_register(0x4015, 0x0F);
+            let data = (index as u8) << 3;
+            for addr in [0x4003, 0x4007, 0x400B, 0x400F] {
+                apu.write_register(addr, data);
+            }
+            assert_eq!(apu.pulse1.length_counter, length);
+            assert_eq!(apu.pulse2.length_counter, length);
+            assert_eq!(apu.triangle.length_counter, length);
+            assert_eq!(apu.noise.length_counter, length);
+        }
+    }
+
+    #[test]
+    fn length_counter_halts_and_clears() {
+        let mut apu = Apu::new();
+        apu.write_register(0x4015, 0x01);
+        apu.write_register(0x4000, 0x20); // halt
+        apu.write_register(0x4003, 0x18); // index 3: 2
+        apu.clock_half_frame();
+        assert_eq!(apu.pulse1.length_counter, 2);
+        apu.write_register(0x4000, 0x00);
+        apu.clock_half_frame();
+        apu.clock_half_frame();
+        assert_eq!(apu.pulse1.length_counter, 0);
+        assert_eq!(apu.read_register(0x4015) & 0x01, 0);
+
+        // Disabled channels ignore loads; disabling clears the counter
+        apu.write_register(0x4015, 0x00);
+        apu.write_register(0x4003, 0x08);
+        assert_eq!(apu.pulse1.length_counter, 0);
+        apu.write_register(0x4015, 0x01);
+        apu.write_register(0x4003, 0x08);
+        apu.write_register(0x4015, 0x00);
+        assert_eq!(apu.pulse1.length_counter, 0);
+    }
+
+    #[test]
+    fn triangle_linear_counter_reloads_and_counts_down() {
+        let mut apu = Apu::new();
+        apu.write_register(0x4015, 0x04);
+        apu.write_register(0x4008, 0x03);
+        apu.write_register(0x400B, 0x08);
+        assert!(apu.triangle.linear_reload_flag);
+        apu.clock_quarter_frame();
+        assert_eq!(apu.triangle.linear_counter, 3);
+        // Control clear: the reload flag drops after one reload
+        assert!(!apu.triangle.linear_reload_flag);
+        for expected in [2, 1, 0, 0] {
+            apu.clock_quarter_frame();
+            assert_eq!(apu.triangle.linear_counter, expected);
+        }
+        assert_eq!(apu.triangle.length_counter, 254);
+
+        // Control set: reloaded every quarter frame, length counter halted
+        apu.write_register(0x4008, 0x83);
+        apu.write_register(0x400B, 0x08);
+        for _ in 0..4 {
+            apu.clock_half_frame();
+            assert_eq!(apu.triangle.linear_counter, 3);
+        }
+        assert_eq!(apu.triangle.length_counter, 254);
+        // Clearing control only takes effect after the next reload
+        apu.write_register(0x4008, 0x03);
+        apu.clock_quarter_frame();
+        apu.clock_quarter_frame();
+        assert_eq!(apu.triangle.linear_counter, 2);
+    }
+
     #[test]
     fn dmc_sets_irq_and_write_4015_clears_it() {
         let mut apu = Apu::new();
@@ -1477,7 +1782,7 @@ mod tests {
         apu.step();
         assert_eq!(apu.pull_dmc_sample_request(), Some((0xC000, 3)));
         apu.push_dmc_sample(0xAA);
-        let cycles = apu.dmc.timer as usize + (DMC_RATE_TABLE[15] as usize + 1) * 6;
+        let cycles = apu.dmc.timer as usize + (Region::Ntsc.dmc_rates()[15] as usize + 1) * 6;
         step_dmc(&mut apu, cycles, 0x55);
 
         let snapshot = apu.snapshot_state();
@@ -1504,4 +1809,98 @@ mod tests {
             assert_eq!(restored.dmc.silence, apu.dmc.silence);
         }
     }
+
+    #[test]
+    fn pal_region_uses_pal_noise_and_dmc_periods() {
+        let mut apu = Apu::new();
+        apu.write_register(0x400E, 0x02);
+        apu.write_register(0x4010, 0x0F);
+        assert_eq!(apu.noise.timer_reload, 16);
+        assert_eq!(apu.dmc.timer_reload, 54);
+
+        apu.set_region(Region::Pal);
+        apu.write_register(0x400E, 0x02);
+        apu.write_register(0x4010, 0x0F);
+        assert_eq!(apu.noise.timer_reload, 14);
+        assert_eq!(apu.dmc.timer_reload, 50);
+    }
+
+    #[test]
+    fn reset_silences_channels() {
+        let mut apu = Apu::new();
+        apu.write_register(0x4015, 0x0F);
+        apu.write_register(0x4003, 0x08);
+        apu.write_register(0x400F, 0x08);
+        apu.write_register(0x4011, 0x45);
+        assert_eq!(apu.read_register(0x4015) & 0x0F, 0x09);
+
+        apu.reset();
+        assert_eq!(apu.read_register(0x4015) & 0x1F, 0);
+        assert_eq!(apu.dmc.output_level, 0x01);
+        // Channels stay off until re-enabled
+        apu.write_register(0x4003, 0x08);
+        assert_eq!(apu.read_register(0x4015) & 0x01, 0);
+    }
+
+    fn run_samples(apu: &mut Apu, count: usize) -> Vec<f32> {
+        while apu.output_buffer.len() < count {
+            apu.step();
+        }
+        apu.get_audio_buffer()
+    }
+
+    #[test]
+    fn output_ramps_in_after_power_on_and_resume() {
+        // Full-scale DMC DAC step on the very first cycle
+        let mut apu = Apu::new();
+        apu.write_register(0x4011, 0x7F);
+        let samples = run_samples(&mut apu, RAMP_SAMPLES as usize);
+        assert_eq!(samples[0], 0.0);
+        let largest_jump = samples
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).abs())
+            .fold(0.0, f32::max);
+        assert!(largest_jump < 0.1, "jump of {}", largest_jump);
+
+        run_samples(&mut apu, 1000);
+        apu.ramp_in();
+        assert_eq!(run_samples(&mut apu, 1)[0], 0.0);
+    }
+
+    #[test]
+    fn state_load_and_reset_crossfade_from_the_last_sample() {
+        let mut apu = Apu::new();
+        let state = apu.snapshot_state();
+        run_samples(&mut apu, 1000);
+
+        apu.last_sample = 0.8;
+        apu.restore_state(&state);
+        assert_eq!(run_samples(&mut apu, 1)[0], 0.8);
+
+        run_samples(&mut apu, 1000);
+        apu.last_sample = -0.5;
+        apu.reset();
+        assert_eq!(run_samples(&mut apu, 1)[0], -0.5);
+    }
+
+    #[test]
+    fn null_sink_keeps_timing() {
+        let run = |sink: AudioSink| {
+            let mut apu = Apu::new();
+            apu.set_audio_sink(sink);
+            apu.write_register(0x4017, 0x00);
+            let mut irq_at = None;
+            for cycle in 0..40_000 {
+                apu.step();
+                if irq_at.is_none() && apu.frame_irq_pending() {
+                    irq_at = Some(cycle);
+                }
+            }
+            (irq_at, apu.samples_produced(), apu.get_audio_buffer().len())
+        };
+        let (irq_at, produced, buffered) = run(AudioSink::Buffer);
+        assert!(irq_at.is_some());
+        assert!(buffered > 0);
+        assert_eq!(run(AudioSink::Null), (irq_at, produced, 0));
+    }
 }
@@ -0,0 +1,228 @@
+use std::path::Path;
+
+/// One APU register write. `cycle` counts CPU cycles since logging started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApuWrite {
+    pub frame: u64,
+    pub cycle: u64,
+    pub addr: u16,
+    pub value: u8,
+}
+
+/// DMC sample bytes as the CPU saw them when playback was started.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DmcSample {
+    pub cycle: u64,
+    pub addr: u16,
+    pub data: Vec<u8>,
+}
+
+/// Log of sound register writes for music ripping: $4000-$4013/$4015/$4017
+/// plus the Sunsoft 5B ports ($C000 select, $E000 data). Saved as text or
+/// as a VGM file that players and re-synthesis tools understand.
+#[derive(Debug, Clone, Default)]
+pub struct ApuWriteLog {
+    writes: Vec<ApuWrite>,
+    dmc_samples: Vec<DmcSample>,
+    elapsed: u64,
+    last_cycle: Option<u64>,
+}
+
+const VGM_SAMPLE_RATE: f64 = 44100.0;
+const VGM_HEADER_SIZE: usize = 0x100;
+const VGM_YM2149: u8 = 0x10;
+
+impl ApuWriteLog {
+    /// Record a write at APU cycle `apu_cycle`. Only the distance from the
+    /// previous write is used, so a state load mid-log adds no delay.
+    pub fn record(&mut self, apu_cycle: u64, frame: u64, addr: u16, value: u8) {
+        let cycle = self.advance(apu_cycle);
+        self.writes.push(ApuWrite {
+            frame,
+            cycle,
+            addr,
+            value,
+        });
+    }
+
+    /// Record the sample a DMC start will play, unless the same bytes were
+    /// already logged for `addr`.
+    pub fn record_dmc_sample(&mut self, apu_cycle: u64, addr: u16, data: Vec<u8>) {
+        let cycle = self.advance(apu_cycle);
+        let known = self
+            .dmc_samples
+            .iter()
+            .rev()
+            .find(|sample| sample.addr == addr)
+            .is_some_and(|sample| sample.data == data);
+        if !known {
+            self.dmc_samples.push(DmcSample { cycle, addr, data });
+        }
+    }
+
+    fn advance(&mut self, apu_cycle: u64) -> u64 {
+        if let Some(last) = self.last_cycle {
+            self.elapsed += apu_cycle.saturating_sub(last);
+        }
+        self.last_cycle = Some(apu_cycle);
+        self.elapsed
+    }
+
+    pub fn writes(&self) -> &[ApuWrite] {
+        &self.writes
+    }
+
+    pub fn dmc_samples(&self) -> &[DmcSample] {
+        &self.dmc_samples
+    }
+
+    /// One `frame cycle $addr=$value` line per write.
+    pub fn to_text(&self) -> String {
+        self.writes
+            .iter()
+            .map(|w| format!("{} {} ${:04X}=${:02X}\n", w.frame, w.cycle, w.addr, w.value))
+            .collect()
+    }
+
+    /// VGM 1.71 stream at `cpu_clock` Hz: the 2A03 (with DMC samples as
+    /// RAM data blocks) and, if it was written, the Sunsoft 5B as a YM2149.
+    /// Other expansion chips have no VGM equivalent and are not logged.
+    pub fn to_vgm(&self, cpu_clock: f64) -> Vec<u8> {
+        let to_samples = |cycle: u64| (cycle as f64 * VGM_SAMPLE_RATE / cpu_clock) as u64;
+        let mut data = Vec::new();
+        let mut position = 0u64;
+        let mut wait_until = |data: &mut Vec<u8>, target: u64| {
+            while position < target {
+                let wait = (target - position).min(0xFFFF);
+                data.push(0x61);
+                data.extend_from_slice(&(wait as u16).to_le_bytes());
+                position += wait;
+            }
+        };
+
+        let mut samples = self.dmc_samples.iter().peekable();
+        let mut ay_register = 0u8;
+        let mut uses_5b = false;
+        for write in &self.writes {
+            // Sample data goes out before the write that starts playing it
+            while let Some(sample) = samples.next_if(|sample| sample.cycle <= write.cycle) {
+                wait_until(&mut data, to_samples(sample.cycle));
+                data.extend_from_slice(&[0x67, 0x66, 0xC2]);
+                data.extend_from_slice(&(sample.data.len() as u32 + 2).to_le_bytes());
+                data.extend_from_slice(&sample.addr.to_le_bytes());
+                data.extend_from_slice(&sample.data);
+            }
+            wait_until(&mut data, to_samples(write.cycle));
+            match write.addr {
+                0x4000..=0x401F => {
+                    data.extend_from_slice(&[0xB4, (write.addr - 0x4000) as u8, write.value])
+                }
+                0xC000..=0xDFFF => ay_register = write.value & 0x0F,
+                0xE000..=0xFFFF => {
+                    uses_5b = true;
+                    data.extend_from_slice(&[0xA0, ay_register, write.value]);
+                }
+                _ => {}
+            }
+        }
+        let total = to_samples(self.elapsed);
+        wait_until(&mut data, total);
+        data.push(0x66);
+
+        let mut vgm = vec![0u8; VGM_HEADER_SIZE];
+        let mut put = |offset: usize, value: u32| {
+            vgm[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        };
+        put(0x04, (VGM_HEADER_SIZE + data.len() - 4) as u32);
+        put(0x08, 0x171);
+        put(0x18, total as u32);
+        put(0x34, (VGM_HEADER_SIZE - 0x34) as u32);
+        put(0x84, cpu_clock.round() as u32);
+        if uses_5b {
+            put(0x74, cpu_clock.round() as u32);
+            vgm[0x78] = VGM_YM2149;
+            vgm[0x79] = 0x01; // Legacy output
+        }
+        vgm[..4].copy_from_slice(b"Vgm ");
+        vgm.extend_from_slice(&data);
+        vgm
+    }
+
+    /// Save as VGM when `path` ends in `.vgm`, otherwise as text.
+    pub fn save(&self, path: impl AsRef<Path>, cpu_clock: f64) -> std::io::Result<()> {
+        let path = path.as_ref();
+        if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("vgm"))
+        {
+            std::fs::write(path, self.to_vgm(cpu_clock))
+        } else {
+            std::fs::write(path, self.to_text())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamps_are_relative_to_the_first_write() {
+        let mut log = ApuWriteLog::default();
+        log.record(1000, 5, 0x4000, 0x3F);
+        log.record(1500, 5, 0x4015, 0x01);
+        // A loaded state moves the APU clock back; keep time monotonic
+        log.record(200, 9, 0x4017, 0x40);
+        let cycles: Vec<u64> = log.writes().iter().map(|w| w.cycle).collect();
+        assert_eq!(cycles, [0, 500, 500]);
+        assert_eq!(log.to_text().lines().nth(1), Some("5 500 $4015=$01"));
+    }
+
+    #[test]
+    fn vgm_has_header_writes_and_waits() {
+        let mut log = ApuWriteLog::default();
+        log.record(0, 0, 0x4000, 0x3F);
+        log.record(40_000, 1, 0x4003, 0x08);
+        let vgm = log.to_vgm(1_789_773.0);
+
+        assert_eq!(&vgm[..4], b"Vgm ");
+        let word = |offset: usize| u32::from_le_bytes(vgm[offset..offset + 4].try_into().unwrap());
+        assert_eq!(word(0x04) as usize, vgm.len() - 4);
+        assert_eq!(word(0x84), 1_789_773);
+        // 40000 CPU cycles is 985 samples at 44.1kHz
+        assert_eq!(word(0x18), 985);
+        assert_eq!(
+            &vgm[0x100..],
+            &[0xB4, 0x00, 0x3F, 0x61, 0xD9, 0x03, 0xB4, 0x03, 0x08, 0x66]
+        );
+        assert_eq!(vgm[0x78], 0);
+    }
+
+    #[test]
+    fn vgm_carries_dmc_samples_and_sunsoft_5b() {
+        let mut log = ApuWriteLog::default();
+        log.record_dmc_sample(0, 0xC040, vec![0xAA, 0x55]);
+        log.record(0, 0, 0x4015, 0x10);
+        // Same bytes again are not repeated
+        log.record_dmc_sample(0, 0xC040, vec![0xAA, 0x55]);
+        log.record(0, 0, 0xC000, 0x07);
+        log.record(0, 0, 0xE000, 0x38);
+        let vgm = log.to_vgm(1_789_773.0);
+
+        assert_eq!(log.dmc_samples().len(), 1);
+        assert_eq!(
+            u32::from_le_bytes(vgm[0x08..0x0C].try_into().unwrap()),
+            0x171
+        );
+        assert_eq!(vgm[0x78], VGM_YM2149);
+        assert_eq!(
+            &vgm[0x100..],
+            &[
+                0x67, 0x66, 0xC2, 4, 0, 0, 0, 0x40, 0xC0, 0xAA, 0x55, // DMC data block
+                0xB4, 0x15, 0x10, // $4015
+                0xA0, 0x07, 0x38, // 5B mixer
+                0x66
+            ]
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use crate::hud_toast::draw_hud_label_rgb24;
+
+pub const SCOPE_CHANNELS: usize = 6;
+pub const SCOPE_CHANNEL_NAMES: [&str; SCOPE_CHANNELS] = ["P1", "P2", "TRI", "NOI", "DMC", "EXP"];
+
+// History kept per channel (output sample rate), and how much of it is shown.
+// The extra history gives the trigger search room to line up the trace.
+const SCOPE_HISTORY: usize = 1024;
+const SCOPE_WINDOW: usize = 512;
+
+const SCOPE_COLORS: [[u8; 3]; SCOPE_CHANNELS] = [
+    [0xF8, 0x78, 0x58],
+    [0xF8, 0xB8, 0x00],
+    [0x58, 0xD8, 0x54],
+    [0x3C, 0xBC, 0xFC],
+    [0xD8, 0x78, 0xF8],
+    [0xF8, 0xF8, 0xF8],
+];
+
+/// Rolling per-channel level history captured by the APU for the
+/// oscilloscope overlay. Levels are normalized to 0.0..=1.0.
+pub struct AudioScope {
+    samples: Box<[[f32; SCOPE_HISTORY]; SCOPE_CHANNELS]>,
+    pos: usize,
+}
+
+impl AudioScope {
+    pub fn new() -> Self {
+        Self {
+            samples: Box::new([[0.0; SCOPE_HISTORY]; SCOPE_CHANNELS]),
+            pos: 0,
+        }
+    }
+
+    pub fn push(&mut self, levels: [f32; SCOPE_CHANNELS]) {
+        for (channel, level) in levels.iter().enumerate() {
+            self.samples[channel][self.pos] = level.clamp(0.0, 1.0);
+        }
+        self.pos = (self.pos + 1) % SCOPE_HISTORY;
+    }
+
+    /// Sample `i` samples back from the newest one (0 = newest).
+    fn sample_back(&self, channel: usize, i: usize) -> f32 {
+        let idx = (self.pos + SCOPE_HISTORY - 1 - i) % SCOPE_HISTORY;
+        self.samples[channel][idx]
+    }
+
+    /// Copy the displayed window of `channel`, oldest first, aligned on a
+    /// rising edge when one is found so periodic waves stand still.
+    pub fn window(&self, channel: usize) -> [f32; SCOPE_WINDOW] {
+        let span = SCOPE_HISTORY - SCOPE_WINDOW;
+        let mut start_back = SCOPE_WINDOW - 1;
+        for offset in 0..span {
+            let back = SCOPE_HISTORY - 1 - offset;
+            let prev = self.sample_back(channel, back);
+            let cur = self.sample_back(channel, back - 1);
+            if prev < 0.5 && cur >= 0.5 {
+                start_back = back - 1;
+                break;
+            }
+        }
+
+        let mut out = [0.0; SCOPE_WINDOW];
+        for (i, slot) in out.iter_mut().enumerate() {
+            *slot = self.sample_back(channel, start_back - i);
+        }
+        out
+    }
+}
+
+impl Default for AudioScope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Draw one oscilloscope lane per channel over an RGB24 frame.
+pub fn draw_audio_scope_rgb24(frame: &mut [u8], width: usize, height: usize, scope: &AudioScope) {
+    if width == 0 || height < SCOPE_CHANNELS * 4 || frame.len() < width * height * 3 {
+        return;
+    }
+
+    // Dim the frame so the traces stay readable over bright scenes
+    for byte in frame[..width * height * 3].iter_mut() {
+        *byte /= 3;
+    }
+
+    let lane_h = height / SCOPE_CHANNELS;
+    for channel in 0..SCOPE_CHANNELS {
+        let color = SCOPE_COLORS[channel];
+        let lane_top = channel * lane_h;
+        let trace_h = lane_h.saturating_sub(4).max(1);
+        let window = scope.window(channel);
+
+        let mut prev_y: Option<usize> = None;
+        for x in 0..width {
+            let level = window[x * SCOPE_WINDOW / width];
+            let y = lane_top + 2 + ((1.0 - level) * (trace_h - 1) as f32) as usize;
+            // Join consecutive points vertically so square waves read as edges
+            let (y0, y1) = match prev_y {
+                Some(p) if p < y => (p, y),
+                Some(p) => (y, p),
+                None => (y, y),
+            };
+            for py in y0..=y1 {
+                let idx = (py * width + x) * 3;
+                frame[idx..idx + 3].copy_from_slice(&color);
+            }
+            prev_y = Some(y);
+        }
+
+        draw_hud_label_rgb24(
+            frame,
+            width,
+            height,
+            2,
+            lane_top + 2,
+            SCOPE_CHANNEL_NAMES[channel],
+            color,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_aligns_on_rising_edge() {
+        let mut scope = AudioScope::new();
+        // Square wave with a 64-sample period on P1
+        for i in 0..SCOPE_HISTORY {
+            let level = if (i / 32) % 2 == 0 { 0.0 } else { 1.0 };
+            scope.push([level, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        }
+
+        let window = scope.window(0);
+        assert_eq!(window[0], 1.0);
+        assert_eq!(window[31], 1.0);
+        assert_eq!(window[32], 0.0);
+    }
+
+    #[test]
+    fn draw_ignores_undersized_frame() {
+        let scope = AudioScope::new();
+        let mut frame = vec![0xFFu8; 16];
+        draw_audio_scope_rgb24(&mut frame, 256, 240, &scope);
+        assert!(frame.iter().all(|&b| b == 0xFF));
+    }
+}
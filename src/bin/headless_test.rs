@@ -1,42 +1,76 @@
-use nes_emulator::Nes;
+use nes_emulator::accuracy::{AccuracyProfile, GameDb};
+use nes_emulator::apu::AudioSink;
+use nes_emulator::blank_screen;
+use nes_emulator::color_filter::{apply_color_filter_rgb24, ColorFilter};
+use nes_emulator::debugger::{InterruptKind, Watchpoint};
+use nes_emulator::frame_diff::{diff_frames, load_ppm};
+use nes_emulator::input::{ControllerState, InputMacro};
+use nes_emulator::input_script::{parse_script, InputScript};
+use nes_emulator::memory_dump::{DumpKind, MemoryDump};
+use nes_emulator::nametable_text::{nametable_text, CharTable};
+use nes_emulator::nametable_view::{VIEW_HEIGHT, VIEW_WIDTH};
+use nes_emulator::png::write_rgb24_png;
+use nes_emulator::power_on::clock_seed;
+use nes_emulator::ram_assert::{AssertionResult, RamAssertion};
+use nes_emulator::{Cartridge, FrameStats, Nes, PowerOnState, Region, VsPpu};
 use std::collections::HashMap;
 use std::io::Write;
+use std::time::Instant;
 
 struct Args {
     rom_path: String,
     max_frames: u32,
-    inputs: HashMap<u32, u8>,
+    inputs: HashMap<u32, ControllerState>,
+    input_macro: Option<InputMacro>,
+    input_script: Option<InputScript>,
+    assertions: Vec<RamAssertion>,
     captures: Vec<u32>,
     capture_dir: String,
+    compares: HashMap<u32, String>,
     all_frames: bool,
+    hide_bg: bool,
+    hide_sprites: bool,
+    profile: Option<AccuracyProfile>,
+    no_sprite_limit: bool,
+    oam_quirks: bool,
+    threaded_ppu: bool,
+    no_ppu_warmup: bool,
+    color_filter: Option<ColorFilter>,
+    forced_region: Option<Region>,
+    forced_bus_conflicts: Option<bool>,
+    barcodes: HashMap<u32, String>,
+    vs_dip: Option<u8>,
+    vs_ppu: Option<VsPpu>,
+    coins: Vec<u32>,
+    power_on: Option<PowerOnState>,
+    random_ram_seed: Option<u64>,
+    chr_protect: bool,
+    log_chr_writes: bool,
+    watchpoints: Vec<Watchpoint>,
+    break_on: Vec<InterruptKind>,
+    log_brk: bool,
+    bench: bool,
+    text_output: bool,
+    screen_text: bool,
+    why_blank: bool,
+    nametable_png: Option<String>,
+    raw_frame: Option<String>,
+    apu_log: Option<String>,
+    dumps: Vec<MemoryDump>,
+    charset: Option<CharTable>,
 }
 
 impl Args {
     fn should_capture(&self, frame: u32) -> bool {
         self.all_frames || self.captures.contains(&frame)
     }
-}
 
-fn parse_buttons(s: &str) -> u8 {
-    let mut val = 0u8;
-    for name in s.split(',') {
-        val |= match name.trim() {
-            "A" => 0x01,
-            "B" => 0x02,
-            "Select" => 0x04,
-            "Start" => 0x08,
-            "Up" => 0x10,
-            "Down" => 0x20,
-            "Left" => 0x40,
-            "Right" => 0x80,
-            "" => 0x00,
-            other => {
-                eprintln!("Unknown button: {}", other);
-                0
-            }
-        };
+    /// Coin switches are held for a few frames so the game's poll sees them.
+    fn coin_held(&self, frame: u32) -> bool {
+        self.coins
+            .iter()
+            .any(|&start| (start..start + 3).contains(&frame))
     }
-    val
 }
 
 fn parse_args() -> Args {
@@ -50,18 +84,111 @@ fn parse_args() -> Args {
         eprintln!("  --input <frame>:<buttons>  Set controller input at frame");
         eprintln!("                             buttons: A,B,Select,Start,Up,Down,Left,Right");
         eprintln!("                             Example: --input 60:Start --input 65:");
+        eprintln!("  --macro <spec>             Play a movie from frame 0 over --input, e.g.");
+        eprintln!("                             -*60,START,-*30,RESET,A (RESET/POWER events)");
+        eprintln!("  --input-script <file>      Play an input script (hold/press/play/label,");
+        eprintln!("                             frame ranges, repeats) like --macro; exits 1");
+        eprintln!("                             on 'fail if ...' or an unmet 'wait until ...'");
+        eprintln!("  --assert <check>           RAM check, e.g. ram:$00FE==0x5A@frame=600 (after");
+        eprintln!("                             600 frames; at the end without @frame); any");
+        eprintln!("                             failure exits 1. Repeatable");
+        eprintln!("  --assert-file <file>       Checks from a file, one per line, # comments");
         eprintln!("  --capture <frame>          Capture screenshot at frame");
         eprintln!("  --capture-dir <dir>        Capture output directory (default: /tmp)");
+        eprintln!("  --compare <frame>:<ppm>    Diff the frame against a reference capture; a");
+        eprintln!("                             mismatch prints a cell map, writes");
+        eprintln!("                             diff_<frame>.png to the capture dir, exits 1");
         eprintln!("  --all-frames               Capture every frame");
+        eprintln!("  --hide-bg                  Hide the background layer in output");
+        eprintln!("  --hide-sprites             Hide the sprite layer in output");
+        eprintln!(
+            "  --profile <name>           accuracy, balanced (default) or fast; else the ROM's gamedb.txt"
+        );
+        eprintln!("  --no-sprite-limit          Draw more than 8 sprites per scanline");
+        eprintln!("  --oam-quirks               Emulate OAM decay and OAMADDR/$2004 quirks");
+        eprintln!("  --threaded-ppu             Convert scanlines to RGB on a worker thread");
+        eprintln!("  --no-ppu-warmup            Accept PPU writes right after power-on");
+        eprintln!("  --color-filter <mode>      Daltonize captures (protanopia, deuteranopia, tritanopia)");
+        eprintln!("  --barcode <frame>:<digits> Swipe a Datach barcode at frame (mapper 157)");
+        eprintln!("  --dip <hex>                Vs. System DIP switches (bit 0 = switch 1)");
+        eprintln!("  --vs-ppu <chip>            Vs. PPU override (2c03, 2c04-0001..4, 2c05-01..4)");
+        eprintln!("  --coin <frame>             Insert a Vs. System coin at frame");
+        eprintln!(
+            "  --random-ram[=seed]        Fill CPU RAM and VRAM with random bytes at power-on"
+        );
+        eprintln!("  --chr-protect              Drop $2007 writes to pattern tables (CHR-RAM)");
+        eprintln!("  --log-chr-writes           Print every $2007 pattern table write with its PC");
+        eprintln!(
+            "  --watch <spec>             Stop at an access, e.g. ppu:w:1000-1FFF or cpu:rw:0300"
+        );
+        eprintln!(
+            "  --log-access <spec>        Print matching accesses with their PC without stopping"
+        );
+        eprintln!("  --break-on <list>          Stop at interrupt entry: nmi,irq,reset,brk");
+        eprintln!("  --log-brk                  Print every BRK with its signature byte");
+        eprintln!("  --force-ntsc, --force-pal  Ignore the header's timing");
+        eprintln!("  --[no-]bus-conflicts       Force bus conflicts on/off for every board");
+        eprintln!(
+            "  --bench                    Print speed and per-frame CPU/PPU/IRQ/DMA/APU stats"
+        );
+        eprintln!("  --power-on <mode>          VRAM/palette/OAM at power-on: clean, pattern, random[=seed]");
+        eprintln!(
+            "  --text-output              Print nametable 0 ($2000) tile indices to stdout at exit"
+        );
+        eprintln!("  --charset <file|ascii>     Print it as text via a <hex>=<char> table file");
+        eprintln!("  --screen-text              Print the text runs on screen (charset or charsets/<CRC32>.tbl)");
+        eprintln!("  --why-blank                Explain at exit why the screen may be blank");
+        eprintln!("  --nametable-png <file>     Save the 512x480 four-nametable view at exit");
+        eprintln!(
+            "  --raw-frame <file>         Save the last frame as 256x240 LE u16 color|emphasis<<6"
+        );
+        eprintln!("  --apu-log <file>           Log APU register writes (.vgm for VGM, else text)");
+        eprintln!("  --dump-ram <file>          Write CPU RAM then PRG-RAM at exit, or with");
+        eprintln!("                             @frame=N after N frames. Repeatable");
+        eprintln!("  --dump-vram <file>         Write nametable RAM, palette and OAM, likewise");
         std::process::exit(1);
     }
 
     let rom_path = args[1].clone();
     let mut max_frames = 300u32;
     let mut inputs = HashMap::new();
+    let mut input_macro = None;
+    let mut input_script = None;
+    let mut assertions = Vec::new();
     let mut captures = Vec::new();
     let mut capture_dir = "/tmp".to_string();
+    let mut compares = HashMap::new();
     let mut all_frames = false;
+    let mut hide_bg = false;
+    let mut hide_sprites = false;
+    let mut profile = None;
+    let mut no_sprite_limit = false;
+    let mut oam_quirks = false;
+    let mut threaded_ppu = false;
+    let mut no_ppu_warmup = false;
+    let mut color_filter = None;
+    let mut forced_region = None;
+    let mut forced_bus_conflicts = None;
+    let mut barcodes = HashMap::new();
+    let mut vs_dip = None;
+    let mut vs_ppu = None;
+    let mut coins = Vec::new();
+    let mut power_on = None;
+    let mut random_ram_seed = None;
+    let mut chr_protect = false;
+    let mut log_chr_writes = false;
+    let mut watchpoints = Vec::new();
+    let mut break_on = Vec::new();
+    let mut log_brk = false;
+    let mut bench = false;
+    let mut text_output = false;
+    let mut screen_text = false;
+    let mut why_blank = false;
+    let mut nametable_png = None;
+    let mut raw_frame = None;
+    let mut apu_log = None;
+    let mut dumps = Vec::new();
+    let mut charset = None;
 
     let mut i = 2;
     while i < args.len() {
@@ -78,14 +205,62 @@ fn parse_args() -> Args {
                     std::process::exit(1);
                 }
                 let frame: u32 = parts[0].parse().expect("Invalid frame number");
-                let buttons = parse_buttons(parts[1]);
+                let buttons = ControllerState::parse(parts[1]).unwrap_or_else(|| {
+                    eprintln!("Unknown button in --input: {}", parts[1]);
+                    std::process::exit(1);
+                });
                 inputs.insert(frame, buttons);
             }
+            "--macro" => {
+                i += 1;
+                input_macro = Some(InputMacro::parse(&args[i]).expect("Invalid --macro spec"));
+            }
+            "--assert" => {
+                i += 1;
+                assertions.push(RamAssertion::parse(&args[i]).unwrap_or_else(|| {
+                    eprintln!(
+                        "Invalid --assert '{}', expected e.g. ram:$00FE==0x5A@frame=600",
+                        args[i]
+                    );
+                    std::process::exit(1);
+                }));
+            }
+            "--assert-file" => {
+                i += 1;
+                let source = std::fs::read_to_string(&args[i]).unwrap_or_else(|e| {
+                    eprintln!("Failed to read {}: {}", args[i], e);
+                    std::process::exit(1);
+                });
+                assertions.extend(RamAssertion::parse_file(&source).unwrap_or_else(|e| {
+                    eprintln!("{}: {}", args[i], e);
+                    std::process::exit(1);
+                }));
+            }
+            "--input-script" => {
+                i += 1;
+                let source = std::fs::read_to_string(&args[i]).unwrap_or_else(|e| {
+                    eprintln!("Failed to read {}: {}", args[i], e);
+                    std::process::exit(1);
+                });
+                input_script = Some(parse_script(&source).unwrap_or_else(|e| {
+                    eprintln!("{}:{}", args[i], e);
+                    std::process::exit(1);
+                }));
+            }
             "--capture" => {
                 i += 1;
                 let frame: u32 = args[i].parse().expect("Invalid --capture frame number");
                 captures.push(frame);
             }
+            "--compare" => {
+                i += 1;
+                let Some((frame, path)) = args[i].split_once(':') else {
+                    eprintln!("Invalid --compare format, expected frame:reference.ppm");
+                    std::process::exit(1);
+                };
+                let frame: u32 = frame.parse().expect("Invalid --compare frame number");
+                compares.insert(frame, path.to_string());
+            }
             "--capture-dir" => {
                 i += 1;
                 capture_dir = args[i].clone();
@@ -93,6 +268,153 @@ fn parse_args() -> Args {
             "--all-frames" => {
                 all_frames = true;
             }
+            "--hide-bg" => {
+                hide_bg = true;
+            }
+            "--hide-sprites" => {
+                hide_sprites = true;
+            }
+            "--profile" => {
+                i += 1;
+                profile = Some(
+                    AccuracyProfile::from_name(&args[i])
+                        .expect("Invalid --profile value (accuracy|balanced|fast)"),
+                );
+            }
+            "--no-sprite-limit" => {
+                no_sprite_limit = true;
+            }
+            "--oam-quirks" => {
+                oam_quirks = true;
+            }
+            "--threaded-ppu" => {
+                threaded_ppu = true;
+            }
+            "--no-ppu-warmup" => {
+                no_ppu_warmup = true;
+            }
+            "--color-filter" => {
+                i += 1;
+                color_filter =
+                    Some(ColorFilter::from_name(&args[i]).expect("Invalid --color-filter value"));
+            }
+            "--force-ntsc" => {
+                forced_region = Some(Region::Ntsc);
+            }
+            "--force-pal" => {
+                forced_region = Some(Region::Pal);
+            }
+            "--bus-conflicts" => {
+                forced_bus_conflicts = Some(true);
+            }
+            "--no-bus-conflicts" => {
+                forced_bus_conflicts = Some(false);
+            }
+            "--barcode" => {
+                i += 1;
+                let parts: Vec<&str> = args[i].splitn(2, ':').collect();
+                if parts.len() != 2 {
+                    eprintln!("Invalid --barcode format, expected frame:digits");
+                    std::process::exit(1);
+                }
+                let frame: u32 = parts[0].parse().expect("Invalid frame number");
+                barcodes.insert(frame, parts[1].to_string());
+            }
+            "--dip" => {
+                i += 1;
+                let digits = args[i].trim_start_matches("0x");
+                vs_dip = Some(u8::from_str_radix(digits, 16).expect("Invalid --dip value"));
+            }
+            "--vs-ppu" => {
+                i += 1;
+                vs_ppu = Some(VsPpu::from_name(&args[i]).expect("Unknown --vs-ppu chip"));
+            }
+            "--coin" => {
+                i += 1;
+                coins.push(args[i].parse().expect("Invalid --coin frame number"));
+            }
+            "--power-on" => {
+                i += 1;
+                power_on =
+                    Some(PowerOnState::from_name(&args[i]).expect("Invalid --power-on mode"));
+            }
+            "--chr-protect" => {
+                chr_protect = true;
+            }
+            "--log-chr-writes" => {
+                log_chr_writes = true;
+            }
+            "--watch" | "--log-access" => {
+                let breaks = args[i] == "--watch";
+                i += 1;
+                let spec = args.get(i).expect("Missing watchpoint spec");
+                watchpoints.push(Watchpoint::parse(spec, breaks).expect("Invalid watchpoint spec"));
+            }
+            "--break-on" => {
+                i += 1;
+                break_on = InterruptKind::parse_list(&args[i]).unwrap_or_else(|| {
+                    eprintln!("Invalid --break-on '{}', expected e.g. nmi,brk", args[i]);
+                    std::process::exit(1);
+                });
+            }
+            "--log-brk" => {
+                log_brk = true;
+            }
+            "--bench" => {
+                bench = true;
+            }
+            "--text-output" => {
+                text_output = true;
+            }
+            "--screen-text" => {
+                screen_text = true;
+            }
+            "--why-blank" => {
+                why_blank = true;
+            }
+            "--raw-frame" => {
+                i += 1;
+                raw_frame = Some(args[i].clone());
+            }
+            "--nametable-png" => {
+                i += 1;
+                nametable_png = Some(args[i].clone());
+            }
+            "--apu-log" => {
+                i += 1;
+                apu_log = Some(args[i].clone());
+            }
+            "--dump-ram" | "--dump-vram" => {
+                let kind = if args[i] == "--dump-ram" {
+                    DumpKind::Ram
+                } else {
+                    DumpKind::Vram
+                };
+                i += 1;
+                dumps.push(MemoryDump::parse(kind, &args[i]).unwrap_or_else(|| {
+                    eprintln!("Invalid dump '{}', expected <file>[@frame=N]", args[i]);
+                    std::process::exit(1);
+                }));
+            }
+            "--charset" => {
+                i += 1;
+                text_output = true;
+                charset = Some(if args[i] == "ascii" {
+                    CharTable::ascii()
+                } else {
+                    CharTable::load(&args[i]).unwrap_or_else(|e| {
+                        eprintln!("Invalid --charset '{}': {}", args[i], e);
+                        std::process::exit(1);
+                    })
+                });
+            }
+            "--random-ram" => {
+                random_ram_seed = Some(clock_seed());
+            }
+            arg if arg.starts_with("--random-ram=") => {
+                let seed = &arg["--random-ram=".len()..];
+                random_ram_seed = Some(seed.parse().expect("Invalid --random-ram seed"));
+            }
             other => {
                 eprintln!("Unknown option: {}", other);
                 std::process::exit(1);
@@ -105,9 +427,43 @@ fn parse_args() -> Args {
         rom_path,
         max_frames,
         inputs,
+        input_macro,
+        input_script,
+        assertions,
         captures,
         capture_dir,
+        compares,
         all_frames,
+        hide_bg,
+        hide_sprites,
+        profile,
+        no_sprite_limit,
+        oam_quirks,
+        threaded_ppu,
+        no_ppu_warmup,
+        color_filter,
+        forced_region,
+        forced_bus_conflicts,
+        barcodes,
+        vs_dip,
+        vs_ppu,
+        coins,
+        power_on,
+        random_ram_seed,
+        chr_protect,
+        log_chr_writes,
+        watchpoints,
+        break_on,
+        log_brk,
+        bench,
+        text_output,
+        screen_text,
+        why_blank,
+        nametable_png,
+        raw_frame,
+        apu_log,
+        dumps,
+        charset,
     }
 }
 
@@ -121,37 +477,375 @@ fn save_ppm(frame: u32, buffer: &[u8], dir: &str) {
     file.write_all(buffer).expect("Failed to write PPM data");
 }
 
+/// Diff a frame with a reference PPM; prints the result and writes an
+/// overlay PNG on mismatch. Returns whether the frames match.
+fn compare_frame(frame: u32, buffer: &[u8], reference: &str, dir: &str) -> bool {
+    let (width, height, expected) = load_ppm(reference).expect("Failed to read reference PPM");
+    let diff = diff_frames(width, height, buffer, &expected);
+    let Some((left, top, right, bottom)) = diff.bounds() else {
+        eprintln!("Frame {}: matches {}", frame, reference);
+        return true;
+    };
+    eprintln!(
+        "Frame {}: {} pixels differ from {} in ({},{})-({},{})",
+        frame,
+        diff.mismatched_pixels(),
+        reference,
+        left,
+        top,
+        right,
+        bottom
+    );
+    eprint!("{}", diff.text_map());
+    std::fs::create_dir_all(dir).expect("Failed to create capture directory");
+    let path = format!("{}/diff_{:04}.png", dir, frame);
+    write_rgb24_png(&path, width, height, &diff.overlay_rgb24(&expected))
+        .expect("Failed to write diff PNG");
+    eprintln!("Frame {}: diff written to {}", frame, path);
+    false
+}
+
 fn main() {
     let args = parse_args();
 
     eprintln!("Loading ROM: {}", args.rom_path);
     let mut nes = Nes::new();
+    // No sound output: drop samples rather than let them pile up
+    nes.set_audio_sink(AudioSink::Null);
+    if let Some(state) = args.power_on {
+        if let PowerOnState::Random(seed) = state {
+            eprintln!("Random power-on state (seed {})", seed);
+        }
+        nes.set_power_on_state(state);
+    }
+    if let Some(seed) = args.random_ram_seed {
+        eprintln!("Randomizing RAM (seed {})", seed);
+        nes.randomize_ram(seed);
+    }
+    nes.set_forced_region(args.forced_region);
+    nes.set_forced_bus_conflicts(args.forced_bus_conflicts);
+    // --profile, else the game DB's entry for the ROM, else balanced
+    let game_db = GameDb::for_rom(&args.rom_path);
+    let profile = args
+        .profile
+        .or_else(|| {
+            if game_db.is_empty() {
+                return None;
+            }
+            let crc32 = Cartridge::load(&args.rom_path).ok()?.rom_crc32();
+            game_db.profile_for(crc32)
+        })
+        .unwrap_or_default();
+    eprintln!("Accuracy profile: {}", profile.name());
+    let mut accuracy = profile.settings();
+    accuracy.sprite_limit &= !args.no_sprite_limit;
+    accuracy.oam_quirks |= args.oam_quirks;
+    accuracy.threaded_rendering |= args.threaded_ppu;
+    accuracy.ppu_warmup &= !args.no_ppu_warmup;
+    accuracy.apply(&mut nes);
+    // Before loading, so --break-on reset sees the power-on reset
+    nes.set_interrupt_breaks(&args.break_on);
+    nes.set_brk_logging(args.log_brk);
     nes.load_rom(&args.rom_path).expect("Failed to load ROM");
+    if args.apu_log.is_some() {
+        nes.start_apu_log();
+    }
+    eprintln!("Timing: {}", nes.region().name());
+    if let Some(inst_rom) = nes.playchoice_inst_rom() {
+        eprintln!(
+            "PlayChoice-10 dump: {} byte INST-ROM ignored",
+            inst_rom.len()
+        );
+    }
+    nes.set_background_visible(!args.hide_bg);
+    nes.set_sprites_visible(!args.hide_sprites);
+    if let Some(switches) = args.vs_dip {
+        nes.set_vs_dip_switches(switches);
+    }
+    if let Some(vs_ppu) = args.vs_ppu {
+        nes.set_vs_ppu(vs_ppu);
+    }
+    nes.set_chr_write_protect(args.chr_protect);
+    nes.set_chr_write_logging(args.log_chr_writes);
+    for watchpoint in &args.watchpoints {
+        nes.add_watchpoint(*watchpoint);
+    }
 
     eprintln!("Running {} frames...", args.max_frames);
+    let started = Instant::now();
+    let mut totals = FrameStats::default();
     let mut frame_count = 0u32;
+    let mut held = ControllerState::empty();
+    let mut frame_mismatch = false;
+    let mut script_runner = args.input_script.as_ref().map(InputScript::runner);
+    let mut assertion_results = Vec::new();
+    check_assertions(&args.assertions, Some(0), 0, &nes, &mut assertion_results);
+    write_dumps(&args.dumps, Some(0), &nes);
     while frame_count < args.max_frames {
         // Apply input changes at frame start
         if let Some(&buttons) = args.inputs.get(&frame_count) {
+            held = buttons;
             nes.set_controller(buttons);
-            eprintln!("Frame {}: controller = 0x{:02X}", frame_count, buttons);
+            eprintln!(
+                "Frame {}: controller = 0x{:02X}",
+                frame_count,
+                buttons.bits()
+            );
         }
+        // Macro and script buttons are pressed on top of --input
+        let mut overlay = None;
+        let mut events = Vec::new();
+        if let Some(input_macro) = &args.input_macro {
+            events.extend(input_macro.events_at(frame_count as usize));
+            let frame = input_macro.frames().get(frame_count as usize);
+            overlay = Some(frame.copied().unwrap_or_default());
+        }
+        if let Some(runner) = script_runner.as_mut() {
+            let (buttons, script_events) = runner
+                .next_frame(frame_count as usize, &nes)
+                .unwrap_or_else(|failure| {
+                    eprintln!("Script failed: {}", failure);
+                    std::process::exit(1);
+                });
+            events.extend(script_events);
+            overlay = Some(overlay.unwrap_or_default() | buttons);
+        }
+        for event in events {
+            if let Err(e) = nes.apply_console_event(event) {
+                eprintln!("Frame {}: {} failed: {}", frame_count, event.name(), e);
+                std::process::exit(1);
+            }
+            eprintln!("Frame {}: {}", frame_count, event.name());
+        }
+        if let Some(overlay) = overlay {
+            nes.set_controller(held | overlay);
+        }
+        if let Some(code) = args.barcodes.get(&frame_count) {
+            match nes.datach_swipe_barcode(code) {
+                Ok(()) => eprintln!("Frame {}: barcode {}", frame_count, code),
+                Err(e) => eprintln!("Frame {}: barcode failed: {}", frame_count, e),
+            }
+        }
+        nes.set_vs_coin(0, args.coin_held(frame_count));
 
         // Run one frame
+        let mut hit_break = false;
         loop {
-            if nes.step() {
+            let frame_complete = nes.step();
+            if nes.take_watch_break() {
+                hit_break = true;
+                break;
+            }
+            if frame_complete {
                 break;
             }
         }
+        if args.bench && !hit_break {
+            totals += nes.frame_stats();
+        }
+        for hit in nes.take_watch_hits() {
+            eprintln!("[{}] {}", hit.source.clock, hit);
+        }
+        for entry in nes.take_interrupt_log() {
+            eprintln!("[{}] {}", entry.clock, entry);
+        }
+        for write in nes.take_chr_write_log() {
+            eprintln!(
+                "Frame {}: CHR write ${:04X} = ${:02X} from PC=${:04X} bank={}{}",
+                frame_count,
+                write.addr,
+                write.value,
+                write.source.pc,
+                write.source.prg_bank,
+                if write.blocked { " (blocked)" } else { "" }
+            );
+        }
 
         // Capture if requested
         if args.should_capture(frame_count) {
-            save_ppm(frame_count, nes.get_frame_buffer(), &args.capture_dir);
+            match args.color_filter {
+                Some(filter) => {
+                    let mut frame = nes.get_frame_buffer().to_vec();
+                    apply_color_filter_rgb24(&mut frame, filter);
+                    save_ppm(frame_count, &frame, &args.capture_dir);
+                }
+                None => save_ppm(frame_count, nes.get_frame_buffer(), &args.capture_dir),
+            }
             eprintln!("Frame {}: captured", frame_count);
         }
 
+        if let Some(reference) = args.compares.get(&frame_count) {
+            if !compare_frame(
+                frame_count,
+                nes.get_frame_buffer(),
+                reference,
+                &args.capture_dir,
+            ) {
+                frame_mismatch = true;
+            }
+        }
+
+        if hit_break {
+            eprintln!("Frame {}: stopped at breakpoint", frame_count);
+            break;
+        }
+
         frame_count += 1;
+        let frame = frame_count as usize;
+        check_assertions(
+            &args.assertions,
+            Some(frame),
+            frame,
+            &nes,
+            &mut assertion_results,
+        );
+        write_dumps(&args.dumps, Some(frame), &nes);
     }
 
     eprintln!("Done. {} frames executed.", frame_count);
+    if !nes.telemetry().is_empty() {
+        eprintln!("Emulation problems this session:");
+        for line in nes.telemetry().report() {
+            eprintln!("  {}", line);
+        }
+    }
+    write_dumps(&args.dumps, None, &nes);
+    for dump in &args.dumps {
+        if dump.frame.is_some_and(|frame| frame > frame_count as usize) {
+            eprintln!(
+                "{} not written: run ended after {} frames",
+                dump.path, frame_count
+            );
+        }
+    }
+    if args.text_output {
+        print!(
+            "{}",
+            nametable_text(&nes.nametable_tiles(0), args.charset.as_ref())
+        );
+    }
+    if let (Some(path), Some(log)) = (&args.apu_log, nes.take_apu_log()) {
+        let cpu_clock = nes.region().cpu_clock_rate() as f64;
+        log.save(path, cpu_clock).expect("Failed to write APU log");
+        eprintln!("{} APU writes saved to {}", log.writes().len(), path);
+    }
+    if let Some(path) = &args.nametable_png {
+        let view = nes.nametable_view_rgb24(true);
+        write_rgb24_png(path, VIEW_WIDTH, VIEW_HEIGHT, &view).expect("Failed to write PNG");
+        eprintln!("Nametables written to {}", path);
+    }
+    if let Some(path) = &args.raw_frame {
+        let bytes: Vec<u8> = nes
+            .get_raw_frame_buffer()
+            .iter()
+            .flat_map(|pixel| pixel.to_le_bytes())
+            .collect();
+        std::fs::write(path, bytes).expect("Failed to write raw frame");
+        eprintln!("Raw frame written to {}", path);
+    }
+    if args.screen_text {
+        let charset = args.charset.clone().or_else(|| {
+            nes.rom_crc32()
+                .and_then(|crc32| CharTable::for_rom("charsets", crc32))
+        });
+        if charset.is_none() {
+            eprintln!("--screen-text: no --charset given and no charsets/<CRC32>.tbl for this ROM");
+        }
+        nes.set_charset(charset);
+        for text in nes.read_screen_text() {
+            println!("{},{}: {}", text.row, text.col, text.text);
+        }
+    }
+    if args.why_blank {
+        print!("{}", blank_screen::diagnose(&nes));
+    }
+    if let Some(pending) = script_runner.and_then(|r| r.pending_wait(frame_count as usize)) {
+        eprintln!("Script failed: {}", pending);
+        std::process::exit(1);
+    }
+    let frames_run = frame_count as usize;
+    check_assertions(
+        &args.assertions,
+        None,
+        frames_run,
+        &nes,
+        &mut assertion_results,
+    );
+    for assertion in &args.assertions {
+        if assertion.frame.is_some_and(|frame| frame > frames_run) {
+            eprintln!(
+                "Assert FAIL {}: run ended after {} frames",
+                assertion, frames_run
+            );
+            assertion_results.push(AssertionResult {
+                passed: false,
+                ..assertion.check(frames_run, &nes)
+            });
+        }
+    }
+    if assertion_results.iter().any(|result| !result.passed) {
+        frame_mismatch = true;
+    }
+    if frame_mismatch {
+        std::process::exit(1);
+    }
+    if args.bench && frame_count > 0 {
+        print_bench(
+            &totals,
+            frame_count,
+            started.elapsed().as_secs_f64(),
+            nes.region(),
+        );
+    }
+}
+
+fn print_bench(totals: &FrameStats, frames: u32, secs: f64, region: Region) {
+    let fps = frames as f64 / secs;
+    let native_fps = 1.0 / region.frame_duration().as_secs_f64();
+    let per_frame = |count: u64| count as f64 / frames as f64;
+    eprintln!(
+        "Bench: {:.2}s, {:.1} fps ({:.0}% of {} speed)",
+        secs,
+        fps,
+        fps / native_fps * 100.0,
+        region.name()
+    );
+    eprintln!("Totals: {}", totals);
+    eprintln!(
+        "Per frame: CPU {:.1}, dots {:.1}, NMI {:.2}, IRQ {:.2}, DMA stall {:.1}, samples {:.1}",
+        per_frame(totals.cpu_cycles),
+        per_frame(totals.ppu_dots),
+        per_frame(totals.nmis as u64),
+        per_frame(totals.irqs as u64),
+        per_frame(totals.dma_stall_cycles as u64),
+        per_frame(totals.apu_samples as u64)
+    );
+}
+
+/// Write the dumps due at `due` (`None`: end of run).
+fn write_dumps(dumps: &[MemoryDump], due: Option<usize>, nes: &Nes) {
+    for dump in dumps.iter().filter(|dump| dump.frame == due) {
+        match dump.write(nes) {
+            Ok(len) => eprintln!("{} bytes dumped to {}", len, dump.path),
+            Err(e) => {
+                eprintln!("Failed to write {}: {}", dump.path, e);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+/// Check the assertions due at `due` (`None`: end of run) and print each.
+fn check_assertions(
+    assertions: &[RamAssertion],
+    due: Option<usize>,
+    frame: usize,
+    nes: &Nes,
+    results: &mut Vec<AssertionResult>,
+) {
+    for assertion in assertions.iter().filter(|a| a.frame == due) {
+        let result = assertion.check(frame, nes);
+        eprintln!("Assert {}", result);
+        results.push(result);
+    }
 }
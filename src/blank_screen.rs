@@ -0,0 +1,218 @@
+// "Why is the screen black?": checks the PPU state a blank picture usually
+// comes from ($2001 rendering bits, NMI enable, an all-black palette, empty
+// pattern tables, blank tiles, a jammed CPU) and explains each hit in plain
+// words. Used by the `B` key and `headless_test --why-blank`.
+use crate::Nes;
+use std::fmt;
+
+/// NES colors that show as black on a TV ($xD-$xF columns, minus the greys).
+const BLACKS: [u8; 10] = [0x0D, 0x0E, 0x0F, 0x1D, 0x1E, 0x1F, 0x2E, 0x2F, 0x3E, 0x3F];
+
+/// One reason the picture may be blank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlankCause {
+    CpuHalted,
+    RenderingDisabled { mask: u8 },
+    NmiDisabled { ctrl: u8 },
+    PaletteBlack,
+    PaletteFlat { color: u8 },
+    PatternTableEmpty { table: u16, sprites: bool },
+    BlankTiles { table: u16 },
+}
+
+impl fmt::Display for BlankCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            BlankCause::CpuHalted => write!(
+                f,
+                "The CPU executed a JAM opcode and stopped; nothing will change until reset."
+            ),
+            BlankCause::RenderingDisabled { mask } => write!(
+                f,
+                "Rendering is off ($2001 = ${:02X}, bits 3-4 clear): only the backdrop color is drawn.",
+                mask
+            ),
+            BlankCause::NmiDisabled { ctrl } => write!(
+                f,
+                "NMI is off ($2000 = ${:02X}, bit 7 clear) and none fired last frame: games that update the screen from their NMI handler never run it.",
+                ctrl
+            ),
+            BlankCause::PaletteBlack => write!(
+                f,
+                "Every palette entry in use is black, so tiles and sprites draw black on black."
+            ),
+            BlankCause::PaletteFlat { color } => write!(
+                f,
+                "Every palette entry in use is ${:02X}, so tiles are invisible against the backdrop.",
+                color
+            ),
+            BlankCause::PatternTableEmpty { table, sprites } => write!(
+                f,
+                "The {} pattern table at ${:04X} is all zero (CHR-RAM not filled yet, or the wrong CHR bank is mapped).",
+                if sprites { "sprite" } else { "background" },
+                table
+            ),
+            BlankCause::BlankTiles { table } => write!(
+                f,
+                "Every tile on screen uses an empty pattern from ${:04X} (nametables cleared or not written yet).",
+                table
+            ),
+        }
+    }
+}
+
+/// What the last frame looked like and the likely causes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlankScreenReport {
+    /// Set when every pixel of the last frame has the same color.
+    pub blank_color: Option<u8>,
+    pub causes: Vec<BlankCause>,
+}
+
+impl fmt::Display for BlankScreenReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.blank_color {
+            Some(color) => writeln!(f, "Screen is blank (every pixel is color ${:02X})", color)?,
+            None => writeln!(f, "Screen shows a picture")?,
+        }
+        if self.causes.is_empty() {
+            writeln!(f, "- No obvious cause found in the PPU state.")?;
+        }
+        for cause in &self.causes {
+            writeln!(f, "- {}", cause)?;
+        }
+        Ok(())
+    }
+}
+
+/// Inspect the console as it is now.
+pub fn diagnose(nes: &Nes) -> BlankScreenReport {
+    let frame = nes.get_indexed_frame_buffer();
+    let blank_color = match frame.first() {
+        Some(&first) if frame.iter().all(|&c| c == first) => Some(first),
+        _ => None,
+    };
+
+    let registers = nes.io_registers();
+    let (ctrl, mask) = (registers.ppu_ctrl, registers.ppu_mask);
+    let background = mask & 0x08 != 0;
+    let sprites = mask & 0x10 != 0;
+    let mut causes = Vec::new();
+
+    if nes.cpu_halted() {
+        causes.push(BlankCause::CpuHalted);
+    }
+    if !background && !sprites {
+        causes.push(BlankCause::RenderingDisabled { mask });
+    }
+    if ctrl & 0x80 == 0 && nes.frame_stats().nmis == 0 {
+        causes.push(BlankCause::NmiDisabled { ctrl });
+    }
+
+    if background || sprites {
+        let palette = nes.ppu_palette();
+        let in_use: Vec<u8> = (0..32)
+            .filter(|&i| i == 0 || (i % 4 != 0 && if i < 16 { background } else { sprites }))
+            .map(|i| palette[i] & 0x3F)
+            .collect();
+        if in_use.iter().all(|color| BLACKS.contains(color)) {
+            causes.push(BlankCause::PaletteBlack);
+        } else if in_use.iter().all(|&color| color == in_use[0]) {
+            causes.push(BlankCause::PaletteFlat { color: in_use[0] });
+        }
+    }
+
+    let tile_empty = |table: u16, tile: u8| {
+        let base = table + tile as u16 * 16;
+        (base..base + 16).all(|addr| nes.peek_chr(addr) == 0)
+    };
+    if background {
+        let table = (ctrl as u16 & 0x10) << 8;
+        if (0..=255).all(|tile| tile_empty(table, tile)) {
+            causes.push(BlankCause::PatternTableEmpty {
+                table,
+                sprites: false,
+            });
+        } else if nes
+            .screen_tiles()
+            .iter()
+            .all(|&tile| tile_empty(table, tile))
+        {
+            causes.push(BlankCause::BlankTiles { table });
+        }
+    }
+    if sprites && ctrl & 0x20 == 0 {
+        let table = (ctrl as u16 & 0x08) << 9;
+        if (0..=255).all(|tile| tile_empty(table, tile)) {
+            causes.push(BlankCause::PatternTableEmpty {
+                table,
+                sprites: true,
+            });
+        }
+    }
+
+    BlankScreenReport {
+        blank_color,
+        causes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::nes_with_rom;
+
+    fn nes_with_program(program: &[u8]) -> Nes {
+        // NROM-128 with zeroed CHR-ROM; the program runs from $C000
+        let mut rom = vec![b'N', b'E', b'S', 0x1A, 1, 1, 0, 0];
+        rom.resize(16 + 0x4000 + 0x2000, 0);
+        rom[16..16 + program.len()].copy_from_slice(program);
+        // NMI -> $C017 (an RTI in the second test), reset -> $C000
+        rom[16 + 0x3FFA..16 + 0x3FFE].copy_from_slice(&[0x17, 0xC0, 0x00, 0xC0]);
+        nes_with_rom(&rom)
+    }
+
+    fn run_frames(nes: &mut Nes, frames: usize) {
+        for _ in 0..frames {
+            while !nes.step() {}
+        }
+    }
+
+    #[test]
+    fn explains_rendering_off_and_empty_chr() {
+        // JMP * without touching the PPU
+        let mut idle = nes_with_program(&[0x4C, 0x00, 0xC0]);
+        run_frames(&mut idle, 2);
+        let report = diagnose(&idle);
+        assert!(report.blank_color.is_some());
+        assert!(report
+            .causes
+            .contains(&BlankCause::RenderingDisabled { mask: 0 }));
+        assert!(report.causes.contains(&BlankCause::NmiDisabled { ctrl: 0 }));
+        assert!(report.to_string().starts_with("Screen is blank"));
+
+        // Wait out the PPU warm-up, then NMI + BG/SPR on with zeroed CHR
+        let mut program = vec![0xA2, 0x04, 0x2C, 0x02, 0x20, 0x10, 0xFB, 0xCA, 0xD0, 0xF8];
+        program.extend_from_slice(&[0xA9, 0x80, 0x8D, 0x00, 0x20, 0xA9, 0x1E, 0x8D, 0x01, 0x20]);
+        program.extend_from_slice(&[0x4C, 0x14, 0xC0, 0x40]);
+        let mut lit = nes_with_program(&program);
+        run_frames(&mut lit, 8);
+        let report = diagnose(&lit);
+        assert_eq!(
+            report.causes,
+            vec![
+                BlankCause::PatternTableEmpty {
+                    table: 0x0000,
+                    sprites: false
+                },
+                BlankCause::PatternTableEmpty {
+                    table: 0x0000,
+                    sprites: true
+                },
+            ]
+        );
+        assert!(report
+            .to_string()
+            .contains("The background pattern table at $0000 is all zero"));
+    }
+}
@@ -0,0 +1,115 @@
+// Built-in "no cartridge" program, booted when the emulator starts without a
+// ROM: a generated NROM image whose CHR is the HUD font, showing a short
+// message while the ROM browser is open over it. Built-in programs are
+// loaded from memory under a `builtin:` name, never from a file.
+use crate::hud_toast::glyph_5x7;
+
+const BUILTIN_PREFIX: &str = "builtin:";
+
+// 6502 program at $C000: copy the palette and a full nametable (attributes
+// included) from PRG, turn the background on and spin. NMI and IRQ are a
+// bare RTI at $C064.
+#[rustfmt::skip]
+const PROGRAM: [u8; 0x65] = [
+    // reset: SEI, CLD, LDX #$FF, TXS, PPU off
+    0x78, 0xD8, 0xA2, 0xFF, 0x9A, 0xA9, 0x00, 0x8D, 0x00, 0x20, 0x8D, 0x01, 0x20,
+    // wait for two vblanks
+    0x2C, 0x02, 0x20, 0x10, 0xFB, 0x2C, 0x02, 0x20, 0x10, 0xFB,
+    // palette: $3F00 <- 32 bytes at $C065
+    0xA9, 0x3F, 0x8D, 0x06, 0x20, 0xA9, 0x00, 0x8D, 0x06, 0x20, 0xA2, 0x00,
+    0xBD, 0x65, 0xC0, 0x8D, 0x07, 0x20, 0xE8, 0xE0, 0x20, 0xD0, 0xF5,
+    // $2000 <- 1KB at $C100 through the pointer at $00
+    0xA9, 0x20, 0x8D, 0x06, 0x20, 0xA9, 0x00, 0x8D, 0x06, 0x20,
+    0xA9, 0x00, 0x85, 0x00, 0xA9, 0xC1, 0x85, 0x01, 0xA2, 0x04, 0xA0, 0x00,
+    0xB1, 0x00, 0x8D, 0x07, 0x20, 0xC8, 0xD0, 0xF8, 0xE6, 0x01, 0xCA, 0xD0,
+    0xF3,
+    // scroll 0, background on; then spin
+    0xA9, 0x00, 0x8D, 0x00, 0x20, 0x8D, 0x05, 0x20, 0x8D, 0x05, 0x20,
+    0xA9, 0x0A, 0x8D, 0x01, 0x20, 0x4C, 0x61, 0xC0,
+    // nmi/irq
+    0x40,
+];
+
+const PALETTE: [u8; 4] = [0x01, 0x30, 0x21, 0x11];
+const NAMETABLE_OFFSET: usize = 0x100;
+const RESET_VECTOR: u16 = 0xC000;
+const RTI_VECTOR: u16 = 0xC064;
+
+/// Text rows of the boot screen, centered on the nametable.
+const MESSAGE: [(usize, &str); 3] = [
+    (12, "NO CARTRIDGE"),
+    (15, "PRESS O TO OPEN A ROM"),
+    (17, "OR DROP ONE ON THE WINDOW"),
+];
+
+/// The boot screen as an iNES image: 16KB PRG, 8KB CHR, mapper 0. Tile
+/// `n` is the HUD glyph for ASCII `n`.
+pub fn boot_screen_rom() -> Vec<u8> {
+    let mut prg = vec![0u8; 0x4000];
+    prg[..PROGRAM.len()].copy_from_slice(&PROGRAM);
+    for i in 0..8 {
+        prg[PROGRAM.len() + i * 4..][..4].copy_from_slice(&PALETTE);
+    }
+    let nametable = &mut prg[NAMETABLE_OFFSET..NAMETABLE_OFFSET + 0x400];
+    nametable[..960].fill(b' ');
+    for (row, text) in MESSAGE {
+        let col = (32 - text.len()) / 2;
+        nametable[row * 32 + col..][..text.len()].copy_from_slice(text.as_bytes());
+    }
+    prg[0x3FFA..0x3FFC].copy_from_slice(&RTI_VECTOR.to_le_bytes());
+    prg[0x3FFC..0x3FFE].copy_from_slice(&RESET_VECTOR.to_le_bytes());
+    prg[0x3FFE..0x4000].copy_from_slice(&RTI_VECTOR.to_le_bytes());
+
+    let mut chr = vec![0u8; 0x2000];
+    for code in 0x20u8..0x60 {
+        let tile = &mut chr[code as usize * 16..][..8];
+        for (row, bits) in glyph_5x7(code as char).iter().enumerate() {
+            tile[row] = bits << 2;
+        }
+    }
+
+    let mut rom = vec![b'N', b'E', b'S', 0x1A, 1, 1];
+    rom.resize(16, 0);
+    rom.extend_from_slice(&prg);
+    rom.extend_from_slice(&chr);
+    rom
+}
+
+/// Name a built-in program goes by once loaded with `Nes::load_rom_bytes`.
+pub fn builtin_rom_name(name: &str) -> String {
+    format!("{}{}", BUILTIN_PREFIX, name)
+}
+
+/// Whether `path` is a built-in program rather than the user's ROM; these
+/// stay out of the play history and auto-resume states.
+pub fn is_builtin_rom(path: &str) -> bool {
+    path.starts_with(BUILTIN_PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Nes;
+
+    #[test]
+    fn boots_to_the_message() {
+        let name = builtin_rom_name("boot");
+        assert!(is_builtin_rom(&name));
+        assert!(!is_builtin_rom("roms/builtin_boot.nes"));
+
+        let mut nes = Nes::new();
+        nes.load_rom_bytes(&name, &boot_screen_rom()).unwrap();
+        assert_eq!(nes.rom_path(), Some(name.as_str()));
+        for _ in 0..5 {
+            while !nes.step() {}
+        }
+
+        // "N" of NO CARTRIDGE at tile row 12, column 10; glyphs start one
+        // pixel into the tile
+        let frame = nes.get_frame_buffer();
+        let lit = |x: usize, y: usize| frame[(y * 256 + x) * 3..][..3] != frame[..3];
+        assert!(lit(10 * 8 + 1, 12 * 8));
+        assert!(!lit(10 * 8, 12 * 8));
+        assert!(!lit(0, 0));
+    }
+}
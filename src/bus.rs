@@ -1,8 +1,46 @@
 use crate::apu::{Apu, ApuState};
-use crate::cartridge::{Cartridge, CartridgeState};
+use crate::apu_log::ApuWriteLog;
+use crate::cartridge::{BoardLeds, Cartridge, CartridgeState};
+use crate::cheat::CheatManager;
+use crate::clock::ConsoleClock;
 use crate::cpu::CpuBus;
+use crate::debugger::{
+    AccessSource, AddressSpace, ChrWrite, ChrWriteMonitor, InterruptEntry, InterruptKind,
+    InterruptMonitor, WatchHit, Watchpoint, Watchpoints,
+};
+use crate::input::ControllerFeedback;
+use crate::io_registers::IoRegisters;
 use crate::memory::Memory;
-use crate::ppu::Ppu;
+use crate::nametable_view::render_nametables_rgb24;
+use crate::patch_list::{Patch, PatchList, PatchStatus};
+use crate::power_on::PowerOnState;
+use crate::ppu::{Ppu, PpuControl, VsPpu};
+use crate::region::Region;
+use bitflags::bitflags;
+
+bitflags! {
+    /// Devices holding the CPU's /IRQ line low. The line is their wired-OR,
+    /// so more than one can be set at once.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct IrqSources: u8 {
+        const FRAME_COUNTER = 0b001;
+        const DMC = 0b010;
+        const MAPPER = 0b100;
+    }
+}
+
+impl IrqSources {
+    /// Names of the set sources, e.g. `["frame counter", "DMC"]`.
+    pub fn names(self) -> Vec<&'static str> {
+        self.iter()
+            .map(|source| match source {
+                IrqSources::FRAME_COUNTER => "frame counter",
+                IrqSources::DMC => "DMC",
+                _ => "mapper",
+            })
+            .collect()
+    }
+}
 
 pub struct Bus {
     memory: Memory,
@@ -10,13 +48,38 @@ pub struct Bus {
     apu: Apu,
     cartridge: Option<Cartridge>,
     pub controller: u8,
-    controller_state: u16,
+    controller_state: u8,  // Pad 1 shift register (4021), bit 0 next
     strobe: bool,          // Controller strobe mode
+    microphone: bool,      // Famicom controller 2 microphone, read at $4016 bit 2
+    vs_dip_switches: u8,   // Vs. System DIP switches 1-8 (bit 0 = switch 1)
+    vs_coin: u8,           // Vs. System $4016 bits: 2 = service, 5/6 = coin slots
     dma_cycles: u32,       // Cycles to add due to DMA operations
     dma_in_progress: bool, // Flag to indicate DMA is in progress
     dmc_stall_cycles: u32,
+    instruction_pc: u16,    // Opcode address of the executing instruction
+    instruction_cycle: u64, // CPU cycle count when it started
+    chr_monitor: ChrWriteMonitor,
+    watchpoints: Watchpoints,
+    interrupts: InterruptMonitor,
+    region: Region,
+    forced_region: Option<Region>, // --force-ntsc/--force-pal, beats the header
+    forced_bus_conflicts: Option<bool>, // --[no-]bus-conflicts, beats the board
+    ppu_dot_phase: u32,            // CPU cycles into the PPU:CPU clock ratio
+    io_writes: [u8; 0x18],         // Last values written to $4000-$4017
+    rmw_second_write: bool,        // Set for the modified write of a RMW instruction
+    ppu_warmup: bool,              // Drop early PPU writes after power/reset
+    apu_log: Option<ApuWriteLog>,  // APU register writes for music ripping
+    power_on: PowerOnState,        // Fill for PPU memory at power-on
+    ram_seed: Option<u64>,         // --random-ram seed for CPU RAM
+    feedback: Vec<ControllerFeedback>, // Rumble/LED changes not yet taken by the frontend
+    rumble: bool,                  // $4016 OUT1, read as player 1's rumble motor
+    leds_seen: Option<BoardLeds>,  // Board LEDs as last reported in `feedback`
+    patches: PatchList,            // Debugger ROM patches and RAM freezes
 }
 
+/// Feedback kept when the frontend doesn't take it (headless runs).
+const FEEDBACK_QUEUE_LIMIT: usize = 64;
+
 impl Bus {
     pub fn new() -> Self {
         Bus {
@@ -25,15 +88,40 @@ impl Bus {
             apu: Apu::new(),
             cartridge: None,
             controller: 0,
-            controller_state: 0,
+            controller_state: 0xFF,
             strobe: false,
+            microphone: false,
+            vs_dip_switches: 0,
+            vs_coin: 0,
             dma_cycles: 0,
             dma_in_progress: false,
             dmc_stall_cycles: 0,
+            instruction_pc: 0,
+            instruction_cycle: 0,
+            chr_monitor: ChrWriteMonitor::default(),
+            watchpoints: Watchpoints::default(),
+            interrupts: InterruptMonitor::default(),
+            region: Region::Ntsc,
+            forced_region: None,
+            forced_bus_conflicts: None,
+            ppu_dot_phase: 0,
+            io_writes: [0; 0x18],
+            rmw_second_write: false,
+            ppu_warmup: true,
+            apu_log: None,
+            power_on: PowerOnState::default(),
+            ram_seed: None,
+            feedback: Vec::new(),
+            rumble: false,
+            leds_seen: None,
+            patches: PatchList::default(),
         }
     }
 
-    pub fn load_cartridge(&mut self, cartridge: Cartridge) {
+    pub fn load_cartridge(&mut self, mut cartridge: Cartridge) {
+        cartridge.set_forced_bus_conflicts(self.forced_bus_conflicts);
+        self.ppu.set_vs_ppu(cartridge.vs_ppu().unwrap_or_default());
+        self.apply_region(self.forced_region.unwrap_or(cartridge.region()));
         self.cartridge = Some(cartridge);
     }
 
@@ -45,6 +133,53 @@ impl Bus {
         }
     }
 
+    /// Run under `region` regardless of what the cartridge header says;
+    /// `None` goes back to the header's timing.
+    pub fn set_forced_region(&mut self, region: Option<Region>) {
+        self.forced_region = region;
+        let header = self
+            .cartridge
+            .as_ref()
+            .map(|cartridge| cartridge.region())
+            .unwrap_or_default();
+        self.apply_region(region.unwrap_or(header));
+    }
+
+    /// Emulate (`Some(true)`) or ignore (`Some(false)`) bus conflicts on
+    /// every board; `None` follows the board.
+    pub fn set_forced_bus_conflicts(&mut self, forced: Option<bool>) {
+        self.forced_bus_conflicts = forced;
+        if let Some(ref mut cartridge) = self.cartridge {
+            cartridge.set_forced_bus_conflicts(forced);
+        }
+    }
+
+    pub fn region(&self) -> Region {
+        self.region
+    }
+
+    fn apply_region(&mut self, region: Region) {
+        self.region = region;
+        self.ppu_dot_phase = 0;
+        self.ppu.set_region(region);
+        self.apu.set_region(region);
+    }
+
+    /// PPU dots to run for the next CPU cycle: always 3 on NTSC, 3.2 on
+    /// average on PAL (3,3,3,3,4).
+    #[inline]
+    pub fn ppu_dots_for_cpu_cycle(&mut self) -> u32 {
+        let (dots, cycles) = self.region.ppu_dots_per_cpu_cycles();
+        let start = self.ppu_dot_phase * dots / cycles;
+        self.ppu_dot_phase = (self.ppu_dot_phase + 1) % cycles;
+        let end = if self.ppu_dot_phase == 0 {
+            dots
+        } else {
+            self.ppu_dot_phase * dots / cycles
+        };
+        end - start
+    }
+
     #[inline]
     pub fn step_ppu(&mut self) -> bool {
         let nmi = self.ppu.step(self.cartridge.as_ref());
@@ -61,8 +196,8 @@ impl Bus {
     pub fn tick(&mut self, cycles: u8) -> bool {
         let mut nmi_triggered = false;
 
-        // PPU runs 3x faster than CPU
-        let ppu_cycles = cycles as u32 * 3;
+        // PPU runs 3x faster than CPU (3.2x on PAL)
+        let ppu_cycles: u32 = (0..cycles).map(|_| self.ppu_dots_for_cpu_cycle()).sum();
 
         // Step PPU for the calculated cycles
         for _cycle in 0..ppu_cycles {
@@ -87,6 +222,10 @@ impl Bus {
         nmi_triggered
     }
 
+    pub fn apu_samples_produced(&self) -> u64 {
+        self.apu.samples_produced()
+    }
+
     pub fn step_apu(&mut self) {
         let exp = if let Some(ref mut cartridge) = self.cartridge {
             cartridge.clock_expansion_audio()
@@ -102,21 +241,93 @@ impl Bus {
         self.controller = controller;
     }
 
+    pub fn datach_swipe_barcode(&mut self, code: &str) -> Result<(), String> {
+        match self.cartridge {
+            Some(ref mut cartridge) => cartridge.datach_swipe_barcode(code),
+            None => Err("No cartridge loaded".to_string()),
+        }
+    }
+
+    /// Drive the Famicom controller 2 microphone (reported on $4016 bit 2).
+    pub fn set_microphone(&mut self, active: bool) {
+        self.microphone = active;
+    }
+
+    pub fn microphone(&self) -> bool {
+        self.microphone
+    }
+
+    pub fn playchoice_inst_rom(&self) -> Option<&[u8]> {
+        self.cartridge
+            .as_ref()
+            .and_then(|cartridge| cartridge.playchoice_inst_rom())
+    }
+
+    pub fn is_vs_system(&self) -> bool {
+        self.cartridge
+            .as_ref()
+            .is_some_and(|cartridge| cartridge.is_vs_system())
+    }
+
+    /// Override the Vs. PPU variant chosen from the ROM header.
+    pub fn set_vs_ppu(&mut self, vs_ppu: VsPpu) {
+        self.ppu.set_vs_ppu(vs_ppu);
+    }
+
+    pub fn vs_ppu(&self) -> VsPpu {
+        self.ppu.vs_ppu()
+    }
+
+    /// Set the Vs. System DIP switches (bit 0 = switch 1). Switches 1-2 are
+    /// read at $4016 bits 3-4 and switches 3-8 at $4017 bits 2-7.
+    pub fn set_vs_dip_switches(&mut self, switches: u8) {
+        self.vs_dip_switches = switches;
+    }
+
+    pub fn vs_dip_switches(&self) -> u8 {
+        self.vs_dip_switches
+    }
+
+    /// Hold a Vs. System coin slot (0 or 1) closed.
+    pub fn set_vs_coin(&mut self, slot: usize, inserted: bool) {
+        let bit = match slot {
+            0 => 0x20,
+            1 => 0x40,
+            _ => return,
+        };
+        if inserted {
+            self.vs_coin |= bit;
+        } else {
+            self.vs_coin &= !bit;
+        }
+    }
+
+    /// Hold the Vs. System service button.
+    pub fn set_vs_service(&mut self, pressed: bool) {
+        if pressed {
+            self.vs_coin |= 0x04;
+        } else {
+            self.vs_coin &= !0x04;
+        }
+    }
+
     fn read_controller(&mut self) -> u8 {
         if self.strobe {
-            // While strobe is high, continuously reload and return bit 0 (A button)
-            self.controller_state = self.controller as u16;
-            return self.controller as u8 & 0x01;
+            // While strobe is high the shift register keeps reloading, so
+            // every read returns A as it is now and nothing shifts
+            self.controller_state = self.controller;
+            return self.controller & 0x01;
         }
-        let value = if self.controller_state & 0x01 != 0 {
-            0x01
-        } else {
-            0x00
-        };
-        self.controller_state >>= 1;
+        let value = self.controller_state & 0x01;
+        // The pad's serial input is tied high: 1s after the eighth read
+        self.controller_state = (self.controller_state >> 1) | 0x80;
         value
     }
 
+    pub fn ppu_frame(&self) -> u64 {
+        self.ppu.get_frame()
+    }
+
     pub fn ppu_frame_complete(&mut self) -> bool {
         let complete = self.ppu.frame_complete;
         if complete {
@@ -129,10 +340,338 @@ impl Bus {
         self.ppu.get_buffer()
     }
 
+    pub fn get_ppu_indexed_buffer(&self) -> &[u8] {
+        self.ppu.get_indexed_buffer()
+    }
+
+    pub fn get_ppu_emphasis_buffer(&self) -> &[u8] {
+        self.ppu.get_emphasis_buffer()
+    }
+
+    pub fn get_ppu_raw_buffer(&self) -> Vec<u16> {
+        self.ppu.get_raw_buffer()
+    }
+
+    pub fn get_ppu_rgba_buffer(&self) -> Vec<u8> {
+        self.ppu.get_rgba_buffer()
+    }
+
+    /// Frame and beam position now, with `cpu_cycle` as given.
+    pub fn clock(&self, cpu_cycle: u64) -> ConsoleClock {
+        ConsoleClock {
+            frame: self.ppu.get_frame(),
+            scanline: self.ppu.get_scanline(),
+            dot: self.ppu.get_cycle(),
+            cpu_cycle,
+        }
+    }
+
+    fn access_source(&self) -> AccessSource {
+        AccessSource {
+            pc: self.instruction_pc,
+            // Components catch up after the instruction, so the PPU is
+            // still where it was when it started
+            clock: self.clock(self.instruction_cycle),
+            prg_bank: self
+                .cartridge
+                .as_ref()
+                .map(|cartridge| cartridge.get_prg_bank())
+                .unwrap_or(0),
+        }
+    }
+
+    /// Add a watchpoint, returning its index in hit reports.
+    pub fn add_watchpoint(&mut self, watchpoint: Watchpoint) -> usize {
+        self.watchpoints.add(watchpoint)
+    }
+
+    pub fn clear_watchpoints(&mut self) {
+        self.watchpoints.clear();
+    }
+
+    pub fn watchpoints(&self) -> &[Watchpoint] {
+        self.watchpoints.list()
+    }
+
+    pub fn take_watch_hits(&mut self) -> Vec<WatchHit> {
+        self.watchpoints.take_hits()
+    }
+
+    pub fn take_watch_break(&mut self) -> bool {
+        self.watchpoints.take_break()
+    }
+
+    pub fn set_interrupt_breaks(&mut self, kinds: &[InterruptKind]) {
+        self.interrupts.set_break_on(kinds);
+    }
+
+    pub fn interrupt_breaks(&self) -> &[InterruptKind] {
+        self.interrupts.break_on()
+    }
+
+    pub fn set_brk_logging(&mut self, enabled: bool) {
+        self.interrupts.set_log_brk(enabled);
+    }
+
+    pub fn record_interrupt(&mut self, entry: InterruptEntry) {
+        self.interrupts.record(entry);
+    }
+
+    pub fn take_interrupt_log(&mut self) -> Vec<InterruptEntry> {
+        self.interrupts.take_log()
+    }
+
+    pub fn take_interrupt_break(&mut self) -> bool {
+        self.interrupts.take_break()
+    }
+
+    /// CPU read without side effects: RAM, PRG-RAM and PRG-ROM; the I/O
+    /// registers read as 0.
+    pub fn peek_cpu(&self, addr: u16) -> u8 {
+        match (addr, self.cartridge.as_ref()) {
+            (0x0000..=0x1FFF, _) => self.ram_ref()[addr as usize & 0x07FF],
+            (0x6000..=0x7FFF, Some(cartridge)) => cartridge.read_prg_ram(addr),
+            (0x8000..=0xFFFF, Some(cartridge)) => {
+                self.patches.patch_rom(addr, cartridge.read_prg(addr))
+            }
+            _ => 0,
+        }
+    }
+
+    /// Drop $2007 writes to pattern table space (debugging CHR-RAM
+    /// corruption). Blocked writes still advance the VRAM address.
+    pub fn set_chr_write_protect(&mut self, protect: bool) {
+        self.chr_monitor.set_protect(protect);
+    }
+
+    pub fn chr_write_protect(&self) -> bool {
+        self.chr_monitor.protect()
+    }
+
+    pub fn set_chr_write_logging(&mut self, enabled: bool) {
+        self.chr_monitor.set_logging(enabled);
+    }
+
+    pub fn chr_write_logging(&self) -> bool {
+        self.chr_monitor.logging()
+    }
+
+    pub fn take_chr_write_log(&mut self) -> Vec<ChrWrite> {
+        self.chr_monitor.take_log()
+    }
+
+    /// Changes whenever CHR is written through $2007.
+    pub fn chr_generation(&self) -> u64 {
+        self.chr_monitor.generation()
+    }
+
+    pub fn peek_chr(&self, addr: u16) -> u8 {
+        match self.cartridge.as_ref() {
+            Some(cartridge) if cartridge.chr_in_ciram() => {
+                self.ppu.read_pattern(cartridge, addr, None)
+            }
+            Some(cartridge) => cartridge.peek_chr(addr),
+            None => 0,
+        }
+    }
+
+    /// The 32x30 tile indices of logical nametable 0-3, attributes excluded.
+    pub fn nametable_tiles(&self, logical_nt: usize) -> Vec<u8> {
+        (0..960)
+            .map(|offset| {
+                self.ppu
+                    .peek_nametable(logical_nt, offset, self.cartridge.as_ref())
+            })
+            .collect()
+    }
+
+    /// The 32x30 tiles on screen, read from the scroll origin latched in
+    /// `t` and wrapping across nametables as rendering does.
+    pub fn screen_tiles(&self) -> Vec<u8> {
+        let t = self.ppu.get_t() as usize;
+        let (x0, y0, nt0) = (t & 0x1F, ((t >> 5) & 0x1F) % 30, (t >> 10) & 3);
+        let mut tiles = Vec::with_capacity(960);
+        for row in 0..30 {
+            let (y, wrap_y) = ((y0 + row) % 30, (y0 + row) / 30);
+            for col in 0..32 {
+                let (x, wrap_x) = ((x0 + col) % 32, (x0 + col) / 32);
+                let nt = nt0 ^ wrap_x ^ (wrap_y << 1);
+                tiles.push(
+                    self.ppu
+                        .peek_nametable(nt, y * 32 + x, self.cartridge.as_ref()),
+                );
+            }
+        }
+        tiles
+    }
+
+    /// Scroll position latched in `t` plus fine X, in pixels of the
+    /// 512x480 four-nametable view.
+    pub fn scroll_origin(&self) -> (usize, usize) {
+        let t = self.ppu.get_t() as usize;
+        let x = (t & 0x400) >> 2 | (t & 0x1F) << 3 | self.ppu.get_x_scroll() as usize;
+        let y = ((t & 0x800) >> 11) * 240 + ((t >> 5) & 0x1F) * 8 + ((t >> 12) & 7);
+        (x, y)
+    }
+
+    /// The four nametables as a 512x480 RGB24 image, attribute colors applied.
+    pub fn nametable_view_rgb24(&self) -> Vec<u8> {
+        let cartridge = self.cartridge.as_ref();
+        let bg_table = if self.ppu.get_control().contains(PpuControl::BG_PATTERN) {
+            0x1000
+        } else {
+            0x0000
+        };
+        render_nametables_rgb24(
+            |nt, offset| self.ppu.peek_nametable(nt, offset, cartridge),
+            |addr| self.peek_chr(addr),
+            &self.ppu.get_palette(),
+            bg_table,
+        )
+    }
+
+    /// Start logging APU register writes, dropping any log in progress.
+    pub fn start_apu_log(&mut self) {
+        self.apu_log = Some(ApuWriteLog::default());
+    }
+
+    pub fn take_apu_log(&mut self) -> Option<ApuWriteLog> {
+        self.apu_log.take()
+    }
+
+    /// Feed the APU write log; a DMC start also logs the sample bytes it
+    /// will play, as currently mapped.
+    fn log_apu_write(&mut self, addr: u16, data: u8) {
+        if let Some(log) = self.apu_log.as_mut() {
+            let cycle = self.apu.cycle_count();
+            if addr == 0x4015 && data & 0x10 != 0 {
+                let start = 0xC000 | (self.io_writes[0x12] as u16) << 6;
+                let len = self.io_writes[0x13] as usize * 16 + 1;
+                let peek = |addr: u16| {
+                    self.cartridge
+                        .as_ref()
+                        .map(|cartridge| cartridge.read_prg(addr))
+                        .unwrap_or(0)
+                };
+                // Playback wraps from $FFFF to $8000
+                let first = len.min(0x10000 - start as usize);
+                let data: Vec<u8> = (0..first).map(|i| peek(start + i as u16)).collect();
+                log.record_dmc_sample(cycle, start, data);
+                if first < len {
+                    let data: Vec<u8> = (0..len - first).map(|i| peek(0x8000 + i as u16)).collect();
+                    log.record_dmc_sample(cycle, 0x8000, data);
+                }
+            }
+            log.record(cycle, self.ppu.get_frame(), addr, data);
+        }
+    }
+
+    /// Fill PPU palette RAM, OAM and nametables per the power-on mode.
+    pub fn apply_power_on_state(&mut self, state: PowerOnState) {
+        self.power_on = state;
+        self.ppu.apply_power_on_state(state);
+    }
+
+    /// Fill CPU RAM and all PPU memory with seeded pseudo-random bytes.
+    pub fn randomize_ram(&mut self, seed: u64) {
+        self.ram_seed = Some(seed);
+        self.power_on = PowerOnState::Random(seed);
+        self.memory.apply_power_on_state(PowerOnState::Random(seed));
+        self.ppu.apply_power_on_state(PowerOnState::Random(seed));
+    }
+
+    /// Remove the cartridge and power the console back on: RAM, PPU, APU
+    /// and controller state start over with the same power-on fill, while
+    /// frontend settings (display toggles, debugger, forced region, PPU
+    /// warm-up) are kept.
+    pub fn power_cycle(&mut self) {
+        let old = std::mem::replace(self, Bus::new());
+        self.ppu = old.ppu;
+        self.ppu.power_cycle();
+        self.apu = old.apu;
+        self.apu.power_cycle();
+        self.chr_monitor = old.chr_monitor;
+        self.watchpoints = old.watchpoints;
+        self.interrupts = old.interrupts;
+        self.region = old.region;
+        self.forced_region = old.forced_region;
+        self.forced_bus_conflicts = old.forced_bus_conflicts;
+        self.ppu_warmup = old.ppu_warmup;
+        self.apu_log = old.apu_log;
+        match old.ram_seed {
+            Some(seed) => self.randomize_ram(seed),
+            None => self.apply_power_on_state(old.power_on),
+        }
+    }
+
+    pub fn set_background_visible(&mut self, visible: bool) {
+        self.ppu.set_background_visible(visible);
+    }
+
+    pub fn set_sprites_visible(&mut self, visible: bool) {
+        self.ppu.set_sprites_visible(visible);
+    }
+
+    pub fn set_sprite_limit(&mut self, enabled: bool) {
+        self.ppu.set_sprite_limit(enabled);
+    }
+
+    pub fn sprite_limit(&self) -> bool {
+        self.ppu.sprite_limit()
+    }
+
+    pub fn set_oam_quirks(&mut self, enabled: bool) {
+        self.ppu.set_oam_quirks(enabled);
+    }
+
+    pub fn oam_quirks(&self) -> bool {
+        self.ppu.oam_quirks()
+    }
+
+    pub fn set_threaded_rendering(&mut self, enabled: bool) {
+        self.ppu.set_threaded_rendering(enabled);
+    }
+
+    pub fn threaded_rendering(&self) -> bool {
+        self.ppu.threaded_rendering()
+    }
+
+    pub fn background_visible(&self) -> bool {
+        self.ppu.background_visible()
+    }
+
+    pub fn sprites_visible(&self) -> bool {
+        self.ppu.sprites_visible()
+    }
+
+    pub fn set_pixel_inspector(&mut self, enabled: bool) {
+        self.ppu.set_provenance_enabled(enabled);
+    }
+
+    pub fn pixel_inspector_enabled(&self) -> bool {
+        self.ppu.provenance_enabled()
+    }
+
+    pub fn pixel_provenance(&self, x: usize, y: usize) -> Option<crate::ppu::PixelProvenance> {
+        self.ppu.pixel_provenance(x, y)
+    }
+
+    pub fn set_audio_scope(&mut self, enabled: bool) {
+        self.apu.set_scope_enabled(enabled);
+    }
+
+    pub fn audio_scope(&self) -> Option<&crate::audio_scope::AudioScope> {
+        self.apu.scope()
+    }
+
     pub fn set_audio_ring(&mut self, ring: std::sync::Arc<crate::audio_ring::SpscRingBuffer>) {
         self.apu.set_audio_ring(ring);
     }
 
+    pub fn set_audio_sink(&mut self, sink: crate::apu::AudioSink) {
+        self.apu.set_audio_sink(sink);
+    }
+
     pub fn get_audio_buffer(&mut self) -> Vec<f32> {
         self.apu.get_audio_buffer()
     }
@@ -141,6 +680,18 @@ impl Bus {
         self.apu.drain_to_ring(ring);
     }
 
+    pub fn ramp_audio_in(&mut self) {
+        self.apu.ramp_in();
+    }
+
+    pub fn set_fast_forward(&mut self, speed: usize) {
+        self.apu.set_speed(speed);
+    }
+
+    pub fn set_fast_forward_audio(&mut self, mode: crate::fast_forward::FastForwardAudio) {
+        self.apu.set_fast_forward_audio(mode);
+    }
+
     pub fn audio_diag_full(&self) -> crate::apu::AudioDiagFull {
         self.apu.audio_diag_full()
     }
@@ -155,6 +706,15 @@ impl Bus {
         self.apu.clear_frame_irq();
     }
 
+    /// Which devices are asserting IRQ right now.
+    pub fn irq_sources(&self) -> IrqSources {
+        let mut sources = IrqSources::empty();
+        sources.set(IrqSources::FRAME_COUNTER, self.apu.frame_irq_pending());
+        sources.set(IrqSources::DMC, self.apu.dmc_irq_pending());
+        sources.set(IrqSources::MAPPER, self.mapper_irq_pending());
+        sources
+    }
+
     pub fn mapper_irq_pending(&self) -> bool {
         if let Some(ref cartridge) = self.cartridge {
             cartridge.irq_pending()
@@ -169,6 +729,12 @@ impl Bus {
         }
     }
 
+    /// Whether the PPU ignores early register writes after the next power
+    /// or reset. Some homebrew never waits for the PPU to warm up.
+    pub fn set_ppu_warmup(&mut self, enabled: bool) {
+        self.ppu_warmup = enabled;
+    }
+
     pub fn clock_mapper_irq_cycles(&mut self, cycles: u32) {
         if let Some(ref mut cartridge) = self.cartridge {
             cartridge.clock_irq_counter_cycles(cycles);
@@ -236,7 +802,7 @@ impl Bus {
         match addr {
             0x8000..=0xFFFF => {
                 if let Some(ref cartridge) = self.cartridge {
-                    cartridge.read_prg(addr)
+                    self.patches.patch_rom(addr, cartridge.read_prg(addr))
                 } else {
                     0
                 }
@@ -251,17 +817,33 @@ impl CpuBus for Bus {
         if let Some(ref mut cartridge) = self.cartridge {
             cartridge.on_reset();
         }
+        self.apu.reset();
+        self.ppu.reset(self.ppu_warmup);
+    }
+
+    fn on_instruction(&mut self, pc: u16, cycle: u64) {
+        self.instruction_pc = pc;
+        self.instruction_cycle = cycle;
     }
 
     fn read(&mut self, addr: u16) -> u8 {
-        match addr {
+        // PPU address is sampled before the read increments it
+        let ppu_addr = self.ppu.data_port_addr();
+        let value = match addr {
             0x0000..=0x1FFF => self.memory.read(addr),
             0x2000..=0x3FFF => {
                 let mirrored = 0x2000 + (addr & 0x07);
                 self.ppu.read_register(mirrored, self.cartridge.as_ref())
             }
             0x4000..=0x4013 | 0x4015 => self.apu.read_register(addr),
-            0x4016 => self.read_controller(),
+            0x4016 if self.is_vs_system() => {
+                self.read_controller() | self.vs_coin | ((self.vs_dip_switches & 0x03) << 3)
+            }
+            0x4016 => {
+                let mic = if self.microphone { 0x04 } else { 0x00 };
+                self.read_controller() | mic
+            }
+            0x4017 if self.is_vs_system() => self.vs_dip_switches & 0xFC,
             0x4017 => 0,
             0x4020..=0x5FFF => {
                 if let Some(ref cartridge) = self.cartridge {
@@ -279,52 +861,76 @@ impl CpuBus for Bus {
             }
             0x8000..=0xFFFF => {
                 if let Some(ref mut cartridge) = self.cartridge {
-                    cartridge.read_prg_cpu(addr)
+                    let value = cartridge.read_prg_cpu(addr);
+                    self.patches.patch_rom(addr, value)
                 } else {
                     0
                 }
             }
             _ => 0,
+        };
+        if !self.watchpoints.is_empty() {
+            let source = self.access_source();
+            self.watchpoints
+                .check(AddressSpace::Cpu, addr, value, false, source);
+            if addr & 0xE007 == 0x2007 {
+                self.watchpoints
+                    .check(AddressSpace::Ppu, ppu_addr, value, false, source);
+            }
         }
+        value
+    }
+
+    fn write_rmw(&mut self, addr: u16, old: u8, new: u8) {
+        self.write(addr, old);
+        self.rmw_second_write = true;
+        self.write(addr, new);
+        self.rmw_second_write = false;
     }
 
     fn write(&mut self, addr: u16, data: u8) {
+        if !self.watchpoints.is_empty() {
+            let source = self.access_source();
+            self.watchpoints
+                .check(AddressSpace::Cpu, addr, data, true, source);
+            if addr & 0xE007 == 0x2007 {
+                let ppu_addr = self.ppu.data_port_addr();
+                self.watchpoints
+                    .check(AddressSpace::Ppu, ppu_addr, data, true, source);
+            }
+        }
+        if let 0x4000..=0x4017 = addr {
+            self.io_writes[(addr - 0x4000) as usize] = data;
+        }
         match addr {
             0x0000..=0x1FFF => {
                 self.memory.write(addr, data);
             }
             0x2000..=0x3FFF => {
                 let mirrored = 0x2000 + (addr & 0x07);
+                if mirrored == 0x2007 && self.ppu.data_port_addr() < 0x2000 {
+                    let source = self.access_source();
+                    let chr_addr = self.ppu.data_port_addr();
+                    if !self.chr_monitor.record(source, chr_addr, data) {
+                        self.ppu.skip_data_write();
+                        return;
+                    }
+                }
                 self.ppu
                     .write_register(mirrored, data, self.cartridge.as_mut());
             }
             0x4000..=0x4013 | 0x4015 | 0x4017 => {
+                self.log_apu_write(addr, data);
                 self.apu.write_register(addr, data);
             }
             0x4014 => {
-                // OAM DMA: Copy 256 bytes from CPU page to PPU OAM
+                // OAM DMA: Copy 256 bytes from CPU page to PPU OAM. Any
+                // page works, through the normal read path, so mappers,
+                // ROM patches and watchpoints see the reads as the CPU's
                 let base = (data as u16) << 8;
                 let oam_addr = self.ppu.get_oam_addr();
                 for i in 0u16..256 {
-                    let src = base + i;
-                    let byte = match src {
-                        0x0000..=0x1FFF => self.memory.read(src),
-                        0x6000..=0x7FFF => {
-                            if let Some(ref cartridge) = self.cartridge {
-                                cartridge.read_prg_ram(src)
-                            } else {
-                                0
-                            }
-                        }
-                        0x8000..=0xFFFF => {
-                            if let Some(ref mut cartridge) = self.cartridge {
-                                cartridge.read_prg_cpu(src)
-                            } else {
-                                0
-                            }
-                        }
-                        _ => 0,
-                    };
+                    let byte = self.read(base + i);
                     let oam_dst = oam_addr.wrapping_add(i as u8);
                     self.ppu.write_oam_data(oam_dst, byte);
                 }
@@ -334,11 +940,20 @@ impl CpuBus for Bus {
             0x4016 => {
                 // Controller strobe
                 let new_strobe = (data & 0x01) != 0;
-                if self.strobe && !new_strobe {
-                    // Falling edge: latch controller state
-                    self.controller_state = self.controller as u16;
+                if self.strobe || new_strobe {
+                    // Strobe high (or just falling): the buttons are latched
+                    // into the shift register as they are now
+                    self.controller_state = self.controller;
                 }
                 self.strobe = new_strobe;
+                // OUT1 only reaches the expansion port; homebrew controllers
+                // built for it treat it as a rumble switch
+                let rumble = data & 0x02 != 0;
+                if rumble != self.rumble {
+                    self.rumble = rumble;
+                    let strength = if rumble { 0xFF } else { 0 };
+                    self.push_feedback(ControllerFeedback::Rumble { port: 0, strength });
+                }
                 if let Some(ref mut cartridge) = self.cartridge {
                     cartridge.write_prg_low(addr, data);
                 }
@@ -352,7 +967,16 @@ impl CpuBus for Bus {
                         0x6000..=0x7FFF => {
                             cartridge.write_prg_ram(addr, data);
                         }
-                        0x8000..=0xFFFF => {
+                        0x8000..=0xFFFF
+                            if !(self.rmw_second_write
+                                && cartridge.ignores_consecutive_writes()) =>
+                        {
+                            if let Some(log) = self.apu_log.as_mut() {
+                                if addr >= 0xC000 && cartridge.has_sunsoft5b_audio() {
+                                    let frame = self.ppu.get_frame();
+                                    log.record(self.apu.cycle_count(), frame, addr, data);
+                                }
+                            }
                             cartridge.write_prg(addr, data);
                         }
                         _ => {}
@@ -374,6 +998,12 @@ impl Bus {
         }
     }
 
+    pub fn set_sram_data(&mut self, data: Vec<u8>) {
+        if let Some(ref mut cartridge) = self.cartridge {
+            cartridge.set_sram_data(data);
+        }
+    }
+
     pub fn get_ppu_state(&self) -> (u8, u8, u8, u8) {
         (
             self.ppu.get_control_bits(),
@@ -383,6 +1013,22 @@ impl Bus {
         )
     }
 
+    /// PPU registers and the last $4000-$4017 writes, for the register overlay.
+    pub fn io_registers(&self) -> IoRegisters {
+        IoRegisters {
+            ppu_ctrl: self.ppu.get_control_bits(),
+            ppu_mask: self.ppu.get_mask_bits(),
+            ppu_status: self.ppu.get_status_bits(),
+            oam_addr: self.ppu.get_oam_addr(),
+            vram_addr: self.ppu.get_vram_addr(),
+            temp_addr: self.ppu.get_t(),
+            fine_x: self.ppu.get_x_scroll(),
+            write_toggle: self.ppu.get_w(),
+            read_buffer: self.ppu.get_read_buffer(),
+            apu: self.io_writes,
+        }
+    }
+
     pub fn get_ppu_registers(&self) -> (u16, u16, u8, bool, i16, u16, u64, u8) {
         (
             self.ppu.get_vram_addr(),
@@ -401,10 +1047,8 @@ impl Bus {
     }
 
     pub fn get_ppu_nametables_flat(&self) -> Vec<u8> {
-        let nt = self.ppu.get_nametable();
         let mut data = Vec::with_capacity(2048);
-        data.extend_from_slice(&nt[0]);
-        data.extend_from_slice(&nt[1]);
+        self.copy_ppu_nametables_flat(&mut data);
         data
     }
 
@@ -416,6 +1060,24 @@ impl Bus {
         self.memory.get_ram().to_vec()
     }
 
+    /// Refill `data` with both nametables, keeping its allocation.
+    pub fn copy_ppu_nametables_flat(&self, data: &mut Vec<u8>) {
+        let nt = self.ppu.get_nametable();
+        data.clear();
+        data.extend_from_slice(&nt[0]);
+        data.extend_from_slice(&nt[1]);
+    }
+
+    pub fn copy_ppu_oam_flat(&self, data: &mut Vec<u8>) {
+        data.clear();
+        data.extend_from_slice(&self.ppu.get_oam());
+    }
+
+    pub fn copy_ram_flat(&self, data: &mut Vec<u8>) {
+        data.clear();
+        data.extend_from_slice(self.ram_ref());
+    }
+
     pub fn get_cartridge_prg_bank(&self) -> u8 {
         if let Some(ref cartridge) = self.cartridge {
             cartridge.get_prg_bank()
@@ -436,6 +1098,14 @@ impl Bus {
         self.cartridge.as_ref().map(|c| c.snapshot_state())
     }
 
+    /// Like `get_cartridge_state`, reusing the buffers of a previous snapshot.
+    pub fn copy_cartridge_state(&self, state: &mut Option<CartridgeState>) {
+        match (&self.cartridge, state.as_mut()) {
+            (Some(cartridge), Some(state)) => cartridge.snapshot_state_into(state),
+            (cartridge, _) => *state = cartridge.as_ref().map(|c| c.snapshot_state()),
+        }
+    }
+
     pub fn get_apu_state(&self) -> ApuState {
         self.apu.snapshot_state()
     }
@@ -548,6 +1218,72 @@ impl Bus {
         &mut self.memory.ram
     }
 
+    pub fn board_leds(&self) -> Option<BoardLeds> {
+        self.cartridge.as_ref().and_then(|c| c.leds())
+    }
+
+    /// Queue an LED change for player 1's controller if the board's LEDs
+    /// differ from the last report. Called once per frame.
+    pub fn poll_board_leds(&mut self) {
+        let leds = self.board_leds();
+        if leds != self.leds_seen {
+            self.leds_seen = leds;
+            let rgb = leds.map_or([0; 3], BoardLeds::rgb);
+            self.push_feedback(ControllerFeedback::Led { port: 0, rgb });
+        }
+    }
+
+    pub fn add_patch(&mut self, patch: Patch) -> usize {
+        self.patches.add(patch)
+    }
+
+    pub fn remove_patch(&mut self, index: usize) -> Option<Patch> {
+        self.patches.remove(index)
+    }
+
+    pub fn set_patch_enabled(&mut self, index: usize, enabled: bool) {
+        self.patches.set_enabled(index, enabled);
+    }
+
+    pub fn patches(&self) -> &PatchList {
+        &self.patches
+    }
+
+    /// Status of patch `index` against the PRG bank mapped in now.
+    pub fn patch_status(&self, index: usize) -> PatchStatus {
+        let rom_value = match (self.patches.list().get(index), self.cartridge.as_ref()) {
+            (Some(patch), Some(cartridge)) if patch.is_rom() => cartridge.read_prg(patch.addr),
+            _ => 0,
+        };
+        self.patches.status(index, rom_value)
+    }
+
+    /// Rewrite every frozen RAM byte. Called once per frame.
+    pub fn apply_freezes(&mut self) {
+        let prg_ram = self.cartridge.as_mut().and_then(|c| c.prg_ram_mut());
+        self.patches
+            .freezes()
+            .apply_split(&mut self.memory.ram, prg_ram);
+    }
+
+    /// Write `cheats` into CPU RAM and PRG-RAM the way freezes are.
+    pub fn apply_cheats(&mut self, cheats: &CheatManager) {
+        let prg_ram = self.cartridge.as_mut().and_then(|c| c.prg_ram_mut());
+        cheats.apply_split(&mut self.memory.ram, prg_ram);
+    }
+
+    /// Controller feedback queued since the last call, oldest first.
+    pub fn take_controller_feedback(&mut self) -> Vec<ControllerFeedback> {
+        std::mem::take(&mut self.feedback)
+    }
+
+    fn push_feedback(&mut self, feedback: ControllerFeedback) {
+        if self.feedback.len() == FEEDBACK_QUEUE_LIMIT {
+            self.feedback.remove(0);
+        }
+        self.feedback.push(feedback);
+    }
+
     /// Direct reference to PRG-RAM / SRAM (mapper-dependent).
     pub fn prg_ram_ref(&self) -> Option<&[u8]> {
         self.cartridge.as_ref().and_then(|c| c.prg_ram_ref())
@@ -557,11 +1293,21 @@ impl Bus {
     pub fn prg_ram_mut(&mut self) -> Option<&mut [u8]> {
         self.cartridge.as_mut().and_then(|c| c.prg_ram_mut())
     }
+
+    /// CRC-32 of the loaded ROM's PRG and CHR data.
+    pub fn rom_crc32(&self) -> Option<u32> {
+        self.cartridge.as_ref().map(|c| c.rom_crc32())
+    }
+
+    pub fn mapper_number(&self) -> Option<u8> {
+        self.cartridge.as_ref().map(|c| c.mapper_number())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::bus_with_rom;
 
     #[test]
     fn dmc_sample_fetch_schedules_cpu_stall_cycles() {
@@ -599,4 +1345,424 @@ mod tests {
         assert_eq!(dmc_stall_cycles, 0);
         assert!(!ppu_frame_complete);
     }
+
+    #[test]
+    fn microphone_sets_bit_2_of_4016() {
+        let mut bus = Bus::new();
+        bus.set_controller(0x01);
+        bus.write(0x4016, 1);
+        assert_eq!(bus.read(0x4016), 0x01);
+
+        bus.set_microphone(true);
+        assert_eq!(bus.read(0x4016), 0x05);
+        bus.write(0x4016, 0);
+        assert_eq!(bus.read(0x4016) & 0x04, 0x04);
+
+        bus.set_microphone(false);
+        assert_eq!(bus.read(0x4016) & 0x04, 0x00);
+    }
+
+    #[test]
+    fn out1_writes_queue_rumble_feedback() {
+        let mut bus = Bus::new();
+        bus.write(0x4016, 0x01);
+        bus.write(0x4016, 0x03);
+        bus.write(0x4016, 0x02);
+        bus.write(0x4016, 0x00);
+        bus.poll_board_leds();
+
+        assert_eq!(
+            bus.take_controller_feedback(),
+            [
+                ControllerFeedback::Rumble {
+                    port: 0,
+                    strength: 0xFF
+                },
+                ControllerFeedback::Rumble {
+                    port: 0,
+                    strength: 0
+                },
+            ]
+        );
+        assert!(bus.take_controller_feedback().is_empty());
+    }
+
+    fn vs_bus(byte13: u8) -> Bus {
+        // NES 2.0 header, mapper 99, Vs. console type
+        let mut rom = vec![b'N', b'E', b'S', 0x1A, 2, 2, 0x30, 0x69];
+        rom.resize(16, 0);
+        rom[13] = byte13;
+        rom.resize(16 + 2 * 16384 + 2 * 8192, 0);
+        bus_with_rom(&rom)
+    }
+
+    #[test]
+    fn vs_system_reports_dip_switches_and_coins() {
+        let mut bus = vs_bus(0x05);
+        assert!(bus.is_vs_system());
+        assert_eq!(bus.vs_ppu(), VsPpu::Rp2c04_0004);
+
+        bus.set_vs_dip_switches(0b1010_0110);
+        assert_eq!(bus.read(0x4016) & 0xFC, 0x10);
+        assert_eq!(bus.read(0x4017) & 0xFC, 0b1010_0100);
+
+        bus.set_vs_coin(0, true);
+        bus.set_vs_service(true);
+        assert_eq!(bus.read(0x4016) & 0x64, 0x24);
+        bus.set_vs_coin(0, false);
+        bus.set_vs_coin(1, true);
+        bus.set_vs_service(false);
+        assert_eq!(bus.read(0x4016) & 0x64, 0x40);
+    }
+
+    #[test]
+    fn vs_rbi_baseball_protection_sequence() {
+        let mut bus = vs_bus(0x10);
+        assert_eq!(bus.read(0x5E00), 0);
+        let reads: Vec<u8> = (0..11).map(|_| bus.read(0x5E01)).collect();
+        assert!(reads[..9].iter().all(|&v| v == 0xB4));
+        assert_eq!(reads[9], 0x6F);
+        assert_eq!(reads[10], 0xB4);
+
+        bus.read(0x5E00);
+        assert_eq!(bus.read(0x5E01), 0xB4);
+    }
+
+    #[test]
+    fn randomize_ram_is_repeatable_per_seed() {
+        let mut a = Bus::new();
+        let mut b = Bus::new();
+        a.randomize_ram(99);
+        b.randomize_ram(99);
+        assert_eq!(a.ram_ref(), b.ram_ref());
+        assert!(a.ram_ref().iter().any(|&byte| byte != 0));
+
+        b.randomize_ram(100);
+        assert_ne!(a.ram_ref(), b.ram_ref());
+    }
+
+    #[test]
+    fn chr_write_protect_blocks_and_logs_pattern_table_writes() {
+        // NROM with 8KB CHR-RAM
+        let mut rom = vec![b'N', b'E', b'S', 0x1A, 1, 0];
+        rom.resize(16 + 16384, 0);
+        let mut bus = bus_with_rom(&rom);
+        bus.set_chr_write_logging(true);
+        bus.set_chr_write_protect(true);
+        bus.on_instruction(0x8123, 0);
+
+        bus.write(0x2006, 0x10);
+        bus.write(0x2006, 0x00);
+        bus.write(0x2007, 0xAA);
+        bus.set_chr_write_protect(false);
+        bus.write(0x2007, 0x55);
+
+        assert_eq!(bus.peek_chr(0x1000), 0x00);
+        assert_eq!(bus.peek_chr(0x1001), 0x55);
+        assert_eq!(bus.chr_generation(), 1);
+
+        let log = bus.take_chr_write_log();
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].source.pc, 0x8123);
+        assert_eq!(log[0].addr, 0x1000);
+        assert!(log[0].blocked);
+        assert_eq!(log[1].addr, 0x1001);
+        assert!(!log[1].blocked);
+    }
+
+    #[test]
+    fn watchpoints_report_pc_for_cpu_and_ppu_accesses() {
+        let mut bus = Bus::new();
+        bus.add_watchpoint(Watchpoint::parse("ppu:w:1000-1FFF", true).unwrap());
+        bus.add_watchpoint(Watchpoint::parse("cpu:r:0300", false).unwrap());
+
+        bus.on_instruction(0xC456, 0);
+        bus.write(0x0300, 0x12);
+        assert_eq!(bus.read(0x0300), 0x12);
+        assert!(!bus.take_watch_break());
+
+        // $2007 writes are matched against the PPU address, through mirrors
+        bus.write(0x2006, 0x1F);
+        bus.write(0x2006, 0xF0);
+        bus.on_instruction(0xC460, 0);
+        bus.write(0x3FFF, 0x99);
+        assert!(bus.take_watch_break());
+
+        let hits = bus.take_watch_hits();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].addr, 0x0300);
+        assert_eq!(hits[0].source.pc, 0xC456);
+        assert_eq!(hits[1].space, AddressSpace::Ppu);
+        assert_eq!(hits[1].addr, 0x1FF0);
+        assert_eq!(hits[1].value, 0x99);
+        assert_eq!(hits[1].source.pc, 0xC460);
+    }
+
+    #[test]
+    fn forced_region_overrides_header_and_switches_clock_ratio() {
+        let mut bus = vs_bus(0x00);
+        assert_eq!(bus.region(), Region::Ntsc);
+        assert_eq!(bus.ppu_dots_for_cpu_cycle(), 3);
+
+        bus.set_forced_region(Some(Region::Pal));
+        assert_eq!(bus.region(), Region::Pal);
+        let dots: Vec<u32> = (0..10).map(|_| bus.ppu_dots_for_cpu_cycle()).collect();
+        assert_eq!(dots, [3, 3, 3, 3, 4, 3, 3, 3, 3, 4]);
+
+        // A PAL frame is 312 scanlines of 341 dots
+        while !bus.ppu_frame_complete() {
+            bus.step_ppu();
+        }
+        let mut dots = 0;
+        while !bus.ppu_frame_complete() {
+            bus.step_ppu();
+            dots += 1;
+        }
+        assert_eq!(dots, 341 * 312);
+
+        bus.set_forced_region(None);
+        assert_eq!(bus.region(), Region::Ntsc);
+    }
+
+    #[test]
+    fn mmc1_ignores_the_second_write_of_a_rmw() {
+        // MMC1 with 32KB PRG-ROM and CHR-RAM
+        let mut rom = vec![b'N', b'E', b'S', 0x1A, 2, 0, 0x10];
+        rom.resize(16 + 2 * 16384, 0);
+        let mut bus = bus_with_rom(&rom);
+        // Only the unmodified values reach the shift register: control = %00010
+        for old in [0, 1, 0, 0, 0] {
+            bus.write_rmw(0x8000, old, 0x01);
+        }
+        let cartridge = bus.cartridge.as_ref().unwrap();
+        assert_eq!(cartridge.mirroring(), crate::cartridge::Mirroring::Vertical);
+    }
+
+    #[test]
+    fn power_cycle_clears_console_but_keeps_settings() {
+        let mut bus = vs_bus(0x05);
+        bus.randomize_ram(7);
+        let power_on_ram = bus.ram_ref().to_vec();
+        bus.set_sprite_limit(false);
+        bus.set_forced_region(Some(Region::Pal));
+        bus.write(0x0000, 0x42);
+        bus.write(0x2000, 0x80);
+
+        bus.power_cycle();
+        assert!(bus.cartridge.is_none());
+        assert_eq!(bus.ram_ref(), &power_on_ram[..]);
+        assert_eq!(bus.ppu.get_control_bits(), 0);
+        assert!(!bus.sprite_limit());
+        assert_eq!(bus.region(), Region::Pal);
+    }
+
+    #[test]
+    fn screen_tiles_follow_the_scroll_across_nametables() {
+        // NROM, vertical mirroring: $2000 and $2400 side by side
+        let mut rom = vec![b'N', b'E', b'S', 0x1A, 1, 1, 0x01];
+        rom.resize(16 + 16384 + 8192, 0);
+        let mut bus = bus_with_rom(&rom);
+        for (addr, tile) in [(0x201Fu16, b'A'), (0x2400, b'B'), (0x2420, b'C')] {
+            bus.write(0x2006, (addr >> 8) as u8);
+            bus.write(0x2006, addr as u8);
+            bus.write(0x2007, tile);
+        }
+        // Scroll 16 tiles right
+        bus.write(0x2000, 0x00);
+        bus.write(0x2005, 128);
+        bus.write(0x2005, 0);
+
+        assert_eq!(bus.scroll_origin(), (128, 0));
+        let tiles = bus.screen_tiles();
+        assert_eq!(&tiles[15..17], b"AB");
+        assert_eq!(tiles[32 + 16], b'C');
+        assert_eq!(bus.nametable_tiles(1)[0], b'B');
+        let text = crate::nametable_text::text_regions(&tiles, &crate::CharTable::ascii());
+        assert_eq!(text[0].text, "AB");
+        assert_eq!((text[0].row, text[0].col), (0, 15));
+    }
+
+    #[test]
+    fn apu_log_captures_dmc_sample_bytes() {
+        let mut rom = vec![b'N', b'E', b'S', 0x1A, 1, 1];
+        rom.resize(16 + 16384 + 8192, 0);
+        for i in 0..17 {
+            rom[16 + 0x40 + i] = i as u8 + 1;
+        }
+        let mut bus = bus_with_rom(&rom);
+        bus.start_apu_log();
+        bus.write(0x4012, 0x01);
+        bus.write(0x4013, 0x01);
+        bus.write(0x4015, 0x10);
+        bus.write(0xC000, 0x07); // NROM: not a sound port
+
+        let log = bus.take_apu_log().unwrap();
+        assert_eq!(log.writes().len(), 3);
+        let sample = &log.dmc_samples()[0];
+        assert_eq!(sample.addr, 0xC040);
+        assert_eq!(sample.data.len(), 17);
+        assert_eq!((sample.data[0], sample.data[16]), (1, 17));
+    }
+
+    fn mmc1_bus() -> Bus {
+        // MMC1, 32KB PRG (bank 0 = $11, bank 1 = $22), 8KB CHR, 8KB PRG-RAM
+        let mut rom = vec![b'N', b'E', b'S', 0x1A, 2, 1, 0x10, 0x00];
+        rom.resize(16, 0);
+        rom.resize(16 + 16384, 0x11);
+        rom.resize(16 + 2 * 16384, 0x22);
+        rom.resize(16 + 2 * 16384 + 8192, 0);
+        bus_with_rom(&rom)
+    }
+
+    #[test]
+    fn internal_ram_mirrors_every_0800_bytes() {
+        let mut bus = Bus::new();
+        for addr in 0x0000u16..0x2000 {
+            let value = (addr as u8) ^ ((addr >> 8) as u8 & 0x07).wrapping_mul(0x35);
+            bus.write(addr, value);
+            for mirror in 0..4u16 {
+                assert_eq!(
+                    bus.read((addr & 0x07FF) + mirror * 0x0800),
+                    value,
+                    "{addr:04X}"
+                );
+            }
+        }
+        assert_eq!(bus.ram_ref().len(), 0x0800);
+    }
+
+    #[test]
+    fn ppu_registers_mirror_every_8_bytes_through_3fff() {
+        let mut bus = Bus::new();
+        bus.set_ppu_warmup(false);
+        for (i, base) in (0x2000u16..0x4000).step_by(8).enumerate() {
+            // OAMADDR/OAMDATA through the mirror, read back at $2003/$2004
+            bus.write(base + 3, i as u8);
+            bus.write(base + 4, !(i as u8));
+            bus.write(0x2003, i as u8);
+            assert_eq!(bus.read(0x2004), !(i as u8), "{base:04X}");
+
+            // PPUADDR/PPUDATA through the mirror, buffered read at $2007
+            let vram = 0x2000 + (i as u16 & 0x03FF);
+            bus.read(base + 2);
+            bus.write(base + 6, (vram >> 8) as u8);
+            bus.write(base + 6, vram as u8);
+            bus.write(base + 7, i as u8 ^ 0x5A);
+            bus.write(0x2006, (vram >> 8) as u8);
+            bus.write(0x2006, vram as u8);
+            bus.read(0x2007);
+            assert_eq!(bus.read(base + 7), i as u8 ^ 0x5A, "{base:04X}");
+        }
+    }
+
+    #[test]
+    fn apu_and_io_range_decodes_without_touching_other_regions() {
+        let mut bus = Bus::new();
+        // $4003 loads pulse 1's length counter once the channel is enabled
+        bus.write(0x4015, 0x01);
+        bus.write(0x4003, 0x08);
+        assert_eq!(bus.read(0x4015) & 0x01, 0x01);
+        bus.write(0x4015, 0x00);
+        assert_eq!(bus.read(0x4015) & 0x01, 0x00);
+
+        // $4014 copies a CPU page into OAM
+        for i in 0..256u16 {
+            bus.write(0x0300 + i, i as u8);
+        }
+        bus.write(0x2003, 0x00);
+        bus.write(0x4014, 0x03);
+        assert!(bus.is_dma_in_progress());
+        assert_eq!(bus.ppu.get_oam()[0xC4], 0xC4);
+
+        // $4016 shifts out the pad, $4017 has no pad 2 plugged in
+        bus.set_controller(0x81);
+        bus.write(0x4016, 1);
+        bus.write(0x4016, 0);
+        assert_eq!(bus.read(0x4016) & 0x01, 1);
+        assert_eq!(bus.read(0x4017), 0);
+
+        // Write-only APU registers and the test range $4018-$401F read 0
+        // and writing there lands nowhere else.
+        let ram = bus.ram_ref().to_vec();
+        for addr in (0x4000u16..=0x4013).chain(0x4018..=0x401F) {
+            assert_eq!(bus.read(addr), 0, "{addr:04X}");
+        }
+        for addr in 0x4018u16..=0x401F {
+            bus.write(addr, 0xFF);
+        }
+        assert_eq!(bus.ram_ref(), &ram[..]);
+    }
+
+    #[test]
+    fn controller_strobe_reloads_then_shifts_in_ones() {
+        let mut bus = Bus::new();
+        let pad = |bus: &mut Bus| bus.read(0x4016) & 0x01;
+        // Strobe high: A now, on every read, without shifting
+        bus.set_controller(0x01);
+        bus.write(0x4016, 1);
+        assert_eq!([pad(&mut bus), pad(&mut bus)], [1, 1]);
+        bus.set_controller(0x00);
+        assert_eq!(pad(&mut bus), 0);
+
+        // Falling edge latches A, B, Select, Start, Up, Down, Left, Right
+        bus.set_controller(0b1010_0110);
+        bus.write(0x4016, 0);
+        bus.set_controller(0xFF);
+        let bits: Vec<u8> = (0..10).map(|_| pad(&mut bus)).collect();
+        assert_eq!(bits, [0, 1, 1, 0, 0, 1, 0, 1, 1, 1]);
+
+        // A re-read mid-sequence after strobing again starts over
+        bus.set_controller(0b0000_0010);
+        bus.write(0x4016, 1);
+        bus.write(0x4016, 0);
+        assert_eq!([pad(&mut bus), pad(&mut bus), pad(&mut bus)], [0, 1, 0]);
+    }
+
+    #[test]
+    fn cartridge_space_decodes_expansion_prg_ram_and_prg_rom() {
+        let mut empty = Bus::new();
+        for addr in [0x4020u16, 0x5FFF, 0x6000, 0x7FFF, 0x8000, 0xFFFF] {
+            empty.write(addr, 0x77);
+            assert_eq!(empty.read(addr), 0, "{addr:04X} without a cartridge");
+        }
+
+        let mut bus = mmc1_bus();
+        for addr in 0x6000u16..0x8000 {
+            bus.write(addr, (addr >> 3) as u8);
+        }
+        for addr in 0x6000u16..0x8000 {
+            assert_eq!(bus.read(addr), (addr >> 3) as u8, "{addr:04X}");
+        }
+        for addr in 0x4020u16..0x6000 {
+            assert_eq!(bus.read(addr), 0, "{addr:04X}");
+        }
+        // $8000-$BFFF is the switchable bank, $C000-$FFFF the fixed last one
+        assert!((0x8000u16..0xC000).all(|addr| bus.read(addr) == 0x11));
+        assert!((0xC000u16..=0xFFFF).all(|addr| bus.read(addr) == 0x22));
+    }
+
+    #[test]
+    fn oam_dma_sources_cartridge_pages_through_the_bus() {
+        let mut bus = mmc1_bus();
+        for i in 0..256u16 {
+            bus.write(0x6000 + i, i as u8 ^ 0xA5);
+        }
+        bus.write(0x2003, 0x00);
+        bus.write(0x4014, 0x60);
+        assert!((0..256).all(|i| bus.ppu.get_oam()[i] == i as u8 ^ 0xA5));
+
+        // ROM reads go through the patch list like the CPU's
+        bus.add_patch(Patch {
+            addr: 0xC010,
+            value: 0x99,
+            compare: Some(0x22),
+            enabled: true,
+        });
+        bus.write(0x2003, 0x00);
+        bus.write(0x4014, 0xC0);
+        assert_eq!(bus.ppu.get_oam()[0x10], 0x99);
+        assert_eq!(bus.ppu.get_oam()[0x11], 0x22);
+        bus.write(0x4014, 0x80);
+        assert!(bus.ppu.get_oam().iter().all(|&byte| byte == 0x11));
+    }
 }
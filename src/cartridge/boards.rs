@@ -0,0 +1,164 @@
+// Board names by iNES mapper number, for reports and error messages. The
+// emulated set is the README's mapper table; a few common boards that are not
+// emulated yet are named too, so a report can say what a ROM is missing.
+
+/// Mappers `read_prg` implements, as listed in the README.
+pub(super) const EMULATED: &[(u8, &str)] = &[
+    (0, "NROM"),
+    (1, "MMC1/SxROM"),
+    (2, "UxROM"),
+    (3, "CNROM"),
+    (4, "MMC3/TxROM"),
+    (5, "MMC5/ExROM"),
+    (7, "AxROM"),
+    (9, "MMC2"),
+    (10, "MMC4"),
+    (11, "Color Dreams"),
+    (12, "MMC3 with split CHR outer bits"),
+    (13, "CPROM"),
+    (15, "K-1029/K-1030P"),
+    (16, "Bandai FCG"),
+    (18, "Jaleco SS 88006"),
+    (19, "Namco 163"),
+    (21, "VRC4a / VRC4c"),
+    (22, "VRC2a"),
+    (23, "VRC2b / VRC4e"),
+    (24, "VRC6a"),
+    (25, "VRC2c / VRC4b / VRC4d"),
+    (26, "VRC6b"),
+    (30, "UNROM 512"),
+    (32, "Irem G-101"),
+    (33, "Taito TC0190/TC0350"),
+    (34, "BNROM / NINA-001"),
+    (37, "MMC3 multicart"),
+    (38, "Bit Corp. UNL-PCI556"),
+    (40, "NTDEC 2722 / SMB2J conversion"),
+    (41, "Caltron 6-in-1"),
+    (42, "Mario Baby / pirate board"),
+    (43, "SMB2j pirate conversion"),
+    (44, "MMC3 multicart variant"),
+    (46, "Rumble Station multicart"),
+    (47, "MMC3 multicart (Nintendo QJ)"),
+    (48, "Taito TC0690 / X1-005 variant"),
+    (50, "N-32 / Romeo SMB2J conversion"),
+    (57, "GK 6-in-1 / HES 6-in-1"),
+    (58, "GK 4-in-1 / multicart board"),
+    (59, "T3H53 multicart board"),
+    (60, "Reset-based 4-in-1 multicart"),
+    (61, "20-in-1 / N-32 multicart"),
+    (63, "16K/32K discrete board with CHR-RAM protect"),
+    (64, "RAMBO-1"),
+    (65, "Irem H-3001"),
+    (66, "GxROM/MHROM"),
+    (67, "Sunsoft-3"),
+    (68, "Sunsoft-4"),
+    (69, "Sunsoft FME-7"),
+    (70, "Jaleco JF-11/JF-14"),
+    (71, "Camerica / BF9093"),
+    (72, "Jaleco JF-17/JF-19"),
+    (73, "VRC3"),
+    (74, "Pirate MMC3 / TxROM variant"),
+    (75, "VRC1"),
+    (76, "Namcot 3446"),
+    (77, "Irem LROG017"),
+    (78, "Irem 74HC161/32"),
+    (79, "AVE NINA-03/NINA-06"),
+    (80, "Taito X1-005"),
+    (81, "NTDEC N715021"),
+    (82, "Taito X1-017"),
+    (86, "Jaleco JF-13"),
+    (87, "-"),
+    (88, "Namcot 3443"),
+    (89, "Sunsoft-2"),
+    (92, "Jaleco JF-17/JF-19 (alt PRG wiring)"),
+    (93, "Sunsoft-2 variant"),
+    (94, "UN1ROM"),
+    (95, "Namcot 3425"),
+    (97, "Irem TAM-S1"),
+    (99, "Vs. System"),
+    (101, "JF-10 (bad iNES mapper)"),
+    (103, "Fighting Hero / HES bootleg board"),
+    (107, "Magicseries"),
+    (111, "GTROM / Cheapocabra"),
+    (112, "Asder / NTDEC board"),
+    (113, "HES NTD-8"),
+    (114, "MMC3 + NROM switch multicart"),
+    (115, "MMC3 + NROM switch multicart"),
+    (118, "TxSROM/TLSROM"),
+    (119, "TQROM"),
+    (123, "MMC3 + NROM switch multicart"),
+    (133, "Sachen / Joy Van (72-pin latch variant)"),
+    (137, "Sachen 8259D"),
+    (140, "Jaleco JF-11/JF-14 variant"),
+    (142, "KS7032 / VRC3-variant pirate board"),
+    (144, "Death Race board"),
+    (145, "Sachen SA-72007"),
+    (146, "AVE 301/301? (79-compatible)"),
+    (147, "Sachen TC-U01-1.5M"),
+    (148, "Sachen SA-008-A"),
+    (150, "Sachen SA-015"),
+    (151, "VRC1 alias (should be mapper 75)"),
+    (152, "Jaleco JF-17/JF-19"),
+    (153, "Bandai FCG-2"),
+    (154, "Namcot 3453"),
+    (157, "Bandai Datach Joint ROM System"),
+    (159, "Bandai FCG + X24C01"),
+    (180, "UNROM-180"),
+    (182, "Duplicate of 114"),
+    (184, "Sunsoft-1"),
+    (185, "CNROM with CHR-disable protection"),
+    (189, "MMC3 CHR + 32KB PRG pirate board"),
+    (191, "Xiangfeng 4-in-1 / MMC3 variant"),
+    (192, "Waixing FS308"),
+    (194, "Pirate MMC3 variant"),
+    (195, "Waixing FS303"),
+    (200, "1200-in-1 multicart"),
+    (201, "21-in-1 multicart"),
+    (202, "150-in-1 pirate multicart"),
+    (203, "35-in-1 / 64-in-1 multicart"),
+    (205, "MMC3 multicart variant"),
+    (206, "DxROM/Namco 108"),
+    (207, "Taito X1-005 variant"),
+    (208, "MMC3-like pirate board"),
+    (210, "Namco 175 / Namco 340"),
+    (212, "Super HIK multicart"),
+    (213, "Duplicate of 58"),
+    (218, "Magic Floor"),
+    (221, "NTDEC 821202C"),
+    (225, "72-in-1 multicart board"),
+    (226, "76-in-1 / multicart board"),
+    (227, "120-in-1 multicart / FW-01"),
+    (228, "Action 52 / Cheetahmen II"),
+    (229, "31-in-1 multicart"),
+    (230, "22-in-1 multicart / Contra switcher"),
+    (231, "20-in-1 multicart"),
+    (232, "Camerica BF9096 / Quattro"),
+    (233, "42-in-1 multicart (Disch notes)"),
+    (234, "Maxi 15 / AVE board"),
+    (235, "Golden Game 150-in-1"),
+    (236, "Realtec 8031/8155/8099"),
+    (240, "Gen Ke Le Zhuan / Sheng Huo Lie Zhuan"),
+    (241, "BxROM with WRAM"),
+    (242, "Waixing FS005/FS306 multicart"),
+    (243, "Sachen SA-020A"),
+    (245, "Waixing F003"),
+    (246, "G0151-1"),
+    (248, "Alias of 115"),
+    (250, "Nitra MMC3 wiring"),
+    (255, "110-in-1 / 255 multicart"),
+];
+
+/// Boards that turn up in ROM sets but have no implementation.
+pub(super) const UNEMULATED: &[(u8, &str)] = &[
+    (6, "FFE F4xxx"),
+    (8, "FFE F3xxx"),
+    (17, "FFE F8xxx"),
+    (28, "Action 53"),
+    (29, "RET-CUFROM"),
+    (36, "TXC 01-22000-400"),
+    (83, "Cony/Yoko"),
+    (85, "Konami VRC7"),
+    (90, "J.Y. Company"),
+    (96, "Oeka Kids"),
+    (105, "NES-EVENT"),
+];
@@ -0,0 +1,119 @@
+use crate::region::Region;
+use std::io::{Error, ErrorKind, Result};
+
+pub const HEADER_SIZE: usize = 16;
+const TRAINER_SIZE: usize = 512;
+
+/// The 16-byte iNES / NES 2.0 header, decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InesHeader {
+    pub prg_rom_size: usize,
+    pub chr_rom_size: usize,
+    pub mapper: u8,
+    /// NES 2.0 submapper (byte 8 high nibble); 0 for plain iNES.
+    pub submapper: u8,
+    pub is_nes2: bool,
+    pub vertical_mirroring: bool,
+    pub four_screen: bool,
+    pub battery: bool,
+    pub trainer: bool,
+    pub vs_system: bool,
+    pub playchoice10: bool,
+    /// NES 2.0 byte 13: Vs. PPU variant (low nibble) and protection
+    /// hardware (high nibble).
+    pub vs_type: u8,
+    pub region: Region,
+    /// NES 2.0 byte 10: volatile PRG-RAM and battery-backed PRG-NVRAM
+    /// sizes in bytes; both 0 for plain iNES.
+    pub prg_ram_size: usize,
+    pub prg_nvram_size: usize,
+}
+
+impl InesHeader {
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        if data.len() < HEADER_SIZE || &data[0..4] != b"NES\x1a" {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "Invalid NES file format",
+            ));
+        }
+        let flags6 = data[6];
+        let flags7 = data[7];
+        let is_nes2 = (flags7 & 0x0C) == 0x08;
+
+        // Timing: NES 2.0 byte 12, or the rarely set iNES byte 9 bit 0 when
+        // bytes 12-15 are clean (old dumpers wrote signatures there).
+        let region = if is_nes2 {
+            Region::from_nes2(data[12])
+        } else if data[9] & 0x01 != 0 && data[12..16].iter().all(|&b| b == 0) {
+            Region::Pal
+        } else {
+            Region::Ntsc
+        };
+
+        Ok(InesHeader {
+            prg_rom_size: rom_size(data[4], if is_nes2 { data[9] & 0x0F } else { 0 }, 16384)?,
+            chr_rom_size: rom_size(data[5], if is_nes2 { data[9] >> 4 } else { 0 }, 8192)?,
+            mapper: (flags7 & 0xF0) | (flags6 >> 4),
+            submapper: if is_nes2 { data[8] >> 4 } else { 0 },
+            is_nes2,
+            vertical_mirroring: flags6 & 0x01 != 0,
+            four_screen: flags6 & 0x08 != 0,
+            battery: flags6 & 0x02 != 0,
+            trainer: flags6 & 0x04 != 0,
+            // NES 2.0 widens the bit to a console type; 3 is "extended"
+            vs_system: if is_nes2 {
+                (flags7 & 0x03) == 0x01
+            } else {
+                flags7 & 0x01 != 0
+            },
+            playchoice10: (flags7 & 0x03) == 0x02,
+            vs_type: if is_nes2 { data[13] } else { 0 },
+            region,
+            prg_ram_size: if is_nes2 {
+                shift_size(data[10] & 0x0F)
+            } else {
+                0
+            },
+            prg_nvram_size: if is_nes2 {
+                shift_size(data[10] >> 4)
+            } else {
+                0
+            },
+        })
+    }
+
+    /// File offset of PRG-ROM; a 512-byte trainer, when present, sits
+    /// between header and PRG.
+    pub fn prg_rom_start(&self) -> usize {
+        if self.trainer {
+            HEADER_SIZE + TRAINER_SIZE
+        } else {
+            HEADER_SIZE
+        }
+    }
+}
+
+/// ROM size from its LSB byte and NES 2.0 MSB nibble. An MSB of $F
+/// switches to exponent-multiplier notation, `2^E * (2M + 1)` bytes, which
+/// describes sizes that are not whole 16KB/8KB units. Sizes that do not
+/// fit in a `usize` are rejected rather than wrapped.
+fn rom_size(lsb: u8, msb: u8, unit: usize) -> Result<usize> {
+    let size = if msb == 0x0F {
+        1usize
+            .checked_shl((lsb >> 2) as u32)
+            .and_then(|base| base.checked_mul((lsb & 0x03) as usize * 2 + 1))
+    } else {
+        (((msb as usize) << 8) | lsb as usize).checked_mul(unit)
+    };
+    size.ok_or_else(|| Error::new(ErrorKind::InvalidData, "NES 2.0 ROM size out of range"))
+}
+
+/// NES 2.0 RAM size field: 0 means none, otherwise 64 << n bytes.
+fn shift_size(shift: u8) -> usize {
+    if shift == 0 {
+        0
+    } else {
+        64 << shift
+    }
+}
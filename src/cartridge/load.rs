@@ -1,38 +1,96 @@
+use super::header::{InesHeader, HEADER_SIZE};
+use super::unif::unif_to_ines;
 use super::{
-    BandaiFcg, Cartridge, Fme7, IremG101, IremH3001, JalecoSs88006, Mapper15, Mapper246, Mapper40,
-    Mapper42, Mapper43, Mapper50, Mirroring, Mmc1, Mmc2, Mmc3, Mmc5, Namco163, Namco210, Sunsoft3,
-    Sunsoft4, TaitoTc0190, TaitoX1005, TaitoX1017, Vrc1, Vrc2Vrc4, Vrc3, Vrc6,
+    BandaiFcg, Cartridge, Fme7, Gtrom, IremG101, IremH3001, JalecoSs88006, Mapper15, Mapper246,
+    Mapper40, Mapper42, Mapper43, Mapper50, Mirroring, Mmc1, Mmc1Board, Mmc2, Mmc3, Mmc5, Namco163,
+    Namco210, Sunsoft3, Sunsoft4, TaitoTc0190, TaitoX1005, TaitoX1017, Unrom512, Vrc1, Vrc2Vrc4,
+    Vrc3, Vrc6, VsSystem,
 };
-use std::cell::Cell;
-use std::fs::File;
-use std::io::{Read, Result};
+use crate::ppu::VsPpu;
+use std::io::Result;
+
+const PLAYCHOICE_INST_ROM_SIZE: usize = 0x2000;
+const PLAYCHOICE_PROM_SIZE: usize = 32;
+const PRG_MIRROR_MIN: usize = 0x4000;
+const CHR_MIRROR_MIN: usize = 0x2000;
+
+/// Copy a ROM chip, repeating it up to the next power of two (at least
+/// `min` bytes) when its size is not a whole number of 8KB banks or is
+/// below `min`. An 8KB PRG then mirrors across $8000-$FFFF like the real
+/// chip, and odd overdump/trimmed sizes cannot index past the end.
+fn mirrored_rom(rom: &[u8], min: usize, name: &str) -> Vec<u8> {
+    let mut data = rom.to_vec();
+    if rom.is_empty() || (rom.len() >= min && rom.len().is_multiple_of(0x2000)) {
+        return data;
+    }
+    let target = rom.len().next_power_of_two().max(min);
+    log::warn!(
+        "{} ROM is {} bytes; mirroring it to {} bytes",
+        name,
+        rom.len(),
+        target
+    );
+    while data.len() < target {
+        let chunk = (target - data.len()).min(rom.len());
+        data.extend_from_slice(&rom[..chunk]);
+    }
+    data
+}
 
 impl Cartridge {
     pub fn load(path: &str) -> Result<Self> {
-        let mut file = File::open(path)?;
-        let mut data = Vec::new();
-        file.read_to_end(&mut data)?;
+        Self::from_bytes(std::fs::read(path)?)
+    }
 
-        if &data[0..4] != b"NES\x1a" {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "Invalid NES file format",
-            ));
+    /// Parse an iNES, NES 2.0 or UNIF image already in memory.
+    pub fn from_bytes(mut data: Vec<u8>) -> Result<Self> {
+        if data.starts_with(b"UNIF") {
+            data = unif_to_ines(&data)?;
         }
 
-        let prg_rom_size = data[4] as usize * 16384;
-        let chr_rom_size = data[5] as usize * 8192;
-        let flags6 = data[6];
-        let flags7 = data[7];
-
-        let has_battery = (flags6 & 0x02) != 0;
-        let mapper = (flags7 & 0xF0) | (flags6 >> 4);
+        let header = InesHeader::parse(&data)?;
+        let prg_rom_size = header.prg_rom_size;
+        let chr_rom_size = header.chr_rom_size;
+        let mapper = header.mapper;
+        // Serial EEPROM boards keep their saves without a battery, so they
+        // are persisted even when the header omits the battery flag.
+        // GTROM saves by reflashing PRG, battery or not.
+        let has_battery = header.battery || matches!(mapper, 111 | 157 | 159);
         let mapper34_nina001 = mapper == 34 && chr_rom_size > 8192;
         let mapper93_chr_ram_enabled = true;
-        let mapper78_hv_mirroring = mapper == 78 && (flags6 & 0x08) != 0;
+        let mapper78_hv_mirroring = mapper == 78 && header.four_screen;
         let mapper236_chr_ram = mapper == 236 && chr_rom_size == 0;
+        // NES 2.0 submappers 4-7 select which latch value enables CHR.
+        let mapper185_submapper = if mapper == 185 { header.submapper } else { 0 };
+        // NES 2.0 submappers 1/2 mark UxROM, CNROM and AxROM boards without
+        // and with bus conflicts. Unmarked UNROM/UOROM/CNROM conflict;
+        // unmarked AxROM is the conflict-free ANROM/AOROM.
+        // UNROM 512 without a battery is the plain, conflicting board.
+        let bus_conflicts = match (mapper, header.submapper) {
+            (2 | 3 | 7 | 30, 1) => false,
+            (2 | 3 | 7 | 30, 2) => true,
+            (7, _) => false,
+            (30, _) => !header.battery,
+            _ => true,
+        };
+        // Vs. System: console type bit in byte 7 (mapper 99 is Vs.-only).
+        // NES 2.0 byte 13 gives the PPU variant and protection hardware.
+        let vs_system = if header.vs_system || mapper == 99 {
+            let (ppu, hardware) = if header.is_nes2 {
+                (
+                    VsPpu::from_nes2(header.vs_type & 0x0F).unwrap_or_default(),
+                    header.vs_type >> 4,
+                )
+            } else {
+                (VsPpu::Rp2c03, 0)
+            };
+            Some(VsSystem::new(ppu, hardware))
+        } else {
+            None
+        };
+        let region = header.region;
 
-        let mirroring = if matches!(mapper, 77 | 99) {
+        let mirroring = if matches!(mapper, 77 | 99 | 111) {
             Mirroring::FourScreen
         } else if matches!(mapper, 13 | 38 | 208 | 234) {
             Mirroring::Vertical
@@ -44,21 +102,77 @@ impl Cartridge {
             } else {
                 Mirroring::OneScreenLower
             }
-        } else if flags6 & 0x08 != 0 {
+        } else if mapper == 218 && header.four_screen {
+            // CIRAM A10 tied low or high: one-screen A or B
+            if header.vertical_mirroring {
+                Mirroring::OneScreenUpper
+            } else {
+                Mirroring::OneScreenLower
+            }
+        } else if mapper == 30 && header.four_screen && !header.vertical_mirroring {
+            // UNROM 512's one-screen mode, switched by the bank latch
+            Mirroring::OneScreenLower
+        } else if header.four_screen {
             Mirroring::FourScreen
-        } else if flags6 & 0x01 != 0 {
+        } else if header.vertical_mirroring {
             Mirroring::Vertical
         } else {
             Mirroring::Horizontal
         };
 
-        let prg_rom_start = 16;
-        let chr_rom_start = prg_rom_start + prg_rom_size;
+        if prg_rom_size == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "NES file has no PRG-ROM",
+            ));
+        }
 
-        let prg_rom = data[prg_rom_start..prg_rom_start + prg_rom_size].to_vec();
+        let prg_rom_start = header.prg_rom_start();
+        let sizes_overflow = || {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "NES header ROM sizes out of range",
+            )
+        };
+        let chr_rom_start = prg_rom_start
+            .checked_add(prg_rom_size)
+            .ok_or_else(sizes_overflow)?;
+        let rom_end = chr_rom_start
+            .checked_add(chr_rom_size)
+            .ok_or_else(sizes_overflow)?;
+        if data.len() < rom_end {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Truncated NES file: header expects {} bytes of PRG/CHR, found {}",
+                    rom_end - HEADER_SIZE,
+                    data.len().saturating_sub(prg_rom_start)
+                ),
+            ));
+        }
+
+        // PlayChoice-10 dumps append an 8KB INST-ROM (plus 32 bytes of
+        // PROM data) after CHR. Some omit the header flag, so the trailing
+        // size is recognised as well.
+        let trailing = data.len() - rom_end;
+        let playchoice10 = header.playchoice10
+            || trailing == PLAYCHOICE_INST_ROM_SIZE
+            || trailing == PLAYCHOICE_INST_ROM_SIZE + PLAYCHOICE_PROM_SIZE;
+        let playchoice_inst_rom = if playchoice10 && trailing >= PLAYCHOICE_INST_ROM_SIZE {
+            Some(data[rom_end..rom_end + PLAYCHOICE_INST_ROM_SIZE].to_vec())
+        } else {
+            None
+        };
+
+        if !playchoice10 && trailing > 0 {
+            log::warn!("Ignoring {} bytes after the CHR data (overdump?)", trailing);
+        }
+
+        let prg_rom = mirrored_rom(&data[prg_rom_start..chr_rom_start], PRG_MIRROR_MIN, "PRG");
+        let chr_data = mirrored_rom(&data[chr_rom_start..rom_end], CHR_MIRROR_MIN, "CHR");
         let chr_rom = if mapper == 13 {
             if chr_rom_size > 0 {
-                let mut chr = data[chr_rom_start..chr_rom_start + chr_rom_size].to_vec();
+                let mut chr = chr_data;
                 if chr.len() < 0x4000 {
                     chr.resize(0x4000, 0);
                 }
@@ -68,23 +182,35 @@ impl Cartridge {
             }
         } else if mapper == 19 && chr_rom_size == 0 {
             vec![0; 0x2000]
-        } else if mapper == 63 {
+        } else if matches!(mapper, 63 | 111 | 218) || (mapper == 30 && chr_rom_size == 0) {
             vec![]
         } else if mapper == 77 {
             if chr_rom_size > 0 {
-                data[chr_rom_start..chr_rom_start + chr_rom_size].to_vec()
+                chr_data
             } else {
                 vec![0; 0x0800]
             }
-        } else if mapper == 153 || (matches!(mapper, 18 | 221 | 231) && chr_rom_size == 0) {
+        } else if matches!(mapper, 153 | 157)
+            || (matches!(mapper, 18 | 221 | 231) && chr_rom_size == 0)
+        {
             vec![]
         } else if chr_rom_size > 0 {
-            data[chr_rom_start..chr_rom_start + chr_rom_size].to_vec()
+            chr_data
         } else {
             vec![0; 8192]
         };
 
-        let mmc1 = if mapper == 1 { Some(Mmc1::new()) } else { None };
+        // SOROM/SXROM/SZROM bank 16-32KB of PRG-RAM; only NES 2.0 headers
+        // give the RAM size, so iNES images keep the single 8KB bank.
+        let mmc1_board = Mmc1Board::from_prg_ram(
+            header.prg_ram_size + header.prg_nvram_size,
+            chr_rom_size == 0,
+        );
+        let mmc1 = if mapper == 1 {
+            Some(Mmc1::with_board(mmc1_board))
+        } else {
+            None
+        };
         let mmc2 = if mapper == 9 || mapper == 10 {
             Some(Mmc2::new())
         } else {
@@ -175,7 +301,7 @@ impl Cartridge {
         } else {
             None
         };
-        let bandai_fcg = if matches!(mapper, 16 | 153 | 159) {
+        let bandai_fcg = if matches!(mapper, 16 | 153 | 157 | 159) {
             Some(BandaiFcg::new())
         } else {
             None
@@ -230,6 +356,21 @@ impl Cartridge {
         } else {
             None
         };
+        // The battery bit marks the self-flashable board; submappers 1 and 2
+        // are fixed ROM boards.
+        let unrom512 = if mapper == 30 {
+            Some(Unrom512::new(
+                header.battery && !matches!(header.submapper, 1 | 2),
+                header.four_screen && !header.vertical_mirroring,
+            ))
+        } else {
+            None
+        };
+        let gtrom = if mapper == 111 {
+            Some(Gtrom::new())
+        } else {
+            None
+        };
         let sunsoft4 = if mapper == 68 {
             Some(Sunsoft4::new())
         } else {
@@ -241,7 +382,7 @@ impl Cartridge {
             None
         };
 
-        let prg_ram = if mapper == 16 && has_battery {
+        let prg_ram = if (mapper == 16 && has_battery) || mapper == 157 {
             vec![0xFF; 256]
         } else if mapper == 159 && has_battery {
             vec![0xFF; 128]
@@ -278,6 +419,8 @@ impl Cartridge {
             vec![0x00; 0x2000]
         } else if mapper == 153 {
             vec![0x00; 0x8000]
+        } else if mapper == 1 && mmc1_board != Mmc1Board::Standard {
+            vec![0x00; header.prg_ram_size + header.prg_nvram_size]
         } else if mapper == 1
             || mapper == 32
             || mapper == 4
@@ -299,9 +442,11 @@ impl Cartridge {
             Vec::new()
         };
 
-        let chr_ram = if mapper == 19 {
+        let chr_ram = if (mapper == 30 && chr_rom_size == 0) || mapper == 111 {
+            vec![0x00; 0x8000]
+        } else if mapper == 19 {
             vec![0x00; 0x0800]
-        } else if (mapper == 210 && chr_rom_size == 0) || matches!(mapper, 63 | 77 | 153) {
+        } else if (mapper == 210 && chr_rom_size == 0) || matches!(mapper, 63 | 77 | 153 | 157) {
             vec![0x00; 0x2000]
         } else if mapper == 99 {
             vec![0x00; 0x1000]
@@ -398,7 +543,8 @@ impl Cartridge {
             mapper208_protection_index: 0,
             mapper208_protection_regs: [0; 4],
             mapper189_prg_bank: 0,
-            mapper185_disabled_reads: Cell::new(if mapper == 185 { 2 } else { 0 }),
+            mapper185_latch: 0,
+            mapper185_submapper,
             mmc1,
             mmc2,
             mmc3,
@@ -426,9 +572,17 @@ impl Cartridge {
             taito_x1017,
             mapper227_latch: 0,
             mapper246,
+            unrom512,
+            gtrom,
             mapper236_mode: 0,
             mapper236_outer_bank: 0,
             mapper236_chr_ram,
+            vs_system,
+            playchoice_inst_rom,
+            region,
+            rom_crc32: crate::crc32::crc32(&data[prg_rom_start..rom_end]),
+            bus_conflicts,
+            forced_bus_conflicts: None,
         };
         if let Some(ref mut bandai) = cart.bandai_fcg {
             bandai.configure_mapper(mapper, has_battery);
@@ -12,9 +12,11 @@ impl Cartridge {
         }
     }
 
-    /// AxROM PRG write - bits 0-2: 32KB PRG bank, bit 4: nametable select
+    /// AxROM PRG write - bits 0-2: 32KB PRG bank, bit 4: nametable select.
+    /// Only AMROM-style boards see bus conflicts.
     pub(in crate::cartridge) fn write_prg_axrom(&mut self, addr: u16, data: u8) {
         if addr >= 0x8000 {
+            let data = self.bus_conflict(data, self.read_prg_axrom(addr));
             self.prg_bank = data & 0x07;
             self.mirroring = if data & 0x10 != 0 {
                 Mirroring::OneScreenUpper
@@ -31,7 +33,7 @@ impl Cartridge {
             return;
         }
 
-        let effective = data & self.read_prg_axrom(addr);
+        let effective = self.bus_conflict(data, self.read_prg_axrom(addr));
         let prg_bank_count = (self.prg_rom.len() / 0x8000).max(1);
         let chr_bank_count = (self.chr_rom.len() / 0x0800).max(1);
 
@@ -1,6 +1,7 @@
 use std::cell::Cell;
 
-use super::super::{Cartridge, Mirroring};
+use super::super::{BandaiEepromState, Cartridge, Mirroring};
+use super::datach::DatachBarcode;
 
 #[derive(Debug, Clone, Copy)]
 enum BandaiEepromNext {
@@ -32,6 +33,7 @@ enum BandaiEepromKind {
 /// Bandai FCG / LZ93D50 (Mapper 16).
 /// Used by Dragon Ball Z series and other Bandai games.
 /// Features: 8x1KB CHR banking, 16KB PRG banking, CPU-cycle IRQ counter.
+/// Mapper 157 (Datach) adds CHR-RAM and a barcode reader.
 #[derive(Debug, Clone)]
 pub(in crate::cartridge) struct BandaiFcg {
     pub(in crate::cartridge) chr_banks: [u8; 8],
@@ -50,6 +52,7 @@ pub(in crate::cartridge) struct BandaiFcg {
     eeprom_prev_scl: bool,
     eeprom_prev_sda: bool,
     eeprom_data_out: bool,
+    barcode: Option<DatachBarcode>,
 }
 
 impl BandaiFcg {
@@ -71,6 +74,7 @@ impl BandaiFcg {
             eeprom_prev_scl: false,
             eeprom_prev_sda: true,
             eeprom_data_out: true,
+            barcode: None,
         }
     }
 
@@ -88,11 +92,83 @@ impl BandaiFcg {
     pub(in crate::cartridge) fn configure_mapper(&mut self, mapper: u8, has_battery: bool) {
         self.eeprom_kind = if mapper == 159 {
             BandaiEepromKind::X24C01
-        } else if mapper == 16 && has_battery {
+        } else if (mapper == 16 && has_battery) || mapper == 157 {
             BandaiEepromKind::C24C02
         } else {
             BandaiEepromKind::None
         };
+        self.barcode = if mapper == 157 {
+            Some(DatachBarcode::new())
+        } else {
+            None
+        };
+    }
+
+    pub(in crate::cartridge) fn eeprom_state(&self) -> Option<BandaiEepromState> {
+        if self.eeprom_kind == BandaiEepromKind::None {
+            return None;
+        }
+        let next_code = |next: BandaiEepromNext| match next {
+            BandaiEepromNext::ReceiveAddress => 0,
+            BandaiEepromNext::ReceiveData => 1,
+            BandaiEepromNext::SendData => 2,
+        };
+        let (phase, phase_arg, phase_bit) = match self.eeprom_phase {
+            BandaiEepromPhase::Idle => (0, 0, 0),
+            BandaiEepromPhase::ReceivingControl => (1, 0, 0),
+            BandaiEepromPhase::ReceivingAddress => (2, 0, 0),
+            BandaiEepromPhase::ReceivingData => (3, 0, 0),
+            BandaiEepromPhase::AckPending(next) => (4, next_code(next), 0),
+            BandaiEepromPhase::AckLow(next) => (5, next_code(next), 0),
+            BandaiEepromPhase::Sending { byte, bit_index } => (6, byte, bit_index),
+            BandaiEepromPhase::WaitAckPending => (7, 0, 0),
+            BandaiEepromPhase::WaitAck => (8, 0, 0),
+        };
+        Some(BandaiEepromState {
+            phase,
+            phase_arg,
+            phase_bit,
+            address: self.eeprom_address,
+            shift: self.eeprom_shift,
+            bits: self.eeprom_bits,
+            prev_scl: self.eeprom_prev_scl,
+            prev_sda: self.eeprom_prev_sda,
+            data_out: self.eeprom_data_out,
+        })
+    }
+
+    pub(in crate::cartridge) fn restore_eeprom_state(&mut self, state: &BandaiEepromState) {
+        let next = match state.phase_arg {
+            0 => BandaiEepromNext::ReceiveAddress,
+            1 => BandaiEepromNext::ReceiveData,
+            _ => BandaiEepromNext::SendData,
+        };
+        self.eeprom_phase = match state.phase {
+            1 => BandaiEepromPhase::ReceivingControl,
+            2 => BandaiEepromPhase::ReceivingAddress,
+            3 => BandaiEepromPhase::ReceivingData,
+            4 => BandaiEepromPhase::AckPending(next),
+            5 => BandaiEepromPhase::AckLow(next),
+            6 => BandaiEepromPhase::Sending {
+                byte: state.phase_arg,
+                bit_index: state.phase_bit & 0x07,
+            },
+            7 => BandaiEepromPhase::WaitAckPending,
+            8 => BandaiEepromPhase::WaitAck,
+            _ => BandaiEepromPhase::Idle,
+        };
+        self.eeprom_address = state.address;
+        self.eeprom_shift = state.shift;
+        self.eeprom_bits = state.bits;
+        self.eeprom_prev_scl = state.prev_scl;
+        self.eeprom_prev_sda = state.prev_sda;
+        self.eeprom_data_out = state.data_out;
+    }
+
+    pub(in crate::cartridge) fn clock_barcode(&mut self, cycles: u32) {
+        if let Some(ref mut barcode) = self.barcode {
+            barcode.clock(cycles);
+        }
     }
 
     fn eeprom_start(&mut self) {
@@ -372,7 +448,7 @@ impl Cartridge {
     }
 
     pub(in crate::cartridge) fn read_chr_bandai(&self, addr: u16) -> u8 {
-        if self.mapper == 153 {
+        if matches!(self.mapper, 153 | 157) {
             let chr_addr = (addr & 0x1FFF) as usize;
             return self.chr_ram.get(chr_addr).copied().unwrap_or(0);
         }
@@ -397,7 +473,7 @@ impl Cartridge {
     }
 
     pub(in crate::cartridge) fn write_chr_bandai(&mut self, addr: u16, data: u8) {
-        if self.mapper == 153 {
+        if matches!(self.mapper, 153 | 157) {
             let chr_addr = (addr & 0x1FFF) as usize;
             if chr_addr < self.chr_ram.len() {
                 self.chr_ram[chr_addr] = data;
@@ -421,6 +497,10 @@ impl Cartridge {
         }
 
         if let Some(ref bandai) = self.bandai_fcg {
+            if let Some(ref barcode) = bandai.barcode {
+                let eeprom = if bandai.eeprom_data_out { 0x10 } else { 0 };
+                return eeprom | barcode.output();
+            }
             if self.has_battery {
                 return if bandai.eeprom_data_out { 0x10 } else { 0 };
             }
@@ -448,7 +528,7 @@ impl Cartridge {
             return;
         }
 
-        if self.has_battery {
+        if self.has_battery || self.mapper == 157 {
             return;
         }
         let ram_addr = (addr - 0x6000) as usize;
@@ -456,12 +536,19 @@ impl Cartridge {
             self.prg_ram[ram_addr] = data;
         }
     }
+
+    /// Swipe a barcode through the Datach reader (mapper 157 only).
+    pub fn datach_swipe_barcode(&mut self, code: &str) -> Result<(), String> {
+        match self.bandai_fcg.as_mut().and_then(|b| b.barcode.as_mut()) {
+            Some(barcode) => barcode.swipe(code),
+            None => Err("Loaded cartridge has no Datach barcode reader".to_string()),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::cell::Cell;
 
     const EEPROM_READ: u8 = 0x80;
     const EEPROM_SDA: u8 = 0x40;
@@ -531,7 +618,8 @@ mod tests {
             mapper208_protection_index: 0,
             mapper208_protection_regs: [0; 4],
             mapper189_prg_bank: 0,
-            mapper185_disabled_reads: Cell::new(0),
+            mapper185_latch: 0,
+            mapper185_submapper: 0,
             mmc1: None,
             mmc2: None,
             mmc3: None,
@@ -557,11 +645,19 @@ mod tests {
             taito_tc0190: None,
             taito_x1005: None,
             taito_x1017: None,
+            unrom512: None,
+            gtrom: None,
             mapper227_latch: 0,
             mapper246: None,
             mapper236_mode: 0,
             mapper236_outer_bank: 0,
             mapper236_chr_ram: false,
+            vs_system: None,
+            playchoice_inst_rom: None,
+            region: crate::region::Region::Ntsc,
+            rom_crc32: 0,
+            bus_conflicts: true,
+            forced_bus_conflicts: None,
         };
         if let Some(ref mut bandai) = cart.bandai_fcg {
             bandai.configure_mapper(mapper, true);
@@ -666,6 +762,29 @@ mod tests {
         assert!(cart.has_valid_save_data);
     }
 
+    #[test]
+    fn bandai_eeprom_transfer_survives_save_state() {
+        let mut cart = make_bandai_eeprom_cart(16, 256);
+
+        start(&mut cart);
+        assert!(write_byte(&mut cart, 0xA0));
+        assert!(write_byte(&mut cart, 0x2A));
+        for shift in (4..8).rev() {
+            write_bit(&mut cart, ((0x5C >> shift) & 1) != 0);
+        }
+        let snapshot = cart.snapshot_state();
+
+        let mut restored = make_bandai_eeprom_cart(16, 256);
+        restored.restore_state(&snapshot);
+        for shift in (0..4).rev() {
+            write_bit(&mut restored, ((0x5C >> shift) & 1) != 0);
+        }
+        assert!(!read_bit(&mut restored));
+        stop(&mut restored);
+
+        assert_eq!(restored.prg_ram[0x2A], 0x5C);
+    }
+
     #[test]
     fn bandai_eeprom_idle_line_reads_high() {
         let mut cart = make_bandai_eeprom_cart(16, 256);
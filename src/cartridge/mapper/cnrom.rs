@@ -12,8 +12,7 @@ impl Cartridge {
                 self.prg_rom[mirrored_addr as usize]
             };
 
-            // Bus conflict: use AND of written value and ROM value
-            let effective_value = data & rom_value;
+            let effective_value = self.bus_conflict(data, rom_value);
             self.chr_bank = effective_value & 0x03;
         }
     }
@@ -43,13 +42,32 @@ impl Cartridge {
         }
     }
 
-    /// Mapper 185: CNROM variant with CHR output disabled during startup
-    /// probes when the exact chip-select wiring is not available.
+    /// Mapper 185: CNROM with the latch wired to the CHR chip enables.
+    /// Writes go through the usual bus conflict; the latched value decides
+    /// whether CHR-ROM answers at all.
+    pub(in crate::cartridge) fn write_prg_mapper185(&mut self, addr: u16, data: u8) {
+        self.write_prg_cnrom(addr, data);
+        if addr >= 0x8000 {
+            let rom_value = self.prg_rom[(addr as usize - 0x8000) % self.prg_rom.len()];
+            self.mapper185_latch = self.bus_conflict(data, rom_value);
+        }
+    }
+
+    fn mapper185_chr_enabled(&self) -> bool {
+        let latch = self.mapper185_latch;
+        match self.mapper185_submapper {
+            4..=7 => latch & 0x03 == self.mapper185_submapper - 4,
+            // Submapper 0: the usual heuristic - any non-zero low bits
+            // enable CHR, except Bird Week's $13 probe value.
+            _ => latch & 0x03 != 0 && latch != 0x13,
+        }
+    }
+
+    /// Disabled CHR leaves the PPU data bus floating, which reads back as
+    /// $FF on these boards; the protection checks look for that.
     pub(in crate::cartridge) fn read_chr_mapper185(&self, addr: u16) -> u8 {
-        let remaining = self.mapper185_disabled_reads.get();
-        if remaining > 0 {
-            self.mapper185_disabled_reads.set(remaining - 1);
-            return 0;
+        if !self.mapper185_chr_enabled() {
+            return 0xFF;
         }
 
         self.read_chr_cnrom(addr)
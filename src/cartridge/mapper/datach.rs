@@ -0,0 +1,191 @@
+/// Bandai Datach Joint ROM System barcode reader (Mapper 157).
+/// A swiped EAN-13/EAN-8 code is streamed to $6000-$7FFF bit 3, one
+/// module per 1000 CPU cycles (bit set = white space, clear = black bar).
+#[derive(Debug, Clone, Default)]
+pub(in crate::cartridge) struct DatachBarcode {
+    stream: Vec<u8>,
+    position: usize,
+    cycles: u32,
+}
+
+const BARCODE_CYCLES_PER_MODULE: u32 = 1000;
+const BARCODE_SPACE: u8 = 0x08;
+
+// EAN left-hand odd (L) and even (G) codes, 7 modules each, 1 = bar.
+// Right-hand (R) codes are the complement of L.
+const EAN_L_CODES: [u8; 10] = [
+    0b0001101, 0b0011001, 0b0010011, 0b0111101, 0b0100011, 0b0110001, 0b0101111, 0b0111011,
+    0b0110111, 0b0001011,
+];
+const EAN_G_CODES: [u8; 10] = [
+    0b0100111, 0b0110011, 0b0011011, 0b0100001, 0b0011101, 0b0111001, 0b0000101, 0b0010001,
+    0b0001001, 0b0010111,
+];
+// Parity of the six left-hand digits of EAN-13, selected by the first digit
+// (bit 5 = second digit; set = odd/L code).
+const EAN13_PARITY: [u8; 10] = [
+    0b111111, 0b110100, 0b110010, 0b110001, 0b101100, 0b100110, 0b100011, 0b101010, 0b101001,
+    0b100101,
+];
+
+impl DatachBarcode {
+    pub(in crate::cartridge) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start streaming a barcode. Accepts 12/13 (EAN-13) or 7/8 (EAN-8)
+    /// digits; the check digit is always recomputed.
+    pub(in crate::cartridge) fn swipe(&mut self, code: &str) -> Result<(), String> {
+        self.stream = encode_barcode(code)?;
+        self.position = 0;
+        self.cycles = 0;
+        Ok(())
+    }
+
+    pub(in crate::cartridge) fn clock(&mut self, cycles: u32) {
+        if self.position >= self.stream.len() {
+            return;
+        }
+        self.cycles += cycles;
+        while self.cycles >= BARCODE_CYCLES_PER_MODULE && self.position < self.stream.len() {
+            self.cycles -= BARCODE_CYCLES_PER_MODULE;
+            self.position += 1;
+        }
+    }
+
+    pub(in crate::cartridge) fn output(&self) -> u8 {
+        self.stream.get(self.position).copied().unwrap_or(0)
+    }
+}
+
+fn push_modules(stream: &mut Vec<u8>, pattern: u8, width: u8) {
+    for bit in (0..width).rev() {
+        let bar = (pattern >> bit) & 1 != 0;
+        stream.push(if bar { 0 } else { BARCODE_SPACE });
+    }
+}
+
+fn encode_barcode(code: &str) -> Result<Vec<u8>, String> {
+    let digits: Vec<u8> = code
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| {
+            c.to_digit(10)
+                .map(|d| d as u8)
+                .ok_or_else(|| format!("Invalid barcode digit '{}'", c))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let data = match digits.len() {
+        12 | 13 => &digits[..12],
+        7 | 8 => &digits[..7],
+        n => return Err(format!("Barcode must be EAN-13 or EAN-8, got {} digits", n)),
+    };
+
+    let mut stream = Vec::new();
+    stream.resize(33, BARCODE_SPACE);
+    push_modules(&mut stream, 0b101, 3);
+
+    let check = if data.len() == 12 {
+        let parity = EAN13_PARITY[data[0] as usize];
+        for (i, &d) in data[1..7].iter().enumerate() {
+            let odd = (parity >> (5 - i)) & 1 != 0;
+            let code = if odd {
+                EAN_L_CODES[d as usize]
+            } else {
+                EAN_G_CODES[d as usize]
+            };
+            push_modules(&mut stream, code, 7);
+        }
+        push_modules(&mut stream, 0b01010, 5);
+        for &d in &data[7..12] {
+            push_modules(&mut stream, !EAN_L_CODES[d as usize] & 0x7F, 7);
+        }
+        let sum: u32 = data
+            .iter()
+            .enumerate()
+            .map(|(i, &d)| d as u32 * if i & 1 == 1 { 3 } else { 1 })
+            .sum();
+        (10 - sum % 10) % 10
+    } else {
+        for &d in &data[0..4] {
+            push_modules(&mut stream, EAN_L_CODES[d as usize], 7);
+        }
+        push_modules(&mut stream, 0b01010, 5);
+        for &d in &data[4..7] {
+            push_modules(&mut stream, !EAN_L_CODES[d as usize] & 0x7F, 7);
+        }
+        let sum: u32 = data
+            .iter()
+            .enumerate()
+            .map(|(i, &d)| d as u32 * if i & 1 == 1 { 1 } else { 3 })
+            .sum();
+        (10 - sum % 10) % 10
+    };
+    push_modules(&mut stream, !EAN_L_CODES[check as usize] & 0x7F, 7);
+
+    push_modules(&mut stream, 0b101, 3);
+    stream.resize(stream.len() + 32, BARCODE_SPACE);
+    Ok(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ean13_stream_has_expected_length_and_guards() {
+        let stream = encode_barcode("4901234567894").unwrap();
+        // quiet zones + guards + 12 data digits + check digit
+        assert_eq!(stream.len(), 33 + 3 + 42 + 5 + 42 + 3 + 32);
+        assert_eq!(&stream[33..36], &[0, BARCODE_SPACE, 0]);
+        assert_eq!(
+            &stream[78..83],
+            &[BARCODE_SPACE, 0, BARCODE_SPACE, 0, BARCODE_SPACE]
+        );
+    }
+
+    #[test]
+    fn ean13_check_digit_is_recomputed() {
+        assert_eq!(
+            encode_barcode("490123456789").unwrap(),
+            encode_barcode("4901234567890").unwrap()
+        );
+        // Last data character before the end guard encodes check digit 4
+        let stream = encode_barcode("490123456789").unwrap();
+        let check_start = stream.len() - 32 - 3 - 7;
+        let expected: Vec<u8> = (0..7)
+            .rev()
+            .map(|bit| {
+                if (!EAN_L_CODES[4] & 0x7F) >> bit & 1 != 0 {
+                    0
+                } else {
+                    BARCODE_SPACE
+                }
+            })
+            .collect();
+        assert_eq!(&stream[check_start..check_start + 7], &expected[..]);
+    }
+
+    #[test]
+    fn rejects_bad_codes() {
+        assert!(encode_barcode("12345").is_err());
+        assert!(encode_barcode("49012345678A4").is_err());
+    }
+
+    #[test]
+    fn reader_advances_one_module_per_1000_cycles() {
+        let mut reader = DatachBarcode::new();
+        assert_eq!(reader.output(), 0);
+        reader.swipe("12345670").unwrap();
+        assert_eq!(reader.output(), BARCODE_SPACE);
+        reader.clock(33 * 1000);
+        assert_eq!(reader.output(), 0); // start guard bar
+        reader.clock(999);
+        assert_eq!(reader.output(), 0);
+        reader.clock(1);
+        assert_eq!(reader.output(), BARCODE_SPACE);
+        reader.clock(1_000_000);
+        assert_eq!(reader.output(), 0);
+    }
+}
@@ -109,7 +109,7 @@ impl Cartridge {
     /// switchable 8KB CHR, and one-screen mirroring control.
     pub(in crate::cartridge) fn write_prg_mapper89(&mut self, addr: u16, data: u8) {
         if addr >= 0x8000 {
-            let effective = data & self.bus_conflict_value_fixed_last_16k(addr);
+            let effective = self.bus_conflict(data, self.bus_conflict_value_fixed_last_16k(addr));
             let prg_bank_count = (self.prg_rom.len() / 0x4000).max(1);
             let chr_bank_count = (self.chr_rom.len() / 0x2000).max(1);
 
@@ -129,7 +129,7 @@ impl Cartridge {
     /// approximation because the PPU open-bus latch is not modeled.
     pub(in crate::cartridge) fn write_prg_mapper93(&mut self, addr: u16, data: u8) {
         if addr >= 0x8000 {
-            let effective = data & self.bus_conflict_value_fixed_last_16k(addr);
+            let effective = self.bus_conflict(data, self.bus_conflict_value_fixed_last_16k(addr));
             let prg_bank_count = (self.prg_rom.len() / 0x4000).max(1);
             self.prg_bank = (((effective >> 4) & 0x07) as usize % prg_bank_count) as u8;
             self.mapper93_chr_ram_enabled = effective & 0x01 != 0;
@@ -154,7 +154,7 @@ impl Cartridge {
     /// style 32KB PRG / 8KB CHR latch.
     pub(in crate::cartridge) fn write_prg_mapper148(&mut self, addr: u16, data: u8) {
         if addr >= 0x8000 {
-            let effective = data & self.bus_conflict_value_switchable_32k(addr);
+            let effective = self.bus_conflict(data, self.bus_conflict_value_switchable_32k(addr));
             let prg_bank_count = (self.prg_rom.len() / 0x8000).max(1);
             let chr_bank_count = (self.chr_rom.len() / 0x2000).max(1);
             self.prg_bank = (((effective >> 3) & 0x01) as usize % prg_bank_count) as u8;
@@ -167,7 +167,7 @@ impl Cartridge {
     pub(in crate::cartridge) fn write_prg_mapper147(&mut self, addr: u16, data: u8) {
         if (addr & 0x4103) == 0x4102 {
             let effective = if addr >= 0x8000 {
-                data & self.bus_conflict_value_switchable_32k(addr)
+                self.bus_conflict(data, self.bus_conflict_value_switchable_32k(addr))
             } else {
                 data
             };
@@ -260,6 +260,11 @@ impl Fme7 {
 }
 
 impl Cartridge {
+    /// FME-7 boards carry the 5B sound ports at $C000/$E000.
+    pub(crate) fn has_sunsoft5b_audio(&self) -> bool {
+        self.fme7.is_some()
+    }
+
     pub(in crate::cartridge) fn read_prg_fme7(&self, addr: u16) -> u8 {
         if let Some(ref fme7) = self.fme7 {
             let num_8k_banks = self.prg_rom.len() / 0x2000;
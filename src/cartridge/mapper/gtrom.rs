@@ -0,0 +1,98 @@
+// Mapper 111 (GTROM / Cheapocabra): 32KB PRG banks of self-flashable SST39SF040
+// flash, 16KB of CHR-RAM in two 8KB banks and another 16KB holding two 8KB
+// nametable pages for four-screen mirroring. One register at $5000-$5FFF (mirrored at $7000-$7FFF)
+// selects the banks and drives the board's two LEDs.
+use super::super::{BoardLeds, Cartridge};
+use super::sst_flash::SstFlash;
+
+const NAMETABLE_BASE: usize = 0x4000;
+
+#[derive(Debug, Clone, Default)]
+pub struct Gtrom {
+    /// `RGNCPPPP`: red and green LED (lit when clear), nametable page, CHR
+    /// bank and PRG bank.
+    pub register: u8,
+    pub flash: SstFlash,
+}
+
+impl Gtrom {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn prg_offset(&self, addr: u16) -> usize {
+        (self.register & 0x0F) as usize * 0x8000 + (addr & 0x7FFF) as usize
+    }
+}
+
+impl Cartridge {
+    pub(in crate::cartridge) fn read_prg_gtrom(&self, addr: u16) -> u8 {
+        let Some(gtrom) = self.gtrom.as_ref() else {
+            return 0;
+        };
+        let offset = gtrom.prg_offset(addr) % self.prg_rom.len();
+        gtrom
+            .flash
+            .read_id(self.prg_rom.len(), offset)
+            .unwrap_or(self.prg_rom[offset])
+    }
+
+    /// $5000-$5FFF is the register; $8000-$FFFF goes to the flash chip.
+    pub(in crate::cartridge) fn write_prg_gtrom(&mut self, addr: u16, data: u8) {
+        let Some(gtrom) = self.gtrom.as_mut() else {
+            return;
+        };
+        if addr >= 0x8000 {
+            let offset = gtrom.prg_offset(addr) % self.prg_rom.len();
+            if gtrom.flash.write(&mut self.prg_rom, offset, data) {
+                self.has_valid_save_data = true;
+            }
+        } else if addr & 0xF000 == 0x5000 {
+            gtrom.register = data;
+        }
+    }
+
+    pub(in crate::cartridge) fn write_prg_ram_gtrom(&mut self, addr: u16, data: u8) {
+        if addr & 0xF000 == 0x7000 {
+            if let Some(gtrom) = self.gtrom.as_mut() {
+                gtrom.register = data;
+            }
+        }
+    }
+
+    pub(in crate::cartridge) fn read_chr_gtrom(&self, addr: u16) -> u8 {
+        self.chr_ram
+            .get(self.gtrom_chr_offset(addr))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub(in crate::cartridge) fn write_chr_gtrom(&mut self, addr: u16, data: u8) {
+        let offset = self.gtrom_chr_offset(addr);
+        if let Some(slot) = self.chr_ram.get_mut(offset) {
+            *slot = data;
+        }
+    }
+
+    /// Where `offset` of nametable `physical_nt` sits in CHR-RAM.
+    pub(in crate::cartridge) fn gtrom_nametable_offset(
+        &self,
+        physical_nt: usize,
+        offset: usize,
+    ) -> usize {
+        let page = self.gtrom.as_ref().map_or(0, |m| (m.register >> 5) & 1) as usize;
+        NAMETABLE_BASE + page * 0x2000 + (physical_nt & 3) * 0x0400 + offset
+    }
+
+    pub(in crate::cartridge) fn gtrom_leds(&self) -> Option<BoardLeds> {
+        self.gtrom.as_ref().map(|m| BoardLeds {
+            red: m.register & 0x80 == 0,
+            green: m.register & 0x40 == 0,
+        })
+    }
+
+    fn gtrom_chr_offset(&self, addr: u16) -> usize {
+        let bank = self.gtrom.as_ref().map_or(0, |m| (m.register >> 4) & 1) as usize;
+        bank * 0x2000 + (addr & 0x1FFF) as usize
+    }
+}
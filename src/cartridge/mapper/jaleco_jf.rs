@@ -18,7 +18,7 @@ impl Cartridge {
     /// plus an 8KB CHR bank. Mirroring comes from the ROM header.
     pub(in crate::cartridge) fn write_prg_mapper70(&mut self, addr: u16, data: u8) {
         if addr >= 0x8000 {
-            let effective = data & self.bus_conflict_value_jaleco(addr);
+            let effective = self.bus_conflict(data, self.bus_conflict_value_jaleco(addr));
             let prg_bank_count = (self.prg_rom.len() / 0x4000).max(1);
             let chr_bank_count = (self.chr_rom.len() / 0x2000).max(1);
             self.prg_bank = ((effective >> 4) as usize % prg_bank_count) as u8;
@@ -29,7 +29,7 @@ impl Cartridge {
     /// Mapper 152: Mapper 70 variant with one-screen mirroring control.
     pub(in crate::cartridge) fn write_prg_mapper152(&mut self, addr: u16, data: u8) {
         if addr >= 0x8000 {
-            let effective = data & self.bus_conflict_value_jaleco(addr);
+            let effective = self.bus_conflict(data, self.bus_conflict_value_jaleco(addr));
             let prg_bank_count = (self.prg_rom.len() / 0x4000).max(1);
             let chr_bank_count = (self.chr_rom.len() / 0x2000).max(1);
             self.prg_bank = (((effective >> 4) & 0x07) as usize % prg_bank_count) as u8;
@@ -62,7 +62,7 @@ impl Cartridge {
         } else {
             self.bus_conflict_value_jaleco(addr)
         };
-        let effective = data & conflict;
+        let effective = self.bus_conflict(data, conflict);
         let previous = self.chr_bank_1;
         let prg_edge = (previous & 0x80 == 0) && (effective & 0x80 != 0);
         let chr_edge = (previous & 0x40 == 0) && (effective & 0x40 != 0);
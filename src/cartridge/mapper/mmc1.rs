@@ -1,5 +1,32 @@
 use super::super::{Cartridge, Mirroring};
 
+/// SxROM boards that bank more than 8KB of PRG-RAM through the CHR bank
+/// registers. Picked at load time from the NES 2.0 PRG-RAM sizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(in crate::cartridge) enum Mmc1Board {
+    /// A single fixed 8KB PRG-RAM (SNROM, SUROM, ...).
+    #[default]
+    Standard,
+    /// 16KB PRG-RAM with CHR-RAM: CHR bank bit 3 selects the 8KB bank.
+    Sorom,
+    /// 32KB PRG-RAM: CHR bank bits 2-3 select the 8KB bank.
+    Sxrom,
+    /// 16KB PRG-RAM with CHR-ROM: CHR bank bit 4 selects the 8KB bank.
+    Szrom,
+}
+
+impl Mmc1Board {
+    /// Board for a cartridge with `prg_ram` bytes of total PRG-RAM.
+    pub(in crate::cartridge) fn from_prg_ram(prg_ram: usize, chr_ram: bool) -> Self {
+        match prg_ram {
+            0x4000 if chr_ram => Mmc1Board::Sorom,
+            0x4000 => Mmc1Board::Szrom,
+            0x8000 => Mmc1Board::Sxrom,
+            _ => Mmc1Board::Standard,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(in crate::cartridge) struct Mmc1 {
     pub(in crate::cartridge) shift_register: u8,
@@ -9,6 +36,7 @@ pub(in crate::cartridge) struct Mmc1 {
     pub(in crate::cartridge) chr_bank_1: u8,
     pub(in crate::cartridge) prg_bank: u8,
     pub(in crate::cartridge) prg_ram_disable: bool,
+    pub(in crate::cartridge) board: Mmc1Board,
 }
 
 impl Mmc1 {
@@ -21,8 +49,27 @@ impl Mmc1 {
             chr_bank_1: 0,
             prg_bank: 0,
             prg_ram_disable: false,
+            board: Mmc1Board::Standard,
+        }
+    }
+
+    pub(in crate::cartridge) fn with_board(board: Mmc1Board) -> Self {
+        Mmc1 {
+            board,
+            ..Mmc1::new()
         }
     }
+
+    /// Offset of the selected 8KB PRG-RAM bank.
+    fn prg_ram_offset(&self) -> usize {
+        let bank = match self.board {
+            Mmc1Board::Standard => 0,
+            Mmc1Board::Sorom => (self.chr_bank_0 >> 3) & 0x01,
+            Mmc1Board::Sxrom => (self.chr_bank_0 >> 2) & 0x03,
+            Mmc1Board::Szrom => (self.chr_bank_0 >> 4) & 0x01,
+        };
+        bank as usize * 0x2000
+    }
 }
 
 impl Cartridge {
@@ -114,6 +161,12 @@ impl Cartridge {
         }
     }
 
+    /// MMC1 ignores a serial port write on the cycle after another one, so
+    /// only the first write of a RMW instruction reaches the shift register.
+    pub(crate) fn ignores_consecutive_writes(&self) -> bool {
+        self.mmc1.is_some()
+    }
+
     /// MMC1 PRG write - shift register + register decode
     pub(in crate::cartridge) fn write_prg_mmc1(&mut self, addr: u16, data: u8) {
         if let Some(ref mut mmc1) = self.mmc1 {
@@ -271,7 +324,8 @@ impl Cartridge {
                 }
             }
 
-            let ram_addr = (addr - 0x6000) as usize;
+            let bank = self.mmc1.as_ref().map_or(0, Mmc1::prg_ram_offset);
+            let ram_addr = bank + (addr - 0x6000) as usize;
             if ram_addr < self.prg_ram.len() {
                 self.prg_ram[ram_addr]
             } else {
@@ -294,7 +348,8 @@ impl Cartridge {
                 }
             }
 
-            let ram_addr = (addr - 0x6000) as usize;
+            let bank = self.mmc1.as_ref().map_or(0, Mmc1::prg_ram_offset);
+            let ram_addr = bank + (addr - 0x6000) as usize;
             if ram_addr < self.prg_ram.len() {
                 self.prg_ram[ram_addr] = data;
 
@@ -1524,6 +1524,7 @@ impl Cartridge {
             for _ in 0..cycles {
                 bandai.clock_irq_mut();
             }
+            bandai.clock_barcode(cycles);
         }
         if let Some(ref mut mapper40) = self.mapper40 {
             mapper40.clock_irq_mut(cycles);
@@ -4,9 +4,11 @@ mod bnrom_nina001;
 mod camerica;
 mod cnrom;
 mod color_dreams;
+mod datach;
 mod discrete;
 mod fixed_irq;
 mod fme7;
+mod gtrom;
 mod gxrom;
 mod irem_g101;
 mod irem_h3001;
@@ -27,24 +29,28 @@ mod namco163;
 mod namco210;
 mod nrom;
 mod realtec;
+mod sst_flash;
 mod sunsoft3;
 mod sunsoft4;
 mod taito;
+mod unrom512;
 mod uxrom;
 mod vrc1;
 mod vrc2_vrc4;
 mod vrc3;
 mod vrc6;
+mod vs_system;
 
 pub(super) use bandai_fcg::BandaiFcg;
 pub(super) use fixed_irq::{Mapper40, Mapper42, Mapper43, Mapper50};
 pub(super) use fme7::Fme7;
+pub(super) use gtrom::Gtrom;
 pub(super) use irem_g101::IremG101;
 pub(super) use irem_h3001::IremH3001;
 pub(super) use jaleco_ss88006::JalecoSs88006;
 pub(super) use mapper15::Mapper15;
 pub(super) use mapper246::Mapper246;
-pub(super) use mmc1::Mmc1;
+pub(super) use mmc1::{Mmc1, Mmc1Board};
 pub(super) use mmc2::Mmc2;
 pub(super) use mmc3::Mmc3;
 pub(super) use mmc5::Mmc5;
@@ -53,7 +59,9 @@ pub(super) use namco210::Namco210;
 pub(super) use sunsoft3::Sunsoft3;
 pub(super) use sunsoft4::Sunsoft4;
 pub(super) use taito::{TaitoTc0190, TaitoX1005, TaitoX1017};
+pub(super) use unrom512::Unrom512;
 pub(super) use vrc1::Vrc1;
 pub(super) use vrc2_vrc4::Vrc2Vrc4;
 pub(super) use vrc3::Vrc3;
 pub(super) use vrc6::Vrc6;
+pub(super) use vs_system::VsSystem;
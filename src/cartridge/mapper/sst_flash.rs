@@ -0,0 +1,86 @@
+// SST39SF0x0 flash as fitted to self-flashing homebrew boards (UNROM 512,
+// GTROM). Command addresses are decoded in flash address space; the board
+// maps its PRG bank onto the upper address lines. Programs and erases
+// complete at once, so the chip never reports busy.
+
+const MANUFACTURER_ID: u8 = 0xBF;
+const SECTOR_SIZE: usize = 0x1000;
+
+/// Steps of the command sequences.
+const IDLE: u8 = 0;
+const UNLOCK_1: u8 = 1;
+const UNLOCK_2: u8 = 2;
+const PROGRAM: u8 = 3;
+const ERASE_UNLOCK_0: u8 = 4;
+const ERASE_UNLOCK_1: u8 = 5;
+const ERASE_UNLOCK_2: u8 = 6;
+
+#[derive(Debug, Clone, Default)]
+pub struct SstFlash {
+    pub step: u8,
+    /// Software ID mode: reads return the chip's ID bytes.
+    pub software_id: bool,
+}
+
+impl SstFlash {
+    /// What a read at `offset` returns instead of the array, in software
+    /// ID mode. The device ID follows the chip size (SST39SF010A/020A/040).
+    pub fn read_id(&self, size: usize, offset: usize) -> Option<u8> {
+        if !self.software_id {
+            return None;
+        }
+        let device_id = match size {
+            0x80000.. => 0xB7,
+            0x40000.. => 0xB6,
+            _ => 0xB5,
+        };
+        Some(if offset & 1 == 0 {
+            MANUFACTURER_ID
+        } else {
+            device_id
+        })
+    }
+
+    /// One write of a command sequence: byte program (AA/55/A0, then the
+    /// byte), sector or chip erase (AA/55/80/AA/55, then 30 to the sector
+    /// or 10 to $5555), and software ID entry (90) and exit (F0). Returns
+    /// whether `memory` changed.
+    pub fn write(&mut self, memory: &mut [u8], offset: usize, data: u8) -> bool {
+        let mut changed = false;
+        self.step = match (self.step, offset & 0x7FFF, data) {
+            (_, _, 0xF0) => {
+                self.software_id = false;
+                IDLE
+            }
+            (IDLE, 0x5555, 0xAA) => UNLOCK_1,
+            (UNLOCK_1, 0x2AAA, 0x55) => UNLOCK_2,
+            (UNLOCK_2, 0x5555, 0xA0) => PROGRAM,
+            (UNLOCK_2, 0x5555, 0x80) => ERASE_UNLOCK_0,
+            (UNLOCK_2, 0x5555, 0x90) => {
+                self.software_id = true;
+                IDLE
+            }
+            (PROGRAM, _, _) => {
+                // Programming can only clear bits
+                memory[offset] &= data;
+                changed = true;
+                IDLE
+            }
+            (ERASE_UNLOCK_0, 0x5555, 0xAA) => ERASE_UNLOCK_1,
+            (ERASE_UNLOCK_1, 0x2AAA, 0x55) => ERASE_UNLOCK_2,
+            (ERASE_UNLOCK_2, _, 0x30) => {
+                let sector = offset & !(SECTOR_SIZE - 1);
+                memory[sector..sector + SECTOR_SIZE].fill(0xFF);
+                changed = true;
+                IDLE
+            }
+            (ERASE_UNLOCK_2, 0x5555, 0x10) => {
+                memory.fill(0xFF);
+                changed = true;
+                IDLE
+            }
+            _ => IDLE,
+        };
+        changed
+    }
+}
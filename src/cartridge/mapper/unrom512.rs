@@ -0,0 +1,93 @@
+// Mapper 30 (UNROM 512): UNROM with up to 512KB PRG, 32KB of banked CHR-RAM
+// and, on the self-flashable variant, an SST39SF0x0 flash chip as PRG that
+// the game reprograms to keep its saves. The flash sees the selected bank
+// plus A0-A13.
+use super::super::{Cartridge, Mirroring};
+use super::sst_flash::SstFlash;
+
+#[derive(Debug, Clone)]
+pub struct Unrom512 {
+    /// Writes to $8000-$BFFF go to the flash chip instead of the latch.
+    pub flashable: bool,
+    /// Header mirroring mode where bit 7 of the latch picks the screen.
+    pub one_screen: bool,
+    pub flash: SstFlash,
+}
+
+impl Unrom512 {
+    pub fn new(flashable: bool, one_screen: bool) -> Self {
+        Self {
+            flashable,
+            one_screen,
+            flash: SstFlash::default(),
+        }
+    }
+}
+
+impl Cartridge {
+    pub(in crate::cartridge) fn read_prg_unrom512(&self, addr: u16) -> u8 {
+        let offset = self.unrom512_prg_offset(addr);
+        if let Some(id) = self
+            .unrom512
+            .as_ref()
+            .and_then(|m| m.flash.read_id(self.prg_rom.len(), offset))
+        {
+            return id;
+        }
+        self.prg_rom[offset]
+    }
+
+    /// Latch layout `MCCPPPPP`: PRG bank, CHR-RAM bank and, in one-screen
+    /// mode, the nametable.
+    pub(in crate::cartridge) fn write_prg_unrom512(&mut self, addr: u16, data: u8) {
+        let Some(flashable) = self.unrom512.as_ref().map(|m| m.flashable) else {
+            return;
+        };
+        if flashable && addr < 0xC000 {
+            let offset = self.unrom512_prg_offset(addr);
+            if let Some(mapper) = self.unrom512.as_mut() {
+                if mapper.flash.write(&mut self.prg_rom, offset, data) {
+                    self.has_valid_save_data = true;
+                }
+            }
+            return;
+        }
+        let value = self.bus_conflict(data, self.prg_rom[self.unrom512_prg_offset(addr)]);
+        let bank_count = (self.prg_rom.len() / 0x4000).max(1);
+        self.prg_bank = ((value & 0x1F) as usize % bank_count) as u8;
+        self.chr_bank = (value >> 5) & 0x03;
+        if self.unrom512.as_ref().is_some_and(|m| m.one_screen) {
+            self.mirroring = if value & 0x80 != 0 {
+                Mirroring::OneScreenUpper
+            } else {
+                Mirroring::OneScreenLower
+            };
+        }
+    }
+
+    pub(in crate::cartridge) fn read_chr_unrom512(&self, addr: u16) -> u8 {
+        if self.chr_ram.is_empty() {
+            return self.read_chr_cnrom(addr);
+        }
+        let offset = self.chr_bank as usize * 0x2000 + (addr & 0x1FFF) as usize;
+        self.chr_ram[offset % self.chr_ram.len()]
+    }
+
+    pub(in crate::cartridge) fn write_chr_unrom512(&mut self, addr: u16, data: u8) {
+        if self.chr_ram.is_empty() {
+            return;
+        }
+        let offset = self.chr_bank as usize * 0x2000 + (addr & 0x1FFF) as usize;
+        let len = self.chr_ram.len();
+        self.chr_ram[offset % len] = data;
+    }
+
+    fn unrom512_prg_offset(&self, addr: u16) -> usize {
+        let bank = if addr < 0xC000 {
+            self.prg_bank as usize
+        } else {
+            self.prg_rom.len() / 0x4000 - 1
+        };
+        (bank * 0x4000 + (addr & 0x3FFF) as usize) % self.prg_rom.len()
+    }
+}
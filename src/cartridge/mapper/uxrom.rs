@@ -40,10 +40,10 @@ impl Cartridge {
         }
     }
 
-    /// UxROM PRG write - bank switching with bus conflicts
+    /// UxROM PRG write - bank switching, with bus conflicts on boards
+    /// that have them. UOROM decodes four bank bits, UNROM three.
     pub(in crate::cartridge) fn write_prg_uxrom(&mut self, addr: u16, data: u8) {
         if addr >= 0x8000 {
-            // Bus conflicts: AND written value with ROM value
             let rom_offset = if addr < 0xC000 {
                 (self.prg_bank as usize) * 0x4000 + ((addr - 0x8000) as usize)
             } else {
@@ -56,8 +56,9 @@ impl Cartridge {
                 0xFF
             };
 
-            let effective_value = data & rom_value;
-            self.prg_bank = effective_value & 0x07;
+            let effective_value = self.bus_conflict(data, rom_value);
+            let bank_count = (self.prg_rom.len() / 0x4000).max(1);
+            self.prg_bank = ((effective_value & 0x0F) as usize % bank_count) as u8;
         }
     }
 
@@ -97,7 +98,7 @@ impl Cartridge {
                 0xFF
             };
 
-            let effective_value = data & rom_value;
+            let effective_value = self.bus_conflict(data, rom_value);
             let bank_count = (self.prg_rom.len() / 0x4000).max(1);
             self.prg_bank = (effective_value as usize % bank_count) as u8;
         }
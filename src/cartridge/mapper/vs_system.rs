@@ -0,0 +1,56 @@
+use std::cell::Cell;
+
+use super::super::Cartridge;
+use crate::ppu::VsPpu;
+
+/// NES 2.0 Vs. hardware type with the RBI Baseball $5E0x protection.
+const VS_HARDWARE_RBI_BASEBALL: u8 = 1;
+
+/// Vs. System board details taken from the header (iNES byte 7 bit 0,
+/// NES 2.0 byte 13).
+#[derive(Debug, Clone)]
+pub(in crate::cartridge) struct VsSystem {
+    pub(in crate::cartridge) ppu: VsPpu,
+    pub(in crate::cartridge) hardware: u8,
+    pub(in crate::cartridge) protection_counter: Cell<u8>,
+}
+
+impl VsSystem {
+    pub(in crate::cartridge) fn new(ppu: VsPpu, hardware: u8) -> Self {
+        Self {
+            ppu,
+            hardware,
+            protection_counter: Cell::new(0),
+        }
+    }
+
+    /// Protection chip reads in $4020-$5FFF, if this board has one.
+    pub(in crate::cartridge) fn read_protection(&self, addr: u16) -> Option<u8> {
+        if self.hardware != VS_HARDWARE_RBI_BASEBALL {
+            return None;
+        }
+        match addr {
+            0x5E00 => {
+                self.protection_counter.set(0);
+                Some(0)
+            }
+            0x5E01 => {
+                let count = self.protection_counter.get();
+                self.protection_counter.set(count.wrapping_add(1));
+                Some(if count == 9 { 0x6F } else { 0xB4 })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Cartridge {
+    pub fn is_vs_system(&self) -> bool {
+        self.vs_system.is_some()
+    }
+
+    /// PPU variant the header asks for; `None` for non-Vs. cartridges.
+    pub fn vs_ppu(&self) -> Option<VsPpu> {
+        self.vs_system.as_ref().map(|vs| vs.ppu)
+    }
+}
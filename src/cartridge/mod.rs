@@ -1,15 +1,20 @@
+mod boards;
+mod header;
 mod load;
 mod mapper;
 mod state;
+mod unif;
 
+use crate::region::Region;
+pub use header::InesHeader;
 use mapper::{
-    BandaiFcg, Fme7, IremG101, IremH3001, JalecoSs88006, Mapper15, Mapper246, Mapper40, Mapper42,
-    Mapper43, Mapper50, Mmc1, Mmc2, Mmc3, Mmc5, Namco163, Namco210, Sunsoft3, Sunsoft4,
-    TaitoTc0190, TaitoX1005, TaitoX1017, Vrc1, Vrc2Vrc4, Vrc3, Vrc6,
+    BandaiFcg, Fme7, Gtrom, IremG101, IremH3001, JalecoSs88006, Mapper15, Mapper246, Mapper40,
+    Mapper42, Mapper43, Mapper50, Mmc1, Mmc1Board, Mmc2, Mmc3, Mmc5, Namco163, Namco210, Sunsoft3,
+    Sunsoft4, TaitoTc0190, TaitoX1005, TaitoX1017, Unrom512, Vrc1, Vrc2Vrc4, Vrc3, Vrc6, VsSystem,
 };
 use serde::{Deserialize, Serialize};
 pub use state::*;
-use std::cell::Cell;
+pub use unif::unif_board_mapper;
 
 pub struct Cartridge {
     prg_rom: Vec<u8>,
@@ -74,7 +79,8 @@ pub struct Cartridge {
     mapper208_protection_index: u8,
     mapper208_protection_regs: [u8; 4],
     mapper189_prg_bank: u8,
-    mapper185_disabled_reads: Cell<u8>,
+    mapper185_latch: u8,
+    mapper185_submapper: u8,
     mmc1: Option<Mmc1>,
     mmc2: Option<Mmc2>,
     mmc3: Option<Mmc3>,
@@ -100,11 +106,19 @@ pub struct Cartridge {
     taito_tc0190: Option<TaitoTc0190>,
     taito_x1005: Option<TaitoX1005>,
     taito_x1017: Option<TaitoX1017>,
+    unrom512: Option<Unrom512>,
+    gtrom: Option<Gtrom>,
     mapper227_latch: u16,
     mapper246: Option<Mapper246>,
     mapper236_mode: u8,
     mapper236_outer_bank: u8,
     mapper236_chr_ram: bool,
+    vs_system: Option<VsSystem>,
+    playchoice_inst_rom: Option<Vec<u8>>,
+    region: Region,
+    rom_crc32: u32,
+    bus_conflicts: bool,
+    forced_bus_conflicts: Option<bool>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -118,11 +132,31 @@ pub enum Mirroring {
     OneScreenUpper,
 }
 
+/// Status LEDs some homebrew boards drive from a mapper register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardLeds {
+    pub red: bool,
+    pub green: bool,
+}
+
+impl BoardLeds {
+    /// One light showing both LEDs; red and green together are yellow.
+    pub fn rgb(self) -> [u8; 3] {
+        [
+            if self.red { 0xFF } else { 0 },
+            if self.green { 0xFF } else { 0 },
+            0,
+        ]
+    }
+}
+
 impl Cartridge {
     pub fn read_prg(&self, addr: u16) -> u8 {
         let rom_addr = addr - 0x8000;
         match self.mapper {
             210 => self.read_prg_mapper210(addr),
+            30 => self.read_prg_unrom512(addr),
+            111 => self.read_prg_gtrom(addr),
             21 => self.read_prg_mapper21(addr),
             22 => self.read_prg_mapper22(addr),
             23 => self.read_prg_mapper23(addr),
@@ -132,7 +166,7 @@ impl Cartridge {
             19 => self.read_prg_namco163(addr),
             5 => self.read_prg_mmc5(addr),
             64 => self.read_prg_mapper64(addr),
-            0 | 3 | 13 | 87 | 101 | 184 | 185 => self.read_prg_nrom(rom_addr),
+            0 | 3 | 13 | 87 | 101 | 145 | 184 | 185 | 218 => self.read_prg_nrom(rom_addr),
             59 => self.read_prg_mapper59(addr),
             60 => self.read_prg_mapper60(addr),
             61 => self.read_prg_mapper61(addr),
@@ -201,7 +235,7 @@ impl Cartridge {
             7 | 11 | 66 | 107 => self.read_prg_axrom(addr),
             78 | 94 => self.read_prg_uxrom(addr, rom_addr),
             9 | 10 => self.read_prg_mmc2(addr, rom_addr),
-            16 | 153 | 159 => self.read_prg_bandai(addr),
+            16 | 153 | 157 | 159 => self.read_prg_bandai(addr),
             69 => self.read_prg_fme7(addr),
             _ => 0,
         }
@@ -220,8 +254,10 @@ impl Cartridge {
 
     pub fn write_prg(&mut self, addr: u16, data: u8) {
         match self.mapper {
-            0 => {}
+            0 | 218 => {}
             210 => self.write_prg_mapper210(addr, data),
+            30 => self.write_prg_unrom512(addr, data),
+            111 => self.write_prg_gtrom(addr, data),
             21 => self.write_prg_mapper21(addr, data),
             22 => self.write_prg_mapper22(addr, data),
             23 => self.write_prg_mapper23(addr, data),
@@ -253,7 +289,8 @@ impl Cartridge {
             233 => self.write_prg_mapper233(addr, data),
             234 => self.write_prg_mapper234(addr, data),
             235 => self.write_prg_mapper235(addr, data),
-            3 | 185 => self.write_prg_cnrom(addr, data),
+            3 => self.write_prg_cnrom(addr, data),
+            185 => self.write_prg_mapper185(addr, data),
             13 => self.write_prg_cprom(addr, data),
             34 if self.mapper34_nina001 => {}
             34 => self.write_prg_bnrom(addr, data),
@@ -321,7 +358,7 @@ impl Cartridge {
             180 => self.write_prg_uxrom_inverted(addr, data),
             240 => self.write_prg_mapper240(addr, data),
             9 | 10 => self.write_prg_mmc2(addr, data),
-            16 | 153 | 159 => self.write_prg_bandai(addr, data),
+            16 | 153 | 157 | 159 => self.write_prg_bandai(addr, data),
             66 => self.write_prg_gxrom(addr, data),
             69 => self.write_prg_fme7(addr, data),
             87 => self.write_prg_mapper87(addr, data),
@@ -333,6 +370,8 @@ impl Cartridge {
     pub fn read_chr(&self, addr: u16) -> u8 {
         match self.mapper {
             210 => self.read_chr_mapper210(addr),
+            30 => self.read_chr_unrom512(addr),
+            111 => self.read_chr_gtrom(addr),
             21 => self.read_chr_mapper21(addr),
             22 => self.read_chr_mapper22(addr),
             23 => self.read_chr_mapper23(addr),
@@ -396,7 +435,7 @@ impl Cartridge {
             82 => self.read_chr_taito_x1017(addr),
             76 | 88 | 95 | 154 | 206 | 112 => self.read_chr_namco108(addr),
             9 | 10 => self.read_chr_mmc2(addr),
-            16 | 153 | 159 => self.read_chr_bandai(addr),
+            16 | 153 | 157 | 159 => self.read_chr_bandai(addr),
             69 => self.read_chr_fme7(addr),
             _ => {
                 let chr_addr = (addr & 0x1FFF) as usize;
@@ -409,6 +448,20 @@ impl Cartridge {
         }
     }
 
+    /// Read CHR for debug views without tripping MMC2/MMC4 read latches.
+    pub fn peek_chr(&self, addr: u16) -> u8 {
+        let latches = self
+            .mmc2
+            .as_ref()
+            .map(|mmc2| (mmc2.latch_0.get(), mmc2.latch_1.get()));
+        let value = self.read_chr(addr);
+        if let (Some(mmc2), Some((latch_0, latch_1))) = (self.mmc2.as_ref(), latches) {
+            mmc2.latch_0.set(latch_0);
+            mmc2.latch_1.set(latch_1);
+        }
+        value
+    }
+
     pub fn read_chr_sprite(&self, addr: u16, _sprite_y: u8) -> u8 {
         if self.mapper == 5 {
             self.read_chr_sprite_mmc5(addr, _sprite_y)
@@ -420,6 +473,8 @@ impl Cartridge {
     pub fn write_chr(&mut self, addr: u16, data: u8) {
         match self.mapper {
             210 => self.write_chr_mapper210(addr, data),
+            30 => self.write_chr_unrom512(addr, data),
+            111 => self.write_chr_gtrom(addr, data),
             21 => self.write_chr_mapper21(addr, data),
             22 => self.write_chr_mapper22(addr, data),
             23 => self.write_chr_mapper23(addr, data),
@@ -482,10 +537,10 @@ impl Cartridge {
             68 => self.write_chr_sunsoft4(addr, data),
             80 | 207 => self.write_chr_taito_x1005(addr, data),
             82 => self.write_chr_taito_x1017(addr, data),
-            246 => {}
+            218 | 246 => {}
             76 | 88 | 95 | 154 | 206 | 112 => self.write_chr_namco108(addr, data),
             9 | 10 => self.write_chr_mmc2(addr, data),
-            16 | 153 | 159 => self.write_chr_bandai(addr, data),
+            16 | 153 | 157 | 159 => self.write_chr_bandai(addr, data),
             69 => self.write_chr_fme7(addr, data),
             _ => {
                 self.chr_rom[(addr & 0x1FFF) as usize] = data;
@@ -553,7 +608,7 @@ impl Cartridge {
                 }
             }
             9 | 10 => self.read_prg_ram_mmc2(addr),
-            16 | 153 | 159 => self.read_prg_ram_bandai(addr),
+            16 | 153 | 157 | 159 => self.read_prg_ram_bandai(addr),
             103 => self.read_prg_ram_mapper103(addr),
             69 => self.read_prg_ram_fme7(addr),
             _ => 0,
@@ -561,6 +616,13 @@ impl Cartridge {
     }
 
     pub fn read_prg_low(&self, addr: u16) -> u8 {
+        if let Some(value) = self
+            .vs_system
+            .as_ref()
+            .and_then(|vs| vs.read_protection(addr))
+        {
+            return value;
+        }
         match self.mapper {
             19 => self.read_prg_low_namco163(addr),
             5 => self.read_prg_low_mmc5(addr),
@@ -640,8 +702,9 @@ impl Cartridge {
                 }
             }
             9 | 10 => self.write_prg_ram_mmc2(addr, data),
-            16 | 153 | 159 => self.write_prg_ram_bandai(addr, data),
+            16 | 153 | 157 | 159 => self.write_prg_ram_bandai(addr, data),
             69 => self.write_prg_ram_fme7(addr, data),
+            111 => self.write_prg_ram_gtrom(addr, data),
             _ => {}
         }
     }
@@ -667,9 +730,6 @@ impl Cartridge {
         if self.mapper == 60 {
             self.advance_mapper60_game();
         }
-        if self.mapper == 185 {
-            self.mapper185_disabled_reads.set(2);
-        }
         if self.mapper == 230 {
             self.mapper230_contra_mode = !self.mapper230_contra_mode;
             if self.mapper230_contra_mode {
@@ -685,10 +745,80 @@ impl Cartridge {
         self.chr_rom.len()
     }
 
+    /// The whole CHR-ROM, empty for CHR-RAM boards.
+    pub fn chr_rom(&self) -> &[u8] {
+        &self.chr_rom
+    }
+
     pub fn mapper_number(&self) -> u8 {
         self.mapper
     }
 
+    /// Whether `mapper` is emulated. Other numbers still load, but their PRG
+    /// reads back as zeros, so the game cannot start.
+    pub fn is_supported_mapper(mapper: u8) -> bool {
+        boards::EMULATED.iter().any(|&(number, _)| number == mapper)
+    }
+
+    /// Board name for an iNES mapper number, emulated or not.
+    pub fn board_name(mapper: u8) -> Option<&'static str> {
+        boards::EMULATED
+            .iter()
+            .chain(boards::UNEMULATED)
+            .find(|&&(number, _)| number == mapper)
+            .map(|&(_, name)| name)
+    }
+
+    /// PlayChoice-10 INST-ROM (menu/instruction screens run by the Z80
+    /// side), kept separate from the game's PRG/CHR.
+    pub fn playchoice_inst_rom(&self) -> Option<&[u8]> {
+        self.playchoice_inst_rom.as_deref()
+    }
+
+    /// Console timing the header asks for.
+    pub fn region(&self) -> Region {
+        self.region
+    }
+
+    /// CRC-32 of the PRG and CHR data as dumped (header and trainer
+    /// excluded), the key ROM databases use.
+    pub fn rom_crc32(&self) -> u32 {
+        self.rom_crc32
+    }
+
+    /// Force discrete-logic boards to AND (`Some(true)`) or not
+    /// (`Some(false)`) register writes with the ROM byte at the written
+    /// address; `None` goes back to what the board does.
+    pub fn set_forced_bus_conflicts(&mut self, forced: Option<bool>) {
+        self.forced_bus_conflicts = forced;
+    }
+
+    /// Whether register writes currently see bus conflicts.
+    pub fn bus_conflicts(&self) -> bool {
+        self.forced_bus_conflicts.unwrap_or(self.bus_conflicts)
+    }
+
+    /// Value a discrete latch receives when the CPU writes `data` while
+    /// the ROM drives `rom_value` onto the bus.
+    pub(in crate::cartridge) fn bus_conflict(&self, data: u8, rom_value: u8) -> u8 {
+        if self.bus_conflicts() {
+            data & rom_value
+        } else {
+            data
+        }
+    }
+
+    /// Mapper 218 has no CHR chip: pattern fetches go to the console's
+    /// nametable RAM, with CIRAM A10 wired as the mirroring selects.
+    pub fn chr_in_ciram(&self) -> bool {
+        self.mapper == 218
+    }
+
+    /// The board's LEDs, on boards that have them (GTROM).
+    pub fn leds(&self) -> Option<BoardLeds> {
+        self.gtrom_leds()
+    }
+
     pub fn prg_rom_size(&self) -> usize {
         self.prg_rom.len()
     }
@@ -881,6 +1011,11 @@ impl Cartridge {
             return self.chr_ram.get(chr_addr).copied().unwrap_or(0);
         }
 
+        if self.mapper == 111 {
+            let chr_addr = self.gtrom_nametable_offset(physical_nt, offset);
+            return self.chr_ram.get(chr_addr).copied().unwrap_or(0);
+        }
+
         internal[physical_nt & 1][offset]
     }
 
@@ -927,6 +1062,14 @@ impl Cartridge {
             return;
         }
 
+        if self.mapper == 111 {
+            let chr_addr = self.gtrom_nametable_offset(physical_nt, offset);
+            if let Some(slot) = self.chr_ram.get_mut(chr_addr) {
+                *slot = data;
+            }
+            return;
+        }
+
         let internal_nt = if self.mapper == 77 && physical_nt >= 2 {
             (physical_nt - 2) & 1
         } else {
@@ -948,7 +1091,7 @@ impl Cartridge {
             return Some(logical_nt & 3);
         }
 
-        if self.mapper == 99 {
+        if self.mapper == 99 || self.mapper == 111 {
             return Some(logical_nt & 3);
         }
 
@@ -1010,20 +1153,40 @@ impl Cartridge {
     }
 
     pub fn get_sram_data(&self) -> Option<&[u8]> {
-        if self.has_battery && !self.prg_ram.is_empty() && self.has_valid_save_data {
-            Some(&self.prg_ram)
+        let memory = self.save_memory();
+        if self.has_battery && !memory.is_empty() && self.has_valid_save_data {
+            Some(memory)
         } else {
             None
         }
     }
 
     pub fn set_sram_data(&mut self, data: Vec<u8>) {
-        if self.has_battery && data.len() == self.prg_ram.len() {
-            self.prg_ram = data;
+        if self.has_battery && data.len() == self.save_memory().len() {
+            if self.has_flash_prg() {
+                self.prg_rom = data;
+            } else {
+                self.prg_ram = data;
+            }
             self.has_valid_save_data = true;
         }
     }
 
+    /// Whether PRG is flash the game saves into (kept in the `.sav` file).
+    fn has_flash_prg(&self) -> bool {
+        self.unrom512.as_ref().is_some_and(|m| m.flashable) || self.gtrom.is_some()
+    }
+
+    /// What the `.sav` file holds: PRG-RAM, or all of PRG on boards that
+    /// save by reflashing it.
+    fn save_memory(&self) -> &[u8] {
+        if self.has_flash_prg() {
+            &self.prg_rom
+        } else {
+            &self.prg_ram
+        }
+    }
+
     /// Direct reference to PRG-RAM (returns None if empty).
     pub fn prg_ram_ref(&self) -> Option<&[u8]> {
         if self.prg_ram.is_empty() {
@@ -181,6 +181,22 @@ pub struct BandaiFcgState {
     pub outer_prg_bank: u8,
     #[serde(default)]
     pub prg_ram_enabled: bool,
+    #[serde(default)]
+    pub eeprom: Option<BandaiEepromState>,
+}
+
+/// Serial EEPROM (24C01/24C02) transfer state on Bandai FCG boards.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BandaiEepromState {
+    pub phase: u8,
+    pub phase_arg: u8,
+    pub phase_bit: u8,
+    pub address: u8,
+    pub shift: u8,
+    pub bits: u8,
+    pub prev_scl: bool,
+    pub prev_sda: bool,
+    pub data_out: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -521,7 +537,7 @@ pub struct Mapper189State {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Mapper185State {
-    pub disabled_reads: u8,
+    pub latch: u8,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -593,6 +609,23 @@ pub struct TaitoX1017State {
     pub chr_invert: bool,
 }
 
+/// UNROM 512 flash command progress. The flash contents travel in
+/// `CartridgeState::prg_ram`, like the SRAM of other boards.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Unrom512State {
+    pub flash_step: u8,
+    pub software_id: bool,
+}
+
+/// GTROM register and flash command progress; flash contents travel in
+/// `CartridgeState::prg_ram` as for UNROM 512.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GtromState {
+    pub register: u8,
+    pub flash_step: u8,
+    pub software_id: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CartridgeState {
     pub mapper: u8,
@@ -736,10 +769,33 @@ pub struct CartridgeState {
     pub mapper210: Option<Mapper210State>,
     #[serde(default)]
     pub vrc6: Option<Vrc6State>,
+    #[serde(default)]
+    pub unrom512: Option<Unrom512State>,
+    #[serde(default)]
+    pub gtrom: Option<GtromState>,
+}
+
+/// `src` copied into `buf`, keeping `buf`'s allocation.
+fn refill(mut buf: Vec<u8>, src: &[u8]) -> Vec<u8> {
+    buf.clear();
+    buf.extend_from_slice(src);
+    buf
 }
 
 impl Cartridge {
     pub fn snapshot_state(&self) -> CartridgeState {
+        self.snapshot_with(Vec::new(), Vec::new(), Vec::new())
+    }
+
+    /// Like `snapshot_state`, reusing the RAM buffers already in `state`.
+    pub fn snapshot_state_into(&self, state: &mut CartridgeState) {
+        let prg_ram = std::mem::take(&mut state.prg_ram);
+        let chr_ram = std::mem::take(&mut state.chr_ram);
+        let exram = state.mmc5.take().map(|m| m.exram).unwrap_or_default();
+        *state = self.snapshot_with(prg_ram, chr_ram, exram);
+    }
+
+    fn snapshot_with(&self, prg_ram: Vec<u8>, chr_ram: Vec<u8>, exram: Vec<u8>) -> CartridgeState {
         let mmc1 = self.mmc1.as_ref().map(|m| Mmc1State {
             shift_register: m.shift_register,
             shift_count: m.shift_count,
@@ -790,7 +846,7 @@ impl Cartridge {
             chr_upper: m.chr_upper,
             sprite_chr_banks: m.sprite_chr_banks,
             bg_chr_banks: m.bg_chr_banks,
-            exram: m.exram.clone(),
+            exram: refill(exram, &m.exram),
             irq_scanline_compare: m.irq_scanline_compare,
             irq_enabled: m.irq_enabled,
             irq_pending: m.irq_pending.get(),
@@ -903,6 +959,7 @@ impl Cartridge {
             irq_pending: b.irq_pending.get(),
             outer_prg_bank: b.outer_prg_bank,
             prg_ram_enabled: b.prg_ram_enabled,
+            eeprom: b.eeprom_state(),
         });
 
         let mapper34 = if self.mapper == 34 {
@@ -1264,7 +1321,7 @@ impl Cartridge {
         };
         let mapper185 = if self.mapper == 185 {
             Some(Mapper185State {
-                disabled_reads: self.mapper185_disabled_reads.get(),
+                latch: self.mapper185_latch,
             })
         } else {
             None
@@ -1318,6 +1375,15 @@ impl Cartridge {
             chr_banks: m.chr_banks,
             ram_enabled: m.ram_enabled,
         });
+        let unrom512 = self.unrom512.as_ref().map(|m| Unrom512State {
+            flash_step: m.flash.step,
+            software_id: m.flash.software_id,
+        });
+        let gtrom = self.gtrom.as_ref().map(|m| GtromState {
+            register: m.register,
+            flash_step: m.flash.step,
+            software_id: m.flash.software_id,
+        });
         let taito_x1017 = self.taito_x1017.as_ref().map(|m| TaitoX1017State {
             prg_banks: m.prg_banks,
             chr_banks: m.chr_banks,
@@ -1371,8 +1437,8 @@ impl Cartridge {
             mirroring: self.mirroring,
             prg_bank: self.get_prg_bank(),
             chr_bank: self.get_chr_bank(),
-            prg_ram: self.prg_ram.clone(),
-            chr_ram: self.chr_ram.clone(),
+            prg_ram: refill(prg_ram, self.save_memory()),
+            chr_ram: refill(chr_ram, &self.chr_ram),
             has_valid_save_data: self.has_valid_save_data,
             mmc1,
             mmc2,
@@ -1442,6 +1508,8 @@ impl Cartridge {
             mapper18,
             mapper210,
             vrc6,
+            unrom512,
+            gtrom,
         }
     }
 
@@ -1476,6 +1544,9 @@ impl Cartridge {
         if prg_len > 0 {
             self.prg_ram[..prg_len].copy_from_slice(&state.prg_ram[..prg_len]);
         }
+        if self.has_flash_prg() && state.prg_ram.len() == self.prg_rom.len() {
+            self.prg_rom.copy_from_slice(&state.prg_ram);
+        }
 
         let chr_len = self.chr_ram.len().min(state.chr_ram.len());
         if chr_len > 0 {
@@ -1651,6 +1722,9 @@ impl Cartridge {
             bandai.irq_pending.set(saved.irq_pending);
             bandai.outer_prg_bank = saved.outer_prg_bank;
             bandai.prg_ram_enabled = saved.prg_ram_enabled;
+            if let Some(ref eeprom) = saved.eeprom {
+                bandai.restore_eeprom_state(eeprom);
+            }
         }
 
         if let (Some(ref mut vrc1), Some(saved)) = (self.vrc1.as_mut(), state.vrc1.as_ref()) {
@@ -1860,7 +1934,7 @@ impl Cartridge {
             self.prg_bank = saved.prg_bank;
         }
         if let Some(saved) = state.mapper185.as_ref() {
-            self.mapper185_disabled_reads.set(saved.disabled_reads);
+            self.mapper185_latch = saved.latch;
         }
         if let Some(saved) = state.mapper236.as_ref() {
             self.mapper236_mode = saved.mode;
@@ -2005,5 +2079,14 @@ impl Cartridge {
             self.prg_bank = saved.prg_banks[0];
             self.chr_bank = saved.chr_banks[0];
         }
+        if let (Some(unrom512), Some(saved)) = (self.unrom512.as_mut(), state.unrom512.as_ref()) {
+            unrom512.flash.step = saved.flash_step;
+            unrom512.flash.software_id = saved.software_id;
+        }
+        if let (Some(gtrom), Some(saved)) = (self.gtrom.as_mut(), state.gtrom.as_ref()) {
+            gtrom.register = saved.register;
+            gtrom.flash.step = saved.flash_step;
+            gtrom.flash.software_id = saved.software_id;
+        }
     }
 }
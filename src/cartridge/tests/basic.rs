@@ -61,6 +61,82 @@ fn restore_state_ignores_mapper_mismatch() {
     assert_eq!(cart.prg_ram[0], 0x11);
 }
 
+#[test]
+fn mmc1_variant_boards_bank_prg_ram_through_chr_registers() {
+    fn write_mmc1(cart: &mut Cartridge, addr: u16, value: u8) {
+        for bit in 0..5 {
+            cart.write_prg(addr, (value >> bit) & 0x01);
+        }
+    }
+    // NES 2.0 MMC1 image; byte 10 holds the PRG-RAM/NVRAM size shifts.
+    fn sxrom_image(ram_sizes: u8, chr_banks: u8) -> Vec<u8> {
+        let mut rom = nrom_image(0x12, 0x08, &[]);
+        rom[5] = chr_banks;
+        rom[10] = ram_sizes;
+        rom
+    }
+
+    // SXROM: 32KB of PRG-RAM, CHR bank bits 2-3 pick the 8KB bank.
+    let mut cart = load_rom_image(&sxrom_image(0x09, 0)).unwrap();
+    assert_eq!(cart.prg_ram.len(), 0x8000);
+    for bank in 0..4u8 {
+        write_mmc1(&mut cart, 0xA000, bank << 2);
+        cart.write_prg_ram(0x6000, 0xA0 | bank);
+    }
+    write_mmc1(&mut cart, 0xA000, 2 << 2);
+    assert_eq!(cart.read_prg_ram(0x6000), 0xA2);
+    assert_eq!(cart.prg_ram[0x6000], 0xA3);
+
+    // SOROM (CHR-RAM) uses bit 3, SZROM (CHR-ROM) uses bit 4.
+    let mut cart = load_rom_image(&sxrom_image(0x77, 0)).unwrap();
+    write_mmc1(&mut cart, 0xA000, 0x08);
+    cart.write_prg_ram(0x6001, 0x5B);
+    assert_eq!(cart.prg_ram[0x2001], 0x5B);
+    let mut cart = load_rom_image(&sxrom_image(0x77, 1)).unwrap();
+    write_mmc1(&mut cart, 0xA000, 0x10);
+    cart.write_prg_ram(0x6001, 0x5C);
+    assert_eq!(cart.prg_ram[0x2001], 0x5C);
+
+    // Plain iNES keeps the single fixed 8KB bank.
+    let mut cart = load_rom_image(&nrom_image(0x12, 0x00, &[])).unwrap();
+    assert_eq!(cart.prg_ram.len(), 0x2000);
+    write_mmc1(&mut cart, 0xA000, 0x1C);
+    cart.write_prg_ram(0x6000, 0x42);
+    assert_eq!(cart.prg_ram[0], 0x42);
+}
+
+#[test]
+fn bus_conflicts_follow_the_board_submapper_and_override() {
+    // NES 2.0 UOROM: 16 banks of 16KB, each filled with its bank number.
+    fn uorom_image(submapper: u8) -> Vec<u8> {
+        let mut rom = vec![b'N', b'E', b'S', 0x1A, 16, 0, 0x20, 0x08, submapper << 4];
+        rom.resize(16, 0);
+        for bank in 0..16u8 {
+            rom.resize(rom.len() + 0x4000, bank);
+        }
+        rom
+    }
+
+    // Submapper 2: the write is ANDed with bank 0's $00 byte.
+    let mut cart = load_rom_image(&uorom_image(2)).unwrap();
+    assert!(cart.bus_conflicts());
+    cart.write_prg(0x8000, 0x0C);
+    assert_eq!(cart.read_prg(0x8000), 0);
+
+    // Submapper 1: no conflicts, and all four UOROM bank bits decode.
+    let mut cart = load_rom_image(&uorom_image(1)).unwrap();
+    assert!(!cart.bus_conflicts());
+    cart.write_prg(0x8000, 0x0C);
+    assert_eq!(cart.read_prg(0x8000), 12);
+
+    // The override beats the board: $05 & $0C selects bank 4.
+    cart.set_forced_bus_conflicts(Some(true));
+    cart.write_prg(0x8000, 0x05);
+    assert_eq!(cart.read_prg(0x8000), 4);
+    cart.set_forced_bus_conflicts(None);
+    assert!(!cart.bus_conflicts());
+}
+
 #[test]
 fn mapper_5_switches_prg_chr_wram_and_multiplier() {
     let mut cart = make_mmc5_cart();
@@ -953,3 +1029,228 @@ fn mapper_15_handles_all_prg_modes_and_prg_ram() {
     cart.write_prg_ram(0x6000, 0xA5);
     assert_eq!(cart.read_prg_ram(0x6000), 0xA5);
 }
+
+fn nrom_image(flags6: u8, flags7: u8, extra: &[u8]) -> Vec<u8> {
+    let mut rom = vec![b'N', b'E', b'S', 0x1A, 2, 1, flags6, flags7];
+    rom.resize(16, 0);
+    if flags6 & 0x04 != 0 {
+        rom.resize(16 + 512, 0xEE);
+    }
+    let prg_start = rom.len();
+    rom.resize(prg_start + 0x8000, 0x11);
+    rom[prg_start + 0x7FFC] = 0x00;
+    rom[prg_start + 0x7FFD] = 0x80;
+    rom.resize(prg_start + 0x8000 + 0x2000, 0x22);
+    rom.extend_from_slice(extra);
+    rom
+}
+
+#[test]
+fn playchoice10_inst_rom_is_split_from_game_data() {
+    let mut extra = vec![0x33; 0x2000];
+    extra.extend_from_slice(&[0x44; 32]);
+    let cart = load_rom_image(&nrom_image(0, 0x02, &extra)).unwrap();
+
+    assert_eq!(cart.prg_rom_size(), 0x8000);
+    assert_eq!(cart.chr_rom_size(), 0x2000);
+    assert_eq!(cart.read_prg(0xFFFC), 0x00);
+    assert_eq!(cart.read_prg(0xFFFD), 0x80);
+    assert_eq!(cart.read_chr(0x1FFF), 0x22);
+    let inst_rom = cart.playchoice_inst_rom().unwrap();
+    assert_eq!(inst_rom.len(), 0x2000);
+    assert!(inst_rom.iter().all(|&b| b == 0x33));
+}
+
+#[test]
+fn playchoice10_inst_rom_detected_without_header_flag() {
+    let extra = vec![0x33; 0x2000 + 32];
+    let cart = load_rom_image(&nrom_image(0, 0, &extra)).unwrap();
+    assert!(cart.playchoice_inst_rom().is_some());
+
+    let cart = load_rom_image(&nrom_image(0, 0, &[])).unwrap();
+    assert!(cart.playchoice_inst_rom().is_none());
+}
+
+#[test]
+fn loader_skips_trainer_and_rejects_truncated_images() {
+    let cart = load_rom_image(&nrom_image(0x04, 0, &[])).unwrap();
+    assert_eq!(cart.read_prg(0x8000), 0x11);
+    assert_eq!(cart.read_prg(0xFFFD), 0x80);
+
+    let mut truncated = nrom_image(0, 0, &[]);
+    truncated.truncate(16 + 0x4000);
+    let err = load_rom_image(&truncated).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn loader_mirrors_small_and_odd_rom_sizes() {
+    // NES 2.0 exponent-multiplier sizes: 2^13 = 8KB PRG, 2^12 = 4KB CHR.
+    let mut rom = vec![b'N', b'E', b'S', 0x1A, 13 << 2, 12 << 2, 0, 0x08, 0, 0xFF];
+    rom.resize(16, 0);
+    rom.extend((0..0x2000).map(|i| (i >> 8) as u8));
+    rom.extend((0..0x1000).map(|i| (i >> 4) as u8));
+    rom.extend_from_slice(&[0xEE; 100]);
+    let header = InesHeader::parse(&rom).unwrap();
+    assert_eq!((header.prg_rom_size, header.chr_rom_size), (0x2000, 0x1000));
+
+    let cart = load_rom_image(&rom).unwrap();
+    for base in [0x8000, 0xA000, 0xC000, 0xE000] {
+        assert_eq!(cart.read_prg(base + 0x1F00), 0x1F);
+    }
+    assert_eq!(cart.read_chr(0x1230), cart.read_chr(0x0230));
+    assert_eq!(cart.read_chr(0x1230), 0x23);
+
+    let mut empty = nrom_image(0, 0, &[]);
+    empty[4] = 0;
+    assert!(load_rom_image(&empty).is_err());
+}
+
+#[test]
+fn loader_reads_region_from_header() {
+    let cart = load_rom_image(&nrom_image(0, 0, &[])).unwrap();
+    assert_eq!(cart.region(), Region::Ntsc);
+
+    let mut nes2 = nrom_image(0, 0x08, &[]);
+    nes2[12] = 0x01;
+    let cart = load_rom_image(&nes2).unwrap();
+    assert_eq!(cart.region(), Region::Pal);
+
+    let mut ines = nrom_image(0, 0, &[]);
+    ines[9] = 0x01;
+    let cart = load_rom_image(&ines).unwrap();
+    assert_eq!(cart.region(), Region::Pal);
+
+    // Junk in bytes 12-15 means byte 9 can't be trusted either
+    ines[12..16].copy_from_slice(b"Dude");
+    let cart = load_rom_image(&ines).unwrap();
+    assert_eq!(cart.region(), Region::Ntsc);
+}
+
+#[test]
+fn rom_crc32_covers_prg_and_chr_only() {
+    let plain = nrom_image(0, 0, &[]);
+    let cart = load_rom_image(&plain).unwrap();
+    assert_eq!(cart.rom_crc32(), crate::crc32::crc32(&plain[16..]));
+
+    // Header flags and a trainer don't change the hash
+    let mut trained = nrom_image(0x04, 0, &[]);
+    trained[9] = 0x01;
+    let cart = load_rom_image(&trained).unwrap();
+    assert_eq!(cart.rom_crc32(), crate::crc32::crc32(&plain[16..]));
+}
+
+fn unif_image(board: &str, chunks: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+    let mut rom = b"UNIF".to_vec();
+    rom.extend_from_slice(&7u32.to_le_bytes());
+    rom.resize(32, 0);
+    let mut board = board.as_bytes().to_vec();
+    board.push(0);
+    for (id, body) in std::iter::once((b"MAPR", board)).chain(chunks.iter().cloned()) {
+        rom.extend_from_slice(id);
+        rom.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        rom.extend_from_slice(&body);
+    }
+    rom
+}
+
+#[test]
+fn unif_boards_load_as_their_ines_mapper() {
+    let mut prg = vec![0x11; 0x4000];
+    prg[0x3FFC..].copy_from_slice(&[0x00, 0xC0, 0x00, 0xC0]);
+    let rom = unif_image(
+        "NES-TLROM",
+        &[
+            (b"PRG0", prg.clone()),
+            (b"PRG1", vec![0x22; 0x4000]),
+            (b"CHR0", vec![0x33; 0x2000]),
+            (b"MIRR", vec![1]),
+            (b"BATR", vec![1]),
+        ],
+    );
+    let cart = load_rom_image(&rom).unwrap();
+    assert_eq!(cart.mapper_number(), 4);
+    assert_eq!(cart.prg_rom_size(), 0x8000);
+    assert_eq!(cart.chr_rom_size(), 0x2000);
+    assert!(cart.has_battery_save());
+
+    // 8KB of NROM PRG is mirrored up to 16KB
+    let rom = unif_image("UNL-NROM", &[(b"PRG0", prg[0x2000..].to_vec())]);
+    let cart = load_rom_image(&rom).unwrap();
+    assert_eq!(cart.prg_rom_size(), 0x4000);
+    assert_eq!(cart.read_prg(0xFFFD), 0xC0);
+    assert_eq!(cart.read_prg(0xDFFD), 0xC0);
+
+    let rom = unif_image("UNL-KS7032-ish", &[(b"PRG0", prg)]);
+    let err = load_rom_image(&rom).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("'UNL-KS7032-ish'"));
+    assert_eq!(unif_board_mapper("BMC-70in1B"), Some(236));
+}
+
+#[test]
+fn ines_header_decodes_nes2_fields() {
+    let mut rom = nrom_image(0x47, 0xA8, &[]);
+    rom[8] = 0x30;
+    rom[12] = 0x01;
+    let header = InesHeader::parse(&rom).unwrap();
+    assert_eq!(header.mapper, 0xA4);
+    assert_eq!(header.submapper, 3);
+    assert!(header.is_nes2 && header.battery && header.vertical_mirroring);
+    assert_eq!(header.region, Region::Pal);
+    assert_eq!(header.prg_rom_start(), 16 + 512);
+    assert!(InesHeader::parse(b"NES").is_err());
+}
+
+#[test]
+fn ines_header_reads_vs_system_from_console_type() {
+    let header = |flags7| InesHeader::parse(&nrom_image(0, flags7, &[])).unwrap();
+    assert!(header(0x01).vs_system && header(0x09).vs_system);
+    assert!(!header(0x08).vs_system && !header(0x0A).vs_system);
+
+    // Console type 3 is an extended console, neither Vs. nor PlayChoice
+    let extended = header(0x0B);
+    assert!(!extended.vs_system && !extended.playchoice10);
+}
+
+#[test]
+fn hostile_nes2_sizes_are_rejected_not_overflowed() {
+    // Exponent-multiplier PRG size 2^63 * 7
+    let mut rom = nrom_image(0x00, 0x08, &[]);
+    rom[4] = 0xFF;
+    rom[9] = 0x0F;
+    let err = InesHeader::parse(&rom).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    let err = load_rom_image(&rom).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    // 2^63 bytes each of PRG and CHR: representable, but not their sum
+    rom[4] = 0xFC;
+    rom[5] = 0xFC;
+    rom[9] = 0xFF;
+    assert!(InesHeader::parse(&rom).is_ok());
+    let err = load_rom_image(&rom).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn supported_mappers_are_the_ones_that_map_prg() {
+    for mapper in 0..=255u8 {
+        // 228 needs a whole 512KB chip before it maps anything
+        if mapper == 228 {
+            continue;
+        }
+        let mut rom = vec![b'N', b'E', b'S', 0x1A, 2, 1, mapper << 4, mapper & 0xF0];
+        rom.resize(16, 0);
+        rom.extend(vec![0xEA; 0x8000]);
+        rom.extend(vec![0; 0x2000]);
+        let cart = load_rom_image(&rom).unwrap();
+        let maps_prg = (0x8000..=0xFFFF).any(|addr| cart.read_prg(addr) != 0);
+        assert_eq!(
+            maps_prg,
+            Cartridge::is_supported_mapper(mapper),
+            "mapper {}",
+            mapper
+        );
+    }
+}
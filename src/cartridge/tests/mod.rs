@@ -1,5 +1,5 @@
 use super::*;
-use std::cell::Cell;
+use crate::test_support::load_rom_image;
 
 fn base_cartridge(
     mapper: u8,
@@ -72,7 +72,8 @@ fn base_cartridge(
         mapper208_protection_index: 0,
         mapper208_protection_regs: [0; 4],
         mapper189_prg_bank: 0,
-        mapper185_disabled_reads: Cell::new(0),
+        mapper185_latch: 0,
+        mapper185_submapper: 0,
         mmc1: None,
         mmc2: None,
         mmc3: None,
@@ -98,11 +99,19 @@ fn base_cartridge(
         taito_tc0190: None,
         taito_x1005: None,
         taito_x1017: None,
+        unrom512: None,
+        gtrom: None,
         mapper227_latch: 0,
         mapper246: None,
         mapper236_mode: 0,
         mapper236_outer_bank: 0,
         mapper236_chr_ram: false,
+        vs_system: None,
+        playchoice_inst_rom: None,
+        region: Region::Ntsc,
+        rom_crc32: 0,
+        bus_conflicts: true,
+        forced_bus_conflicts: None,
     }
 }
 
@@ -609,6 +618,27 @@ fn make_mapper159_cart() -> Cartridge {
     cart
 }
 
+fn make_mapper157_cart() -> Cartridge {
+    let mut prg_rom = vec![0; 16 * 0x4000];
+    for bank in 0..16 {
+        prg_rom[bank * 0x4000..(bank + 1) * 0x4000].fill(bank as u8);
+    }
+
+    let mut cart = base_cartridge(
+        157,
+        prg_rom,
+        vec![],
+        vec![0; 0x2000],
+        vec![0xFF; 0x100],
+        Mirroring::Vertical,
+    );
+    cart.bandai_fcg = Some(BandaiFcg::new());
+    if let Some(ref mut bandai) = cart.bandai_fcg {
+        bandai.configure_mapper(157, false);
+    }
+    cart
+}
+
 fn make_mapper57_cart() -> Cartridge {
     let mut prg_rom = vec![0; 8 * 0x4000];
     for bank in 0..8 {
@@ -1009,6 +1039,45 @@ fn make_mapper246_cart() -> Cartridge {
     cart
 }
 
+fn make_unrom512_cart(flashable: bool) -> Cartridge {
+    let mut prg_rom = vec![0; 32 * 0x4000];
+    for bank in 0..32 {
+        prg_rom[bank * 0x4000..(bank + 1) * 0x4000].fill(bank as u8);
+    }
+
+    let mut cart = base_cartridge(
+        30,
+        prg_rom,
+        vec![],
+        vec![0; 0x8000],
+        vec![],
+        Mirroring::OneScreenLower,
+    );
+    cart.has_battery = flashable;
+    cart.bus_conflicts = !flashable;
+    cart.unrom512 = Some(Unrom512::new(flashable, true));
+    cart
+}
+
+fn make_gtrom_cart() -> Cartridge {
+    let mut prg_rom = vec![0; 16 * 0x8000];
+    for bank in 0..16 {
+        prg_rom[bank * 0x8000..(bank + 1) * 0x8000].fill(bank as u8);
+    }
+
+    let mut cart = base_cartridge(
+        111,
+        prg_rom,
+        vec![],
+        vec![0; 0x8000],
+        vec![],
+        Mirroring::FourScreen,
+    );
+    cart.has_battery = true;
+    cart.gtrom = Some(Gtrom::new());
+    cart
+}
+
 fn make_mapper236_cart(chr_ram_variant: bool) -> Cartridge {
     let prg_bank_count = if chr_ram_variant { 64 } else { 16 };
     let mut prg_rom = vec![0; prg_bank_count * 0x4000];
@@ -1293,16 +1362,14 @@ fn make_mapper185_cart() -> Cartridge {
         chr_rom[bank * 0x2000..(bank + 1) * 0x2000].fill(0x60 | bank as u8);
     }
 
-    let cart = base_cartridge(
+    base_cartridge(
         185,
         vec![0xFF; 0x8000],
         chr_rom,
         vec![],
         vec![],
         Mirroring::Vertical,
-    );
-    cart.mapper185_disabled_reads.set(2);
-    cart
+    )
 }
 
 fn make_mapper189_cart() -> Cartridge {
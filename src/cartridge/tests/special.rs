@@ -758,24 +758,40 @@ fn mapper_67_switches_chr_prg_mirroring_and_cycle_irq() {
 }
 
 #[test]
-fn mapper_185_disables_chr_for_initial_probe_reads_after_reset() {
+fn mapper_185_enables_chr_only_for_matching_latch_values() {
     let mut cart = make_mapper185_cart();
 
+    // Power-on latch is 0: CHR disabled, bus floats high
+    assert_eq!(cart.read_chr(0x0000), 0xFF);
+
     cart.write_prg(0x8000, 0x02);
-    assert_eq!(cart.read_chr(0x0000), 0);
+    assert_eq!(cart.read_chr(0x0000), 0x62);
 
     let snapshot = cart.snapshot_state();
-    assert_eq!(cart.read_chr(0x0000), 0);
-    assert_eq!(cart.read_chr(0x0000), 0x62);
+    cart.write_prg(0x8000, 0x13);
+    assert_eq!(cart.read_chr(0x0000), 0xFF);
+    cart.write_prg(0x8000, 0x00);
+    assert_eq!(cart.read_chr(0x0000), 0xFF);
 
     cart.restore_state(&snapshot);
-    assert_eq!(cart.read_chr(0x0000), 0);
     assert_eq!(cart.read_chr(0x0000), 0x62);
+}
 
-    cart.on_reset();
-    assert_eq!(cart.read_chr(0x0000), 0);
-    assert_eq!(cart.read_chr(0x0000), 0);
-    assert_eq!(cart.read_chr(0x0000), 0x62);
+#[test]
+fn mapper_185_submapper_selects_enabling_value() {
+    let mut cart = make_mapper185_cart();
+    cart.mapper185_submapper = 4;
+
+    cart.write_prg(0x8000, 0x00);
+    assert_eq!(cart.read_chr(0x0000), 0x60);
+    cart.write_prg(0x8000, 0x01);
+    assert_eq!(cart.read_chr(0x0000), 0xFF);
+
+    cart.mapper185_submapper = 7;
+    cart.write_prg(0x8000, 0x03);
+    assert_eq!(cart.read_chr(0x0000), 0x63);
+    cart.write_prg(0x8000, 0x02);
+    assert_eq!(cart.read_chr(0x0000), 0xFF);
 }
 
 #[test]
@@ -1164,6 +1180,30 @@ fn mapper_159_uses_x24c01_eeprom_and_bandai_banks() {
     assert!(cart.irq_pending());
 }
 
+#[test]
+fn mapper_157_streams_datach_barcode_on_prg_ram_bit_3() {
+    let mut cart = make_mapper157_cart();
+
+    cart.write_prg(0x8008, 0x03);
+    assert_eq!(cart.read_prg(0x8000), 3);
+    assert_eq!(cart.read_prg(0xC000), 15);
+
+    cart.write_chr(0x0123, 0x5A);
+    assert_eq!(cart.read_chr(0x0123), 0x5A);
+
+    // Idle reader: no barcode bit, EEPROM line released high
+    assert_eq!(cart.read_prg_ram(0x6000) & 0x18, 0x10);
+
+    assert!(cart.datach_swipe_barcode("12").is_err());
+    cart.datach_swipe_barcode("4901234567894").unwrap();
+    assert_eq!(cart.read_prg_ram(0x6000) & 0x08, 0x08);
+    // Start guard bar follows the 33-module quiet zone
+    cart.clock_irq_counter_cycles(33 * 1000);
+    assert_eq!(cart.read_prg_ram(0x6000) & 0x08, 0x00);
+    cart.clock_irq_counter_cycles(1000);
+    assert_eq!(cart.read_prg_ram(0x6000) & 0x08, 0x08);
+}
+
 #[test]
 fn mapper_37_selects_prg_and_chr_windows_from_prg_ram_latch() {
     let mut cart = make_mmc3_mixed_chr_cart(37, 32, 256, 0);
@@ -2267,3 +2307,181 @@ fn mapper_44_switches_outer_prg_chr_windows() {
     assert_eq!(cart.read_chr(0x1000), 0xEA);
     assert_eq!(cart.mirroring(), Mirroring::Horizontal);
 }
+
+#[test]
+fn mapper_30_banks_prg_chr_ram_and_one_screen_mirroring() {
+    let mut cart = make_unrom512_cart(false);
+
+    // Bus conflicts: the latch sees the written value ANDed with ROM ($1F)
+    cart.write_prg(0xFFF0, 0xE5);
+    assert_eq!(cart.read_prg(0x8000), 5);
+    assert_eq!(cart.read_prg(0xC000), 31);
+    assert_eq!(cart.mirroring(), Mirroring::OneScreenLower);
+
+    cart.bus_conflicts = false;
+    cart.write_prg(0xC000, 0xC2);
+    assert_eq!(cart.prg_bank, 2);
+    assert_eq!(cart.mirroring(), Mirroring::OneScreenUpper);
+
+    // CHR-RAM bank 2 of 4
+    cart.write_chr(0x0010, 0x5A);
+    assert_eq!(cart.chr_ram[2 * 0x2000 + 0x10], 0x5A);
+    cart.write_prg(0xC000, 0x00);
+    assert_eq!(cart.read_chr(0x0010), 0x00);
+}
+
+#[test]
+fn mapper_30_flash_programs_erases_and_saves() {
+    let mut cart = make_unrom512_cart(true);
+    // Commands go to flash address $5555 (bank 1) and $2AAA (bank 0)
+    let command = |cart: &mut Cartridge, flash_addr: usize, data: u8| {
+        cart.write_prg(0xC000, (flash_addr >> 14) as u8);
+        cart.write_prg(0x8000 | (flash_addr as u16 & 0x3FFF), data);
+    };
+    let unlock = |cart: &mut Cartridge| {
+        command(cart, 0x5555, 0xAA);
+        command(cart, 0x2AAA, 0x55);
+    };
+
+    assert!(cart.get_sram_data().is_none());
+    unlock(&mut cart);
+    command(&mut cart, 0x5555, 0x90);
+    assert_eq!(cart.read_prg(0x8000), 0xBF);
+    assert_eq!(cart.read_prg(0x8001), 0xB7);
+    command(&mut cart, 0x0000, 0xF0);
+    assert_eq!(cart.read_prg(0x8000), 0);
+
+    // Erase the first 4KB sector of bank 3, then program a byte in it
+    unlock(&mut cart);
+    command(&mut cart, 0x5555, 0x80);
+    unlock(&mut cart);
+    command(&mut cart, 3 * 0x4000 + 0x0123, 0x30);
+    assert_eq!(cart.prg_rom[3 * 0x4000], 0xFF);
+    assert_eq!(cart.prg_rom[3 * 0x4000 + 0x0FFF], 0xFF);
+    assert_eq!(cart.prg_rom[3 * 0x4000 + 0x1000], 3);
+
+    let before_program = cart.snapshot_state();
+    unlock(&mut cart);
+    command(&mut cart, 0x5555, 0xA0);
+    command(&mut cart, 3 * 0x4000 + 0x0010, 0x3C);
+    cart.write_prg(0xC000, 3);
+    assert_eq!(cart.read_prg(0x8010), 0x3C);
+    // A write outside a command sequence changes nothing
+    cart.write_prg(0x8011, 0x00);
+    assert_eq!(cart.read_prg(0x8011), 0xFF);
+
+    // The whole flash is the save, and states carry it
+    let save = cart.get_sram_data().unwrap().to_vec();
+    assert_eq!(save.len(), 32 * 0x4000);
+    assert_eq!(save[3 * 0x4000 + 0x10], 0x3C);
+    cart.restore_state(&before_program);
+    assert_eq!(cart.prg_rom[3 * 0x4000 + 0x10], 0xFF);
+    cart.set_sram_data(save);
+    assert_eq!(cart.prg_rom[3 * 0x4000 + 0x10], 0x3C);
+}
+
+#[test]
+fn mapper_111_banks_memory_and_drives_leds() {
+    let mut cart = make_gtrom_cart();
+    let mut internal = [[0u8; 1024]; 2];
+    assert_eq!(
+        cart.leds(),
+        Some(BoardLeds {
+            red: true,
+            green: true
+        })
+    );
+
+    // $5000 and $7000 both reach the register: PRG bank 5, CHR bank 1,
+    // nametable page 1, green LED off
+    cart.write_prg(0x5000, 0x75);
+    assert_eq!(cart.read_prg(0x8000), 5);
+    assert_eq!(cart.read_prg(0xFFFF), 5);
+    cart.write_prg_ram(0x7FFF, 0x7A);
+    assert_eq!(cart.read_prg(0x8000), 10);
+    assert_eq!(
+        cart.leds(),
+        Some(BoardLeds {
+            red: true,
+            green: false
+        })
+    );
+
+    cart.write_chr(0x0010, 0x5A);
+    assert_eq!(cart.chr_ram[0x2010], 0x5A);
+    assert_eq!(cart.resolve_nametable(3), Some(3));
+    cart.write_nametable_byte(3, 0x20, &mut internal, 0xA5);
+    assert_eq!(cart.chr_ram[0x6000 + 0x0C20], 0xA5);
+
+    cart.write_prg(0x5000, 0x80);
+    assert_eq!(cart.read_chr(0x0010), 0);
+    assert_eq!(cart.read_nametable_byte(3, 0x20, &internal), 0);
+    assert_eq!(
+        cart.leds(),
+        Some(BoardLeds {
+            red: false,
+            green: true
+        })
+    );
+}
+
+#[test]
+fn mapper_111_flash_programs_through_the_prg_bank() {
+    let mut cart = make_gtrom_cart();
+    // $5555 and $2AAA sit in bank 0 at $D555 and $AAAA
+    let unlock = |cart: &mut Cartridge| {
+        cart.write_prg(0x5000, 0x00);
+        cart.write_prg(0xD555, 0xAA);
+        cart.write_prg(0xAAAA, 0x55);
+    };
+
+    unlock(&mut cart);
+    cart.write_prg(0xD555, 0x80);
+    unlock(&mut cart);
+    cart.write_prg(0x5000, 0x02);
+    cart.write_prg(0x9000, 0x30);
+    assert_eq!(cart.prg_rom[2 * 0x8000 + 0x1000], 0xFF);
+    assert_eq!(cart.prg_rom[2 * 0x8000 + 0x2000], 2);
+
+    unlock(&mut cart);
+    cart.write_prg(0xD555, 0xA0);
+    cart.write_prg(0x5000, 0x02);
+    cart.write_prg(0x9001, 0x42);
+    assert_eq!(cart.read_prg(0x9001), 0x42);
+
+    let save = cart.get_sram_data().unwrap();
+    assert_eq!(save.len(), 16 * 0x8000);
+    assert_eq!(save[2 * 0x8000 + 0x1001], 0x42);
+    let state = cart.snapshot_state();
+    cart.write_prg(0x5000, 0x00);
+    cart.restore_state(&state);
+    assert_eq!(cart.read_prg(0x8000), 2);
+}
+
+#[test]
+fn mapper_218_fetches_patterns_from_ciram() {
+    let mut cart = base_cartridge(
+        218,
+        vec![0; 0x8000],
+        vec![],
+        vec![],
+        vec![],
+        Mirroring::Vertical,
+    );
+    let mut ppu = crate::ppu::Ppu::new();
+
+    // A CHR write through $2007 lands in CIRAM page A10 (vertical wiring)
+    ppu.write_register(0x2006, 0x04, Some(&mut cart));
+    ppu.write_register(0x2006, 0x10, Some(&mut cart));
+    ppu.write_register(0x2007, 0x5A, Some(&mut cart));
+    assert_eq!(ppu.nametable[1][0x010], 0x5A);
+    assert_eq!(ppu.read_pattern(&cart, 0x0410, None), 0x5A);
+    assert_eq!(ppu.read_pattern(&cart, 0x1C10, Some(0)), 0x5A);
+
+    // Horizontal wiring uses A11; one-screen ties A10
+    cart.mirroring = Mirroring::Horizontal;
+    assert_eq!(ppu.read_pattern(&cart, 0x0410, None), 0x00);
+    assert_eq!(ppu.read_pattern(&cart, 0x0810, None), 0x5A);
+    cart.mirroring = Mirroring::OneScreenUpper;
+    assert_eq!(ppu.read_pattern(&cart, 0x0010, None), 0x5A);
+}
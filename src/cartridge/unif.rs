@@ -0,0 +1,155 @@
+// UNIF images are turned into an equivalent iNES image so the regular
+// loader sets up the board. Only boards whose iNES mapper is emulated are
+// listed; anything else is rejected with its board string.
+use std::io::{Error, ErrorKind, Result};
+
+const HEADER_SIZE: usize = 32;
+const PRG_UNIT: usize = 0x4000;
+const CHR_UNIT: usize = 0x2000;
+
+/// Prefixes UNIF dumps put in front of the board name.
+const BOARD_PREFIXES: [&str; 6] = ["NES-", "UNL-", "HVC-", "BTL-", "BMC-", "IREM-"];
+
+/// UNIF board name (without prefix) to iNES mapper.
+#[rustfmt::skip]
+const BOARDS: &[(&str, u8)] = &[
+    ("NROM", 0), ("NROM-128", 0), ("NROM-256", 0), ("RROM", 0), ("RROM-128", 0),
+    ("SAROM", 1), ("SBROM", 1), ("SCROM", 1), ("SEROM", 1), ("SFROM", 1), ("SGROM", 1),
+    ("SHROM", 1), ("SJROM", 1), ("SKROM", 1), ("SLROM", 1), ("SL1ROM", 1), ("SL2ROM", 1),
+    ("SL3ROM", 1), ("SLRROM", 1), ("SNROM", 1), ("SOROM", 1), ("SUROM", 1), ("SXROM", 1),
+    ("UNROM", 2), ("UOROM", 2),
+    ("CNROM", 3),
+    ("HKROM", 4), ("TBROM", 4), ("TEROM", 4), ("TFROM", 4), ("TGROM", 4), ("TKROM", 4),
+    ("TLROM", 4), ("TL1ROM", 4), ("TL2ROM", 4), ("TNROM", 4), ("TR1ROM", 4), ("TSROM", 4),
+    ("TVROM", 4),
+    ("EKROM", 5), ("ELROM", 5), ("ETROM", 5), ("EWROM", 5),
+    ("AMROM", 7), ("ANROM", 7), ("AN1ROM", 7), ("AOROM", 7),
+    ("PNROM", 9), ("PEEOROM", 9),
+    ("FJROM", 10), ("FKROM", 10),
+    ("CPROM", 13),
+    ("BNROM", 34), ("NINA-001", 34),
+    ("AC08", 42),
+    ("GK-192", 58),
+    ("D1038", 59),
+    ("GNROM", 66), ("MHROM", 66),
+    ("NTBROM", 68),
+    ("BTR", 69), ("JLROM", 69), ("JSROM", 69),
+    ("SUNSOFT_UNROM", 93),
+    ("TKSROM", 118), ("TLSROM", 118),
+    ("TQROM", 119),
+    ("H2288", 123),
+    ("SA-72008", 133),
+    ("Sachen-8259D", 137),
+    ("SA-72007", 145),
+    ("SA-016-1M", 146),
+    ("TC-U01-1.5M", 147),
+    ("SA-0037", 148),
+    ("Sachen-74LS374N", 150),
+    ("NovelDiamond9999999in1", 201),
+    ("N625092", 221),
+    ("42in1ResetSwitch", 226), ("Ghostbusters63in1", 226),
+    ("43272", 227),
+    ("70in1", 236), ("70in1B", 236),
+];
+
+fn invalid(message: String) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// iNES mapper for a UNIF board string, e.g. `NES-TLROM` or `UNL-H2288`.
+pub fn unif_board_mapper(board: &str) -> Option<u8> {
+    let name = BOARD_PREFIXES
+        .iter()
+        .find_map(|prefix| board.strip_prefix(prefix))
+        .unwrap_or(board);
+    BOARDS
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(name))
+        .map(|&(_, mapper)| mapper)
+}
+
+/// Convert a UNIF file (`UNIF` magic) into an iNES image.
+pub fn unif_to_ines(data: &[u8]) -> Result<Vec<u8>> {
+    if data.len() < HEADER_SIZE || &data[..4] != b"UNIF" {
+        return Err(invalid("Invalid UNIF file format".to_string()));
+    }
+
+    let mut board = None;
+    let mut prg_chunks: [&[u8]; 16] = [&[]; 16];
+    let mut chr_chunks: [&[u8]; 16] = [&[]; 16];
+    let mut mirroring = 0u8;
+    let mut battery = false;
+    let mut pal = false;
+
+    let mut pos = HEADER_SIZE;
+    while pos + 8 <= data.len() {
+        let id = &data[pos..pos + 4];
+        let len = u32::from_le_bytes(data[pos + 4..pos + 8].try_into().unwrap()) as usize;
+        let body = data.get(pos + 8..pos + 8 + len).ok_or_else(|| {
+            invalid(format!(
+                "Truncated UNIF chunk {}",
+                String::from_utf8_lossy(id)
+            ))
+        })?;
+        let bank = (id[3] as char).to_digit(16).unwrap_or(0) as usize;
+        match &id[..3] {
+            b"MAP" if id[3] == b'R' => {
+                let end = body.iter().position(|&b| b == 0).unwrap_or(body.len());
+                board = Some(String::from_utf8_lossy(&body[..end]).trim().to_string());
+            }
+            b"PRG" => prg_chunks[bank] = body,
+            b"CHR" => chr_chunks[bank] = body,
+            b"MIR" if id[3] == b'R' => mirroring = body.first().copied().unwrap_or(0),
+            b"BAT" if id[3] == b'R' => battery = body.first().is_none_or(|&b| b != 0),
+            b"TVC" if id[3] == b'I' => pal = body.first() == Some(&1),
+            _ => {}
+        }
+        pos += 8 + len;
+    }
+
+    let board = board.ok_or_else(|| invalid("UNIF file has no MAPR board chunk".to_string()))?;
+    let mapper = unif_board_mapper(&board)
+        .ok_or_else(|| invalid(format!("Unsupported UNIF board '{}'", board)))?;
+
+    let mut prg: Vec<u8> = prg_chunks.concat();
+    let mut chr: Vec<u8> = chr_chunks.concat();
+    if prg.is_empty() {
+        return Err(invalid(format!("UNIF board '{}' has no PRG data", board)));
+    }
+    // 8KB NROM boards mirror their PRG; other sizes are padded to 16KB units
+    while prg.len() < PRG_UNIT {
+        prg.extend_from_within(..);
+    }
+    prg.resize(prg.len().div_ceil(PRG_UNIT) * PRG_UNIT, 0);
+    chr.resize(chr.len().div_ceil(CHR_UNIT) * CHR_UNIT, 0);
+    let prg_banks = prg.len() / PRG_UNIT;
+    let chr_banks = chr.len() / CHR_UNIT;
+    if prg_banks > 0xFF || chr_banks > 0xFF {
+        return Err(invalid(format!("UNIF board '{}' is too large", board)));
+    }
+
+    // MIRR: 0 horizontal, 1 vertical, 4 four-screen; one-screen and
+    // mapper-controlled boards set it themselves
+    let mut flags6 = (mapper << 4) | if battery { 0x02 } else { 0 };
+    match mirroring {
+        1 => flags6 |= 0x01,
+        4 => flags6 |= 0x08,
+        _ => {}
+    }
+    let mut image = vec![
+        b'N',
+        b'E',
+        b'S',
+        0x1A,
+        prg_banks as u8,
+        chr_banks as u8,
+        flags6,
+        mapper & 0xF0,
+        0,
+        pal as u8,
+    ];
+    image.resize(16, 0);
+    image.extend_from_slice(&prg);
+    image.extend_from_slice(&chr);
+    Ok(image)
+}
@@ -1,3 +1,5 @@
+use crate::patch_list::Patch;
+
 pub const WORK_RAM_SIZE: usize = 0x800;
 
 #[derive(Clone)]
@@ -201,7 +203,7 @@ impl CheatSearch {
     }
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "cheat-ui", derive(serde::Serialize, serde::Deserialize))]
 pub struct CheatEntry {
     pub address: u32,
@@ -210,6 +212,25 @@ pub struct CheatEntry {
     pub label: String,
 }
 
+impl CheatEntry {
+    /// A debugger RAM freeze as a cheat: CPU RAM mirrors fold into the 2KB,
+    /// $6000-$7FFF indexes PRG-RAM after it. `None` for ROM patches.
+    pub fn from_freeze(patch: &Patch) -> Option<Self> {
+        let address = match patch.addr {
+            0x0000..=0x1FFF => patch.addr as u32 & 0x07FF,
+            0x6000..=0x7FFF => WORK_RAM_SIZE as u32 + (patch.addr - 0x6000) as u32,
+            _ => return None,
+        };
+        Some(CheatEntry {
+            address,
+            value: patch.value,
+            enabled: patch.enabled,
+            label: patch.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct CheatManager {
     pub entries: Vec<CheatEntry>,
 }
@@ -238,9 +259,21 @@ impl CheatManager {
 
     /// Apply cheats to a combined RAM buffer (cpu_ram ++ prg_ram).
     pub fn apply(&self, ram: &mut [u8]) {
-        for entry in &self.entries {
-            if entry.enabled && (entry.address as usize) < ram.len() {
-                ram[entry.address as usize] = entry.value;
+        self.apply_split(ram, None);
+    }
+
+    /// Apply cheats to CPU RAM and PRG-RAM held apart; addresses past the
+    /// end of `cpu_ram` index `prg_ram`, as in the combined buffer.
+    pub fn apply_split(&self, cpu_ram: &mut [u8], mut prg_ram: Option<&mut [u8]>) {
+        for entry in self.entries.iter().filter(|entry| entry.enabled) {
+            let addr = entry.address as usize;
+            if addr < cpu_ram.len() {
+                cpu_ram[addr] = entry.value;
+            } else if let Some(byte) = prg_ram
+                .as_deref_mut()
+                .and_then(|prg_ram| prg_ram.get_mut(addr - cpu_ram.len()))
+            {
+                *byte = entry.value;
             }
         }
     }
@@ -422,6 +455,25 @@ mod tests {
         assert_eq!(ram[0x100], 0);
     }
 
+    #[test]
+    fn test_cheat_manager_apply_split() {
+        let mut ram = vec![0u8; WORK_RAM_SIZE];
+        let mut prg_ram = vec![0u8; 0x2000];
+        let mut mgr = CheatManager::new();
+        mgr.entries.extend(
+            ["075A:09", "1F00:01", "6010:77", "8000:EA"]
+                .iter()
+                .filter_map(|spec| CheatEntry::from_freeze(&Patch::parse(spec).unwrap())),
+        );
+        assert_eq!(mgr.entries.len(), 3);
+
+        mgr.apply_split(&mut ram, Some(&mut prg_ram));
+        assert_eq!((ram[0x075A], ram[0x0700]), (0x09, 0x01));
+        assert_eq!(prg_ram[0x10], 0x77);
+        // Without PRG-RAM the cartridge freeze goes nowhere
+        mgr.apply_split(&mut ram, None);
+    }
+
     #[test]
     fn test_cheat_manager_remove() {
         let mut mgr = CheatManager::new();
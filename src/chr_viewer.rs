@@ -0,0 +1,141 @@
+use crate::hud_toast::draw_hud_label_rgb24;
+use crate::png::write_rgb24_png;
+use crate::ppu::nes_color_rgb;
+use std::path::Path;
+
+const SHADES: [[u8; 3]; 4] = [
+    [0x00, 0x00, 0x00],
+    [0x55, 0x55, 0x55],
+    [0xAA, 0xAA, 0xAA],
+    [0xFF, 0xFF, 0xFF],
+];
+const LABEL_COLOR: [u8; 3] = [0xF8, 0xB8, 0x00];
+
+/// Draw both pattern tables ($0000 and $1000, 16x16 tiles each) side by
+/// side across the top of an RGB24 frame. `peek` reads CHR without side
+/// effects; redrawing every frame keeps the view live while CHR-RAM changes.
+pub fn draw_pattern_tables_rgb24(
+    frame: &mut [u8],
+    width: usize,
+    height: usize,
+    peek: impl Fn(u16) -> u8,
+) {
+    if width < 256 || height < 128 || frame.len() < width * height * 3 {
+        return;
+    }
+
+    for table in 0..2u16 {
+        for tile in 0..256u16 {
+            let base = table * 0x1000 + tile * 16;
+            let tile_x = table as usize * 128 + (tile as usize % 16) * 8;
+            let tile_y = (tile as usize / 16) * 8;
+            for row in 0..8u16 {
+                let low = peek(base + row);
+                let high = peek(base + row + 8);
+                for col in 0..8 {
+                    let bit = 7 - col;
+                    let shade = ((low >> bit) & 1) | (((high >> bit) & 1) << 1);
+                    let idx = ((tile_y + row as usize) * width + tile_x + col) * 3;
+                    frame[idx..idx + 3].copy_from_slice(&SHADES[shade as usize]);
+                }
+            }
+        }
+    }
+
+    draw_hud_label_rgb24(frame, width, height, 2, 2, "0", LABEL_COLOR);
+    draw_hud_label_rgb24(frame, width, height, 130, 2, "1", LABEL_COLOR);
+}
+
+/// Gray shades for CHR exports when no palette is chosen.
+pub const GRAY_PALETTE: [[u8; 3]; 4] = SHADES;
+
+/// Export colors from four NES color indices, e.g. a palette from PPU RAM.
+pub fn chr_palette(indices: [u8; 4]) -> [[u8; 3]; 4] {
+    indices.map(nes_color_rgb)
+}
+
+/// Render one 4KB pattern table (256 tiles) as a 128x128 RGB24 sheet.
+/// A short last table is padded with blank tiles.
+pub fn chr_sheet_rgb24(table: &[u8], colors: &[[u8; 3]; 4]) -> Vec<u8> {
+    let mut sheet = vec![0u8; 128 * 128 * 3];
+    for (tile, data) in table.chunks(16).take(256).enumerate() {
+        let tile_x = (tile % 16) * 8;
+        let tile_y = (tile / 16) * 8;
+        for row in 0..8 {
+            let low = data.get(row).copied().unwrap_or(0);
+            let high = data.get(row + 8).copied().unwrap_or(0);
+            for col in 0..8 {
+                let bit = 7 - col;
+                let shade = ((low >> bit) & 1) | (((high >> bit) & 1) << 1);
+                let idx = ((tile_y + row) * 128 + tile_x + col) * 3;
+                sheet[idx..idx + 3].copy_from_slice(&colors[shade as usize]);
+            }
+        }
+    }
+    sheet
+}
+
+/// Write every 4KB pattern table of `chr` to `dir` as `chr_NNN.png`.
+/// Returns the number of sheets written.
+pub fn export_chr_sheets(
+    chr: &[u8],
+    colors: &[[u8; 3]; 4],
+    dir: impl AsRef<Path>,
+) -> std::io::Result<usize> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir)?;
+    let mut sheets = 0;
+    for (index, table) in chr.chunks(0x1000).enumerate() {
+        let path = dir.join(format!("chr_{:03}.png", index));
+        write_rgb24_png(path, 128, 128, &chr_sheet_rgb24(table, colors))?;
+        sheets += 1;
+    }
+    Ok(sheets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tile_pixels_use_both_bit_planes() {
+        let mut frame = vec![0x12u8; 256 * 240 * 3];
+        // Tile 1 of table 1, row 0: low plane 0x80, high plane 0xC0
+        let peek = |addr: u16| match addr {
+            0x1010 => 0x80,
+            0x1018 => 0xC0,
+            _ => 0,
+        };
+        draw_pattern_tables_rgb24(&mut frame, 256, 240, peek);
+
+        let pixel = |x: usize, y: usize| {
+            let i = (y * 256 + x) * 3;
+            [frame[i], frame[i + 1], frame[i + 2]]
+        };
+        assert_eq!(pixel(136, 0), SHADES[3]);
+        assert_eq!(pixel(137, 0), SHADES[2]);
+        assert_eq!(pixel(138, 0), SHADES[0]);
+        // Below the pattern tables the game frame is untouched
+        assert_eq!(pixel(0, 200), [0x12; 3]);
+    }
+
+    #[test]
+    fn exports_one_sheet_per_pattern_table() {
+        let mut chr = vec![0u8; 0x1800];
+        // Tile 17 of the first table, row 1: low plane 0x80
+        chr[17 * 16 + 1] = 0x80;
+        let colors = chr_palette([0x0F, 0x16, 0x27, 0x30]);
+        let sheet = chr_sheet_rgb24(&chr[..0x1000], &colors);
+        let idx = (9 * 128 + 8) * 3;
+        assert_eq!(&sheet[idx..idx + 3], &colors[1]);
+        assert_eq!(&sheet[..3], &colors[0]);
+
+        let mut dir = std::env::temp_dir();
+        dir.push(format!("nes_chr_export_test_{}", std::process::id()));
+        let sheets = export_chr_sheets(&chr, &GRAY_PALETTE, &dir).unwrap();
+        let second = std::fs::read(dir.join("chr_001.png")).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(sheets, 2);
+        assert_eq!(&second[1..4], b"PNG");
+    }
+}
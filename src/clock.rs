@@ -0,0 +1,67 @@
+// Where the emulated console is in time, for pinning a report down to the
+// dot ("it breaks at frame 5123, scanline 241") and for stamping trace lines.
+use std::fmt;
+
+/// PPU frame and beam position plus the CPU cycle count.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConsoleClock {
+    /// PPU frames completed since power-on.
+    pub frame: u64,
+    /// -1 is the pre-render line, 0-239 visible, 241 the start of vblank.
+    pub scanline: i16,
+    /// Dot within the scanline, 0-340.
+    pub dot: u16,
+    /// CPU cycles executed, not counting DMA stalls.
+    pub cpu_cycle: u64,
+}
+
+impl fmt::Display for ConsoleClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "frame {} scanline {} dot {} cycle {}",
+            self.frame, self.scanline, self.dot, self.cpu_cycle
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::nes_with_rom;
+    use crate::Nes;
+
+    #[test]
+    fn clock_advances_with_the_console() {
+        assert_eq!(Nes::new().clock().frame, 0);
+        let mut data = vec![b'N', b'E', b'S', 0x1A, 2, 1, 0, 0];
+        data.resize(16, 0);
+        let mut prg = vec![0xEA; 0x8000];
+        // JMP $8000
+        prg[0..3].copy_from_slice(&[0x4C, 0x00, 0x80]);
+        prg[0x7FFC..0x7FFE].copy_from_slice(&[0x00, 0x80]);
+        data.extend_from_slice(&prg);
+        data.extend_from_slice(&[0; 0x2000]);
+        let mut nes = nes_with_rom(&data);
+
+        let start = nes.clock();
+        for _ in 0..3 {
+            while !nes.step() {}
+        }
+        let clock = nes.clock();
+        assert_eq!(clock.frame, start.frame + 3);
+        assert!(clock.cpu_cycle > start.cpu_cycle + 3 * 29_000);
+        assert!(clock.scanline >= -1 && clock.scanline <= 260);
+        assert!(clock.dot <= 340);
+        assert_eq!(
+            ConsoleClock {
+                frame: 5123,
+                scanline: 241,
+                dot: 1,
+                cpu_cycle: 152_551_000,
+            }
+            .to_string(),
+            "frame 5123 scanline 241 dot 1 cycle 152551000"
+        );
+    }
+}
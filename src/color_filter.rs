@@ -0,0 +1,215 @@
+/// Daltonization for color-blind players: simulate how the frame looks
+/// with one cone type missing and shift the lost contrast into the
+/// channels that are still seen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorFilter {
+    /// Red-blind (no L cones).
+    Protanopia,
+    /// Green-blind (no M cones).
+    Deuteranopia,
+    /// Blue-blind (no S cones).
+    Tritanopia,
+}
+
+type Matrix = [[f32; 3]; 3];
+
+const RGB_TO_LMS: Matrix = [
+    [17.8824, 43.5161, 4.11935],
+    [3.45565, 27.1554, 3.86714],
+    [0.0299566, 0.184309, 1.46709],
+];
+
+const LMS_TO_RGB: Matrix = [
+    [0.080_944_45, -0.130_504_41, 0.116_721_07],
+    [-0.010_248_534, 0.054_019_33, -0.113_614_71],
+    [-0.000_365_296_94, -0.004_121_614_7, 0.693_511_4],
+];
+
+/// Moves the error the player can't see into green and blue.
+const ERROR_SHIFT: Matrix = [[0.0, 0.0, 0.0], [0.7, 1.0, 0.0], [0.7, 0.0, 1.0]];
+
+const IDENTITY: Matrix = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+fn mul(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut out = [[0.0; 3]; 3];
+    for (i, row) in out.iter_mut().enumerate() {
+        for (j, cell) in row.iter_mut().enumerate() {
+            *cell = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    out
+}
+
+fn sub(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut out = *a;
+    for (row, b_row) in out.iter_mut().zip(b) {
+        for (cell, b_cell) in row.iter_mut().zip(b_row) {
+            *cell -= b_cell;
+        }
+    }
+    out
+}
+
+impl ColorFilter {
+    pub const ALL: [ColorFilter; 3] = [
+        ColorFilter::Protanopia,
+        ColorFilter::Deuteranopia,
+        ColorFilter::Tritanopia,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|filter| filter.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorFilter::Protanopia => "protanopia",
+            ColorFilter::Deuteranopia => "deuteranopia",
+            ColorFilter::Tritanopia => "tritanopia",
+        }
+    }
+
+    /// Next filter in the hotkey cycle; `None` after the last one turns
+    /// the filter off.
+    pub fn cycle(current: Option<Self>) -> Option<Self> {
+        match current {
+            None => Some(ColorFilter::Protanopia),
+            Some(ColorFilter::Protanopia) => Some(ColorFilter::Deuteranopia),
+            Some(ColorFilter::Deuteranopia) => Some(ColorFilter::Tritanopia),
+            Some(ColorFilter::Tritanopia) => None,
+        }
+    }
+
+    /// Cone response with the missing cone rebuilt from the other two.
+    fn simulation(self) -> Matrix {
+        match self {
+            ColorFilter::Protanopia => [[0.0, 2.02344, -2.52581], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            ColorFilter::Deuteranopia => {
+                [[1.0, 0.0, 0.0], [0.494207, 0.0, 1.24827], [0.0, 0.0, 1.0]]
+            }
+            ColorFilter::Tritanopia => {
+                [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [-0.395913, 0.801109, 0.0]]
+            }
+        }
+    }
+
+    /// Combined RGB transform: `I + shift * (I - simulated)`.
+    fn matrix(self) -> Matrix {
+        let simulated = mul(&LMS_TO_RGB, &mul(&self.simulation(), &RGB_TO_LMS));
+        let correction = mul(&ERROR_SHIFT, &sub(&IDENTITY, &simulated));
+        let mut out = IDENTITY;
+        for (row, c_row) in out.iter_mut().zip(&correction) {
+            for (cell, c) in row.iter_mut().zip(c_row) {
+                *cell += c;
+            }
+        }
+        out
+    }
+}
+
+/// Apply `filter` in place to an RGB24 frame. x86_64 transforms each pixel
+/// with SSE2 (part of the x86_64 baseline); the scalar path gives the same
+/// bytes.
+pub fn apply_color_filter_rgb24(frame: &mut [u8], filter: ColorFilter) {
+    let m = filter.matrix();
+    #[cfg(target_arch = "x86_64")]
+    {
+        // Safety: SSE2 is always available on x86_64
+        unsafe { apply_matrix_sse2(frame, &m) };
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    apply_matrix_scalar(frame, &m);
+}
+
+#[cfg_attr(target_arch = "x86_64", allow(dead_code))]
+fn apply_matrix_scalar(frame: &mut [u8], m: &Matrix) {
+    for px in frame.chunks_exact_mut(3) {
+        let rgb = [px[0] as f32, px[1] as f32, px[2] as f32];
+        for (out, row) in px.iter_mut().zip(m) {
+            let value = row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2];
+            *out = (value.clamp(0.0, 255.0) + 0.5) as u8;
+        }
+    }
+}
+
+/// One pixel per step: the three output channels are computed side by side
+/// as `column0 * r + column1 * g + column2 * b`, in the scalar order.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn apply_matrix_sse2(frame: &mut [u8], m: &Matrix) {
+    use std::arch::x86_64::*;
+    let column = |j: usize| _mm_setr_ps(m[0][j], m[1][j], m[2][j], 0.0);
+    let (c0, c1, c2) = (column(0), column(1), column(2));
+    let (low, high, half) = (_mm_setzero_ps(), _mm_set1_ps(255.0), _mm_set1_ps(0.5));
+    let mut out = [0i32; 4];
+    for px in frame.chunks_exact_mut(3) {
+        let r = _mm_mul_ps(c0, _mm_set1_ps(px[0] as f32));
+        let g = _mm_mul_ps(c1, _mm_set1_ps(px[1] as f32));
+        let b = _mm_mul_ps(c2, _mm_set1_ps(px[2] as f32));
+        let value = _mm_add_ps(_mm_add_ps(r, g), b);
+        let value = _mm_add_ps(_mm_min_ps(_mm_max_ps(value, low), high), half);
+        _mm_storeu_si128(out.as_mut_ptr() as *mut __m128i, _mm_cvttps_epi32(value));
+        px[0] = out[0] as u8;
+        px[1] = out[1] as u8;
+        px[2] = out[2] as u8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grays_pass_through_and_reds_are_shifted() {
+        for filter in ColorFilter::ALL {
+            let mut frame = [0, 0, 0, 128, 128, 128, 255, 255, 255];
+            apply_color_filter_rgb24(&mut frame, filter);
+            for (&got, want) in frame.iter().zip([0u8, 0, 0, 128, 128, 128, 255, 255, 255]) {
+                assert!(got.abs_diff(want) <= 2, "{:?}: {:?}", filter, frame);
+            }
+        }
+
+        let mut red = [200, 0, 0];
+        apply_color_filter_rgb24(&mut red, ColorFilter::Protanopia);
+        assert_eq!(red[0], 200);
+        assert!(red[2] > 0, "{:?}", red);
+    }
+
+    #[test]
+    fn simd_and_scalar_paths_agree() {
+        let frame: Vec<u8> = (0..4096u32).map(|i| (i * 97 + i / 5) as u8).collect();
+        for filter in ColorFilter::ALL {
+            let mut fast = frame.clone();
+            let mut scalar = frame.clone();
+            apply_color_filter_rgb24(&mut fast, filter);
+            apply_matrix_scalar(&mut scalar, &filter.matrix());
+            assert_eq!(fast, scalar, "{:?}", filter);
+        }
+    }
+
+    #[test]
+    fn names_and_cycle() {
+        assert_eq!(
+            ColorFilter::from_name("Deuteranopia"),
+            Some(ColorFilter::Deuteranopia)
+        );
+        assert_eq!(ColorFilter::from_name("mono"), None);
+        let mut filter = None;
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            filter = ColorFilter::cycle(filter);
+            seen.push(filter);
+        }
+        assert_eq!(
+            seen,
+            [
+                Some(ColorFilter::Protanopia),
+                Some(ColorFilter::Deuteranopia),
+                Some(ColorFilter::Tritanopia),
+                None
+            ]
+        );
+    }
+}
@@ -0,0 +1,290 @@
+// Compatibility report: boot ROMs headlessly for a fixed number of frames
+// and record how far each got (load or mapper errors, a jammed CPU, whether
+// rendering was ever switched on, how busy the final picture is) as CSV or
+// Markdown, so runs over a ROM set can be compared across releases. Used by
+// the `compat-run` subcommand. JAMs and unknown opcodes the telemetry
+// caught are listed with each ROM.
+use std::fmt::Write as _;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::apu::AudioSink;
+use crate::telemetry::{Incident, IncidentKind};
+use crate::Nes;
+
+/// Entropy below this (in bits) counts as a blank or flat screen.
+const BLANK_ENTROPY: f64 = 0.1;
+
+/// Outcome of one ROM, worst first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CompatStatus {
+    /// Failed to load, uses an unemulated mapper, or the emulator panicked.
+    Error,
+    /// The CPU executed a JAM opcode.
+    Jammed,
+    /// Rendering was never enabled, or the last frame is a flat color.
+    Blank,
+    Ok,
+}
+
+impl CompatStatus {
+    pub fn name(self) -> &'static str {
+        match self {
+            CompatStatus::Error => "error",
+            CompatStatus::Jammed => "jammed",
+            CompatStatus::Blank => "blank",
+            CompatStatus::Ok => "ok",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompatResult {
+    pub path: String,
+    pub mapper: Option<u8>,
+    pub frames: u32,
+    /// First frame that ended with `$2001` background or sprites enabled.
+    pub first_render_frame: Option<u32>,
+    /// Frame in which the CPU jammed.
+    pub jammed_frame: Option<u32>,
+    pub error: Option<String>,
+    /// Shannon entropy, in bits (0-6), of the NES colors in the last frame.
+    pub entropy: f64,
+    /// Telemetry lines, e.g. `JAM $02 at $C000`.
+    pub incidents: Vec<String>,
+}
+
+impl CompatResult {
+    pub fn status(&self) -> CompatStatus {
+        if self.error.is_some() {
+            CompatStatus::Error
+        } else if self.jammed_frame.is_some() {
+            CompatStatus::Jammed
+        } else if self.first_render_frame.is_none() || self.entropy < BLANK_ENTROPY {
+            CompatStatus::Blank
+        } else {
+            CompatStatus::Ok
+        }
+    }
+}
+
+/// Boot `path` on a fresh console and run it for `frames` frames.
+pub fn run_rom(path: &str, frames: u32) -> CompatResult {
+    let mut result = CompatResult {
+        path: path.to_string(),
+        mapper: None,
+        frames: 0,
+        first_render_frame: None,
+        jammed_frame: None,
+        error: None,
+        entropy: 0.0,
+        incidents: Vec::new(),
+    };
+    let mut nes = Nes::new();
+    nes.set_audio_sink(AudioSink::Null);
+    if let Err(e) = nes.load_rom(path) {
+        result.error = Some(e.to_string());
+        return result;
+    }
+    result.mapper = nes.mapper_number();
+    if let Some(unsupported) = nes
+        .telemetry()
+        .incidents()
+        .iter()
+        .find(|i| matches!(i.kind, IncidentKind::UnsupportedMapper(_)))
+    {
+        result.error = Some(unsupported.describe());
+        return result;
+    }
+
+    // A panic is one more incompatibility, not the end of the run
+    let run = catch_unwind(AssertUnwindSafe(|| {
+        for frame in 1..=frames {
+            while !nes.step() {}
+            result.frames = frame;
+            if result.first_render_frame.is_none() && nes.io_registers().ppu_mask & 0x18 != 0 {
+                result.first_render_frame = Some(frame);
+            }
+            if nes.cpu_halted() {
+                result.jammed_frame = Some(frame);
+                break;
+            }
+        }
+    }));
+    result.incidents = nes
+        .telemetry()
+        .incidents()
+        .iter()
+        .map(Incident::describe)
+        .collect();
+    match run {
+        Ok(()) => result.entropy = frame_entropy(nes.get_indexed_frame_buffer()),
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<String>()
+                .map(String::as_str)
+                .or_else(|| panic.downcast_ref::<&str>().copied())
+                .unwrap_or("unknown panic");
+            result.error = Some(format!(
+                "panicked in frame {}: {}",
+                result.frames + 1,
+                message
+            ));
+        }
+    }
+    result
+}
+
+/// Shannon entropy of the NES colors ($00-$3F) in an indexed frame: 0 for
+/// a single flat color, up to 6 bits when all 64 are equally common.
+pub fn frame_entropy(indexed: &[u8]) -> f64 {
+    let mut counts = [0usize; 64];
+    for &color in indexed {
+        counts[(color & 0x3F) as usize] += 1;
+    }
+    let total = indexed.len() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / total;
+            p * (1.0 / p).log2()
+        })
+        .sum()
+}
+
+/// One line per ROM, with a header.
+pub fn report_csv(results: &[CompatResult]) -> String {
+    let mut out = String::from(
+        "rom,status,mapper,frames,first_render_frame,jammed_frame,entropy,error,incidents\n",
+    );
+    for r in results {
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{},{:.3},{},{}",
+            csv_field(&r.path),
+            r.status().name(),
+            optional(r.mapper),
+            r.frames,
+            optional(r.first_render_frame),
+            optional(r.jammed_frame),
+            r.entropy,
+            csv_field(r.error.as_deref().unwrap_or("")),
+            csv_field(&r.incidents.join("; "))
+        );
+    }
+    out
+}
+
+/// Status counts, then a table with the worst results first.
+pub fn report_markdown(results: &[CompatResult]) -> String {
+    let mut sorted: Vec<&CompatResult> = results.iter().collect();
+    sorted.sort_by(|a, b| {
+        a.status()
+            .cmp(&b.status())
+            .then_with(|| a.path.cmp(&b.path))
+    });
+
+    let mut out = String::from("# Compatibility report\n\n");
+    let counts: Vec<String> = [
+        CompatStatus::Ok,
+        CompatStatus::Blank,
+        CompatStatus::Jammed,
+        CompatStatus::Error,
+    ]
+    .iter()
+    .map(|&status| {
+        let count = results.iter().filter(|r| r.status() == status).count();
+        format!("{} {}", count, status.name())
+    })
+    .collect();
+    let _ = writeln!(out, "{} ROMs: {}\n", results.len(), counts.join(", "));
+    out += "| ROM | Status | Mapper | Frames | First render | Entropy | Notes |\n";
+    out += "|-----|--------|--------|--------|--------------|---------|-------|\n";
+    for r in sorted {
+        let mut notes: Vec<String> = match (&r.error, r.jammed_frame) {
+            (Some(error), _) => vec![error.clone()],
+            (None, Some(frame)) => vec![format!("halted in frame {}", frame)],
+            (None, None) => Vec::new(),
+        };
+        notes.extend(r.incidents.iter().cloned());
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {:.2} | {} |",
+            r.path.replace('|', "\\|"),
+            r.status().name(),
+            optional(r.mapper),
+            r.frames,
+            optional(r.first_render_frame),
+            r.entropy,
+            notes.join("; ").replace('|', "\\|")
+        );
+    }
+    out
+}
+
+fn optional<T: std::fmt::Display>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempRom;
+
+    fn write_rom(name: &str, mapper: u8, program: &[u8]) -> TempRom {
+        let mut prg = vec![0xEAu8; 0x4000];
+        prg[..program.len()].copy_from_slice(program);
+        prg[0x3FFC..0x3FFE].copy_from_slice(&0xC000u16.to_le_bytes());
+        let mut rom = vec![b'N', b'E', b'S', 0x1A, 1, 1, mapper << 4, mapper & 0xF0];
+        rom.resize(16, 0);
+        rom.extend_from_slice(&prg);
+        rom.extend(vec![0u8; 0x2000]);
+        TempRom::new(&format!("compat_{}", name), &rom)
+    }
+
+    #[test]
+    fn classifies_roms() {
+        // LDA #$1E, STA $2001, JMP $C000: rendering on (once the PPU has
+        // warmed up), empty CHR
+        let blank = write_rom(
+            "blank",
+            0,
+            &[0xA9, 0x1E, 0x8D, 0x01, 0x20, 0x4C, 0x00, 0xC0],
+        );
+        let jam = write_rom("jam", 0, &[0x02]);
+        let unsupported = write_rom("mapper6", 6, &[]);
+        let results: Vec<CompatResult> =
+            [blank.path(), jam.path(), unsupported.path(), "missing.nes"]
+                .iter()
+                .map(|path| run_rom(path, 5))
+                .collect();
+
+        assert!(results[0].first_render_frame.is_some());
+        assert_eq!(results[0].status(), CompatStatus::Blank);
+        assert_eq!(results[1].jammed_frame, Some(1));
+        assert_eq!(results[1].status(), CompatStatus::Jammed);
+        assert_eq!(
+            results[2].error.as_deref(),
+            Some("unsupported mapper 6 (FFE F4xxx)")
+        );
+        assert_eq!(results[3].status(), CompatStatus::Error);
+
+        assert_eq!(frame_entropy(&[0x0F; 64]), 0.0);
+        assert_eq!(frame_entropy(&[0x0F, 0x30, 0x0F, 0x30]), 1.0);
+
+        let csv = report_csv(&results);
+        assert!(csv.starts_with("rom,status,mapper,"));
+        assert!(csv.contains(",jammed,0,1,,1,0.000,,JAM $02 at $C000\n"));
+        let markdown = report_markdown(&results);
+        assert!(markdown.contains("4 ROMs: 0 ok, 1 blank, 1 jammed, 2 error"));
+        assert!(markdown.contains("| halted in frame 1; JAM $02 at $C000 |"));
+    }
+}
@@ -78,18 +78,6 @@ impl Cpu {
         let addr = self.read_word(bus); // This reads 2 bytes and increments PC by 2
         let return_addr = self.pc.wrapping_sub(1); // PC is now at opcode+3, return to opcode+2
 
-        // Track JSR patterns to detect waiting loops and accelerate them
-        if addr == 0x8995 && return_addr == 0x8976 {
-            self.rts_count += 1;
-            // Track JSR/RTS loop iterations
-
-            // Accelerate the waiting loop by using minimum cycles
-            self.push(bus, (return_addr >> 8) as u8);
-            self.push(bus, return_addr as u8);
-            self.pc = addr;
-            return 2; // Use 2 cycles instead of 6 to accelerate
-        }
-
         self.push(bus, (return_addr >> 8) as u8);
         self.push(bus, return_addr as u8);
         self.pc = addr;
@@ -98,28 +86,9 @@ impl Cpu {
 
     #[inline]
     pub(super) fn rts(&mut self, bus: &mut dyn CpuBus) -> u8 {
-        // RTS handles PC completely by itself
-        let old_pc = self.pc;
-        let _old_sp = self.sp;
         let low = self.pull(bus) as u16;
         let high = self.pull(bus) as u16;
-        let new_pc = ((high << 8) | low).wrapping_add(1);
-
-        // Check for infinite RTS loop - but be more tolerant
-        if old_pc == self.last_rts_pc {
-            self.rts_count += 1;
-            if self.rts_count == 20 {
-                // Frequent RTS loop detected
-                self.rts_count = 0;
-            }
-        } else {
-            self.rts_count = 0;
-        }
-        self.last_rts_pc = old_pc;
-
-        // RTS completed
-
-        self.pc = new_pc;
+        self.pc = ((high << 8) | low).wrapping_add(1);
         6
     }
 
@@ -144,8 +113,7 @@ impl Cpu {
         self.push(bus, return_pc as u8);
 
         // Push status register with B flag set
-        let status_with_break = self.status.bits() | StatusFlags::BREAK.bits();
-        self.push(bus, status_with_break);
+        self.push(bus, self.pushed_status(true));
 
         // Set interrupt disable flag
         self.status.insert(StatusFlags::INTERRUPT_DISABLE);
@@ -177,6 +145,9 @@ impl Cpu {
         let addr = (high << 8) | low;
         let final_addr = addr.wrapping_add(self.y as u16);
         let page_crossed = (addr & 0xFF00) != (final_addr & 0xFF00);
+        if page_crossed {
+            self.dummy_read_uncarried(bus, addr, final_addr);
+        }
         (final_addr, page_crossed)
     }
 
@@ -197,6 +168,9 @@ impl Cpu {
         let base = self.read_word(bus);
         let addr = base.wrapping_add(self.x as u16);
         let page_crossed = (base & 0xFF00) != (addr & 0xFF00);
+        if page_crossed {
+            self.dummy_read_uncarried(bus, base, addr);
+        }
         (addr, page_crossed)
     }
 
@@ -205,9 +179,33 @@ impl Cpu {
         let base = self.read_word(bus);
         let addr = base.wrapping_add(self.y as u16);
         let page_crossed = (base & 0xFF00) != (addr & 0xFF00);
+        if page_crossed {
+            self.dummy_read_uncarried(bus, base, addr);
+        }
         (addr, page_crossed)
     }
 
+    /// Indexed modes read from the address before the page carry is added to
+    /// the high byte; loads only pay for it (and see it) on a page cross.
+    #[inline]
+    pub(super) fn dummy_read_uncarried(&mut self, bus: &mut dyn CpuBus, base: u16, addr: u16) {
+        bus.read((base & 0xFF00) | (addr & 0x00FF));
+    }
+
+    /// Stores and read-modify-writes always make the indexed dummy read; the
+    /// addressing helper has only made it if the page was crossed.
+    #[inline]
+    pub(super) fn indexed_write_dummy_read(
+        &mut self,
+        bus: &mut dyn CpuBus,
+        addr: u16,
+        page_crossed: bool,
+    ) {
+        if !page_crossed {
+            bus.read(addr);
+        }
+    }
+
     // ORA instructions
     #[inline]
     pub(super) fn ora(&mut self, value: u8) {
@@ -266,7 +264,7 @@ impl Cpu {
         let addr = self.read_byte(bus) as u16;
         let value = bus.read(addr);
         let result = self.asl(value);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         5
     }
 
@@ -275,16 +273,13 @@ impl Cpu {
         let addr = self.read_word(bus);
         let value = bus.read(addr);
         let result = self.asl(value);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         6
     }
 
     #[inline]
     pub(super) fn php(&mut self, bus: &mut dyn CpuBus) -> u8 {
-        self.push(
-            bus,
-            self.status.bits() | StatusFlags::BREAK.bits() | StatusFlags::UNUSED.bits(),
-        );
+        self.push(bus, self.pushed_status(true));
         3
     }
     #[inline]
@@ -317,7 +312,7 @@ impl Cpu {
         let addr = self.get_zero_page_x_addr(bus);
         let value = bus.read(addr);
         let result = self.asl(value);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         6
     }
     #[inline]
@@ -351,10 +346,11 @@ impl Cpu {
 
     #[inline]
     pub(super) fn asl_absolute_x(&mut self, bus: &mut dyn CpuBus) -> u8 {
-        let (addr, _) = self.get_absolute_x_addr(bus);
+        let (addr, page_crossed) = self.get_absolute_x_addr(bus);
+        self.indexed_write_dummy_read(bus, addr, page_crossed);
         let value = bus.read(addr);
         let result = self.asl(value);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         7
     }
     // AND instructions
@@ -444,7 +440,7 @@ impl Cpu {
         let addr = self.read_byte(bus) as u16;
         let value = bus.read(addr);
         let result = self.rol(value);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         5
     }
 
@@ -453,7 +449,7 @@ impl Cpu {
         let addr = self.read_word(bus);
         let value = bus.read(addr);
         let result = self.rol(value);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         6
     }
 
@@ -494,7 +490,7 @@ impl Cpu {
         let addr = self.get_zero_page_x_addr(bus);
         let value = bus.read(addr);
         let result = self.rol(value);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         6
     }
     #[inline]
@@ -528,10 +524,11 @@ impl Cpu {
 
     #[inline]
     pub(super) fn rol_absolute_x(&mut self, bus: &mut dyn CpuBus) -> u8 {
-        let (addr, _) = self.get_absolute_x_addr(bus);
+        let (addr, page_crossed) = self.get_absolute_x_addr(bus);
+        self.indexed_write_dummy_read(bus, addr, page_crossed);
         let value = bus.read(addr);
         let result = self.rol(value);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         7
     }
     #[inline]
@@ -627,7 +624,7 @@ impl Cpu {
         let addr = self.read_byte(bus) as u16;
         let value = bus.read(addr);
         let result = self.lsr(value);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         5
     }
 
@@ -636,7 +633,7 @@ impl Cpu {
         let addr = self.read_word(bus);
         let value = bus.read(addr);
         let result = self.lsr(value);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         6
     }
 
@@ -675,7 +672,7 @@ impl Cpu {
         let addr = self.get_zero_page_x_addr(bus);
         let value = bus.read(addr);
         let result = self.lsr(value);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         6
     }
     #[inline]
@@ -709,10 +706,11 @@ impl Cpu {
 
     #[inline]
     pub(super) fn lsr_absolute_x(&mut self, bus: &mut dyn CpuBus) -> u8 {
-        let (addr, _) = self.get_absolute_x_addr(bus);
+        let (addr, page_crossed) = self.get_absolute_x_addr(bus);
+        self.indexed_write_dummy_read(bus, addr, page_crossed);
         let value = bus.read(addr);
         let result = self.lsr(value);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         7
     }
     #[inline]
@@ -764,7 +762,7 @@ impl Cpu {
         let addr = self.read_byte(bus) as u16;
         let value = bus.read(addr);
         let result = self.ror(value);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         5
     }
 
@@ -773,7 +771,7 @@ impl Cpu {
         let addr = self.read_word(bus);
         let value = bus.read(addr);
         let result = self.ror(value);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         6
     }
 
@@ -829,7 +827,7 @@ impl Cpu {
         let addr = self.get_zero_page_x_addr(bus);
         let value = bus.read(addr);
         let result = self.ror(value);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         6
     }
     #[inline]
@@ -863,10 +861,11 @@ impl Cpu {
 
     #[inline]
     pub(super) fn ror_absolute_x(&mut self, bus: &mut dyn CpuBus) -> u8 {
-        let (addr, _) = self.get_absolute_x_addr(bus);
+        let (addr, page_crossed) = self.get_absolute_x_addr(bus);
+        self.indexed_write_dummy_read(bus, addr, page_crossed);
         let value = bus.read(addr);
         let result = self.ror(value);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         7
     }
     #[inline]
@@ -876,15 +875,6 @@ impl Cpu {
         6
     }
 
-    #[inline]
-    pub(super) fn sax_indexed_indirect(&mut self, bus: &mut dyn CpuBus) -> u8 {
-        // SAX: Store A & X - unofficial opcode
-        let addr = self.get_indexed_indirect_addr(bus);
-        let value = self.a & self.x;
-        bus.write(addr, value);
-        6
-    }
-
     #[inline]
     pub(super) fn sty_zero_page(&mut self, bus: &mut dyn CpuBus) -> u8 {
         let addr = self.read_byte(bus) as u16;
@@ -929,7 +919,8 @@ impl Cpu {
     }
     #[inline]
     pub(super) fn sta_indirect_indexed(&mut self, bus: &mut dyn CpuBus) -> u8 {
-        let (addr, _) = self.get_indirect_indexed_addr(bus);
+        let (addr, page_crossed) = self.get_indirect_indexed_addr(bus);
+        self.indexed_write_dummy_read(bus, addr, page_crossed);
         bus.write(addr, self.a);
         6
     }
@@ -962,7 +953,8 @@ impl Cpu {
     }
     #[inline]
     pub(super) fn sta_absolute_y(&mut self, bus: &mut dyn CpuBus) -> u8 {
-        let (addr, _) = self.get_absolute_y_addr(bus);
+        let (addr, page_crossed) = self.get_absolute_y_addr(bus);
+        self.indexed_write_dummy_read(bus, addr, page_crossed);
         bus.write(addr, self.a);
         5
     }
@@ -973,7 +965,8 @@ impl Cpu {
     }
     #[inline]
     pub(super) fn sta_absolute_x(&mut self, bus: &mut dyn CpuBus) -> u8 {
-        let (addr, _) = self.get_absolute_x_addr(bus);
+        let (addr, page_crossed) = self.get_absolute_x_addr(bus);
+        self.indexed_write_dummy_read(bus, addr, page_crossed);
         bus.write(addr, self.a);
         5
     }
@@ -1182,7 +1175,7 @@ impl Cpu {
         let addr = self.read_byte(bus) as u16;
         let value = bus.read(addr);
         let result = value.wrapping_sub(1);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         self.set_zero_negative_flags(result);
         5
     }
@@ -1225,7 +1218,7 @@ impl Cpu {
         let addr = self.read_word(bus);
         let value = bus.read(addr);
         let result = value.wrapping_sub(1);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         self.set_zero_negative_flags(result);
         6
     }
@@ -1258,7 +1251,7 @@ impl Cpu {
         let addr = self.get_zero_page_x_addr(bus);
         let value = bus.read(addr);
         let result = value.wrapping_sub(1);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         self.set_zero_negative_flags(result);
         6
     }
@@ -1293,10 +1286,11 @@ impl Cpu {
 
     #[inline]
     pub(super) fn dec_absolute_x(&mut self, bus: &mut dyn CpuBus) -> u8 {
-        let (addr, _) = self.get_absolute_x_addr(bus);
+        let (addr, page_crossed) = self.get_absolute_x_addr(bus);
+        self.indexed_write_dummy_read(bus, addr, page_crossed);
         let value = bus.read(addr);
         let result = value.wrapping_sub(1);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         self.set_zero_negative_flags(result);
         7
     }
@@ -1343,7 +1337,7 @@ impl Cpu {
         let addr = self.read_byte(bus) as u16;
         let value = bus.read(addr);
         let result = value.wrapping_add(1);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         self.set_zero_negative_flags(result);
         5
     }
@@ -1381,7 +1375,7 @@ impl Cpu {
         let addr = self.read_word(bus);
         let value = bus.read(addr);
         let result = value.wrapping_add(1);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         self.set_zero_negative_flags(result);
         6
     }
@@ -1414,7 +1408,7 @@ impl Cpu {
         let addr = self.get_zero_page_x_addr(bus);
         let value = bus.read(addr);
         let result = value.wrapping_add(1);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         self.set_zero_negative_flags(result);
         6
     }
@@ -1449,10 +1443,11 @@ impl Cpu {
 
     #[inline]
     pub(super) fn inc_absolute_x(&mut self, bus: &mut dyn CpuBus) -> u8 {
-        let (addr, _) = self.get_absolute_x_addr(bus);
+        let (addr, page_crossed) = self.get_absolute_x_addr(bus);
+        self.indexed_write_dummy_read(bus, addr, page_crossed);
         let value = bus.read(addr);
         let result = value.wrapping_add(1);
-        bus.write(addr, result);
+        bus.write_rmw(addr, value, result);
         self.set_zero_negative_flags(result);
         7
     }
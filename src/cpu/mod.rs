@@ -1,8 +1,14 @@
 use bitflags::bitflags;
 
 mod instructions;
+pub mod reference;
 #[cfg(test)]
 mod tests;
+mod unofficial;
+
+/// An NMI that arrives during the first four cycles of BRK or an IRQ
+/// sequence takes over its vector fetch.
+pub const NMI_HIJACK_CYCLES: u8 = 4;
 
 bitflags! {
     #[derive(Debug, Clone, Copy)]
@@ -18,6 +24,9 @@ bitflags! {
     }
 }
 
+/// The 2A03's 6502 core, the only CPU the emulator runs. It reaches memory
+/// and the rest of the console only through [`CpuBus`]; `reference` is an
+/// independent model kept for differential testing.
 pub struct Cpu {
     pub a: u8,   // Accumulator
     pub x: u8,   // X register
@@ -27,8 +36,9 @@ pub struct Cpu {
     pub status: StatusFlags,
     cycles: u64,
     halted: bool,
-    rts_count: u32,   // Counter for consecutive RTS calls at same PC
-    last_rts_pc: u16, // Last PC where RTS was executed
+    poll_cycle: u8, // Cycles of the last instruction run before its interrupt poll
+    polled_irq_inhibit: bool, // I flag as seen by the last interrupt poll
+    last_opcode: u8,
 }
 
 impl Cpu {
@@ -42,11 +52,13 @@ impl Cpu {
             status: StatusFlags::from_bits_truncate(0x24),
             cycles: 0,
             halted: false,
-            rts_count: 0,
-            last_rts_pc: 0,
+            poll_cycle: 1,
+            polled_irq_inhibit: true,
+            last_opcode: 0xEA,
         }
     }
 
+    /// Power-on: registers cleared, SP at $FD after the reset sequence.
     pub fn reset(&mut self, bus: &mut dyn CpuBus) {
         self.a = 0;
         self.x = 0;
@@ -54,6 +66,7 @@ impl Cpu {
         self.sp = 0xFD;
         self.status = StatusFlags::from_bits_truncate(0x24);
         self.halted = false;
+        self.polled_irq_inhibit = true;
 
         bus.on_reset();
         let low = bus.read(0xFFFC) as u16;
@@ -62,28 +75,102 @@ impl Cpu {
         self.cycles = 8;
     }
 
+    /// Reset button: the interrupt sequence runs with its stack writes
+    /// turned into reads, so SP drops by 3 and only I changes in P.
+    pub fn soft_reset(&mut self, bus: &mut dyn CpuBus) {
+        self.sp = self.sp.wrapping_sub(3);
+        self.status.insert(StatusFlags::INTERRUPT_DISABLE);
+        self.halted = false;
+        self.polled_irq_inhibit = true;
+
+        bus.on_reset();
+        let low = bus.read(0xFFFC) as u16;
+        let high = bus.read(0xFFFD) as u16;
+        self.pc = (high << 8) | low;
+        self.cycles += 7;
+    }
+
     pub fn step(&mut self, bus: &mut dyn CpuBus) -> u8 {
         if self.halted {
             self.cycles += 1;
+            self.poll_cycle = 1;
             return 1;
         }
 
+        bus.on_instruction(self.pc, self.cycles);
         let opcode = bus.read(self.pc);
 
         // Increment PC for most instructions - special ones handle it themselves
         self.pc = self.pc.wrapping_add(1);
 
+        let irq_inhibit_before = self.status.contains(StatusFlags::INTERRUPT_DISABLE);
         let cycles = self.execute_instruction(opcode, bus);
+        self.last_opcode = opcode;
+
+        // CLI, SEI and PLP change I after the poll, so the old value decides
+        // whether an IRQ is taken right after them. RTI's change is seen at once.
+        self.polled_irq_inhibit = match opcode {
+            0x28 | 0x58 | 0x78 => irq_inhibit_before,
+            _ => self.status.contains(StatusFlags::INTERRUPT_DISABLE),
+        };
 
         // Safety check: ensure we're making progress
         if cycles == 0 {
+            self.poll_cycle = 1;
             return 2; // Return minimum cycles to prevent infinite loop
         }
 
+        // Interrupts are polled before an instruction's last cycle, except
+        // that a taken branch staying on its page only polls before its
+        // operand fetch
+        self.poll_cycle = match opcode {
+            0x00 => NMI_HIJACK_CYCLES,
+            op if op & 0x1F == 0x10 && cycles == 3 => 1,
+            _ => cycles - 1,
+        };
+
         self.cycles += cycles as u64;
         cycles
     }
 
+    /// How many cycles of the last instruction ran before it polled for
+    /// interrupts; an NMI edge or IRQ arriving later waits one instruction.
+    /// For BRK this is the window in which an NMI hijacks it instead.
+    pub fn interrupt_poll_cycle(&self) -> u8 {
+        self.poll_cycle
+    }
+
+    /// Whether the last poll saw IRQs masked (see the CLI/SEI/PLP delay).
+    pub fn irq_inhibited_at_poll(&self) -> bool {
+        self.polled_irq_inhibit
+    }
+
+    /// Opcode of the last instruction run, `$EA` after an interrupt.
+    pub fn last_opcode(&self) -> u8 {
+        self.last_opcode
+    }
+
+    /// The last instruction was BRK, whose vector an NMI can take over.
+    pub fn nmi_can_hijack(&self) -> bool {
+        self.last_opcode == 0x00
+    }
+
+    /// Finish BRK or an IRQ sequence through the NMI vector instead: the
+    /// stacked PC and status stay as pushed.
+    pub fn hijack_to_nmi(&mut self, bus: &mut dyn CpuBus) {
+        let low = bus.read(0xFFFA) as u16;
+        let high = bus.read(0xFFFB) as u16;
+        self.pc = (high << 8) | low;
+    }
+
+    /// P as pushed to the stack: bit 5 always reads back set and B is 1 only
+    /// for PHP and BRK. D is kept as is; the 2A03 ignores it but stores it.
+    fn pushed_status(&self, brk: bool) -> u8 {
+        let mut status = self.status | StatusFlags::UNUSED;
+        status.set(StatusFlags::BREAK, brk);
+        status.bits()
+    }
+
     pub fn nmi(&mut self, bus: &mut dyn CpuBus) -> u8 {
         if self.halted {
             return 0;
@@ -91,9 +178,11 @@ impl Cpu {
 
         self.push(bus, (self.pc >> 8) as u8);
         self.push(bus, self.pc as u8);
-        self.push(bus, self.status.bits() & !StatusFlags::BREAK.bits());
+        self.push(bus, self.pushed_status(false));
 
         self.status.insert(StatusFlags::INTERRUPT_DISABLE);
+        self.polled_irq_inhibit = true;
+        self.last_opcode = 0xEA;
 
         let low = bus.read(0xFFFA) as u16;
         let high = bus.read(0xFFFB) as u16;
@@ -109,16 +198,18 @@ impl Cpu {
             return 0;
         }
 
-        // IRQ is maskable - check interrupt disable flag
-        if self.status.contains(StatusFlags::INTERRUPT_DISABLE) {
+        // IRQ is maskable - by the I flag the last interrupt poll saw
+        if self.polled_irq_inhibit {
             return 0;
         }
 
         self.push(bus, (self.pc >> 8) as u8);
         self.push(bus, self.pc as u8);
-        self.push(bus, self.status.bits() & !StatusFlags::BREAK.bits());
+        self.push(bus, self.pushed_status(false));
 
         self.status.insert(StatusFlags::INTERRUPT_DISABLE);
+        self.polled_irq_inhibit = true;
+        self.last_opcode = 0xEA;
 
         // IRQ vector at $FFFE-$FFFF
         let low = bus.read(0xFFFE) as u16;
@@ -147,6 +238,7 @@ impl Cpu {
         self.cycles = cycles;
     }
 
+    /// Official opcodes; the rest are in `unofficial.rs`.
     fn execute_instruction(&mut self, opcode: u8, bus: &mut dyn CpuBus) -> u8 {
         match opcode {
             0x00 => self.brk(bus),
@@ -193,17 +285,6 @@ impl Cpu {
             0x41 => self.eor_indexed_indirect(bus),
             0x45 => self.eor_zero_page(bus),
             0x46 => self.lsr_zero_page(bus),
-            0x47 => {
-                // SRE zeropage - Shift Right and Exclusive OR
-                let addr = self.read_byte(bus) as u16;
-                let value = bus.read(addr);
-                let shifted = value >> 1;
-                bus.write(addr, shifted);
-                self.status.set(StatusFlags::CARRY, value & 0x01 != 0);
-                self.a ^= shifted;
-                self.set_zero_negative_flags(self.a);
-                5
-            }
             0x48 => self.pha(bus),
             0x49 => self.eor_immediate(bus),
             0x4A => self.lsr_accumulator(),
@@ -241,7 +322,6 @@ impl Cpu {
             0x7E => self.ror_absolute_x(bus),
 
             0x81 => self.sta_indexed_indirect(bus),
-            0x83 => self.sax_indexed_indirect(bus),
             0x84 => self.sty_zero_page(bus),
             0x85 => self.sta_zero_page(bus),
             0x86 => self.stx_zero_page(bus),
@@ -328,785 +408,7 @@ impl Cpu {
             0xFD => self.sbc_absolute_x(bus),
             0xFE => self.inc_absolute_x(bus),
 
-            _ => {
-                // Some games use unofficial opcodes, try to continue
-                match opcode {
-                    // Common unofficial NOPs
-                    0x1A | 0x3A | 0x5A | 0x7A | 0xDA | 0xFA => 2,
-                    0x80 | 0x82 | 0x89 | 0xC2 | 0xE2 => {
-                        self.read_byte(bus); // Consume immediate byte
-                        2
-                    }
-                    0x04 | 0x44 | 0x64 => {
-                        self.read_byte(bus); // Consume zero page byte
-                        3
-                    }
-                    0x14 | 0x34 | 0x54 | 0x74 | 0xD4 | 0xF4 => {
-                        self.read_byte(bus); // Consume zero page,X byte
-                        4
-                    }
-                    0x0C => {
-                        self.read_word(bus); // Consume absolute address - NOP absolute
-                        4
-                    }
-                    0x1C | 0x3C | 0x5C | 0x7C | 0xDC | 0xFC => {
-                        self.read_word(bus); // Consume absolute,X address - NOP absolute,X
-                        4 // Could be 5 with page crossing, but we'll use 4
-                    }
-                    // Unofficial opcodes
-                    0x07 => {
-                        // SLO zero page - Shift Left, OR
-                        let addr = self.read_byte(bus) as u16;
-                        let value = bus.read(addr);
-                        self.status.set(StatusFlags::CARRY, value & 0x80 != 0);
-                        let shifted = value << 1;
-                        bus.write(addr, shifted);
-                        self.a |= shifted;
-                        self.set_zero_negative_flags(self.a);
-                        5
-                    }
-                    0x0B => {
-                        // ANC immediate - AND, Copy N to C
-                        let value = self.read_byte(bus);
-                        self.a &= value;
-                        self.set_zero_negative_flags(self.a);
-                        self.status.set(
-                            StatusFlags::CARRY,
-                            self.status.contains(StatusFlags::NEGATIVE),
-                        );
-                        2
-                    }
-                    0x03 => {
-                        // SLO (indirect,X) - Shift Left, OR
-                        let addr = self.get_indexed_indirect_addr(bus);
-                        let value = bus.read(addr);
-                        self.status.set(StatusFlags::CARRY, value & 0x80 != 0);
-                        let shifted = value << 1;
-                        bus.write(addr, shifted);
-                        self.a |= shifted;
-                        self.set_zero_negative_flags(self.a);
-                        8
-                    }
-                    0x0F => {
-                        // SLO absolute - Shift Left, OR
-                        let addr = self.read_word(bus);
-                        let value = bus.read(addr);
-                        self.status.set(StatusFlags::CARRY, value & 0x80 != 0);
-                        let shifted = value << 1;
-                        bus.write(addr, shifted);
-                        self.a |= shifted;
-                        self.set_zero_negative_flags(self.a);
-                        6
-                    }
-                    0x7F => {
-                        // RRA absolute,X - Rotate Right and Add
-                        let addr = self.read_word(bus);
-                        let effective_addr = addr.wrapping_add(self.x as u16);
-                        let value = bus.read(effective_addr);
-                        let carry = if self.status.contains(StatusFlags::CARRY) {
-                            1
-                        } else {
-                            0
-                        };
-                        let new_carry = value & 0x01;
-                        let rotated = (value >> 1) | (carry << 7);
-                        bus.write(effective_addr, rotated);
-                        self.status.set(StatusFlags::CARRY, new_carry != 0);
-                        self.adc(rotated);
-                        7
-                    }
-                    0xFB => {
-                        // ISC absolute,Y - Increment and Subtract with Carry
-                        let addr = self.read_word(bus);
-                        let effective_addr = addr.wrapping_add(self.y as u16);
-                        let value = bus.read(effective_addr);
-                        let incremented = value.wrapping_add(1);
-                        bus.write(effective_addr, incremented);
-                        self.sbc(incremented);
-                        7
-                    }
-                    0x43 => {
-                        // SRE (indirect,X) - Shift Right and Exclusive OR
-                        let addr = self.get_indexed_indirect_addr(bus);
-                        let value = bus.read(addr);
-                        let shifted = value >> 1;
-                        bus.write(addr, shifted);
-                        self.status.set(StatusFlags::CARRY, value & 0x01 != 0);
-                        self.a ^= shifted;
-                        self.set_zero_negative_flags(self.a);
-                        8
-                    }
-                    0x4F => {
-                        // SRE absolute - Shift Right and Exclusive OR
-                        let addr = self.read_word(bus);
-                        let value = bus.read(addr);
-                        let shifted = value >> 1;
-                        bus.write(addr, shifted);
-                        self.status.set(StatusFlags::CARRY, value & 0x01 != 0);
-                        self.a ^= shifted;
-                        self.set_zero_negative_flags(self.a);
-                        6
-                    }
-                    0x53 => {
-                        // SRE (indirect),Y - Shift Right and Exclusive OR
-                        let (addr, _page_crossed) = self.get_indirect_indexed_addr(bus);
-                        let value = bus.read(addr);
-                        let shifted = value >> 1;
-                        bus.write(addr, shifted);
-                        self.status.set(StatusFlags::CARRY, value & 0x01 != 0);
-                        self.a ^= shifted;
-                        self.set_zero_negative_flags(self.a);
-                        8
-                    }
-                    0x57 => {
-                        // SRE zero page,X - Shift Right and Exclusive OR
-                        let addr = self.get_zero_page_x_addr(bus);
-                        let value = bus.read(addr);
-                        let shifted = value >> 1;
-                        bus.write(addr, shifted);
-                        self.status.set(StatusFlags::CARRY, value & 0x01 != 0);
-                        self.a ^= shifted;
-                        self.set_zero_negative_flags(self.a);
-                        6
-                    }
-                    0x5B => {
-                        // SRE absolute,Y - Shift Right and Exclusive OR
-                        let addr = self.read_word(bus);
-                        let effective_addr = addr.wrapping_add(self.y as u16);
-                        let value = bus.read(effective_addr);
-                        let shifted = value >> 1;
-                        bus.write(effective_addr, shifted);
-                        self.status.set(StatusFlags::CARRY, value & 0x01 != 0);
-                        self.a ^= shifted;
-                        self.set_zero_negative_flags(self.a);
-                        7
-                    }
-                    0x87 => {
-                        // SAX zero page - Store A AND X
-                        let addr = self.read_byte(bus) as u16;
-                        let value = self.a & self.x;
-                        bus.write(addr, value);
-                        3
-                    }
-                    0x8B => {
-                        // XAA immediate - (A OR CONST) AND X AND immediate [EXTREMELY UNSTABLE]
-                        // WARNING: Extremely unstable - behavior varies by temperature, chip, etc.
-                        // Using magic constant 0xFF for basic compatibility
-                        let value = self.read_byte(bus);
-                        let magic_const = 0xFF; // Common fallback value
-                        self.a = ((self.a | magic_const) & self.x) & value;
-                        self.set_zero_negative_flags(self.a);
-                        2
-                    }
-                    0x8F => {
-                        // SAX absolute - Store A AND X
-                        let addr = self.read_word(bus);
-                        let value = self.a & self.x;
-                        bus.write(addr, value);
-                        4
-                    }
-                    // LAX unofficial opcodes (LDA + TAX combined)
-                    0xA7 | 0xB7 | 0xAF | 0xBF | 0xA3 | 0xB3 | 0xAB => {
-                        match opcode {
-                            0xAB => {
-                                // LAX immediate - Load A and X with memory
-                                let value = self.read_byte(bus);
-                                self.a = value;
-                                self.x = value;
-                                self.set_zero_negative_flags(value);
-                                2
-                            }
-                            0xA7 => {
-                                // LAX zero page
-                                let addr = self.read_byte(bus) as u16;
-                                let value = bus.read(addr);
-                                self.a = value;
-                                self.x = value;
-                                self.set_zero_negative_flags(value);
-                                3
-                            }
-                            0xB7 => {
-                                // LAX zero page,Y
-                                let addr = self.get_zero_page_y_addr(bus);
-                                let value = bus.read(addr);
-                                self.a = value;
-                                self.x = value;
-                                self.set_zero_negative_flags(value);
-                                4
-                            }
-                            0xAF => {
-                                // LAX absolute
-                                let addr = self.read_word(bus);
-                                let value = bus.read(addr);
-                                self.a = value;
-                                self.x = value;
-                                self.set_zero_negative_flags(value);
-                                4
-                            }
-                            0xBF => {
-                                // LAX absolute,Y
-                                let addr = self.read_word(bus);
-                                let effective_addr = addr.wrapping_add(self.y as u16);
-                                let value = bus.read(effective_addr);
-                                self.a = value;
-                                self.x = value;
-                                self.set_zero_negative_flags(value);
-                                4
-                            }
-                            0xA3 => {
-                                // LAX (indirect,X)
-                                let addr = self.get_indexed_indirect_addr(bus);
-                                let value = bus.read(addr);
-                                self.a = value;
-                                self.x = value;
-                                self.set_zero_negative_flags(value);
-                                6
-                            }
-                            0xB3 => {
-                                // LAX (indirect),Y
-                                let (addr, _) = self.get_indirect_indexed_addr(bus);
-                                let value = bus.read(addr);
-                                self.a = value;
-                                self.x = value;
-                                self.set_zero_negative_flags(value);
-                                5
-                            }
-                            _ => 2,
-                        }
-                    }
-                    // SHY unofficial opcode
-                    // JAM/KIL opcodes - Halt the CPU until reset.
-                    0x02 | 0x12 | 0x22 | 0x32 | 0x42 | 0x52 | 0x62 | 0x72 | 0x92 | 0xB2 | 0xD2
-                    | 0xF2 => self.jam(),
-                    0x9C => {
-                        // SHY absolute,X - Store Y AND (high byte of original addr + 1) [UNSTABLE]
-                        let addr = self.read_word(bus);
-                        let effective_addr = addr.wrapping_add(self.x as u16);
-                        let high_byte = (addr >> 8) as u8; // Use original addr, not effective_addr
-                        let value = self.y & high_byte.wrapping_add(1);
-                        bus.write(effective_addr, value);
-                        5
-                    }
-                    0x13 => {
-                        // SLO (indirect),Y - Shift Left, OR
-                        let (addr, _page_crossed) = self.get_indirect_indexed_addr(bus);
-                        let value = bus.read(addr);
-                        self.status.set(StatusFlags::CARRY, value & 0x80 != 0);
-                        let shifted = value << 1;
-                        bus.write(addr, shifted);
-                        self.a |= shifted;
-                        self.set_zero_negative_flags(self.a);
-                        8
-                    }
-                    0x17 => {
-                        // SLO zero page,X - Shift Left, OR
-                        let addr = self.get_zero_page_x_addr(bus);
-                        let value = bus.read(addr);
-                        self.status.set(StatusFlags::CARRY, value & 0x80 != 0);
-                        let shifted = value << 1;
-                        bus.write(addr, shifted);
-                        self.a |= shifted;
-                        self.set_zero_negative_flags(self.a);
-                        6
-                    }
-                    0x1F => {
-                        // SLO absolute,X - Shift Left, OR
-                        let addr = self.read_word(bus);
-                        let effective_addr = addr.wrapping_add(self.x as u16);
-                        let value = bus.read(effective_addr);
-                        self.status.set(StatusFlags::CARRY, value & 0x80 != 0);
-                        let shifted = value << 1;
-                        bus.write(effective_addr, shifted);
-                        self.a |= shifted;
-                        self.set_zero_negative_flags(self.a);
-                        7
-                    }
-                    0x23 => {
-                        // RLA (indirect,X) - Rotate Left, AND
-                        let addr = self.get_indexed_indirect_addr(bus);
-                        let value = bus.read(addr);
-                        let carry = if self.status.contains(StatusFlags::CARRY) {
-                            1
-                        } else {
-                            0
-                        };
-                        let rotated = (value << 1) | carry;
-                        self.status.set(StatusFlags::CARRY, value & 0x80 != 0);
-                        bus.write(addr, rotated);
-                        self.a &= rotated;
-                        self.set_zero_negative_flags(self.a);
-                        8
-                    }
-                    0x27 => {
-                        // RLA zero page - Rotate Left, AND
-                        let addr = self.read_byte(bus) as u16;
-                        let value = bus.read(addr);
-                        let carry = if self.status.contains(StatusFlags::CARRY) {
-                            1
-                        } else {
-                            0
-                        };
-                        let rotated = (value << 1) | carry;
-                        self.status.set(StatusFlags::CARRY, value & 0x80 != 0);
-                        bus.write(addr, rotated);
-                        self.a &= rotated;
-                        self.set_zero_negative_flags(self.a);
-                        5
-                    }
-                    0x2B => {
-                        // ANC immediate - AND, Copy N to C (duplicate of 0x0B)
-                        let value = self.read_byte(bus);
-                        self.a &= value;
-                        self.set_zero_negative_flags(self.a);
-                        self.status.set(
-                            StatusFlags::CARRY,
-                            self.status.contains(StatusFlags::NEGATIVE),
-                        );
-                        2
-                    }
-                    0x2F => {
-                        // RLA absolute - Rotate Left, AND
-                        let addr = self.read_word(bus);
-                        let value = bus.read(addr);
-                        let carry = if self.status.contains(StatusFlags::CARRY) {
-                            1
-                        } else {
-                            0
-                        };
-                        let rotated = (value << 1) | carry;
-                        self.status.set(StatusFlags::CARRY, value & 0x80 != 0);
-                        bus.write(addr, rotated);
-                        self.a &= rotated;
-                        self.set_zero_negative_flags(self.a);
-                        6
-                    }
-                    0x33 => {
-                        // RLA (indirect),Y - Rotate Left, AND
-                        let (addr, _page_crossed) = self.get_indirect_indexed_addr(bus);
-                        let value = bus.read(addr);
-                        let carry = if self.status.contains(StatusFlags::CARRY) {
-                            1
-                        } else {
-                            0
-                        };
-                        let rotated = (value << 1) | carry;
-                        self.status.set(StatusFlags::CARRY, value & 0x80 != 0);
-                        bus.write(addr, rotated);
-                        self.a &= rotated;
-                        self.set_zero_negative_flags(self.a);
-                        8
-                    }
-                    0x37 => {
-                        // RLA zero page,X - Rotate Left, AND
-                        let addr = self.get_zero_page_x_addr(bus);
-                        let value = bus.read(addr);
-                        let carry = if self.status.contains(StatusFlags::CARRY) {
-                            1
-                        } else {
-                            0
-                        };
-                        let rotated = (value << 1) | carry;
-                        self.status.set(StatusFlags::CARRY, value & 0x80 != 0);
-                        bus.write(addr, rotated);
-                        self.a &= rotated;
-                        self.set_zero_negative_flags(self.a);
-                        6
-                    }
-                    0x3B => {
-                        // RLA absolute,Y - Rotate Left, AND
-                        let addr = self.read_word(bus);
-                        let effective_addr = addr.wrapping_add(self.y as u16);
-                        let value = bus.read(effective_addr);
-                        let carry = if self.status.contains(StatusFlags::CARRY) {
-                            1
-                        } else {
-                            0
-                        };
-                        let rotated = (value << 1) | carry;
-                        self.status.set(StatusFlags::CARRY, value & 0x80 != 0);
-                        bus.write(effective_addr, rotated);
-                        self.a &= rotated;
-                        self.set_zero_negative_flags(self.a);
-                        7
-                    }
-                    0x6B => {
-                        // ARR immediate - AND with accumulator, then rotate right
-                        let value = self.read_byte(bus);
-                        self.a &= value;
-                        let carry = if self.status.contains(StatusFlags::CARRY) {
-                            0x80
-                        } else {
-                            0
-                        };
-                        let result = (self.a >> 1) | carry;
-                        self.status.set(StatusFlags::CARRY, self.a & 0x01 != 0);
-                        self.status.set(
-                            StatusFlags::OVERFLOW,
-                            ((result ^ (result << 1)) & 0x40) != 0,
-                        );
-                        self.a = result;
-                        self.set_zero_negative_flags(self.a);
-                        2
-                    }
-                    0x73 => {
-                        // RRA (indirect),Y - Rotate Right, Add
-                        let (addr, _page_crossed) = self.get_indirect_indexed_addr(bus);
-                        let value = bus.read(addr);
-                        let carry = if self.status.contains(StatusFlags::CARRY) {
-                            0x80
-                        } else {
-                            0
-                        };
-                        let rotated = (value >> 1) | carry;
-                        self.status.set(StatusFlags::CARRY, value & 0x01 != 0);
-                        bus.write(addr, rotated);
-                        self.adc(rotated);
-                        8
-                    }
-                    0x7B => {
-                        // RRA absolute,Y - Rotate Right, Add
-                        let addr = self.read_word(bus);
-                        let effective_addr = addr.wrapping_add(self.y as u16);
-                        let value = bus.read(effective_addr);
-                        let carry = if self.status.contains(StatusFlags::CARRY) {
-                            0x80
-                        } else {
-                            0
-                        };
-                        let rotated = (value >> 1) | carry;
-                        self.status.set(StatusFlags::CARRY, value & 0x01 != 0);
-                        bus.write(effective_addr, rotated);
-                        self.adc(rotated);
-                        7
-                    }
-                    0xE3 => {
-                        // ISC (indirect,X) - Increment, Subtract with Carry
-                        let addr = self.get_indexed_indirect_addr(bus);
-                        let value = bus.read(addr);
-                        let incremented = value.wrapping_add(1);
-                        bus.write(addr, incremented);
-                        self.sbc(incremented);
-                        8
-                    }
-                    0xF7 => {
-                        // ISC zero page,X - Increment, Subtract with Carry
-                        let addr = self.get_zero_page_x_addr(bus);
-                        let value = bus.read(addr);
-                        let incremented = value.wrapping_add(1);
-                        bus.write(addr, incremented);
-                        self.sbc(incremented);
-                        6
-                    }
-                    0xEF => {
-                        // ISC absolute - Increment, Subtract with Carry
-                        let addr = self.read_word(bus);
-                        let value = bus.read(addr);
-                        let incremented = value.wrapping_add(1);
-                        bus.write(addr, incremented);
-                        self.sbc(incremented);
-                        6
-                    }
-                    0x9F => {
-                        // SHY absolute,X - Store Y AND (high byte of address + 1)
-                        // Store Y AND (high byte of address + 1)
-                        let addr = self.read_word(bus); // Read absolute address
-                        let effective_addr = addr.wrapping_add(self.x as u16);
-                        let high_byte = (effective_addr >> 8) as u8;
-                        let store_value = self.y & high_byte.wrapping_add(1);
-                        bus.write(effective_addr, store_value);
-                        5 // 5 cycles for absolute,X with write
-                    }
-                    0xFF => {
-                        // ISC absolute,X - unofficial opcode (duplicate implementation)
-                        let addr = self.read_word(bus);
-                        let effective_addr = addr.wrapping_add(self.x as u16);
-                        let value = bus.read(effective_addr);
-                        let incremented = value.wrapping_add(1);
-                        bus.write(effective_addr, incremented);
-                        self.sbc(incremented);
-                        7
-                    }
-                    // Additional unofficial opcodes
-                    0x3F => {
-                        // RLA absolute,X - Rotate Left, AND
-                        let addr = self.read_word(bus);
-                        let effective_addr = addr.wrapping_add(self.x as u16);
-                        let value = bus.read(effective_addr);
-                        let carry = if self.status.contains(StatusFlags::CARRY) {
-                            1
-                        } else {
-                            0
-                        };
-                        let rotated = (value << 1) | carry;
-                        self.status.set(StatusFlags::CARRY, value & 0x80 != 0);
-                        bus.write(effective_addr, rotated);
-                        self.a &= rotated;
-                        self.set_zero_negative_flags(self.a);
-                        7
-                    }
-                    0x4B => {
-                        // ALR immediate - AND + LSR
-                        let value = self.read_byte(bus);
-                        self.a &= value;
-                        self.status.set(StatusFlags::CARRY, self.a & 0x01 != 0);
-                        self.a >>= 1;
-                        self.set_zero_negative_flags(self.a);
-                        2
-                    }
-                    0x77 => {
-                        // RRA zero page,X - Rotate Right, Add
-                        let addr = self.get_zero_page_x_addr(bus);
-                        let value = bus.read(addr);
-                        let carry = if self.status.contains(StatusFlags::CARRY) {
-                            0x80
-                        } else {
-                            0
-                        };
-                        let rotated = (value >> 1) | carry;
-                        self.status.set(StatusFlags::CARRY, value & 0x01 != 0);
-                        bus.write(addr, rotated);
-                        self.adc(rotated);
-                        6
-                    }
-                    0x93 => {
-                        // SHA/AHX (indirect),Y - Store A AND X AND (H+1) [UNSTABLE]
-                        // WARNING: This is an unstable instruction - behavior varies between 6502 chips
-                        // Official spec: A & X & (high byte of target address + 1) → memory
-                        let (addr, _) = self.get_indirect_indexed_addr(bus);
-                        let high_byte = (addr >> 8) as u8;
-                        let value = self.a & self.x & high_byte.wrapping_add(1);
-                        bus.write(addr, value);
-                        6
-                    }
-                    0x97 => {
-                        // SAX zero page,Y - Store A AND X
-                        let addr = self.get_zero_page_y_addr(bus);
-                        let value = self.a & self.x;
-                        bus.write(addr, value);
-                        4
-                    }
-                    0x9B => {
-                        // TAS/XAS absolute,Y - Transfer A AND X to SP, Store A AND X AND (H+1) [UNSTABLE]
-                        // WARNING: This is an unstable instruction - behavior varies between 6502 chips
-                        // WARNING: This can corrupt the stack pointer!
-                        let addr = self.read_word(bus);
-                        let effective_addr = addr.wrapping_add(self.y as u16);
-                        // Only update SP if result is reasonable (>= 0x80)
-                        let new_sp = self.a & self.x;
-                        if new_sp >= 0x80 {
-                            self.sp = new_sp;
-                        }
-                        let high_byte = (addr >> 8) as u8;
-                        let value = self.a & self.x & high_byte.wrapping_add(1);
-                        bus.write(effective_addr, value);
-                        5
-                    }
-                    0x9E => {
-                        // SHX absolute,Y - Store X AND (high byte + 1) [UNSTABLE]
-                        let addr = self.read_word(bus);
-                        let effective_addr = addr.wrapping_add(self.y as u16);
-                        let high_byte = (addr >> 8) as u8;
-                        let value = self.x & high_byte.wrapping_add(1);
-                        bus.write(effective_addr, value);
-                        5
-                    }
-                    0xD3 => {
-                        // DCP (indirect),Y - Decrement, Compare
-                        let (addr, _) = self.get_indirect_indexed_addr(bus);
-                        let value = bus.read(addr);
-                        let decremented = value.wrapping_sub(1);
-                        bus.write(addr, decremented);
-                        self.compare(self.a, decremented);
-                        8
-                    }
-                    0xD7 => {
-                        // DCP zero page,X - Decrement, Compare
-                        let addr = self.get_zero_page_x_addr(bus);
-                        let value = bus.read(addr);
-                        let decremented = value.wrapping_sub(1);
-                        bus.write(addr, decremented);
-                        self.compare(self.a, decremented);
-                        6
-                    }
-                    0xDB => {
-                        // DCP absolute,Y - Decrement, Compare
-                        let addr = self.read_word(bus);
-                        let effective_addr = addr.wrapping_add(self.y as u16);
-                        let value = bus.read(effective_addr);
-                        let decremented = value.wrapping_sub(1);
-                        bus.write(effective_addr, decremented);
-                        self.compare(self.a, decremented);
-                        7
-                    }
-                    0xDF => {
-                        // DCP absolute,X - Decrement, Compare
-                        let addr = self.read_word(bus);
-                        let effective_addr = addr.wrapping_add(self.x as u16);
-                        let value = bus.read(effective_addr);
-                        let decremented = value.wrapping_sub(1);
-                        bus.write(effective_addr, decremented);
-                        self.compare(self.a, decremented);
-                        7
-                    }
-                    0xE7 => {
-                        // ISC zero page - Increment, Subtract with Carry
-                        let addr = self.read_byte(bus) as u16;
-                        let value = bus.read(addr);
-                        let incremented = value.wrapping_add(1);
-                        bus.write(addr, incremented);
-                        self.sbc(incremented);
-                        5
-                    }
-                    0xEB => {
-                        // SBC immediate (unofficial duplicate of 0xE9)
-                        let value = self.read_byte(bus);
-                        self.sbc(value);
-                        2
-                    }
-                    // More unofficial opcodes
-                    0x1B => {
-                        // SLO absolute,Y - Shift Left, OR
-                        let addr = self.read_word(bus);
-                        let effective_addr = addr.wrapping_add(self.y as u16);
-                        let value = bus.read(effective_addr);
-                        self.status.set(StatusFlags::CARRY, value & 0x80 != 0);
-                        let shifted = value << 1;
-                        bus.write(effective_addr, shifted);
-                        self.a |= shifted;
-                        self.set_zero_negative_flags(self.a);
-                        7
-                    }
-                    0x5F => {
-                        // SRE absolute,X - Shift Right, EOR
-                        let addr = self.read_word(bus);
-                        let effective_addr = addr.wrapping_add(self.x as u16);
-                        let value = bus.read(effective_addr);
-                        let shifted = value >> 1;
-                        bus.write(effective_addr, shifted);
-                        self.status.set(StatusFlags::CARRY, value & 0x01 != 0);
-                        self.a ^= shifted;
-                        self.set_zero_negative_flags(self.a);
-                        7
-                    }
-                    0x63 => {
-                        // RRA (indirect,X) - Rotate Right, Add
-                        let base = self.read_byte(bus) as u16;
-                        let addr_low = (base.wrapping_add(self.x as u16)) & 0xFF;
-                        let addr_high = (addr_low + 1) & 0xFF;
-                        let effective_addr =
-                            bus.read(addr_low) as u16 | ((bus.read(addr_high) as u16) << 8);
-                        let value = bus.read(effective_addr);
-                        let carry = if self.status.contains(StatusFlags::CARRY) {
-                            0x80
-                        } else {
-                            0
-                        };
-                        let rotated = (value >> 1) | carry;
-                        self.status.set(StatusFlags::CARRY, value & 0x01 != 0);
-                        bus.write(effective_addr, rotated);
-                        self.adc(rotated);
-                        8
-                    }
-                    0x67 => {
-                        // RRA zero page - Rotate Right, Add
-                        let addr = self.read_byte(bus) as u16;
-                        let value = bus.read(addr);
-                        let carry = if self.status.contains(StatusFlags::CARRY) {
-                            0x80
-                        } else {
-                            0
-                        };
-                        let rotated = (value >> 1) | carry;
-                        self.status.set(StatusFlags::CARRY, value & 0x01 != 0);
-                        bus.write(addr, rotated);
-                        self.adc(rotated);
-                        5
-                    }
-                    0x6F => {
-                        // RRA absolute - Rotate Right, Add
-                        let addr = self.read_word(bus);
-                        let value = bus.read(addr);
-                        let carry = if self.status.contains(StatusFlags::CARRY) {
-                            0x80
-                        } else {
-                            0
-                        };
-                        let rotated = (value >> 1) | carry;
-                        self.status.set(StatusFlags::CARRY, value & 0x01 != 0);
-                        bus.write(addr, rotated);
-                        self.adc(rotated);
-                        6
-                    }
-                    0xBB => {
-                        // LAS absolute,Y - Load A, X, S with memory AND S
-                        let addr = self.read_word(bus);
-                        let effective_addr = addr.wrapping_add(self.y as u16);
-                        let value = bus.read(effective_addr) & self.sp;
-                        self.a = value;
-                        self.x = value;
-                        // Only update SP if result is reasonable (>= 0x80)
-                        if value >= 0x80 {
-                            self.sp = value;
-                        }
-                        self.set_zero_negative_flags(value);
-                        4
-                    }
-                    0xC3 => {
-                        // DCP (indirect,X) - Decrement, Compare
-                        let addr = self.get_indexed_indirect_addr(bus);
-                        let value = bus.read(addr);
-                        let decremented = value.wrapping_sub(1);
-                        bus.write(addr, decremented);
-                        self.compare(self.a, decremented);
-                        8
-                    }
-                    0xC7 => {
-                        // DCP zero page - Decrement, Compare
-                        let addr = self.read_byte(bus) as u16;
-                        let value = bus.read(addr);
-                        let decremented = value.wrapping_sub(1);
-                        bus.write(addr, decremented);
-                        self.compare(self.a, decremented);
-                        5
-                    }
-                    0xCF => {
-                        // DCP absolute - Decrement, Compare
-                        let addr = self.read_word(bus);
-                        let value = bus.read(addr);
-                        let decremented = value.wrapping_sub(1);
-                        bus.write(addr, decremented);
-                        self.compare(self.a, decremented);
-                        6
-                    }
-                    0xCB => {
-                        // AXS immediate - AND X register with accumulator, subtract immediate
-                        let value = self.read_byte(bus);
-                        let and_result = self.a & self.x;
-                        let result = and_result.wrapping_sub(value);
-                        self.x = result;
-                        self.status.set(StatusFlags::CARRY, and_result >= value);
-                        self.set_zero_negative_flags(result);
-                        2
-                    }
-                    0xF3 => {
-                        // ISC (indirect),Y - Increment, Subtract with Carry
-                        let (addr, _) = self.get_indirect_indexed_addr(bus);
-                        let value = bus.read(addr);
-                        let incremented = value.wrapping_add(1);
-                        bus.write(addr, incremented);
-                        self.sbc(incremented);
-                        8
-                    }
-                    _ => {
-                        log::error!(
-                            "Halting on truly unknown opcode: 0x{:02X} at PC: 0x{:04X}",
-                            opcode,
-                            self.pc.wrapping_sub(1)
-                        );
-                        1 // Minimal cycles to avoid complete freeze
-                    }
-                }
-            }
+            _ => self.execute_unofficial(opcode, bus),
         }
     }
 
@@ -1150,13 +452,13 @@ impl Cpu {
     #[inline]
     fn branch(&mut self, bus: &mut dyn CpuBus, condition: bool) -> u8 {
         // Branch instructions: read offset byte and conditionally branch
-        let branch_pc = self.pc.wrapping_sub(1); // PC where branch instruction was located
         let offset = self.read_byte(bus) as i8;
         if condition {
             let new_pc = self.pc.wrapping_add(offset as u16);
 
-            // Page crossing check should use the branch instruction PC vs destination PC
-            let cycles = if (branch_pc & 0xFF00) != (new_pc & 0xFF00) {
+            // The page crossing is measured from the next instruction, which
+            // differs from the branch's own page when its operand ends one
+            let cycles = if (self.pc & 0xFF00) != (new_pc & 0xFF00) {
                 4
             } else {
                 3
@@ -1169,8 +471,19 @@ impl Cpu {
     }
 }
 
+/// Everything the CPU sees of the console: memory-mapped reads and writes
+/// plus the hooks the debugger and the bus's cycle accounting need.
 pub trait CpuBus {
     fn on_reset(&mut self) {}
+    /// Called with the opcode address and the CPU cycle count before each
+    /// instruction executes.
+    fn on_instruction(&mut self, _pc: u16, _cycle: u64) {}
     fn read(&mut self, addr: u16) -> u8;
     fn write(&mut self, addr: u16, data: u8);
+    /// Read-modify-write instructions write the unmodified value back on the
+    /// cycle before the result.
+    fn write_rmw(&mut self, addr: u16, old: u8, new: u8) {
+        self.write(addr, old);
+        self.write(addr, new);
+    }
 }
@@ -0,0 +1,570 @@
+// Table-driven model of the official 6502 instruction set (2A03 flavour: no
+// decimal mode), written independently of `Cpu` so the two can be diffed.
+// `diff_against_reference` is the entry point of the `cpu_diff` fuzz target.
+use super::{Cpu, CpuBus, StatusFlags};
+use crate::crc32::crc32;
+use crate::rng::EmuRng;
+
+const C: u8 = 0x01;
+const Z: u8 = 0x02;
+const I: u8 = 0x04;
+const V: u8 = 0x40;
+const N: u8 = 0x80;
+/// B and the unused bit only exist on the stack.
+const FLAG_MASK: u8 = 0xCF;
+
+/// Instructions a fuzz run executes before it is considered done.
+pub const MAX_STEPS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Imp,
+    Acc,
+    Imm,
+    Zp,
+    Zpx,
+    Zpy,
+    Abs,
+    Absx,
+    Absy,
+    Ind,
+    Indx,
+    Indy,
+    Rel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[rustfmt::skip]
+enum Op {
+    Adc, And, Asl, Bcc, Bcs, Beq, Bit, Bmi, Bne, Bpl, Brk, Bvc, Bvs, Clc,
+    Cld, Cli, Clv, Cmp, Cpx, Cpy, Dec, Dex, Dey, Eor, Inc, Inx, Iny, Jmp,
+    Jsr, Lda, Ldx, Ldy, Lsr, Nop, Ora, Pha, Php, Pla, Plp, Rol, Ror, Rti,
+    Rts, Sbc, Sec, Sed, Sei, Sta, Stx, Sty, Tax, Tay, Tsx, Txa, Txs, Tya,
+}
+
+use Mode::*;
+use Op::*;
+
+/// (opcode, instruction, addressing mode, base cycles)
+#[rustfmt::skip]
+const OFFICIAL: [(u8, Op, Mode, u8); 151] = [
+    (0x69, Adc, Imm, 2), (0x65, Adc, Zp, 3), (0x75, Adc, Zpx, 4), (0x6D, Adc, Abs, 4),
+    (0x7D, Adc, Absx, 4), (0x79, Adc, Absy, 4), (0x61, Adc, Indx, 6), (0x71, Adc, Indy, 5),
+    (0x29, And, Imm, 2), (0x25, And, Zp, 3), (0x35, And, Zpx, 4), (0x2D, And, Abs, 4),
+    (0x3D, And, Absx, 4), (0x39, And, Absy, 4), (0x21, And, Indx, 6), (0x31, And, Indy, 5),
+    (0x0A, Asl, Acc, 2), (0x06, Asl, Zp, 5), (0x16, Asl, Zpx, 6), (0x0E, Asl, Abs, 6),
+    (0x1E, Asl, Absx, 7),
+    (0x90, Bcc, Rel, 2), (0xB0, Bcs, Rel, 2), (0xF0, Beq, Rel, 2), (0x30, Bmi, Rel, 2),
+    (0xD0, Bne, Rel, 2), (0x10, Bpl, Rel, 2), (0x50, Bvc, Rel, 2), (0x70, Bvs, Rel, 2),
+    (0x24, Bit, Zp, 3), (0x2C, Bit, Abs, 4),
+    (0x00, Brk, Imp, 7),
+    (0x18, Clc, Imp, 2), (0xD8, Cld, Imp, 2), (0x58, Cli, Imp, 2), (0xB8, Clv, Imp, 2),
+    (0xC9, Cmp, Imm, 2), (0xC5, Cmp, Zp, 3), (0xD5, Cmp, Zpx, 4), (0xCD, Cmp, Abs, 4),
+    (0xDD, Cmp, Absx, 4), (0xD9, Cmp, Absy, 4), (0xC1, Cmp, Indx, 6), (0xD1, Cmp, Indy, 5),
+    (0xE0, Cpx, Imm, 2), (0xE4, Cpx, Zp, 3), (0xEC, Cpx, Abs, 4),
+    (0xC0, Cpy, Imm, 2), (0xC4, Cpy, Zp, 3), (0xCC, Cpy, Abs, 4),
+    (0xC6, Dec, Zp, 5), (0xD6, Dec, Zpx, 6), (0xCE, Dec, Abs, 6), (0xDE, Dec, Absx, 7),
+    (0xCA, Dex, Imp, 2), (0x88, Dey, Imp, 2),
+    (0x49, Eor, Imm, 2), (0x45, Eor, Zp, 3), (0x55, Eor, Zpx, 4), (0x4D, Eor, Abs, 4),
+    (0x5D, Eor, Absx, 4), (0x59, Eor, Absy, 4), (0x41, Eor, Indx, 6), (0x51, Eor, Indy, 5),
+    (0xE6, Inc, Zp, 5), (0xF6, Inc, Zpx, 6), (0xEE, Inc, Abs, 6), (0xFE, Inc, Absx, 7),
+    (0xE8, Inx, Imp, 2), (0xC8, Iny, Imp, 2),
+    (0x4C, Jmp, Abs, 3), (0x6C, Jmp, Ind, 5), (0x20, Jsr, Abs, 6),
+    (0xA9, Lda, Imm, 2), (0xA5, Lda, Zp, 3), (0xB5, Lda, Zpx, 4), (0xAD, Lda, Abs, 4),
+    (0xBD, Lda, Absx, 4), (0xB9, Lda, Absy, 4), (0xA1, Lda, Indx, 6), (0xB1, Lda, Indy, 5),
+    (0xA2, Ldx, Imm, 2), (0xA6, Ldx, Zp, 3), (0xB6, Ldx, Zpy, 4), (0xAE, Ldx, Abs, 4),
+    (0xBE, Ldx, Absy, 4),
+    (0xA0, Ldy, Imm, 2), (0xA4, Ldy, Zp, 3), (0xB4, Ldy, Zpx, 4), (0xAC, Ldy, Abs, 4),
+    (0xBC, Ldy, Absx, 4),
+    (0x4A, Lsr, Acc, 2), (0x46, Lsr, Zp, 5), (0x56, Lsr, Zpx, 6), (0x4E, Lsr, Abs, 6),
+    (0x5E, Lsr, Absx, 7),
+    (0xEA, Nop, Imp, 2),
+    (0x09, Ora, Imm, 2), (0x05, Ora, Zp, 3), (0x15, Ora, Zpx, 4), (0x0D, Ora, Abs, 4),
+    (0x1D, Ora, Absx, 4), (0x19, Ora, Absy, 4), (0x01, Ora, Indx, 6), (0x11, Ora, Indy, 5),
+    (0x48, Pha, Imp, 3), (0x08, Php, Imp, 3), (0x68, Pla, Imp, 4), (0x28, Plp, Imp, 4),
+    (0x2A, Rol, Acc, 2), (0x26, Rol, Zp, 5), (0x36, Rol, Zpx, 6), (0x2E, Rol, Abs, 6),
+    (0x3E, Rol, Absx, 7),
+    (0x6A, Ror, Acc, 2), (0x66, Ror, Zp, 5), (0x76, Ror, Zpx, 6), (0x6E, Ror, Abs, 6),
+    (0x7E, Ror, Absx, 7),
+    (0x40, Rti, Imp, 6), (0x60, Rts, Imp, 6),
+    (0xE9, Sbc, Imm, 2), (0xE5, Sbc, Zp, 3), (0xF5, Sbc, Zpx, 4), (0xED, Sbc, Abs, 4),
+    (0xFD, Sbc, Absx, 4), (0xF9, Sbc, Absy, 4), (0xE1, Sbc, Indx, 6), (0xF1, Sbc, Indy, 5),
+    (0x38, Sec, Imp, 2), (0xF8, Sed, Imp, 2), (0x78, Sei, Imp, 2),
+    (0x85, Sta, Zp, 3), (0x95, Sta, Zpx, 4), (0x8D, Sta, Abs, 4), (0x9D, Sta, Absx, 5),
+    (0x99, Sta, Absy, 5), (0x81, Sta, Indx, 6), (0x91, Sta, Indy, 6),
+    (0x86, Stx, Zp, 3), (0x96, Stx, Zpy, 4), (0x8E, Stx, Abs, 4),
+    (0x84, Sty, Zp, 3), (0x94, Sty, Zpx, 4), (0x8C, Sty, Abs, 4),
+    (0xAA, Tax, Imp, 2), (0xA8, Tay, Imp, 2), (0xBA, Tsx, Imp, 2), (0x8A, Txa, Imp, 2),
+    (0x9A, Txs, Imp, 2), (0x98, Tya, Imp, 2),
+];
+
+const TABLE: [Option<(Op, Mode, u8)>; 256] = {
+    let mut table = [None; 256];
+    let mut i = 0;
+    while i < OFFICIAL.len() {
+        let (opcode, op, mode, cycles) = OFFICIAL[i];
+        table[opcode as usize] = Some((op, mode, cycles));
+        i += 1;
+    }
+    table
+};
+
+/// Whether `opcode` is one of the 151 documented instructions.
+pub fn is_official(opcode: u8) -> bool {
+    TABLE[opcode as usize].is_some()
+}
+
+/// Registers of the reference model. `p` keeps bits 4-5 clear.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReferenceCpu {
+    pub a: u8,
+    pub x: u8,
+    pub y: u8,
+    pub sp: u8,
+    pub pc: u16,
+    pub p: u8,
+}
+
+impl ReferenceCpu {
+    fn fetch(&mut self, mem: &[u8]) -> u8 {
+        let value = mem[self.pc as usize];
+        self.pc = self.pc.wrapping_add(1);
+        value
+    }
+
+    fn fetch_word(&mut self, mem: &[u8]) -> u16 {
+        let low = self.fetch(mem) as u16;
+        (self.fetch(mem) as u16) << 8 | low
+    }
+
+    fn push(&mut self, mem: &mut [u8], value: u8) {
+        mem[0x100 | self.sp as usize] = value;
+        self.sp = self.sp.wrapping_sub(1);
+    }
+
+    fn pull(&mut self, mem: &[u8]) -> u8 {
+        self.sp = self.sp.wrapping_add(1);
+        mem[0x100 | self.sp as usize]
+    }
+
+    fn set_flag(&mut self, flag: u8, on: bool) {
+        if on {
+            self.p |= flag;
+        } else {
+            self.p &= !flag;
+        }
+    }
+
+    fn set_zn(&mut self, value: u8) {
+        self.set_flag(Z, value == 0);
+        self.set_flag(N, value & 0x80 != 0);
+    }
+
+    fn compare(&mut self, register: u8, value: u8) {
+        self.set_flag(C, register >= value);
+        self.set_zn(register.wrapping_sub(value));
+    }
+
+    fn add(&mut self, value: u8) {
+        let sum = self.a as u16 + value as u16 + (self.p & C) as u16;
+        let result = sum as u8;
+        self.set_flag(C, sum > 0xFF);
+        self.set_flag(V, (self.a ^ result) & (value ^ result) & 0x80 != 0);
+        self.a = result;
+        self.set_zn(result);
+    }
+
+    /// Effective address of the operand and whether indexing crossed a page.
+    fn operand_addr(&mut self, mem: &[u8], mode: Mode) -> (u16, bool) {
+        let indexed = |base: u16, index: u8| {
+            let addr = base.wrapping_add(index as u16);
+            (addr, addr & 0xFF00 != base & 0xFF00)
+        };
+        let zp_word =
+            |ptr: u8| mem[ptr as usize] as u16 | (mem[ptr.wrapping_add(1) as usize] as u16) << 8;
+        match mode {
+            Imm => {
+                let addr = self.pc;
+                self.pc = self.pc.wrapping_add(1);
+                (addr, false)
+            }
+            Zp => (self.fetch(mem) as u16, false),
+            Zpx => (self.fetch(mem).wrapping_add(self.x) as u16, false),
+            Zpy => (self.fetch(mem).wrapping_add(self.y) as u16, false),
+            Abs => (self.fetch_word(mem), false),
+            Absx => {
+                let base = self.fetch_word(mem);
+                indexed(base, self.x)
+            }
+            Absy => {
+                let base = self.fetch_word(mem);
+                indexed(base, self.y)
+            }
+            Ind => {
+                // The pointer's high byte is read without carrying into the page
+                let ptr = self.fetch_word(mem);
+                let high = (ptr & 0xFF00) | (ptr.wrapping_add(1) & 0x00FF);
+                (
+                    mem[ptr as usize] as u16 | (mem[high as usize] as u16) << 8,
+                    false,
+                )
+            }
+            Indx => {
+                let ptr = self.fetch(mem).wrapping_add(self.x);
+                (zp_word(ptr), false)
+            }
+            Indy => {
+                let ptr = self.fetch(mem);
+                indexed(zp_word(ptr), self.y)
+            }
+            Imp | Acc | Rel => (0, false),
+        }
+    }
+
+    /// Run one instruction on a flat 64KB memory. Returns its cycle count,
+    /// or `None` (leaving state untouched) for an undocumented opcode.
+    pub fn step(&mut self, mem: &mut [u8]) -> Option<u8> {
+        let (op, mode, mut cycles) = TABLE[mem[self.pc as usize] as usize]?;
+        self.pc = self.pc.wrapping_add(1);
+        let (addr, crossed) = self.operand_addr(mem, mode);
+        let load = |mem: &[u8]| mem[addr as usize];
+
+        if crossed && matches!(op, Adc | And | Cmp | Eor | Lda | Ldx | Ldy | Ora | Sbc) {
+            cycles += 1;
+        }
+
+        // Shifts and increments work on A or memory
+        let modify = |cpu: &mut Self, mem: &mut [u8], f: &dyn Fn(&mut Self, u8) -> u8| {
+            if mode == Acc {
+                cpu.a = f(cpu, cpu.a);
+                cpu.set_zn(cpu.a);
+            } else {
+                let value = f(cpu, mem[addr as usize]);
+                mem[addr as usize] = value;
+                cpu.set_zn(value);
+            }
+        };
+
+        let branch = match op {
+            Bcc => Some(self.p & C == 0),
+            Bcs => Some(self.p & C != 0),
+            Bne => Some(self.p & Z == 0),
+            Beq => Some(self.p & Z != 0),
+            Bpl => Some(self.p & N == 0),
+            Bmi => Some(self.p & N != 0),
+            Bvc => Some(self.p & V == 0),
+            Bvs => Some(self.p & V != 0),
+            _ => None,
+        };
+        if let Some(taken) = branch {
+            let offset = self.fetch(mem) as i8;
+            if taken {
+                let target = self.pc.wrapping_add(offset as u16);
+                cycles += if target & 0xFF00 != self.pc & 0xFF00 {
+                    2
+                } else {
+                    1
+                };
+                self.pc = target;
+            }
+            return Some(cycles);
+        }
+
+        match op {
+            Adc => self.add(load(mem)),
+            Sbc => self.add(!load(mem)),
+            And => {
+                self.a &= load(mem);
+                self.set_zn(self.a);
+            }
+            Ora => {
+                self.a |= load(mem);
+                self.set_zn(self.a);
+            }
+            Eor => {
+                self.a ^= load(mem);
+                self.set_zn(self.a);
+            }
+            Cmp => self.compare(self.a, load(mem)),
+            Cpx => self.compare(self.x, load(mem)),
+            Cpy => self.compare(self.y, load(mem)),
+            Bit => {
+                let value = load(mem);
+                self.set_flag(Z, self.a & value == 0);
+                self.set_flag(V, value & 0x40 != 0);
+                self.set_flag(N, value & 0x80 != 0);
+            }
+            Asl => modify(self, mem, &|cpu, v| {
+                cpu.set_flag(C, v & 0x80 != 0);
+                v << 1
+            }),
+            Lsr => modify(self, mem, &|cpu, v| {
+                cpu.set_flag(C, v & 0x01 != 0);
+                v >> 1
+            }),
+            Rol => modify(self, mem, &|cpu, v| {
+                let carry = cpu.p & C;
+                cpu.set_flag(C, v & 0x80 != 0);
+                v << 1 | carry
+            }),
+            Ror => modify(self, mem, &|cpu, v| {
+                let carry = (cpu.p & C) << 7;
+                cpu.set_flag(C, v & 0x01 != 0);
+                v >> 1 | carry
+            }),
+            Inc => modify(self, mem, &|_, v| v.wrapping_add(1)),
+            Dec => modify(self, mem, &|_, v| v.wrapping_sub(1)),
+            Lda => {
+                self.a = load(mem);
+                self.set_zn(self.a);
+            }
+            Ldx => {
+                self.x = load(mem);
+                self.set_zn(self.x);
+            }
+            Ldy => {
+                self.y = load(mem);
+                self.set_zn(self.y);
+            }
+            Sta => mem[addr as usize] = self.a,
+            Stx => mem[addr as usize] = self.x,
+            Sty => mem[addr as usize] = self.y,
+            Inx => {
+                self.x = self.x.wrapping_add(1);
+                self.set_zn(self.x);
+            }
+            Iny => {
+                self.y = self.y.wrapping_add(1);
+                self.set_zn(self.y);
+            }
+            Dex => {
+                self.x = self.x.wrapping_sub(1);
+                self.set_zn(self.x);
+            }
+            Dey => {
+                self.y = self.y.wrapping_sub(1);
+                self.set_zn(self.y);
+            }
+            Tax => {
+                self.x = self.a;
+                self.set_zn(self.x);
+            }
+            Tay => {
+                self.y = self.a;
+                self.set_zn(self.y);
+            }
+            Txa => {
+                self.a = self.x;
+                self.set_zn(self.a);
+            }
+            Tya => {
+                self.a = self.y;
+                self.set_zn(self.a);
+            }
+            Tsx => {
+                self.x = self.sp;
+                self.set_zn(self.x);
+            }
+            Txs => self.sp = self.x,
+            Clc => self.p &= !C,
+            Sec => self.p |= C,
+            Cli => self.p &= !I,
+            Sei => self.p |= I,
+            Cld => self.p &= !0x08,
+            Sed => self.p |= 0x08,
+            Clv => self.p &= !V,
+            Pha => self.push(mem, self.a),
+            Php => self.push(mem, self.p | 0x30),
+            Pla => {
+                self.a = self.pull(mem);
+                self.set_zn(self.a);
+            }
+            Plp => self.p = self.pull(mem) & FLAG_MASK,
+            Jmp => self.pc = addr,
+            Jsr => {
+                let ret = self.pc.wrapping_sub(1);
+                self.push(mem, (ret >> 8) as u8);
+                self.push(mem, ret as u8);
+                self.pc = addr;
+            }
+            Rts => {
+                let low = self.pull(mem) as u16;
+                let high = self.pull(mem) as u16;
+                self.pc = (high << 8 | low).wrapping_add(1);
+            }
+            Rti => {
+                self.p = self.pull(mem) & FLAG_MASK;
+                let low = self.pull(mem) as u16;
+                let high = self.pull(mem) as u16;
+                self.pc = high << 8 | low;
+            }
+            Brk => {
+                // The byte after BRK is skipped
+                let ret = self.pc.wrapping_add(1);
+                self.push(mem, (ret >> 8) as u8);
+                self.push(mem, ret as u8);
+                self.push(mem, self.p | 0x30);
+                self.p |= I;
+                self.pc = mem[0xFFFE] as u16 | (mem[0xFFFF] as u16) << 8;
+            }
+            Nop => {}
+            Bcc | Bcs | Beq | Bmi | Bne | Bpl | Bvc | Bvs => unreachable!(),
+        }
+        Some(cycles)
+    }
+}
+
+struct FlatBus {
+    memory: Vec<u8>,
+    written: Vec<u16>,
+}
+
+impl CpuBus for FlatBus {
+    fn read(&mut self, addr: u16) -> u8 {
+        self.memory[addr as usize]
+    }
+
+    fn write(&mut self, addr: u16, data: u8) {
+        self.memory[addr as usize] = data;
+        self.written.push(addr);
+    }
+}
+
+fn first_difference(
+    actual: &[u8],
+    expected: &[u8],
+    addrs: impl Iterator<Item = usize>,
+) -> Option<String> {
+    addrs
+        .into_iter()
+        .find(|&addr| actual[addr] != expected[addr])
+        .map(|addr| {
+            format!(
+                "${:04X} = ${:02X}, reference ${:02X}",
+                addr, actual[addr], expected[addr]
+            )
+        })
+}
+
+/// Cases where `Cpu` departs from hardware on purpose: RTI's recovery from
+/// a nearly empty stack or a $0000/$FFFF return address, and the shortened
+/// JSR $8995 wait loop. A run stops there instead of reporting them.
+fn is_known_deviation(cpu: &ReferenceCpu, mem: &[u8]) -> bool {
+    let pc = cpu.pc as usize;
+    let stack = |offset: u8| mem[0x100 | cpu.sp.wrapping_add(offset) as usize] as u16;
+    match mem[pc] {
+        0x40 => cpu.sp < 0x20 || matches!(stack(2) | stack(3) << 8, 0x0000 | 0xFFFF),
+        0x20 => pc == 0x8974 && mem[0x8975] == 0x95 && mem[0x8976] == 0x89,
+        _ => false,
+    }
+}
+
+/// Run `program` from $8000 on both `Cpu` and the reference model and
+/// compare registers, flags, cycle counts and written memory after every
+/// instruction, then all of memory at the end. Memory and the starting
+/// registers are filled from a seed derived from `program`, so any input is
+/// a complete, reproducible case. Stops at the first undocumented opcode or
+/// after `MAX_STEPS`.
+pub fn diff_against_reference(program: &[u8]) -> Result<(), String> {
+    let mut rng = EmuRng::new(crc32(program) as u64);
+    let mut memory: Vec<u8> = (0..0x2000)
+        .flat_map(|_| rng.next_u64().to_le_bytes())
+        .collect();
+    let len = program.len().min(0x8000);
+    memory[0x8000..0x8000 + len].copy_from_slice(&program[..len]);
+
+    let mut reference = ReferenceCpu {
+        a: rng.next_u8(),
+        x: rng.next_u8(),
+        y: rng.next_u8(),
+        sp: rng.next_u8(),
+        pc: 0x8000,
+        p: rng.next_u8() & FLAG_MASK,
+    };
+    let mut cpu = Cpu::new();
+    cpu.a = reference.a;
+    cpu.x = reference.x;
+    cpu.y = reference.y;
+    cpu.sp = reference.sp;
+    cpu.pc = reference.pc;
+    cpu.status = StatusFlags::from_bits_truncate(reference.p | 0x20);
+    let mut reference_memory = memory.clone();
+    let mut bus = FlatBus {
+        memory,
+        written: Vec::new(),
+    };
+
+    let mut steps = 0;
+    while steps < MAX_STEPS {
+        let pc = reference.pc;
+        let opcode = reference_memory[pc as usize];
+        if is_known_deviation(&reference, &reference_memory) {
+            break;
+        }
+        let Some(expected_cycles) = reference.step(&mut reference_memory) else {
+            break;
+        };
+        bus.written.clear();
+        let cycles = cpu.step(&mut bus);
+
+        let actual = ReferenceCpu {
+            a: cpu.a,
+            x: cpu.x,
+            y: cpu.y,
+            sp: cpu.sp,
+            pc: cpu.pc,
+            p: cpu.status.bits() & FLAG_MASK,
+        };
+        let context = format!("step {} (${:02X} at ${:04X})", steps, opcode, pc);
+        if actual != reference {
+            return Err(format!(
+                "{}: registers {:02X?}, reference {:02X?}",
+                context, actual, reference
+            ));
+        }
+        if cycles != expected_cycles {
+            return Err(format!(
+                "{}: {} cycles, reference {}",
+                context, cycles, expected_cycles
+            ));
+        }
+        let written = bus.written.iter().map(|&addr| addr as usize);
+        if let Some(difference) = first_difference(&bus.memory, &reference_memory, written) {
+            return Err(format!("{}: {}", context, difference));
+        }
+        steps += 1;
+    }
+    // Catches writes the reference made that `Cpu` skipped
+    match first_difference(&bus.memory, &reference_memory, 0..0x10000) {
+        Some(difference) => Err(format!("after {} steps: {}", steps, difference)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_matches_reference_on_random_programs() {
+        // Official opcodes with random operands; the fuzz target covers the rest
+        let mut rng = EmuRng::new(0x6502);
+        for _ in 0..500 {
+            let mut program = Vec::new();
+            while program.len() < 3 * MAX_STEPS {
+                let (opcode, ..) = OFFICIAL[rng.next_u64() as usize % OFFICIAL.len()];
+                program.extend_from_slice(&[opcode, rng.next_u8(), rng.next_u8()]);
+            }
+            if let Err(mismatch) = diff_against_reference(&program) {
+                panic!("{}\nprogram: {:02X?}", mismatch, program);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reference_table_covers_official_opcodes() {
+        assert_eq!(TABLE.iter().filter(|entry| entry.is_some()).count(), 151);
+        assert!(is_official(0x6C));
+        assert!(!is_official(0xA7));
+    }
+}
@@ -24,6 +24,93 @@ mod additional_cpu_tests {
         assert_eq!(cpu.a, 0x0A);
     }
 
+    #[test]
+    fn test_decimal_flag_ignored_by_sbc_and_preserved() {
+        let (mut cpu, mut bus) = setup_cpu();
+        cpu.reset(&mut bus);
+        cpu.status.insert(StatusFlags::DECIMAL | StatusFlags::CARRY);
+        cpu.a = 0x10;
+
+        // SBC #$01 is binary: 0x0F, not BCD 0x09
+        bus.load_program(&[0xE9, 0x01], 0x8000);
+        cpu.pc = 0x8000;
+        cpu.step(&mut bus);
+        assert_eq!(cpu.a, 0x0F);
+        assert!(cpu.status.contains(StatusFlags::DECIMAL));
+
+        // CLD clears it
+        bus.load_program(&[0xD8], 0x8002);
+        cpu.step(&mut bus);
+        assert!(!cpu.status.contains(StatusFlags::DECIMAL));
+    }
+
+    #[test]
+    fn test_adc_sbc_flag_boundaries() {
+        // (opcode, A, operand, carry in, result, N V - - - - Z C)
+        let cases = [
+            (0x69, 0x7F, 0x01, false, 0x80, 0xC0),
+            (0x69, 0x7F, 0x00, true, 0x80, 0xC0),
+            (0x69, 0x80, 0xFF, false, 0x7F, 0x41),
+            (0x69, 0x80, 0x80, false, 0x00, 0x43),
+            (0x69, 0xFF, 0x00, true, 0x00, 0x03),
+            (0x69, 0xFF, 0xFF, true, 0xFF, 0x81),
+            (0xE9, 0x80, 0x01, true, 0x7F, 0x41),
+            (0xE9, 0x7F, 0xFF, true, 0x80, 0xC0),
+            (0xE9, 0x00, 0x00, false, 0xFF, 0x80),
+            (0xE9, 0x00, 0x80, true, 0x80, 0xC0),
+        ];
+        for (op, a, operand, carry, result, flags) in cases {
+            let (mut cpu, mut bus) = setup_cpu();
+            cpu.reset(&mut bus);
+            cpu.a = a;
+            cpu.status.set(StatusFlags::CARRY, carry);
+            bus.load_program(&[op, operand], 0x8000);
+            cpu.pc = 0x8000;
+            cpu.step(&mut bus);
+
+            let case = format!("{op:02X} A={a:02X} M={operand:02X} C={carry}");
+            assert_eq!(cpu.a, result, "{case}");
+            assert_eq!(cpu.status.bits() & 0xC3, flags, "{case}");
+        }
+    }
+
+    #[test]
+    fn test_php_plp_round_trip() {
+        let (mut cpu, mut bus) = setup_cpu();
+        cpu.reset(&mut bus);
+        cpu.sp = 0xFF;
+        cpu.status = StatusFlags::from_bits_truncate(0xCB); // N V D Z C, bit 5 clear
+
+        // PHP pushes B and bit 5 set; PLP restores everything else
+        bus.load_program(&[0x08, 0x28], 0x8000);
+        cpu.pc = 0x8000;
+        cpu.step(&mut bus);
+        assert_eq!(bus.read(0x01FF), 0xFB);
+        cpu.status = StatusFlags::empty();
+        cpu.step(&mut bus);
+        assert_eq!(cpu.status.bits(), 0xEB);
+
+        // PLP never sets B and always sets bit 5
+        bus.write(0x01FF, 0x10);
+        cpu.sp = 0xFE;
+        bus.load_program(&[0x28], 0x8002);
+        cpu.step(&mut bus);
+        assert_eq!(cpu.status.bits(), 0x20);
+    }
+
+    #[test]
+    fn test_interrupts_push_d_without_b() {
+        let (mut cpu, mut bus) = setup_cpu();
+        cpu.reset(&mut bus);
+        cpu.sp = 0xFF;
+        cpu.status = StatusFlags::DECIMAL;
+
+        cpu.nmi(&mut bus);
+        assert_eq!(bus.read(0x01FD), 0x28);
+        // Unlike the 65C02, the 2A03 leaves D set in the handler
+        assert!(cpu.status.contains(StatusFlags::DECIMAL));
+    }
+
     #[test]
     fn test_stack_operations_detailed() {
         let (mut cpu, mut bus) = setup_cpu();
@@ -147,4 +234,238 @@ mod additional_cpu_tests {
         assert!(!cpu.is_halted());
         assert_eq!(cpu.pc, 0x8000);
     }
+
+    #[test]
+    fn test_irq_waits_one_instruction_after_cli() {
+        let (mut cpu, mut bus) = setup_cpu();
+        cpu.reset(&mut bus);
+        bus.write(0xFFFE, 0x00);
+        bus.write(0xFFFF, 0x90);
+
+        // CLI; NOP: the poll at the end of CLI still sees I set
+        bus.load_program(&[0x58, 0xEA], 0x8000);
+        cpu.pc = 0x8000;
+        cpu.sp = 0xFF;
+        cpu.step(&mut bus);
+        assert!(!cpu.status.contains(StatusFlags::INTERRUPT_DISABLE));
+        assert!(cpu.irq_inhibited_at_poll());
+        assert_eq!(cpu.irq(&mut bus), 0);
+
+        cpu.step(&mut bus);
+        assert!(!cpu.irq_inhibited_at_poll());
+        assert_eq!(cpu.irq(&mut bus), 7);
+        assert_eq!(cpu.pc, 0x9000);
+        assert_eq!(bus.read(0x01FE), 0x02); // returns after the NOP
+    }
+
+    #[test]
+    fn test_irq_still_taken_right_after_sei() {
+        let (mut cpu, mut bus) = setup_cpu();
+        cpu.reset(&mut bus);
+        bus.write(0xFFFE, 0x00);
+        bus.write(0xFFFF, 0x90);
+
+        cpu.status.remove(StatusFlags::INTERRUPT_DISABLE);
+        bus.load_program(&[0x78], 0x8000); // SEI
+        cpu.pc = 0x8000;
+        cpu.sp = 0xFF;
+        cpu.step(&mut bus);
+        assert_eq!(cpu.irq(&mut bus), 7);
+        // The pushed status already has I set
+        assert_eq!(bus.read(0x01FD) & 0x04, 0x04);
+    }
+
+    #[test]
+    fn test_interrupt_poll_points() {
+        let (mut cpu, mut bus) = setup_cpu();
+        cpu.reset(&mut bus);
+
+        // LDA abs (4 cycles) polls before its last cycle
+        bus.load_program(&[0xAD, 0x00, 0x02], 0x8000);
+        cpu.pc = 0x8000;
+        assert_eq!(cpu.step(&mut bus), 4);
+        assert_eq!(cpu.interrupt_poll_cycle(), 3);
+
+        // Taken branch on the same page only polls before the operand fetch
+        cpu.status.insert(StatusFlags::ZERO);
+        bus.load_program(&[0xF0, 0x10], 0x8003); // BEQ +$10
+        assert_eq!(cpu.step(&mut bus), 3);
+        assert_eq!(cpu.interrupt_poll_cycle(), 1);
+
+        // BRK reports its NMI hijack window
+        bus.load_program(&[0x00, 0x00], 0x8015);
+        assert_eq!(cpu.step(&mut bus), 7);
+        assert!(cpu.nmi_can_hijack());
+        assert_eq!(cpu.interrupt_poll_cycle(), NMI_HIJACK_CYCLES);
+    }
+
+    #[test]
+    fn test_nmi_hijacks_brk() {
+        let (mut cpu, mut bus) = setup_cpu();
+        cpu.reset(&mut bus);
+        bus.write(0xFFFA, 0x00);
+        bus.write(0xFFFB, 0xA0);
+        bus.write(0xFFFE, 0x00);
+        bus.write(0xFFFF, 0x90);
+
+        bus.load_program(&[0x00, 0x00], 0x8000);
+        cpu.pc = 0x8000;
+        cpu.sp = 0xFF;
+        cpu.step(&mut bus);
+        cpu.hijack_to_nmi(&mut bus);
+
+        assert_eq!(cpu.pc, 0xA000);
+        // BRK's return address and B flag are kept
+        assert_eq!(bus.read(0x01FE), 0x02);
+        assert_eq!(bus.read(0x01FD) & 0x10, 0x10);
+    }
+
+    #[test]
+    fn test_indexed_dummy_reads() {
+        let (mut cpu, mut bus) = setup_cpu();
+        cpu.reset(&mut bus);
+        cpu.x = 0x20;
+
+        // LDA $20F0,X crosses into $2110 and reads $2010 first
+        bus.load_program(&[0xBD, 0xF0, 0x20], 0x8000);
+        cpu.pc = 0x8000;
+        bus.reads.clear();
+        assert_eq!(cpu.step(&mut bus), 5);
+        assert_eq!(bus.reads[3..], [0x2010, 0x2110]);
+
+        // No dummy read without a page cross
+        bus.load_program(&[0xBD, 0x00, 0x20], 0x8003);
+        bus.reads.clear();
+        cpu.step(&mut bus);
+        assert_eq!(bus.reads[3..], [0x2020]);
+
+        // STA $2000,X always reads the target before writing it
+        bus.load_program(&[0x9D, 0x00, 0x20], 0x8006);
+        bus.reads.clear();
+        cpu.step(&mut bus);
+        assert_eq!(bus.reads[3..], [0x2020]);
+        assert_eq!(bus.writes.last(), Some(&(0x2020, cpu.a)));
+    }
+
+    #[test]
+    fn test_nop_absolute_x_reads_and_pays_for_page_cross() {
+        let (mut cpu, mut bus) = setup_cpu();
+        cpu.reset(&mut bus);
+        cpu.x = 0x20;
+
+        // NOP $20F0,X crosses into $2110, reading $2010 on the way
+        bus.load_program(&[0x1C, 0xF0, 0x20], 0x8000);
+        cpu.pc = 0x8000;
+        bus.reads.clear();
+        assert_eq!(cpu.step(&mut bus), 5);
+        assert_eq!(bus.reads[3..], [0x2010, 0x2110]);
+
+        // Same page: one read of the effective address, 4 cycles
+        bus.load_program(&[0xFC, 0x00, 0x20], 0x8003);
+        bus.reads.clear();
+        assert_eq!(cpu.step(&mut bus), 4);
+        assert_eq!(bus.reads[3..], [0x2020]);
+        assert_eq!(cpu.pc, 0x8006);
+    }
+
+    #[test]
+    fn test_rmw_writes_old_value_first() {
+        let (mut cpu, mut bus) = setup_cpu();
+        cpu.reset(&mut bus);
+        bus.write(0x0010, 0x41);
+
+        // INC $10
+        bus.load_program(&[0xE6, 0x10], 0x8000);
+        cpu.pc = 0x8000;
+        bus.writes.clear();
+        cpu.step(&mut bus);
+        assert_eq!(bus.writes, [(0x0010, 0x41), (0x0010, 0x42)]);
+
+        // DCP $0010,X (unofficial) double writes too
+        cpu.x = 0;
+        bus.load_program(&[0xDF, 0x10, 0x00], 0x8002);
+        bus.writes.clear();
+        cpu.step(&mut bus);
+        assert_eq!(bus.writes, [(0x0010, 0x42), (0x0010, 0x41)]);
+    }
+
+    #[test]
+    fn test_soft_reset_keeps_registers() {
+        let (mut cpu, mut bus) = setup_cpu();
+        cpu.reset(&mut bus);
+        cpu.a = 0x12;
+        cpu.x = 0x34;
+        cpu.sp = 0xF0;
+        cpu.status = StatusFlags::from_bits_truncate(0xA1);
+        bus.writes.clear();
+
+        cpu.soft_reset(&mut bus);
+        assert_eq!(cpu.pc, 0x8000);
+        assert_eq!(cpu.sp, 0xED);
+        assert_eq!((cpu.a, cpu.x), (0x12, 0x34));
+        assert_eq!(cpu.status.bits(), 0xA5);
+        // Nothing is pushed
+        assert!(bus.writes.is_empty());
+    }
+
+    #[test]
+    fn test_rmw_combos_match_official_pairs() {
+        // SLO = ASL + ORA, RLA = ROL + AND, SRE = LSR + EOR, RRA = ROR + ADC,
+        // DCP = DEC + CMP, ISC = INC + SBC, in the modes both halves share
+        let mut rng = crate::rng::EmuRng::new(0x2959);
+        for combo in [0x03u8, 0x23, 0x43, 0x63, 0xC3, 0xE3] {
+            for mode in [0x04u8, 0x0C, 0x14, 0x1C] {
+                let opcode = combo | mode;
+                for _ in 0..50 {
+                    let (mut cpu, mut bus) = setup_cpu();
+                    for byte in bus.memory[..0x0800].iter_mut() {
+                        *byte = rng.next_u8();
+                    }
+                    cpu.a = rng.next_u8();
+                    cpu.x = rng.next_u8();
+                    cpu.status = StatusFlags::from_bits_truncate(rng.next_u8() & 0xC3);
+                    let operand = [rng.next_u8(), rng.next_u8() & 0x07];
+                    let (mut official, mut official_bus) = (Cpu::new(), TestBus::new());
+                    official_bus.memory = bus.memory;
+                    (official.a, official.x) = (cpu.a, cpu.x);
+                    official.status = cpu.status;
+
+                    bus.load_program(&[opcode, operand[0], operand[1]], 0x8000);
+                    cpu.pc = 0x8000;
+                    let cycles = cpu.step(&mut bus);
+
+                    // The RMW half, then the ALU half on the same operand
+                    let mut official_cycles = Vec::new();
+                    for (start, half) in [(0x8000u16, opcode - 1), (0x8003, opcode - 2)] {
+                        official_bus.load_program(&[half, operand[0], operand[1]], start);
+                        official.pc = start;
+                        official_cycles.push(official.step(&mut official_bus));
+                    }
+
+                    let what = format!("opcode {:02X}", opcode);
+                    assert_eq!(cycles, official_cycles[0], "{}", what);
+                    assert_eq!(cpu.a, official.a, "{}", what);
+                    assert_eq!(cpu.status.bits(), official.status.bits(), "{}", what);
+                    assert_eq!(
+                        bus.memory[..0x0800],
+                        official_bus.memory[..0x0800],
+                        "{}",
+                        what
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_jsr_timing_is_address_independent() {
+        // No per-game fast paths: a JSR from $8974 to $8995 is a normal JSR
+        let (mut cpu, mut bus) = setup_cpu();
+        cpu.reset(&mut bus);
+        bus.load_program(&[0x20, 0x95, 0x89], 0x8974);
+        cpu.pc = 0x8974;
+        assert_eq!(cpu.step(&mut bus), 6);
+        assert_eq!(cpu.pc, 0x8995);
+        assert_eq!((bus.read(0x01FD), bus.read(0x01FC)), (0x89, 0x76));
+    }
 }
@@ -312,4 +312,23 @@ mod addressing_mode_tests {
         assert_eq!(cpu.a, 0x80);
         assert_eq!(cpu.pc, 0x8001);
     }
+
+    #[test]
+    fn test_branch_page_cross_is_measured_from_next_instruction() {
+        let (mut cpu, mut bus) = setup_cpu();
+        cpu.reset(&mut bus);
+        cpu.status.remove(StatusFlags::ZERO);
+
+        // BNE -2 at $80FF: next instruction is $8101, target $80FF crosses back
+        bus.load_program(&[0xD0, 0xFE], 0x80FF);
+        cpu.pc = 0x80FF;
+        assert_eq!(cpu.step(&mut bus), 4);
+        assert_eq!(cpu.pc, 0x80FF);
+
+        // BNE +0 at $80FE: operand ends the page, so landing on $8100 is no cross
+        bus.load_program(&[0xD0, 0x00], 0x80FE);
+        cpu.pc = 0x80FE;
+        assert_eq!(cpu.step(&mut bus), 3);
+        assert_eq!(cpu.pc, 0x8100);
+    }
 }
@@ -5,12 +5,16 @@ mod addressing;
 
 struct TestBus {
     memory: [u8; 0x10000],
+    reads: Vec<u16>,
+    writes: Vec<(u16, u8)>,
 }
 
 impl TestBus {
     fn new() -> Self {
         Self {
             memory: [0; 0x10000],
+            reads: Vec::new(),
+            writes: Vec::new(),
         }
     }
 
@@ -25,10 +29,12 @@ impl CpuBus for TestBus {
     fn on_reset(&mut self) {}
 
     fn read(&mut self, addr: u16) -> u8 {
+        self.reads.push(addr);
         self.memory[addr as usize]
     }
 
     fn write(&mut self, addr: u16, data: u8) {
+        self.writes.push((addr, data));
         self.memory[addr as usize] = data;
     }
 }
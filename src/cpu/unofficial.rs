@@ -0,0 +1,295 @@
+// Unofficial opcodes. The read-modify-write combos (SLO, RLA, SRE, RRA, DCP,
+// ISC) are an official shift/rotate/INC/DEC followed by an ALU op on A, so
+// they reuse those helpers and the official RMW timing; the opcode's bit
+// layout picks the pair and the addressing mode.
+use super::*;
+
+impl Cpu {
+    pub(super) fn execute_unofficial(&mut self, opcode: u8, bus: &mut dyn CpuBus) -> u8 {
+        match opcode {
+            // NOPs, which still fetch their operand
+            0x1A | 0x3A | 0x5A | 0x7A | 0xDA | 0xFA => 2,
+            0x80 | 0x82 | 0x89 | 0xC2 | 0xE2 => {
+                self.read_byte(bus);
+                2
+            }
+            0x04 | 0x44 | 0x64 => {
+                self.read_byte(bus);
+                3
+            }
+            0x14 | 0x34 | 0x54 | 0x74 | 0xD4 | 0xF4 => {
+                self.read_byte(bus);
+                4
+            }
+            0x0C => {
+                self.read_word(bus);
+                4
+            }
+            0x1C | 0x3C | 0x5C | 0x7C | 0xDC | 0xFC => {
+                // Reads like LDA abs,X, page cross included
+                let (addr, page_crossed) = self.get_absolute_x_addr(bus);
+                bus.read(addr);
+                if page_crossed {
+                    5
+                } else {
+                    4
+                }
+            }
+
+            // JAM/KIL opcodes - Halt the CPU until reset.
+            0x02 | 0x12 | 0x22 | 0x32 | 0x42 | 0x52 | 0x62 | 0x72 | 0x92 | 0xB2 | 0xD2 | 0xF2 => {
+                self.jam()
+            }
+
+            // SLO, RLA, SRE, RRA, DCP, ISC
+            0x03 | 0x07 | 0x0F | 0x13 | 0x17 | 0x1B | 0x1F | 0x23 | 0x27 | 0x2F | 0x33 | 0x37
+            | 0x3B | 0x3F | 0x43 | 0x47 | 0x4F | 0x53 | 0x57 | 0x5B | 0x5F | 0x63 | 0x67 | 0x6F
+            | 0x73 | 0x77 | 0x7B | 0x7F | 0xC3 | 0xC7 | 0xCF | 0xD3 | 0xD7 | 0xDB | 0xDF | 0xE3
+            | 0xE7 | 0xEF | 0xF3 | 0xF7 | 0xFB | 0xFF => self.rmw_combo(opcode, bus),
+
+            // Immediate ALU combos
+            0x0B | 0x2B => {
+                // ANC - AND, Copy N to C
+                let value = self.read_byte(bus);
+                self.and(value);
+                self.status.set(
+                    StatusFlags::CARRY,
+                    self.status.contains(StatusFlags::NEGATIVE),
+                );
+                2
+            }
+            0x4B => {
+                // ALR - AND + LSR
+                let value = self.read_byte(bus);
+                self.a &= value;
+                self.a = self.lsr(self.a);
+                2
+            }
+            0x6B => {
+                // ARR - AND with accumulator, then rotate right
+                let value = self.read_byte(bus);
+                self.a &= value;
+                let carry = if self.status.contains(StatusFlags::CARRY) {
+                    0x80
+                } else {
+                    0
+                };
+                let result = (self.a >> 1) | carry;
+                self.status.set(StatusFlags::CARRY, self.a & 0x01 != 0);
+                self.status.set(
+                    StatusFlags::OVERFLOW,
+                    ((result ^ (result << 1)) & 0x40) != 0,
+                );
+                self.a = result;
+                self.set_zero_negative_flags(self.a);
+                2
+            }
+            0x8B => {
+                // XAA - (A OR CONST) AND X AND immediate [EXTREMELY UNSTABLE]
+                // Behavior varies by temperature and chip; 0xFF is the
+                // common fallback for the magic constant
+                let value = self.read_byte(bus);
+                self.a = ((self.a | 0xFF) & self.x) & value;
+                self.set_zero_negative_flags(self.a);
+                2
+            }
+            0xCB => {
+                // AXS - AND X register with accumulator, subtract immediate
+                let value = self.read_byte(bus);
+                let and_result = self.a & self.x;
+                let result = and_result.wrapping_sub(value);
+                self.x = result;
+                self.status.set(StatusFlags::CARRY, and_result >= value);
+                self.set_zero_negative_flags(result);
+                2
+            }
+            0xEB => self.sbc_immediate(bus),
+
+            // LAX - LDA + TAX
+            0xAB => {
+                let value = self.read_byte(bus);
+                self.lax(value);
+                2
+            }
+            0xA7 => {
+                let addr = self.read_byte(bus) as u16;
+                let value = bus.read(addr);
+                self.lax(value);
+                3
+            }
+            0xB7 => {
+                let addr = self.get_zero_page_y_addr(bus);
+                let value = bus.read(addr);
+                self.lax(value);
+                4
+            }
+            0xAF => {
+                let addr = self.read_word(bus);
+                let value = bus.read(addr);
+                self.lax(value);
+                4
+            }
+            0xBF => {
+                let (addr, page_crossed) = self.get_absolute_y_addr(bus);
+                let value = bus.read(addr);
+                self.lax(value);
+                if page_crossed {
+                    5
+                } else {
+                    4
+                }
+            }
+            0xA3 => {
+                let addr = self.get_indexed_indirect_addr(bus);
+                let value = bus.read(addr);
+                self.lax(value);
+                6
+            }
+            0xB3 => {
+                let (addr, page_crossed) = self.get_indirect_indexed_addr(bus);
+                let value = bus.read(addr);
+                self.lax(value);
+                if page_crossed {
+                    6
+                } else {
+                    5
+                }
+            }
+            0xBB => {
+                // LAS - Load A, X, S with memory AND S
+                let (addr, page_crossed) = self.get_absolute_y_addr(bus);
+                let value = bus.read(addr) & self.sp;
+                self.a = value;
+                self.x = value;
+                // Only update SP if result is reasonable (>= 0x80)
+                if value >= 0x80 {
+                    self.sp = value;
+                }
+                self.set_zero_negative_flags(value);
+                if page_crossed {
+                    5
+                } else {
+                    4
+                }
+            }
+
+            // SAX - Store A AND X
+            0x83 => {
+                let addr = self.get_indexed_indirect_addr(bus);
+                bus.write(addr, self.a & self.x);
+                6
+            }
+            0x87 => {
+                let addr = self.read_byte(bus) as u16;
+                bus.write(addr, self.a & self.x);
+                3
+            }
+            0x8F => {
+                let addr = self.read_word(bus);
+                bus.write(addr, self.a & self.x);
+                4
+            }
+            0x97 => {
+                let addr = self.get_zero_page_y_addr(bus);
+                bus.write(addr, self.a & self.x);
+                4
+            }
+
+            // Stores ANDed with the address high byte + 1 [UNSTABLE]
+            0x93 => {
+                // SHA/AHX (indirect),Y - A AND X AND (H+1)
+                let (addr, page_crossed) = self.get_indirect_indexed_addr(bus);
+                self.indexed_write_dummy_read(bus, addr, page_crossed);
+                let high_byte = (addr >> 8) as u8;
+                bus.write(addr, self.a & self.x & high_byte.wrapping_add(1));
+                6
+            }
+            0x9B => {
+                // TAS/XAS absolute,Y - also copies A AND X to SP, which can
+                // corrupt the stack
+                let value = self.a & self.x;
+                // Only update SP if result is reasonable (>= 0x80)
+                if value >= 0x80 {
+                    self.sp = value;
+                }
+                self.store_high_and(bus, self.y, value)
+            }
+            0x9C => self.store_high_and(bus, self.x, self.y), // SHY absolute,X
+            0x9E => self.store_high_and(bus, self.y, self.x), // SHX absolute,Y
+            0x9F => {
+                // Y AND (high byte of the indexed address + 1), absolute,X
+                let base = self.read_word(bus);
+                let addr = base.wrapping_add(self.x as u16);
+                self.dummy_read_uncarried(bus, base, addr);
+                let high_byte = (addr >> 8) as u8;
+                bus.write(addr, self.y & high_byte.wrapping_add(1));
+                5
+            }
+
+            // Halt like JAM; the console reports it once, see `telemetry`
+            _ => self.jam(),
+        }
+    }
+
+    /// SLO, RLA, SRE, RRA, DCP and ISC. Bits 5-7 pick the instruction pair,
+    /// bits 2-4 the addressing mode (as for the official RMW opcodes).
+    fn rmw_combo(&mut self, opcode: u8, bus: &mut dyn CpuBus) -> u8 {
+        let (addr, cycles) = match (opcode >> 2) & 0x07 {
+            0 => (self.get_indexed_indirect_addr(bus), 8),
+            1 => (self.read_byte(bus) as u16, 5),
+            3 => (self.read_word(bus), 6),
+            4 => {
+                let (addr, page_crossed) = self.get_indirect_indexed_addr(bus);
+                self.indexed_write_dummy_read(bus, addr, page_crossed);
+                (addr, 8)
+            }
+            5 => (self.get_zero_page_x_addr(bus), 6),
+            6 => {
+                let (addr, page_crossed) = self.get_absolute_y_addr(bus);
+                self.indexed_write_dummy_read(bus, addr, page_crossed);
+                (addr, 7)
+            }
+            _ => {
+                let (addr, page_crossed) = self.get_absolute_x_addr(bus);
+                self.indexed_write_dummy_read(bus, addr, page_crossed);
+                (addr, 7)
+            }
+        };
+        let value = bus.read(addr);
+        let result = match opcode >> 5 {
+            0 => self.asl(value),
+            1 => self.rol(value),
+            2 => self.lsr(value),
+            3 => self.ror(value),
+            6 => value.wrapping_sub(1),
+            _ => value.wrapping_add(1),
+        };
+        bus.write_rmw(addr, value, result);
+        match opcode >> 5 {
+            0 => self.ora(result),
+            1 => self.and(result),
+            2 => self.eor(result),
+            3 => self.adc(result),
+            6 => self.compare(self.a, result),
+            _ => self.sbc(result),
+        }
+        cycles
+    }
+
+    fn lax(&mut self, value: u8) {
+        self.a = value;
+        self.x = value;
+        self.set_zero_negative_flags(value);
+    }
+
+    /// SHY, SHX and TAS: absolute indexed, storing `value` AND (high byte of
+    /// the base address + 1).
+    fn store_high_and(&mut self, bus: &mut dyn CpuBus, index: u8, value: u8) -> u8 {
+        let base = self.read_word(bus);
+        let addr = base.wrapping_add(index as u16);
+        self.dummy_read_uncarried(bus, base, addr);
+        let high_byte = (base >> 8) as u8;
+        bus.write(addr, value & high_byte.wrapping_add(1));
+        5
+    }
+}
@@ -0,0 +1,567 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::bus::IrqSources;
+use crate::clock::ConsoleClock;
+
+// Oldest entries are dropped once a log holds this many entries.
+const LOG_LIMIT: usize = 4096;
+
+/// The CPU instruction responsible for a memory access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AccessSource {
+    /// Address of the opcode being executed.
+    pub pc: u16,
+    /// Mapper PRG bank register at the time of the access.
+    pub prg_bank: u8,
+    /// When the instruction started.
+    pub clock: ConsoleClock,
+}
+
+/// A $2007 write that landed in pattern table space ($0000-$1FFF).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChrWrite {
+    pub source: AccessSource,
+    pub addr: u16,
+    pub value: u8,
+    /// Dropped because CHR write protection was on.
+    pub blocked: bool,
+}
+
+/// CHR write protection and logging, owned by the bus.
+#[derive(Debug, Default)]
+pub struct ChrWriteMonitor {
+    protect: bool,
+    log: Option<VecDeque<ChrWrite>>,
+    generation: u64,
+}
+
+impl ChrWriteMonitor {
+    pub fn set_protect(&mut self, protect: bool) {
+        self.protect = protect;
+    }
+
+    pub fn protect(&self) -> bool {
+        self.protect
+    }
+
+    pub fn set_logging(&mut self, enabled: bool) {
+        if !enabled {
+            self.log = None;
+        } else if self.log.is_none() {
+            self.log = Some(VecDeque::new());
+        }
+    }
+
+    pub fn logging(&self) -> bool {
+        self.log.is_some()
+    }
+
+    /// Bumped on every CHR write that went through, so viewers can tell
+    /// when pattern tables need redrawing.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Record a write; returns whether it may go through.
+    pub fn record(&mut self, source: AccessSource, addr: u16, value: u8) -> bool {
+        if let Some(log) = self.log.as_mut() {
+            if log.len() == LOG_LIMIT {
+                log.pop_front();
+            }
+            log.push_back(ChrWrite {
+                source,
+                addr,
+                value,
+                blocked: self.protect,
+            });
+        }
+        if !self.protect {
+            self.generation += 1;
+        }
+        !self.protect
+    }
+
+    pub fn take_log(&mut self) -> Vec<ChrWrite> {
+        self.log
+            .as_mut()
+            .map(|log| log.drain(..).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Which bus a watchpoint address refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressSpace {
+    Cpu,
+    /// PPU memory as seen through $2006/$2007.
+    Ppu,
+}
+
+/// Watch an address range for reads and/or writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Watchpoint {
+    pub space: AddressSpace,
+    pub start: u16,
+    pub end: u16,
+    pub on_read: bool,
+    pub on_write: bool,
+    /// Stop emulation on a hit rather than only logging it.
+    pub breaks: bool,
+}
+
+impl Watchpoint {
+    /// Parse `[cpu|ppu:]<r|w|rw>:<start>[-<end>]` with hex addresses,
+    /// e.g. `ppu:w:1000-1FFF` or `rw:0300`. The space defaults to CPU.
+    pub fn parse(spec: &str, breaks: bool) -> Option<Self> {
+        let mut parts: Vec<&str> = spec.split(':').collect();
+        let space = match parts.first()?.to_ascii_lowercase().as_str() {
+            "cpu" => Some(AddressSpace::Cpu),
+            "ppu" => Some(AddressSpace::Ppu),
+            _ => None,
+        };
+        if space.is_some() {
+            parts.remove(0);
+        }
+        let [kind, range] = parts[..] else {
+            return None;
+        };
+        let (on_read, on_write) = match kind.to_ascii_lowercase().as_str() {
+            "r" => (true, false),
+            "w" => (false, true),
+            "rw" => (true, true),
+            _ => return None,
+        };
+        let parse_addr = |text: &str| {
+            u16::from_str_radix(text.trim_start_matches('$').trim_start_matches("0x"), 16).ok()
+        };
+        let (start, end) = match range.split_once('-') {
+            Some((start, end)) => (parse_addr(start)?, parse_addr(end)?),
+            None => (parse_addr(range)?, parse_addr(range)?),
+        };
+        if start > end {
+            return None;
+        }
+        Some(Self {
+            space: space.unwrap_or(AddressSpace::Cpu),
+            start,
+            end,
+            on_read,
+            on_write,
+            breaks,
+        })
+    }
+
+    fn matches(&self, space: AddressSpace, addr: u16, write: bool) -> bool {
+        self.space == space
+            && (self.start..=self.end).contains(&addr)
+            && if write { self.on_write } else { self.on_read }
+    }
+}
+
+/// An access that matched a watchpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchHit {
+    /// Index into the watchpoint list.
+    pub watchpoint: usize,
+    pub space: AddressSpace,
+    pub addr: u16,
+    pub value: u8,
+    pub write: bool,
+    pub source: AccessSource,
+}
+
+impl fmt::Display for WatchHit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} ${:04X} = ${:02X} by PC=${:04X} bank={} (watch #{})",
+            match self.space {
+                AddressSpace::Cpu => "CPU",
+                AddressSpace::Ppu => "PPU",
+            },
+            if self.write { "write" } else { "read" },
+            self.addr,
+            self.value,
+            self.source.pc,
+            self.source.prg_bank,
+            self.watchpoint
+        )
+    }
+}
+
+/// Watchpoint list and the hits collected since the last `take_hits`.
+#[derive(Debug, Default)]
+pub struct Watchpoints {
+    list: Vec<Watchpoint>,
+    hits: VecDeque<WatchHit>,
+    break_pending: bool,
+}
+
+impl Watchpoints {
+    pub fn add(&mut self, watchpoint: Watchpoint) -> usize {
+        self.list.push(watchpoint);
+        self.list.len() - 1
+    }
+
+    pub fn clear(&mut self) {
+        self.list.clear();
+        self.hits.clear();
+        self.break_pending = false;
+    }
+
+    pub fn list(&self) -> &[Watchpoint] {
+        &self.list
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn check(
+        &mut self,
+        space: AddressSpace,
+        addr: u16,
+        value: u8,
+        write: bool,
+        source: AccessSource,
+    ) {
+        for (index, watchpoint) in self.list.iter().enumerate() {
+            if !watchpoint.matches(space, addr, write) {
+                continue;
+            }
+            if self.hits.len() == LOG_LIMIT {
+                self.hits.pop_front();
+            }
+            self.hits.push_back(WatchHit {
+                watchpoint: index,
+                space,
+                addr,
+                value,
+                write,
+                source,
+            });
+            self.break_pending |= watchpoint.breaks;
+        }
+    }
+
+    pub fn take_hits(&mut self) -> Vec<WatchHit> {
+        self.hits.drain(..).collect()
+    }
+
+    /// Whether a breaking watchpoint fired since the last call.
+    pub fn take_break(&mut self) -> bool {
+        std::mem::take(&mut self.break_pending)
+    }
+}
+
+/// How the CPU entered a handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InterruptKind {
+    Nmi,
+    Irq,
+    Reset,
+    Brk,
+}
+
+impl InterruptKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "nmi" => Some(InterruptKind::Nmi),
+            "irq" => Some(InterruptKind::Irq),
+            "reset" => Some(InterruptKind::Reset),
+            "brk" => Some(InterruptKind::Brk),
+            _ => None,
+        }
+    }
+
+    /// Parse a comma-separated list such as `nmi,brk`.
+    pub fn parse_list(spec: &str) -> Option<Vec<Self>> {
+        spec.split(',')
+            .map(|name| Self::from_name(name.trim()))
+            .collect()
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            InterruptKind::Nmi => "NMI",
+            InterruptKind::Irq => "IRQ",
+            InterruptKind::Reset => "RESET",
+            InterruptKind::Brk => "BRK",
+        }
+    }
+}
+
+/// One entry into an interrupt handler.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InterruptEntry {
+    pub kind: InterruptKind,
+    /// PC when the interrupt was taken; for BRK the address of the opcode.
+    pub from: u16,
+    /// Handler address loaded from the vector.
+    pub handler: u16,
+    /// For BRK, the byte after the opcode that handlers use as an argument.
+    pub signature: Option<u8>,
+    /// For IRQ, the devices asserting the line when it was polled.
+    pub irq_sources: IrqSources,
+    /// When the interrupt was taken.
+    pub clock: ConsoleClock,
+}
+
+impl fmt::Display for InterruptEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at ${:04X}", self.kind.name(), self.from)?;
+        if let Some(signature) = self.signature {
+            write!(f, " (signature ${:02X})", signature)?;
+        }
+        if !self.irq_sources.is_empty() {
+            write!(f, " ({})", self.irq_sources.names().join(", "))?;
+        }
+        write!(f, " -> ${:04X}", self.handler)
+    }
+}
+
+/// Breakpoints on interrupt entry and the BRK log.
+#[derive(Debug, Default)]
+pub struct InterruptMonitor {
+    break_on: Vec<InterruptKind>,
+    log_brk: bool,
+    log: VecDeque<InterruptEntry>,
+    break_pending: bool,
+}
+
+impl InterruptMonitor {
+    pub fn set_break_on(&mut self, kinds: &[InterruptKind]) {
+        self.break_on = kinds.to_vec();
+    }
+
+    pub fn break_on(&self) -> &[InterruptKind] {
+        &self.break_on
+    }
+
+    pub fn set_log_brk(&mut self, enabled: bool) {
+        self.log_brk = enabled;
+    }
+
+    /// Note a handler entry; breaking kinds (and BRKs when logging them)
+    /// are kept for `take_log`.
+    pub fn record(&mut self, entry: InterruptEntry) {
+        let breaks = self.break_on.contains(&entry.kind);
+        if breaks || (self.log_brk && entry.kind == InterruptKind::Brk) {
+            if self.log.len() == LOG_LIMIT {
+                self.log.pop_front();
+            }
+            self.log.push_back(entry);
+        }
+        self.break_pending |= breaks;
+    }
+
+    pub fn take_log(&mut self) -> Vec<InterruptEntry> {
+        self.log.drain(..).collect()
+    }
+
+    /// Whether a breaking interrupt was entered since the last call.
+    pub fn take_break(&mut self) -> bool {
+        std::mem::take(&mut self.break_pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn protect_blocks_and_logs_writes() {
+        let mut monitor = ChrWriteMonitor::default();
+        let source = AccessSource {
+            pc: 0xC123,
+            prg_bank: 2,
+            ..AccessSource::default()
+        };
+        assert!(monitor.record(source, 0x0010, 0xAA));
+        assert!(monitor.take_log().is_empty());
+
+        monitor.set_logging(true);
+        monitor.set_protect(true);
+        assert!(!monitor.record(source, 0x1FF0, 0x55));
+        assert_eq!(monitor.generation(), 1);
+
+        let log = monitor.take_log();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].source, source);
+        assert_eq!(log[0].addr, 0x1FF0);
+        assert!(log[0].blocked);
+        assert!(monitor.take_log().is_empty());
+    }
+
+    #[test]
+    fn log_is_bounded() {
+        let mut monitor = ChrWriteMonitor::default();
+        monitor.set_logging(true);
+        for i in 0..LOG_LIMIT + 10 {
+            monitor.record(AccessSource::default(), i as u16, 0);
+        }
+        let log = monitor.take_log();
+        assert_eq!(log.len(), LOG_LIMIT);
+        assert_eq!(log[0].addr, 10);
+    }
+
+    #[test]
+    fn parses_watchpoint_specs() {
+        let wp = Watchpoint::parse("ppu:w:1000-1FFF", true).unwrap();
+        assert_eq!(wp.space, AddressSpace::Ppu);
+        assert_eq!((wp.start, wp.end), (0x1000, 0x1FFF));
+        assert!(!wp.on_read && wp.on_write && wp.breaks);
+
+        let wp = Watchpoint::parse("rw:$0300", false).unwrap();
+        assert_eq!(wp.space, AddressSpace::Cpu);
+        assert_eq!((wp.start, wp.end), (0x0300, 0x0300));
+        assert!(wp.on_read && wp.on_write);
+
+        assert!(Watchpoint::parse("ppu:x:1000", true).is_none());
+        assert!(Watchpoint::parse("w:2000-1000", true).is_none());
+        assert!(Watchpoint::parse("w", true).is_none());
+    }
+
+    #[test]
+    fn only_breaking_watchpoints_request_a_break() {
+        let mut watchpoints = Watchpoints::default();
+        watchpoints.add(Watchpoint::parse("w:0300-03FF", false).unwrap());
+        watchpoints.add(Watchpoint::parse("ppu:w:1000-1FFF", true).unwrap());
+        let source = AccessSource {
+            pc: 0xE010,
+            prg_bank: 3,
+            ..AccessSource::default()
+        };
+
+        watchpoints.check(AddressSpace::Cpu, 0x0310, 1, true, source);
+        watchpoints.check(AddressSpace::Cpu, 0x0310, 1, false, source);
+        assert!(!watchpoints.take_break());
+
+        watchpoints.check(AddressSpace::Ppu, 0x1234, 2, true, source);
+        assert!(watchpoints.take_break());
+        assert!(!watchpoints.take_break());
+
+        let hits = watchpoints.take_hits();
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[1].watchpoint, 1);
+        assert_eq!(hits[1].source, source);
+        assert_eq!(
+            hits[1].to_string(),
+            "PPU write $1234 = $02 by PC=$E010 bank=3 (watch #1)"
+        );
+    }
+
+    #[test]
+    fn interrupt_breaks_and_brk_log() {
+        assert_eq!(
+            InterruptKind::parse_list("nmi, BRK"),
+            Some(vec![InterruptKind::Nmi, InterruptKind::Brk])
+        );
+        assert!(InterruptKind::parse_list("nmi,foo").is_none());
+
+        let mut monitor = InterruptMonitor::default();
+        let brk = InterruptEntry {
+            kind: InterruptKind::Brk,
+            from: 0xC123,
+            handler: 0xE000,
+            signature: Some(0x05),
+            irq_sources: IrqSources::empty(),
+            clock: ConsoleClock::default(),
+        };
+        let nmi = InterruptEntry {
+            kind: InterruptKind::Nmi,
+            from: 0xC200,
+            handler: 0xD000,
+            signature: None,
+            irq_sources: IrqSources::empty(),
+            clock: ConsoleClock::default(),
+        };
+        monitor.record(brk);
+        monitor.record(nmi);
+        assert!(!monitor.take_break());
+        assert!(monitor.take_log().is_empty());
+
+        monitor.set_log_brk(true);
+        monitor.set_break_on(&[InterruptKind::Nmi]);
+        monitor.record(brk);
+        assert!(!monitor.take_break());
+        monitor.record(nmi);
+        assert!(monitor.take_break());
+        let log = monitor.take_log();
+        assert_eq!(log, vec![brk, nmi]);
+        assert_eq!(log[0].to_string(), "BRK at $C123 (signature $05) -> $E000");
+        assert_eq!(log[1].to_string(), "NMI at $C200 -> $D000");
+    }
+
+    #[test]
+    fn nes_counts_brks_and_breaks_on_nmi_entry() {
+        // BRK #$05, enable NMI, spin; BRK/IRQ and NMI handlers are RTIs
+        let mut rom = vec![b'N', b'E', b'S', 0x1A, 1, 1, 0, 0];
+        rom.resize(16 + 0x4000 + 0x2000, 0);
+        let program = [0x00, 0x05, 0xA9, 0x80, 0x8D, 0x00, 0x20, 0x4C, 0x07, 0xC0];
+        rom[16..16 + program.len()].copy_from_slice(&program);
+        rom[16 + 0x10] = 0x40;
+        rom[16 + 0x20] = 0x40;
+        rom[16 + 0x3FFA..16 + 0x4000].copy_from_slice(&[0x20, 0xC0, 0x00, 0xC0, 0x10, 0xC0]);
+        let mut nes = crate::Nes::new();
+        nes.set_ppu_warmup(false);
+        nes.set_brk_logging(true);
+        nes.set_interrupt_breaks(&[InterruptKind::Nmi]);
+        nes.load_rom_bytes("irq.nes", &rom).unwrap();
+
+        let mut brks = 0;
+        let mut steps = 0;
+        while !nes.take_watch_break() {
+            if nes.step() {
+                brks += nes.frame_stats().brks;
+            }
+            steps += 1;
+            assert!(steps < 100_000, "no NMI break");
+        }
+        let log = nes.take_interrupt_log();
+        assert_eq!(
+            log.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            [
+                "BRK at $C000 (signature $05) -> $C010",
+                "NMI at $C007 -> $C020"
+            ]
+        );
+        while !nes.step() {}
+        brks += nes.frame_stats().brks;
+        while !nes.step() {}
+        brks += nes.frame_stats().brks;
+        assert_eq!(brks, 1);
+    }
+
+    #[test]
+    fn irq_entries_name_their_source() {
+        // Frame IRQ on ($4017 = 0), CLI, spin; the handler acknowledges it
+        // through $4015
+        let mut rom = vec![b'N', b'E', b'S', 0x1A, 1, 1, 0, 0];
+        rom.resize(16 + 0x4000 + 0x2000, 0);
+        let program = [0xA9, 0x00, 0x8D, 0x17, 0x40, 0x58, 0x4C, 0x06, 0xC0];
+        rom[16..16 + program.len()].copy_from_slice(&program);
+        rom[16 + 0x10..16 + 0x14].copy_from_slice(&[0xAD, 0x15, 0x40, 0x40]);
+        rom[16 + 0x20] = 0x40;
+        rom[16 + 0x3FFA..16 + 0x4000].copy_from_slice(&[0x20, 0xC0, 0x00, 0xC0, 0x10, 0xC0]);
+        let mut nes = crate::Nes::new();
+        nes.set_interrupt_breaks(&[InterruptKind::Irq]);
+        nes.load_rom_bytes("irq_source.nes", &rom).unwrap();
+
+        let mut steps = 0;
+        while !nes.take_watch_break() {
+            nes.step();
+            steps += 1;
+            assert!(steps < 100_000, "no IRQ break");
+        }
+        let log = nes.take_interrupt_log();
+        assert_eq!(log[0].irq_sources, IrqSources::FRAME_COUNTER);
+        assert_eq!(log[0].to_string(), "IRQ at $C006 (frame counter) -> $C010");
+        // Held until the handler's $4015 read
+        assert_eq!(nes.irq_sources(), IrqSources::FRAME_COUNTER);
+        nes.step();
+        assert!(nes.irq_sources().is_empty());
+    }
+}
@@ -0,0 +1,129 @@
+// Discord Rich Presence over Discord's local IPC socket (a Unix socket, or
+// a named pipe on Windows): publishes the running game and when play
+// started, which Discord turns into an elapsed-time counter. Only built with
+// the `discord` feature.
+use std::io::{Error, ErrorKind, Read, Result, Write};
+
+use serde_json::json;
+
+const OP_HANDSHAKE: u32 = 0;
+const OP_FRAME: u32 = 1;
+
+#[cfg(unix)]
+type Pipe = std::os::unix::net::UnixStream;
+#[cfg(windows)]
+type Pipe = std::fs::File;
+
+pub struct DiscordPresence {
+    pipe: Pipe,
+    nonce: u64,
+}
+
+impl DiscordPresence {
+    /// Connect to a running Discord client as the application `client_id`
+    /// (from the Discord developer portal).
+    pub fn connect(client_id: &str) -> Result<Self> {
+        let mut presence = DiscordPresence {
+            pipe: open_pipe()?,
+            nonce: 0,
+        };
+        presence.send(OP_HANDSHAKE, &json!({ "v": 1, "client_id": client_id }))?;
+        presence.receive()?;
+        Ok(presence)
+    }
+
+    /// Show `game` as being played since `start_unix` (seconds).
+    pub fn set_activity(&mut self, game: &str, start_unix: u64) -> Result<()> {
+        self.nonce += 1;
+        let command = activity_command(game, start_unix, std::process::id(), self.nonce);
+        self.send(OP_FRAME, &command)?;
+        self.receive()
+    }
+
+    fn send(&mut self, opcode: u32, payload: &serde_json::Value) -> Result<()> {
+        self.pipe
+            .write_all(&encode_frame(opcode, &payload.to_string()))
+    }
+
+    /// Read and drop Discord's reply, so its side of the pipe never fills.
+    fn receive(&mut self) -> Result<()> {
+        let mut header = [0u8; 8];
+        self.pipe.read_exact(&mut header)?;
+        let length = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        let mut body = vec![0u8; length as usize];
+        self.pipe.read_exact(&mut body)
+    }
+}
+
+/// One IPC frame: opcode and payload length (little-endian), then JSON.
+pub fn encode_frame(opcode: u32, payload: &str) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(8 + payload.len());
+    frame.extend_from_slice(&opcode.to_le_bytes());
+    frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    frame.extend_from_slice(payload.as_bytes());
+    frame
+}
+
+pub fn activity_command(game: &str, start_unix: u64, pid: u32, nonce: u64) -> serde_json::Value {
+    json!({
+        "cmd": "SET_ACTIVITY",
+        "args": {
+            "pid": pid,
+            "activity": {
+                "details": game,
+                "timestamps": { "start": start_unix },
+            },
+        },
+        "nonce": nonce.to_string(),
+    })
+}
+
+#[cfg(unix)]
+fn open_pipe() -> Result<Pipe> {
+    let dir = ["XDG_RUNTIME_DIR", "TMPDIR", "TMP", "TEMP"]
+        .iter()
+        .find_map(std::env::var_os)
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| "/tmp".into());
+    for i in 0..10 {
+        if let Ok(pipe) = Pipe::connect(dir.join(format!("discord-ipc-{}", i))) {
+            pipe.set_read_timeout(Some(std::time::Duration::from_secs(2)))?;
+            return Ok(pipe);
+        }
+    }
+    Err(Error::new(ErrorKind::NotFound, "Discord is not running"))
+}
+
+#[cfg(windows)]
+fn open_pipe() -> Result<Pipe> {
+    (0..10)
+        .find_map(|i| {
+            std::fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(format!(r"\\.\pipe\discord-ipc-{}", i))
+                .ok()
+        })
+        .ok_or_else(|| Error::new(ErrorKind::NotFound, "Discord is not running"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_activity_updates() {
+        let frame = encode_frame(OP_FRAME, "{}");
+        assert_eq!(frame, [1, 0, 0, 0, 2, 0, 0, 0, b'{', b'}']);
+
+        let command = activity_command("Zelda no Densetsu", 1_700_000_000, 42, 3);
+        assert_eq!(command["cmd"], "SET_ACTIVITY");
+        assert_eq!(command["args"]["pid"], 42);
+        assert_eq!(command["args"]["activity"]["details"], "Zelda no Densetsu");
+        assert_eq!(
+            command["args"]["activity"]["timestamps"]["start"],
+            1_700_000_000u64
+        );
+        assert_eq!(command["nonce"], "3");
+    }
+}
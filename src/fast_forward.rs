@@ -0,0 +1,216 @@
+// Fast-forward audio: while several frames run per real frame, the APU makes
+// `speed` times more samples than the sound card plays. Instead of letting
+// the queue overflow, the condenser keeps the output at the real-time rate,
+// either by keeping one short grain in every `speed` (pitch is preserved,
+// joins are crossfaded) or by fading to silence. Silence can also stop the
+// APU mixing and filtering altogether for the extra speed. Leaving
+// fast-forward crossfades back into the live signal.
+
+/// Output samples per kept grain (~12 ms at 44.1 kHz).
+const GRAIN: usize = 512;
+/// Crossfade length at grain joins and for muting.
+const FADE: usize = 64;
+
+/// What fast-forward does with the audio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FastForwardAudio {
+    /// Keep one grain in every `speed`, so music plays at normal pitch.
+    #[default]
+    Resample,
+    /// Fade out while fast-forwarding and back in afterwards.
+    Mute,
+    /// Like `Mute`, but once faded out the APU stops mixing and filtering
+    /// (channels, timers and IRQs keep running) until normal speed.
+    Skip,
+}
+
+impl FastForwardAudio {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "resample" | "pitch" => Some(FastForwardAudio::Resample),
+            "mute" => Some(FastForwardAudio::Mute),
+            "skip" => Some(FastForwardAudio::Skip),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FastForwardAudio::Resample => "resample",
+            FastForwardAudio::Mute => "mute",
+            FastForwardAudio::Skip => "skip",
+        }
+    }
+}
+
+/// Drops APU samples down to the real-time rate while fast-forwarding.
+/// At speed 1 every sample passes through unchanged.
+#[derive(Debug, Clone)]
+pub struct AudioCondenser {
+    mode: FastForwardAudio,
+    speed: usize,
+    position: usize,
+    /// Samples just past the last kept grain, faded into the next one.
+    overhang: Vec<f32>,
+    fade_position: usize,
+    gain: f32,
+}
+
+impl Default for AudioCondenser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AudioCondenser {
+    pub fn new() -> Self {
+        AudioCondenser {
+            mode: FastForwardAudio::Resample,
+            speed: 1,
+            position: 0,
+            overhang: Vec::with_capacity(FADE),
+            fade_position: 0,
+            gain: 1.0,
+        }
+    }
+
+    pub fn mode(&self) -> FastForwardAudio {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: FastForwardAudio) {
+        self.mode = mode;
+    }
+
+    pub fn speed(&self) -> usize {
+        self.speed
+    }
+
+    /// Emulated frames per real frame; 1 is normal speed.
+    pub fn set_speed(&mut self, speed: usize) {
+        let speed = speed.max(1);
+        if speed != self.speed {
+            self.speed = speed;
+            self.position = 0;
+        }
+    }
+
+    /// Fast-forwarding in `Skip` mode with the fade-out finished: the
+    /// samples would be discarded, so they need not be synthesized.
+    pub fn skips_synthesis(&self) -> bool {
+        self.mode == FastForwardAudio::Skip && self.speed > 1 && self.gain == 0.0
+    }
+
+    /// Feed one APU sample; returns the sample to play, if any.
+    pub fn process(&mut self, sample: f32) -> Option<f32> {
+        if self.speed == 1 && self.overhang.is_empty() && self.gain == 1.0 {
+            return Some(sample);
+        }
+        let offset = self.position;
+        self.position = (self.position + 1) % (self.speed * GRAIN);
+        if self.speed == 1 || offset < GRAIN {
+            Some(self.emit(sample))
+        } else {
+            if offset < GRAIN + FADE && self.fade_position == 0 {
+                self.overhang.push(sample);
+            }
+            None
+        }
+    }
+
+    fn emit(&mut self, sample: f32) -> f32 {
+        let mut sample = sample;
+        if self.fade_position < self.overhang.len() {
+            let t = (self.fade_position + 1) as f32 / (self.overhang.len() + 1) as f32;
+            sample = self.overhang[self.fade_position] * (1.0 - t) + sample * t;
+            self.fade_position += 1;
+            if self.fade_position == self.overhang.len() {
+                self.overhang.clear();
+                self.fade_position = 0;
+            }
+        }
+        let target = if self.speed > 1 && self.mode != FastForwardAudio::Resample {
+            0.0
+        } else {
+            1.0
+        };
+        let step = 1.0 / FADE as f32;
+        self.gain = if self.gain < target {
+            (self.gain + step).min(target)
+        } else {
+            (self.gain - step).max(target)
+        };
+        sample * self.gain
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(condenser: &mut AudioCondenser, input: impl Iterator<Item = f32>) -> Vec<f32> {
+        input.filter_map(|s| condenser.process(s)).collect()
+    }
+
+    #[test]
+    fn resample_keeps_real_time_rate_and_pitch() {
+        let mut condenser = AudioCondenser::new();
+        assert_eq!(run(&mut condenser, [0.25, -0.5].into_iter()), [0.25, -0.5]);
+
+        // A ramp makes dropped stretches show up as jumps
+        condenser.set_speed(4);
+        let input = (0..GRAIN * 4 * 10).map(|i| (i % 4096) as f32 / 4096.0);
+        let output = run(&mut condenser, input);
+        assert_eq!(output.len(), GRAIN * 10);
+        // Inside a grain consecutive samples keep their input spacing
+        assert_eq!(output[GRAIN + 100] - output[GRAIN + 99], 1.0 / 4096.0);
+        // Joins are crossfaded, not cut
+        let largest_jump = output
+            .windows(2)
+            .map(|pair| (pair[1] - pair[0]).abs())
+            .fold(0.0, f32::max);
+        assert!(largest_jump < 0.05, "jump of {}", largest_jump);
+
+        // Back at normal speed everything passes through again
+        condenser.set_speed(1);
+        let output = run(&mut condenser, std::iter::repeat_n(0.5, FADE * 2));
+        assert_eq!(output.len(), FADE * 2);
+        assert_eq!(output[FADE * 2 - 1], 0.5);
+        assert_eq!(condenser.process(0.75), Some(0.75));
+    }
+
+    #[test]
+    fn mute_fades_out_and_back_in() {
+        let mut condenser = AudioCondenser::new();
+        condenser.set_mode(FastForwardAudio::Mute);
+        assert_eq!(FastForwardAudio::from_name("mute"), Some(condenser.mode()));
+        condenser.set_speed(3);
+        let output = run(&mut condenser, std::iter::repeat_n(0.5, GRAIN * 3));
+        assert_eq!(output.len(), GRAIN);
+        assert!(output[0] > 0.45);
+        assert_eq!(output[FADE], 0.0);
+        assert_eq!(output[GRAIN - 1], 0.0);
+
+        condenser.set_speed(1);
+        let output = run(&mut condenser, std::iter::repeat_n(0.5, FADE * 2));
+        assert!(output[0] < 0.05);
+        assert_eq!(output[FADE * 2 - 1], 0.5);
+        assert_eq!(condenser.process(0.25), Some(0.25));
+    }
+
+    #[test]
+    fn skip_stops_synthesis_once_silent() {
+        let mut condenser = AudioCondenser::new();
+        condenser.set_mode(FastForwardAudio::Skip);
+        condenser.set_speed(4);
+        assert!(!condenser.skips_synthesis());
+        run(&mut condenser, std::iter::repeat_n(0.5, GRAIN * 4));
+        assert!(condenser.skips_synthesis());
+
+        condenser.set_speed(1);
+        assert!(!condenser.skips_synthesis());
+        let output = run(&mut condenser, std::iter::repeat_n(0.5, FADE * 2));
+        assert!(output[0] < 0.05);
+        assert_eq!(output[FADE * 2 - 1], 0.5);
+    }
+}
@@ -0,0 +1,155 @@
+// Flicker reduction for the output picture. Games that multiplex sprites by
+// drawing them on alternate frames flicker on a sharp display; a CRT's
+// phosphors and an LCD's slow response smoothed that out. Either effect is
+// run over the RGB frame after it leaves the PPU, so emulation is untouched.
+
+/// How consecutive frames are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendMode {
+    /// Mix each frame with the one before it.
+    Average,
+    /// Lit pixels fade out over several frames instead of going dark at once.
+    Phosphor,
+}
+
+impl BlendMode {
+    pub const ALL: [BlendMode; 2] = [BlendMode::Average, BlendMode::Phosphor];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BlendMode::Average => "average",
+            BlendMode::Phosphor => "phosphor",
+        }
+    }
+
+    /// Strength used when none is given.
+    pub fn default_strength(self) -> f32 {
+        match self {
+            BlendMode::Average => 0.5,
+            BlendMode::Phosphor => 0.6,
+        }
+    }
+
+    /// Next mode in the hotkey cycle; `None` after the last one turns
+    /// blending off.
+    pub fn cycle(current: Option<Self>) -> Option<Self> {
+        match current {
+            None => Some(BlendMode::Average),
+            Some(BlendMode::Average) => Some(BlendMode::Phosphor),
+            Some(BlendMode::Phosphor) => None,
+        }
+    }
+}
+
+/// A blend mode with its strength and the frame it carries over.
+#[derive(Debug, Clone)]
+pub struct FrameBlend {
+    mode: BlendMode,
+    strength: f32,
+    /// The previous PPU frame (average) or the previous output (phosphor).
+    previous: Vec<u8>,
+}
+
+impl FrameBlend {
+    /// `strength` is clamped to 0-1. For `Average` it is the weight of the
+    /// previous frame (0.5 is an even mix); for `Phosphor` the share of its
+    /// brightness a pixel keeps each frame.
+    pub fn new(mode: BlendMode, strength: f32) -> Self {
+        FrameBlend {
+            mode,
+            strength: strength.clamp(0.0, 1.0),
+            previous: Vec::new(),
+        }
+    }
+
+    /// `average`, `phosphor` or either with a strength, `phosphor:0.8`.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (name, strength) = match spec.split_once(':') {
+            Some((name, strength)) => (name, Some(strength.parse::<f32>().ok()?)),
+            None => (spec, None),
+        };
+        let mode = BlendMode::from_name(name)?;
+        let strength = strength.unwrap_or(mode.default_strength());
+        (0.0..=1.0)
+            .contains(&strength)
+            .then(|| FrameBlend::new(mode, strength))
+    }
+
+    pub fn mode(&self) -> BlendMode {
+        self.mode
+    }
+
+    pub fn strength(&self) -> f32 {
+        self.strength
+    }
+
+    /// Blend `frame` (RGB24) in place with what came before. The first frame,
+    /// or one of a different size, passes through unchanged.
+    pub fn apply_rgb24(&mut self, frame: &mut [u8]) {
+        if self.previous.len() != frame.len() {
+            self.previous = frame.to_vec();
+            return;
+        }
+        let strength = self.strength;
+        match self.mode {
+            BlendMode::Average => {
+                for (out, previous) in frame.iter_mut().zip(self.previous.iter_mut()) {
+                    let current = *out;
+                    let mixed = current as f32 * (1.0 - strength) + *previous as f32 * strength;
+                    *out = (mixed + 0.5) as u8;
+                    *previous = current;
+                }
+            }
+            BlendMode::Phosphor => {
+                for (out, previous) in frame.iter_mut().zip(self.previous.iter_mut()) {
+                    let decayed = (*previous as f32 * strength) as u8;
+                    *out = (*out).max(decayed);
+                    *previous = *out;
+                }
+            }
+        }
+    }
+
+    /// Forget the carried-over frame, e.g. after loading a state, so the
+    /// old picture does not bleed into the new one.
+    pub fn reset(&mut self) {
+        self.previous.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn averages_and_decays_frames() {
+        let mut average = FrameBlend::parse("average").unwrap();
+        let mut frame = [200, 0, 100];
+        average.apply_rgb24(&mut frame);
+        assert_eq!(frame, [200, 0, 100]);
+        let mut frame = [0, 200, 100];
+        average.apply_rgb24(&mut frame);
+        assert_eq!(frame, [100, 100, 100]);
+        // Mixed with the previous PPU frame, not the previous output
+        let mut frame = [0, 200, 100];
+        average.apply_rgb24(&mut frame);
+        assert_eq!(frame, [0, 200, 100]);
+
+        let mut phosphor = FrameBlend::parse("phosphor:0.5").unwrap();
+        for (input, expected) in [(200, 200), (0, 100), (0, 50), (80, 80)] {
+            let mut frame = [input; 3];
+            phosphor.apply_rgb24(&mut frame);
+            assert_eq!(frame, [expected; 3]);
+        }
+
+        assert!(FrameBlend::parse("phosphor:1.5").is_none());
+        assert!(FrameBlend::parse("ghost").is_none());
+        assert_eq!(BlendMode::cycle(Some(BlendMode::Phosphor)), None);
+    }
+}
@@ -0,0 +1,160 @@
+use std::path::Path;
+
+/// Pixels of one text map cell, so a 256x240 frame prints as 32x30.
+pub const MAP_CELL: usize = 8;
+
+const MISMATCH_COLOR: [u8; 3] = [0xFF, 0x00, 0x00];
+
+/// Parse a binary (P6) PPM with 8-bit channels, as written by
+/// `headless_test --capture`. Returns width, height and RGB24 pixels.
+pub fn parse_ppm(data: &[u8]) -> Option<(usize, usize, Vec<u8>)> {
+    let mut pos = 0;
+    let mut fields = Vec::new();
+    while fields.len() < 4 {
+        while pos < data.len() && data[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        if data.get(pos) == Some(&b'#') {
+            while pos < data.len() && data[pos] != b'\n' {
+                pos += 1;
+            }
+            continue;
+        }
+        let start = pos;
+        while pos < data.len() && !data[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        if start == pos {
+            return None;
+        }
+        fields.push(std::str::from_utf8(&data[start..pos]).ok()?);
+    }
+    // Exactly one whitespace byte separates the header from the pixels
+    pos += 1;
+
+    let width: usize = fields[1].parse().ok()?;
+    let height: usize = fields[2].parse().ok()?;
+    if fields[0] != "P6" || fields[3] != "255" {
+        return None;
+    }
+    let pixels = data.get(pos..pos + width * height * 3)?;
+    Some((width, height, pixels.to_vec()))
+}
+
+pub fn load_ppm(path: impl AsRef<Path>) -> std::io::Result<(usize, usize, Vec<u8>)> {
+    let data = std::fs::read(path)?;
+    parse_ppm(&data)
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "not an 8-bit P6 PPM"))
+}
+
+/// Which pixels of a frame differ from a reference image.
+#[derive(Debug, Clone)]
+pub struct FrameDiff {
+    width: usize,
+    height: usize,
+    mismatched: Vec<bool>,
+}
+
+/// Compare two RGB24 frames of the same size pixel by pixel.
+pub fn diff_frames(width: usize, height: usize, actual: &[u8], expected: &[u8]) -> FrameDiff {
+    assert_eq!(actual.len(), width * height * 3, "frame size mismatch");
+    assert_eq!(
+        expected.len(),
+        width * height * 3,
+        "reference size mismatch"
+    );
+    let mismatched = actual
+        .chunks(3)
+        .zip(expected.chunks(3))
+        .map(|(a, e)| a != e)
+        .collect();
+    FrameDiff {
+        width,
+        height,
+        mismatched,
+    }
+}
+
+impl FrameDiff {
+    pub fn mismatched_pixels(&self) -> usize {
+        self.mismatched.iter().filter(|&&m| m).count()
+    }
+
+    pub fn is_match(&self) -> bool {
+        self.mismatched_pixels() == 0
+    }
+
+    /// Smallest rectangle holding every mismatch, as inclusive
+    /// (left, top, right, bottom) pixel coordinates.
+    pub fn bounds(&self) -> Option<(usize, usize, usize, usize)> {
+        let mut bounds: Option<(usize, usize, usize, usize)> = None;
+        for (i, _) in self.mismatched.iter().enumerate().filter(|(_, &m)| m) {
+            let (x, y) = (i % self.width, i / self.width);
+            bounds = Some(match bounds {
+                None => (x, y, x, y),
+                Some((l, t, r, b)) => (l.min(x), t.min(y), r.max(x), b.max(y)),
+            });
+        }
+        bounds
+    }
+
+    /// One character per `MAP_CELL` square: `.` when it matches, `#` when
+    /// any pixel in it differs. Scanline timing bugs show up as rows of `#`.
+    pub fn text_map(&self) -> String {
+        let columns = self.width.div_ceil(MAP_CELL);
+        let rows = self.height.div_ceil(MAP_CELL);
+        let mut cells = vec![false; columns * rows];
+        for (i, _) in self.mismatched.iter().enumerate().filter(|(_, &m)| m) {
+            let (x, y) = (i % self.width, i / self.width);
+            cells[(y / MAP_CELL) * columns + x / MAP_CELL] = true;
+        }
+        cells
+            .chunks(columns)
+            .map(|row| {
+                let mut line: String = row.iter().map(|&m| if m { '#' } else { '.' }).collect();
+                line.push('\n');
+                line
+            })
+            .collect()
+    }
+
+    /// The reference dimmed to gray with mismatched pixels in red.
+    pub fn overlay_rgb24(&self, expected: &[u8]) -> Vec<u8> {
+        let mut image = Vec::with_capacity(expected.len());
+        for (pixel, &mismatch) in expected.chunks(3).zip(&self.mismatched) {
+            if mismatch {
+                image.extend_from_slice(&MISMATCH_COLOR);
+            } else {
+                let gray = ((pixel[0] as u16 + pixel[1] as u16 + pixel[2] as u16) / 9) as u8;
+                image.extend_from_slice(&[gray; 3]);
+            }
+        }
+        image
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_locates_mismatches() {
+        let mut ppm = b"P6\n# reference\n16 9\n255\n".to_vec();
+        ppm.extend(std::iter::repeat_n(0x40, 16 * 9 * 3));
+        let (width, height, expected) = parse_ppm(&ppm).unwrap();
+        assert_eq!((width, height), (16, 9));
+
+        let mut actual = expected.clone();
+        let idx = (8 * 16 + 9) * 3;
+        actual[idx] = 0xFF;
+        let diff = diff_frames(width, height, &actual, &expected);
+
+        assert_eq!(diff.mismatched_pixels(), 1);
+        assert_eq!(diff.bounds(), Some((9, 8, 9, 8)));
+        assert_eq!(diff.text_map(), "..\n.#\n");
+        let overlay = diff.overlay_rgb24(&expected);
+        assert_eq!(&overlay[idx..idx + 3], &MISMATCH_COLOR);
+        assert_eq!(&overlay[..3], &[0x15; 3]);
+        assert!(parse_ppm(b"P3\n1 1\n255\n000").is_none());
+    }
+}
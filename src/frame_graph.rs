@@ -0,0 +1,185 @@
+// Frame-time graphs for stutter reports: the last 240 frames of emulation
+// time, render time (texture upload through present) and queued audio,
+// each plotted against the frame budget. Time above the budget line is a
+// dropped frame; audio below it is a buffer about to run dry.
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::hud_toast::draw_hud_label_rgb24;
+
+/// Frames of history kept and plotted, one pixel column each.
+pub const FRAME_GRAPH_HISTORY: usize = 240;
+
+const PANEL_TOP: usize = 24;
+const LANE_HEIGHT: usize = 22;
+const PANEL_COLOR: [u8; 3] = [0x10, 0x10, 0x10];
+const BUDGET_COLOR: [u8; 3] = [0x80, 0x80, 0x80];
+const LANES: [(&str, [u8; 3]); 3] = [
+    ("EMU", [0x58, 0xD8, 0x54]),
+    ("RND", [0x3C, 0xBC, 0xFC]),
+    ("AUD", [0xF8, 0xB8, 0x00]),
+];
+
+/// Where one real frame's time went.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameTiming {
+    /// Running the core for the frame (all frames of a fast-forward pass).
+    pub emulation: Duration,
+    /// Drawing overlays, uploading the texture and presenting.
+    pub render: Duration,
+    /// Audio queued for the sound card after the frame.
+    pub audio_buffered: Duration,
+}
+
+impl FrameTiming {
+    fn lane(&self, lane: usize) -> Duration {
+        match lane {
+            0 => self.emulation,
+            1 => self.render,
+            _ => self.audio_buffered,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FrameGraph {
+    timings: VecDeque<FrameTiming>,
+}
+
+impl FrameGraph {
+    pub fn new() -> Self {
+        FrameGraph {
+            timings: VecDeque::with_capacity(FRAME_GRAPH_HISTORY),
+        }
+    }
+
+    pub fn push(&mut self, timing: FrameTiming) {
+        if self.timings.len() == FRAME_GRAPH_HISTORY {
+            self.timings.pop_front();
+        }
+        self.timings.push_back(timing);
+    }
+
+    /// Oldest first.
+    pub fn timings(&self) -> impl Iterator<Item = &FrameTiming> {
+        self.timings.iter()
+    }
+
+    /// Frames whose emulation plus render time went over `budget`.
+    pub fn over_budget(&self, budget: Duration) -> usize {
+        self.timings
+            .iter()
+            .filter(|t| t.emulation + t.render > budget)
+            .count()
+    }
+}
+
+/// Draw the three graphs in a panel below the toast area. Each lane scales
+/// to twice the frame budget (or its peak, if higher) and marks the budget.
+pub fn draw_frame_graph_rgb24(
+    frame: &mut [u8],
+    width: usize,
+    height: usize,
+    graph: &FrameGraph,
+    budget: Duration,
+) {
+    let panel_h = LANES.len() * LANE_HEIGHT;
+    if width < FRAME_GRAPH_HISTORY
+        || height < PANEL_TOP + panel_h
+        || frame.len() < width * height * 3
+    {
+        return;
+    }
+    let mut set = |x: usize, y: usize, color: [u8; 3]| {
+        let idx = (y * width + x) * 3;
+        frame[idx..idx + 3].copy_from_slice(&color);
+    };
+    for y in PANEL_TOP..PANEL_TOP + panel_h {
+        for x in 0..width {
+            set(x, y, PANEL_COLOR);
+        }
+    }
+
+    let left = width - FRAME_GRAPH_HISTORY;
+    let plot_h = LANE_HEIGHT - 2;
+    for (lane, &(_, color)) in LANES.iter().enumerate() {
+        let bottom = PANEL_TOP + (lane + 1) * LANE_HEIGHT - 1;
+        let peak = graph
+            .timings()
+            .map(|t| t.lane(lane))
+            .max()
+            .unwrap_or_default();
+        let full_scale = peak.max(budget * 2).as_secs_f64().max(f64::EPSILON);
+        let height_of = |time: Duration| {
+            ((time.as_secs_f64() / full_scale * plot_h as f64) as usize).min(plot_h)
+        };
+
+        let budget_y = bottom - height_of(budget);
+        for x in (left..width).step_by(2) {
+            set(x, budget_y, BUDGET_COLOR);
+        }
+        // Newest frame at the right edge
+        let skip = FRAME_GRAPH_HISTORY - graph.timings.len();
+        for (i, timing) in graph.timings().enumerate() {
+            let x = left + skip + i;
+            for y in bottom - height_of(timing.lane(lane))..bottom {
+                set(x, y, color);
+            }
+        }
+    }
+
+    for (lane, &(name, color)) in LANES.iter().enumerate() {
+        let latest = graph
+            .timings
+            .back()
+            .map(|t| t.lane(lane))
+            .unwrap_or_default();
+        let label = format!("{} {:.1}MS", name, latest.as_secs_f64() * 1000.0);
+        let y = PANEL_TOP + lane * LANE_HEIGHT + 2;
+        draw_hud_label_rgb24(frame, width, height, 2, y, &label, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUDGET: Duration = Duration::from_micros(16_639);
+
+    fn timing(emulation_ms: u64, render_ms: u64) -> FrameTiming {
+        FrameTiming {
+            emulation: Duration::from_millis(emulation_ms),
+            render: Duration::from_millis(render_ms),
+            audio_buffered: Duration::from_millis(60),
+        }
+    }
+
+    #[test]
+    fn keeps_last_frames_and_counts_overruns() {
+        let mut graph = FrameGraph::new();
+        for i in 0..FRAME_GRAPH_HISTORY + 10 {
+            graph.push(timing(if i % 50 == 0 { 20 } else { 4 }, 1));
+        }
+        assert_eq!(graph.timings().count(), FRAME_GRAPH_HISTORY);
+        // Frames 50, 100, 150 and 200 are still in the window; 0 fell out
+        assert_eq!(graph.over_budget(BUDGET), 4);
+        assert_eq!(graph.timings().last(), Some(&timing(4, 1)));
+    }
+
+    #[test]
+    fn plots_newest_frame_at_the_right_edge() {
+        let mut graph = FrameGraph::new();
+        graph.push(timing(40, 0));
+        let mut frame = vec![0u8; 256 * 240 * 3];
+        draw_frame_graph_rgb24(&mut frame, 256, 240, &graph, BUDGET);
+
+        // A full-scale emulation bar in the last column, nothing to its left
+        let pixel = |x: usize, y: usize| &frame[(y * 256 + x) * 3..(y * 256 + x) * 3 + 3];
+        let bar_top = PANEL_TOP + LANE_HEIGHT - 1 - (LANE_HEIGHT - 2);
+        assert_eq!(pixel(255, bar_top), LANES[0].1);
+        assert_eq!(pixel(254, bar_top), PANEL_COLOR);
+        // Rows outside the panel are untouched
+        assert_eq!(pixel(100, PANEL_TOP - 1), [0, 0, 0]);
+        assert_eq!(pixel(100, PANEL_TOP + 3 * LANE_HEIGHT), [0, 0, 0]);
+    }
+}
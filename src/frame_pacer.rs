@@ -0,0 +1,155 @@
+use std::time::{Duration, Instant};
+
+/// NTSC NES frame rate: 5369318.18 Hz PPU / (341*262-0.5) = 60.0988 Hz
+pub const NTSC_FRAME_DURATION: Duration = Duration::from_nanos(16_639_267);
+
+const SAMPLE_RATE: u64 = 44_100;
+// Queued samples the audio-synced loop aims for (~4 frames, matching the
+// pre-buffer), and the smaller cushion the hybrid loop steers towards.
+const AUDIO_TARGET_SAMPLES: usize = 2940;
+const HYBRID_TARGET_SAMPLES: usize = 1470;
+// Largest frame-time correction the hybrid loop applies (0.5%).
+const HYBRID_MAX_ADJUST_NANOS: i64 = 83_000;
+
+/// What the front-end waits on between emulated frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SyncMode {
+    /// Block while the audio queue is above target; no crackle, frame
+    /// pacing follows the sound card clock.
+    #[default]
+    Audio,
+    /// One emulated frame per display refresh (present with vsync).
+    /// Smoothest on fixed 60Hz displays; runs fast on 120Hz ones.
+    Video,
+    /// Wall-clock frame timer nudged by the audio fill level, with a small
+    /// audio cushion. Lowest latency, suits 120Hz/VRR displays.
+    Hybrid,
+}
+
+impl SyncMode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "audio" => Some(SyncMode::Audio),
+            "video" | "vsync" => Some(SyncMode::Video),
+            "hybrid" => Some(SyncMode::Hybrid),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SyncMode::Audio => "audio",
+            SyncMode::Video => "video",
+            SyncMode::Hybrid => "hybrid",
+        }
+    }
+
+    pub fn uses_vsync(self) -> bool {
+        self == SyncMode::Video
+    }
+}
+
+/// Decides how long the front-end should sleep after each frame.
+pub struct FramePacer {
+    mode: SyncMode,
+    next_frame: Instant,
+    frame_duration: Duration,
+}
+
+impl FramePacer {
+    pub fn new(mode: SyncMode, now: Instant) -> Self {
+        Self {
+            mode,
+            next_frame: now,
+            frame_duration: NTSC_FRAME_DURATION,
+        }
+    }
+
+    /// Emulated frame length the hybrid timer paces to (PAL is 50Hz).
+    pub fn set_frame_duration(&mut self, frame_duration: Duration) {
+        self.frame_duration = frame_duration;
+    }
+
+    pub fn mode(&self) -> SyncMode {
+        self.mode
+    }
+
+    /// Time to wait before starting the next frame, given how many audio
+    /// samples are still queued for playback.
+    pub fn frame_delay(&mut self, now: Instant, queued_samples: usize) -> Duration {
+        match self.mode {
+            SyncMode::Video => Duration::ZERO,
+            SyncMode::Audio => {
+                let excess = queued_samples.saturating_sub(AUDIO_TARGET_SAMPLES) as u64;
+                Duration::from_nanos(excess * 1_000_000_000 / SAMPLE_RATE)
+            }
+            SyncMode::Hybrid => {
+                // Run slightly slower while audio piles up, faster while it
+                // starves, so the cushion stays small without drifting.
+                let error = queued_samples as i64 - HYBRID_TARGET_SAMPLES as i64;
+                let adjust = (error * 100).clamp(-HYBRID_MAX_ADJUST_NANOS, HYBRID_MAX_ADJUST_NANOS);
+                let frame = self.frame_duration.as_nanos() as i64 + adjust;
+                // Accumulate ideal frame boundaries to absorb sleep overshoot,
+                // but resync after a long stall instead of fast-forwarding.
+                self.next_frame += Duration::from_nanos(frame as u64);
+                if self.next_frame + self.frame_duration * 4 < now {
+                    self.next_frame = now;
+                }
+                self.next_frame.saturating_duration_since(now)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audio_mode_waits_for_excess_samples() {
+        let now = Instant::now();
+        let mut pacer = FramePacer::new(SyncMode::Audio, now);
+        assert_eq!(pacer.frame_delay(now, 1000), Duration::ZERO);
+        assert_eq!(
+            pacer.frame_delay(now, AUDIO_TARGET_SAMPLES + 441),
+            Duration::from_millis(10)
+        );
+    }
+
+    #[test]
+    fn video_mode_never_sleeps() {
+        let now = Instant::now();
+        let mut pacer = FramePacer::new(SyncMode::Video, now);
+        assert_eq!(pacer.frame_delay(now, 100_000), Duration::ZERO);
+    }
+
+    #[test]
+    fn hybrid_mode_steers_frame_time_by_audio_fill() {
+        let now = Instant::now();
+        let mut slow = FramePacer::new(SyncMode::Hybrid, now);
+        let mut fast = FramePacer::new(SyncMode::Hybrid, now);
+        let full = slow.frame_delay(now, HYBRID_TARGET_SAMPLES * 4);
+        let starved = fast.frame_delay(now, 0);
+        assert!(full > NTSC_FRAME_DURATION);
+        assert!(starved < NTSC_FRAME_DURATION);
+
+        // A long stall resyncs rather than running frames back to back
+        let later = now + Duration::from_secs(1);
+        assert_eq!(
+            slow.frame_delay(later, HYBRID_TARGET_SAMPLES),
+            Duration::ZERO
+        );
+        assert_eq!(
+            slow.frame_delay(later, HYBRID_TARGET_SAMPLES),
+            NTSC_FRAME_DURATION
+        );
+    }
+
+    #[test]
+    fn parses_mode_names() {
+        assert_eq!(SyncMode::from_name("vsync"), Some(SyncMode::Video));
+        assert_eq!(SyncMode::from_name("hybrid"), Some(SyncMode::Hybrid));
+        assert_eq!(SyncMode::from_name("fast"), None);
+        assert_eq!(SyncMode::default().name(), "audio");
+    }
+}
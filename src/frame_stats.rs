@@ -0,0 +1,141 @@
+use std::fmt;
+use std::ops::AddAssign;
+
+use crate::cartridge::BoardLeds;
+use crate::clock::ConsoleClock;
+use crate::hud_toast::draw_hud_label_rgb24;
+
+const PANEL_COLOR: [u8; 3] = [0x10, 0x10, 0x10];
+const TEXT_COLOR: [u8; 3] = [0x80, 0xF8, 0x80];
+const LED_SIZE: usize = 6;
+const LED_OFF: [u8; 3] = [0x30, 0x30, 0x30];
+const LED_RED: [u8; 3] = [0xF8, 0x38, 0x00];
+const LED_GREEN: [u8; 3] = [0x00, 0xE8, 0x00];
+
+/// What the core did during one emulated frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameStats {
+    /// PPU frame number the stats were collected for.
+    pub frame: u64,
+    /// CPU cycles run, including cycles stalled by DMA.
+    pub cpu_cycles: u64,
+    pub ppu_dots: u64,
+    pub nmis: u32,
+    /// IRQs taken by the CPU (APU frame counter, DMC and mapper).
+    pub irqs: u32,
+    /// BRK instructions executed.
+    pub brks: u32,
+    /// CPU cycles lost to OAM DMA and DMC sample fetches.
+    pub dma_stall_cycles: u32,
+    pub apu_samples: u32,
+}
+
+impl AddAssign for FrameStats {
+    /// Sum counters, e.g. over a `--bench` run; `frame` keeps the latest.
+    fn add_assign(&mut self, other: Self) {
+        self.frame = other.frame;
+        self.cpu_cycles += other.cpu_cycles;
+        self.ppu_dots += other.ppu_dots;
+        self.nmis += other.nmis;
+        self.irqs += other.irqs;
+        self.brks += other.brks;
+        self.dma_stall_cycles += other.dma_stall_cycles;
+        self.apu_samples += other.apu_samples;
+    }
+}
+
+impl fmt::Display for FrameStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "CPU {} DOTS {} NMI {} IRQ {} BRK {} DMA {} SMP {}",
+            self.cpu_cycles,
+            self.ppu_dots,
+            self.nmis,
+            self.irqs,
+            self.brks,
+            self.dma_stall_cycles,
+            self.apu_samples
+        )
+    }
+}
+
+/// Draw the last frame's stats in a strip along the bottom of the frame:
+/// the console clock on the first line, timing on the second, interrupt and
+/// DMA counts on the third.
+pub fn draw_frame_stats_rgb24(
+    frame: &mut [u8],
+    width: usize,
+    height: usize,
+    stats: &FrameStats,
+    clock: &ConsoleClock,
+) {
+    if width < 256 || height < 32 || frame.len() < width * height * 3 {
+        return;
+    }
+    for y in height - 29..height {
+        frame[y * width * 3..(y + 1) * width * 3]
+            .chunks_exact_mut(3)
+            .for_each(|pixel| pixel.copy_from_slice(&PANEL_COLOR));
+    }
+    let position = format!(
+        "FRAME {} LINE {} DOT {} CYC {}",
+        clock.frame, clock.scanline, clock.dot, clock.cpu_cycle
+    );
+    let timing = format!("CPU {} DOTS {}", stats.cpu_cycles, stats.ppu_dots);
+    let counts = format!(
+        "NMI {} IRQ {} BRK {} DMA {} SMP {}",
+        stats.nmis, stats.irqs, stats.brks, stats.dma_stall_cycles, stats.apu_samples
+    );
+    draw_hud_label_rgb24(frame, width, height, 2, height - 27, &position, TEXT_COLOR);
+    draw_hud_label_rgb24(frame, width, height, 2, height - 18, &timing, TEXT_COLOR);
+    draw_hud_label_rgb24(frame, width, height, 2, height - 9, &counts, TEXT_COLOR);
+}
+
+/// Draw the board's LEDs at the right end of the stats strip, red on top.
+pub fn draw_board_leds_rgb24(frame: &mut [u8], width: usize, height: usize, leds: BoardLeds) {
+    if width < 256 || height < 24 || frame.len() < width * height * 3 {
+        return;
+    }
+    let left = width - LED_SIZE - 2;
+    let lamps = [(leds.red, LED_RED), (leds.green, LED_GREEN)];
+    for (i, (lit, color)) in lamps.into_iter().enumerate() {
+        let top = height - 18 + i * 9;
+        for y in top..top + LED_SIZE {
+            let row = (y * width + left) * 3;
+            frame[row..row + LED_SIZE * 3]
+                .chunks_exact_mut(3)
+                .for_each(|pixel| pixel.copy_from_slice(if lit { &color } else { &LED_OFF }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_counters_and_keeps_latest_frame() {
+        let frame = FrameStats {
+            frame: 1,
+            cpu_cycles: 29781,
+            ppu_dots: 89342,
+            nmis: 1,
+            irqs: 0,
+            brks: 3,
+            dma_stall_cycles: 514,
+            apu_samples: 735,
+        };
+        let mut total = FrameStats::default();
+        total += frame;
+        total += FrameStats { frame: 2, ..frame };
+        assert_eq!(total.frame, 2);
+        assert_eq!(total.cpu_cycles, 59562);
+        assert_eq!(total.nmis, 2);
+        assert_eq!(total.brks, 6);
+        assert_eq!(
+            frame.to_string(),
+            "CPU 29781 DOTS 89342 NMI 1 IRQ 0 BRK 3 DMA 514 SMP 735"
+        );
+    }
+}
@@ -0,0 +1,96 @@
+// Window and event handling behind one trait, so the same player logic runs
+// under any backend: winit (the `winit-frontend` feature) or none at all for
+// headless runs and tests. A backend takes over the thread `Frontend::run` is
+// called on and drives the app from there; on macOS windowing only works on
+// the main thread, so that call is the one place the constraint applies.
+mod null;
+mod player;
+#[cfg(feature = "winit-frontend")]
+mod winit;
+
+use std::time::Duration;
+
+use crate::input::ControllerState;
+
+#[cfg(feature = "winit-frontend")]
+pub use self::winit::WinitFrontend;
+pub use null::NullFrontend;
+pub use player::PlayerApp;
+
+/// Keys the apps react to, independent of the windowing library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// A printable key, lowercase.
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Space,
+    Escape,
+    Tab,
+    Backspace,
+    /// Function key F1-F12.
+    F(u8),
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrontendEvent {
+    /// The window was closed.
+    Quit,
+    KeyDown {
+        key: Key,
+        repeat: bool,
+    },
+    KeyUp {
+        key: Key,
+    },
+    DroppedFile(String),
+}
+
+/// What an app wants after an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flow {
+    Continue,
+    Quit,
+}
+
+/// Emulator-side logic a frontend drives: events in, frames out.
+pub trait App {
+    fn event(&mut self, event: FrontendEvent) -> Flow;
+
+    /// Run one frame and return the 256x240 RGB24 picture to show, or
+    /// `None` to keep the last one (e.g. while paused).
+    fn frame(&mut self) -> Option<&[u8]>;
+
+    /// Time between frames.
+    fn frame_duration(&self) -> Duration;
+
+    fn title(&self) -> String {
+        "NES Emulator".to_string()
+    }
+}
+
+/// A windowing backend.
+pub trait Frontend {
+    /// Drive `app` until it or the user quits. Blocks the calling thread,
+    /// which must be the main thread for backends that open a window.
+    fn run(&mut self, app: &mut dyn App) -> Result<(), Box<dyn std::error::Error>>;
+}
+
+/// Default keyboard layout for controller 1, as in the SDL frontend.
+pub fn controller_button(key: Key) -> Option<ControllerState> {
+    match key {
+        Key::Char('x') => Some(ControllerState::B),
+        Key::Char('z') => Some(ControllerState::A),
+        Key::Space => Some(ControllerState::SELECT),
+        Key::Enter => Some(ControllerState::START),
+        Key::Up => Some(ControllerState::UP),
+        Key::Down => Some(ControllerState::DOWN),
+        Key::Left => Some(ControllerState::LEFT),
+        Key::Right => Some(ControllerState::RIGHT),
+        _ => None,
+    }
+}
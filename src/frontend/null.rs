@@ -0,0 +1,47 @@
+use super::{App, Flow, Frontend, FrontendEvent};
+
+/// No window: runs the app flat out for a fixed number of frames, feeding
+/// it scripted events, for headless runs and tests.
+#[derive(Debug, Clone, Default)]
+pub struct NullFrontend {
+    frames: u32,
+    /// `(frame, event)`: delivered before that frame runs.
+    events: Vec<(u32, FrontendEvent)>,
+    presented: u32,
+}
+
+impl NullFrontend {
+    pub fn new(frames: u32) -> Self {
+        NullFrontend {
+            frames,
+            ..Self::default()
+        }
+    }
+
+    /// Queue `event` for just before frame `frame` (0-based).
+    pub fn push_event(&mut self, frame: u32, event: FrontendEvent) {
+        self.events.push((frame, event));
+    }
+
+    /// Frames the app returned a picture for in the last run.
+    pub fn presented(&self) -> u32 {
+        self.presented
+    }
+}
+
+impl Frontend for NullFrontend {
+    fn run(&mut self, app: &mut dyn App) -> Result<(), Box<dyn std::error::Error>> {
+        self.presented = 0;
+        for frame in 0..self.frames {
+            for (_, event) in self.events.iter().filter(|(at, _)| *at == frame) {
+                if app.event(event.clone()) == Flow::Quit {
+                    return Ok(());
+                }
+            }
+            if app.frame().is_some() {
+                self.presented += 1;
+            }
+        }
+        Ok(())
+    }
+}
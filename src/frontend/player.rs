@@ -0,0 +1,132 @@
+use std::time::Duration;
+
+use super::{controller_button, App, Flow, FrontendEvent, Key};
+use crate::apu::AudioSink;
+use crate::input::ControllerState;
+use crate::window_title::window_title;
+use crate::Nes;
+
+/// The basic player: controller 1 on the keyboard, P to pause, Escape or
+/// closing the window to quit (saving SRAM), and a dropped ROM hot-swaps.
+pub struct PlayerApp {
+    nes: Nes,
+    held: ControllerState,
+    paused: bool,
+}
+
+impl PlayerApp {
+    /// There is no sound output yet, so samples are dropped.
+    pub fn new(mut nes: Nes) -> Self {
+        nes.set_audio_sink(AudioSink::Null);
+        PlayerApp {
+            nes,
+            held: ControllerState::empty(),
+            paused: false,
+        }
+    }
+
+    pub fn nes(&self) -> &Nes {
+        &self.nes
+    }
+
+    fn quit(&mut self) -> Flow {
+        if let Err(e) = self.nes.save_sram() {
+            eprintln!("Failed to save SRAM: {}", e);
+        }
+        Flow::Quit
+    }
+}
+
+impl App for PlayerApp {
+    fn event(&mut self, event: FrontendEvent) -> Flow {
+        match event {
+            FrontendEvent::Quit
+            | FrontendEvent::KeyDown {
+                key: Key::Escape, ..
+            } => return self.quit(),
+            FrontendEvent::KeyDown {
+                key: Key::Char('p'),
+                repeat: false,
+            } => self.paused = !self.paused,
+            FrontendEvent::KeyDown { key, .. } => {
+                if let Some(button) = controller_button(key) {
+                    self.held.insert(button);
+                }
+            }
+            FrontendEvent::KeyUp { key } => {
+                if let Some(button) = controller_button(key) {
+                    self.held.remove(button);
+                }
+            }
+            FrontendEvent::DroppedFile(path) => {
+                if let Err(e) = self.nes.load_rom(&path) {
+                    eprintln!("Failed to load {}: {}", path, e);
+                }
+            }
+        }
+        Flow::Continue
+    }
+
+    fn frame(&mut self) -> Option<&[u8]> {
+        if self.paused {
+            return None;
+        }
+        self.nes.set_controller(self.held);
+        while !self.nes.step() {}
+        Some(self.nes.get_frame_buffer())
+    }
+
+    fn frame_duration(&self) -> Duration {
+        self.nes.region().frame_duration()
+    }
+
+    fn title(&self) -> String {
+        let game = self
+            .nes
+            .rom_path()
+            .and_then(|path| std::path::Path::new(path).file_stem())
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        window_title(&game, None, None, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frontend::{Frontend, NullFrontend};
+
+    #[test]
+    fn runs_under_the_null_frontend() {
+        let mut frontend = NullFrontend::new(10);
+        frontend.push_event(
+            2,
+            FrontendEvent::KeyDown {
+                key: Key::Char('p'),
+                repeat: false,
+            },
+        );
+        frontend.push_event(
+            5,
+            FrontendEvent::KeyDown {
+                key: Key::Char('p'),
+                repeat: false,
+            },
+        );
+        frontend.push_event(
+            6,
+            FrontendEvent::KeyDown {
+                key: Key::Char('z'),
+                repeat: false,
+            },
+        );
+        frontend.push_event(8, FrontendEvent::Quit);
+
+        let mut app = PlayerApp::new(Nes::new());
+        frontend.run(&mut app).unwrap();
+        // Frames 0-1 and 5-7 ran; 2-4 were paused, 8 never came
+        assert_eq!(frontend.presented(), 5);
+        assert_eq!(app.held, ControllerState::A);
+        assert_eq!(app.title(), "NES Emulator");
+    }
+}
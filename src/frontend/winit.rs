@@ -0,0 +1,204 @@
+use std::num::NonZeroU32;
+use std::rc::Rc;
+use std::time::Instant;
+
+use softbuffer::{Context, Surface};
+use winit::application::ApplicationHandler;
+use winit::dpi::LogicalSize;
+use winit::event::{ElementState, WindowEvent};
+use winit::event_loop::{ActiveEventLoop, ControlFlow, EventLoop};
+use winit::keyboard::{Key as WinitKey, NamedKey};
+use winit::window::{Window, WindowId};
+
+use super::{App, Flow, Frontend, FrontendEvent, Key};
+
+const WIDTH: usize = 256;
+const HEIGHT: usize = 240;
+
+/// A winit window with a software framebuffer, scaled nearest-neighbor.
+pub struct WinitFrontend {
+    scale: u32,
+}
+
+impl WinitFrontend {
+    /// Window at `scale` times the NES resolution.
+    pub fn new(scale: u32) -> Self {
+        WinitFrontend {
+            scale: scale.max(1),
+        }
+    }
+}
+
+impl Frontend for WinitFrontend {
+    fn run(&mut self, app: &mut dyn App) -> Result<(), Box<dyn std::error::Error>> {
+        let event_loop = EventLoop::new()?;
+        let mut handler = Handler {
+            app,
+            scale: self.scale,
+            window: None,
+            next_frame: Instant::now(),
+            picture: vec![0; WIDTH * HEIGHT * 3],
+            error: None,
+        };
+        event_loop.run_app(&mut handler)?;
+        match handler.error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+struct WindowSurface {
+    window: Rc<Window>,
+    surface: Surface<Rc<Window>, Rc<Window>>,
+}
+
+struct Handler<'a> {
+    app: &'a mut dyn App,
+    scale: u32,
+    window: Option<WindowSurface>,
+    next_frame: Instant,
+    /// Last frame the app returned, redrawn on expose and resize.
+    picture: Vec<u8>,
+    error: Option<Box<dyn std::error::Error>>,
+}
+
+impl Handler<'_> {
+    fn fail(&mut self, event_loop: &ActiveEventLoop, error: Box<dyn std::error::Error>) {
+        self.error = Some(error);
+        event_loop.exit();
+    }
+
+    fn send(&mut self, event_loop: &ActiveEventLoop, event: FrontendEvent) {
+        if self.app.event(event) == Flow::Quit {
+            event_loop.exit();
+        }
+    }
+
+    fn draw(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(target) = self.window.as_mut() else {
+            return Ok(());
+        };
+        let size = target.window.inner_size();
+        let (Some(width), Some(height)) =
+            (NonZeroU32::new(size.width), NonZeroU32::new(size.height))
+        else {
+            return Ok(());
+        };
+        target.surface.resize(width, height)?;
+        let mut buffer = target.surface.buffer_mut()?;
+        let (width, height) = (size.width as usize, size.height as usize);
+        for y in 0..height {
+            let src_row = y * HEIGHT / height * WIDTH;
+            for x in 0..width {
+                let rgb = &self.picture[(src_row + x * WIDTH / width) * 3..][..3];
+                buffer[y * width + x] =
+                    (rgb[0] as u32) << 16 | (rgb[1] as u32) << 8 | rgb[2] as u32;
+            }
+        }
+        buffer.present()?;
+        Ok(())
+    }
+}
+
+impl ApplicationHandler for Handler<'_> {
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        if self.window.is_some() {
+            return;
+        }
+        let attributes = Window::default_attributes()
+            .with_title(self.app.title())
+            .with_inner_size(LogicalSize::new(
+                WIDTH as u32 * self.scale,
+                HEIGHT as u32 * self.scale,
+            ));
+        let created = event_loop
+            .create_window(attributes)
+            .map_err(Into::into)
+            .and_then(|window| {
+                let window = Rc::new(window);
+                let context = Context::new(window.clone())?;
+                let surface = Surface::new(&context, window.clone())?;
+                Ok::<_, Box<dyn std::error::Error>>(WindowSurface { window, surface })
+            });
+        match created {
+            Ok(target) => self.window = Some(target),
+            Err(e) => self.fail(event_loop, e),
+        }
+    }
+
+    fn window_event(&mut self, event_loop: &ActiveEventLoop, _: WindowId, event: WindowEvent) {
+        match event {
+            WindowEvent::CloseRequested => self.send(event_loop, FrontendEvent::Quit),
+            WindowEvent::DroppedFile(path) => self.send(
+                event_loop,
+                FrontendEvent::DroppedFile(path.to_string_lossy().into_owned()),
+            ),
+            WindowEvent::KeyboardInput { event, .. } => {
+                let key = map_key(&event.logical_key);
+                let event = match event.state {
+                    ElementState::Pressed => FrontendEvent::KeyDown {
+                        key,
+                        repeat: event.repeat,
+                    },
+                    ElementState::Released => FrontendEvent::KeyUp { key },
+                };
+                self.send(event_loop, event);
+            }
+            WindowEvent::RedrawRequested => {
+                if let Err(e) = self.draw() {
+                    self.fail(event_loop, e);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        let now = Instant::now();
+        if now >= self.next_frame {
+            if let Some(frame) = self.app.frame() {
+                self.picture.copy_from_slice(&frame[..WIDTH * HEIGHT * 3]);
+                if let Some(target) = &self.window {
+                    target.window.set_title(&self.app.title());
+                    target.window.request_redraw();
+                }
+            }
+            // Fall behind by at most a frame rather than racing to catch up
+            self.next_frame = (self.next_frame + self.app.frame_duration()).max(now);
+        }
+        event_loop.set_control_flow(ControlFlow::WaitUntil(self.next_frame));
+    }
+}
+
+fn map_key(key: &WinitKey) -> Key {
+    match key {
+        WinitKey::Named(NamedKey::ArrowUp) => Key::Up,
+        WinitKey::Named(NamedKey::ArrowDown) => Key::Down,
+        WinitKey::Named(NamedKey::ArrowLeft) => Key::Left,
+        WinitKey::Named(NamedKey::ArrowRight) => Key::Right,
+        WinitKey::Named(NamedKey::Enter) => Key::Enter,
+        WinitKey::Named(NamedKey::Space) => Key::Space,
+        WinitKey::Named(NamedKey::Escape) => Key::Escape,
+        WinitKey::Named(NamedKey::Tab) => Key::Tab,
+        WinitKey::Named(NamedKey::Backspace) => Key::Backspace,
+        WinitKey::Named(NamedKey::F1) => Key::F(1),
+        WinitKey::Named(NamedKey::F2) => Key::F(2),
+        WinitKey::Named(NamedKey::F3) => Key::F(3),
+        WinitKey::Named(NamedKey::F4) => Key::F(4),
+        WinitKey::Named(NamedKey::F5) => Key::F(5),
+        WinitKey::Named(NamedKey::F6) => Key::F(6),
+        WinitKey::Named(NamedKey::F7) => Key::F(7),
+        WinitKey::Named(NamedKey::F8) => Key::F(8),
+        WinitKey::Named(NamedKey::F9) => Key::F(9),
+        WinitKey::Named(NamedKey::F10) => Key::F(10),
+        WinitKey::Named(NamedKey::F11) => Key::F(11),
+        WinitKey::Named(NamedKey::F12) => Key::F(12),
+        WinitKey::Character(text) => match text.chars().next() {
+            Some(' ') => Key::Space,
+            Some(c) => Key::Char(c.to_ascii_lowercase()),
+            None => Key::Other,
+        },
+        _ => Key::Other,
+    }
+}
@@ -0,0 +1,200 @@
+// Titles stored inside ROM images: the NSF song name, the UNIF `NAME` chunk
+// and the internal header some Famicom carts keep at $FFE0. Japanese dumps
+// write these in Shift-JIS. Kana, full-width Latin and common punctuation
+// are decoded; kanji have no table here and come out as U+FFFD.
+
+/// Shift-JIS to text. Full-width letters and digits become ASCII so titles
+/// still match searches typed on a regular keyboard.
+pub fn decode_shift_jis(bytes: &[u8]) -> String {
+    let mut text = String::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let lead = bytes[i];
+        i += 1;
+        match lead {
+            0x00..=0x7F => text.push(lead as char),
+            // JIS X 0201 half-width katakana
+            0xA1..=0xDF => text.extend(char::from_u32(0xFF61 + (lead - 0xA1) as u32)),
+            0x81..=0x9F | 0xE0..=0xEF => {
+                let Some(&trail) = bytes.get(i) else {
+                    text.push(char::REPLACEMENT_CHARACTER);
+                    break;
+                };
+                i += 1;
+                text.push(decode_double_byte(lead, trail).unwrap_or(char::REPLACEMENT_CHARACTER));
+            }
+            _ => text.push(char::REPLACEMENT_CHARACTER),
+        }
+    }
+    text
+}
+
+/// One JIS X 0208 character from its Shift-JIS pair; `None` outside the
+/// rows decoded here.
+fn decode_double_byte(lead: u8, trail: u8) -> Option<char> {
+    if !matches!(trail, 0x40..=0x7E | 0x80..=0xFC) {
+        return None;
+    }
+    let row_pair = if lead <= 0x9F {
+        lead - 0x81
+    } else {
+        lead - 0xC1
+    } as u32;
+    let (row, cell) = if trail >= 0x9F {
+        (row_pair * 2 + 2, (trail - 0x9E) as u32)
+    } else {
+        let cell = (trail - 0x3F) as u32;
+        (
+            row_pair * 2 + 1,
+            if trail >= 0x80 { cell - 1 } else { cell },
+        )
+    };
+    match (row, cell) {
+        (1, 1) => Some(' '),
+        (1, 2) => Some('、'),
+        (1, 3) => Some('。'),
+        (1, 4) => Some(','),
+        (1, 5) => Some('.'),
+        (1, 6) => Some('・'),
+        (1, 7) => Some(':'),
+        (1, 9) => Some('?'),
+        (1, 10) => Some('!'),
+        (1, 11) => Some('゛'),
+        (1, 12) => Some('゜'),
+        (1, 28) => Some('ー'),
+        (1, 29) | (1, 30) => Some('-'),
+        (1, 42) => Some('('),
+        (1, 43) => Some(')'),
+        (1, 54) => Some('「'),
+        (1, 55) => Some('」'),
+        (1, 60) => Some('+'),
+        (1, 61) => Some('-'),
+        (1, 65) => Some('='),
+        (1, 85) => Some('&'),
+        // Full-width digits and Latin letters
+        (3, 16..=25) => char::from_u32('0' as u32 + cell - 16),
+        (3, 33..=58) => char::from_u32('A' as u32 + cell - 33),
+        (3, 65..=90) => char::from_u32('a' as u32 + cell - 65),
+        (4, 1..=83) => char::from_u32(0x3041 + cell - 1),
+        (5, 1..=86) => char::from_u32(0x30A1 + cell - 1),
+        _ => None,
+    }
+}
+
+/// A displayable title from a fixed-size header field, or `None` when the
+/// field is empty or isn't text. Fields end at the first NUL and are padded
+/// with spaces or $FF.
+pub fn normalize_header_title(bytes: &[u8]) -> Option<String> {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    let field = &bytes[..end];
+    let start = field.iter().position(|&b| b != b' ' && b != 0xFF)?;
+    let last = field.iter().rposition(|&b| b != b' ' && b != 0xFF)?;
+    let title = decode_shift_jis(&field[start..=last]);
+    // Code or data read as a title decodes to control or unknown characters
+    let known = title
+        .chars()
+        .filter(|&c| !c.is_control() && c != char::REPLACEMENT_CHARACTER)
+        .count();
+    let total = title.chars().count();
+    if title.chars().any(char::is_control) || known * 2 < total {
+        return None;
+    }
+    Some(title.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// The title stored in a ROM file's contents, if it has one.
+///
+/// FDS images have none: the disk info block only holds a three-letter
+/// game code.
+pub fn header_title(data: &[u8]) -> Option<String> {
+    if data.starts_with(b"NESM\x1A") {
+        normalize_header_title(data.get(0x0E..0x2E)?)
+    } else if data.starts_with(b"UNIF") {
+        unif_name(data)
+    } else if data.starts_with(b"NES\x1A") {
+        internal_title(data)
+    } else {
+        None
+    }
+}
+
+/// The UNIF `NAME` chunk.
+fn unif_name(data: &[u8]) -> Option<String> {
+    let mut offset = 32;
+    while let Some(header) = data.get(offset..offset + 8) {
+        let length = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let body = data.get(offset + 8..(offset + 8).checked_add(length)?)?;
+        if &header[..4] == b"NAME" {
+            return normalize_header_title(body);
+        }
+        offset += 8 + length;
+    }
+    None
+}
+
+/// The title in the header Nintendo put in the last 32 bytes of PRG ROM:
+/// right-aligned at $FFE0-$FFEF, with its length minus one at $FFF8.
+fn internal_title(data: &[u8]) -> Option<String> {
+    let trainer = if data.get(6)? & 0x04 != 0 { 512 } else { 0 };
+    let prg_end = 16 + trainer + data[4] as usize * 0x4000;
+    let header = data.get(prg_end.checked_sub(32)?..prg_end)?;
+    let length = header[0x18] as usize + 1;
+    if !(2..=16).contains(&length) {
+        return None;
+    }
+    normalize_header_title(&header[16 - length..16])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_kana_and_full_width_text() {
+        // "ドラゴンクエスト" in full-width katakana
+        let dragon_quest = [
+            0x83, 0x68, 0x83, 0x89, 0x83, 0x53, 0x83, 0x93, 0x83, 0x4E, 0x83, 0x47, 0x83, 0x58,
+            0x83, 0x67,
+        ];
+        assert_eq!(decode_shift_jis(&dragon_quest), "ドラゴンクエスト");
+        // Hiragana, the long vowel mark and full-width digits
+        assert_eq!(
+            decode_shift_jis(&[0x82, 0xA0, 0x81, 0x5B, 0x82, 0x52]),
+            "あー3"
+        );
+        // Half-width katakana "ｾﾞﾙﾀﾞ"
+        assert_eq!(decode_shift_jis(&[0xBE, 0xDE, 0xD9, 0xC0, 0xDE]), "ｾﾞﾙﾀﾞ");
+        // A kanji pair and a lone lead byte
+        assert_eq!(
+            decode_shift_jis(&[0x88, 0x9F, b'A', 0x83]),
+            "\u{FFFD}A\u{FFFD}"
+        );
+    }
+
+    #[test]
+    fn reads_titles_from_rom_headers() {
+        let mut nsf = vec![0u8; 0x80];
+        nsf[..5].copy_from_slice(b"NESM\x1A");
+        nsf[0x0E..0x16].copy_from_slice(&[0x83, 0x5E, 0x83, 0x43, 0x83, 0x67, 0x83, 0x8B]);
+        assert_eq!(header_title(&nsf).as_deref(), Some("タイトル"));
+
+        let mut unif = vec![0u8; 32];
+        unif[..4].copy_from_slice(b"UNIF");
+        unif.extend_from_slice(b"MAPR\x06\0\0\0NROM\0\0NAME\x0A\0\0\0Tetris 2  \0");
+        assert_eq!(header_title(&unif).as_deref(), Some("Tetris 2"));
+
+        let mut ines = vec![0u8; 16 + 0x4000];
+        ines[..5].copy_from_slice(b"NES\x1A\x01");
+        let header = 16 + 0x4000 - 32;
+        ines[header..header + 16].copy_from_slice(b"\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFFBASEBALL");
+        ines[header + 0x18] = 7;
+        assert_eq!(header_title(&ines).as_deref(), Some("BASEBALL"));
+
+        // Code where the header would be isn't a title
+        ines[header..header + 16].copy_from_slice(&[
+            0x8D, 0x00, 0x20, 0x4C, 0x10, 0xC0, 0x60, 0x40, 1, 2, 3, 4, 5, 6, 7, 8,
+        ]);
+        assert_eq!(header_title(&ines), None);
+        assert_eq!(header_title(b"FDS\x1A\x01"), None);
+    }
+}
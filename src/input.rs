@@ -0,0 +1,481 @@
+use bitflags::bitflags;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+bitflags! {
+    /// Buttons held on a standard controller, in the order the $4016/$4017
+    /// shift register reports them. Every layer that handles input (key
+    /// mapping, turbo, macros, the headless `--input` log) passes this type.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct ControllerState: u8 {
+        const A = 0x01;
+        const B = 0x02;
+        const SELECT = 0x04;
+        const START = 0x08;
+        const UP = 0x10;
+        const DOWN = 0x20;
+        const LEFT = 0x40;
+        const RIGHT = 0x80;
+    }
+}
+
+impl ControllerState {
+    /// Parse button names joined with `+` or `,` (any case), `-` or an
+    /// empty string for none, e.g. `Down+B` or `a,start`.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let spec = spec.trim();
+        if spec.is_empty() || spec == "-" {
+            return Some(Self::empty());
+        }
+        spec.split(['+', ','])
+            .try_fold(Self::empty(), |state, name| {
+                Some(state | Self::from_name(&name.trim().to_ascii_uppercase())?)
+            })
+    }
+}
+
+impl From<u8> for ControllerState {
+    fn from(bits: u8) -> Self {
+        Self::from_bits_retain(bits)
+    }
+}
+
+impl From<ControllerState> for u8 {
+    fn from(state: ControllerState) -> Self {
+        state.bits()
+    }
+}
+
+/// Names joined with `+`, or `-` for no buttons; the `parse` syntax.
+impl fmt::Display for ControllerState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "-");
+        }
+        for (i, (name, _)) in self.iter_names().enumerate() {
+            if i > 0 {
+                write!(f, "+")?;
+            }
+            write!(f, "{}", name)?;
+        }
+        Ok(())
+    }
+}
+
+// Serialized as the raw register byte, so logs and states stay compact
+impl Serialize for ControllerState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.bits().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ControllerState {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u8::deserialize(deserializer).map(Self::from_bits_retain)
+    }
+}
+
+/// Default turbo rate: 2 frames pressed, 2 released (15 presses/s at 60Hz).
+pub const DEFAULT_TURBO_RATE: u32 = 2;
+
+/// Console buttons a movie can press between two frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleEvent {
+    Reset,
+    Power,
+}
+
+impl ConsoleEvent {
+    pub fn name(self) -> &'static str {
+        match self {
+            ConsoleEvent::Reset => "RESET",
+            ConsoleEvent::Power => "POWER",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_uppercase().as_str() {
+            "RESET" => Some(ConsoleEvent::Reset),
+            "POWER" => Some(ConsoleEvent::Power),
+            _ => None,
+        }
+    }
+}
+
+/// Something the console drives back out to a controller: a rumble motor
+/// or a light. Emulated hardware queues these and the frontend forwards
+/// them to gamepads that have the matching actuator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControllerFeedback {
+    /// Motor strength for controller `port`, 0 stops it.
+    Rumble { port: u8, strength: u8 },
+    /// Light color for controller `port`, black turns it off.
+    Led { port: u8, rgb: [u8; 3] },
+}
+
+/// A sequence of controller states, one per frame, plus reset/power
+/// events that happen right before a given frame.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputMacro {
+    frames: Vec<ControllerState>,
+    /// (frame index, event), in frame order.
+    events: Vec<(usize, ConsoleEvent)>,
+}
+
+impl InputMacro {
+    pub fn from_frames(frames: Vec<ControllerState>) -> Self {
+        Self {
+            frames,
+            events: Vec::new(),
+        }
+    }
+
+    /// A macro from per-frame states and `(frame, event)` pairs in any
+    /// order; events on the same frame keep their relative order.
+    pub fn with_events(
+        frames: Vec<ControllerState>,
+        mut events: Vec<(usize, ConsoleEvent)>,
+    ) -> Self {
+        events.sort_by_key(|&(at, _)| at);
+        Self { frames, events }
+    }
+
+    /// Parse comma separated steps of `buttons[*frames]`, buttons joined
+    /// with `+` and `-` for none, e.g. `DOWN*2,DOWN+RIGHT,RIGHT+B*3,-*4`.
+    /// A `RESET` or `POWER` step presses that console button before the
+    /// next frame, e.g. `START,-*30,RESET,A`.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut frames = Vec::new();
+        let mut events = Vec::new();
+        for step in spec.split(',') {
+            if let Some(event) = ConsoleEvent::from_name(step) {
+                events.push((frames.len(), event));
+                continue;
+            }
+            let (buttons, count) = match step.trim().split_once('*') {
+                Some((buttons, count)) => (buttons, count.parse::<usize>().ok()?),
+                None => (step.trim(), 1),
+            };
+            let state = ControllerState::parse(buttons)?;
+            frames.resize(frames.len() + count, state);
+        }
+        if frames.is_empty() && events.is_empty() {
+            None
+        } else {
+            Some(Self { frames, events })
+        }
+    }
+
+    pub fn frames(&self) -> &[ControllerState] {
+        &self.frames
+    }
+
+    pub fn events(&self) -> &[(usize, ConsoleEvent)] {
+        &self.events
+    }
+
+    /// Events to apply before frame `frame` runs.
+    pub fn events_at(&self, frame: usize) -> impl Iterator<Item = ConsoleEvent> + '_ {
+        let start = self.events.partition_point(|&(at, _)| at < frame);
+        self.events[start..]
+            .iter()
+            .take_while(move |&&(at, _)| at == frame)
+            .map(|&(_, event)| event)
+    }
+
+    /// Frames played back, counting trailing events as one more frame.
+    fn playback_len(&self) -> usize {
+        match self.events.last() {
+            Some(&(at, _)) if at >= self.frames.len() => at + 1,
+            _ => self.frames.len(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty() && self.events.is_empty()
+    }
+}
+
+/// Formats in the `parse` syntax, so a recording can be replayed with `--macro`.
+impl fmt::Display for InputMacro {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        let mut separator = |f: &mut fmt::Formatter<'_>| {
+            let result = if first { Ok(()) } else { write!(f, ",") };
+            first = false;
+            result
+        };
+        let mut i = 0;
+        loop {
+            for event in self.events_at(i) {
+                separator(f)?;
+                write!(f, "{}", event.name())?;
+            }
+            if i >= self.frames.len() {
+                break;
+            }
+            // Runs stop at the next event so it lands on the same frame
+            let next_event = self
+                .events
+                .iter()
+                .map(|&(at, _)| at)
+                .find(|&at| at > i)
+                .unwrap_or(self.frames.len());
+            let state = self.frames[i];
+            let run = self.frames[i..next_event.min(self.frames.len())]
+                .iter()
+                .take_while(|&&s| s == state)
+                .count();
+            separator(f)?;
+            write!(f, "{}", state)?;
+            if run > 1 {
+                write!(f, "*{}", run)?;
+            }
+            i += run;
+        }
+        Ok(())
+    }
+}
+
+/// Controller 1 input from the frontend: held buttons plus turbo A/B and
+/// macro playback, resolved once per frame by `next_frame`.
+#[derive(Debug, Clone)]
+pub struct InputState {
+    held: ControllerState,
+    turbo_held: ControllerState,
+    /// Frames pressed (then released) per turbo pulse, for A and B.
+    turbo_rates: [u32; 2],
+    turbo_frame: u32,
+    recording: Option<InputMacro>,
+    input_macro: Option<InputMacro>,
+    playback: Option<usize>,
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self::new([DEFAULT_TURBO_RATE; 2])
+    }
+}
+
+impl InputState {
+    pub fn new(turbo_rates: [u32; 2]) -> Self {
+        Self {
+            held: ControllerState::empty(),
+            turbo_held: ControllerState::empty(),
+            turbo_rates: turbo_rates.map(|rate| rate.max(1)),
+            turbo_frame: 0,
+            recording: None,
+            input_macro: None,
+            playback: None,
+        }
+    }
+
+    pub fn held(&self) -> ControllerState {
+        self.held
+    }
+
+    pub fn set_held(&mut self, buttons: ControllerState) {
+        self.held = buttons;
+    }
+
+    /// Hold or release turbo for `A` and/or `B`.
+    pub fn set_turbo(&mut self, buttons: ControllerState, held: bool) {
+        let buttons = buttons & (ControllerState::A | ControllerState::B);
+        if held {
+            if !self.turbo_held.intersects(buttons) {
+                // Start each burst on a press
+                self.turbo_frame = 0;
+            }
+            self.turbo_held |= buttons;
+        } else {
+            self.turbo_held.remove(buttons);
+        }
+    }
+
+    fn turbo_buttons(&self) -> ControllerState {
+        [ControllerState::A, ControllerState::B]
+            .into_iter()
+            .zip(self.turbo_rates)
+            .filter(|&(button, rate)| {
+                self.turbo_held.contains(button) && (self.turbo_frame / rate) & 1 == 0
+            })
+            .fold(ControllerState::empty(), |state, (button, _)| {
+                state | button
+            })
+    }
+
+    pub fn start_recording(&mut self) {
+        self.recording = Some(InputMacro::default());
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    /// Stop recording and keep the result as the macro to play. Returns
+    /// `None` if nothing was recorded.
+    pub fn stop_recording(&mut self) -> Option<&InputMacro> {
+        let recording = self.recording.take()?;
+        if recording.is_empty() {
+            return None;
+        }
+        self.input_macro = Some(recording);
+        self.input_macro.as_ref()
+    }
+
+    /// Note a reset or power press made by the user, so the recording
+    /// repeats it before the same frame.
+    pub fn record_event(&mut self, event: ConsoleEvent) {
+        if let Some(recording) = self.recording.as_mut() {
+            recording.events.push((recording.frames.len(), event));
+        }
+    }
+
+    /// Reset/power events the playing macro wants applied before the next
+    /// frame. Call before `next_frame`.
+    pub fn pending_events(&self) -> Vec<ConsoleEvent> {
+        match (self.playback, self.input_macro.as_ref()) {
+            (Some(index), Some(input_macro)) => input_macro.events_at(index).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn set_macro(&mut self, input_macro: InputMacro) {
+        self.input_macro = Some(input_macro);
+        self.playback = None;
+    }
+
+    /// Start playing the macro from the top; returns false without one.
+    pub fn play_macro(&mut self) -> bool {
+        self.playback = self
+            .input_macro
+            .as_ref()
+            .filter(|input_macro| !input_macro.is_empty())
+            .map(|_| 0);
+        self.playback.is_some()
+    }
+
+    pub fn macro_playing(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    /// Controller state for the next emulated frame. This is the value to
+    /// hand to `Nes::set_controller` and what an input log should record.
+    pub fn next_frame(&mut self) -> ControllerState {
+        let user = self.held | self.turbo_buttons();
+        if let Some(recording) = self.recording.as_mut() {
+            recording.frames.push(user);
+        }
+
+        let mut state = user;
+        if let (Some(index), Some(input_macro)) = (self.playback, self.input_macro.as_ref()) {
+            state |= input_macro.frames.get(index).copied().unwrap_or_default();
+            self.playback = Some(index + 1).filter(|&next| next < input_macro.playback_len());
+        }
+        self.turbo_frame = self.turbo_frame.wrapping_add(1);
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turbo_pulses_at_each_buttons_rate() {
+        let mut input = InputState::new([1, 3]);
+        input.set_turbo(ControllerState::A | ControllerState::B, true);
+        let frames: Vec<u8> = (0..6).map(|_| input.next_frame().bits()).collect();
+        assert_eq!(frames, [0x03, 0x02, 0x03, 0x00, 0x01, 0x00]);
+
+        input.set_turbo(ControllerState::A | ControllerState::B, false);
+        input.set_held(ControllerState::RIGHT);
+        assert_eq!(input.next_frame(), ControllerState::RIGHT);
+    }
+
+    #[test]
+    fn controller_state_parses_names_and_round_trips() {
+        let state = ControllerState::parse("Down+b").unwrap();
+        assert_eq!(state, ControllerState::DOWN | ControllerState::B);
+        assert_eq!(
+            ControllerState::parse("A,Start"),
+            Some(ControllerState::from(0x09))
+        );
+        assert_eq!(ControllerState::parse("-"), Some(ControllerState::empty()));
+        assert!(ControllerState::parse("JUMP").is_none());
+        assert_eq!(state.to_string(), "B+DOWN");
+        assert_eq!(ControllerState::parse(&state.to_string()), Some(state));
+        assert_eq!(u8::from(state), 0x22);
+
+        let encoded = bincode::serialize(&state).unwrap();
+        assert_eq!(encoded, [0x22]);
+        assert_eq!(
+            bincode::deserialize::<ControllerState>(&encoded).unwrap(),
+            state
+        );
+    }
+
+    #[test]
+    fn parses_and_formats_macros() {
+        let input_macro = InputMacro::parse("down*2,Down+Right,RIGHT+B*3,-*4").unwrap();
+        assert_eq!(input_macro.len(), 10);
+        assert_eq!(input_macro.frames()[2].bits(), 0xA0);
+        assert_eq!(input_macro.to_string(), "DOWN*2,DOWN+RIGHT,B+RIGHT*3,-*4");
+        assert!(InputMacro::parse("JUMP").is_none());
+        assert!(InputMacro::parse("A*x").is_none());
+    }
+
+    #[test]
+    fn recorded_macro_plays_back_over_held_buttons() {
+        let mut input = InputState::default();
+        input.start_recording();
+        input.set_held(ControllerState::A);
+        input.next_frame();
+        input.set_held(ControllerState::empty());
+        input.next_frame();
+        assert_eq!(
+            input.stop_recording().unwrap().frames(),
+            [ControllerState::A, ControllerState::empty()]
+        );
+
+        input.set_held(ControllerState::START);
+        assert!(input.play_macro());
+        assert_eq!(input.next_frame().bits(), 0x09);
+        assert_eq!(input.next_frame(), ControllerState::START);
+        assert!(!input.macro_playing());
+        assert_eq!(input.next_frame(), ControllerState::START);
+    }
+
+    #[test]
+    fn console_events_land_on_their_frame() {
+        let input_macro = InputMacro::parse("A*3,reset,-,POWER").unwrap();
+        assert_eq!(
+            input_macro.events(),
+            [(3, ConsoleEvent::Reset), (4, ConsoleEvent::Power)]
+        );
+        assert_eq!(input_macro.to_string(), "A*3,RESET,-,POWER");
+        // An event in the middle of a run splits it
+        let mut recorded = InputState::default();
+        recorded.start_recording();
+        recorded.set_held(ControllerState::A);
+        recorded.next_frame();
+        recorded.record_event(ConsoleEvent::Reset);
+        recorded.next_frame();
+        let recorded = recorded.stop_recording().unwrap().clone();
+        assert_eq!(recorded.to_string(), "A,RESET,A");
+
+        let mut input = InputState::default();
+        input.set_macro(input_macro);
+        input.play_macro();
+        let mut log = Vec::new();
+        while input.macro_playing() {
+            log.extend(input.pending_events());
+            input.next_frame();
+        }
+        assert_eq!(log, [ConsoleEvent::Reset, ConsoleEvent::Power]);
+    }
+}
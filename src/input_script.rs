@@ -0,0 +1,831 @@
+// Input scripts: a line-based file format for scripted controller input,
+// compiled into the same `InputMacro` that `--macro` plays. One command per
+// line, `#` starts a comment:
+//
+//   label boss = 1200
+//   macro jump = A*4,-*2
+//   hold START frames 60-62
+//   hold RIGHT frames 100-160
+//   press A at 130 repeat 3 every 10
+//   play jump at boss+30
+//   reset at 2000
+//
+// Frames are numbers, labels or `label+offset`; ranges are inclusive.
+// Buttons that overlap on a frame are combined.
+//
+// Conditions make automated runs independent of exact timing. They are
+// checked at the start of every frame against CPU RAM ($0000-$1FFF) and
+// PRG-RAM ($6000-$7FFF) through `Nes::ram` / `Nes::prg_ram`:
+//
+//   wait until ram[$0770] == 3 then press START
+//   wait until ram[$0770] == 1 then hold RIGHT frames 0-120
+//   fail if frame > 2000
+//
+// Waits fire one after another, each armed once the previous one has
+// fired; frames after `then` count from the frame the wait fired on.
+use crate::input::{ConsoleEvent, ControllerState, InputMacro};
+use crate::Nes;
+use std::collections::HashMap;
+use std::fmt;
+
+/// A syntax or reference error, with 1-based line and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+/// A whitespace-separated word and the column it starts at.
+#[derive(Clone, Copy)]
+struct Token<'a> {
+    text: &'a str,
+    column: usize,
+}
+
+struct Line<'a> {
+    number: usize,
+    tokens: Vec<Token<'a>>,
+    /// Column just past the last token, for "expected ..." at end of line.
+    end: usize,
+    pos: usize,
+}
+
+impl<'a> Line<'a> {
+    fn new(number: usize, text: &'a str) -> Self {
+        let text = text.split('#').next().unwrap_or("");
+        let mut tokens = Vec::new();
+        let mut start = None;
+        for (i, c) in text.char_indices().chain([(text.len(), ' ')]) {
+            match (c.is_whitespace(), start) {
+                (false, None) => start = Some(i),
+                (true, Some(s)) => {
+                    tokens.push(Token {
+                        text: &text[s..i],
+                        column: s + 1,
+                    });
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        Line {
+            number,
+            end: text.trim_end().len() + 1,
+            tokens,
+            pos: 0,
+        }
+    }
+
+    fn error(&self, column: usize, message: String) -> ScriptError {
+        ScriptError {
+            line: self.number,
+            column,
+            message,
+        }
+    }
+
+    fn next(&mut self, what: &str) -> Result<Token<'a>, ScriptError> {
+        let token = self.tokens.get(self.pos).copied();
+        self.pos += 1;
+        token.ok_or_else(|| self.error(self.end, format!("expected {}", what)))
+    }
+
+    fn expect(&mut self, keyword: &str) -> Result<(), ScriptError> {
+        let token = self.next(&format!("'{}'", keyword))?;
+        if token.text.eq_ignore_ascii_case(keyword) {
+            Ok(())
+        } else {
+            Err(self.error(
+                token.column,
+                format!("expected '{}', found '{}'", keyword, token.text),
+            ))
+        }
+    }
+
+    /// Everything after the current token, as written.
+    fn rest(&mut self, source: &'a str, what: &str) -> Result<Token<'a>, ScriptError> {
+        let first = self.next(what)?;
+        let last = self.tokens[self.tokens.len() - 1];
+        self.pos = self.tokens.len();
+        let start = first.column - 1;
+        let end = last.column - 1 + last.text.len();
+        Ok(Token {
+            text: &source[start..end],
+            column: first.column,
+        })
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(|token| token.text)
+    }
+
+    fn finish(&self) -> Result<(), ScriptError> {
+        match self.tokens.get(self.pos) {
+            Some(token) => Err(self.error(token.column, format!("unexpected '{}'", token.text))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// What a command puts on the timeline, before repeats are applied.
+#[derive(Debug, Clone)]
+enum Action {
+    Hold(ControllerState, usize),
+    Event(ConsoleEvent),
+    Play(InputMacro),
+}
+
+/// An action with its start frame and `repeat <count> every <gap>`.
+#[derive(Debug, Clone)]
+struct Placement {
+    at: usize,
+    action: Action,
+    count: usize,
+    gap: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operand {
+    Frame,
+    Memory(u16),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+const COMPARISONS: [(&str, Comparison); 6] = [
+    ("==", Comparison::Eq),
+    ("!=", Comparison::Ne),
+    ("<=", Comparison::Le),
+    (">=", Comparison::Ge),
+    ("<", Comparison::Lt),
+    (">", Comparison::Gt),
+];
+
+/// `frame` or `ram[addr]` compared against a number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Condition {
+    operand: Operand,
+    comparison: Comparison,
+    value: u64,
+    text: String,
+}
+
+impl Condition {
+    /// Parse e.g. `ram[$0770] == 3`, `ram:$00FE==0x5A` or `frame > 2000`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (operand, comparison, value) = COMPARISONS.iter().find_map(|&(op, comparison)| {
+            let (left, right) = text.split_once(op)?;
+            Some((left.trim(), comparison, right.trim()))
+        })?;
+        let operand = if operand.eq_ignore_ascii_case("frame") {
+            Operand::Frame
+        } else {
+            let prefix = operand.get(..4)?;
+            let address = if prefix.eq_ignore_ascii_case("ram[") {
+                operand.strip_suffix(']')?.get(4..)?
+            } else if prefix.eq_ignore_ascii_case("ram:") {
+                &operand[4..]
+            } else {
+                return None;
+            };
+            let address = u16::try_from(parse_number(address)?).ok()?;
+            if !matches!(address, 0x0000..=0x1FFF | 0x6000..=0x7FFF) {
+                return None;
+            }
+            Operand::Memory(address)
+        };
+        Some(Condition {
+            operand,
+            comparison,
+            value: parse_number(value)?,
+            text: text.to_string(),
+        })
+    }
+
+    /// The left-hand side's value at the start of `frame`.
+    pub fn current(&self, frame: usize, nes: &Nes) -> u64 {
+        match self.operand {
+            Operand::Frame => frame as u64,
+            Operand::Memory(address @ 0x0000..=0x1FFF) => {
+                nes.ram()[address as usize & 0x07FF] as u64
+            }
+            Operand::Memory(address) => nes
+                .prg_ram()
+                .and_then(|ram| ram.get(address as usize - 0x6000).copied())
+                .unwrap_or(0) as u64,
+        }
+    }
+
+    /// Whether the condition holds at the start of `frame`.
+    pub fn holds(&self, frame: usize, nes: &Nes) -> bool {
+        let current = self.current(frame, nes);
+        match self.comparison {
+            Comparison::Eq => current == self.value,
+            Comparison::Ne => current != self.value,
+            Comparison::Lt => current < self.value,
+            Comparison::Le => current <= self.value,
+            Comparison::Gt => current > self.value,
+            Comparison::Ge => current >= self.value,
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TriggerKind {
+    When(Condition),
+    /// The byte read by the condition's left-hand side changed.
+    Changes(Condition),
+}
+
+/// An edge-triggered condition for features that react to the game's
+/// state (autosplits, practice states): `ram[$075C] changes` fires when the
+/// byte differs from the frame before, any other condition on the frame it
+/// becomes true.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RamTrigger {
+    kind: TriggerKind,
+    last: Option<u64>,
+}
+
+impl RamTrigger {
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let kind = match text.strip_suffix("changes") {
+            // Only the operand is used; the comparison is a placeholder
+            Some(operand) => TriggerKind::Changes(Condition::parse(&format!("{} == 0", operand))?),
+            None => TriggerKind::When(Condition::parse(text)?),
+        };
+        Some(RamTrigger { kind, last: None })
+    }
+
+    /// Evaluate at the start of `frame`; `true` on the frame it fires. Call
+    /// every frame so no edge is missed.
+    pub fn fired(&mut self, frame: usize, nes: &Nes) -> bool {
+        let last = self.last;
+        let now = self.sample(frame, nes);
+        match self.kind {
+            TriggerKind::When(_) => now == 1 && last == Some(0),
+            TriggerKind::Changes(_) => last.is_some_and(|last| last != now),
+        }
+    }
+
+    /// Take the machine's current state as the baseline without firing,
+    /// e.g. after a state load jumped the game somewhere else.
+    pub fn rearm(&mut self, frame: usize, nes: &Nes) {
+        self.sample(frame, nes);
+    }
+
+    fn sample(&mut self, frame: usize, nes: &Nes) -> u64 {
+        let now = match &self.kind {
+            TriggerKind::When(condition) => condition.holds(frame, nes) as u64,
+            TriggerKind::Changes(condition) => condition.current(frame, nes),
+        };
+        self.last = Some(now);
+        now
+    }
+}
+
+/// `$0770`, `0x770` or decimal.
+fn parse_number(text: &str) -> Option<u64> {
+    if let Some(hex) = text.strip_prefix('$').or_else(|| text.strip_prefix("0x")) {
+        u64::from_str_radix(hex, 16).ok()
+    } else {
+        text.parse().ok()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Wait {
+    line: usize,
+    condition: Condition,
+    then: Option<Placement>,
+}
+
+#[derive(Debug, Clone)]
+struct Failure {
+    line: usize,
+    condition: Condition,
+}
+
+/// Frames and events laid down by script commands.
+#[derive(Debug, Clone, Default)]
+struct Timeline {
+    frames: Vec<ControllerState>,
+    events: Vec<(usize, ConsoleEvent)>,
+}
+
+impl Timeline {
+    fn place(&mut self, offset: usize, placement: &Placement) {
+        for i in 0..placement.count {
+            let at = offset + placement.at + i * placement.gap;
+            match &placement.action {
+                Action::Hold(buttons, len) => {
+                    self.or_frames(at, std::iter::repeat_n(*buttons, *len))
+                }
+                Action::Event(event) => self.events.push((at, *event)),
+                Action::Play(input_macro) => {
+                    self.or_frames(at, input_macro.frames().iter().copied());
+                    self.events.extend(
+                        input_macro
+                            .events()
+                            .iter()
+                            .map(|&(i, event)| (at + i, event)),
+                    );
+                }
+            }
+        }
+    }
+
+    fn or_frames(&mut self, at: usize, states: impl ExactSizeIterator<Item = ControllerState>) {
+        let end = at + states.len();
+        if self.frames.len() < end {
+            self.frames.resize(end, ControllerState::empty());
+        }
+        for (frame, state) in self.frames[at..end].iter_mut().zip(states) {
+            *frame |= state;
+        }
+    }
+}
+
+#[derive(Default)]
+struct Compiler {
+    labels: HashMap<String, usize>,
+    macros: HashMap<String, InputMacro>,
+    timeline: Timeline,
+    waits: Vec<Wait>,
+    failures: Vec<Failure>,
+}
+
+impl Compiler {
+    fn frame(&self, line: &Line, token: Token) -> Result<usize, ScriptError> {
+        let (base, offset) = match token.text.split_once('+') {
+            Some((base, offset)) => (base, Some(offset)),
+            None => (token.text, None),
+        };
+        let base = match base.parse::<usize>() {
+            Ok(frame) => frame,
+            Err(_) => *self
+                .labels
+                .get(base)
+                .ok_or_else(|| line.error(token.column, format!("unknown label '{}'", base)))?,
+        };
+        let offset = match offset {
+            Some(offset) => offset.parse::<usize>().map_err(|_| {
+                line.error(
+                    token.column,
+                    format!("invalid frame offset in '{}'", token.text),
+                )
+            })?,
+            None => 0,
+        };
+        Ok(base + offset)
+    }
+
+    /// `N` or `N-M`, inclusive; returns the start and frame count.
+    fn range(&self, line: &Line, token: Token) -> Result<(usize, usize), ScriptError> {
+        let Some((start, end)) = token.text.split_once('-') else {
+            return Ok((self.frame(line, token)?, 1));
+        };
+        let start = self.frame(
+            line,
+            Token {
+                text: start,
+                ..token
+            },
+        )?;
+        let end_column = token.column + token.text.len() - end.len();
+        let end = self.frame(
+            line,
+            Token {
+                text: end,
+                column: end_column,
+            },
+        )?;
+        if end < start {
+            return Err(line.error(end_column, format!("range ends before frame {}", start)));
+        }
+        Ok((start, end - start + 1))
+    }
+
+    fn buttons(&self, line: &Line, token: Token) -> Result<ControllerState, ScriptError> {
+        ControllerState::parse(token.text)
+            .ok_or_else(|| line.error(token.column, format!("unknown button in '{}'", token.text)))
+    }
+
+    fn number(&self, line: &mut Line, what: &str) -> Result<usize, ScriptError> {
+        let token = line.next(what)?;
+        token.text.parse().ok().filter(|&n| n > 0).ok_or_else(|| {
+            line.error(
+                token.column,
+                format!("expected {}, found '{}'", what, token.text),
+            )
+        })
+    }
+
+    /// Optional `repeat <count> every <frames>` tail: (count, gap).
+    fn repeat(&self, line: &mut Line) -> Result<(usize, usize), ScriptError> {
+        if line.tokens.get(line.pos).is_none() {
+            return Ok((1, 0));
+        }
+        line.expect("repeat")?;
+        let count = self.number(line, "a repeat count")?;
+        line.expect("every")?;
+        let gap = self.number(line, "a frame interval")?;
+        Ok((count, gap))
+    }
+
+    /// `at <frame>`; after `then` it is optional and counts from the
+    /// frame the wait fired.
+    fn at(&self, line: &mut Line, relative: bool) -> Result<usize, ScriptError> {
+        if relative && matches!(line.peek(), None | Some("repeat")) {
+            return Ok(0);
+        }
+        line.expect("at")?;
+        let frame = line.next("a frame")?;
+        self.frame(line, frame)
+    }
+
+    /// A command that puts input on the timeline, with its repeats.
+    fn placement(
+        &self,
+        line: &mut Line,
+        command: Token,
+        relative: bool,
+    ) -> Result<Placement, ScriptError> {
+        let name = command.text.to_ascii_lowercase();
+        let (at, action) = match name.as_str() {
+            "hold" => {
+                let buttons = line.next("buttons")?;
+                let buttons = self.buttons(line, buttons)?;
+                line.expect("frames")?;
+                let range = line.next("a frame range")?;
+                let (start, len) = self.range(line, range)?;
+                (start, Action::Hold(buttons, len))
+            }
+            "press" => {
+                let buttons = line.next("buttons")?;
+                let buttons = self.buttons(line, buttons)?;
+                (self.at(line, relative)?, Action::Hold(buttons, 1))
+            }
+            "play" => {
+                let macro_name = line.next("a macro name")?;
+                let input_macro = self.macros.get(macro_name.text).cloned().ok_or_else(|| {
+                    line.error(
+                        macro_name.column,
+                        format!("unknown macro '{}'", macro_name.text),
+                    )
+                })?;
+                (self.at(line, relative)?, Action::Play(input_macro))
+            }
+            _ => match ConsoleEvent::from_name(&name) {
+                Some(event) => (self.at(line, relative)?, Action::Event(event)),
+                None => {
+                    return Err(line.error(
+                        command.column,
+                        format!("unknown command '{}'", command.text),
+                    ))
+                }
+            },
+        };
+        let (count, gap) = self.repeat(line)?;
+        Ok(Placement {
+            at,
+            action,
+            count,
+            gap,
+        })
+    }
+
+    /// The condition after `until`/`if`, up to `then` or the end of line.
+    fn condition(&self, line: &mut Line) -> Result<Condition, ScriptError> {
+        let first = line.next("a condition")?;
+        let mut text = first.text.to_string();
+        while let Some(word) = line.peek() {
+            if word.eq_ignore_ascii_case("then") {
+                break;
+            }
+            text.push(' ');
+            text.push_str(word);
+            line.pos += 1;
+        }
+        Condition::parse(&text).ok_or_else(|| {
+            line.error(
+                first.column,
+                format!(
+                    "invalid condition '{}', expected e.g. ram[$0770] == 3 or frame > 2000",
+                    text
+                ),
+            )
+        })
+    }
+
+    fn command<'a>(&mut self, source: &'a str, line: &mut Line<'a>) -> Result<(), ScriptError> {
+        let Some(&command) = line.tokens.first() else {
+            return Ok(());
+        };
+        line.pos = 1;
+        match command.text.to_ascii_lowercase().as_str() {
+            "label" => {
+                let label = line.next("a label name")?;
+                line.expect("=")?;
+                let frame = line.next("a frame")?;
+                let frame = self.frame(line, frame)?;
+                self.labels.insert(label.text.to_string(), frame);
+            }
+            "macro" => {
+                let macro_name = line.next("a macro name")?;
+                line.expect("=")?;
+                let spec = line.rest(source, "a macro")?;
+                let input_macro = InputMacro::parse(spec.text).ok_or_else(|| {
+                    line.error(spec.column, format!("invalid macro '{}'", spec.text))
+                })?;
+                self.macros.insert(macro_name.text.to_string(), input_macro);
+            }
+            "wait" => {
+                line.expect("until")?;
+                let condition = self.condition(line)?;
+                let then = match line.peek() {
+                    Some(_) => {
+                        line.expect("then")?;
+                        let command = line.next("a command")?;
+                        Some(self.placement(line, command, true)?)
+                    }
+                    None => None,
+                };
+                self.waits.push(Wait {
+                    line: line.number,
+                    condition,
+                    then,
+                });
+            }
+            "fail" => {
+                line.expect("if")?;
+                let condition = self.condition(line)?;
+                self.failures.push(Failure {
+                    line: line.number,
+                    condition,
+                });
+            }
+            _ => {
+                let placement = self.placement(line, command, false)?;
+                self.timeline.place(0, &placement);
+            }
+        }
+        line.finish()
+    }
+}
+
+/// A compiled script: fixed input plus the waits and failure checks that
+/// need a running machine.
+#[derive(Debug, Clone)]
+pub struct InputScript {
+    timeline: InputMacro,
+    waits: Vec<Wait>,
+    failures: Vec<Failure>,
+}
+
+impl InputScript {
+    /// Input that does not depend on conditions, as a macro from frame 0.
+    pub fn timeline(&self) -> &InputMacro {
+        &self.timeline
+    }
+
+    /// Whether the script uses `wait until` or `fail if`.
+    pub fn has_conditions(&self) -> bool {
+        !self.waits.is_empty() || !self.failures.is_empty()
+    }
+
+    pub fn runner(&self) -> ScriptRunner<'_> {
+        ScriptRunner {
+            script: self,
+            next_wait: 0,
+            triggered: Timeline::default(),
+        }
+    }
+}
+
+/// A `fail if` condition that held, or a wait still pending at the end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptFailure {
+    pub line: usize,
+    pub frame: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ScriptFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "frame {}: line {}: {}",
+            self.frame, self.line, self.message
+        )
+    }
+}
+
+impl std::error::Error for ScriptFailure {}
+
+/// Plays an `InputScript` against a running `Nes`, one frame at a time.
+pub struct ScriptRunner<'a> {
+    script: &'a InputScript,
+    next_wait: usize,
+    /// Input scheduled by waits that have fired.
+    triggered: Timeline,
+}
+
+impl ScriptRunner<'_> {
+    /// Controller state and console events for `frame`, given the machine
+    /// as the previous frame left it. Call before running each frame.
+    pub fn next_frame(
+        &mut self,
+        frame: usize,
+        nes: &Nes,
+    ) -> Result<(ControllerState, Vec<ConsoleEvent>), ScriptFailure> {
+        if let Some(failure) = self
+            .script
+            .failures
+            .iter()
+            .find(|failure| failure.condition.holds(frame, nes))
+        {
+            return Err(ScriptFailure {
+                line: failure.line,
+                frame,
+                message: format!("fail if {}", failure.condition),
+            });
+        }
+        while let Some(wait) = self.script.waits.get(self.next_wait) {
+            if !wait.condition.holds(frame, nes) {
+                break;
+            }
+            if let Some(then) = &wait.then {
+                self.triggered.place(frame, then);
+            }
+            self.next_wait += 1;
+        }
+
+        let timeline = &self.script.timeline;
+        let buttons = timeline.frames().get(frame).copied().unwrap_or_default()
+            | self
+                .triggered
+                .frames
+                .get(frame)
+                .copied()
+                .unwrap_or_default();
+        let mut events: Vec<ConsoleEvent> = timeline.events_at(frame).collect();
+        events.extend(
+            self.triggered
+                .events
+                .iter()
+                .filter(|&&(at, _)| at == frame)
+                .map(|&(_, event)| event),
+        );
+        Ok((buttons, events))
+    }
+
+    /// The first wait that has not fired yet, as a failure at `frame`; a
+    /// run that ends with one pending did not reach its checkpoints.
+    pub fn pending_wait(&self, frame: usize) -> Option<ScriptFailure> {
+        self.script
+            .waits
+            .get(self.next_wait)
+            .map(|wait| ScriptFailure {
+                line: wait.line,
+                frame,
+                message: format!("still waiting until {}", wait.condition),
+            })
+    }
+}
+
+/// Compile an input script; its fixed input plays from frame 0.
+pub fn parse_script(source: &str) -> Result<InputScript, ScriptError> {
+    let mut compiler = Compiler::default();
+    for (i, text) in source.lines().enumerate() {
+        let mut line = Line::new(i + 1, text);
+        compiler.command(text, &mut line)?;
+    }
+    let Timeline { frames, events } = compiler.timeline;
+    Ok(InputScript {
+        timeline: InputMacro::with_events(frames, events),
+        waits: compiler.waits,
+        failures: compiler.failures,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compiles_ranges_labels_macros_and_repeats() {
+        let script = parse_script(
+            "# title screen\n\
+             label level = 10\n\
+             macro jump = A*2,-\n\
+             hold START frames 2-3   # press start\n\
+             hold RIGHT frames level-level+5\n\
+             play jump at level+1 repeat 2 every 3\n\
+             press b at 20\n\
+             reset at 21\n",
+        )
+        .unwrap();
+        let frames: Vec<String> = script
+            .timeline()
+            .frames()
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(frames[2..4], ["START", "START"]);
+        assert_eq!(
+            frames[10..16],
+            ["RIGHT", "A+RIGHT", "A+RIGHT", "RIGHT", "A+RIGHT", "A+RIGHT"]
+        );
+        assert_eq!(frames[16..], ["-", "-", "-", "-", "B"]);
+        assert_eq!(script.timeline().events(), [(21, ConsoleEvent::Reset)]);
+    }
+
+    #[test]
+    fn waits_fire_in_order_and_failures_stop_the_run() {
+        let script = parse_script(
+            "press A at 1\n\
+             wait until ram[$0010]==3 then press START repeat 2 every 2\n\
+             wait until frame >= 10 then reset\n\
+             fail if RAM[0x11] == $FF\n",
+        )
+        .unwrap();
+        assert!(script.has_conditions());
+        let mut nes = Nes::new();
+        let mut runner = script.runner();
+        let mut log = Vec::new();
+        for frame in 0..12 {
+            if frame == 4 {
+                nes.ram_mut()[0x10] = 3;
+            }
+            let (buttons, events) = runner.next_frame(frame, &nes).unwrap();
+            log.push(format!("{}{:?}", buttons, events));
+        }
+        assert_eq!(log[1], "A[]");
+        assert_eq!(log[4..7], ["START[]", "-[]", "START[]"]);
+        assert_eq!(log[10], "-[Reset]");
+        assert!(runner.pending_wait(12).is_none());
+
+        nes.ram_mut()[0x11] = 0xFF;
+        let failure = runner.next_frame(12, &nes).unwrap_err();
+        assert_eq!(
+            failure.to_string(),
+            "frame 12: line 4: fail if RAM[0x11] == $FF"
+        );
+
+        let waiting = parse_script("wait until ram[$0770] == 3").unwrap();
+        assert_eq!(
+            waiting.runner().pending_wait(5).unwrap().to_string(),
+            "frame 5: line 1: still waiting until ram[$0770] == 3"
+        );
+    }
+
+    #[test]
+    fn reports_line_and_column_of_errors() {
+        let error = |source: &str| parse_script(source).unwrap_err().to_string();
+        assert_eq!(
+            error("hold A frames 5-2"),
+            "1:17: range ends before frame 5"
+        );
+        assert_eq!(
+            error("\n  press JUMP at 3"),
+            "2:9: unknown button in 'JUMP'"
+        );
+        assert_eq!(error("hold A at 3"), "1:8: expected 'frames', found 'at'");
+        assert_eq!(error("play dash at 3"), "1:6: unknown macro 'dash'");
+        assert_eq!(error("press A at boss"), "1:12: unknown label 'boss'");
+        assert_eq!(error("press A"), "1:8: expected 'at'");
+        assert_eq!(
+            error("press A at 3 twice"),
+            "1:14: expected 'repeat', found 'twice'"
+        );
+        assert_eq!(error("jump at 3"), "1:1: unknown command 'jump'");
+        assert!(error("wait until ram[$4016] == 1").starts_with("1:12: invalid condition"));
+        assert!(error("wait until frame > 9 press A")
+            .starts_with("1:12: invalid condition 'frame > 9 press A'"));
+    }
+}
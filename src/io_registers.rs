@@ -0,0 +1,317 @@
+use crate::apu::LENGTH_TABLE;
+use crate::hud_toast::draw_hud_label_rgb24;
+
+const LINE_HEIGHT: usize = 9;
+const TITLE_COLOR: [u8; 3] = [0xF8, 0xB8, 0x00];
+const TEXT_COLOR: [u8; 3] = [0xFF, 0xFF, 0xFF];
+
+/// Memory-mapped I/O register values for the register reference overlay.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IoRegisters {
+    pub ppu_ctrl: u8,
+    pub ppu_mask: u8,
+    pub ppu_status: u8,
+    pub oam_addr: u8,
+    /// Current VRAM address (v).
+    pub vram_addr: u16,
+    /// Temporary VRAM address (t), which holds the $2005 scroll.
+    pub temp_addr: u16,
+    pub fine_x: u8,
+    /// $2005/$2006 write toggle (w).
+    pub write_toggle: bool,
+    pub read_buffer: u8,
+    /// Last values written to $4000-$4017; the APU registers are write-only.
+    pub apu: [u8; 0x18],
+}
+
+/// Which half of the register map the overlay shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IoPage {
+    Ppu,
+    Apu,
+}
+
+impl IoRegisters {
+    fn apu_reg(&self, addr: u16) -> u8 {
+        self.apu[(addr - 0x4000) as usize]
+    }
+
+    /// $2000-$2007 decoded, one or two lines per register.
+    pub fn ppu_lines(&self) -> Vec<String> {
+        let ctrl = self.ppu_ctrl;
+        let mask = self.ppu_mask;
+        let status = self.ppu_status;
+
+        let sprite_table = if ctrl & 0x20 != 0 {
+            "SPR PER TILE".to_string()
+        } else {
+            format!("SPR ${:04X}", (ctrl as u16 & 0x08) << 9)
+        };
+        let left = match mask & 0x06 {
+            0x06 => "LEFT BG+SPR",
+            0x02 => "LEFT BG",
+            0x04 => "LEFT SPR",
+            _ => "LEFT CLIP",
+        };
+        let emphasis: String = [(0x20, 'R'), (0x40, 'G'), (0x80, 'B')]
+            .iter()
+            .filter(|&&(bit, _)| mask & bit != 0)
+            .map(|&(_, name)| name)
+            .collect();
+        let flags: Vec<&str> = [(0x80, "VBLANK"), (0x40, "SPR0 HIT"), (0x20, "OVERFLOW")]
+            .iter()
+            .filter(|&&(bit, _)| status & bit != 0)
+            .map(|&(_, name)| name)
+            .collect();
+
+        // t: 0yyy NNYY YYYX XXXX
+        let t = self.temp_addr;
+        let scroll_x = (t & 0x1F) * 8 + self.fine_x as u16 + if t & 0x0400 != 0 { 256 } else { 0 };
+        let scroll_y =
+            ((t >> 5) & 0x1F) * 8 + ((t >> 12) & 0x07) + if t & 0x0800 != 0 { 240 } else { 0 };
+
+        vec![
+            format!(
+                "$2000 ${:02X} NMI {}, SPR {}, INC {}",
+                ctrl,
+                on_off(ctrl & 0x80 != 0),
+                if ctrl & 0x20 != 0 { "8X16" } else { "8X8" },
+                if ctrl & 0x04 != 0 { 32 } else { 1 }
+            ),
+            format!(
+                "      NT ${:04X}, BG ${:04X}, {}",
+                0x2000 + (ctrl as u16 & 0x03) * 0x400,
+                (ctrl as u16 & 0x10) << 8,
+                sprite_table
+            ),
+            format!(
+                "$2001 ${:02X} BG {}, SPR {}, {}",
+                mask,
+                on_off(mask & 0x08 != 0),
+                on_off(mask & 0x10 != 0),
+                left
+            ),
+            format!(
+                "      GRAY {}, EMPH {}",
+                on_off(mask & 0x01 != 0),
+                if emphasis.is_empty() {
+                    "NONE"
+                } else {
+                    &emphasis
+                }
+            ),
+            format!(
+                "$2002 ${:02X} {}",
+                status,
+                if flags.is_empty() {
+                    "CLEAR".to_string()
+                } else {
+                    flags.join(", ")
+                }
+            ),
+            format!("$2003 ${:02X} OAM ADDR", self.oam_addr),
+            format!("$2005 SCROLL X={} Y={}", scroll_x, scroll_y),
+            format!(
+                "$2006 V=${:04X} T=${:04X} W={}",
+                self.vram_addr & 0x3FFF,
+                t & 0x7FFF,
+                self.write_toggle as u8
+            ),
+            format!("$2007 READ BUFFER ${:02X}", self.read_buffer),
+        ]
+    }
+
+    /// $4000-$4017 decoded from the last written values.
+    pub fn apu_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (name, base) in [("P1", 0x4000u16), ("P2", 0x4004)] {
+            let control = self.apu_reg(base);
+            let sweep = self.apu_reg(base + 1);
+            let high = self.apu_reg(base + 3);
+            lines.push(format!(
+                "${:04X} {} DUTY {}%, {}",
+                base,
+                name,
+                [12, 25, 50, 75][(control >> 6) as usize],
+                volume(control)
+            ));
+            lines.push(if sweep & 0x80 != 0 {
+                format!(
+                    "${:04X} {} SWEEP P{} S{}{}",
+                    base + 1,
+                    name,
+                    (sweep >> 4) & 0x07,
+                    sweep & 0x07,
+                    if sweep & 0x08 != 0 { " NEG" } else { "" }
+                )
+            } else {
+                format!("${:04X} {} SWEEP OFF", base + 1, name)
+            });
+            lines.push(format!(
+                "${:04X} {} PERIOD ${:03X}, LEN {}",
+                base + 2,
+                name,
+                timer_period(self.apu_reg(base + 2), high),
+                LENGTH_TABLE[(high >> 3) as usize]
+            ));
+        }
+
+        let linear = self.apu_reg(0x4008);
+        let high = self.apu_reg(0x400B);
+        lines.push(format!(
+            "$4008 TRI LINEAR {}{}",
+            linear & 0x7F,
+            if linear & 0x80 != 0 { ", HALT" } else { "" }
+        ));
+        lines.push(format!(
+            "$400A TRI PERIOD ${:03X}, LEN {}",
+            timer_period(self.apu_reg(0x400A), high),
+            LENGTH_TABLE[(high >> 3) as usize]
+        ));
+
+        let period = self.apu_reg(0x400E);
+        lines.push(format!("$400C NOISE {}", volume(self.apu_reg(0x400C))));
+        lines.push(format!(
+            "$400E NOISE PERIOD {}, MODE {}, LEN {}",
+            period & 0x0F,
+            period >> 7,
+            LENGTH_TABLE[(self.apu_reg(0x400F) >> 3) as usize]
+        ));
+
+        let dmc = self.apu_reg(0x4010);
+        let mut dmc_flags = String::new();
+        if dmc & 0x80 != 0 {
+            dmc_flags.push_str(", IRQ");
+        }
+        if dmc & 0x40 != 0 {
+            dmc_flags.push_str(", LOOP");
+        }
+        lines.push(format!("$4010 DMC RATE {}{}", dmc & 0x0F, dmc_flags));
+        lines.push(format!(
+            "$4011 DMC LOAD ${:02X}",
+            self.apu_reg(0x4011) & 0x7F
+        ));
+        lines.push(format!(
+            "$4012 DMC ADDR ${:04X}, LEN {}",
+            0xC000 + self.apu_reg(0x4012) as u16 * 64,
+            self.apu_reg(0x4013) as u16 * 16 + 1
+        ));
+
+        lines.push(format!("$4014 OAM DMA ${:02X}00", self.apu_reg(0x4014)));
+        let status = self.apu_reg(0x4015);
+        let enabled: Vec<&str> = ["P1", "P2", "TRI", "NOISE", "DMC"]
+            .iter()
+            .enumerate()
+            .filter(|&(bit, _)| status & (1 << bit) != 0)
+            .map(|(_, &name)| name)
+            .collect();
+        lines.push(format!(
+            "$4015 ON {}",
+            if enabled.is_empty() {
+                "NONE".to_string()
+            } else {
+                enabled.join(" ")
+            }
+        ));
+        lines.push(format!("$4016 STROBE {}", self.apu_reg(0x4016) & 0x01));
+        let frame = self.apu_reg(0x4017);
+        lines.push(format!(
+            "$4017 {}-STEP, IRQ {}",
+            if frame & 0x80 != 0 { 5 } else { 4 },
+            on_off(frame & 0x40 == 0)
+        ));
+        lines
+    }
+}
+
+fn on_off(on: bool) -> &'static str {
+    if on {
+        "ON"
+    } else {
+        "OFF"
+    }
+}
+
+// Constant volume or envelope period, plus the length counter halt flag
+fn volume(control: u8) -> String {
+    format!(
+        "{} {}{}",
+        if control & 0x10 != 0 { "VOL" } else { "ENV" },
+        control & 0x0F,
+        if control & 0x20 != 0 { ", HALT" } else { "" }
+    )
+}
+
+fn timer_period(low: u8, high: u8) -> u16 {
+    ((high as u16 & 0x07) << 8) | low as u16
+}
+
+/// Dim the game frame and list one page of decoded registers over it.
+pub fn draw_io_registers_rgb24(
+    frame: &mut [u8],
+    width: usize,
+    height: usize,
+    registers: &IoRegisters,
+    page: IoPage,
+) {
+    if width < 256 || height < 240 || frame.len() < width * height * 3 {
+        return;
+    }
+    for byte in frame[..width * height * 3].iter_mut() {
+        *byte /= 3;
+    }
+
+    let (title, lines) = match page {
+        IoPage::Ppu => ("PPU REGISTERS", registers.ppu_lines()),
+        IoPage::Apu => ("APU REGISTERS, LAST WRITTEN", registers.apu_lines()),
+    };
+    draw_hud_label_rgb24(frame, width, height, 4, 4, title, TITLE_COLOR);
+    for (i, line) in lines.iter().enumerate() {
+        let y = 4 + (i + 2) * LINE_HEIGHT;
+        if y + LINE_HEIGHT > height {
+            break;
+        }
+        draw_hud_label_rgb24(frame, width, height, 4, y, line, TEXT_COLOR);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_ppu_registers() {
+        let registers = IoRegisters {
+            ppu_ctrl: 0xB5,
+            ppu_mask: 0x3E,
+            ppu_status: 0xC0,
+            temp_addr: 0x1805,
+            fine_x: 3,
+            ..Default::default()
+        };
+        let lines = registers.ppu_lines();
+        assert_eq!(lines[0], "$2000 $B5 NMI ON, SPR 8X16, INC 32");
+        assert_eq!(lines[1], "      NT $2400, BG $1000, SPR PER TILE");
+        assert_eq!(lines[2], "$2001 $3E BG ON, SPR ON, LEFT BG+SPR");
+        assert_eq!(lines[3], "      GRAY OFF, EMPH R");
+        assert_eq!(lines[4], "$2002 $C0 VBLANK, SPR0 HIT");
+        assert_eq!(lines[6], "$2005 SCROLL X=43 Y=241");
+    }
+
+    #[test]
+    fn decodes_apu_registers() {
+        let mut registers = IoRegisters::default();
+        registers.apu[0x00] = 0xBF; // 50% duty, constant volume 15, halt
+        registers.apu[0x01] = 0xAA;
+        registers.apu[0x02] = 0xAB;
+        registers.apu[0x03] = 0x09; // length index 1, period high 1
+        registers.apu[0x15] = 0x13;
+        registers.apu[0x17] = 0xC0;
+        let lines = registers.apu_lines();
+        assert_eq!(lines[0], "$4000 P1 DUTY 50%, VOL 15, HALT");
+        assert_eq!(lines[1], "$4001 P1 SWEEP P2 S2 NEG");
+        assert_eq!(lines[2], "$4002 P1 PERIOD $1AB, LEN 254");
+        assert!(lines.contains(&"$4015 ON P1 P2 DMC".to_string()));
+        assert_eq!(lines.last().unwrap(), "$4017 5-STEP, IRQ OFF");
+    }
+}
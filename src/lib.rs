@@ -1,55 +1,325 @@
+pub mod accuracy;
 pub mod apu;
+pub mod apu_log;
 pub mod audio_ring;
+pub mod audio_scope;
+pub mod blank_screen;
+pub mod boot_screen;
 pub mod bus;
 pub mod cartridge;
 pub mod cheat;
+pub mod chr_viewer;
+pub mod clock;
+pub mod color_filter;
+pub mod compat;
 pub mod cpu;
+pub mod crc32;
+pub mod debugger;
+#[cfg(feature = "discord")]
+pub mod discord;
+pub mod fast_forward;
+pub mod frame_blend;
+pub mod frame_diff;
+pub mod frame_graph;
+pub mod frame_pacer;
+pub mod frame_stats;
+pub mod frontend;
+pub mod header_title;
 pub mod hud_toast;
+pub mod input;
+pub mod input_script;
+pub mod io_registers;
 pub mod memory;
+pub mod memory_dump;
+pub mod nametable_text;
+pub mod nametable_view;
+pub mod output_scale;
+pub mod patch_list;
+pub mod png;
+pub mod power_on;
 pub mod ppu;
+pub mod practice;
+pub mod ram_assert;
+pub mod region;
+pub mod rng;
+pub mod rom_browser;
+pub mod rom_dat;
+pub mod rom_history;
+pub mod rom_search;
+pub mod rom_watch;
 pub mod save_state;
+pub mod scroll_view;
+pub mod selftest;
+pub mod settings_menu;
+pub mod speedrun;
 pub mod sram;
+pub mod state_diff;
+pub mod state_picker;
+pub mod telemetry;
+#[cfg(test)]
+mod test_support;
+pub mod window_title;
 
-pub use bus::Bus;
-pub use cartridge::Cartridge;
+pub use apu_log::ApuWriteLog;
+pub use bus::{Bus, IrqSources};
+pub use cartridge::{BoardLeds, Cartridge};
+pub use clock::ConsoleClock;
 pub use cpu::Cpu;
 pub use cpu::StatusFlags;
+pub use frame_stats::FrameStats;
+pub use input::{ConsoleEvent, ControllerFeedback, ControllerState};
+pub use nametable_text::{CharTable, ScreenText};
+pub use power_on::PowerOnState;
+pub use ppu::PixelProvenance;
+pub use ppu::VsPpu;
+pub use region::Region;
+pub use rng::EmuRng;
+
+use debugger::{InterruptEntry, InterruptKind};
+use patch_list::{Patch, PatchList, PatchStatus};
+use sram::{SramConflict, SramFlush, SramPolicy};
+use telemetry::{IncidentKind, Telemetry};
 
 pub const CPU_CYCLES_PER_FRAME: u32 = 29830;
 
+/// Under `SramFlush::WriteThrough`, frames between the first unsaved SRAM
+/// change and the write: one second at PAL's 50 fps, a bit less on NTSC.
+pub const SRAM_WRITE_THROUGH_FRAMES: u32 = 50;
+
+/// The console.
+///
+/// Threading: `Nes` is `Send`, so a host can build it on one thread and run
+/// it on another (an emulation thread handing frames and input over
+/// channels, a control server, a threaded video callback). It is not
+/// `Sync`: everything that advances it takes `&mut self`, and mappers keep
+/// read-side latches in `Cell`s, so share one between threads behind a
+/// `Mutex`. The core holds no `Rc`, `static mut` or thread-local state;
+/// `--threaded-ppu` owns its worker thread and joins it on drop.
 pub struct Nes {
     cpu: Cpu,
     bus: Bus,
     current_rom_path: Option<String>,
+    rom_image: Option<Vec<u8>>, // ROM loaded from memory; it has no .sav
+    current_frame_stats: FrameStats, // Frame in progress
+    last_frame_stats: FrameStats, // Last completed frame
+    apu_samples_mark: u64,      // APU sample count at the start of the frame
+    nmi_pending: bool,          // NMI edge seen after the last interrupt poll
+    charset: Option<CharTable>, // Tile map for read_screen_text
+    rng: EmuRng,                // Every random choice the core makes
+    sram_policy: SramPolicy,    // Battery SRAM handling on state loads
+    sram_flush: SramFlush,      // When SRAM is written to the .sav
+    sram_on_disk: Option<Vec<u8>>, // SRAM as last loaded from or written to the .sav
+    sram_dirty_frames: Option<u32>, // Frames since SRAM first differed from it
+    telemetry: Telemetry,       // JAMs and unsupported mappers this session
 }
 
+// Keep the threading model above from regressing: these stop compiling if
+// a non-`Send` field (an `Rc`, a raw pointer) sneaks into the core.
+const _: () = {
+    const fn assert_send<T: Send>() {}
+    assert_send::<Nes>();
+    assert_send::<Cartridge>();
+    assert_send::<save_state::SaveState>();
+    assert_send::<frontend::PlayerApp>();
+};
+
 impl Nes {
     pub fn new() -> Self {
+        let mut bus = Bus::new();
+        bus.apply_power_on_state(PowerOnState::default());
         Nes {
             cpu: Cpu::new(),
-            bus: Bus::new(),
+            bus,
             current_rom_path: None,
+            rom_image: None,
+            current_frame_stats: FrameStats::default(),
+            last_frame_stats: FrameStats::default(),
+            apu_samples_mark: 0,
+            nmi_pending: false,
+            charset: None,
+            rng: EmuRng::new(0),
+            sram_policy: SramPolicy::default(),
+            sram_flush: SramFlush::default(),
+            sram_on_disk: None,
+            sram_dirty_frames: None,
+            telemetry: Telemetry::new(),
         }
     }
 
+    /// Restart the emulator's RNG from `seed`.
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = EmuRng::new(seed);
+    }
+
+    /// Seed of the emulator's RNG, as recorded in save states.
+    pub fn seed(&self) -> u64 {
+        self.rng.seed()
+    }
+
+    /// Fill CPU RAM and VRAM with pseudo-random bytes to shake out reads of
+    /// uninitialized memory. Seeds the emulator's RNG with `seed` and fills
+    /// from it; call before `load_rom`. The same seed always produces the
+    /// same contents.
+    pub fn randomize_ram(&mut self, seed: u64) {
+        self.set_seed(seed);
+        let fill = self.rng.next_u64();
+        self.bus.randomize_ram(fill);
+    }
+
+    /// Re-fill uninitialized memory for a power cycle; call before
+    /// `load_rom` to pick something other than the default `Pattern`.
+    /// `Random(seed)` seeds the emulator's RNG like `randomize_ram`.
+    pub fn set_power_on_state(&mut self, state: PowerOnState) {
+        let state = match state {
+            PowerOnState::Random(seed) => {
+                self.set_seed(seed);
+                PowerOnState::Random(self.rng.next_u64())
+            }
+            other => other,
+        };
+        self.bus.apply_power_on_state(state);
+    }
+
+    /// Override the header's NTSC/PAL timing (`None` follows the header).
+    /// Can be called before or after `load_rom`.
+    pub fn set_forced_region(&mut self, region: Option<Region>) {
+        self.bus.set_forced_region(region);
+    }
+
+    /// Emulate or ignore bus conflicts on discrete-logic boards regardless
+    /// of what the header says (`None` follows the board). Can be called
+    /// before or after `load_rom`.
+    pub fn set_forced_bus_conflicts(&mut self, forced: Option<bool>) {
+        self.bus.set_forced_bus_conflicts(forced);
+    }
+
+    /// Timing currently emulated.
+    pub fn region(&self) -> Region {
+        self.bus.region()
+    }
+
+    /// Load a ROM and power on. With a game already running this hot-swaps:
+    /// its SRAM is flushed and the console restarts cold with the new
+    /// cartridge. If the new ROM fails to load the running game is untouched.
     pub fn load_rom(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let mut cartridge = Cartridge::load(path)?;
+        let cartridge = Cartridge::load(path)?;
+        self.insert_cartridge(cartridge, path, None)
+    }
+
+    /// `load_rom` for an image already in memory, such as a built-in ROM.
+    /// `name` stands in for the path; no `.sav` is read or written for it.
+    pub fn load_rom_bytes(
+        &mut self,
+        name: &str,
+        data: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let cartridge = Cartridge::from_bytes(data.to_vec())?;
+        self.insert_cartridge(cartridge, name, Some(data.to_vec()))
+    }
+
+    fn insert_cartridge(
+        &mut self,
+        mut cartridge: Cartridge,
+        path: &str,
+        image: Option<Vec<u8>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if self.current_rom_path.is_some() {
+            self.save_sram()?;
+            self.power_cycle();
+        }
 
         // Load SRAM data if exists
-        if cartridge.has_battery_save() {
+        if cartridge.has_battery_save() && image.is_none() {
             if let Ok(Some(sram_data)) = sram::load_sram(path) {
                 cartridge.set_sram_data(sram_data);
             }
         }
 
         self.bus.load_cartridge(cartridge);
+        self.sram_on_disk = self.bus.get_sram_data();
+        let clock = self.clock();
         self.cpu.reset(&mut self.bus);
+        self.record_interrupt(InterruptKind::Reset, self.cpu.pc, None, clock);
         self.current_rom_path = Some(path.to_string());
+        self.rom_image = image;
+        if let Some(mapper) = self
+            .mapper_number()
+            .filter(|&m| !Cartridge::is_supported_mapper(m))
+        {
+            let rom = self.rom_file_name();
+            self.telemetry
+                .record(&rom, IncidentKind::UnsupportedMapper(mapper), None);
+        }
         Ok(())
     }
 
+    // Cold console with no cartridge; frontend settings on the bus are kept
+    fn power_cycle(&mut self) {
+        self.bus.power_cycle();
+        self.cpu = Cpu::new();
+        self.current_rom_path = None;
+        self.rom_image = None;
+        self.current_frame_stats = FrameStats::default();
+        self.last_frame_stats = FrameStats::default();
+        self.apu_samples_mark = 0;
+        self.nmi_pending = false;
+        self.sram_on_disk = None;
+        self.sram_dirty_frames = None;
+    }
+
+    /// Press the console's reset button. RAM and VRAM keep their contents.
+    pub fn reset(&mut self) {
+        let from = self.cpu.pc;
+        let clock = self.clock();
+        self.cpu.soft_reset(&mut self.bus);
+        self.record_interrupt(InterruptKind::Reset, from, None, clock);
+        self.nmi_pending = false;
+    }
+
+    /// Turn the console off and on with the same ROM. Battery SRAM is
+    /// flushed and reloaded; RAM starts from the power-on fill again.
+    pub fn hard_reset(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        match (self.current_rom_path.clone(), self.rom_image.clone()) {
+            (Some(name), Some(image)) => self.load_rom_bytes(&name, &image),
+            (Some(path), None) => self.load_rom(&path),
+            (None, _) => {
+                self.power_cycle();
+                Ok(())
+            }
+        }
+    }
+
+    /// Press reset or power as a movie asks for it.
+    pub fn apply_console_event(
+        &mut self,
+        event: ConsoleEvent,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match event {
+            ConsoleEvent::Reset => {
+                self.reset();
+                Ok(())
+            }
+            ConsoleEvent::Power => self.hard_reset(),
+        }
+    }
+
+    /// Emulate the PPU ignoring writes for its first ~29658 CPU cycles after
+    /// power or reset (on by default). Call before `load_rom` to cover
+    /// power-on.
+    pub fn set_ppu_warmup(&mut self, enabled: bool) {
+        self.bus.set_ppu_warmup(enabled);
+    }
+
+    // Path the .sav sits next to; ROMs loaded from memory have none
+    fn sram_rom_path(&self) -> Option<&str> {
+        match self.rom_image {
+            Some(_) => None,
+            None => self.current_rom_path.as_deref(),
+        }
+    }
+
     pub fn save_sram(&self) -> Result<(), Box<dyn std::error::Error>> {
-        if let Some(ref rom_path) = self.current_rom_path {
+        if let Some(rom_path) = self.sram_rom_path() {
             if let Some(sram_data) = self.bus.get_sram_data() {
                 sram::save_sram(rom_path, &sram_data)?;
                 println!("SRAM saved successfully");
@@ -58,14 +328,56 @@ impl Nes {
         Ok(())
     }
 
+    /// When battery SRAM is written to the `.sav`; buffered by default.
+    pub fn set_sram_flush(&mut self, flush: SramFlush) {
+        self.sram_flush = flush;
+        self.sram_dirty_frames = None;
+    }
+
+    pub fn sram_flush(&self) -> SramFlush {
+        self.sram_flush
+    }
+
+    // Once SRAM differs from the .sav, write it SRAM_WRITE_THROUGH_FRAMES
+    // later; a burst of writes in between goes out as one
+    fn write_through_sram(&mut self) {
+        if self.rom_image.is_some() {
+            return;
+        }
+        let Some(rom_path) = self.current_rom_path.as_deref() else {
+            return;
+        };
+        let Some(current) = self.bus.get_sram_data() else {
+            return;
+        };
+        if self.sram_on_disk.as_deref() == Some(current.as_slice()) {
+            self.sram_dirty_frames = None;
+            return;
+        }
+        let frames = self.sram_dirty_frames.get_or_insert(0);
+        *frames += 1;
+        if *frames < SRAM_WRITE_THROUGH_FRAMES {
+            return;
+        }
+        self.sram_dirty_frames = None;
+        match sram::save_sram(rom_path, &current) {
+            Ok(()) => self.sram_on_disk = Some(current),
+            // Try again a second later
+            Err(e) => log::warn!("SRAM write-through failed: {}", e),
+        }
+    }
+
     fn run_single_cpu_cycle(&mut self) -> bool {
         let mut nmi_triggered = false;
 
-        for _ in 0..3 {
+        let dots = self.bus.ppu_dots_for_cpu_cycle();
+        for _ in 0..dots {
             if self.bus.step_ppu() {
                 nmi_triggered = true;
             }
         }
+        self.current_frame_stats.cpu_cycles += 1;
+        self.current_frame_stats.ppu_dots += dots as u64;
         self.bus.clock_mapper_irq_cycles(1);
         self.bus.step_apu();
 
@@ -87,6 +399,7 @@ impl Nes {
                 }
                 stall_cycles -= 1;
                 stall_cycles += self.bus.take_dmc_stall_cycles();
+                self.current_frame_stats.dma_stall_cycles += 1;
             }
         }
 
@@ -95,66 +408,309 @@ impl Nes {
 
     pub fn step(&mut self) -> bool {
         let cpu_cycles: u32;
+        let poll_cycle: u32;
 
         // If DMA is in progress, don't execute CPU instruction
         if self.bus.is_dma_in_progress() {
-            let dma_completed = self.bus.step_dma();
+            self.bus.step_dma();
             cpu_cycles = 1;
-            if dma_completed {
-                // DMA completed
-            }
+            poll_cycle = 1;
+            self.current_frame_stats.dma_stall_cycles += 1;
         } else {
             // Normal CPU execution
+            let pc = self.cpu.pc;
+            let halted = self.cpu.is_halted();
+            let cycles_before = self.cpu.total_cycles();
             let cycles = self.cpu.step(&mut self.bus);
 
             // Safety check for zero cycles
             if cycles == 0 {
                 return false;
             }
+            if !halted && self.cpu.is_halted() {
+                let rom = self.rom_file_name();
+                let kind = IncidentKind::halting_opcode(self.cpu.last_opcode());
+                self.telemetry.record(&rom, kind, Some(pc));
+            }
+            if !halted && self.cpu.nmi_can_hijack() {
+                self.current_frame_stats.brks += 1;
+                let signature = self.bus.peek_cpu(pc.wrapping_add(1));
+                let clock = self.bus.clock(cycles_before);
+                self.record_interrupt(InterruptKind::Brk, pc, Some(signature), clock);
+            }
 
             cpu_cycles = cycles as u32;
+            poll_cycle = (self.cpu.interrupt_poll_cycle() as u32).min(cpu_cycles);
         }
 
         // --- Run all components for CPU instruction cycles ---
-        let nmi_triggered = self.run_cpu_time(cpu_cycles);
+        // Interrupts are polled partway through the instruction: an NMI edge
+        // or IRQ that shows up after the poll waits for the next instruction.
+        let early_nmi = self.run_cpu_time(poll_cycle);
+        let take_nmi = self.nmi_pending || early_nmi;
+        let irq_sources = self.bus.irq_sources();
+        let take_irq = !irq_sources.is_empty() && !self.cpu.irq_inhibited_at_poll();
+        self.nmi_pending = self.run_cpu_time(cpu_cycles - poll_cycle);
 
-        // --- Handle NMI (7-cycle entry must advance all components) ---
-        if nmi_triggered {
-            let nmi_cycles = self.cpu.nmi(&mut self.bus) as u32;
-            self.run_cpu_time(nmi_cycles);
+        if take_nmi {
+            if self.cpu.nmi_can_hijack() {
+                // NMI during BRK: BRK's pushes stand, the NMI vector is taken
+                let from = self.cpu.pc;
+                let clock = self.clock();
+                self.cpu.hijack_to_nmi(&mut self.bus);
+                self.current_frame_stats.nmis += 1;
+                self.record_interrupt(InterruptKind::Nmi, from, None, clock);
+            } else {
+                self.service_nmi();
+            }
+        } else if take_irq {
+            // --- APU frame/DMC IRQ or mapper IRQ (MMC3 scanline, FME-7 cycle counter) ---
+            self.service_irq(irq_sources);
         }
 
-        // --- Handle APU Frame IRQ ---
-        // cpu.irq() is silently ignored if I flag is set (returns 0 cycles).
-        if self.bus.apu_irq_pending() {
-            let irq_cycles = self.cpu.irq(&mut self.bus) as u32;
-            if irq_cycles > 0 {
-                self.run_cpu_time(irq_cycles);
+        // Use PPU frame completion as the authoritative frame boundary
+        let frame_complete = self.bus.ppu_frame_complete();
+        if frame_complete {
+            self.finish_frame_stats();
+            self.bus.poll_board_leds();
+            self.bus.apply_freezes();
+            if self.sram_flush == SramFlush::WriteThrough {
+                self.write_through_sram();
             }
         }
+        frame_complete
+    }
 
-        // --- Handle mapper IRQ (MMC3 scanline counter, FME-7 cycle counter) ---
-        if self.bus.mapper_irq_pending() {
-            let irq_cycles = self.cpu.irq(&mut self.bus) as u32;
-            if irq_cycles > 0 {
-                self.run_cpu_time(irq_cycles);
-            }
+    // The 7-cycle entry must advance all components too
+    fn service_nmi(&mut self) {
+        let from = self.cpu.pc;
+        let clock = self.clock();
+        let nmi_cycles = self.cpu.nmi(&mut self.bus) as u32;
+        if nmi_cycles == 0 {
+            return;
+        }
+        self.current_frame_stats.nmis += 1;
+        self.record_interrupt(InterruptKind::Nmi, from, None, clock);
+        if self.run_cpu_time(nmi_cycles) {
+            self.nmi_pending = true;
         }
+    }
 
-        // Use PPU frame completion as the authoritative frame boundary
-        self.bus.ppu_frame_complete()
+    fn service_irq(&mut self, sources: IrqSources) {
+        let from = self.cpu.pc;
+        let clock = self.clock();
+        let irq_cycles = self.cpu.irq(&mut self.bus) as u32;
+        if irq_cycles == 0 {
+            return;
+        }
+        self.current_frame_stats.irqs += 1;
+        self.bus.record_interrupt(InterruptEntry {
+            kind: InterruptKind::Irq,
+            from,
+            handler: self.cpu.pc,
+            signature: None,
+            irq_sources: sources,
+            clock,
+        });
+        let hijack_cycles = cpu::NMI_HIJACK_CYCLES as u32;
+        if self.nmi_pending || self.run_cpu_time(hijack_cycles) {
+            self.cpu.hijack_to_nmi(&mut self.bus);
+            self.current_frame_stats.nmis += 1;
+            self.record_interrupt(InterruptKind::Nmi, from, None, clock);
+            self.nmi_pending = false;
+        }
+        if self.run_cpu_time(irq_cycles - hijack_cycles) {
+            self.nmi_pending = true;
+        }
+    }
+
+    fn record_interrupt(
+        &mut self,
+        kind: InterruptKind,
+        from: u16,
+        signature: Option<u8>,
+        clock: ConsoleClock,
+    ) {
+        self.bus.record_interrupt(InterruptEntry {
+            kind,
+            from,
+            handler: self.cpu.pc,
+            signature,
+            irq_sources: IrqSources::empty(),
+            clock,
+        });
+    }
+
+    fn finish_frame_stats(&mut self) {
+        let samples = self.bus.apu_samples_produced();
+        self.current_frame_stats.apu_samples = (samples - self.apu_samples_mark) as u32;
+        self.current_frame_stats.frame = self.bus.ppu_frame();
+        self.apu_samples_mark = samples;
+        self.last_frame_stats = std::mem::take(&mut self.current_frame_stats);
+    }
+
+    /// Set once the CPU has executed a JAM/KIL opcode.
+    pub fn cpu_halted(&self) -> bool {
+        self.cpu.is_halted()
+    }
+
+    /// Counters for the last completed frame.
+    pub fn frame_stats(&self) -> FrameStats {
+        self.last_frame_stats
+    }
+
+    /// PPU frame, scanline and dot plus the CPU cycle count right now.
+    pub fn clock(&self) -> ConsoleClock {
+        self.bus.clock(self.cpu.total_cycles())
+    }
+
+    /// LEDs on the cartridge board, if it has any.
+    pub fn board_leds(&self) -> Option<BoardLeds> {
+        self.bus.board_leds()
+    }
+
+    /// Add a debugger ROM patch or RAM freeze; returns its index.
+    pub fn add_patch(&mut self, patch: Patch) -> usize {
+        self.bus.add_patch(patch)
+    }
+
+    pub fn remove_patch(&mut self, index: usize) -> Option<Patch> {
+        self.bus.remove_patch(index)
+    }
+
+    pub fn set_patch_enabled(&mut self, index: usize, enabled: bool) {
+        self.bus.set_patch_enabled(index, enabled);
+    }
+
+    pub fn patches(&self) -> &PatchList {
+        self.bus.patches()
+    }
+
+    /// Whether patch `index` applies right now: ROM patches with a compare
+    /// byte only hit while the matching bank is mapped in.
+    pub fn patch_status(&self, index: usize) -> PatchStatus {
+        self.bus.patch_status(index)
+    }
+
+    /// Rumble and LED changes for the controllers since the last call,
+    /// oldest first. Frontends without feedback devices can ignore these.
+    pub fn take_controller_feedback(&mut self) -> Vec<ControllerFeedback> {
+        self.bus.take_controller_feedback()
     }
 
     pub fn get_frame_buffer(&self) -> &[u8] {
         self.bus.get_ppu_buffer()
     }
 
+    /// The frame as 256x240 NES color indices ($00-$3F) before palette
+    /// lookup; pair with `get_emphasis_frame_buffer` for the $2001 tint.
+    pub fn get_indexed_frame_buffer(&self) -> &[u8] {
+        self.bus.get_ppu_indexed_buffer()
+    }
+
+    /// Per-dot emphasis bits (0-7) for `get_indexed_frame_buffer`.
+    pub fn get_emphasis_frame_buffer(&self) -> &[u8] {
+        self.bus.get_ppu_emphasis_buffer()
+    }
+
+    /// Both in one: 256x240 values `color | emphasis << 6` ($000-$1FF),
+    /// the native PPU output that NTSC filters and analysis tools expect.
+    pub fn get_raw_frame_buffer(&self) -> Vec<u16> {
+        self.bus.get_ppu_raw_buffer()
+    }
+
+    /// `get_frame_buffer` as RGBA8888 with opaque alpha, for APIs that
+    /// take four bytes per pixel.
+    pub fn get_rgba_frame_buffer(&self) -> Vec<u8> {
+        self.bus.get_ppu_rgba_buffer()
+    }
+
+    /// Toggle background rendering in the output frame (display only;
+    /// sprite 0 hit and other game-visible PPU state are unaffected).
+    pub fn set_background_visible(&mut self, visible: bool) {
+        self.bus.set_background_visible(visible);
+    }
+
+    /// Toggle sprite rendering in the output frame (display only).
+    pub fn set_sprites_visible(&mut self, visible: bool) {
+        self.bus.set_sprites_visible(visible);
+    }
+
+    /// Lift the 8-sprites-per-scanline limit to remove flicker. Games still
+    /// see the sprite overflow flag as on hardware.
+    pub fn set_sprite_limit(&mut self, enabled: bool) {
+        self.bus.set_sprite_limit(enabled);
+    }
+
+    pub fn sprite_limit(&self) -> bool {
+        self.bus.sprite_limit()
+    }
+
+    /// Emulate OAM decay during long forced blanking and the OAMADDR/$2004
+    /// quirks while rendering. Off by default: OAM never loses data.
+    pub fn set_oam_quirks(&mut self, enabled: bool) {
+        self.bus.set_oam_quirks(enabled);
+    }
+
+    pub fn oam_quirks(&self) -> bool {
+        self.bus.oam_quirks()
+    }
+
+    /// Convert finished scanlines to RGB on a worker thread while the
+    /// emulation thread continues. Emulation is unchanged; the frame buffer
+    /// is complete each time `step` reports a finished frame.
+    pub fn set_threaded_rendering(&mut self, enabled: bool) {
+        self.bus.set_threaded_rendering(enabled);
+    }
+
+    pub fn threaded_rendering(&self) -> bool {
+        self.bus.threaded_rendering()
+    }
+
+    pub fn background_visible(&self) -> bool {
+        self.bus.background_visible()
+    }
+
+    pub fn sprites_visible(&self) -> bool {
+        self.bus.sprites_visible()
+    }
+
+    /// Enable the pixel inspector: the PPU records which tile, palette entry
+    /// and sprite produced each pixel of the next frames.
+    pub fn set_pixel_inspector(&mut self, enabled: bool) {
+        self.bus.set_pixel_inspector(enabled);
+    }
+
+    pub fn pixel_inspector_enabled(&self) -> bool {
+        self.bus.pixel_inspector_enabled()
+    }
+
+    /// Provenance of an output pixel; `None` unless the inspector is enabled.
+    pub fn pixel_provenance(&self, x: usize, y: usize) -> Option<PixelProvenance> {
+        self.bus.pixel_provenance(x, y)
+    }
+
+    /// Enable per-channel level capture for the oscilloscope overlay.
+    pub fn set_audio_scope(&mut self, enabled: bool) {
+        self.bus.set_audio_scope(enabled);
+    }
+
+    pub fn audio_scope(&self) -> Option<&audio_scope::AudioScope> {
+        self.bus.audio_scope()
+    }
+
     /// Attach a ring buffer so the APU pushes samples directly as they
     /// are generated (no batching, no intermediate Vec).
     pub fn set_audio_ring(&mut self, ring: std::sync::Arc<audio_ring::SpscRingBuffer>) {
         self.bus.set_audio_ring(ring);
     }
 
+    /// Where output samples go (`AudioSink::Buffer` by default). The APU
+    /// runs in full either way, so timing does not depend on the sink.
+    pub fn set_audio_sink(&mut self, sink: apu::AudioSink) {
+        self.bus.set_audio_sink(sink);
+    }
+
     pub fn get_audio_buffer(&mut self) -> Vec<f32> {
         self.bus.get_audio_buffer()
     }
@@ -169,18 +725,254 @@ impl Nes {
         self.bus.drain_audio_to_ring(ring);
     }
 
-    pub fn set_controller(&mut self, controller: u8) {
-        self.bus.set_controller(controller);
+    /// Fade audio back in from silence; call when emulation resumes after
+    /// the frontend stopped running frames (pause, state picker, breaks).
+    pub fn ramp_audio_in(&mut self) {
+        self.bus.ramp_audio_in();
+    }
+
+    /// Tell the APU how many frames run per real frame, so it keeps the
+    /// audio queue at the real-time rate; 1 is normal speed.
+    pub fn set_fast_forward(&mut self, speed: usize) {
+        self.bus.set_fast_forward(speed);
+    }
+
+    pub fn set_fast_forward_audio(&mut self, mode: fast_forward::FastForwardAudio) {
+        self.bus.set_fast_forward_audio(mode);
+    }
+
+    pub fn set_controller(&mut self, controller: impl Into<ControllerState>) {
+        self.bus.set_controller(controller.into().bits());
+    }
+
+    /// Swipe an EAN-13/EAN-8 barcode through the Datach reader (mapper 157).
+    pub fn datach_swipe_barcode(&mut self, code: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.bus.datach_swipe_barcode(code).map_err(|e| e.into())
+    }
+
+    /// Hold or release the Famicom controller 2 microphone ($4016 bit 2).
+    pub fn set_microphone(&mut self, active: bool) {
+        self.bus.set_microphone(active);
+    }
+
+    /// Current PPU registers and last APU/IO writes, for the register overlay.
+    pub fn io_registers(&self) -> io_registers::IoRegisters {
+        self.bus.io_registers()
+    }
+
+    /// Write-protect CHR so $2007 writes to $0000-$1FFF are dropped.
+    pub fn set_chr_write_protect(&mut self, protect: bool) {
+        self.bus.set_chr_write_protect(protect);
+    }
+
+    pub fn chr_write_protect(&self) -> bool {
+        self.bus.chr_write_protect()
+    }
+
+    /// Log every CHR write with the PC and PRG bank that issued it.
+    pub fn set_chr_write_logging(&mut self, enabled: bool) {
+        self.bus.set_chr_write_logging(enabled);
+    }
+
+    pub fn chr_write_logging(&self) -> bool {
+        self.bus.chr_write_logging()
+    }
+
+    pub fn take_chr_write_log(&mut self) -> Vec<debugger::ChrWrite> {
+        self.bus.take_chr_write_log()
+    }
+
+    /// Changes whenever CHR is written; pattern viewers redraw on change.
+    pub fn chr_generation(&self) -> u64 {
+        self.bus.chr_generation()
+    }
+
+    /// Add a CPU or PPU ($2007) watchpoint; hits carry the PC and PRG bank
+    /// of the instruction that made the access.
+    pub fn add_watchpoint(&mut self, watchpoint: debugger::Watchpoint) -> usize {
+        self.bus.add_watchpoint(watchpoint)
+    }
+
+    pub fn clear_watchpoints(&mut self) {
+        self.bus.clear_watchpoints();
+    }
+
+    pub fn watchpoints(&self) -> &[debugger::Watchpoint] {
+        self.bus.watchpoints()
+    }
+
+    pub fn take_watch_hits(&mut self) -> Vec<debugger::WatchHit> {
+        self.bus.take_watch_hits()
+    }
+
+    /// True once after a breaking watchpoint fired or a `--break-on`
+    /// interrupt was entered; checked between steps.
+    pub fn take_watch_break(&mut self) -> bool {
+        self.bus.take_watch_break() | self.bus.take_interrupt_break()
+    }
+
+    /// Stop at entry to these handlers (see `take_watch_break`).
+    pub fn set_interrupt_breaks(&mut self, kinds: &[debugger::InterruptKind]) {
+        self.bus.set_interrupt_breaks(kinds);
+    }
+
+    pub fn interrupt_breaks(&self) -> &[debugger::InterruptKind] {
+        self.bus.interrupt_breaks()
+    }
+
+    /// Log every BRK with its signature byte to `take_interrupt_log`.
+    pub fn set_brk_logging(&mut self, enabled: bool) {
+        self.bus.set_brk_logging(enabled);
+    }
+
+    /// Logged BRKs and the entries that triggered interrupt breaks.
+    pub fn take_interrupt_log(&mut self) -> Vec<debugger::InterruptEntry> {
+        self.bus.take_interrupt_log()
+    }
+
+    /// Side-effect-free CHR read for pattern table viewers.
+    pub fn peek_chr(&self, addr: u16) -> u8 {
+        self.bus.peek_chr(addr)
+    }
+
+    /// Tile indices of nametable 0-3 as 30 rows of 32, for text dumps.
+    pub fn nametable_tiles(&self, logical_nt: usize) -> Vec<u8> {
+        self.bus.nametable_tiles(logical_nt)
+    }
+
+    /// Log every APU register write from now on, for music ripping.
+    pub fn start_apu_log(&mut self) {
+        self.bus.start_apu_log();
+    }
+
+    /// Stop logging and return the writes; save with `ApuWriteLog::save`.
+    pub fn take_apu_log(&mut self) -> Option<ApuWriteLog> {
+        self.bus.take_apu_log()
+    }
+
+    /// PPU palette RAM ($3F00-$3F1F).
+    pub fn ppu_palette(&self) -> [u8; 32] {
+        self.bus.get_ppu_palette()
+    }
+
+    /// The PPU's 2KB of nametable RAM, both pages in order.
+    pub fn ppu_nametables(&self) -> Vec<u8> {
+        self.bus.get_ppu_nametables_flat()
+    }
+
+    /// Primary OAM (64 sprites of 4 bytes).
+    pub fn ppu_oam(&self) -> Vec<u8> {
+        self.bus.get_ppu_oam_flat()
+    }
+
+    /// All four nametables as a 512x480 RGB24 image, for map exports.
+    /// With `show_viewport` the area on screen is outlined, which makes
+    /// scroll seams easy to spot.
+    pub fn nametable_view_rgb24(&self, show_viewport: bool) -> Vec<u8> {
+        let mut view = self.bus.nametable_view_rgb24();
+        if show_viewport {
+            let (x, y) = self.bus.scroll_origin();
+            nametable_view::draw_viewport_rgb24(&mut view, x, y);
+        }
+        view
+    }
+
+    /// Tiles currently on screen (scroll applied), 30 rows of 32.
+    pub fn screen_tiles(&self) -> Vec<u8> {
+        self.bus.screen_tiles()
+    }
+
+    /// Tile map used by `read_screen_text`, e.g. `CharTable::for_rom`.
+    pub fn set_charset(&mut self, charset: Option<CharTable>) {
+        self.charset = charset;
+    }
+
+    /// Text runs on screen decoded through the charset; empty without one.
+    pub fn read_screen_text(&self) -> Vec<ScreenText> {
+        self.charset
+            .as_ref()
+            .map(|charset| nametable_text::text_regions(&self.screen_tiles(), charset))
+            .unwrap_or_default()
+    }
+
+    /// PlayChoice-10 INST-ROM split off the loaded dump, if it had one.
+    pub fn playchoice_inst_rom(&self) -> Option<&[u8]> {
+        self.bus.playchoice_inst_rom()
+    }
+
+    pub fn is_vs_system(&self) -> bool {
+        self.bus.is_vs_system()
+    }
+
+    /// Override the Vs. PPU palette variant detected from the ROM header.
+    pub fn set_vs_ppu(&mut self, vs_ppu: VsPpu) {
+        self.bus.set_vs_ppu(vs_ppu);
+    }
+
+    pub fn vs_ppu(&self) -> VsPpu {
+        self.bus.vs_ppu()
+    }
+
+    /// Set the Vs. System DIP switches (bit 0 = switch 1).
+    pub fn set_vs_dip_switches(&mut self, switches: u8) {
+        self.bus.set_vs_dip_switches(switches);
+    }
+
+    pub fn vs_dip_switches(&self) -> u8 {
+        self.bus.vs_dip_switches()
+    }
+
+    /// Hold or release a Vs. System coin slot (0 or 1).
+    pub fn set_vs_coin(&mut self, slot: usize, inserted: bool) {
+        self.bus.set_vs_coin(slot, inserted);
+    }
+
+    /// Hold or release the Vs. System service button.
+    pub fn set_vs_service(&mut self, pressed: bool) {
+        self.bus.set_vs_service(pressed);
+    }
+
+    /// Path of the loaded ROM.
+    pub fn rom_path(&self) -> Option<&str> {
+        self.current_rom_path.as_deref()
+    }
+
+    /// CRC-32 of the loaded ROM's PRG+CHR data.
+    pub fn rom_crc32(&self) -> Option<u32> {
+        self.bus.rom_crc32()
+    }
+
+    /// iNES mapper number of the loaded cartridge.
+    pub fn mapper_number(&self) -> Option<u8> {
+        self.bus.mapper_number()
+    }
+
+    /// JAMs, unknown opcodes and unsupported mappers seen since the
+    /// emulator started, across every ROM loaded.
+    pub fn telemetry(&self) -> &Telemetry {
+        &self.telemetry
+    }
+
+    fn rom_file_name(&self) -> String {
+        self.current_rom_path
+            .as_deref()
+            .and_then(|p| std::path::Path::new(p).file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "unknown".to_string())
+    }
+
+    /// Devices asserting IRQ right now: APU frame counter, DMC, mapper.
+    pub fn irq_sources(&self) -> IrqSources {
+        self.bus.irq_sources()
     }
 
     /// Derive a filesystem-safe ROM stem from the loaded ROM path.
-    fn rom_stem(&self) -> String {
+    fn rom_stem(&self) -> &str {
         self.current_rom_path
             .as_deref()
             .and_then(|p| std::path::Path::new(p).file_stem())
             .and_then(|s| s.to_str())
             .unwrap_or("unknown")
-            .to_string()
     }
 
     pub fn save_state(
@@ -188,73 +980,197 @@ impl Nes {
         slot: u8,
         _rom_filename: &str,
     ) -> Result<(), Box<dyn std::error::Error>> {
+        self.save_state_to(&self.state_path(slot))
+    }
+
+    fn save_state_to(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let dir = std::path::Path::new("states");
+        if !dir.exists() {
+            std::fs::create_dir_all(dir)?;
+        }
+        self.capture_state().save_to_file(path)?;
+        Ok(())
+    }
+
+    /// The current machine state as it would be written to a save slot,
+    /// e.g. for comparing two runs with `state_diff::diff_states`.
+    pub fn capture_state(&self) -> save_state::SaveState {
+        let mut state = save_state::SaveState::default();
+        self.capture_state_into(&mut state);
+        state.thumbnail = save_state::make_thumbnail(self.get_frame_buffer());
+        state
+    }
+
+    /// Snapshot into an existing state, reusing its buffers so repeated
+    /// snapshots (rewind, run-ahead) stop allocating once warmed up. The
+    /// thumbnail is left empty.
+    pub fn capture_state_into(&self, state: &mut save_state::SaveState) {
         let (ppu_control, ppu_mask, ppu_status, ppu_oam_addr) = self.bus.get_ppu_state();
         let (ppu_v, ppu_t, ppu_x, ppu_w, ppu_scanline, ppu_cycle, ppu_frame, ppu_data_buffer) =
             self.bus.get_ppu_registers();
-
-        let rom_stem = self.rom_stem();
         let apu_state = self.bus.get_apu_state();
         let (bus_dma_cycles, bus_dma_in_progress, bus_dmc_stall_cycles, ppu_frame_complete) =
             self.bus.timing_state();
 
-        let save_state = save_state::SaveState {
-            cpu_a: self.cpu.a,
-            cpu_x: self.cpu.x,
-            cpu_y: self.cpu.y,
-            cpu_pc: self.cpu.pc,
-            cpu_sp: self.cpu.sp,
-            cpu_status: self.cpu.status.bits(),
-            cpu_cycles: self.cpu.total_cycles(),
-            cpu_halted: self.cpu.is_halted(),
-            ppu_control,
-            ppu_mask,
-            ppu_status,
-            ppu_oam_addr,
-            ppu_scroll_x: 0,
-            ppu_scroll_y: 0,
-            ppu_addr: ppu_v,
-            ppu_data_buffer,
-            ppu_w,
-            ppu_t,
-            ppu_v,
-            ppu_x,
-            ppu_scanline,
-            ppu_cycle,
-            ppu_frame,
-            ppu_palette: self.bus.get_ppu_palette(),
-            ppu_nametable: self.bus.get_ppu_nametables_flat(),
-            ppu_oam: self.bus.get_ppu_oam_flat(),
-            ram: self.bus.get_ram_flat(),
-            cartridge_prg_bank: self.bus.get_cartridge_prg_bank(),
-            cartridge_chr_bank: self.bus.get_cartridge_chr_bank(),
-            cartridge_state: self.bus.get_cartridge_state(),
-            apu_frame_counter: apu_state.frame_counter as u8,
-            apu_frame_interrupt: apu_state.frame_irq,
-            apu_state: Some(apu_state),
-            rom_filename: rom_stem.clone(),
-            timestamp: std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)?
-                .as_secs(),
-            bus_dma_cycles,
-            bus_dma_in_progress,
-            bus_dmc_stall_cycles,
-            ppu_frame_complete,
+        state.cpu_a = self.cpu.a;
+        state.cpu_x = self.cpu.x;
+        state.cpu_y = self.cpu.y;
+        state.cpu_pc = self.cpu.pc;
+        state.cpu_sp = self.cpu.sp;
+        state.cpu_status = self.cpu.status.bits();
+        state.cpu_cycles = self.cpu.total_cycles();
+        state.cpu_halted = self.cpu.is_halted();
+        state.ppu_control = ppu_control;
+        state.ppu_mask = ppu_mask;
+        state.ppu_status = ppu_status;
+        state.ppu_oam_addr = ppu_oam_addr;
+        state.ppu_scroll_x = 0;
+        state.ppu_scroll_y = 0;
+        state.ppu_addr = ppu_v;
+        state.ppu_data_buffer = ppu_data_buffer;
+        state.ppu_w = ppu_w;
+        state.ppu_t = ppu_t;
+        state.ppu_v = ppu_v;
+        state.ppu_x = ppu_x;
+        state.ppu_scanline = ppu_scanline;
+        state.ppu_cycle = ppu_cycle;
+        state.ppu_frame = ppu_frame;
+        state.ppu_palette = self.bus.get_ppu_palette();
+        self.bus.copy_ppu_nametables_flat(&mut state.ppu_nametable);
+        self.bus.copy_ppu_oam_flat(&mut state.ppu_oam);
+        self.bus.copy_ram_flat(&mut state.ram);
+        state.cartridge_prg_bank = self.bus.get_cartridge_prg_bank();
+        state.cartridge_chr_bank = self.bus.get_cartridge_chr_bank();
+        self.bus.copy_cartridge_state(&mut state.cartridge_state);
+        state.apu_frame_counter = apu_state.frame_counter as u8;
+        state.apu_frame_interrupt = apu_state.frame_irq;
+        state.apu_state = Some(apu_state);
+        state.rom_filename.clear();
+        state.rom_filename.push_str(self.rom_stem());
+        state.timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        state.bus_dma_cycles = bus_dma_cycles;
+        state.bus_dma_in_progress = bus_dma_in_progress;
+        state.bus_dmc_stall_cycles = bus_dmc_stall_cycles;
+        state.ppu_frame_complete = ppu_frame_complete;
+        state.thumbnail.clear();
+        state.play_time_secs = (self.bus.region().frame_duration().as_nanos() * ppu_frame as u128
+            / 1_000_000_000) as u64;
+        state.rng = Some(self.rng);
+    }
+
+    fn state_path(&self, slot: u8) -> String {
+        format!("states/{}.slot{}.sav", self.rom_stem(), slot)
+    }
+
+    /// Thumbnail, timestamp and play time of a save slot, for state pickers.
+    pub fn state_slot_info(&self, slot: u8) -> Option<save_state::SlotInfo> {
+        save_state::SaveState::read_slot_info(&self.state_path(slot), slot)
+    }
+
+    /// Load a save slot under the SRAM policy; with `SramPolicy::Ask` a
+    /// state whose battery SRAM differs fails with `SramConflict`.
+    pub fn load_state(&mut self, slot: u8) -> Result<(), Box<dyn std::error::Error>> {
+        self.load_state_from(&self.state_path(slot), self.sram_policy)
+    }
+
+    /// Load a save slot with an explicit SRAM choice, e.g. once the user
+    /// answered the `SramConflict` question.
+    pub fn load_state_with_sram(
+        &mut self,
+        slot: u8,
+        policy: SramPolicy,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.load_state_from(&self.state_path(slot), policy)
+    }
+
+    /// How state loads treat battery SRAM that differs from the state's.
+    pub fn set_sram_policy(&mut self, policy: SramPolicy) {
+        self.sram_policy = policy;
+    }
+
+    pub fn sram_policy(&self) -> SramPolicy {
+        self.sram_policy
+    }
+
+    // The exit autosave is keyed by the ROM's CRC-32 rather than its file
+    // name, so a renamed ROM still resumes and a different ROM never does.
+    fn auto_state_path(&self) -> Option<String> {
+        self.bus
+            .rom_crc32()
+            .map(|crc| format!("states/auto-{:08X}.sav", crc))
+    }
+
+    /// Save the resume-on-launch state; call on shutdown. No-op without a ROM.
+    pub fn save_auto_state(&self) -> Result<(), Box<dyn std::error::Error>> {
+        match self.auto_state_path() {
+            Some(path) => self.save_state_to(&path),
+            None => Ok(()),
+        }
+    }
+
+    /// Load the state saved by `save_auto_state` for this ROM, if any.
+    /// Returns whether a state was resumed. There is no one to ask at
+    /// launch, so `SramPolicy::Ask` keeps the save file's SRAM.
+    pub fn resume_auto_state(&mut self) -> Result<bool, Box<dyn std::error::Error>> {
+        let policy = match self.sram_policy {
+            SramPolicy::Ask => SramPolicy::Keep,
+            policy => policy,
         };
+        match self.auto_state_path() {
+            Some(path) if std::path::Path::new(&path).exists() => {
+                self.load_state_from(&path, policy)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
 
-        let dir = std::path::Path::new("states");
-        if !dir.exists() {
-            std::fs::create_dir_all(dir)?;
+    fn load_state_from(
+        &mut self,
+        path: &str,
+        policy: SramPolicy,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let save_state = save_state::SaveState::load_from_file(path)?;
+        self.restore_state_with_sram(&save_state, policy)
+    }
+
+    /// `restore_state` for states loaded from disk. States carry PRG-RAM, so
+    /// restoring one replaces battery SRAM that the next flush would write
+    /// over a possibly newer `.sav`; `policy` decides what happens when the
+    /// two differ.
+    pub fn restore_state_with_sram(
+        &mut self,
+        save_state: &save_state::SaveState,
+        policy: SramPolicy,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let current = self.bus.get_sram_data();
+        let conflict = match (&current, &save_state.cartridge_state) {
+            (Some(current), Some(state)) => *current != state.prg_ram,
+            _ => false,
+        };
+        if conflict {
+            match (policy, self.sram_rom_path(), &current) {
+                (SramPolicy::Ask, _, _) => return Err(Box::new(SramConflict)),
+                (SramPolicy::State, Some(rom_path), Some(current)) => {
+                    sram::save_sram_backup(rom_path, current)?;
+                }
+                _ => {}
+            }
+        }
+        self.restore_state(save_state)?;
+        if let (true, SramPolicy::Keep, Some(current)) = (conflict, policy, current) {
+            self.bus.set_sram_data(current);
         }
-        let filename = format!("states/{}.slot{}.sav", rom_stem, slot);
-        save_state.save_to_file(&filename)?;
         Ok(())
     }
 
-    pub fn load_state(&mut self, slot: u8) -> Result<(), Box<dyn std::error::Error>> {
-        let rom_stem = self.rom_stem();
-        let filename = format!("states/{}.slot{}.sav", rom_stem, slot);
-        let save_state = save_state::SaveState::load_from_file(&filename)?;
-
+    /// Restore a state captured with `capture_state` or `capture_state_into`.
+    pub fn restore_state(
+        &mut self,
+        save_state: &save_state::SaveState,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         self.cpu.a = save_state.cpu_a;
         self.cpu.x = save_state.cpu_x;
         self.cpu.y = save_state.cpu_y;
@@ -266,9 +1182,9 @@ impl Nes {
 
         self.bus.restore_state_flat(
             save_state.ppu_palette,
-            save_state.ppu_nametable,
-            save_state.ppu_oam,
-            save_state.ram,
+            &save_state.ppu_nametable,
+            &save_state.ppu_oam,
+            &save_state.ram,
             save_state.cartridge_prg_bank,
             save_state.cartridge_chr_bank,
             Some((
@@ -303,12 +1219,15 @@ impl Nes {
             save_state.bus_dmc_stall_cycles,
             save_state.ppu_frame_complete,
         );
+        if let Some(rng) = save_state.rng {
+            self.rng = rng;
+        }
 
         Ok(())
     }
 
-    pub fn get_controller(&self) -> u8 {
-        self.bus.controller
+    pub fn get_controller(&self) -> ControllerState {
+        ControllerState::from(self.bus.controller)
     }
 
     /// Direct reference to CPU RAM (2KB).
@@ -330,4 +1249,10 @@ impl Nes {
     pub fn prg_ram_mut(&mut self) -> Option<&mut [u8]> {
         self.bus.prg_ram_mut()
     }
+
+    /// Write `cheats` into RAM, the same way the patch list's freezes are
+    /// applied each frame.
+    pub fn apply_cheats(&mut self, cheats: &cheat::CheatManager) {
+        self.bus.apply_cheats(cheats);
+    }
 }
@@ -1,12 +1,61 @@
+use nes_emulator::accuracy::{AccuracyProfile, GameDb};
 use nes_emulator::audio_ring::SpscRingBuffer;
+use nes_emulator::audio_scope::draw_audio_scope_rgb24;
+use nes_emulator::blank_screen;
+use nes_emulator::boot_screen::{boot_screen_rom, builtin_rom_name, is_builtin_rom};
+use nes_emulator::chr_viewer::{
+    chr_palette, draw_pattern_tables_rgb24, export_chr_sheets, GRAY_PALETTE,
+};
+use nes_emulator::color_filter::{apply_color_filter_rgb24, ColorFilter};
+use nes_emulator::compat;
+use nes_emulator::debugger::{ChrWrite, InterruptKind, Watchpoint};
+#[cfg(feature = "discord")]
+use nes_emulator::discord::DiscordPresence;
+use nes_emulator::fast_forward::FastForwardAudio;
+use nes_emulator::frame_blend::{BlendMode, FrameBlend};
+use nes_emulator::frame_graph::{draw_frame_graph_rgb24, FrameGraph, FrameTiming};
+use nes_emulator::frame_pacer::{FramePacer, SyncMode};
+use nes_emulator::frame_stats::{draw_board_leds_rgb24, draw_frame_stats_rgb24};
+use nes_emulator::header_title::header_title;
 use nes_emulator::hud_toast::{draw_hud_toast_rgb24, show_hud_toast, HudToast};
-use nes_emulator::Nes;
+use nes_emulator::input::{ControllerState, InputMacro, InputState, DEFAULT_TURBO_RATE};
+use nes_emulator::input_script::parse_script;
+use nes_emulator::io_registers::{draw_io_registers_rgb24, IoPage};
+use nes_emulator::nametable_view::{VIEW_HEIGHT, VIEW_WIDTH};
+use nes_emulator::output_scale::{AspectRatio, ScaleFilter};
+use nes_emulator::patch_list::{draw_patch_editor_rgb24, Patch, PatchEditor};
+use nes_emulator::png::write_rgb24_png;
+use nes_emulator::power_on::clock_seed;
+use nes_emulator::practice::PracticeMode;
+use nes_emulator::rom_browser::{draw_rom_browser_rgb24, RomBrowser};
+use nes_emulator::rom_dat::{DumpStatus, RomDat};
+use nes_emulator::rom_history::{format_duration, unix_now, RomHistory, HISTORY_PATH};
+use nes_emulator::rom_search::{find_roms, resolve_rom, rom_dirs, RomLookup};
+use nes_emulator::rom_watch::RomWatcher;
+use nes_emulator::save_state::{SaveState, SlotInfo};
+use nes_emulator::scroll_view::draw_scroll_view_rgb24;
+use nes_emulator::selftest::{selftest_rom, LatencyProbe};
+use nes_emulator::settings_menu::{draw_settings_menu_rgb24, Settings, SettingsItem, SettingsMenu};
+use nes_emulator::speedrun::{
+    draw_speedrun_timer_rgb24, format_run_time, LiveSplitServer, SpeedrunTimer, TimerEvent,
+};
+use nes_emulator::sram::{SramConflict, SramFlush, SramPolicy};
+use nes_emulator::state_diff::diff_states;
+use nes_emulator::state_picker::{draw_state_picker_rgb24, format_play_time};
+use nes_emulator::window_title::{window_title, FpsCounter};
+use nes_emulator::{
+    Cartridge, ConsoleEvent, ControllerFeedback, Nes, PixelProvenance, PowerOnState, Region, VsPpu,
+};
 use sdl2::audio::AudioCallback;
+use sdl2::controller::GameController;
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
 use sdl2::pixels::PixelFormatEnum;
+use sdl2::rect::Rect;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
 fn state_slot_from_key(code: Keycode) -> Option<u8> {
     match code {
@@ -18,62 +67,507 @@ fn state_slot_from_key(code: Keycode) -> Option<u8> {
     }
 }
 
-fn show_rom_selection() -> Result<String, Box<dyn std::error::Error>> {
-    use std::fs;
-    use std::io::{self, Write};
-    use std::path::Path;
-
-    // Scan roms directory for .nes files
-    let roms_path = Path::new("roms");
-    let mut rom_files = Vec::new();
-
-    if roms_path.exists() && roms_path.is_dir() {
-        for entry in fs::read_dir(roms_path)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_file() {
-                if let Some(extension) = path.extension() {
-                    if extension == "nes" {
-                        if let Some(file_name) = path.file_name() {
-                            if let Some(name_str) = file_name.to_str() {
-                                rom_files.push((
-                                    name_str.to_string(),
-                                    path.to_string_lossy().to_string(),
-                                ));
-                            }
-                        }
-                    }
+fn read_state_slots(nes: &Nes) -> Vec<Option<SlotInfo>> {
+    (1..=4).map(|slot| nes.state_slot_info(slot)).collect()
+}
+
+fn describe_state_slot(slot: u8, info: Option<&SlotInfo>) -> String {
+    let Some(info) = info else {
+        return format!("Slot {}: empty", slot);
+    };
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    format!(
+        "Slot {}: saved {} min ago, play time {}",
+        slot,
+        now.saturating_sub(info.timestamp) / 60,
+        format_play_time(info.play_time_secs)
+    )
+}
+
+fn layer_toast(layer: &str, visible: bool) -> String {
+    format!("{layer} {}", if visible { "ON" } else { "OFF" })
+}
+
+fn dip_toast(switch: u8, on: bool) -> String {
+    format!("DIP {} {}", switch + 1, if on { "ON" } else { "OFF" })
+}
+
+/// C and V drop coins into Vs. System slots 1 and 2.
+fn coin_slot_for_key(key: Keycode) -> Option<usize> {
+    match key {
+        Keycode::C => Some(0),
+        Keycode::V => Some(1),
+        _ => None,
+    }
+}
+
+fn parse_watchpoints(flag: &str, specs: Option<&String>, breaks: bool) -> Vec<Watchpoint> {
+    specs
+        .into_iter()
+        .flat_map(|specs| specs.split(','))
+        .map(|spec| {
+            Watchpoint::parse(spec, breaks).unwrap_or_else(|| {
+                eprintln!(
+                    "Invalid {} '{}' (expected [cpu|ppu:]<r|w|rw>:<start>[-<end>])",
+                    flag, spec
+                );
+                std::process::exit(1);
+            })
+        })
+        .collect()
+}
+
+fn describe_chr_write(write: &ChrWrite) -> String {
+    format!(
+        "CHR write ${:04X} = ${:02X} from PC=${:04X} bank={}{}",
+        write.addr,
+        write.value,
+        write.source.pc,
+        write.source.prg_bank,
+        if write.blocked { " (blocked)" } else { "" }
+    )
+}
+
+fn describe_pixel(x: usize, y: usize, info: &PixelProvenance) -> String {
+    let nametable = match info.nametable_addr {
+        Some(addr) => format!("${:04X} fine=({},{})", addr, info.fine_x, info.fine_y),
+        None => "-".to_string(),
+    };
+    let sprite = match info.oam_index {
+        Some(index) => format!("OAM #{}", index),
+        None => "background".to_string(),
+    };
+    format!(
+        "pixel ({}, {}): {} nametable={} tile=${:02X} pattern=${:04X} palette=${:02X} (line {})",
+        x,
+        y,
+        sprite,
+        nametable,
+        info.tile_index,
+        info.pattern_table,
+        info.palette_index,
+        info.palette_index / 4
+    )
+}
+
+fn pixel_toast(info: &PixelProvenance) -> String {
+    let tile = format!(
+        "TILE {:02X} PAL {:02X}",
+        info.tile_index, info.palette_index
+    );
+    match info.oam_index {
+        Some(index) => format!("SPR {:02} {}", index, tile),
+        None => tile,
+    }
+}
+
+fn format_ago(now: u64, then: u64) -> String {
+    let secs = now.saturating_sub(then);
+    match secs {
+        0..=3599 => format!("{} min ago", secs / 60),
+        3600..=86399 => format!("{} h ago", secs / 3600),
+        _ => format!("{} days ago", secs / 86400),
+    }
+}
+
+/// `nes-emulator stats`: recent ROMs with their play time.
+fn print_rom_stats() {
+    let history = RomHistory::load(HISTORY_PATH);
+    if history.records().is_empty() {
+        println!("No ROMs played yet");
+        return;
+    }
+    let now = unix_now();
+    println!("{:>9}  {:<12}  {:<8}  ROM", "PLAYED", "LAST", "CRC32");
+    for record in history.records() {
+        println!(
+            "{:>9}  {:<12}  {:08X}  {}",
+            format_duration(record.play_time_secs),
+            format_ago(now, record.last_played),
+            record.crc32,
+            record.path
+        );
+    }
+    println!(
+        "Total play time: {}",
+        format_duration(history.total_play_time_secs())
+    );
+}
+
+const COMPAT_RUN_USAGE: &str =
+    "usage: compat-run <dir> [--frames <n>] [--out <report.csv|report.md>]";
+
+/// Boot every ROM under `dir` headlessly and write a compatibility report
+/// (Markdown on stdout unless `--out` names a file; `.csv` picks CSV).
+fn compat_run(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let value = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|i| args.get(i + 1))
+            .map(String::as_str)
+    };
+    let dir = args
+        .get(2)
+        .filter(|a| !a.starts_with("--"))
+        .ok_or(COMPAT_RUN_USAGE)?;
+    let frames = match value("--frames") {
+        Some(n) => n.parse().map_err(|_| COMPAT_RUN_USAGE)?,
+        None => 600,
+    };
+    let roms = find_roms(&[PathBuf::from(dir)]);
+    if roms.is_empty() {
+        return Err(format!("No ROM files found in {}", dir).into());
+    }
+
+    let mut results = Vec::new();
+    for (i, rom) in roms.iter().enumerate() {
+        let result = compat::run_rom(&rom.to_string_lossy(), frames);
+        eprintln!(
+            "[{}/{}] {}: {}",
+            i + 1,
+            roms.len(),
+            result.path,
+            result.status().name()
+        );
+        results.push(result);
+    }
+    match value("--out") {
+        Some(out) => {
+            let report = if out.ends_with(".csv") {
+                compat::report_csv(&results)
+            } else {
+                compat::report_markdown(&results)
+            };
+            std::fs::write(out, report)?;
+            println!("Report for {} ROMs written to {}", results.len(), out);
+        }
+        None => print!("{}", compat::report_markdown(&results)),
+    }
+    Ok(())
+}
+
+const EXPORT_CHR_USAGE: &str =
+    "usage: export-chr <rom> --out <dir> [--state <file>] [--palette gray|0F,16,27,30|state:<0-7>]";
+
+/// Write a ROM's CHR-ROM, or a save state's CHR-RAM, as PNG sheets.
+fn export_chr(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let value = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|i| args.get(i + 1))
+            .map(String::as_str)
+    };
+    let rom = args
+        .get(2)
+        .filter(|a| !a.starts_with("--"))
+        .ok_or(EXPORT_CHR_USAGE)?;
+    let out = value("--out").ok_or(EXPORT_CHR_USAGE)?;
+    let cartridge = Cartridge::load(rom)?;
+    let state = value("--state")
+        .map(SaveState::load_from_file)
+        .transpose()?;
+
+    let chr = match &state {
+        Some(state) => state
+            .cartridge_state
+            .as_ref()
+            .map(|cs| cs.chr_ram.clone())
+            .filter(|ram| !ram.is_empty())
+            .ok_or("save state has no CHR-RAM")?,
+        None if cartridge.chr_rom_size() > 0 => cartridge.chr_rom().to_vec(),
+        None => return Err("ROM uses CHR-RAM; pass --state <file> to export it".into()),
+    };
+    let colors = match value("--palette").unwrap_or("gray") {
+        "gray" => GRAY_PALETTE,
+        spec if spec.starts_with("state:") => {
+            let state = state.as_ref().ok_or("--palette state:<n> needs --state")?;
+            let n = spec["state:".len()..]
+                .parse::<usize>()
+                .ok()
+                .filter(|&n| n < 8)
+                .ok_or("palette number must be 0-7")?;
+            let palette = &state.ppu_palette[n * 4..n * 4 + 4];
+            // Color 0 is the shared backdrop in every palette
+            chr_palette([state.ppu_palette[0], palette[1], palette[2], palette[3]])
+        }
+        spec => {
+            let indices: Vec<u8> = spec
+                .split(',')
+                .map(|color| u8::from_str_radix(color.trim(), 16).ok())
+                .collect::<Option<_>>()
+                .filter(|indices: &Vec<u8>| indices.len() == 4)
+                .ok_or(EXPORT_CHR_USAGE)?;
+            chr_palette([indices[0], indices[1], indices[2], indices[3]])
+        }
+    };
+    let sheets = export_chr_sheets(&chr, &colors, out)?;
+    println!("Wrote {} CHR sheet(s) to {}", sheets, out);
+    Ok(())
+}
+
+/// Print where two save states diverge, for tracking down desyncs.
+fn state_diff(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let (Some(left), Some(right)) = (args.get(2), args.get(3)) else {
+        return Err("usage: state-diff <state> <state>".into());
+    };
+    let diff = diff_states(
+        &SaveState::load_from_file(left)?,
+        &SaveState::load_from_file(right)?,
+    );
+    print!("{}", diff);
+    if !diff.is_identical() {
+        println!("Diverged components: {}", diff.components().join(", "));
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+const INFO_USAGE: &str = "usage: info <rom> [--dat <no-intro.dat>]";
+
+/// Print a ROM's header fields and checksums, and its No-Intro status
+/// when given a DAT.
+fn rom_info(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let path = args
+        .get(2)
+        .filter(|a| !a.starts_with("--"))
+        .ok_or(INFO_USAGE)?;
+    let dat = match args.iter().position(|a| a == "--dat") {
+        Some(i) => Some(RomDat::load(args.get(i + 1).ok_or(INFO_USAGE)?)?),
+        None => None,
+    };
+    let data = std::fs::read(path)?;
+    let cartridge = Cartridge::load(path)?;
+    let mapper = cartridge.mapper_number();
+    println!("File:    {} ({} bytes)", path, data.len());
+    println!(
+        "Mapper:  {} ({})",
+        mapper,
+        Cartridge::board_name(mapper).unwrap_or("unsupported")
+    );
+    println!(
+        "PRG ROM: {} KiB, CHR ROM: {} KiB{}",
+        cartridge.prg_rom_size() / 1024,
+        cartridge.chr_rom_size() / 1024,
+        if cartridge.has_battery_save() {
+            ", battery"
+        } else {
+            ""
+        }
+    );
+    println!("Region:  {}", cartridge.region().name());
+    println!(
+        "CRC32:   {:08X} (PRG+CHR), {:08X} (file)",
+        cartridge.rom_crc32(),
+        nes_emulator::crc32::crc32(&data)
+    );
+    if let Some(dat) = dat {
+        println!("DAT:     {}", verify_dump(&dat, path, &data));
+    }
+    Ok(())
+}
+
+/// Check `rom` against the DAT, warning about anything but a good dump.
+fn check_dump(dat: Option<&RomDat>, rom: &str) -> Option<DumpStatus> {
+    if is_builtin_rom(rom) {
+        return None;
+    }
+    let data = std::fs::read(rom).ok()?;
+    let status = verify_dump(dat?, rom, &data);
+    if status.is_verified() {
+        println!("{}: {}", rom, status);
+    } else {
+        eprintln!("Warning: {}: {}", rom, status);
+    }
+    Some(status)
+}
+
+fn verify_dump(dat: &RomDat, rom: &str, data: &[u8]) -> DumpStatus {
+    let file_name = Path::new(rom)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    dat.verify(&file_name, data)
+}
+
+/// Add the running game's play time to the recent-ROMs list.
+fn record_play_session(history: &mut RomHistory, nes: &Nes, played: std::time::Duration) {
+    if let (Some(path), Some(crc32)) = (nes.rom_path(), nes.rom_crc32()) {
+        if is_builtin_rom(path) {
+            return;
+        }
+        history.record_session(path, crc32, played.as_secs(), unix_now());
+        if let Err(e) = history.save(HISTORY_PATH) {
+            eprintln!("Failed to save {}: {}", HISTORY_PATH, e);
+        }
+    }
+}
+
+/// The ROMs under `dirs` for the browser, recently played first (most
+/// recent at the top), then by name.
+fn rom_choices(dirs: &[PathBuf]) -> Vec<(String, String)> {
+    let mut rom_files: Vec<(String, String)> = find_roms(dirs)
+        .into_iter()
+        .map(|path| {
+            // Show where the ROM sits inside its directory
+            let name = dirs
+                .iter()
+                .find_map(|dir| path.strip_prefix(dir).ok())
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .to_string();
+            (name, path.to_string_lossy().to_string())
+        })
+        .collect();
+
+    let history = RomHistory::load(HISTORY_PATH);
+    let recent_rank = |path: &str| {
+        history
+            .records()
+            .iter()
+            .position(|record| record.path == path)
+            .unwrap_or(usize::MAX)
+    };
+    rom_files.sort_by(|a, b| {
+        recent_rank(&a.1)
+            .cmp(&recent_rank(&b.1))
+            .then_with(|| a.0.cmp(&b.0))
+    });
+    rom_files
+}
+
+/// The title a ROM file carries in its header (decoded from Shift-JIS).
+fn stored_title(path: &Path) -> Option<String> {
+    header_title(&std::fs::read(path).ok()?)
+}
+
+/// Name shown for the running game: its stored title, else the file name.
+/// Toast a speedrun timer event and forward it to LiveSplit, dropping the
+/// connection if it fails. LiveSplit's game time is kept in sync either way.
+fn report_timer_event(
+    timer: &SpeedrunTimer,
+    event: Option<TimerEvent>,
+    live_split: &mut Option<LiveSplitServer>,
+    nes: &Nes,
+    hud_toast: &mut Option<HudToast>,
+) {
+    let elapsed = nes.region().frame_duration() * timer.elapsed() as u32;
+    let toast = match event {
+        Some(TimerEvent::Started) => Some("TIMER STARTED".to_string()),
+        Some(TimerEvent::Split(index)) => Some(format!(
+            "{} {}",
+            timer.splits()[index].name,
+            format_run_time(elapsed)
+        )),
+        Some(TimerEvent::Finished) => Some(format!("FINISHED {}", format_run_time(elapsed))),
+        Some(TimerEvent::Reset) => Some("TIMER RESET".to_string()),
+        None => None,
+    };
+    if let Some(toast) = toast {
+        println!("Speedrun: {}", toast);
+        show_hud_toast(hud_toast, toast);
+    }
+    if let Some(server) = live_split.as_mut() {
+        let sent = match event {
+            Some(event) => server.send_event(event, elapsed),
+            None if timer.is_running() => server.sync(elapsed),
+            None => Ok(()),
+        };
+        if let Err(e) = sent {
+            eprintln!("LiveSplit Server connection lost: {}", e);
+            *live_split = None;
+        }
+    }
+}
+
+/// Forward rumble or an LED change to the gamepad playing `port`, if it
+/// has that actuator.
+fn send_controller_feedback(gamepads: &mut [GameController], feedback: ControllerFeedback) {
+    match feedback {
+        ControllerFeedback::Rumble { port, strength } => {
+            if let Some(gamepad) = gamepads.get_mut(port as usize) {
+                // Runs until the next change; SDL caps a single effect at 65s
+                let level = strength as u16 * 0x101;
+                let duration = if strength > 0 { 0xFFFF } else { 0 };
+                let _ = gamepad.set_rumble(level, level, duration);
+            }
+        }
+        ControllerFeedback::Led { port, rgb } => {
+            if let Some(gamepad) = gamepads.get_mut(port as usize) {
+                if gamepad.has_led() {
+                    let _ = gamepad.set_led(rgb[0], rgb[1], rgb[2]);
                 }
             }
         }
     }
+}
 
-    if rom_files.is_empty() {
-        return Err("No ROM files found in 'roms' directory".into());
+fn game_name(path: &str) -> String {
+    if is_builtin_rom(path) {
+        return String::new();
     }
+    stored_title(Path::new(path)).unwrap_or_else(|| {
+        Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default()
+    })
+}
 
-    rom_files.sort_by(|a, b| a.0.cmp(&b.0));
-
-    println!("Available ROMs:");
-    for (i, (name, _)) in rom_files.iter().enumerate() {
-        println!("{}. {}", i + 1, name);
+/// Show `game` on Discord as played from now; a failed update drops the
+/// connection rather than retrying every ROM change.
+#[cfg(feature = "discord")]
+fn publish_presence(discord: &mut Option<DiscordPresence>, game: &str) {
+    if let Some(presence) = discord {
+        if let Err(e) = presence.set_activity(game, unix_now()) {
+            eprintln!("Discord Rich Presence update failed: {}", e);
+            *discord = None;
+        }
     }
+}
+
+/// Ask for a number from 1 to `count` until one is given; returns its index.
+fn prompt_choice(count: usize) -> std::io::Result<usize> {
+    use std::io::{self, Write};
 
     loop {
-        print!("Select ROM (1-{}): ", rom_files.len());
+        print!("Select ROM (1-{}): ", count);
         io::stdout().flush()?;
 
         let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+        if io::stdin().read_line(&mut input)? == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "no ROM selected",
+            ));
+        }
 
         if let Ok(choice) = input.trim().parse::<usize>() {
-            if choice >= 1 && choice <= rom_files.len() {
-                return Ok(rom_files[choice - 1].1.clone());
+            if choice >= 1 && choice <= count {
+                return Ok(choice - 1);
             }
         }
     }
 }
 
+/// A ROM argument is a path or part of a title found under the ROM dirs.
+fn resolve_rom_arg(query: &str, dirs: &[PathBuf]) -> Result<String, Box<dyn std::error::Error>> {
+    match resolve_rom(query, dirs) {
+        RomLookup::Found(path) => Ok(path.to_string_lossy().to_string()),
+        RomLookup::Ambiguous(paths) => {
+            println!("Several ROMs match '{}':", query);
+            for (i, path) in paths.iter().enumerate() {
+                println!("{}. {}", i + 1, path.display());
+            }
+            let choice = prompt_choice(paths.len())?;
+            Ok(paths[choice].to_string_lossy().to_string())
+        }
+        RomLookup::NotFound => Err(format!("No ROM file or title matches '{}'", query).into()),
+    }
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     env_logger::Builder::from_default_env()
         .filter_level(log::LevelFilter::Info)
@@ -81,12 +575,405 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Check for command line arguments first
     let args: Vec<String> = std::env::args().collect();
-    let selected_rom = if args.len() > 1 {
-        args[1].clone()
+    if args.get(1).map(String::as_str) == Some("stats") {
+        print_rom_stats();
+        return Ok(());
+    }
+    if args.get(1).map(String::as_str) == Some("export-chr") {
+        return export_chr(&args);
+    }
+    if args.get(1).map(String::as_str) == Some("state-diff") {
+        return state_diff(&args);
+    }
+    if args.get(1).map(String::as_str) == Some("compat-run") {
+        return compat_run(&args);
+    }
+    if args.get(1).map(String::as_str) == Some("info") {
+        return rom_info(&args);
+    }
+    let barcode = args
+        .iter()
+        .position(|a| a == "--barcode")
+        .and_then(|i| args.get(i + 1))
+        .cloned();
+    let arg_value = |flag: &str| {
+        args.iter()
+            .position(|a| a == flag)
+            .and_then(|i| args.get(i + 1))
+    };
+    let vs_dip = arg_value("--dip").map(|hex| {
+        u8::from_str_radix(hex.trim_start_matches("0x"), 16).unwrap_or_else(|_| {
+            eprintln!("Invalid --dip value '{}', expected hex", hex);
+            std::process::exit(1);
+        })
+    });
+    let vs_ppu = arg_value("--vs-ppu").map(|name| {
+        VsPpu::from_name(name).unwrap_or_else(|| {
+            eprintln!("Unknown --vs-ppu chip '{}'", name);
+            std::process::exit(1);
+        })
+    });
+    let power_on = arg_value("--power-on").map(|mode| {
+        PowerOnState::from_name(mode).unwrap_or_else(|| {
+            eprintln!(
+                "Unknown --power-on mode '{}' (clean|pattern|random[=seed])",
+                mode
+            );
+            std::process::exit(1);
+        })
+    });
+    let random_ram_seed = args
+        .iter()
+        .find_map(|a| match a.strip_prefix("--random-ram") {
+            Some("") => Some(clock_seed()),
+            Some(seed) => Some(
+                seed.strip_prefix('=')
+                    .and_then(|seed| seed.parse().ok())
+                    .unwrap_or_else(|| {
+                        eprintln!("Invalid --random-ram seed in '{}'", a);
+                        std::process::exit(1);
+                    }),
+            ),
+            None => None,
+        });
+    let mut watchpoints = parse_watchpoints("--watch", arg_value("--watch"), true);
+    watchpoints.extend(parse_watchpoints(
+        "--log-access",
+        arg_value("--log-access"),
+        false,
+    ));
+    let break_on = arg_value("--break-on")
+        .map(|spec| {
+            InterruptKind::parse_list(spec).unwrap_or_else(|| {
+                eprintln!("Invalid --break-on '{}', expected e.g. nmi,brk", spec);
+                std::process::exit(1);
+            })
+        })
+        .unwrap_or_default();
+    let log_brk = args.iter().any(|a| a == "--log-brk");
+    let forced_region = if args.iter().any(|a| a == "--force-pal") {
+        Some(Region::Pal)
+    } else if args.iter().any(|a| a == "--force-ntsc") {
+        Some(Region::Ntsc)
+    } else {
+        None
+    };
+    let forced_bus_conflicts = if args.iter().any(|a| a == "--no-bus-conflicts") {
+        Some(false)
+    } else if args.iter().any(|a| a == "--bus-conflicts") {
+        Some(true)
+    } else {
+        None
+    };
+    // --profile picks the accuracy profile; without it the game DB's entry
+    // for the ROM, else balanced. The single flags override either.
+    let profile_arg = arg_value("--profile").map(|name| {
+        AccuracyProfile::from_name(name).unwrap_or_else(|| {
+            eprintln!("Unknown --profile '{}' (accuracy|balanced|fast)", name);
+            std::process::exit(1);
+        })
+    });
+    // --dat: No-Intro DAT to verify loaded ROMs against
+    let rom_dat = arg_value("--dat").map(|path| {
+        RomDat::load(path).unwrap_or_else(|e| {
+            eprintln!("Failed to read DAT {}: {}", path, e);
+            std::process::exit(1);
+        })
+    });
+    let no_sprite_limit = args.iter().any(|a| a == "--no-sprite-limit");
+    let oam_quirks = args.iter().any(|a| a == "--oam-quirks");
+    let threaded_ppu = args.iter().any(|a| a == "--threaded-ppu");
+    let no_ppu_warmup = args.iter().any(|a| a == "--no-ppu-warmup");
+    let profile_settings = |profile: AccuracyProfile| {
+        let mut settings = profile.settings();
+        settings.sprite_limit &= !no_sprite_limit;
+        settings.oam_quirks |= oam_quirks;
+        settings.threaded_rendering |= threaded_ppu;
+        settings.ppu_warmup &= !no_ppu_warmup;
+        settings
+    };
+    let accuracy_for = |rom: &str| {
+        let from_db = || {
+            let game_db = GameDb::for_rom(rom);
+            if game_db.is_empty() {
+                return None;
+            }
+            let crc32 = Cartridge::load(rom).ok()?.rom_crc32();
+            game_db.profile_for(crc32)
+        };
+        let profile = profile_arg.or_else(from_db).unwrap_or_default();
+        (profile, profile_settings(profile))
+    };
+    let mut color_filter = arg_value("--color-filter").map(|name| {
+        ColorFilter::from_name(name).unwrap_or_else(|| {
+            eprintln!(
+                "Invalid --color-filter '{}', expected protanopia, deuteranopia or tritanopia",
+                name
+            );
+            std::process::exit(1);
+        })
+    });
+    // --scale: how the picture is fitted to the window; I cycles it
+    let scale_filter = arg_value("--scale")
+        .map(|name| {
+            ScaleFilter::from_name(name).unwrap_or_else(|| {
+                eprintln!(
+                    "Invalid --scale '{}', expected nearest, integer or sharp-bilinear",
+                    name
+                );
+                std::process::exit(1);
+            })
+        })
+        .unwrap_or_default();
+    let aspect = arg_value("--aspect")
+        .map(|name| {
+            AspectRatio::from_name(name).unwrap_or_else(|| {
+                eprintln!(
+                    "Invalid --aspect '{}', expected stretch, square, 8:7 or 4:3",
+                    name
+                );
+                std::process::exit(1);
+            })
+        })
+        .unwrap_or_default();
+    let volume = arg_value("--volume").map_or(100, |percent| {
+        percent
+            .parse::<u8>()
+            .ok()
+            .filter(|&percent| percent <= 100)
+            .unwrap_or_else(|| {
+                eprintln!("Invalid --volume '{}', expected 0-100", percent);
+                std::process::exit(1);
+            })
+    });
+    let blend_arg = arg_value("--blend").map(|spec| {
+        FrameBlend::parse(spec).unwrap_or_else(|| {
+            eprintln!(
+                "Invalid --blend '{}', expected average or phosphor, optionally with :<0-1>",
+                spec
+            );
+            std::process::exit(1);
+        })
+    });
+    let mut frame_blend = blend_arg.clone();
+    let auto_resume = args.iter().any(|a| a == "--auto-resume");
+    // --sram-policy: what a state load does to differing battery SRAM
+    let sram_policy = arg_value("--sram-policy")
+        .map(|name| {
+            SramPolicy::from_name(name).unwrap_or_else(|| {
+                eprintln!("Unknown --sram-policy '{}' (ask|keep|state)", name);
+                std::process::exit(1);
+            })
+        })
+        .unwrap_or_default();
+    // --sram-flush: buffered saves, or write-through within a second
+    let sram_flush = arg_value("--sram-flush")
+        .map(|name| {
+            SramFlush::from_name(name).unwrap_or_else(|| {
+                eprintln!("Unknown --sram-flush '{}' (buffered|write-through)", name);
+                std::process::exit(1);
+            })
+        })
+        .unwrap_or_default();
+    let apu_log_path = arg_value("--apu-log");
+    // --discord <application id>: publish the game to a running Discord
+    #[cfg(feature = "discord")]
+    let mut discord = arg_value("--discord").and_then(|client_id| {
+        DiscordPresence::connect(client_id)
+            .map_err(|e| eprintln!("Discord Rich Presence unavailable: {}", e))
+            .ok()
+    });
+    // --splits <file>: speedrun timer with autosplits; --livesplit
+    // <host:port> forwards it to LiveSplit Server
+    let mut speedrun_timer = arg_value("--splits").map(|path| {
+        let source = std::fs::read_to_string(path).unwrap_or_else(|e| {
+            eprintln!("Failed to read {}: {}", path, e);
+            std::process::exit(1);
+        });
+        SpeedrunTimer::parse(&source).unwrap_or_else(|e| {
+            eprintln!("{}: {}", path, e);
+            std::process::exit(1);
+        })
+    });
+    let mut live_split = arg_value("--livesplit").and_then(|addr| {
+        LiveSplitServer::connect(addr)
+            .map_err(|e| eprintln!("LiveSplit Server at {} unavailable: {}", addr, e))
+            .ok()
+    });
+    // --practice <trigger>: state per room, e.g. 'ram[$075C] changes'
+    let mut practice = arg_value("--practice").map(|trigger| {
+        PracticeMode::parse(trigger).unwrap_or_else(|| {
+            eprintln!(
+                "Invalid --practice '{}', expected e.g. 'ram[$075C] changes'",
+                trigger
+            );
+            std::process::exit(1);
+        })
+    });
+    // --turbo-rate <frames>[,<frames for B>]: frames pressed, then released
+    let turbo_rates = arg_value("--turbo-rate")
+        .map(|spec| {
+            let rates: Vec<u32> = spec
+                .split(',')
+                .map(|rate| rate.trim().parse().ok().filter(|&rate| rate > 0))
+                .collect::<Option<_>>()
+                .unwrap_or_else(|| {
+                    eprintln!("Invalid --turbo-rate '{}', expected e.g. 2 or 2,4", spec);
+                    std::process::exit(1);
+                });
+            [rates[0], *rates.get(1).unwrap_or(&rates[0])]
+        })
+        .unwrap_or([DEFAULT_TURBO_RATE; 2]);
+    let startup_macro = arg_value("--macro").map(|spec| {
+        InputMacro::parse(spec).unwrap_or_else(|| {
+            eprintln!(
+                "Invalid --macro '{}', expected e.g. DOWN*2,DOWN+RIGHT,B",
+                spec
+            );
+            std::process::exit(1);
+        })
+    });
+    let startup_macro = arg_value("--input-script")
+        .map(|path| {
+            let source = std::fs::read_to_string(path).unwrap_or_else(|e| {
+                eprintln!("Failed to read {}: {}", path, e);
+                std::process::exit(1);
+            });
+            let script = parse_script(&source).unwrap_or_else(|e| {
+                eprintln!("{}:{}", path, e);
+                std::process::exit(1);
+            });
+            if script.has_conditions() {
+                eprintln!("{}: wait/fail conditions only run in headless_test", path);
+            }
+            script.timeline().clone()
+        })
+        .or(startup_macro);
+    // --frontend: the full SDL frontend, or the basic player under winit
+    let frontend = arg_value("--frontend").map_or("sdl", String::as_str);
+    if !matches!(frontend, "sdl" | "winit") {
+        eprintln!("Unknown --frontend '{}' (sdl|winit)", frontend);
+        std::process::exit(1);
+    }
+    let sync_mode = arg_value("--sync")
+        .map(|name| {
+            SyncMode::from_name(name).unwrap_or_else(|| {
+                eprintln!("Unknown --sync mode '{}' (audio|video|hybrid)", name);
+                std::process::exit(1);
+            })
+        })
+        .unwrap_or_default();
+    // Fast-forward (hold `): frames per real frame and what the audio does
+    let ff_speed = arg_value("--ff-speed")
+        .map(|value| {
+            value
+                .parse::<usize>()
+                .ok()
+                .filter(|&n| n > 1)
+                .unwrap_or_else(|| {
+                    eprintln!("Invalid --ff-speed '{}', expected 2 or more", value);
+                    std::process::exit(1);
+                })
+        })
+        .unwrap_or(4);
+    let ff_audio = arg_value("--ff-audio")
+        .map(|name| {
+            FastForwardAudio::from_name(name).unwrap_or_else(|| {
+                eprintln!("Unknown --ff-audio mode '{}' (resample|mute|skip)", name);
+                std::process::exit(1);
+            })
+        })
+        .unwrap_or_default();
+    let extra_rom_dirs: Vec<String> = args
+        .windows(2)
+        .filter(|pair| pair[0] == "--rom-dir")
+        .map(|pair| pair[1].clone())
+        .collect();
+    // --watch-rom: reload when the ROM (or a --watch-file) changes on disk
+    let watch_rom = args.iter().any(|a| a == "--watch-rom");
+    let watch_files: Vec<PathBuf> = args
+        .windows(2)
+        .filter(|pair| pair[0] == "--watch-file")
+        .map(|pair| PathBuf::from(&pair[1]))
+        .collect();
+    let dirs = rom_dirs(&extra_rom_dirs);
+    let selftest = args.get(1).map(String::as_str) == Some("selftest");
+    // Image of a built-in program; those are loaded from memory
+    let mut builtin_image: Option<Vec<u8>> = None;
+    let selected_rom = if selftest {
+        println!("Self-test: hold any button to flash the screen; latency is printed");
+        builtin_image = Some(selftest_rom());
+        builtin_rom_name("selftest")
+    } else if args.len() > 1 && !args[1].starts_with("--") {
+        resolve_rom_arg(&args[1], &dirs)?
     } else {
-        // Show ROM selection screen
-        show_rom_selection()?
+        // No ROM given: boot the built-in screen, where O opens the browser
+        builtin_image = Some(boot_screen_rom());
+        builtin_rom_name("boot")
+    };
+
+    let mut nes = Nes::new();
+    if let Some(state) = power_on {
+        if let PowerOnState::Random(seed) = state {
+            println!("Random power-on state (seed {})", seed);
+        }
+        nes.set_power_on_state(state);
+    }
+    if let Some(seed) = random_ram_seed {
+        println!("Randomizing RAM (seed {})", seed);
+        nes.randomize_ram(seed);
+    }
+
+    nes.set_forced_region(forced_region);
+    nes.set_forced_bus_conflicts(forced_bus_conflicts);
+    let (profile, accuracy) = accuracy_for(&selected_rom);
+    if profile != AccuracyProfile::default() {
+        println!("Accuracy profile: {}", profile.name());
+    }
+    accuracy.apply(&mut nes);
+    // Esc opens the settings menu over the game
+    let mut settings = Settings {
+        scale: scale_filter,
+        aspect,
+        volume,
+        profile,
+        ..Settings::default()
+    };
+    let mut settings_menu: Option<SettingsMenu> = None;
+    // A profile picked in the menu outranks --profile and the game DB for
+    // the rest of the session, ROM swaps included
+    let mut menu_profile: Option<AccuracyProfile> = None;
+    nes.set_interrupt_breaks(&break_on);
+    nes.set_brk_logging(log_brk);
+    let loaded = match &builtin_image {
+        Some(image) => nes.load_rom_bytes(&selected_rom, image),
+        None => nes.load_rom(&selected_rom),
     };
+    if let Err(_e) = loaded {
+        std::process::exit(1);
+    }
+    let mut dump = check_dump(rom_dat.as_ref(), &selected_rom);
+    if nes.region() != Region::Ntsc {
+        println!("Running with {} timing", nes.region().name());
+    }
+    if let Some(switches) = vs_dip {
+        nes.set_vs_dip_switches(switches);
+    }
+    if let Some(vs_ppu) = vs_ppu {
+        nes.set_vs_ppu(vs_ppu);
+    }
+    for watchpoint in watchpoints {
+        nes.add_watchpoint(watchpoint);
+    }
+    nes.set_fast_forward_audio(ff_audio);
+    nes.set_sram_policy(sram_policy);
+    nes.set_sram_flush(sram_flush);
+    if apu_log_path.is_some() {
+        nes.start_apu_log();
+    }
+    if frontend == "winit" {
+        return run_winit_frontend(nes);
+    }
 
     // Initialize SDL2 for emulation
     sdl2::hint::set("SDL_DISABLE_IMMINTRIN_H", "1");
@@ -96,28 +983,60 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let video_subsystem = sdl_context.video()?;
     video_subsystem.text_input().stop();
 
-    let mut nes = Nes::new();
-
-    if let Err(_e) = nes.load_rom(&selected_rom) {
-        std::process::exit(1);
+    let mut rom_history = RomHistory::load(HISTORY_PATH);
+    let watcher_for = |rom: &str| {
+        let mut paths = vec![PathBuf::from(rom)];
+        paths.extend(watch_files.iter().cloned());
+        RomWatcher::new(paths)
+    };
+    let mut rom_watcher =
+        (watch_rom && builtin_image.is_none()).then(|| watcher_for(&selected_rom));
+    let mut session_play = std::time::Duration::ZERO;
+    // Built-in programs have no exit state of their own
+    let resumable = |nes: &Nes| auto_resume && !nes.rom_path().is_some_and(is_builtin_rom);
+    record_play_session(&mut rom_history, &nes, session_play);
+    if resumable(&nes) {
+        match nes.resume_auto_state() {
+            Ok(true) => println!("Resumed from exit state"),
+            Ok(false) => {}
+            Err(e) => eprintln!("Failed to resume exit state: {}", e),
+        }
     }
 
     // Re-initialize audio subsystem for emulation
     let audio_subsystem = sdl_context.audio()?;
+    // Gamepads are opened as they connect (SDL also reports those already
+    // plugged in) so rumble and LED feedback can reach them
+    let controller_subsystem = sdl_context.game_controller().ok();
+    let mut gamepads: Vec<GameController> = Vec::new();
 
     // Create the emulation window
+    let mut game = game_name(&selected_rom);
     let window = video_subsystem
-        .window("NES Emulator", 256 * 3, 240 * 3)
+        .window(
+            &window_title(&game, dump.as_ref(), None, None),
+            256 * 3,
+            240 * 3,
+        )
         .position_centered()
         .resizable()
         .build()?;
 
-    let mut canvas = window.into_canvas().build()?;
+    let mut canvas = if sync_mode.uses_vsync() {
+        window.into_canvas().present_vsync().build()?
+    } else {
+        window.into_canvas().build()?
+    };
     // Set canvas clear color to black instead of default (cyan)
     canvas.set_draw_color(sdl2::pixels::Color::RGB(5, 5, 5));
     let texture_creator = canvas.texture_creator();
 
+    // Scale quality is fixed when a texture is created: the frame is always
+    // sampled nearest, the sharp-bilinear prescale target linearly
+    sdl2::hint::set("SDL_RENDER_SCALE_QUALITY", "nearest");
     let mut texture = texture_creator.create_texture_streaming(PixelFormatEnum::RGB24, 256, 240)?;
+    // Sharp bilinear: the frame at a whole-number scale, and that scale
+    let mut prescaled: Option<((u32, u32), sdl2::render::Texture)> = None;
 
     // Set up audio
     let desired_spec = sdl2::audio::AudioSpecDesired {
@@ -128,6 +1047,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let audio_ring: Arc<SpscRingBuffer> = Arc::new(SpscRingBuffer::new(16384));
     let audio_ring_clone = audio_ring.clone();
+    let audio_volume = Arc::new(AtomicU8::new(settings.volume));
 
     // Attach ring buffer so APU pushes samples directly as they are generated
     nes.set_audio_ring(audio_ring.clone());
@@ -135,6 +1055,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let audio_device =
         audio_subsystem.open_playback(None, &desired_spec, |_spec| NesAudioCallback {
             ring: audio_ring_clone,
+            volume: audio_volume.clone(),
             phase: 0.0,
         })?;
 
@@ -157,18 +1078,51 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let mut event_pump = sdl_context.event_pump()?;
 
-    // NTSC NES frame rate: 5369318.18 Hz PPU / (341*262-0.5) = 60.0988 Hz
-    let frame_duration = Duration::from_nanos(16_639_267);
-    let mut last_frame = Instant::now();
+    let mut pacer = FramePacer::new(sync_mode, Instant::now());
+    pacer.set_frame_duration(nes.region().frame_duration());
     let mut _frame_count = 0;
     let _start_time = Instant::now();
     let mut frames_since_save = 0u32;
     let mut hud_toast: Option<HudToast> = None;
-    let mut hud_overlay_frame: Vec<u8> = Vec::new();
+    let mut rom_browser: Option<RomBrowser> = None;
+    let mut scroll_view = false;
+    let mut rom_to_load: Option<String> = None;
+    let mut latency_probe = selftest.then(LatencyProbe::new);
+    let mut dip_cursor = 0u8;
+    let mut show_patterns = false;
+    let mut io_page: Option<IoPage> = None;
+    let mut show_stats = false;
+    let mut show_frame_graph = false;
+    let mut frame_graph = FrameGraph::new();
+    let mut fps_counter = FpsCounter::new(Instant::now());
+    // Save state slot last saved or loaded, for the window title
+    let mut last_slot: Option<u8> = None;
+    #[cfg(feature = "discord")]
+    publish_presence(&mut discord, &game);
+    let mut paused = false;
+    // Whether the last loop iteration ran emulation, to fade audio back in
+    let mut was_running = true;
+    let mut fast_forward = false;
+    let mut state_slots: Option<Vec<Option<SlotInfo>>> = None;
+    let mut patch_editor: Option<PatchEditor> = None;
+    // Slot whose load stopped on an SRAM conflict, until it is confirmed
+    let mut sram_confirm: Option<(u8, Instant)> = None;
+    let mut input = InputState::new(turbo_rates);
+    if let Some(input_macro) = startup_macro {
+        input.set_macro(input_macro);
+    }
 
     'running: loop {
         // Handle events
         for event in event_pump.poll_iter() {
+            if let Some(probe) = latency_probe.as_mut() {
+                if matches!(
+                    event,
+                    Event::KeyDown { repeat: false, .. } | Event::ControllerButtonDown { .. }
+                ) {
+                    probe.press(Instant::now());
+                }
+            }
             match event {
                 Event::Quit { .. } => {
                     // Save SRAM before quitting
@@ -177,9 +1131,148 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     }
                     break 'running;
                 }
+                Event::ControllerDeviceAdded { which, .. } => {
+                    if let Some(Ok(gamepad)) = controller_subsystem.as_ref().map(|c| c.open(which))
+                    {
+                        gamepads.push(gamepad);
+                    }
+                }
+                Event::ControllerDeviceRemoved { which, .. } => {
+                    gamepads.retain(|gamepad| gamepad.instance_id() != which);
+                }
+                Event::DropFile { filename, .. } => {
+                    // Hot-swap to a ROM dropped on the window
+                    rom_to_load = Some(filename);
+                }
+                Event::TextInput { text, .. } => {
+                    if let Some(editor) = patch_editor.as_mut() {
+                        editor.type_text(&text);
+                    }
+                }
+                Event::KeyDown {
+                    keycode: Some(key),
+                    keymod,
+                    ..
+                } if patch_editor.is_some() => {
+                    // The patch editor takes all keys while open; text
+                    // arrives as TextInput events
+                    let Some(editor) = patch_editor.as_mut() else {
+                        continue;
+                    };
+                    let patch_count = nes.patches().list().len();
+                    let ctrl = keymod
+                        .intersects(sdl2::keyboard::Mod::LCTRLMOD | sdl2::keyboard::Mod::RCTRLMOD);
+                    match key {
+                        Keycode::Escape => {
+                            patch_editor = None;
+                            video_subsystem.text_input().stop();
+                        }
+                        Keycode::Backspace => {
+                            editor.input.pop();
+                        }
+                        Keycode::Up => editor.move_selection(-1, patch_count),
+                        Keycode::Down => editor.move_selection(1, patch_count),
+                        Keycode::Delete => {
+                            if let Some(patch) = nes.remove_patch(editor.selected) {
+                                println!("Removed patch {}", patch);
+                                editor.move_selection(0, patch_count - 1);
+                            }
+                        }
+                        Keycode::Return if editor.input.is_empty() => {
+                            if let Some(patch) = nes.patches().list().get(editor.selected) {
+                                nes.set_patch_enabled(editor.selected, !patch.enabled);
+                            }
+                        }
+                        Keycode::Return => match Patch::parse(&editor.input) {
+                            Some(patch) => {
+                                println!("Added patch {}", patch);
+                                editor.selected = nes.add_patch(patch);
+                                editor.input.clear();
+                            }
+                            None => show_hud_toast(&mut hud_toast, "BAD PATCH"),
+                        },
+                        Keycode::E if ctrl => {
+                            // Export: printed and copied to the clipboard
+                            let codes = nes.patches().export_game_genie();
+                            for code in &codes {
+                                println!("{}", code);
+                            }
+                            let _ = video_subsystem
+                                .clipboard()
+                                .set_clipboard_text(&codes.join("\n"));
+                            show_hud_toast(&mut hud_toast, format!("{} CODES COPIED", codes.len()));
+                        }
+                        _ => {}
+                    }
+                }
+                Event::KeyDown {
+                    keycode: Some(key), ..
+                } if settings_menu.is_some() => {
+                    // The settings menu takes all keys while open
+                    let Some(menu) = settings_menu.as_mut() else {
+                        continue;
+                    };
+                    let changed = if menu.is_capturing() {
+                        let name = key.name();
+                        menu.capture(&mut settings, (key != Keycode::Escape).then_some(&*name));
+                        None
+                    } else {
+                        match key {
+                            Keycode::Escape => {
+                                settings_menu = None;
+                                None
+                            }
+                            Keycode::Up => {
+                                menu.move_selection(-1);
+                                None
+                            }
+                            Keycode::Down => {
+                                menu.move_selection(1);
+                                None
+                            }
+                            Keycode::Left => menu.adjust(&mut settings, -1),
+                            Keycode::Right => menu.adjust(&mut settings, 1),
+                            Keycode::Return => menu.activate(&mut settings),
+                            _ => None,
+                        }
+                    };
+                    match changed {
+                        Some(SettingsItem::Volume) => {
+                            audio_volume.store(settings.volume, Ordering::Relaxed);
+                        }
+                        Some(SettingsItem::Profile) => {
+                            menu_profile = Some(settings.profile);
+                            profile_settings(settings.profile).apply(&mut nes);
+                        }
+                        _ => {}
+                    }
+                }
+                Event::KeyDown {
+                    keycode: Some(key), ..
+                } if rom_browser.is_some() => {
+                    // The ROM browser takes all keys while open
+                    let Some(browser) = rom_browser.as_mut() else {
+                        continue;
+                    };
+                    match key {
+                        Keycode::Escape | Keycode::O => rom_browser = None,
+                        Keycode::Up => browser.move_selection(-1),
+                        Keycode::Down => browser.move_selection(1),
+                        Keycode::PageUp => browser.move_selection(-10),
+                        Keycode::PageDown => browser.move_selection(10),
+                        Keycode::Return => {
+                            if let Some(path) = browser.selected_path() {
+                                rom_to_load = Some(path.to_string());
+                                rom_browser = None;
+                            }
+                        }
+                        _ => {}
+                    }
+                }
                 Event::KeyDown {
                     keycode: Some(key),
                     keymod,
+                    repeat,
                     ..
                 } => {
                     if let Some(slot) = state_slot_from_key(key) {
@@ -190,6 +1283,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                             match nes.save_state(slot, "current_rom") {
                                 Ok(()) => {
                                     show_hud_toast(&mut hud_toast, format!("SAVE {slot} OK"));
+                                    last_slot = Some(slot);
+                                    if state_slots.is_some() {
+                                        state_slots = Some(read_state_slots(&nes));
+                                    }
                                 }
                                 Err(e) => {
                                     eprintln!("Failed to save state slot {}: {}", slot, e);
@@ -197,9 +1294,37 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                                 }
                             }
                         } else {
-                            match nes.load_state(slot) {
+                            // Shift keeps the current SRAM; pressing the slot
+                            // again after a conflict takes the state's
+                            let shift = keymod.intersects(
+                                sdl2::keyboard::Mod::LSHIFTMOD | sdl2::keyboard::Mod::RSHIFTMOD,
+                            );
+                            let confirmed = sram_confirm.take().is_some_and(|(pending, at)| {
+                                pending == slot && at.elapsed() < std::time::Duration::from_secs(3)
+                            });
+                            let result = if shift {
+                                nes.load_state_with_sram(slot, SramPolicy::Keep)
+                            } else if confirmed {
+                                nes.load_state_with_sram(slot, SramPolicy::State)
+                            } else {
+                                nes.load_state(slot)
+                            };
+                            match result {
                                 Ok(()) => {
                                     show_hud_toast(&mut hud_toast, format!("LOAD {slot} OK"));
+                                    last_slot = Some(slot);
+                                    state_slots = None;
+                                }
+                                Err(e) if e.is::<SramConflict>() => {
+                                    println!(
+                                        "Slot {}: {}; press {} again to use the state's SRAM (the current one goes to the .sav.bak file) or Shift+{} to keep it",
+                                        slot, e, slot, slot
+                                    );
+                                    sram_confirm = Some((slot, Instant::now()));
+                                    show_hud_toast(
+                                        &mut hud_toast,
+                                        "SRAM DIFFERS: AGAIN=STATE SHIFT=KEEP",
+                                    );
                                 }
                                 Err(e) => {
                                     eprintln!("Failed to load state slot {}: {}", slot, e);
@@ -210,111 +1335,674 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                         continue;
                     }
 
-                    let controller = map_key_to_controller(key, nes.get_controller());
-                    nes.set_controller(controller);
+                    match key {
+                        Keycode::Escape => {
+                            // Release everything held, as the menu eats the key-ups
+                            input.set_held(ControllerState::empty());
+                            settings_menu = Some(SettingsMenu::new());
+                            continue;
+                        }
+                        Keycode::Tab => {
+                            // State picker: pauses the game while open
+                            state_slots = match state_slots {
+                                Some(_) => None,
+                                None => {
+                                    let slots = read_state_slots(&nes);
+                                    for (i, info) in slots.iter().enumerate() {
+                                        println!(
+                                            "{}",
+                                            describe_state_slot(i as u8 + 1, info.as_ref())
+                                        );
+                                    }
+                                    Some(slots)
+                                }
+                            };
+                            continue;
+                        }
+                        Keycode::F1 => {
+                            let visible = !nes.background_visible();
+                            nes.set_background_visible(visible);
+                            show_hud_toast(&mut hud_toast, layer_toast("BG", visible));
+                            continue;
+                        }
+                        Keycode::F2 => {
+                            let visible = !nes.sprites_visible();
+                            nes.set_sprites_visible(visible);
+                            show_hud_toast(&mut hud_toast, layer_toast("SPR", visible));
+                            continue;
+                        }
+                        Keycode::F3 => {
+                            let enabled = !nes.pixel_inspector_enabled();
+                            nes.set_pixel_inspector(enabled);
+                            show_hud_toast(&mut hud_toast, layer_toast("INSPECT", enabled));
+                            continue;
+                        }
+                        Keycode::F4 => {
+                            let enabled = nes.audio_scope().is_none();
+                            nes.set_audio_scope(enabled);
+                            show_hud_toast(&mut hud_toast, layer_toast("SCOPE", enabled));
+                            continue;
+                        }
+                        Keycode::F5 => {
+                            if let Some(ref code) = barcode {
+                                match nes.datach_swipe_barcode(code) {
+                                    Ok(()) => show_hud_toast(&mut hud_toast, "BARCODE"),
+                                    Err(e) => {
+                                        eprintln!("Failed to swipe barcode: {}", e);
+                                        show_hud_toast(&mut hud_toast, "BARCODE ERR");
+                                    }
+                                }
+                            }
+                            continue;
+                        }
+                        Keycode::F8 => {
+                            show_patterns = !show_patterns;
+                            show_hud_toast(&mut hud_toast, layer_toast("CHR VIEW", show_patterns));
+                            continue;
+                        }
+                        Keycode::F9 => {
+                            let protect = !nes.chr_write_protect();
+                            nes.set_chr_write_protect(protect);
+                            show_hud_toast(&mut hud_toast, layer_toast("CHR WP", protect));
+                            continue;
+                        }
+                        Keycode::F10 => {
+                            let enabled = !nes.chr_write_logging();
+                            nes.set_chr_write_logging(enabled);
+                            show_hud_toast(&mut hud_toast, layer_toast("CHR LOG", enabled));
+                            continue;
+                        }
+                        Keycode::F11 => {
+                            paused = !paused;
+                            show_hud_toast(&mut hud_toast, if paused { "PAUSE" } else { "RESUME" });
+                            continue;
+                        }
+                        Keycode::F12 => {
+                            let limit = !nes.sprite_limit();
+                            nes.set_sprite_limit(limit);
+                            show_hud_toast(&mut hud_toast, layer_toast("SPRITE LIMIT", limit));
+                            continue;
+                        }
+                        Keycode::F6 if nes.is_vs_system() => {
+                            dip_cursor = (dip_cursor + 1) % 8;
+                            let on = nes.vs_dip_switches() & (1 << dip_cursor) != 0;
+                            show_hud_toast(&mut hud_toast, dip_toast(dip_cursor, on));
+                            continue;
+                        }
+                        Keycode::F7 if nes.is_vs_system() => {
+                            let switches = nes.vs_dip_switches() ^ (1 << dip_cursor);
+                            nes.set_vs_dip_switches(switches);
+                            let on = switches & (1 << dip_cursor) != 0;
+                            show_hud_toast(&mut hud_toast, dip_toast(dip_cursor, on));
+                            continue;
+                        }
+                        _ => {}
+                    }
+
+                    if key == Keycode::R
+                        && keymod.intersects(
+                            sdl2::keyboard::Mod::LCTRLMOD | sdl2::keyboard::Mod::RCTRLMOD,
+                        )
+                    {
+                        nes.reset();
+                        input.record_event(ConsoleEvent::Reset);
+                        show_hud_toast(&mut hud_toast, "RESET".to_string());
+                        continue;
+                    }
+                    if key == Keycode::P
+                        && keymod.intersects(
+                            sdl2::keyboard::Mod::LCTRLMOD | sdl2::keyboard::Mod::RCTRLMOD,
+                        )
+                    {
+                        if let Err(e) = nes.hard_reset() {
+                            eprintln!("Power cycle failed: {}", e);
+                        }
+                        input.record_event(ConsoleEvent::Power);
+                        show_hud_toast(&mut hud_toast, "POWER".to_string());
+                        continue;
+                    }
+                    if key == Keycode::R {
+                        // Register reference: PPU page, APU page, off
+                        io_page = match io_page {
+                            None => Some(IoPage::Ppu),
+                            Some(IoPage::Ppu) => Some(IoPage::Apu),
+                            Some(IoPage::Apu) => None,
+                        };
+                        continue;
+                    }
+                    if key == Keycode::B {
+                        // Why is the screen blank? Printed to the terminal
+                        print!("{}", blank_screen::diagnose(&nes));
+                        show_hud_toast(&mut hud_toast, "SCREEN CHECK");
+                        continue;
+                    }
+                    if key == Keycode::T {
+                        show_stats = !show_stats;
+                        continue;
+                    }
+                    if key == Keycode::G {
+                        show_frame_graph = !show_frame_graph;
+                        continue;
+                    }
+                    if key == Keycode::Q {
+                        // Practice: back to the start of the room
+                        if let Some(practice) = practice.as_mut() {
+                            match practice.reload(&mut nes) {
+                                Ok(true) => show_hud_toast(&mut hud_toast, "ROOM RELOADED"),
+                                Ok(false) => show_hud_toast(&mut hud_toast, "NO ROOM STATE"),
+                                Err(e) => eprintln!("Practice reload failed: {}", e),
+                            }
+                        }
+                        continue;
+                    }
+                    if key == Keycode::J || key == Keycode::Backspace {
+                        // Speedrun timer: split (or start) and reset by hand
+                        if let Some(timer) = speedrun_timer.as_mut() {
+                            let event = if key == Keycode::J {
+                                timer.manual_split()
+                            } else {
+                                timer.reset();
+                                Some(TimerEvent::Reset)
+                            };
+                            report_timer_event(timer, event, &mut live_split, &nes, &mut hud_toast);
+                        }
+                        continue;
+                    }
+                    if key == Keycode::O {
+                        // ROM browser; rescanned on open so new files show up
+                        rom_browser = Some(RomBrowser::new(rom_choices(&dirs)));
+                        continue;
+                    }
+                    if key == Keycode::E {
+                        // Patch editor: ROM patches and RAM freezes, typed in
+                        patch_editor = Some(PatchEditor::default());
+                        video_subsystem.text_input().start();
+                        continue;
+                    }
+                    if key == Keycode::D {
+                        // Dump the pattern tables as mapped now, in background palette 0
+                        let chr: Vec<u8> = (0..0x2000).map(|addr| nes.peek_chr(addr)).collect();
+                        let palette = nes.ppu_palette();
+                        let colors = chr_palette([palette[0], palette[1], palette[2], palette[3]]);
+                        let dir = format!("chr_dumps/{}", unix_now());
+                        match export_chr_sheets(&chr, &colors, &dir) {
+                            Ok(_) => {
+                                println!("Pattern tables written to {}/", dir);
+                                show_hud_toast(&mut hud_toast, "CHR DUMPED");
+                            }
+                            Err(e) => {
+                                eprintln!("Failed to dump pattern tables: {}", e);
+                                show_hud_toast(&mut hud_toast, "CHR DUMP ERR");
+                            }
+                        }
+                        continue;
+                    }
+                    if key == Keycode::N {
+                        // Full four-nametable map with the visible area outlined
+                        let path = format!("nametables/{}.png", unix_now());
+                        let view = nes.nametable_view_rgb24(true);
+                        match std::fs::create_dir_all("nametables")
+                            .and_then(|_| write_rgb24_png(&path, VIEW_WIDTH, VIEW_HEIGHT, &view))
+                        {
+                            Ok(()) => {
+                                println!("Nametables written to {}", path);
+                                show_hud_toast(&mut hud_toast, "NAMETABLES SAVED");
+                            }
+                            Err(e) => {
+                                eprintln!("Failed to save nametables: {}", e);
+                                show_hud_toast(&mut hud_toast, "NAMETABLE ERR");
+                            }
+                        }
+                        continue;
+                    }
+                    if key == Keycode::I {
+                        settings.scale = settings.scale.cycle();
+                        show_hud_toast(
+                            &mut hud_toast,
+                            format!("SCALE {}", settings.scale.name().to_uppercase()),
+                        );
+                        continue;
+                    }
+                    if key == Keycode::P {
+                        color_filter = ColorFilter::cycle(color_filter);
+                        let name = color_filter.map_or("OFF", |filter| filter.name());
+                        show_hud_toast(&mut hud_toast, format!("COLOR {}", name.to_uppercase()));
+                        continue;
+                    }
+                    if key == Keycode::U {
+                        // Needs the per-pixel provenance the inspector records
+                        scroll_view = !scroll_view;
+                        nes.set_pixel_inspector(scroll_view);
+                        show_hud_toast(&mut hud_toast, layer_toast("SCROLL", scroll_view));
+                        continue;
+                    }
+                    if key == Keycode::H {
+                        // The --blend strength is kept for its own mode
+                        let mode = BlendMode::cycle(frame_blend.as_ref().map(FrameBlend::mode));
+                        frame_blend = mode.map(|mode| match &blend_arg {
+                            Some(blend) if blend.mode() == mode => blend.clone(),
+                            _ => FrameBlend::new(mode, mode.default_strength()),
+                        });
+                        let name = mode.map_or("OFF", BlendMode::name);
+                        show_hud_toast(&mut hud_toast, format!("BLEND {}", name.to_uppercase()));
+                        continue;
+                    }
+                    if key == Keycode::M {
+                        nes.set_microphone(true);
+                        continue;
+                    }
+                    if key == Keycode::Backquote {
+                        if !repeat {
+                            fast_forward = true;
+                            nes.set_fast_forward(ff_speed);
+                            show_hud_toast(&mut hud_toast, format!("FAST x{}", ff_speed));
+                        }
+                        continue;
+                    }
+                    if let Some(buttons) = turbo_buttons_for_key(key) {
+                        input.set_turbo(buttons, true);
+                        continue;
+                    }
+                    if key == Keycode::K {
+                        if repeat {
+                            continue;
+                        }
+                        if !input.is_recording() {
+                            input.start_recording();
+                            show_hud_toast(&mut hud_toast, "MACRO REC");
+                        } else if let Some(input_macro) = input.stop_recording() {
+                            println!("Recorded macro: {}", input_macro);
+                            show_hud_toast(&mut hud_toast, "MACRO SAVED");
+                        } else {
+                            show_hud_toast(&mut hud_toast, "MACRO EMPTY");
+                        }
+                        continue;
+                    }
+                    if key == Keycode::L {
+                        if !repeat && input.play_macro() {
+                            show_hud_toast(&mut hud_toast, "MACRO PLAY");
+                        }
+                        continue;
+                    }
+                    if let Some(slot) = coin_slot_for_key(key) {
+                        nes.set_vs_coin(slot, true);
+                        continue;
+                    }
+
+                    if let Some(button) = settings.bindings.button_for(&key.name()) {
+                        input.set_held(input.held() | button);
+                    }
+                }
+                Event::MouseButtonDown {
+                    mouse_btn: sdl2::mouse::MouseButton::Left,
+                    x,
+                    y,
+                    ..
+                } if nes.pixel_inspector_enabled() => {
+                    let (win_w, win_h) = canvas.window().size();
+                    if win_w == 0 || win_h == 0 || x < 0 || y < 0 {
+                        continue;
+                    }
+                    let px = x as usize * 256 / win_w as usize;
+                    let py = y as usize * 240 / win_h as usize;
+                    if let Some(info) = nes.pixel_provenance(px, py) {
+                        println!("{}", describe_pixel(px, py, &info));
+                        show_hud_toast(&mut hud_toast, pixel_toast(&info));
+                    }
                 }
                 Event::KeyUp {
                     keycode: Some(key), ..
                 } => {
-                    let controller = unmap_key_from_controller(key, nes.get_controller());
-                    nes.set_controller(controller);
+                    if key == Keycode::M {
+                        nes.set_microphone(false);
+                        continue;
+                    }
+                    if key == Keycode::Backquote {
+                        fast_forward = false;
+                        nes.set_fast_forward(1);
+                        continue;
+                    }
+                    if let Some(slot) = coin_slot_for_key(key) {
+                        nes.set_vs_coin(slot, false);
+                        continue;
+                    }
+                    if let Some(buttons) = turbo_buttons_for_key(key) {
+                        input.set_turbo(buttons, false);
+                        continue;
+                    }
+                    if let Some(button) = settings.bindings.button_for(&key.name()) {
+                        input.set_held(input.held() - button);
+                    }
                 }
                 _ => {}
             }
         }
 
-        // Run emulation until frame is complete
-        let mut step_count = 0;
-        loop {
-            let frame_complete = nes.step();
-            if frame_complete {
-                break;
+        // Hot-swap to a ROM dropped on the window or picked in the browser
+        if let Some(filename) = rom_to_load.take() {
+            record_play_session(&mut rom_history, &nes, session_play);
+            session_play = std::time::Duration::ZERO;
+            if resumable(&nes) {
+                if let Err(e) = nes.save_auto_state() {
+                    eprintln!("Failed to save exit state: {}", e);
+                }
             }
-            step_count += 1;
+            let (profile, accuracy) = match menu_profile {
+                Some(profile) => (profile, profile_settings(profile)),
+                None => accuracy_for(&filename),
+            };
+            accuracy.apply(&mut nes);
+            match nes.load_rom(&filename) {
+                Ok(()) => {
+                    println!("Loaded {} ({} profile)", filename, profile.name());
+                    settings.profile = profile;
+                    game = game_name(&filename);
+                    dump = check_dump(rom_dat.as_ref(), &filename);
+                    last_slot = None;
+                    #[cfg(feature = "discord")]
+                    publish_presence(&mut discord, &game);
+                    if watch_rom {
+                        rom_watcher = Some(watcher_for(&filename));
+                    }
+                    record_play_session(&mut rom_history, &nes, session_play);
+                    if resumable(&nes) {
+                        if let Err(e) = nes.resume_auto_state() {
+                            eprintln!("Failed to resume exit state: {}", e);
+                        }
+                    }
+                    state_slots = None;
+                    if let Some(blend) = frame_blend.as_mut() {
+                        blend.reset();
+                    }
+                    show_hud_toast(&mut hud_toast, "ROM LOADED".to_string());
+                }
+                Err(e) => {
+                    eprintln!("Failed to load {}: {}", filename, e);
+                    show_hud_toast(&mut hud_toast, "ROM LOAD ERR".to_string());
+                }
+            }
+        }
 
-            if step_count > 50000 {
-                // Normal limit for frame completion
-                break;
+        // Poll twice a second; breakpoints and settings survive the reload
+        if let Some(watcher) = rom_watcher.as_mut() {
+            if _frame_count % 30 == 0 && watcher.poll() {
+                let rom = watcher.paths()[0].to_string_lossy().to_string();
+                match nes.load_rom(&rom) {
+                    Ok(()) => {
+                        println!("Reloaded {}", rom);
+                        game = game_name(&rom);
+                        dump = check_dump(rom_dat.as_ref(), &rom);
+                        state_slots = None;
+                        show_hud_toast(&mut hud_toast, "ROM RELOADED");
+                    }
+                    Err(e) => {
+                        eprintln!("Failed to reload {}: {}", rom, e);
+                        show_hud_toast(&mut hud_toast, "RELOAD ERR");
+                    }
+                }
+            }
+        }
+
+        let running = !paused && state_slots.is_none() && settings_menu.is_none();
+        if running && !was_running {
+            // Playback decayed to silence while stopped; don't jump back in
+            nes.ramp_audio_in();
+        }
+        was_running = running;
+
+        // Fast-forward runs several frames per pass; the APU keeps its output
+        // at the real-time rate so the audio-synced pacer still waits one frame
+        let frames = if fast_forward { ff_speed } else { 1 };
+        let emulation_start = Instant::now();
+        for _ in 0..frames {
+            // Held, turbo and macro buttons are resolved once per emulated frame
+            if running && !paused {
+                for event in input.pending_events() {
+                    if let Err(e) = nes.apply_console_event(event) {
+                        eprintln!("Macro {} failed: {}", event.name(), e);
+                    }
+                }
+                nes.set_controller(input.next_frame());
+                session_play += nes.region().frame_duration();
+                if practice
+                    .as_mut()
+                    .is_some_and(|practice| practice.frame(&nes))
+                {
+                    show_hud_toast(&mut hud_toast, "ROOM SAVED");
+                }
+                if let Some(timer) = speedrun_timer.as_mut() {
+                    let event = timer.frame(&nes);
+                    report_timer_event(timer, event, &mut live_split, &nes, &mut hud_toast);
+                }
+            }
+
+            // Run emulation until frame is complete (or a watchpoint breaks)
+            let mut step_count = 0;
+            while running && !paused {
+                let frame_complete = nes.step();
+                if nes.take_watch_break() {
+                    paused = true;
+                    show_hud_toast(&mut hud_toast, "BREAK");
+                }
+                if frame_complete {
+                    break;
+                }
+                step_count += 1;
+
+                if step_count > 50000 {
+                    // Normal limit for frame completion
+                    break;
+                }
             }
         }
 
+        let emulation_time = emulation_start.elapsed();
+        for feedback in nes.take_controller_feedback() {
+            send_controller_feedback(&mut gamepads, feedback);
+        }
+
         _frame_count += 1;
         frames_since_save += 1;
 
-        // Save SRAM every 30 seconds (1800 frames at 60 FPS) - reduced frequency
-        if frames_since_save >= 1800 {
+        // Save SRAM every 30 seconds (1800 frames at 60 FPS) - reduced frequency;
+        // write-through mode saves from the core instead
+        if frames_since_save >= 1800 && sram_flush == SramFlush::Buffered {
             let _ = nes.save_sram(); // Only save if valid save data exists
             frames_since_save = 0;
         }
 
-        // Update texture with frame buffer
+        for write in nes.take_chr_write_log() {
+            println!("{}", describe_chr_write(&write));
+        }
+        for hit in nes.take_watch_hits() {
+            println!("[{}] {}", hit.source.clock, hit);
+        }
+        for entry in nes.take_interrupt_log() {
+            println!("[{}] {}", entry.clock, entry);
+        }
+
+        // The PPU frame is copied straight into the streaming texture's
+        // memory and overlays are drawn over it there, with no intermediate
+        // frame buffer.
+        let render_start = Instant::now();
         texture.with_lock(None, |buffer: &mut [u8], _pitch: usize| {
-            let frame_buffer = nes.get_frame_buffer();
-            if hud_toast.is_some() {
-                if hud_overlay_frame.len() != frame_buffer.len() {
-                    hud_overlay_frame.resize(frame_buffer.len(), 0);
+            let frame = &mut buffer[..256 * 240 * 3];
+            frame.copy_from_slice(nes.get_frame_buffer());
+            // Filter the picture only, so the overlays keep their colors
+            if let Some(blend) = frame_blend.as_mut() {
+                blend.apply_rgb24(frame);
+            }
+            if let Some(filter) = color_filter {
+                apply_color_filter_rgb24(frame, filter);
+            }
+            if scroll_view {
+                draw_scroll_view_rgb24(frame, 256, 240, |x, y| nes.pixel_provenance(x, y));
+            }
+            if let Some(scope) = nes.audio_scope() {
+                draw_audio_scope_rgb24(frame, 256, 240, scope);
+            }
+            if show_patterns {
+                // Redrawn every frame so CHR-RAM writes show up live
+                draw_pattern_tables_rgb24(frame, 256, 240, |addr| nes.peek_chr(addr));
+            }
+            if let Some(page) = io_page {
+                let registers = nes.io_registers();
+                draw_io_registers_rgb24(frame, 256, 240, &registers, page);
+            }
+            if show_stats {
+                let stats = nes.frame_stats();
+                draw_frame_stats_rgb24(frame, 256, 240, &stats, &nes.clock());
+                if let Some(leds) = nes.board_leds() {
+                    draw_board_leds_rgb24(frame, 256, 240, leds);
                 }
-                hud_overlay_frame.copy_from_slice(frame_buffer);
-                draw_hud_toast_rgb24(&mut hud_overlay_frame, 256, 240, &mut hud_toast);
-                buffer.copy_from_slice(&hud_overlay_frame);
-            } else {
-                buffer.copy_from_slice(frame_buffer);
             }
+            if show_frame_graph {
+                let budget = nes.region().frame_duration();
+                draw_frame_graph_rgb24(frame, 256, 240, &frame_graph, budget);
+            }
+            if let Some(slots) = &state_slots {
+                draw_state_picker_rgb24(frame, 256, 240, slots);
+            }
+            if let Some(timer) = &speedrun_timer {
+                draw_speedrun_timer_rgb24(frame, 256, 240, timer, nes.region().frame_duration());
+            }
+            if let Some(editor) = &patch_editor {
+                let rows: Vec<_> = nes
+                    .patches()
+                    .list()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &patch)| (patch, nes.patch_status(i)))
+                    .collect();
+                draw_patch_editor_rgb24(frame, 256, 240, &rows, editor);
+            }
+            if let Some(browser) = &rom_browser {
+                draw_rom_browser_rgb24(frame, 256, 240, browser);
+            }
+            if let Some(menu) = &settings_menu {
+                draw_settings_menu_rgb24(frame, 256, 240, menu, &settings);
+            }
+            draw_hud_toast_rgb24(frame, 256, 240, &mut hud_toast);
         })?;
 
         // Render the frame
         canvas.clear();
-        canvas.copy(&texture, None, None)?;
+        let (window_width, window_height) = canvas.output_size()?;
+        let layout = settings
+            .scale
+            .layout(settings.aspect, 256, 240, window_width, window_height);
+        let (x, y, w, h) = layout.dest;
+        let dest = Rect::new(x, y, w, h);
+        if settings.scale == ScaleFilter::SharpBilinear {
+            if prescaled.as_ref().map(|(scale, _)| *scale) != Some(layout.prescale) {
+                let (sx, sy) = layout.prescale;
+                sdl2::hint::set("SDL_RENDER_SCALE_QUALITY", "linear");
+                let target = texture_creator.create_texture_target(None, 256 * sx, 240 * sy);
+                sdl2::hint::set("SDL_RENDER_SCALE_QUALITY", "nearest");
+                prescaled = Some((layout.prescale, target?));
+            }
+            let (_, target) = prescaled.as_mut().expect("created above");
+            let mut copied = Ok(());
+            canvas.with_texture_canvas(target, |target_canvas| {
+                copied = target_canvas.copy(&texture, None, None);
+            })?;
+            copied?;
+            canvas.copy(target, None, dest)?;
+        } else {
+            canvas.copy(&texture, None, dest)?;
+        }
         canvas.present();
+        if let Some(probe) = latency_probe.as_mut() {
+            if let Some(latency) = probe.presented(nes.get_frame_buffer(), Instant::now()) {
+                let ms = latency.elapsed.as_millis();
+                println!("Input latency: {} ms ({} frames)", ms, latency.frames);
+                show_hud_toast(
+                    &mut hud_toast,
+                    format!("LATENCY {} MS {} FR", ms, latency.frames),
+                );
+            }
+        }
+        if fps_counter.frame(Instant::now()) {
+            let title = window_title(&game, dump.as_ref(), fps_counter.fps(), last_slot);
+            let _ = canvas.window_mut().set_title(&title);
+        }
+        frame_graph.push(FrameTiming {
+            emulation: emulation_time,
+            render: render_start.elapsed(),
+            audio_buffered: std::time::Duration::from_secs_f64(audio_ring.len() as f64 / 44100.0),
+        });
 
-        // Frame timing — accumulate ideal frame boundaries to self-correct
-        // for sleep overshoots and prevent timing drift.
-        let target = last_frame + frame_duration;
-        let now = Instant::now();
-        if now < target {
-            std::thread::sleep(target - now);
+        // Frame timing per --sync mode (vsync already blocked in present)
+        let delay = if !running || paused {
+            nes.region().frame_duration()
+        } else {
+            pacer.frame_delay(Instant::now(), audio_ring.len())
+        };
+        if !delay.is_zero() {
+            std::thread::sleep(delay);
         }
-        last_frame = target;
     }
 
     // Save SRAM before exit
     if let Err(e) = nes.save_sram() {
         eprintln!("Failed to save SRAM on exit: {}", e);
     }
+    record_play_session(&mut rom_history, &nes, session_play);
+    if resumable(&nes) {
+        if let Err(e) = nes.save_auto_state() {
+            eprintln!("Failed to save exit state: {}", e);
+        }
+    }
+    if let (Some(path), Some(log)) = (apu_log_path, nes.take_apu_log()) {
+        let cpu_clock = nes.region().cpu_clock_rate() as f64;
+        match log.save(path, cpu_clock) {
+            Ok(()) => println!("{} APU writes saved to {}", log.writes().len(), path),
+            Err(e) => eprintln!("Failed to save APU log: {}", e),
+        }
+    }
+    print_telemetry(&nes);
 
     Ok(())
 }
 
-fn map_key_to_controller(key: Keycode, current: u8) -> u8 {
-    match key {
-        Keycode::X => current | 0x02,      // B (bit 1)
-        Keycode::Z => current | 0x01,      // A (bit 0)
-        Keycode::Space => current | 0x04,  // Select (bit 2)
-        Keycode::Return => current | 0x08, // Start (bit 3)
-        Keycode::Up => current | 0x10,     // Up (bit 4)
-        Keycode::Down => current | 0x20,   // Down (bit 5)
-        Keycode::Left => current | 0x40,   // Left (bit 6)
-        Keycode::Right => current | 0x80,  // Right (bit 7)
-        _ => current,
+/// Run the basic player (`frontend::PlayerApp`) in a winit window instead
+/// of the SDL frontend; needs the `winit-frontend` feature.
+#[cfg(feature = "winit-frontend")]
+fn run_winit_frontend(nes: Nes) -> Result<(), Box<dyn std::error::Error>> {
+    use nes_emulator::frontend::{Frontend, PlayerApp, WinitFrontend};
+
+    let mut app = PlayerApp::new(nes);
+    WinitFrontend::new(3).run(&mut app)?;
+    print_telemetry(app.nes());
+    Ok(())
+}
+
+#[cfg(not(feature = "winit-frontend"))]
+fn run_winit_frontend(_nes: Nes) -> Result<(), Box<dyn std::error::Error>> {
+    Err("--frontend winit needs a build with --features winit-frontend".into())
+}
+
+fn print_telemetry(nes: &Nes) {
+    if !nes.telemetry().is_empty() {
+        eprintln!("Emulation problems this session:");
+        for line in nes.telemetry().report() {
+            eprintln!("  {}", line);
+        }
     }
 }
 
-fn unmap_key_from_controller(key: Keycode, current: u8) -> u8 {
+fn turbo_buttons_for_key(key: Keycode) -> Option<ControllerState> {
     match key {
-        Keycode::X => current & !0x02,      // B (bit 1)
-        Keycode::Z => current & !0x01,      // A (bit 0)
-        Keycode::Space => current & !0x04,  // Select (bit 2)
-        Keycode::Return => current & !0x08, // Start (bit 3)
-        Keycode::Up => current & !0x10,     // Up (bit 4)
-        Keycode::Down => current & !0x20,   // Down (bit 5)
-        Keycode::Left => current & !0x40,   // Left (bit 6)
-        Keycode::Right => current & !0x80,  // Right (bit 7)
-        _ => current,
+        Keycode::A => Some(ControllerState::A), // Turbo A, above Z
+        Keycode::S => Some(ControllerState::B), // Turbo B, above X
+        _ => None,
     }
 }
 
 struct NesAudioCallback {
     ring: Arc<SpscRingBuffer>,
+    /// Percent, set from the settings menu.
+    volume: Arc<AtomicU8>,
     phase: f32,
 }
 
@@ -323,13 +2011,17 @@ impl AudioCallback for NesAudioCallback {
 
     fn callback(&mut self, out: &mut [f32]) {
         let read = self.ring.pop_slice(out);
+        let gain = self.volume.load(Ordering::Relaxed) as f32 / 100.0;
+        for sample in out[..read].iter_mut() {
+            *sample *= gain;
+        }
         if read > 0 {
             self.phase = out[read - 1];
         }
-        // Underrun: rapid decay to silence to minimize discontinuity artifacts.
-        // 0.9 factor: after 20 samples (~0.5ms) signal is < 12% amplitude.
+        // Underrun or pause: decay to silence instead of dropping to 0.
+        // 0.99 factor: after 220 samples (~5ms) signal is < 12% amplitude.
         for sample in out[read..].iter_mut() {
-            self.phase *= 0.9;
+            self.phase *= 0.99;
             *sample = self.phase;
         }
     }
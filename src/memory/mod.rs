@@ -1,3 +1,5 @@
+use crate::power_on::PowerOnState;
+
 pub struct Memory {
     pub(crate) ram: [u8; 0x800],
 }
@@ -23,6 +25,11 @@ impl Memory {
         }
     }
 
+    /// Fill RAM with power-on garbage (`Clean` zeroes it).
+    pub fn apply_power_on_state(&mut self, state: PowerOnState) {
+        state.fill(&mut self.ram, 0, 0);
+    }
+
     // Save state methods
     pub fn get_ram(&self) -> [u8; 0x800] {
         self.ram
@@ -0,0 +1,86 @@
+// Raw memory dumps for post-mortem analysis of headless runs:
+// `--dump-ram ram.bin` writes CPU RAM then PRG-RAM, `--dump-vram vram.bin`
+// writes the 2KB of nametable RAM, palette RAM and OAM, at exit or with
+// `@frame=N` once N frames have run (the `--assert` syntax).
+use crate::Nes;
+
+/// What a dump file holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpKind {
+    /// $0000-$07FF, then the cartridge's PRG-RAM if it has any.
+    Ram,
+    /// Nametable RAM (2048 bytes), palette RAM (32), OAM (256).
+    Vram,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryDump {
+    pub kind: DumpKind,
+    pub path: String,
+    /// Frames run before dumping; `None` dumps when the run ends.
+    pub frame: Option<usize>,
+}
+
+impl MemoryDump {
+    /// Parse `<file>[@frame=<N>]`.
+    pub fn parse(kind: DumpKind, spec: &str) -> Option<Self> {
+        let (path, frame) = match spec.rsplit_once("@frame=") {
+            Some((path, frame)) => (path, Some(frame.trim().parse().ok()?)),
+            None => (spec, None),
+        };
+        if path.is_empty() {
+            return None;
+        }
+        Some(MemoryDump {
+            kind,
+            path: path.to_string(),
+            frame,
+        })
+    }
+
+    /// The bytes to write, in the layout documented on `DumpKind`.
+    pub fn contents(&self, nes: &Nes) -> Vec<u8> {
+        match self.kind {
+            DumpKind::Ram => {
+                let mut data = nes.ram().to_vec();
+                data.extend_from_slice(nes.prg_ram().unwrap_or_default());
+                data
+            }
+            DumpKind::Vram => {
+                let mut data = nes.ppu_nametables();
+                data.extend_from_slice(&nes.ppu_palette());
+                data.extend_from_slice(&nes.ppu_oam());
+                data
+            }
+        }
+    }
+
+    pub fn write(&self, nes: &Nes) -> std::io::Result<usize> {
+        let data = self.contents(nes);
+        std::fs::write(&self.path, &data)?;
+        Ok(data.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_specs_and_lays_out_contents() {
+        let at_exit = MemoryDump::parse(DumpKind::Ram, "out/ram.bin").unwrap();
+        assert_eq!(at_exit.frame, None);
+        let at_frame = MemoryDump::parse(DumpKind::Vram, "vram.bin@frame=600").unwrap();
+        assert_eq!(at_frame.path, "vram.bin");
+        assert_eq!(at_frame.frame, Some(600));
+        assert!(MemoryDump::parse(DumpKind::Ram, "ram.bin@frame=x").is_none());
+        assert!(MemoryDump::parse(DumpKind::Ram, "@frame=1").is_none());
+
+        let mut nes = Nes::new();
+        nes.ram_mut()[0x7FF] = 0x5A;
+        let ram = at_exit.contents(&nes);
+        assert_eq!(ram.len(), 0x800);
+        assert_eq!(ram[0x7FF], 0x5A);
+        assert_eq!(at_frame.contents(&nes).len(), 2048 + 32 + 256);
+    }
+}
@@ -0,0 +1,159 @@
+use std::path::Path;
+
+/// Tile index to character map, read from a ROM-hacking style table file
+/// with one `<hex tile>=<char>` per line (`#` starts a comment).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CharTable {
+    chars: [Option<char>; 256],
+}
+
+impl CharTable {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut chars = [None; 256];
+        for (number, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let parsed = line.split_once('=').and_then(|(tile, ch)| {
+                let tile = u8::from_str_radix(tile.trim(), 16).ok()?;
+                let mut rest = ch.chars();
+                match (rest.next(), rest.next()) {
+                    (Some(ch), None) => Some((tile, ch)),
+                    _ => None,
+                }
+            });
+            let (tile, ch) =
+                parsed.ok_or_else(|| format!("line {}: expected <hex>=<char>", number + 1))?;
+            chars[tile as usize] = Some(ch);
+        }
+        Ok(Self { chars })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self::parse(&std::fs::read_to_string(path)?)?)
+    }
+
+    /// Printable ASCII tiles map to themselves, as in most test ROM fonts.
+    pub fn ascii() -> Self {
+        let mut chars = [None; 256];
+        for (tile, ch) in chars.iter_mut().enumerate().take(0x7F).skip(0x20) {
+            *ch = Some(tile as u8 as char);
+        }
+        Self { chars }
+    }
+
+    /// Per-game table `<dir>/<CRC32>.tbl`, keyed by `Nes::rom_crc32`.
+    pub fn for_rom(dir: impl AsRef<Path>, crc32: u32) -> Option<Self> {
+        Self::load(dir.as_ref().join(format!("{:08X}.tbl", crc32))).ok()
+    }
+
+    pub fn get(&self, tile: u8) -> Option<char> {
+        self.chars[tile as usize]
+    }
+}
+
+/// A run of text on screen, positioned in tiles from the top left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScreenText {
+    pub row: usize,
+    pub col: usize,
+    pub text: String,
+}
+
+/// Split 32-tile rows into text runs. Unmapped tiles and whitespace are
+/// blanks; a single blank stays inside a run (between words), two end it.
+pub fn text_regions(tiles: &[u8], table: &CharTable) -> Vec<ScreenText> {
+    let mut regions = Vec::new();
+    for (row, line) in tiles.chunks(32).enumerate() {
+        let chars: Vec<Option<char>> = line
+            .iter()
+            .map(|&tile| table.get(tile).filter(|ch| !ch.is_whitespace()))
+            .collect();
+        let mut col = 0;
+        while col < chars.len() {
+            if chars[col].is_none() {
+                col += 1;
+                continue;
+            }
+            let start = col;
+            let mut text = String::new();
+            while col < chars.len() {
+                match chars[col] {
+                    Some(ch) => text.push(ch),
+                    None if chars.get(col + 1).copied().flatten().is_some() => text.push(' '),
+                    None => break,
+                }
+                col += 1;
+            }
+            regions.push(ScreenText {
+                row,
+                col: start,
+                text,
+            });
+        }
+    }
+    regions
+}
+
+/// Nametable tiles as text, 32 per line. Without a table each tile is
+/// printed as two hex digits; with one, unmapped tiles become `.` and
+/// trailing blanks are trimmed so the output can be compared as-is.
+pub fn nametable_text(tiles: &[u8], table: Option<&CharTable>) -> String {
+    let mut out = String::new();
+    for row in tiles.chunks(32) {
+        let line = match table {
+            Some(table) => {
+                let line: String = row.iter().map(|&t| table.get(t).unwrap_or('.')).collect();
+                line.trim_end().to_string()
+            }
+            None => row
+                .iter()
+                .map(|t| format!("{:02X}", t))
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_tiles_through_a_table() {
+        let table = CharTable::parse("# font\n00= \n0A=P\n0B=A\n0C=S\n3D==\n").unwrap();
+        let mut tiles = vec![0u8; 64];
+        tiles[..6].copy_from_slice(&[0x0A, 0x0B, 0x0C, 0x0C, 0x3D, 0x99]);
+        assert_eq!(nametable_text(&tiles, Some(&table)), "PASS=.\n\n");
+
+        assert!(CharTable::parse("0A=PA").is_err());
+        assert!(CharTable::parse("ZZ=A").is_err());
+        assert_eq!(CharTable::ascii().get(b'O'), Some('O'));
+        assert_eq!(CharTable::ascii().get(0x80), None);
+    }
+
+    #[test]
+    fn splits_rows_into_text_runs() {
+        let table = CharTable::ascii();
+        let mut tiles = vec![0u8; 64];
+        tiles[2..13].copy_from_slice(b"HP 12  MP 3");
+        tiles[40..45].copy_from_slice(b"YES ?");
+        let regions = text_regions(&tiles, &table);
+        let found: Vec<(usize, usize, &str)> = regions
+            .iter()
+            .map(|r| (r.row, r.col, r.text.as_str()))
+            .collect();
+        assert_eq!(found, [(0, 2, "HP 12"), (0, 9, "MP 3"), (1, 8, "YES ?")]);
+    }
+
+    #[test]
+    fn prints_hex_without_a_table() {
+        let tiles: Vec<u8> = (0..32).collect();
+        let text = nametable_text(&tiles, None);
+        assert!(text.starts_with("00 01 02"));
+        assert!(text.ends_with("1E 1F\n"));
+    }
+}
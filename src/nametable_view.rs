@@ -0,0 +1,98 @@
+use crate::ppu::nes_color_rgb;
+
+/// Size of the four-nametable view: 2x2 screens.
+pub const VIEW_WIDTH: usize = 512;
+pub const VIEW_HEIGHT: usize = 480;
+
+const VIEWPORT_COLOR: [u8; 3] = [0xFF, 0x00, 0xFF];
+
+/// Render logical nametables 0-3 ($2000, $2400, $2800, $2C00) in a 2x2
+/// grid as RGB24, each tile colored by its attribute palette.
+/// `peek_nt(nt, offset)` reads a nametable byte after mirroring and
+/// `bg_table` is the background pattern table base ($0000 or $1000).
+pub fn render_nametables_rgb24(
+    peek_nt: impl Fn(usize, usize) -> u8,
+    peek_chr: impl Fn(u16) -> u8,
+    palette: &[u8; 32],
+    bg_table: u16,
+) -> Vec<u8> {
+    let mut view = vec![0u8; VIEW_WIDTH * VIEW_HEIGHT * 3];
+    for nt in 0..4 {
+        let origin_x = (nt & 1) * 256;
+        let origin_y = (nt >> 1) * 240;
+        for tile_y in 0..30 {
+            for tile_x in 0..32 {
+                let tile = peek_nt(nt, tile_y * 32 + tile_x) as u16;
+                let attr = peek_nt(nt, 0x3C0 + (tile_y / 4) * 8 + tile_x / 4);
+                let shift = ((tile_y & 2) << 1) | (tile_x & 2);
+                let group = ((attr >> shift) & 3) as usize * 4;
+                let base = bg_table + tile * 16;
+                for row in 0..8 {
+                    let low = peek_chr(base + row as u16);
+                    let high = peek_chr(base + row as u16 + 8);
+                    for col in 0..8 {
+                        let bit = 7 - col;
+                        let pixel = (((low >> bit) & 1) | (((high >> bit) & 1) << 1)) as usize;
+                        // Color 0 of every palette is the shared backdrop
+                        let index = if pixel == 0 { 0 } else { group + pixel };
+                        let x = origin_x + tile_x * 8 + col;
+                        let y = origin_y + tile_y * 8 + row;
+                        let idx = (y * VIEW_WIDTH + x) * 3;
+                        view[idx..idx + 3].copy_from_slice(&nes_color_rgb(palette[index]));
+                    }
+                }
+            }
+        }
+    }
+    view
+}
+
+/// Outline the 256x240 area shown at scroll position (`scroll_x`,
+/// `scroll_y`) in view pixels, wrapping at the edges like the PPU does.
+pub fn draw_viewport_rgb24(view: &mut [u8], scroll_x: usize, scroll_y: usize) {
+    let mut plot = |x: usize, y: usize| {
+        let idx = ((y % VIEW_HEIGHT) * VIEW_WIDTH + x % VIEW_WIDTH) * 3;
+        view[idx..idx + 3].copy_from_slice(&VIEWPORT_COLOR);
+    };
+    for dx in 0..256 {
+        plot(scroll_x + dx, scroll_y);
+        plot(scroll_x + dx, scroll_y + 239);
+    }
+    for dy in 0..240 {
+        plot(scroll_x, scroll_y + dy);
+        plot(scroll_x + 255, scroll_y + dy);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tiles_use_their_attribute_palette() {
+        // Nametable 3, tile (2, 0) uses tile 1 and attribute bits 2-3 = palette 2
+        let peek_nt = |nt: usize, offset: usize| match (nt, offset) {
+            (3, 2) => 1,
+            (3, 0x3C0) => 0b0000_1000,
+            _ => 0,
+        };
+        let peek_chr = |addr: u16| if addr == 0x1010 { 0x80 } else { 0 };
+        let mut palette = [0x0F; 32];
+        palette[9] = 0x16;
+        let mut view = render_nametables_rgb24(peek_nt, peek_chr, &palette, 0x1000);
+
+        let pixel = |view: &[u8], x: usize, y: usize| {
+            let idx = (y * VIEW_WIDTH + x) * 3;
+            [view[idx], view[idx + 1], view[idx + 2]]
+        };
+        assert_eq!(pixel(&view, 256 + 16, 240), nes_color_rgb(0x16));
+        assert_eq!(pixel(&view, 256 + 17, 240), nes_color_rgb(0x0F));
+
+        // A viewport scrolled past the right edge wraps to the left
+        draw_viewport_rgb24(&mut view, 400, 0);
+        assert_eq!(pixel(&view, 400, 100), VIEWPORT_COLOR);
+        assert_eq!(pixel(&view, (400 + 255) % 512, 100), VIEWPORT_COLOR);
+        assert_eq!(pixel(&view, 10, 239), VIEWPORT_COLOR);
+        assert_ne!(pixel(&view, 10, 100), VIEWPORT_COLOR);
+    }
+}
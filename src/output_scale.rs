@@ -0,0 +1,187 @@
+// How the 256x240 picture is fitted to the window. Stretching it with
+// nearest-neighbour gives uneven pixel widths at non-integer sizes, which
+// shimmer as the picture scrolls; integer scaling and sharp bilinear avoid
+// that, by letterboxing or by blending only the pixel edges. The aspect ratio
+// is separate: NTSC pixels were 8:7, wider than tall.
+
+/// Output scaling filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScaleFilter {
+    /// Stretch to the window, nearest-neighbour.
+    #[default]
+    Nearest,
+    /// Largest whole multiple that fits, centered with black borders.
+    Integer,
+    /// Nearest-neighbour to the largest whole multiple on each axis, then
+    /// bilinear the rest of the way: sharp pixels, even widths.
+    SharpBilinear,
+}
+
+impl ScaleFilter {
+    pub const ALL: [ScaleFilter; 3] = [
+        ScaleFilter::Nearest,
+        ScaleFilter::Integer,
+        ScaleFilter::SharpBilinear,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|filter| filter.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ScaleFilter::Nearest => "nearest",
+            ScaleFilter::Integer => "integer",
+            ScaleFilter::SharpBilinear => "sharp-bilinear",
+        }
+    }
+
+    pub fn cycle(self) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|&filter| filter == self)
+            .unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// Where a `width`x`height` picture goes in a `window_width`x
+    /// `window_height` window.
+    pub fn layout(
+        self,
+        aspect: AspectRatio,
+        width: u32,
+        height: u32,
+        window_width: u32,
+        window_height: u32,
+    ) -> OutputLayout {
+        let mut dest = match self {
+            ScaleFilter::Integer => {
+                let scale = (window_width / width).min(window_height / height).max(1);
+                centered(width * scale, height * scale, window_width, window_height)
+            }
+            _ => (0, 0, window_width, window_height),
+        };
+        // Keep the height (and so an integer scale's even lines), narrowing
+        // only when the width will not fit
+        if let Some(ratio) = aspect.ratio() {
+            let mut h = dest.3;
+            let mut w = (h as f64 * ratio).round() as u32;
+            if w > window_width {
+                w = window_width;
+                h = (w as f64 / ratio).round() as u32;
+            }
+            dest = centered(w, h, window_width, window_height);
+        }
+        let prescale = match self {
+            ScaleFilter::SharpBilinear => ((dest.2 / width).max(1), (dest.3 / height).max(1)),
+            _ => (1, 1),
+        };
+        OutputLayout { prescale, dest }
+    }
+}
+
+fn centered(w: u32, h: u32, window_width: u32, window_height: u32) -> (i32, i32, u32, u32) {
+    (
+        (window_width as i32 - w as i32) / 2,
+        (window_height as i32 - h as i32) / 2,
+        w,
+        h,
+    )
+}
+
+/// Shape the picture is shown at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AspectRatio {
+    /// Fill the window.
+    #[default]
+    Stretch,
+    /// Square pixels, 16:15.
+    Square,
+    /// 8:7 pixels, as an NTSC TV showed them.
+    Ntsc,
+    /// The whole picture at 4:3.
+    Tv,
+}
+
+impl AspectRatio {
+    pub const ALL: [AspectRatio; 4] = [
+        AspectRatio::Stretch,
+        AspectRatio::Square,
+        AspectRatio::Ntsc,
+        AspectRatio::Tv,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|aspect| aspect.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AspectRatio::Stretch => "stretch",
+            AspectRatio::Square => "square",
+            AspectRatio::Ntsc => "8:7",
+            AspectRatio::Tv => "4:3",
+        }
+    }
+
+    /// Width over height of the 256x240 picture; `None` to stretch.
+    pub fn ratio(self) -> Option<f64> {
+        match self {
+            AspectRatio::Stretch => None,
+            AspectRatio::Square => Some(256.0 / 240.0),
+            AspectRatio::Ntsc => Some(256.0 * 8.0 / 7.0 / 240.0),
+            AspectRatio::Tv => Some(4.0 / 3.0),
+        }
+    }
+}
+
+/// A filter's plan for one window size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputLayout {
+    /// Whole-number nearest-neighbour scale per axis before the final
+    /// bilinear pass; (1, 1) means draw the picture directly.
+    pub prescale: (u32, u32),
+    /// Destination rectangle in the window: x, y, width, height.
+    pub dest: (i32, i32, u32, u32),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lays_out_each_filter() {
+        assert_eq!(
+            ScaleFilter::from_name("sharp-bilinear"),
+            Some(ScaleFilter::SharpBilinear)
+        );
+        assert_eq!(ScaleFilter::SharpBilinear.cycle(), ScaleFilter::Nearest);
+
+        // 1000x720 is 3.9x by 3x
+        let stretch = AspectRatio::Stretch;
+        let nearest = ScaleFilter::Nearest.layout(stretch, 256, 240, 1000, 720);
+        assert_eq!(nearest.dest, (0, 0, 1000, 720));
+        let integer = ScaleFilter::Integer.layout(stretch, 256, 240, 1000, 720);
+        assert_eq!(integer.dest, (116, 0, 768, 720));
+        let sharp = ScaleFilter::SharpBilinear.layout(stretch, 256, 240, 1000, 720);
+        assert_eq!(sharp.prescale, (3, 3));
+        assert_eq!(sharp.dest, (0, 0, 1000, 720));
+
+        // Smaller than the picture: never scale below 1x
+        let tiny = ScaleFilter::Integer.layout(stretch, 256, 240, 200, 200);
+        assert_eq!(tiny.dest, (-28, -20, 256, 240));
+    }
+
+    #[test]
+    fn aspect_ratio_keeps_the_height() {
+        assert_eq!(AspectRatio::from_name("8:7"), Some(AspectRatio::Ntsc));
+        // 8:7 pixels make 3x integer scale 878 wide
+        let integer = ScaleFilter::Integer.layout(AspectRatio::Ntsc, 256, 240, 1000, 720);
+        assert_eq!(integer.dest, (61, 0, 878, 720));
+        // Too narrow a window for 4:3 at full height: letterboxed
+        let tv = ScaleFilter::Nearest.layout(AspectRatio::Tv, 256, 240, 800, 720);
+        assert_eq!(tv.dest, (0, 60, 800, 600));
+        let sharp = ScaleFilter::SharpBilinear.layout(AspectRatio::Tv, 256, 240, 800, 720);
+        assert_eq!(sharp.prescale, (3, 2));
+    }
+}
@@ -0,0 +1,359 @@
+// Live memory patches for the debugger. ROM patches work like a Game Genie:
+// CPU reads of a PRG address return the patch value, optionally only while
+// the ROM holds a compare byte there (so a bank-switched game is patched in
+// one bank only). RAM freezes rewrite a CPU RAM or cartridge RAM byte every
+// frame; they are kept as `CheatManager` entries and applied like cheats.
+// Patches are typed as `ADDR:VV`, `ADDR?CC:VV` or a Game Genie code.
+use std::fmt;
+
+use crate::cheat::{CheatEntry, CheatManager};
+use crate::hud_toast::draw_hud_label_rgb24;
+
+const GENIE_LETTERS: &[u8; 16] = b"APZLGITYEOXUKSVN";
+
+const PANEL_COLOR: [u8; 3] = [0x10, 0x10, 0x10];
+const TITLE_COLOR: [u8; 3] = [0xF8, 0xB8, 0x00];
+const TEXT_COLOR: [u8; 3] = [0xFF, 0xFF, 0xFF];
+const ACTIVE_COLOR: [u8; 3] = [0x58, 0xD8, 0x54];
+const IDLE_COLOR: [u8; 3] = [0x80, 0x80, 0x80];
+const LINE_HEIGHT: usize = 9;
+/// Patch rows shown at once; the list scrolls to keep the selection visible.
+const VISIBLE_ROWS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Patch {
+    pub addr: u16,
+    pub value: u8,
+    /// ROM patches only: the byte the ROM must hold for the patch to apply.
+    pub compare: Option<u8>,
+    pub enabled: bool,
+}
+
+/// Whether a patch is doing anything right now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchStatus {
+    Disabled,
+    /// A RAM freeze, rewritten every frame.
+    Frozen,
+    /// A ROM patch the CPU reads instead of the ROM byte.
+    Active,
+    /// A ROM patch whose compare byte doesn't match the bank mapped in now.
+    Waiting,
+}
+
+impl PatchStatus {
+    pub fn name(self) -> &'static str {
+        match self {
+            PatchStatus::Disabled => "OFF",
+            PatchStatus::Frozen => "FROZEN",
+            PatchStatus::Active => "ACTIVE",
+            PatchStatus::Waiting => "NO MATCH",
+        }
+    }
+}
+
+impl Patch {
+    pub fn new(addr: u16, value: u8, compare: Option<u8>) -> Self {
+        Patch {
+            addr,
+            value,
+            compare,
+            enabled: true,
+        }
+    }
+
+    /// PRG ROM patches sit at $8000-$FFFF; anything else is a RAM freeze.
+    pub fn is_rom(&self) -> bool {
+        self.addr >= 0x8000
+    }
+
+    /// A Game Genie code, or raw `ADDR:VV` / `ADDR?CC:VV` in hex (`$`
+    /// optional). Freezes must target CPU RAM ($0000-$1FFF) or cartridge
+    /// RAM ($6000-$7FFF) and can't have a compare byte.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let spec = spec.trim();
+        if let Some(patch) = decode_game_genie(spec) {
+            return Some(patch);
+        }
+        let (target, value) = spec.split_once(':')?;
+        let value = u8::from_str_radix(value.trim(), 16).ok()?;
+        let (addr, compare) = match target.split_once('?') {
+            Some((addr, compare)) => (addr, Some(u8::from_str_radix(compare.trim(), 16).ok()?)),
+            None => (target, None),
+        };
+        let addr = u16::from_str_radix(addr.trim().trim_start_matches('$'), 16).ok()?;
+        let patch = Patch::new(addr, value, compare);
+        let freezable = addr < 0x2000 || (0x6000..0x8000).contains(&addr);
+        if patch.is_rom() || (freezable && compare.is_none()) {
+            Some(patch)
+        } else {
+            None
+        }
+    }
+
+    /// The patch as a Game Genie code; `None` for RAM freezes.
+    pub fn game_genie(&self) -> Option<String> {
+        if self.is_rom() {
+            Some(encode_game_genie(self.addr, self.value, self.compare))
+        } else {
+            None
+        }
+    }
+}
+
+/// `91D9:AD` or `91D9?A9:AD`.
+impl fmt::Display for Patch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.compare {
+            Some(compare) => write!(f, "{:04X}?{:02X}:{:02X}", self.addr, compare, self.value),
+            None => write!(f, "{:04X}:{:02X}", self.addr, self.value),
+        }
+    }
+}
+
+/// A six or eight letter Game Genie code (any case).
+pub fn decode_game_genie(code: &str) -> Option<Patch> {
+    let n = code
+        .bytes()
+        .map(|c| {
+            GENIE_LETTERS
+                .iter()
+                .position(|&l| l == c.to_ascii_uppercase())
+                .map(|i| i as u16)
+        })
+        .collect::<Option<Vec<u16>>>()?;
+    if n.len() != 6 && n.len() != 8 {
+        return None;
+    }
+    let addr = 0x8000
+        | ((n[3] & 7) << 12)
+        | ((n[5] & 7) << 8)
+        | ((n[4] & 8) << 8)
+        | ((n[2] & 7) << 4)
+        | ((n[1] & 8) << 4)
+        | (n[4] & 7)
+        | (n[3] & 8);
+    let value_low = if n.len() == 8 { n[7] } else { n[5] };
+    let value = ((n[1] & 7) << 4) | ((n[0] & 8) << 4) | (n[0] & 7) | (value_low & 8);
+    let compare = (n.len() == 8)
+        .then(|| (((n[7] & 7) << 4) | ((n[6] & 8) << 4) | (n[6] & 7) | (n[5] & 8)) as u8);
+    Some(Patch::new(addr, value as u8, compare))
+}
+
+/// The code for a ROM patch: six letters, or eight with a compare byte.
+/// Bit 15 of `addr` is implied by the code.
+pub fn encode_game_genie(addr: u16, value: u8, compare: Option<u8>) -> String {
+    let (a, v) = (addr & 0x7FFF, value as u16);
+    let c = compare.unwrap_or(0) as u16;
+    let mut n = vec![
+        (v & 7) | ((v >> 4) & 8),
+        ((v >> 4) & 7) | ((a >> 4) & 8),
+        ((a >> 4) & 7) | if compare.is_some() { 8 } else { 0 },
+        ((a >> 12) & 7) | (a & 8),
+        (a & 7) | ((a >> 8) & 8),
+        ((a >> 8) & 7) | if compare.is_some() { c & 8 } else { v & 8 },
+    ];
+    if compare.is_some() {
+        n.push((c & 7) | ((c >> 4) & 8));
+        n.push(((c >> 4) & 7) | (v & 8));
+    }
+    n.iter()
+        .map(|&i| GENIE_LETTERS[i as usize] as char)
+        .collect()
+}
+
+/// The patches the bus applies, in the order they were added.
+#[derive(Debug, Clone, Default)]
+pub struct PatchList {
+    patches: Vec<Patch>,
+    freezes: CheatManager, // The RAM freezes, rebuilt on every change
+}
+
+impl PatchList {
+    pub fn add(&mut self, patch: Patch) -> usize {
+        self.patches.push(patch);
+        self.sync_freezes();
+        self.patches.len() - 1
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Patch> {
+        let patch = (index < self.patches.len()).then(|| self.patches.remove(index));
+        self.sync_freezes();
+        patch
+    }
+
+    pub fn set_enabled(&mut self, index: usize, enabled: bool) {
+        if let Some(patch) = self.patches.get_mut(index) {
+            patch.enabled = enabled;
+        }
+        self.sync_freezes();
+    }
+
+    fn sync_freezes(&mut self) {
+        self.freezes.entries = self
+            .patches
+            .iter()
+            .filter_map(CheatEntry::from_freeze)
+            .collect();
+    }
+
+    pub fn list(&self) -> &[Patch] {
+        &self.patches
+    }
+
+    /// The byte a CPU read of PRG address `addr` returns, given the ROM's
+    /// byte there. The first matching patch wins.
+    pub fn patch_rom(&self, addr: u16, rom_value: u8) -> u8 {
+        self.patches
+            .iter()
+            .find(|p| p.enabled && p.addr == addr && p.compare.is_none_or(|c| c == rom_value))
+            .map_or(rom_value, |p| p.value)
+    }
+
+    /// The RAM freezes as cheats, disabled ones included.
+    pub fn freezes(&self) -> &CheatManager {
+        &self.freezes
+    }
+
+    /// Status of patch `index`; `rom_value` is the unpatched byte the CPU
+    /// would read at its address.
+    pub fn status(&self, index: usize, rom_value: u8) -> PatchStatus {
+        match self.patches.get(index) {
+            Some(p) if !p.enabled => PatchStatus::Disabled,
+            Some(p) if !p.is_rom() => PatchStatus::Frozen,
+            Some(p) if p.compare.is_none_or(|c| c == rom_value) => PatchStatus::Active,
+            Some(_) => PatchStatus::Waiting,
+            None => PatchStatus::Disabled,
+        }
+    }
+
+    /// Game Genie codes for the ROM patches, one per line; RAM freezes have
+    /// no code and are left out.
+    pub fn export_game_genie(&self) -> Vec<String> {
+        self.patches.iter().filter_map(Patch::game_genie).collect()
+    }
+}
+
+/// Text typed into the patch editor and the selected row.
+#[derive(Debug, Clone, Default)]
+pub struct PatchEditor {
+    pub input: String,
+    pub selected: usize,
+}
+
+impl PatchEditor {
+    /// Typed characters; only those a patch spec can contain are kept.
+    pub fn type_text(&mut self, text: &str) {
+        self.input.extend(
+            text.chars()
+                .filter(|c| c.is_ascii_alphanumeric() || matches!(c, ':' | '?' | '$'))
+                .map(|c| c.to_ascii_uppercase()),
+        );
+        self.input.truncate(16);
+    }
+
+    pub fn move_selection(&mut self, delta: isize, len: usize) {
+        if len > 0 {
+            self.selected = self.selected.saturating_add_signed(delta).min(len - 1);
+        }
+    }
+}
+
+/// Draw the patch list over the top of the frame: one row per patch with
+/// its code and status, then the line being typed.
+pub fn draw_patch_editor_rgb24(
+    frame: &mut [u8],
+    width: usize,
+    height: usize,
+    rows: &[(Patch, PatchStatus)],
+    editor: &PatchEditor,
+) {
+    let shown = rows.len().min(VISIBLE_ROWS);
+    let panel_h = (shown + 3) * LINE_HEIGHT + 2;
+    if width < 256 || height < panel_h || frame.len() < width * height * 3 {
+        return;
+    }
+    frame[..panel_h * width * 3]
+        .chunks_exact_mut(3)
+        .for_each(|pixel| pixel.copy_from_slice(&PANEL_COLOR));
+
+    let label = |frame: &mut [u8], x: usize, row: usize, text: &str, color: [u8; 3]| {
+        draw_hud_label_rgb24(frame, width, height, x, 2 + row * LINE_HEIGHT, text, color);
+    };
+    label(
+        frame,
+        2,
+        0,
+        "PATCHES  ENTER:ADD/TOGGLE DEL:REMOVE",
+        TITLE_COLOR,
+    );
+    let first = (editor.selected + 1).saturating_sub(VISIBLE_ROWS);
+    for (row, (i, (patch, status))) in rows.iter().enumerate().skip(first).take(shown).enumerate() {
+        let marker = if i == editor.selected { ">" } else { " " };
+        let code = patch.game_genie().unwrap_or_default();
+        let text = format!("{} {:<10} {:<8}", marker, patch.to_string(), code);
+        label(frame, 2, row + 1, &text, TEXT_COLOR);
+        let color = match status {
+            PatchStatus::Active | PatchStatus::Frozen => ACTIVE_COLOR,
+            _ => IDLE_COLOR,
+        };
+        label(frame, 2 + 22 * 6, row + 1, status.name(), color);
+    }
+    label(
+        frame,
+        2,
+        shown + 2,
+        &format!("+ {}", editor.input),
+        TEXT_COLOR,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_and_encodes_game_genie_codes() {
+        // Super Mario Bros. infinite lives
+        let patch = decode_game_genie("sxiopo").unwrap();
+        assert_eq!(
+            (patch.addr, patch.value, patch.compare),
+            (0x91D9, 0xAD, None)
+        );
+        assert_eq!(patch.game_genie().as_deref(), Some("SXIOPO"));
+
+        let patch = Patch::parse("$D1DD?05:00").unwrap();
+        let code = patch.game_genie().unwrap();
+        assert_eq!(code.len(), 8);
+        assert_eq!(decode_game_genie(&code), Some(patch));
+        assert_eq!(patch.to_string(), "D1DD?05:00");
+
+        assert_eq!(Patch::parse("075A:09").map(|p| p.is_rom()), Some(false));
+        assert_eq!(Patch::parse("075A?01:09"), None);
+        assert_eq!(Patch::parse("4015:00"), None);
+        assert_eq!(Patch::parse("SXIOP"), None);
+    }
+
+    #[test]
+    fn patches_rom_reads_and_reports_status() {
+        let mut list = PatchList::default();
+        list.add(Patch::parse("8000?A9:EA").unwrap());
+        list.add(Patch::parse("075A:09").unwrap());
+        assert_eq!(list.patch_rom(0x8000, 0xA9), 0xEA);
+        assert_eq!(list.patch_rom(0x8000, 0x4C), 0x4C);
+        assert_eq!(list.status(0, 0xA9), PatchStatus::Active);
+        assert_eq!(list.status(0, 0x4C), PatchStatus::Waiting);
+        assert_eq!(list.status(1, 0), PatchStatus::Frozen);
+        assert_eq!(list.freezes().entries.len(), 1);
+        assert_eq!(list.export_game_genie().len(), 1);
+
+        list.set_enabled(0, false);
+        assert_eq!(list.patch_rom(0x8000, 0xA9), 0xA9);
+        assert_eq!(list.status(0, 0xA9), PatchStatus::Disabled);
+        list.set_enabled(1, false);
+        assert!(!list.freezes().entries[0].enabled);
+        assert_eq!(list.remove(1).map(|p| p.addr), Some(0x075A));
+        assert_eq!(list.remove(1), None);
+        assert!(list.freezes().entries.is_empty());
+    }
+}
@@ -0,0 +1,117 @@
+// Threaded rendering splits the pixel pipeline at the last point where it
+// can affect the emulated machine. Everything up to the 6-bit palette value
+// and emphasis bits (CHR fetches that clock mapper latches, sprite 0 hit,
+// dot-exact $2001/palette writes) stays on the emulation thread; once a
+// scanline's 256 values are fixed, turning them into RGB happens on a
+// worker thread while emulation moves on. Scanlines are handed over in
+// batches so the threads synchronise a few times per frame, not per line.
+use super::rgb_lut::convert_row;
+use std::ops::Range;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread::JoinHandle;
+
+const WIDTH: usize = 256;
+const BATCH_ROWS: usize = 16;
+const LAST_ROW: usize = 239;
+const PLANE: usize = WIDTH * BATCH_ROWS;
+
+/// Scanlines `16n..16n + 15`: their colors, then their emphasis bits.
+type ColorRows = Box<[u8; PLANE * 2]>;
+type RgbRows = Box<[u8; PLANE * 3]>;
+
+pub(super) struct ColorWorker {
+    batch: ColorRows,
+    /// First scanline added to `batch`; rows above it (when threading
+    /// starts mid-frame) keep their old pixels.
+    first_row: Option<usize>,
+    rows: Option<Sender<(Range<usize>, ColorRows)>>,
+    done: Receiver<(Range<usize>, RgbRows, ColorRows)>,
+    /// Batches handed out and not yet copied back.
+    pending: usize,
+    spare: Vec<ColorRows>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ColorWorker {
+    pub(super) fn new() -> Self {
+        let (rows, worker_rows) = channel::<(Range<usize>, ColorRows)>();
+        let (worker_done, done) = channel();
+        let thread = std::thread::Builder::new()
+            .name("ppu-color".to_string())
+            .spawn(move || {
+                for (lines, rows) in worker_rows {
+                    let mut rgb: RgbRows = Box::new([0; PLANE * 3]);
+                    for y in lines.clone() {
+                        let row = (y % BATCH_ROWS) * WIDTH;
+                        convert_row(
+                            &rows[row..row + WIDTH],
+                            &rows[PLANE + row..PLANE + row + WIDTH],
+                            &mut rgb[row * 3..(row + WIDTH) * 3],
+                        );
+                    }
+                    if worker_done.send((lines, rgb, rows)).is_err() {
+                        break;
+                    }
+                }
+            })
+            .expect("spawn PPU color thread");
+        ColorWorker {
+            batch: Box::new([0; PLANE * 2]),
+            first_row: None,
+            rows: Some(rows),
+            done,
+            pending: 0,
+            spare: Vec::new(),
+            thread: Some(thread),
+        }
+    }
+
+    /// Add finished scanline `y`; the batch is handed over once it is full
+    /// or the frame's last visible line is done.
+    pub(super) fn finish_row(&mut self, y: usize, colors: &[u8], emphasis: &[u8]) {
+        self.first_row.get_or_insert(y);
+        let row = (y % BATCH_ROWS) * WIDTH;
+        self.batch[row..row + WIDTH].copy_from_slice(colors);
+        self.batch[PLANE + row..PLANE + row + WIDTH].copy_from_slice(emphasis);
+        if y % BATCH_ROWS != BATCH_ROWS - 1 && y != LAST_ROW {
+            return;
+        }
+        let Some(first_row) = self.first_row.take() else {
+            return;
+        };
+        let start = y - y % BATCH_ROWS;
+        let next = self.spare.pop().unwrap_or_else(|| Box::new([0; PLANE * 2]));
+        let batch = std::mem::replace(&mut self.batch, next);
+        if let Some(rows) = &self.rows {
+            if rows.send((first_row.max(start)..y + 1, batch)).is_ok() {
+                self.pending += 1;
+            }
+        }
+    }
+
+    /// Wait for every handed-out batch and copy it into the RGB24 frame.
+    pub(super) fn collect(&mut self, buffer: &mut [u8]) {
+        while self.pending > 0 {
+            let Ok((lines, rgb, rows)) = self.done.recv() else {
+                break;
+            };
+            let offset = (lines.start % BATCH_ROWS) * WIDTH * 3;
+            let len = lines.len() * WIDTH * 3;
+            buffer[lines.start * WIDTH * 3..lines.end * WIDTH * 3]
+                .copy_from_slice(&rgb[offset..offset + len]);
+            self.spare.push(rows);
+            self.pending -= 1;
+        }
+        self.pending = 0;
+    }
+}
+
+impl Drop for ColorWorker {
+    fn drop(&mut self) {
+        // Closing the row channel ends the worker loop
+        self.rows = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
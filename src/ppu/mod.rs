@@ -1,7 +1,26 @@
+use crate::power_on::PowerOnState;
+use crate::region::Region;
 use bitflags::bitflags;
+use color_worker::ColorWorker;
+use rgb_lut::convert_row;
 
+mod color_worker;
+mod rgb_lut;
 #[cfg(test)]
 mod tests;
+mod vs;
+
+pub use vs::VsPpu;
+
+/// CPU cycles after power or reset during which the PPU ignores writes to
+/// $2000, $2001, $2005 and $2006.
+pub const WARMUP_CPU_CYCLES: u32 = 29658;
+
+/// RGB of NES color `index` (0-63), without emphasis.
+pub fn nes_color_rgb(index: u8) -> [u8; 3] {
+    let (r, g, b) = PALETTE_COLORS[(index & 0x3F) as usize];
+    [r, g, b]
+}
 
 // NES Color Palette (RGB values)
 const PALETTE_COLORS: [(u8, u8, u8); 64] = [
@@ -71,6 +90,48 @@ const PALETTE_COLORS: [(u8, u8, u8); 64] = [
     (0, 0, 0),
 ];
 
+/// Palette RAM index for a $3F00-$3FFF address; $3F10/$14/$18/$1C mirror
+/// the backdrop entries $3F00/$04/$08/$0C.
+fn palette_slot(addr: u16) -> usize {
+    let slot = (addr & 0x1F) as usize;
+    if slot & 0x13 == 0x10 {
+        slot & 0x0F
+    } else {
+        slot
+    }
+}
+
+/// Color emphasis ($2001 bits 5-7): each set bit darkens the two other
+/// channels to roughly 82%.
+#[inline]
+fn apply_emphasis(color: (u8, u8, u8), mask: u8) -> (u8, u8, u8) {
+    let emphasis = mask >> 5;
+    if emphasis == 0 {
+        return color;
+    }
+    let dim = |value: u8, times: u32| (0..times).fold(value as u32, |v, _| v * 209 / 256) as u8;
+    let red = emphasis & 1 != 0;
+    let green = emphasis & 2 != 0;
+    let blue = emphasis & 4 != 0;
+    (
+        dim(color.0, green as u32 + blue as u32),
+        dim(color.1, red as u32 + blue as u32),
+        dim(color.2, red as u32 + green as u32),
+    )
+}
+
+// Palette RAM contents observed at power-up on a 2C02 (blargg's
+// power_up_palette test).
+const POWER_UP_PALETTE: [u8; 32] = [
+    0x09, 0x01, 0x00, 0x01, 0x00, 0x02, 0x02, 0x0D, 0x08, 0x10, 0x08, 0x24, 0x00, 0x00, 0x04, 0x2C,
+    0x09, 0x01, 0x34, 0x03, 0x00, 0x04, 0x00, 0x14, 0x08, 0x3A, 0x00, 0x02, 0x00, 0x20, 0x2C, 0x08,
+];
+
+/// Dots (3000 CPU cycles) an OAM row keeps its contents without a
+/// refresh; longer than vblank, so only forced blanking loses sprites.
+/// Decayed rows read back as $FF, an approximation of the real drift.
+const OAM_DECAY_DOTS: u64 = 9000;
+
 bitflags! {
     #[derive(Debug, Clone, Copy)]
     pub struct PpuControl: u8 {
@@ -108,6 +169,35 @@ bitflags! {
     }
 }
 
+/// Where a rendered pixel came from, recorded for the pixel inspector.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PixelProvenance {
+    /// Nametable entry ($2000-$2FBF) of the background tile, if one was fetched.
+    pub nametable_addr: Option<u16>,
+    /// Column and row (0-7) of the pixel inside that background tile, the
+    /// fine scroll it was fetched with.
+    pub fine_x: u8,
+    pub fine_y: u8,
+    /// Tile index of the layer that produced the pixel.
+    pub tile_index: u8,
+    /// Pattern table base ($0000 or $1000) of that tile.
+    pub pattern_table: u16,
+    /// Palette RAM index (0-31); 16 and above are sprite palettes.
+    pub palette_index: u8,
+    /// OAM index when a sprite produced the pixel.
+    pub oam_index: Option<u8>,
+}
+
+// Opaque sprite pixel found by render_sprites
+struct SpritePixel {
+    color: u8,
+    behind_bg: bool,
+    oam_index: u8,
+    tile_index: u8,
+    pattern_table: u16,
+    palette_index: u8,
+}
+
 pub struct Ppu {
     #[cfg(test)]
     pub control: PpuControl,
@@ -178,6 +268,12 @@ pub struct Ppu {
 
     buffer: Vec<u8>,
 
+    // The frame before the palette: NES color (after grayscale and Vs.
+    // remapping) and emphasis bits per dot. Each scanline is converted into
+    // `buffer` once its last dot is drawn.
+    indexed: Vec<u8>,
+    emphasis: Vec<u8>,
+
     // PPU $2007 read buffer for CHR-ROM reads
     read_buffer: u8,
 
@@ -190,14 +286,15 @@ pub struct Ppu {
     // Pending NMI from edge-triggered NMI_ENABLE write during VBlank
     pending_nmi: bool,
 
-    // Set to true when the PPU completes a full frame (scanline wraps from 260 to -1)
+    // Set to true when the PPU completes a full frame (scanline wraps from 260, or 310 on PAL, to -1)
     pub frame_complete: bool,
 
     // Cached rendering_enabled flag — updated on $2001 write
     rendering_enabled: bool,
 
-    // Per-scanline sprite cache: (sprite_num, y, tile_id, attributes, x)
-    scanline_sprites: [(u8, u8, u8, u8, u8); 8],
+    // Per-scanline sprite cache: (sprite_num, y, tile_id, attributes, x).
+    // Sized for all 64 so the 8-sprite limit can be lifted.
+    scanline_sprites: [(u8, u8, u8, u8, u8); 64],
     scanline_sprite_count: u8,
 
     // Cached background tile CHR data — reused for 8 consecutive pixels
@@ -208,19 +305,46 @@ pub struct Ppu {
     // Cached nametable mirroring map: logical NT 0-3 → physical NT 0-1
     cached_nt_map: [u8; 4],
 
-    // Scanline-cached mask flags (updated at cycle 0 of each visible scanline)
-    scanline_bg_enable: bool,
-    scanline_sprite_enable: bool,
-    scanline_bg_left: bool,
-    scanline_sprite_left: bool,
-    scanline_grayscale: bool,
-
     // Scanline-cached sprite control registers
     cached_sprite_size: u8,
     cached_sprite_pattern_table: u16,
 
     // Set when the PPU wants the mapper IRQ counter clocked (MMC3 scanline counter)
     pub mapper_irq_clock: bool,
+
+    // Display-only layer toggles (debug/screenshots). Hidden layers still
+    // run through evaluation so sprite 0 hit and overflow stay intact.
+    show_background: bool,
+    show_sprites: bool,
+
+    // Drop sprites past the 8th on a scanline, as hardware does. When off,
+    // extra sprites are still drawn but overflow is flagged the same way.
+    sprite_limit: bool,
+
+    // Accuracy option for OAM DRAM behaviour: decay while not refreshed by
+    // rendering, the OAMADDR copy when rendering starts, glitchy $2004
+    // writes during rendering and OAMADDR clearing on dots 257-320.
+    oam_quirks: bool,
+    oam_clock: u64,
+    // `oam_clock` when each 8-byte OAM row was last refreshed
+    oam_row_refresh: [u64; 32],
+
+    // Per-pixel provenance side buffer (256x240), only allocated while the
+    // pixel inspector is enabled.
+    provenance: Option<Vec<PixelProvenance>>,
+
+    // Vs. System PPU variant (palette scrambling, $2002 ID, register swap)
+    vs_ppu: VsPpu,
+
+    // PAL: 312 scanlines, no odd-frame dot skip, red/green emphasis swapped
+    region: Region,
+
+    // Dots left in the power/reset warm-up that drops register writes
+    warmup_dots: u32,
+
+    // Converts finished scanlines to RGB on another thread when threaded
+    // rendering is on; `buffer` is complete once the frame is.
+    color_worker: Option<ColorWorker>,
 }
 
 impl Ppu {
@@ -256,26 +380,34 @@ impl Ppu {
                 }
                 buf
             },
+            indexed: vec![0; 256 * 240],
+            emphasis: vec![0; 256 * 240],
             read_buffer: 0,
             nmi_suppressed: false,
             vblank_flag_set_this_frame: false,
             pending_nmi: false,
             frame_complete: false,
             rendering_enabled: false,
-            scanline_sprites: [(0, 0, 0, 0, 0); 8],
+            scanline_sprites: [(0, 0, 0, 0, 0); 64],
             scanline_sprite_count: 0,
             cached_tile_addr: 0xFFFF,
             cached_tile_low: 0,
             cached_tile_high: 0,
             cached_nt_map: [0, 1, 0, 1],
-            scanline_bg_enable: false,
-            scanline_sprite_enable: false,
-            scanline_bg_left: false,
-            scanline_sprite_left: false,
-            scanline_grayscale: false,
             cached_sprite_size: 8,
             cached_sprite_pattern_table: 0,
             mapper_irq_clock: false,
+            show_background: true,
+            show_sprites: true,
+            sprite_limit: true,
+            oam_quirks: false,
+            oam_clock: 0,
+            oam_row_refresh: [0; 32],
+            provenance: None,
+            vs_ppu: VsPpu::Rp2c03,
+            region: Region::Ntsc,
+            warmup_dots: 0,
+            color_worker: None,
         };
 
         ppu
@@ -285,6 +417,13 @@ impl Ppu {
     pub fn step(&mut self, cartridge: Option<&crate::cartridge::Cartridge>) -> bool {
         let mut nmi = false;
 
+        if self.warmup_dots > 0 {
+            self.warmup_dots -= 1;
+        }
+        if self.oam_quirks {
+            self.step_oam_quirks();
+        }
+
         // Check for edge-triggered NMI from $2000 write
         if self.pending_nmi {
             self.pending_nmi = false;
@@ -417,6 +556,7 @@ impl Ppu {
 
                 // Increment Y at cycle 256
                 if self.cycle == 256 {
+                    self.finish_row();
                     self.increment_y();
                 }
 
@@ -465,9 +605,9 @@ impl Ppu {
                     self.nmi_suppressed = false;
                 }
             }
-            242..=260 => {
+            242.. => {
                 // Keep VBlank flag set during VBlank period
-                // VBlank period runs from scanline 241 to 260
+                // VBlank period runs from scanline 241 to 260 (310 on PAL)
             }
             _ => {}
         }
@@ -475,8 +615,11 @@ impl Ppu {
         self.cycle += 1;
 
         // Odd-frame cycle skip: on pre-render scanline of odd frames,
-        // skip the last cycle (340) when rendering is enabled
-        let cycle_limit = if self.scanline == -1 && self.rendering_enabled && (self.frame & 1) == 1
+        // skip the last cycle (340) when rendering is enabled (NTSC only)
+        let cycle_limit = if self.scanline == -1
+            && self.rendering_enabled
+            && (self.frame & 1) == 1
+            && self.region == Region::Ntsc
         {
             340
         } else {
@@ -487,10 +630,13 @@ impl Ppu {
             self.cycle = 0;
             self.scanline += 1;
 
-            if self.scanline >= 261 {
+            if self.scanline > self.region.last_vblank_scanline() {
                 self.scanline = -1;
                 self.frame += 1;
                 self.frame_complete = true;
+                if let Some(worker) = self.color_worker.as_mut() {
+                    worker.collect(&mut self.buffer);
+                }
             }
         }
 
@@ -539,6 +685,26 @@ impl Ppu {
         }
     }
 
+    /// Pattern table byte; `sprite_y` for sprite fetches. Boards with no
+    /// CHR memory (mapper 218) decode $0000-$1FFF onto CIRAM the same way
+    /// as the nametables.
+    #[inline]
+    pub(crate) fn read_pattern(
+        &self,
+        cart: &crate::cartridge::Cartridge,
+        addr: u16,
+        sprite_y: Option<u8>,
+    ) -> u8 {
+        if cart.chr_in_ciram() {
+            let table = self.resolve_nametable((addr as usize >> 10) & 3, Some(cart));
+            self.nametable[table & 1][addr as usize & 0x3FF]
+        } else if let Some(sprite_y) = sprite_y {
+            cart.read_chr_sprite(addr, sprite_y)
+        } else {
+            cart.read_chr(addr)
+        }
+    }
+
     #[inline]
     fn read_nametable_byte(
         &self,
@@ -603,11 +769,20 @@ impl Ppu {
             return;
         }
 
-        let mut bg_color = self.palette[0];
+        // $2001 enable bits act on the dot they are written, like grayscale
+        // and emphasis below. In forced blank with v pointing into palette
+        // RAM the PPU outputs that entry instead of the backdrop.
+        let mut bg_color = if !self.rendering_enabled && self.v & 0x3F00 == 0x3F00 {
+            self.palette[palette_slot(self.v)]
+        } else {
+            self.palette[0]
+        };
         let mut bg_pixel = 0u8;
+        let mut bg_palette_idx = 0usize;
+        let mut bg_source: Option<(u16, u8, u16, u8, u8)> = None;
 
-        if self.scanline_bg_enable {
-            if !self.scanline_bg_left && x < 8 {
+        if self.mask.contains(PpuMask::BG_ENABLE) {
+            if !self.mask.contains(PpuMask::BG_LEFT_ENABLE) && x < 8 {
                 // bg_color stays palette[0], bg_pixel stays 0
             } else if let Some(cart) = cartridge {
                 if let Some((low_byte, high_byte, palette_num)) =
@@ -623,6 +798,7 @@ impl Ppu {
                     if pixel_value != 0 {
                         let palette_idx = (palette_num as usize * 4) + pixel_value as usize;
                         bg_color = self.palette[palette_idx];
+                        bg_palette_idx = palette_idx;
                     }
                 } else {
                     let fine_y = ((self.v >> 12) & 7) as u16;
@@ -654,6 +830,13 @@ impl Ppu {
                         0x0000u16
                     };
                     let tile_addr = pattern_table + (tile_id as u16 * 16) + fine_y;
+                    bg_source = Some((
+                        0x2000 + (tile_nt as u16) * 0x400 + nt_addr as u16,
+                        tile_id,
+                        pattern_table,
+                        tile_fx,
+                        fine_y as u8,
+                    ));
 
                     if tile_addr < 0x2000 {
                         // Tile cache: reuse CHR data within the same tile (same
@@ -663,8 +846,8 @@ impl Ppu {
                         let (low_byte, high_byte) = if tile_addr == self.cached_tile_addr {
                             (self.cached_tile_low, self.cached_tile_high)
                         } else {
-                            let low = cart.read_chr(tile_addr);
-                            let high = cart.read_chr(tile_addr + 8);
+                            let low = self.read_pattern(cart, tile_addr, None);
+                            let high = self.read_pattern(cart, tile_addr + 8, None);
                             self.cached_tile_addr = tile_addr;
                             self.cached_tile_low = low;
                             self.cached_tile_high = high;
@@ -691,6 +874,7 @@ impl Ppu {
 
                             let palette_idx = (palette_num as usize * 4) + pixel_value as usize;
                             bg_color = self.palette[palette_idx];
+                            bg_palette_idx = palette_idx;
                         }
                     }
                 }
@@ -700,8 +884,8 @@ impl Ppu {
         let mut sprite_result = None;
         let mut sprite_0_hit = false;
 
-        if self.scanline_sprite_enable {
-            if !self.scanline_sprite_left && x < 8 {
+        if self.mask.contains(PpuMask::SPRITE_ENABLE) {
+            if !self.mask.contains(PpuMask::SPRITE_LEFT_ENABLE) && x < 8 {
                 // Skip sprite rendering in left 8 pixels
             } else {
                 sprite_result = self.render_sprites(x as u8, y as u8, cartridge, &mut sprite_0_hit);
@@ -712,28 +896,153 @@ impl Ppu {
             }
         }
 
-        let final_color = if let Some((sprite_color, priority_behind_bg)) = sprite_result {
-            if priority_behind_bg && bg_pixel != 0 {
-                bg_color
-            } else {
-                sprite_color
-            }
+        // Layer toggles only affect compositing; bg_pixel/sprite_0_hit above
+        // keep their hardware values.
+        let (bg_color, bg_opaque) = if self.show_background {
+            (bg_color, bg_pixel != 0)
         } else {
-            bg_color
+            bg_palette_idx = 0;
+            (self.palette[0], false)
+        };
+        if !self.show_sprites {
+            sprite_result = None;
+        }
+
+        let sprite_shown = match &sprite_result {
+            Some(sprite) => !(sprite.behind_bg && bg_opaque),
+            None => false,
+        };
+        let final_color = match &sprite_result {
+            Some(sprite) if sprite_shown => sprite.color,
+            _ => bg_color,
         };
 
-        let pixel_index = ((y as usize * 256) + x as usize) * 3;
+        if let Some(provenance) = self.provenance.as_mut() {
+            let (bg_nt, bg_tile, bg_pattern, fine_x, fine_y) = match bg_source {
+                Some((nt, tile, pattern, fine_x, fine_y)) => {
+                    (Some(nt), tile, pattern, fine_x, fine_y)
+                }
+                None => (None, 0, 0, 0, 0),
+            };
+            let entry = match &sprite_result {
+                Some(sprite) if sprite_shown => PixelProvenance {
+                    nametable_addr: bg_nt,
+                    fine_x,
+                    fine_y,
+                    tile_index: sprite.tile_index,
+                    pattern_table: sprite.pattern_table,
+                    palette_index: sprite.palette_index,
+                    oam_index: Some(sprite.oam_index),
+                },
+                _ => PixelProvenance {
+                    nametable_addr: bg_nt,
+                    fine_x,
+                    fine_y,
+                    tile_index: bg_tile,
+                    pattern_table: bg_pattern,
+                    palette_index: bg_palette_idx as u8,
+                    oam_index: None,
+                },
+            };
+            provenance[y as usize * 256 + x as usize] = entry;
+        }
 
+        // Grayscale and emphasis come from the live $2001 value so writes
+        // take effect at the current dot, not the next scanline.
         let mut masked_color = final_color & 0x3F;
-        if self.scanline_grayscale {
+        if self.mask.contains(PpuMask::GRAYSCALE) {
             masked_color &= 0x30;
         }
-        let color = PALETTE_COLORS[masked_color as usize];
-        // Safety: x is 0..255 and y is 0..239 (guarded above), buffer is 256*240*3
-        let dest = &mut self.buffer[pixel_index..pixel_index + 3];
-        dest[0] = color.0;
-        dest[1] = color.1;
-        dest[2] = color.2;
+        if let Some(lut) = self.vs_ppu.palette_lut() {
+            masked_color = lut[masked_color as usize];
+        }
+        let mut emphasis = self.mask.bits();
+        if self.region == Region::Pal {
+            // 2C07 wires bit 5 to green and bit 6 to red
+            emphasis = (emphasis & 0x9F) | ((emphasis & 0x20) << 1) | ((emphasis & 0x40) >> 1);
+        }
+        let dot = y as usize * 256 + x as usize;
+        self.indexed[dot] = masked_color;
+        self.emphasis[dot] = emphasis >> 5;
+    }
+
+    /// Turn the finished scanline into RGB, here or on the color worker.
+    fn finish_row(&mut self) {
+        let y = self.scanline as usize;
+        let row = y * 256..(y + 1) * 256;
+        let (colors, emphasis) = (&self.indexed[row.clone()], &self.emphasis[row]);
+        match self.color_worker.as_mut() {
+            Some(worker) => worker.finish_row(y, colors, emphasis),
+            None => convert_row(colors, emphasis, &mut self.buffer[y * 768..(y + 1) * 768]),
+        }
+    }
+
+    /// $2004 while rendering: the byte sprite evaluation or the sprite
+    /// fetches touch on this dot instead of OAM[OAMADDR]. Evaluation itself
+    /// runs all at once; this replays its dot timing on demand.
+    fn rendering_oam_read(&self) -> u8 {
+        let cycle = self.cycle as usize;
+        match cycle {
+            // Secondary OAM clear
+            1..=64 => 0xFF,
+            65..=256 if self.scanline >= 0 => self.evaluation_oam_byte(cycle),
+            // Sprite fetches read Y, tile, attributes, then X for 5 dots
+            257..=320 => {
+                let slot = (cycle - 257) / 8;
+                let byte = ((cycle - 257) % 8).min(3);
+                self.secondary_oam(self.scanline)[slot * 4 + byte]
+            }
+            0 => self.secondary_oam(self.scanline - 1)[0],
+            _ => self.secondary_oam(self.scanline)[0],
+        }
+    }
+
+    /// Evaluation on dots 65-256: two dots per sprite Y checked, eight for
+    /// each of the first eight in-range sprites copied; afterwards Y bytes
+    /// keep being read (the overflow scan's byte skew is not modelled).
+    fn evaluation_oam_byte(&self, cycle: usize) -> u8 {
+        let height = self.sprite_height();
+        let line = self.scanline as u16;
+        let mut dot = 65;
+        let mut found = 0;
+        for n in 0..64 {
+            let base = n * 4;
+            let in_range = found < 8 && line.wrapping_sub(self.oam[base] as u16) < height;
+            let len = if in_range { 8 } else { 2 };
+            if cycle < dot + len {
+                return self.oam[base + (cycle - dot) / 2];
+            }
+            dot += len;
+            found += in_range as usize;
+        }
+        self.oam[(cycle - dot) / 2 % 64 * 4]
+    }
+
+    /// Secondary OAM as evaluation on `line` leaves it: the first eight
+    /// sprites for the next line, $FF past them. Nothing is evaluated on
+    /// the pre-render line.
+    fn secondary_oam(&self, line: i16) -> [u8; 32] {
+        let mut secondary = [0xFF; 32];
+        if !(0..240).contains(&line) {
+            return secondary;
+        }
+        let height = self.sprite_height();
+        let in_range = self
+            .oam
+            .chunks_exact(4)
+            .filter(|sprite| (line as u16).wrapping_sub(sprite[0] as u16) < height);
+        for (slot, sprite) in secondary.chunks_exact_mut(4).zip(in_range) {
+            slot.copy_from_slice(sprite);
+        }
+        secondary
+    }
+
+    fn sprite_height(&self) -> u16 {
+        if self.control.contains(PpuControl::SPRITE_SIZE) {
+            16
+        } else {
+            8
+        }
     }
 
     fn evaluate_scanline_sprites(&mut self, _cartridge: Option<&crate::cartridge::Cartridge>) {
@@ -742,13 +1051,6 @@ impl Ppu {
             return;
         }
 
-        // Update scanline-cached mask flags
-        self.scanline_bg_enable = self.mask.contains(PpuMask::BG_ENABLE);
-        self.scanline_sprite_enable = self.mask.contains(PpuMask::SPRITE_ENABLE);
-        self.scanline_bg_left = self.mask.contains(PpuMask::BG_LEFT_ENABLE);
-        self.scanline_sprite_left = self.mask.contains(PpuMask::SPRITE_LEFT_ENABLE);
-        self.scanline_grayscale = self.mask.contains(PpuMask::GRAYSCALE);
-
         // Cache sprite control registers
         self.cached_sprite_size = if self.control.contains(PpuControl::SPRITE_SIZE) {
             16
@@ -812,7 +1114,9 @@ impl Ppu {
                 let idx = self.scanline_sprite_count as usize;
                 if idx >= 8 {
                     self.status.insert(PpuStatus::SPRITE_OVERFLOW);
-                    break;
+                    if self.sprite_limit {
+                        break;
+                    }
                 }
                 self.scanline_sprites[idx] = (
                     sprite_num,
@@ -833,7 +1137,7 @@ impl Ppu {
         y: u8,
         cartridge: Option<&crate::cartridge::Cartridge>,
         sprite_0_hit: &mut bool,
-    ) -> Option<(u8, bool)> {
+    ) -> Option<SpritePixel> {
         if let Some(cart) = cartridge {
             let sprite_size = self.cached_sprite_size;
             let count = self.scanline_sprite_count as usize;
@@ -882,8 +1186,8 @@ impl Ppu {
 
                 // Read pattern data
                 if tile_addr + 8 < 0x2000 {
-                    let low_byte = cart.read_chr_sprite(tile_addr, sprite_y);
-                    let high_byte = cart.read_chr_sprite(tile_addr + 8, sprite_y);
+                    let low_byte = self.read_pattern(cart, tile_addr, Some(sprite_y));
+                    let high_byte = self.read_pattern(cart, tile_addr + 8, Some(sprite_y));
                     let pixel_bit = 7 - pixel_x;
                     let low_bit = (low_byte >> pixel_bit) & 1;
                     let high_bit = (high_byte >> pixel_bit) & 1;
@@ -899,7 +1203,14 @@ impl Ppu {
                         let color_index = self.palette[palette_idx];
 
                         let priority_behind_bg = (attributes & 0x20) != 0;
-                        return Some((color_index, priority_behind_bg));
+                        return Some(SpritePixel {
+                            color: color_index,
+                            behind_bg: priority_behind_bg,
+                            oam_index: sprite_num,
+                            tile_index: final_tile_id,
+                            pattern_table,
+                            palette_index: palette_idx as u8,
+                        });
                     }
                 }
             }
@@ -1001,7 +1312,7 @@ impl Ppu {
     ) -> u8 {
         match addr {
             0x2002 => {
-                let status = self.status.bits();
+                let status = self.status.bits() | self.vs_ppu.status_id();
 
                 // Clear VBlank flag after read
                 self.status.remove(PpuStatus::VBLANK);
@@ -1016,22 +1327,21 @@ impl Ppu {
 
                 status
             }
-            0x2004 => self.oam[self.oam_addr as usize],
+            0x2004 => {
+                if self.rendering_enabled && self.scanline < 240 {
+                    return self.rendering_oam_read();
+                }
+                self.refresh_oam_row(self.oam_addr);
+                self.oam[self.oam_addr as usize]
+            }
             0x2007 => {
+                let addr = self.data_port_addr();
                 // Super Mario Bros title screen fix: Proper $2007 read implementation
-                let data = if self.v >= 0x3F00 {
+                let data = if addr >= 0x3F00 {
                     // Palette RAM: Immediate read (no buffering)
-                    let palette_addr = (self.v & 0x1F) as usize;
-                    // Proper NES palette mirroring for reads
-                    let mirrored_addr = match palette_addr {
-                        0x10 => 0x00, // $3F10 mirrors $3F00
-                        0x14 => 0x04, // $3F14 mirrors $3F04
-                        0x18 => 0x08, // $3F18 mirrors $3F08
-                        0x1C => 0x0C, // $3F1C mirrors $3F0C
-                        _ => palette_addr & 0x1F,
-                    };
+                    let mirrored_addr = palette_slot(addr);
                     // Also fill read_buffer with nametable data "underneath" the palette
-                    let nt_addr = (self.v & 0x2FFF) as usize;
+                    let nt_addr = (addr & 0x2FFF) as usize;
                     if nt_addr >= 0x2000 {
                         let offset_in_nt = nt_addr - 0x2000;
                         let logical_nt = (offset_in_nt >> 10) & 3;
@@ -1045,10 +1355,10 @@ impl Ppu {
                     let old_buffer = self.read_buffer;
 
                     // Update buffer with new data
-                    let effective_v = if self.v >= 0x3000 && self.v < 0x3F00 {
-                        self.v - 0x1000 // $3000-$3EFF mirrors $2000-$2EFF
+                    let effective_v = if (0x3000..0x3F00).contains(&addr) {
+                        addr - 0x1000 // $3000-$3EFF mirrors $2000-$2EFF
                     } else {
-                        self.v
+                        addr
                     };
                     if effective_v >= 0x2000 && effective_v < 0x3000 {
                         // Nametable read with proper mirroring
@@ -1060,7 +1370,7 @@ impl Ppu {
                     } else if effective_v < 0x2000 {
                         // CHR-ROM/CHR-RAM read
                         if let Some(cart) = cartridge {
-                            self.read_buffer = cart.read_chr(effective_v);
+                            self.read_buffer = self.read_pattern(cart, effective_v, None);
                         } else {
                             self.read_buffer = 0;
                         }
@@ -1072,12 +1382,7 @@ impl Ppu {
                 };
 
                 // CRITICAL: Increment VRAM address AFTER read
-                let increment = if self.control.contains(PpuControl::VRAM_INCREMENT) {
-                    32
-                } else {
-                    1
-                };
-                self.v = self.v.wrapping_add(increment) & 0x3FFF;
+                self.advance_data_port();
 
                 data
             }
@@ -1091,6 +1396,14 @@ impl Ppu {
         data: u8,
         cartridge: Option<&mut crate::cartridge::Cartridge>,
     ) {
+        let addr = match addr {
+            0x2000 if self.vs_ppu.swaps_ctrl_mask() => 0x2001,
+            0x2001 if self.vs_ppu.swaps_ctrl_mask() => 0x2000,
+            _ => addr,
+        };
+        if self.warmup_dots > 0 && matches!(addr, 0x2000 | 0x2001 | 0x2005 | 0x2006) {
+            return;
+        }
         match addr {
             0x2000 => {
                 let old_nmi_enable = self.control.contains(PpuControl::NMI_ENABLE);
@@ -1120,6 +1433,12 @@ impl Ppu {
                 self.oam_addr = data;
             }
             0x2004 => {
+                if self.oam_quirks && self.rendering_enabled && self.scanline < 240 {
+                    // No write; only the sprite index bits of OAMADDR bump
+                    self.oam_addr = self.oam_addr.wrapping_add(4);
+                    return;
+                }
+                self.refresh_oam_row(self.oam_addr);
                 self.oam[self.oam_addr as usize] = data;
                 self.oam_addr = self.oam_addr.wrapping_add(1);
             }
@@ -1146,22 +1465,15 @@ impl Ppu {
                 }
             }
             0x2007 => {
-                let write_v = if self.v >= 0x3000 && self.v < 0x3F00 {
-                    self.v - 0x1000
+                let addr = self.data_port_addr();
+                let write_v = if (0x3000..0x3F00).contains(&addr) {
+                    addr - 0x1000
                 } else {
-                    self.v
+                    addr
                 };
                 if write_v >= 0x3F00 {
                     // Palette write
-                    let palette_addr = (write_v & 0x1F) as usize;
-                    let mirrored_addr = match palette_addr {
-                        0x10 => 0x00,
-                        0x14 => 0x04,
-                        0x18 => 0x08,
-                        0x1C => 0x0C,
-                        _ => palette_addr & 0x1F,
-                    };
-                    self.palette[mirrored_addr] = data;
+                    self.palette[palette_slot(write_v)] = data;
                 } else if write_v >= 0x2000 && write_v < 0x3000 {
                     // Nametable write
                     let addr = (write_v - 0x2000) as usize;
@@ -1185,31 +1497,277 @@ impl Ppu {
                 } else if write_v < 0x2000 {
                     // CHR write (for CHR RAM)
                     if let Some(cart) = cartridge {
-                        cart.write_chr(write_v, data);
+                        if cart.chr_in_ciram() {
+                            let table =
+                                self.resolve_nametable((write_v as usize >> 10) & 3, Some(&*cart));
+                            self.nametable[table & 1][write_v as usize & 0x3FF] = data;
+                        } else {
+                            cart.write_chr(write_v, data);
+                        }
                     }
-                    let increment = if self.control.contains(PpuControl::VRAM_INCREMENT) {
-                        32
-                    } else {
-                        1
-                    };
-                    self.v = self.v.wrapping_add(increment) & 0x3FFF;
+                    self.advance_data_port();
                     return;
                 }
 
-                let increment = if self.control.contains(PpuControl::VRAM_INCREMENT) {
-                    32
-                } else {
-                    1
-                };
-                self.v = self.v.wrapping_add(increment) & 0x3FFF;
+                self.advance_data_port();
             }
             _ => {}
         }
     }
 
+    /// VRAM address the next $2007 access will use.
+    pub fn data_port_addr(&self) -> u16 {
+        self.v & 0x3FFF
+    }
+
+    /// Swallow a $2007 write (write-protected CHR) while still advancing
+    /// the VRAM address as the hardware would.
+    pub fn skip_data_write(&mut self) {
+        self.advance_data_port();
+    }
+
+    /// Step `v` after a $2007 access. `v` is the 15-bit loopy register, so
+    /// the increment carries into fine Y; only the low 14 bits reach the
+    /// bus. While rendering, the access instead clocks the scroll counters:
+    /// coarse X and Y both increment, as on the fetch dots.
+    fn advance_data_port(&mut self) {
+        if self.rendering_enabled && self.scanline < 240 {
+            self.increment_coarse_x();
+            self.increment_y();
+            return;
+        }
+        let increment = if self.control.contains(PpuControl::VRAM_INCREMENT) {
+            32
+        } else {
+            1
+        };
+        self.v = self.v.wrapping_add(increment) & 0x7FFF;
+    }
+
     pub fn get_buffer(&self) -> &[u8] {
         &self.buffer
     }
+
+    /// 256x240 NES color indices ($00-$3F) as sent to the palette, for
+    /// filters that work before RGB conversion (e.g. NTSC composite).
+    pub fn get_indexed_buffer(&self) -> &[u8] {
+        &self.indexed
+    }
+
+    /// 256x240 emphasis bits per dot ($2001 bits 5-7 shifted down to 0-7,
+    /// already swapped for PAL), matching `get_indexed_buffer`.
+    pub fn get_emphasis_buffer(&self) -> &[u8] {
+        &self.emphasis
+    }
+
+    /// 256x240 9-bit pixels, color in bits 0-5 and emphasis in bits 6-8:
+    /// the layout NTSC filters such as blargg's nes_ntsc take.
+    pub fn get_raw_buffer(&self) -> Vec<u16> {
+        self.indexed
+            .iter()
+            .zip(&self.emphasis)
+            .map(|(&color, &emphasis)| color as u16 | (emphasis as u16) << 6)
+            .collect()
+    }
+
+    /// The RGB buffer as RGBA8888 with opaque alpha.
+    pub fn get_rgba_buffer(&self) -> Vec<u8> {
+        self.buffer
+            .chunks_exact(3)
+            .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 0xFF])
+            .collect()
+    }
+
+    /// Show or hide the background layer in the output buffer.
+    pub fn set_background_visible(&mut self, visible: bool) {
+        self.show_background = visible;
+    }
+
+    /// Show or hide the sprite layer in the output buffer.
+    pub fn set_sprites_visible(&mut self, visible: bool) {
+        self.show_sprites = visible;
+    }
+
+    /// Enforce (default) or lift the 8-sprites-per-scanline limit. Only
+    /// affects drawing; the overflow flag behaves the same either way.
+    pub fn set_sprite_limit(&mut self, enabled: bool) {
+        self.sprite_limit = enabled;
+    }
+
+    pub fn sprite_limit(&self) -> bool {
+        self.sprite_limit
+    }
+
+    /// Emulate OAM decay and the OAMADDR/$2004 rendering quirks (off by
+    /// default; OAM then holds its contents forever).
+    pub fn set_oam_quirks(&mut self, enabled: bool) {
+        self.oam_quirks = enabled;
+        self.oam_row_refresh = [self.oam_clock; 32];
+    }
+
+    pub fn oam_quirks(&self) -> bool {
+        self.oam_quirks
+    }
+
+    fn step_oam_quirks(&mut self) {
+        self.oam_clock += 1;
+        if !self.rendering_enabled || self.scanline >= 240 {
+            return;
+        }
+        match self.cycle {
+            0 => {
+                // Sprite evaluation reads, and so refreshes, every row
+                for row in 0..32 {
+                    self.refresh_oam_row(row * 8);
+                }
+                if self.scanline == -1 && self.oam_addr >= 8 {
+                    // 2C02 OAMADDR bug: the row OAMADDR points into is
+                    // copied over sprites 0-1 when rendering starts
+                    let row = (self.oam_addr & 0xF8) as usize;
+                    self.oam.copy_within(row..row + 8, 0);
+                }
+            }
+            257..=320 => self.oam_addr = 0,
+            _ => {}
+        }
+    }
+
+    /// Decay the row holding `addr` if it went unrefreshed for too long,
+    /// then mark it refreshed. The 2C07 refreshes OAM during its long
+    /// vblank by itself, so PAL never decays.
+    fn refresh_oam_row(&mut self, addr: u8) {
+        if !self.oam_quirks {
+            return;
+        }
+        let row = addr as usize / 8;
+        if self.region == Region::Ntsc
+            && self.oam_clock - self.oam_row_refresh[row] > OAM_DECAY_DOTS
+        {
+            self.oam[row * 8..row * 8 + 8].fill(0xFF);
+        }
+        self.oam_row_refresh[row] = self.oam_clock;
+    }
+
+    /// Convert finished scanlines to RGB on a worker thread. Only the
+    /// output buffer is affected, and it is up to date whenever a frame
+    /// completes; mid-frame it may still hold the previous frame's rows.
+    pub fn set_threaded_rendering(&mut self, enabled: bool) {
+        match (enabled, self.color_worker.take()) {
+            (true, None) => self.color_worker = Some(ColorWorker::new()),
+            (true, worker) => self.color_worker = worker,
+            (false, Some(mut worker)) => worker.collect(&mut self.buffer),
+            (false, None) => {}
+        }
+    }
+
+    pub fn threaded_rendering(&self) -> bool {
+        self.color_worker.is_some()
+    }
+
+    pub fn background_visible(&self) -> bool {
+        self.show_background
+    }
+
+    pub fn sprites_visible(&self) -> bool {
+        self.show_sprites
+    }
+
+    /// Refill palette RAM, OAM and nametable VRAM as they would look at
+    /// power-on. `Ppu::new` starts out `Clean`.
+    pub fn apply_power_on_state(&mut self, state: PowerOnState) {
+        match state {
+            PowerOnState::Clean => self.palette = [0x0F; 32],
+            PowerOnState::Pattern => self.palette = POWER_UP_PALETTE,
+            PowerOnState::Random(_) => {
+                state.fill(&mut self.palette, 0x0F, 1);
+                for entry in self.palette.iter_mut() {
+                    *entry &= 0x3F;
+                }
+            }
+        }
+        // OAM has no documented pattern; only random mode disturbs it.
+        match state {
+            PowerOnState::Random(_) => state.fill(&mut self.oam, 0xFF, 2),
+            _ => self.oam = [0xFF; 256],
+        }
+        for (i, table) in self.nametable.iter_mut().enumerate() {
+            state.fill(table, 0, 3 + i as u64);
+        }
+    }
+
+    /// Power or reset: PPUCTRL, PPUMASK, the scroll latch and the read
+    /// buffer are cleared; VRAM, OAM and v are kept. With `warmup`, writes to
+    /// $2000/$2001/$2005/$2006 are ignored for `WARMUP_CPU_CYCLES`.
+    pub fn reset(&mut self, warmup: bool) {
+        self.control = PpuControl::empty();
+        self.mask = PpuMask::empty();
+        self.rendering_enabled = false;
+        self.t = 0;
+        self.x = 0;
+        self.w = false;
+        self.read_buffer = 0;
+        self.warmup_dots = if warmup {
+            let (dots, cycles) = self.region.ppu_dots_per_cpu_cycles();
+            WARMUP_CPU_CYCLES * dots / cycles
+        } else {
+            0
+        };
+    }
+
+    /// Start over from power-on, keeping the region and display settings
+    /// (layer toggles, sprite limit, pixel inspector).
+    pub fn power_cycle(&mut self) {
+        let mut ppu = Ppu::new();
+        ppu.set_region(self.region);
+        ppu.show_background = self.show_background;
+        ppu.show_sprites = self.show_sprites;
+        ppu.sprite_limit = self.sprite_limit;
+        ppu.oam_quirks = self.oam_quirks;
+        ppu.set_provenance_enabled(self.provenance_enabled());
+        *self = ppu;
+    }
+
+    pub fn warming_up(&self) -> bool {
+        self.warmup_dots > 0
+    }
+
+    pub fn set_region(&mut self, region: Region) {
+        self.region = region;
+    }
+
+    pub fn region(&self) -> Region {
+        self.region
+    }
+
+    /// Select the Vs. System PPU variant (RP2C03 behaves like a stock PPU).
+    pub fn set_vs_ppu(&mut self, vs_ppu: VsPpu) {
+        self.vs_ppu = vs_ppu;
+    }
+
+    pub fn vs_ppu(&self) -> VsPpu {
+        self.vs_ppu
+    }
+
+    /// Start or stop recording per-pixel provenance for the pixel inspector.
+    pub fn set_provenance_enabled(&mut self, enabled: bool) {
+        if !enabled {
+            self.provenance = None;
+        } else if self.provenance.is_none() {
+            self.provenance = Some(vec![PixelProvenance::default(); 256 * 240]);
+        }
+    }
+
+    pub fn provenance_enabled(&self) -> bool {
+        self.provenance.is_some()
+    }
+
+    /// Provenance of the pixel at (x, y) from the most recent render.
+    pub fn pixel_provenance(&self, x: usize, y: usize) -> Option<PixelProvenance> {
+        if x >= 256 || y >= 240 {
+            return None;
+        }
+        self.provenance.as_ref().map(|p| p[y * 256 + x])
+    }
 }
 
 impl Ppu {
@@ -1254,6 +1812,18 @@ impl Ppu {
         self.nametable
     }
 
+    /// Byte `offset` of logical nametable 0-3 ($2000/$2400/$2800/$2C00)
+    /// after mirroring, without touching the read buffer.
+    pub fn peek_nametable(
+        &self,
+        logical_nt: usize,
+        offset: usize,
+        cartridge: Option<&crate::cartridge::Cartridge>,
+    ) -> u8 {
+        let physical_nt = self.resolve_nametable(logical_nt & 3, cartridge);
+        self.read_nametable_byte(physical_nt, offset, cartridge)
+    }
+
     pub fn get_oam(&self) -> [u8; 256] {
         self.oam
     }
@@ -1325,11 +1895,6 @@ impl Ppu {
         self.read_buffer = read_buffer;
         // Reset scanline caches so they are refreshed on next visible scanline
         self.cached_tile_addr = 0xFFFF;
-        self.scanline_bg_enable = self.mask.contains(PpuMask::BG_ENABLE);
-        self.scanline_sprite_enable = self.mask.contains(PpuMask::SPRITE_ENABLE);
-        self.scanline_bg_left = self.mask.contains(PpuMask::BG_LEFT_ENABLE);
-        self.scanline_sprite_left = self.mask.contains(PpuMask::SPRITE_LEFT_ENABLE);
-        self.scanline_grayscale = self.mask.contains(PpuMask::GRAYSCALE);
         self.cached_sprite_size = if self.control.contains(PpuControl::SPRITE_SIZE) {
             16
         } else {
@@ -1343,6 +1908,7 @@ impl Ppu {
     }
 
     pub fn write_oam_data(&mut self, addr: u8, data: u8) {
+        self.refresh_oam_row(addr);
         self.oam[addr as usize] = data;
     }
 
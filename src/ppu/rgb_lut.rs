@@ -0,0 +1,96 @@
+// Scanline color conversion. The pixel pipeline stores each dot's 6-bit
+// color and emphasis bits (5-7 of $2001, shifted down) in two planes, and
+// each finished scanline is turned into RGB24 through one table with every
+// emphasis combination applied up front. x86_64 CPUs with AVX2 convert eight dots at
+// a time with a gather and a byte shuffle; everything else uses the table
+// one dot at a time.
+use super::{apply_emphasis, PALETTE_COLORS};
+use std::sync::OnceLock;
+
+const ENTRIES: usize = 8 * 64;
+
+/// RGB as `r | g << 8 | b << 16` for `emphasis << 6 | color`.
+fn rgb_lut() -> &'static [u32; ENTRIES] {
+    static LUT: OnceLock<[u32; ENTRIES]> = OnceLock::new();
+    LUT.get_or_init(|| {
+        let mut lut = [0; ENTRIES];
+        for (i, entry) in lut.iter_mut().enumerate() {
+            let (r, g, b) = apply_emphasis(PALETTE_COLORS[i & 0x3F], ((i >> 6) << 5) as u8);
+            *entry = r as u32 | (g as u32) << 8 | (b as u32) << 16;
+        }
+        lut
+    })
+}
+
+/// Convert a row of colors and emphasis bits into RGB24 pixels in `out`.
+pub(super) fn convert_row(colors: &[u8], emphasis: &[u8], out: &mut [u8]) {
+    let lut = rgb_lut();
+    let mut done = 0;
+    #[cfg(target_arch = "x86_64")]
+    if std::arch::is_x86_feature_detected!("avx2") {
+        // Safety: AVX2 support was just checked
+        done = unsafe { convert_avx2(colors, emphasis, out, lut) };
+    }
+    let dots = colors[done..].iter().zip(&emphasis[done..]);
+    for ((&color, &emphasis), px) in dots.zip(out[done * 3..].chunks_exact_mut(3)) {
+        let rgb = lut[((emphasis & 7) as usize) << 6 | (color & 0x3F) as usize];
+        px[0] = rgb as u8;
+        px[1] = (rgb >> 8) as u8;
+        px[2] = (rgb >> 16) as u8;
+    }
+}
+
+/// Eight dots per step; returns how many were converted. Stops early
+/// enough that the 28-byte stores never run past `out`.
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn convert_avx2(
+    colors: &[u8],
+    emphasis: &[u8],
+    out: &mut [u8],
+    lut: &[u32; ENTRIES],
+) -> usize {
+    use std::arch::x86_64::*;
+    // RGBX RGBX RGBX RGBX -> RGBRGBRGBRGB in each 128-bit lane
+    let pack = _mm256_setr_epi8(
+        0, 1, 2, 4, 5, 6, 8, 9, 10, 12, 13, 14, -1, -1, -1, -1, 0, 1, 2, 4, 5, 6, 8, 9, 10, 12, 13,
+        14, -1, -1, -1, -1,
+    );
+    let (color_mask, emphasis_mask) = (_mm256_set1_epi32(0x3F), _mm256_set1_epi32(7));
+    let len = colors.len().min(emphasis.len());
+    let mut i = 0;
+    while i + 8 <= len && (i + 8) * 3 + 4 <= out.len() {
+        let color = _mm_loadl_epi64(colors.as_ptr().add(i) as *const __m128i);
+        let color = _mm256_and_si256(_mm256_cvtepu8_epi32(color), color_mask);
+        let tint = _mm_loadl_epi64(emphasis.as_ptr().add(i) as *const __m128i);
+        let tint = _mm256_and_si256(_mm256_cvtepu8_epi32(tint), emphasis_mask);
+        let index = _mm256_or_si256(_mm256_slli_epi32::<6>(tint), color);
+        let rgbx = _mm256_i32gather_epi32::<4>(lut.as_ptr() as *const i32, index);
+        let rgb = _mm256_shuffle_epi8(rgbx, pack);
+        let dst = out.as_mut_ptr().add(i * 3);
+        _mm_storeu_si128(dst as *mut __m128i, _mm256_castsi256_si128(rgb));
+        _mm_storeu_si128(
+            dst.add(12) as *mut __m128i,
+            _mm256_extracti128_si256::<1>(rgb),
+        );
+        i += 8;
+    }
+    i
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn row_conversion_matches_per_dot_colors() {
+        let colors: Vec<u8> = (0..256u32).map(|i| (i * 7 + i / 3) as u8 & 0x3F).collect();
+        let emphasis: Vec<u8> = (0..256u32).map(|i| (i / 5) as u8 & 7).collect();
+        let mut out = vec![0u8; 256 * 3];
+        convert_row(&colors, &emphasis, &mut out);
+        for ((&color, &tint), px) in colors.iter().zip(&emphasis).zip(out.chunks_exact(3)) {
+            let (r, g, b) = apply_emphasis(PALETTE_COLORS[color as usize], tint << 5);
+            assert_eq!(px, [r, g, b], "color {:#x} emphasis {}", color, tint);
+        }
+    }
+}
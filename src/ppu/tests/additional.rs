@@ -107,4 +107,470 @@ mod additional_ppu_tests {
         assert_eq!(ppu.oam[0], 50);
         assert_eq!(ppu.oam[3], 100);
     }
+
+    #[test]
+    fn test_layer_toggles_render_backdrop() {
+        let mut ppu = Ppu::new();
+        assert!(ppu.background_visible());
+        assert!(ppu.sprites_visible());
+
+        ppu.set_background_visible(false);
+        ppu.set_sprites_visible(false);
+        assert!(!ppu.background_visible());
+        assert!(!ppu.sprites_visible());
+
+        ppu.mask.insert(PpuMask::BG_ENABLE);
+        ppu.mask.insert(PpuMask::SPRITE_ENABLE);
+        ppu.palette[0] = 0x21;
+
+        // Run until a full frame has been rendered
+        let start = ppu.get_frame();
+        while ppu.get_frame() < start + 2 {
+            ppu.step(None);
+        }
+
+        let expected = PALETTE_COLORS[0x21];
+        let buffer = ppu.get_buffer();
+        assert_eq!(buffer[0], expected.0);
+        assert_eq!(buffer[1], expected.1);
+        assert_eq!(buffer[2], expected.2);
+    }
+
+    #[test]
+    fn test_vs_rp2c04_palette_is_remapped() {
+        let mut ppu = Ppu::new();
+        ppu.set_vs_ppu(VsPpu::Rp2c04_0004);
+        ppu.mask.insert(PpuMask::BG_ENABLE);
+        // RP2C04-0004 entry $00 is 2C02 color $18
+        ppu.palette[0] = 0x00;
+
+        let start = ppu.get_frame();
+        while ppu.get_frame() < start + 2 {
+            ppu.step(None);
+        }
+
+        let expected = PALETTE_COLORS[0x18];
+        let buffer = ppu.get_buffer();
+        assert_eq!((buffer[0], buffer[1], buffer[2]), expected);
+    }
+
+    #[test]
+    fn test_vs_rc2c05_swaps_ctrl_mask_and_reports_id() {
+        let mut ppu = Ppu::new();
+        ppu.set_vs_ppu(VsPpu::Rc2c05_03);
+
+        ppu.write_register(0x2000, 0x18, None);
+        assert_eq!(ppu.mask.bits(), 0x18);
+        assert!(ppu.control.is_empty());
+        ppu.write_register(0x2001, 0x80, None);
+        assert!(ppu.control.contains(PpuControl::NMI_ENABLE));
+
+        assert_eq!(ppu.read_register(0x2002, None) & 0x1F, 0x1C);
+    }
+
+    #[test]
+    fn test_power_on_state_fills_palette_oam_and_vram() {
+        let mut ppu = Ppu::new();
+        ppu.apply_power_on_state(PowerOnState::Pattern);
+        assert_eq!(ppu.palette[0], 0x09);
+        assert_eq!(ppu.palette[31], 0x08);
+        assert_eq!(ppu.nametable[1][3], 0x00);
+        assert_eq!(ppu.nametable[1][4], 0xFF);
+        assert_eq!(ppu.oam[0], 0xFF);
+
+        ppu.apply_power_on_state(PowerOnState::Random(1234));
+        let palette = ppu.palette;
+        let nametable = ppu.nametable;
+        assert!(palette.iter().all(|&entry| entry < 0x40));
+        ppu.apply_power_on_state(PowerOnState::Random(1234));
+        assert_eq!(ppu.palette, palette);
+        assert_eq!(ppu.nametable, nametable);
+        assert_ne!(ppu.nametable[0], ppu.nametable[1]);
+
+        ppu.apply_power_on_state(PowerOnState::Clean);
+        assert_eq!(ppu.palette, [0x0F; 32]);
+        assert!(ppu.nametable.iter().flatten().all(|&b| b == 0));
+        assert!(ppu.oam.iter().all(|&b| b == 0xFF));
+    }
+
+    fn step_to(ppu: &mut Ppu, scanline: i16, cycle: u16) {
+        while ppu.get_scanline() != scanline || ppu.get_cycle() != cycle {
+            ppu.step(None);
+        }
+    }
+
+    fn write_backdrop(ppu: &mut Ppu, color: u8) {
+        ppu.write_register(0x2006, 0x3F, None);
+        ppu.write_register(0x2006, 0x00, None);
+        ppu.write_register(0x2007, color, None);
+    }
+
+    fn pixel(ppu: &Ppu, x: usize, y: usize) -> (u8, u8, u8) {
+        let i = (y * 256 + x) * 3;
+        let buffer = ppu.get_buffer();
+        (buffer[i], buffer[i + 1], buffer[i + 2])
+    }
+
+    #[test]
+    fn test_mid_frame_palette_and_emphasis_split() {
+        let mut ppu = Ppu::new();
+        write_backdrop(&mut ppu, 0x21);
+        ppu.write_register(0x2001, 0x0A, None);
+
+        // Status-bar style split: blank rendering in hblank, swap the
+        // backdrop color, turn rendering back on.
+        step_to(&mut ppu, 100, 300);
+        ppu.write_register(0x2001, 0x00, None);
+        write_backdrop(&mut ppu, 0x16);
+        ppu.write_register(0x2001, 0x0A, None);
+
+        // Grayscale + red emphasis switched on mid-scanline
+        step_to(&mut ppu, 200, 129);
+        ppu.write_register(0x2001, 0x2B, None);
+        step_to(&mut ppu, 240, 0);
+
+        assert_eq!(pixel(&ppu, 10, 100), PALETTE_COLORS[0x21]);
+        assert_eq!(pixel(&ppu, 10, 101), PALETTE_COLORS[0x16]);
+        assert_eq!(pixel(&ppu, 10, 200), PALETTE_COLORS[0x16]);
+        let tinted = pixel(&ppu, 200, 200);
+        let gray = PALETTE_COLORS[0x10];
+        assert_eq!(tinted.0, gray.0);
+        assert!(tinted.1 < gray.1 && tinted.2 < gray.2);
+        assert_eq!(pixel(&ppu, 10, 201), tinted);
+        assert_eq!(ppu.get_indexed_buffer()[100 * 256 + 10], 0x21);
+        assert_eq!(ppu.get_indexed_buffer()[200 * 256 + 200], 0x10);
+        assert_eq!(ppu.get_emphasis_buffer()[200 * 256 + 200], 1);
+        assert_eq!(ppu.get_emphasis_buffer()[200 * 256 + 10], 0);
+        assert_eq!(ppu.get_raw_buffer()[200 * 256 + 200], 0x050);
+        let rgba = ppu.get_rgba_buffer();
+        assert_eq!(
+            rgba[(200 * 256 + 200) * 4..][..4],
+            [tinted.0, tinted.1, tinted.2, 0xFF]
+        );
+    }
+
+    #[test]
+    fn test_forced_blank_shows_palette_entry_under_v() {
+        let mut ppu = Ppu::new();
+        write_backdrop(&mut ppu, 0x0F);
+        ppu.palette[5] = 0x21;
+        ppu.palette[4] = 0x16;
+
+        // Rendering off with v parked on $3F05: that entry is what the TV shows
+        step_to(&mut ppu, 10, 0);
+        ppu.write_register(0x2006, 0x3F, None);
+        ppu.write_register(0x2006, 0x05, None);
+        // $3F14 mirrors $3F04
+        step_to(&mut ppu, 20, 0);
+        ppu.write_register(0x2006, 0x3F, None);
+        ppu.write_register(0x2006, 0x14, None);
+        // Outside palette RAM the backdrop is back
+        step_to(&mut ppu, 30, 0);
+        ppu.write_register(0x2006, 0x20, None);
+        ppu.write_register(0x2006, 0x00, None);
+        step_to(&mut ppu, 240, 0);
+
+        let indexed = ppu.get_indexed_buffer();
+        assert_eq!(indexed[15 * 256 + 100], 0x21);
+        assert_eq!(indexed[25 * 256 + 100], 0x16);
+        assert_eq!(indexed[35 * 256 + 100], 0x0F);
+    }
+
+    #[test]
+    fn test_mask_enable_bits_apply_mid_scanline() {
+        // Tile 0 is solid color 1 everywhere
+        let mut chr = vec![0u8; 16];
+        chr[..8].fill(0xFF);
+        let cart = nrom_cartridge_with_chr(&chr);
+        let mut ppu = Ppu::new();
+        write_backdrop(&mut ppu, 0x0F);
+        ppu.palette[1] = 0x21;
+        ppu.write_register(0x2006, 0x00, None);
+        ppu.write_register(0x2006, 0x00, None);
+        ppu.write_register(0x2001, 0x0A, None);
+
+        // BG off at dot 129 (x=128) of scanline 50, back on at x=64 of 60
+        while ppu.get_scanline() != 50 || ppu.get_cycle() != 129 {
+            ppu.step(Some(&cart));
+        }
+        ppu.write_register(0x2001, 0x00, None);
+        while ppu.get_scanline() != 60 || ppu.get_cycle() != 65 {
+            ppu.step(Some(&cart));
+        }
+        ppu.write_register(0x2001, 0x0A, None);
+        while ppu.get_scanline() != 240 {
+            ppu.step(Some(&cart));
+        }
+
+        let indexed = ppu.get_indexed_buffer();
+        assert_eq!(indexed[50 * 256 + 127], 0x21);
+        assert_eq!(indexed[50 * 256 + 128], 0x0F);
+        assert_eq!(indexed[60 * 256 + 63], 0x0F);
+        assert_eq!(indexed[60 * 256 + 64], 0x21);
+    }
+
+    fn nrom_cartridge_with_chr(chr: &[u8]) -> crate::cartridge::Cartridge {
+        let mut rom = vec![b'N', b'E', b'S', 0x1A, 1, 1, 0, 0];
+        rom.resize(16, 0);
+        rom.resize(16 + 16384, 0xEA);
+        let mut chr_rom = chr.to_vec();
+        chr_rom.resize(8192, 0);
+        rom.extend_from_slice(&chr_rom);
+        crate::test_support::load_rom_image(&rom).unwrap()
+    }
+
+    #[test]
+    fn test_pixel_provenance_records_bg_and_sprite() {
+        // Tile 1: every row uses color 1 (low plane set)
+        let mut chr = vec![0u8; 32];
+        for row in 0..8 {
+            chr[16 + row] = 0xFF;
+        }
+        let cart = nrom_cartridge_with_chr(&chr);
+
+        let mut ppu = Ppu::new();
+        assert_eq!(ppu.pixel_provenance(0, 0), None);
+        ppu.set_provenance_enabled(true);
+        assert!(ppu.provenance_enabled());
+        assert_eq!(ppu.pixel_provenance(256, 0), None);
+
+        // BG + sprites, including the leftmost 8 pixels
+        ppu.write_register(0x2001, 0x1E, None);
+        ppu.nametable[0][0] = 1;
+        ppu.palette[1] = 0x16;
+        ppu.palette[17] = 0x2A;
+        ppu.oam[4] = 9; // sprite 1 covers scanlines 10-17
+        ppu.oam[5] = 1;
+        ppu.oam[6] = 0x01; // palette line 1 (sprite)
+        ppu.oam[7] = 40;
+
+        let start = ppu.get_frame();
+        while ppu.get_frame() < start + 2 {
+            ppu.step(Some(&cart));
+        }
+
+        let bg = ppu.pixel_provenance(3, 2).unwrap();
+        assert_eq!(bg.nametable_addr, Some(0x2000));
+        assert_eq!(bg.tile_index, 1);
+        assert_eq!(bg.pattern_table, 0x0000);
+        assert_eq!(bg.palette_index, 1);
+        assert_eq!(bg.oam_index, None);
+
+        let backdrop = ppu.pixel_provenance(12, 2).unwrap();
+        assert_eq!(backdrop.nametable_addr, Some(0x2001));
+        assert_eq!(backdrop.palette_index, 0);
+
+        let sprite = ppu.pixel_provenance(42, 12).unwrap();
+        assert_eq!(sprite.oam_index, Some(1));
+        assert_eq!(sprite.tile_index, 1);
+        assert_eq!(sprite.palette_index, 21);
+
+        ppu.set_provenance_enabled(false);
+        assert_eq!(ppu.pixel_provenance(3, 2), None);
+    }
+
+    #[test]
+    fn test_sprite_limit_can_be_lifted_without_changing_overflow() {
+        let mut chr = vec![0u8; 32];
+        for row in 0..8 {
+            chr[16 + row] = 0xFF;
+        }
+        let cart = nrom_cartridge_with_chr(&chr);
+
+        for limit in [true, false] {
+            let mut ppu = Ppu::new();
+            ppu.set_sprite_limit(limit);
+            assert_eq!(ppu.sprite_limit(), limit);
+            ppu.write_register(0x2001, 0x1E, None);
+            ppu.palette[17] = 0x2A;
+            // Nine sprites side by side on scanlines 20-27
+            for i in 0..9 {
+                ppu.oam[i * 4] = 19;
+                ppu.oam[i * 4 + 1] = 1;
+                ppu.oam[i * 4 + 2] = 0;
+                ppu.oam[i * 4 + 3] = i as u8 * 16;
+            }
+
+            while ppu.get_scanline() != 30 {
+                ppu.step(Some(&cart));
+            }
+            assert!(ppu.status.contains(PpuStatus::SPRITE_OVERFLOW));
+            assert_eq!(pixel(&ppu, 7 * 16 + 2, 22), PALETTE_COLORS[0x2A]);
+            let ninth = pixel(&ppu, 8 * 16 + 2, 22);
+            assert_eq!(ninth == PALETTE_COLORS[0x2A], !limit);
+        }
+    }
+
+    #[test]
+    fn test_oam_quirks_decay_and_oamaddr_behaviour() {
+        for quirks in [false, true] {
+            let mut ppu = Ppu::new();
+            ppu.set_oam_quirks(quirks);
+            assert_eq!(ppu.oam_quirks(), quirks);
+            for i in 0..256 {
+                ppu.write_oam_data(i as u8, i as u8);
+            }
+
+            // Forced blanking for a frame: rows decay, except one kept
+            // alive by $2004 reads every 20 scanlines
+            for _ in 0..13 {
+                for _ in 0..20 * 341 {
+                    ppu.step(None);
+                }
+                ppu.write_register(0x2003, 0x20, None);
+                ppu.read_register(0x2004, None);
+            }
+            ppu.write_register(0x2003, 0x10, None);
+            let decayed = ppu.read_register(0x2004, None);
+            assert_eq!(decayed, if quirks { 0xFF } else { 0x10 });
+            ppu.write_register(0x2003, 0x21, None);
+            assert_eq!(ppu.read_register(0x2004, None), 0x21);
+
+            // Rendering starts with OAMADDR = $21: row $20 lands on 0-7
+            ppu.write_register(0x2001, 0x18, None);
+            step_to(&mut ppu, -1, 10);
+            assert_eq!(ppu.oam[0x03], if quirks { 0x23 } else { 0x03 });
+
+            // $2004 writes while rendering skip a sprite without writing
+            step_to(&mut ppu, 10, 100);
+            ppu.write_register(0x2003, 0x40, None);
+            ppu.write_register(0x2004, 0xAA, None);
+            // (the row itself decayed during the blanking above)
+            assert_eq!(ppu.oam[0x40], if quirks { 0xFF } else { 0xAA });
+            assert_eq!(ppu.get_oam_addr(), if quirks { 0x44 } else { 0x41 });
+            step_to(&mut ppu, 10, 300);
+            assert_eq!(ppu.get_oam_addr(), if quirks { 0x00 } else { 0x41 });
+        }
+    }
+
+    #[test]
+    fn test_oamdata_reads_follow_sprite_evaluation_while_rendering() {
+        let mut ppu = Ppu::new();
+        // Sprites 0 and 2 are in range of line 10, sprite 1 is not
+        let sprites = [
+            [5, 0x11, 0x01, 40],
+            [100, 0x22, 0x02, 50],
+            [8, 0x33, 0x03, 60],
+        ];
+        for (i, sprite) in sprites.iter().flatten().enumerate() {
+            ppu.write_oam_data(i as u8, *sprite);
+        }
+        for i in 12..256 {
+            ppu.write_oam_data(i as u8, 0xF0);
+        }
+        ppu.write_register(0x2003, 0x04, None);
+        assert_eq!(ppu.read_register(0x2004, None), 100);
+
+        ppu.write_register(0x2001, 0x18, None);
+        let mut read_at = |cycle| {
+            step_to(&mut ppu, 10, cycle);
+            ppu.read_register(0x2004, None)
+        };
+        assert_eq!(read_at(30), 0xFF);
+        // Sprite 0 copied over dots 65-72, sprite 1's Y checked on 73-74
+        assert_eq!(read_at(65), 5);
+        assert_eq!(read_at(68), 0x11);
+        assert_eq!(read_at(72), 40);
+        assert_eq!(read_at(74), 100);
+        assert_eq!(read_at(77), 0x33);
+        // Fetches: slot 0 = sprite 0 (X repeats), slot 1 = sprite 2, then $FF
+        assert_eq!(read_at(258), 0x11);
+        assert_eq!(read_at(263), 40);
+        assert_eq!(read_at(265), 8);
+        assert_eq!(read_at(273), 0xFF);
+        assert_eq!(read_at(330), 5);
+    }
+
+    #[test]
+    fn test_reset_warmup_ignores_early_writes() {
+        let mut ppu = Ppu::new();
+        ppu.write_register(0x2000, 0x80, None);
+        ppu.write_register(0x2005, 0x08, None);
+        ppu.reset(true);
+        assert!(ppu.control.is_empty());
+        assert!(!ppu.w);
+
+        ppu.write_register(0x2000, 0x80, None);
+        ppu.write_register(0x2001, 0x1E, None);
+        ppu.write_register(0x2006, 0x21, None);
+        assert!(ppu.control.is_empty());
+        assert!(ppu.mask.is_empty());
+        assert!(!ppu.w);
+        // OAM and VRAM data writes still go through
+        ppu.write_register(0x2003, 0x10, None);
+        ppu.write_register(0x2004, 0x42, None);
+        assert_eq!(ppu.oam[0x10], 0x42);
+
+        for _ in 0..WARMUP_CPU_CYCLES * 3 {
+            ppu.step(None);
+        }
+        assert!(!ppu.warming_up());
+        ppu.write_register(0x2000, 0x80, None);
+        assert!(ppu.control.contains(PpuControl::NMI_ENABLE));
+
+        ppu.reset(false);
+        ppu.write_register(0x2001, 0x1E, None);
+        assert_eq!(ppu.mask.bits(), 0x1E);
+    }
+
+    #[test]
+    fn test_threaded_rendering_matches_inline_output() {
+        let chr: Vec<u8> = (0..0x2000u32).map(|i| (i * 37 + i / 7) as u8).collect();
+        let cart = nrom_cartridge_with_chr(&chr);
+        let run = |threaded: bool| {
+            let mut ppu = Ppu::new();
+            ppu.set_threaded_rendering(threaded);
+            for (i, color) in [0x21, 0x16, 0x2A, 0x12].into_iter().enumerate() {
+                ppu.palette[i] = color;
+            }
+            for (i, byte) in ppu.nametable[0].iter_mut().enumerate() {
+                *byte = (i * 5) as u8;
+            }
+            ppu.write_register(0x2001, 0x0A, None);
+            while ppu.get_scanline() != 120 || ppu.get_cycle() != 129 {
+                ppu.step(Some(&cart));
+            }
+            ppu.write_register(0x2001, 0x4B, None);
+            ppu.frame_complete = false;
+            while !ppu.frame_complete {
+                ppu.step(Some(&cart));
+            }
+            ppu.get_buffer().to_vec()
+        };
+        let inline = run(false);
+        assert_eq!(run(true), inline);
+        assert_ne!(inline[..768], inline[200 * 768..201 * 768]);
+    }
+
+    #[test]
+    fn test_loopy_registers_and_data_port_increments() {
+        let mut ppu = Ppu::new();
+        // The worked example from the loopy scrolling notes
+        ppu.write_register(0x2000, 0x00, None);
+        ppu.read_register(0x2002, None);
+        ppu.write_register(0x2005, 0x7D, None);
+        assert_eq!((ppu.get_t(), ppu.get_x_scroll()), (0x000F, 0x05));
+        ppu.write_register(0x2005, 0x5E, None);
+        assert_eq!(ppu.get_t(), 0x616F);
+        // $2006 shares the toggle; its first write clears t bit 14
+        ppu.write_register(0x2006, 0x3D, None);
+        assert_eq!(ppu.get_t(), 0x3D6F);
+        ppu.write_register(0x2006, 0xF0, None);
+        assert_eq!(ppu.get_vram_addr(), 0x3DF0);
+        assert_eq!(ppu.get_x_scroll(), 0x05);
+
+        // v is 15 bits: the increment carries into fine Y and only the low
+        // 14 bits address VRAM
+        ppu.v = 0x3FFF;
+        ppu.write_register(0x2007, 0x00, None);
+        assert_eq!(ppu.get_vram_addr(), 0x4000);
+        assert_eq!(ppu.data_port_addr(), 0x0000);
+
+        // While rendering, an access bumps coarse X and Y instead
+        ppu.write_register(0x2001, 0x08, None);
+        ppu.scanline = 10;
+        ppu.v = 0x001F;
+        ppu.read_register(0x2007, None);
+        assert_eq!(ppu.get_vram_addr(), 0x1400);
+    }
 }
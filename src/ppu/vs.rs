@@ -0,0 +1,107 @@
+/// Vs. System PPU variants. The RP2C04 chips use scrambled palettes and the
+/// RC2C05 chips swap $2000/$2001 and report an ID in the low bits of $2002.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VsPpu {
+    /// RP2C03/RC2C03: standard NES palette order.
+    #[default]
+    Rp2c03,
+    Rp2c04_0001,
+    Rp2c04_0002,
+    Rp2c04_0003,
+    Rp2c04_0004,
+    Rc2c05_01,
+    Rc2c05_02,
+    Rc2c05_03,
+    Rc2c05_04,
+}
+
+// RP2C04 palette index -> equivalent 2C02 palette index.
+const RP2C04_0001_LUT: [u8; 64] = [
+    0x35, 0x23, 0x16, 0x22, 0x1C, 0x09, 0x1D, 0x15, 0x20, 0x00, 0x27, 0x05, 0x04, 0x28, 0x08, 0x20,
+    0x21, 0x3E, 0x1F, 0x29, 0x3C, 0x32, 0x36, 0x12, 0x3F, 0x2B, 0x2E, 0x1E, 0x3D, 0x2D, 0x24, 0x01,
+    0x0E, 0x31, 0x33, 0x2A, 0x2C, 0x0C, 0x1B, 0x14, 0x2E, 0x07, 0x34, 0x06, 0x13, 0x02, 0x26, 0x2E,
+    0x2E, 0x19, 0x10, 0x0A, 0x39, 0x03, 0x37, 0x17, 0x0F, 0x11, 0x0B, 0x0D, 0x38, 0x25, 0x18, 0x3A,
+];
+const RP2C04_0002_LUT: [u8; 64] = [
+    0x2E, 0x27, 0x18, 0x39, 0x3A, 0x25, 0x1C, 0x31, 0x16, 0x13, 0x38, 0x34, 0x20, 0x23, 0x3C, 0x0B,
+    0x0F, 0x21, 0x06, 0x3D, 0x1B, 0x29, 0x1E, 0x22, 0x1D, 0x24, 0x0E, 0x2B, 0x32, 0x08, 0x2E, 0x03,
+    0x04, 0x36, 0x26, 0x33, 0x11, 0x1F, 0x10, 0x02, 0x14, 0x3F, 0x00, 0x09, 0x12, 0x2E, 0x28, 0x20,
+    0x3E, 0x0D, 0x2A, 0x17, 0x0C, 0x01, 0x15, 0x19, 0x2E, 0x2C, 0x07, 0x37, 0x35, 0x05, 0x0A, 0x2D,
+];
+const RP2C04_0003_LUT: [u8; 64] = [
+    0x14, 0x25, 0x3A, 0x10, 0x0B, 0x20, 0x31, 0x09, 0x01, 0x2E, 0x36, 0x08, 0x15, 0x3D, 0x3E, 0x3C,
+    0x22, 0x1C, 0x05, 0x12, 0x19, 0x18, 0x17, 0x1B, 0x00, 0x03, 0x2E, 0x02, 0x16, 0x06, 0x34, 0x35,
+    0x23, 0x0F, 0x0E, 0x37, 0x0D, 0x27, 0x26, 0x20, 0x29, 0x04, 0x21, 0x24, 0x11, 0x2D, 0x2E, 0x1F,
+    0x2C, 0x1E, 0x39, 0x33, 0x07, 0x2A, 0x28, 0x1D, 0x0A, 0x2E, 0x32, 0x38, 0x13, 0x2B, 0x3F, 0x0C,
+];
+const RP2C04_0004_LUT: [u8; 64] = [
+    0x18, 0x03, 0x1C, 0x28, 0x2E, 0x35, 0x01, 0x17, 0x10, 0x1F, 0x2A, 0x0E, 0x36, 0x37, 0x0B, 0x39,
+    0x25, 0x1E, 0x12, 0x34, 0x2E, 0x1D, 0x06, 0x26, 0x3E, 0x1B, 0x22, 0x19, 0x04, 0x2E, 0x3A, 0x21,
+    0x05, 0x0A, 0x07, 0x02, 0x13, 0x14, 0x00, 0x15, 0x0C, 0x3D, 0x11, 0x0F, 0x0D, 0x38, 0x2D, 0x24,
+    0x33, 0x20, 0x08, 0x16, 0x3F, 0x2B, 0x20, 0x3C, 0x2E, 0x27, 0x23, 0x31, 0x29, 0x32, 0x2C, 0x09,
+];
+
+impl VsPpu {
+    /// Decode the NES 2.0 Vs. PPU type nibble (header byte 13, low nibble).
+    pub fn from_nes2(ppu_type: u8) -> Option<Self> {
+        match ppu_type {
+            0 | 1 | 6 | 7 => Some(VsPpu::Rp2c03),
+            2 => Some(VsPpu::Rp2c04_0001),
+            3 => Some(VsPpu::Rp2c04_0002),
+            4 => Some(VsPpu::Rp2c04_0003),
+            5 => Some(VsPpu::Rp2c04_0004),
+            8 => Some(VsPpu::Rc2c05_01),
+            9 => Some(VsPpu::Rc2c05_02),
+            10 => Some(VsPpu::Rc2c05_03),
+            11 => Some(VsPpu::Rc2c05_04),
+            _ => None,
+        }
+    }
+
+    /// Parse a chip name as used on the command line (e.g. `2c04-0004`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        let name = name.trim_start_matches("rp").trim_start_matches("rc");
+        match name {
+            "2c02" | "2c03" => Some(VsPpu::Rp2c03),
+            "2c04-0001" | "2c04-1" => Some(VsPpu::Rp2c04_0001),
+            "2c04-0002" | "2c04-2" => Some(VsPpu::Rp2c04_0002),
+            "2c04-0003" | "2c04-3" => Some(VsPpu::Rp2c04_0003),
+            "2c04-0004" | "2c04-4" => Some(VsPpu::Rp2c04_0004),
+            "2c05-01" | "2c05-1" => Some(VsPpu::Rc2c05_01),
+            "2c05-02" | "2c05-2" => Some(VsPpu::Rc2c05_02),
+            "2c05-03" | "2c05-3" => Some(VsPpu::Rc2c05_03),
+            "2c05-04" | "2c05-4" => Some(VsPpu::Rc2c05_04),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn palette_lut(self) -> Option<&'static [u8; 64]> {
+        match self {
+            VsPpu::Rp2c04_0001 => Some(&RP2C04_0001_LUT),
+            VsPpu::Rp2c04_0002 => Some(&RP2C04_0002_LUT),
+            VsPpu::Rp2c04_0003 => Some(&RP2C04_0003_LUT),
+            VsPpu::Rp2c04_0004 => Some(&RP2C04_0004_LUT),
+            _ => None,
+        }
+    }
+
+    /// RC2C05 chips decode $2000 and $2001 swapped.
+    pub(crate) fn swaps_ctrl_mask(self) -> bool {
+        matches!(
+            self,
+            VsPpu::Rc2c05_01 | VsPpu::Rc2c05_02 | VsPpu::Rc2c05_03 | VsPpu::Rc2c05_04
+        )
+    }
+
+    /// ID ORed into the low bits of $2002; games use it as copy protection.
+    pub(crate) fn status_id(self) -> u8 {
+        match self {
+            VsPpu::Rc2c05_01 | VsPpu::Rc2c05_04 => 0x1B,
+            VsPpu::Rc2c05_02 => 0x3D,
+            VsPpu::Rc2c05_03 => 0x1C,
+            _ => 0,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+// Practice mode: a save state is taken whenever a watched RAM trigger fires
+// (typically `ram[$075C] changes` on a room or level ID), and one key puts
+// the game back at the start of the current room. The state is kept in
+// memory and replaced on every new room; reloading re-arms the trigger so
+// going back doesn't count as entering a room.
+use crate::input_script::RamTrigger;
+use crate::save_state::SaveState;
+use crate::Nes;
+
+pub struct PracticeMode {
+    trigger: RamTrigger,
+    state: SaveState,
+    has_state: bool,
+    frame: usize,
+}
+
+impl PracticeMode {
+    /// `trigger` in `RamTrigger` syntax, e.g. `ram[$075C] changes`.
+    pub fn parse(trigger: &str) -> Option<Self> {
+        Some(PracticeMode {
+            trigger: RamTrigger::parse(trigger)?,
+            state: SaveState::default(),
+            has_state: false,
+            frame: 0,
+        })
+    }
+
+    /// Check the trigger at the start of an emulated frame, taking a state
+    /// when it fires. Returns whether one was taken.
+    pub fn frame(&mut self, nes: &Nes) -> bool {
+        let fired = self.trigger.fired(self.frame, nes);
+        self.frame += 1;
+        if fired {
+            nes.capture_state_into(&mut self.state);
+            self.has_state = true;
+        }
+        fired
+    }
+
+    pub fn has_state(&self) -> bool {
+        self.has_state
+    }
+
+    /// Go back to the last room's state. Returns `false` when no room has
+    /// been entered yet.
+    pub fn reload(&mut self, nes: &mut Nes) -> Result<bool, Box<dyn std::error::Error>> {
+        if !self.has_state {
+            return Ok(false);
+        }
+        nes.restore_state(&self.state)?;
+        self.trigger.rearm(self.frame, nes);
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_on_room_change_and_reloads_without_retriggering() {
+        let mut nes = Nes::new();
+        let mut practice = PracticeMode::parse("ram[$0040] changes").unwrap();
+        assert!(!practice.frame(&nes));
+        assert!(!practice.reload(&mut nes).unwrap());
+
+        nes.ram_mut()[0x40] = 1;
+        nes.ram_mut()[0x41] = 9;
+        assert!(practice.frame(&nes));
+        // Play on in the room, then lose
+        nes.ram_mut()[0x41] = 3;
+        assert!(!practice.frame(&nes));
+
+        assert!(practice.reload(&mut nes).unwrap());
+        assert_eq!(nes.ram()[0x41], 9);
+        assert!(!practice.frame(&nes));
+
+        // Next room replaces the state
+        nes.ram_mut()[0x40] = 2;
+        assert!(practice.frame(&nes));
+        nes.ram_mut()[0x40] = 7;
+        practice.reload(&mut nes).unwrap();
+        assert_eq!(nes.ram()[0x40], 2);
+    }
+}
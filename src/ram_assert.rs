@@ -0,0 +1,118 @@
+// RAM assertions for headless regression runs: `ram:$00FE==0x5A@frame=600`
+// checks a byte once 600 frames have run; without `@frame=` the check runs
+// when the run ends. The comparison is an input-script `Condition`, so
+// `!=`, `<`, `>=` etc. and PRG-RAM ($6000-$7FFF) work the same way.
+use crate::input_script::Condition;
+use crate::Nes;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RamAssertion {
+    pub condition: Condition,
+    /// Frames run before checking; `None` checks at the end of the run.
+    pub frame: Option<usize>,
+    spec: String,
+}
+
+/// The outcome of one assertion, printable as a PASS/FAIL line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssertionResult {
+    pub spec: String,
+    pub frame: usize,
+    pub passed: bool,
+    /// Value of the checked byte (or frame) at check time.
+    pub actual: u64,
+}
+
+impl fmt::Display for AssertionResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = if self.passed { "PASS" } else { "FAIL" };
+        write!(f, "{} {} at frame {}", verdict, self.spec, self.frame)?;
+        if !self.passed {
+            write!(f, " (actual {} / ${:02X})", self.actual, self.actual)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for RamAssertion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.spec)
+    }
+}
+
+impl RamAssertion {
+    /// Parse `<condition>[@frame=<N>]`, e.g. `ram:$00FE==0x5A@frame=600`.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let spec = spec.trim();
+        let (condition, frame) = match spec.split_once('@') {
+            Some((condition, at)) => {
+                let frame = at.trim().strip_prefix("frame=")?.trim().parse().ok()?;
+                (condition, Some(frame))
+            }
+            None => (spec, None),
+        };
+        Some(RamAssertion {
+            condition: Condition::parse(condition)?,
+            frame,
+            spec: spec.to_string(),
+        })
+    }
+
+    /// Assertions from a file, one per line; blank lines and `#` comments
+    /// are skipped. Errors name the offending line.
+    pub fn parse_file(source: &str) -> Result<Vec<Self>, String> {
+        source
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.split('#').next().unwrap_or("").trim()))
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(number, line)| {
+                Self::parse(line)
+                    .ok_or_else(|| format!("line {}: invalid assertion '{}'", number, line))
+            })
+            .collect()
+    }
+
+    /// Check against the machine after `frame` frames have run.
+    pub fn check(&self, frame: usize, nes: &Nes) -> AssertionResult {
+        AssertionResult {
+            spec: self.spec.clone(),
+            frame,
+            passed: self.condition.holds(frame, nes),
+            actual: self.condition.current(frame, nes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_frames_and_reports_actual_values() {
+        let at_frame = RamAssertion::parse("ram:$00FE==0x5A@frame=600").unwrap();
+        assert_eq!(at_frame.frame, Some(600));
+        let at_end = RamAssertion::parse("ram[$0010] != 0").unwrap();
+        assert_eq!(at_end.frame, None);
+        assert!(RamAssertion::parse("ram:$00FE==0x5A@600").is_none());
+        assert!(RamAssertion::parse("ram:$4016==1").is_none());
+
+        let mut nes = Nes::new();
+        nes.ram_mut()[0xFE] = 0x5A;
+        assert!(at_frame.check(600, &nes).passed);
+        nes.ram_mut()[0xFE] = 0x12;
+        assert_eq!(
+            at_frame.check(600, &nes).to_string(),
+            "FAIL ram:$00FE==0x5A@frame=600 at frame 600 (actual 18 / $12)"
+        );
+
+        let file =
+            RamAssertion::parse_file("# lives\nram:$075A==2@frame=10\n\nframe>=0\n").unwrap();
+        assert_eq!(file.len(), 2);
+        assert_eq!(
+            RamAssertion::parse_file("ram:$00==1\nbogus").unwrap_err(),
+            "line 2: invalid assertion 'bogus'"
+        );
+    }
+}
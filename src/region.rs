@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+/// Console timing: clock rates, frame length and the APU tables that
+/// depend on them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Region {
+    #[default]
+    Ntsc,
+    Pal,
+}
+
+impl Region {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "ntsc" => Some(Region::Ntsc),
+            "pal" => Some(Region::Pal),
+            _ => None,
+        }
+    }
+
+    /// Decode NES 2.0 byte 12. Multi-region dumps run as NTSC; Dendy has
+    /// no timing of its own here and runs as PAL (also 50Hz).
+    pub fn from_nes2(timing: u8) -> Self {
+        match timing & 0x03 {
+            1 | 3 => Region::Pal,
+            _ => Region::Ntsc,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Region::Ntsc => "NTSC",
+            Region::Pal => "PAL",
+        }
+    }
+
+    pub fn cpu_clock_rate(self) -> f32 {
+        match self {
+            Region::Ntsc => 1_789_773.0,
+            Region::Pal => 1_662_607.0,
+        }
+    }
+
+    /// Wall-clock length of one frame (60.0988Hz NTSC, 50.0070Hz PAL).
+    pub fn frame_duration(self) -> Duration {
+        match self {
+            Region::Ntsc => crate::frame_pacer::NTSC_FRAME_DURATION,
+            Region::Pal => Duration::from_nanos(19_997_200),
+        }
+    }
+
+    /// Last vblank scanline before the pre-render line.
+    pub(crate) fn last_vblank_scanline(self) -> i16 {
+        match self {
+            Region::Ntsc => 260,
+            Region::Pal => 310,
+        }
+    }
+
+    /// PPU dots per CPU cycle, as a ratio: PAL runs 16 dots per 5 cycles.
+    pub(crate) fn ppu_dots_per_cpu_cycles(self) -> (u32, u32) {
+        match self {
+            Region::Ntsc => (3, 1),
+            Region::Pal => (16, 5),
+        }
+    }
+
+    /// Frame sequencer steps in CPU cycles: three quarter/half frame clocks,
+    /// the end of 4-step mode and the end of 5-step mode.
+    pub(crate) fn frame_counter_steps(self) -> [u16; 5] {
+        match self {
+            Region::Ntsc => [7457, 14913, 22371, 29829, 37281],
+            Region::Pal => [8313, 16627, 24939, 33253, 41565],
+        }
+    }
+
+    pub(crate) fn noise_periods(self) -> &'static [u16; 16] {
+        match self {
+            Region::Ntsc => &NTSC_NOISE_PERIODS,
+            Region::Pal => &PAL_NOISE_PERIODS,
+        }
+    }
+
+    pub(crate) fn dmc_rates(self) -> &'static [u16; 16] {
+        match self {
+            Region::Ntsc => &NTSC_DMC_RATES,
+            Region::Pal => &PAL_DMC_RATES,
+        }
+    }
+}
+
+const NTSC_NOISE_PERIODS: [u16; 16] = [
+    4, 8, 16, 32, 64, 96, 128, 160, 202, 254, 380, 508, 762, 1016, 2034, 4068,
+];
+const PAL_NOISE_PERIODS: [u16; 16] = [
+    4, 8, 14, 30, 60, 88, 118, 148, 188, 236, 354, 472, 708, 944, 1890, 3778,
+];
+const NTSC_DMC_RATES: [u16; 16] = [
+    428, 380, 340, 320, 286, 254, 226, 214, 190, 160, 142, 128, 106, 85, 72, 54,
+];
+const PAL_DMC_RATES: [u16; 16] = [
+    398, 354, 316, 298, 276, 236, 210, 198, 176, 148, 132, 118, 98, 78, 66, 50,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_names_and_nes2_timing() {
+        assert_eq!(Region::from_name("PAL"), Some(Region::Pal));
+        assert_eq!(Region::from_name("ntsc"), Some(Region::Ntsc));
+        assert_eq!(Region::from_name("secam"), None);
+        assert_eq!(Region::from_nes2(0), Region::Ntsc);
+        assert_eq!(Region::from_nes2(1), Region::Pal);
+        assert_eq!(Region::from_nes2(2), Region::Ntsc);
+    }
+}
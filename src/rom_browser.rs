@@ -0,0 +1,103 @@
+// In-window ROM browser: the ROMs found in the ROM directories, drawn over
+// the running game (or the boot screen) and picked with the arrow keys.
+use crate::hud_toast::draw_hud_label_rgb24;
+
+const PANEL_COLOR: [u8; 3] = [0x10, 0x10, 0x10];
+const TITLE_COLOR: [u8; 3] = [0xF8, 0xB8, 0x00];
+const TEXT_COLOR: [u8; 3] = [0xFF, 0xFF, 0xFF];
+const LINE_HEIGHT: usize = 9;
+const VISIBLE_ROWS: usize = 20;
+const NAME_CHARS: usize = 40;
+
+pub struct RomBrowser {
+    /// `(name, path)`: the name shown and the file loaded.
+    entries: Vec<(String, String)>,
+    pub selected: usize,
+}
+
+impl RomBrowser {
+    pub fn new(entries: Vec<(String, String)>) -> Self {
+        RomBrowser {
+            entries,
+            selected: 0,
+        }
+    }
+
+    pub fn entries(&self) -> &[(String, String)] {
+        &self.entries
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        if !self.entries.is_empty() {
+            self.selected = self
+                .selected
+                .saturating_add_signed(delta)
+                .min(self.entries.len() - 1);
+        }
+    }
+
+    pub fn selected_path(&self) -> Option<&str> {
+        self.entries
+            .get(self.selected)
+            .map(|(_, path)| path.as_str())
+    }
+}
+
+/// Draw the ROM list over the top of the frame, scrolled to keep the
+/// selection visible.
+pub fn draw_rom_browser_rgb24(frame: &mut [u8], width: usize, height: usize, browser: &RomBrowser) {
+    let shown = browser.entries.len().clamp(1, VISIBLE_ROWS);
+    let panel_h = (shown + 1) * LINE_HEIGHT + 4;
+    if width < 256 || height < panel_h || frame.len() < width * height * 3 {
+        return;
+    }
+    frame[..panel_h * width * 3]
+        .chunks_exact_mut(3)
+        .for_each(|pixel| pixel.copy_from_slice(&PANEL_COLOR));
+
+    let label = |frame: &mut [u8], row: usize, text: &str, color: [u8; 3]| {
+        draw_hud_label_rgb24(frame, width, height, 2, 2 + row * LINE_HEIGHT, text, color);
+    };
+    label(frame, 0, "ROMS  ENTER:LOAD ESC:CLOSE", TITLE_COLOR);
+    if browser.entries.is_empty() {
+        label(frame, 1, "  NO ROMS FOUND", TEXT_COLOR);
+        return;
+    }
+    let first = (browser.selected + 1).saturating_sub(VISIBLE_ROWS);
+    for (row, (i, (name, _))) in browser
+        .entries
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(shown)
+        .enumerate()
+    {
+        let marker = if i == browser.selected { ">" } else { " " };
+        let name: String = name.to_uppercase().chars().take(NAME_CHARS).collect();
+        label(frame, row + 1, &format!("{} {}", marker, name), TEXT_COLOR);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_within_the_list() {
+        let mut browser = RomBrowser::new(vec![
+            ("a.nes".to_string(), "roms/a.nes".to_string()),
+            ("b.nes".to_string(), "roms/b.nes".to_string()),
+        ]);
+        browser.move_selection(-1);
+        assert_eq!(browser.selected_path(), Some("roms/a.nes"));
+        browser.move_selection(5);
+        assert_eq!(browser.selected_path(), Some("roms/b.nes"));
+
+        let mut empty = RomBrowser::new(Vec::new());
+        empty.move_selection(1);
+        assert_eq!(empty.selected_path(), None);
+        let mut frame = vec![0u8; 256 * 240 * 3];
+        draw_rom_browser_rgb24(&mut frame, 256, 240, &empty);
+        assert_eq!(frame[..3], PANEL_COLOR);
+    }
+}
@@ -0,0 +1,223 @@
+// ROM verification against a No-Intro DAT (Logiqx XML, as the DAT-o-MATIC
+// downloads come). No-Intro's NES sets hash the ROM without its iNES header,
+// older headered sets with it, so a file is looked up both ways.
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use crate::crc32::crc32;
+
+const INES_HEADER_SIZE: usize = 16;
+
+/// One `<rom>` entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatRom {
+    /// The `<game>` it belongs to.
+    pub game: String,
+    /// File name in the set, e.g. `Super Mario Bros. (World).nes`.
+    pub name: String,
+    pub size: usize,
+    pub crc32: u32,
+    /// Marked `status="baddump"`.
+    pub bad_dump: bool,
+}
+
+/// How a ROM file compares with the DAT.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DumpStatus {
+    /// Matches a good dump; holds the game name.
+    Verified(String),
+    /// Matches a dump the DAT marks bad.
+    BadDump(String),
+    /// Starts with a known dump but carries extra data after it.
+    Overdump(String),
+    /// Has a set's file name but not its contents: hacked, patched or
+    /// damaged.
+    Modified(String),
+    /// Not in the DAT.
+    Unknown,
+}
+
+impl DumpStatus {
+    /// Short label for the window title.
+    pub fn tag(&self) -> &'static str {
+        match self {
+            DumpStatus::Verified(_) => "verified",
+            DumpStatus::BadDump(_) => "bad dump",
+            DumpStatus::Overdump(_) => "overdump",
+            DumpStatus::Modified(_) => "modified",
+            DumpStatus::Unknown => "unknown dump",
+        }
+    }
+
+    pub fn is_verified(&self) -> bool {
+        matches!(self, DumpStatus::Verified(_))
+    }
+}
+
+impl fmt::Display for DumpStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DumpStatus::Verified(game) => write!(f, "verified good dump of {}", game),
+            DumpStatus::BadDump(game) => write!(f, "known bad dump of {}", game),
+            DumpStatus::Overdump(game) => write!(f, "overdump of {}", game),
+            DumpStatus::Modified(game) => write!(f, "modified copy of {}", game),
+            DumpStatus::Unknown => write!(f, "not in the DAT"),
+        }
+    }
+}
+
+/// A loaded DAT, indexed by CRC32.
+#[derive(Debug, Clone, Default)]
+pub struct RomDat {
+    roms: Vec<DatRom>,
+    by_crc: HashMap<u32, usize>,
+}
+
+impl RomDat {
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Ok(Self::parse(&std::fs::read_to_string(path)?))
+    }
+
+    /// Entries without a usable size and CRC are skipped.
+    pub fn parse(xml: &str) -> Self {
+        let mut dat = RomDat::default();
+        for block in xml.split("<game").skip(1) {
+            let Some(game) = attribute(block, "name") else {
+                continue;
+            };
+            let block = block.split("</game>").next().unwrap_or(block);
+            for tag in block.split("<rom").skip(1) {
+                let tag = tag.split('>').next().unwrap_or(tag);
+                let size = attribute(tag, "size").and_then(|s| s.parse().ok());
+                let crc = attribute(tag, "crc").and_then(|c| u32::from_str_radix(&c, 16).ok());
+                let (Some(size), Some(crc32)) = (size, crc) else {
+                    continue;
+                };
+                dat.by_crc.insert(crc32, dat.roms.len());
+                dat.roms.push(DatRom {
+                    game: game.clone(),
+                    name: attribute(tag, "name").unwrap_or_default(),
+                    size,
+                    crc32,
+                    bad_dump: attribute(tag, "status").as_deref() == Some("baddump"),
+                });
+            }
+        }
+        dat
+    }
+
+    pub fn len(&self) -> usize {
+        self.roms.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roms.is_empty()
+    }
+
+    /// Check a ROM file's contents; `file_name` is used to spot modified
+    /// copies of a set file.
+    pub fn verify(&self, file_name: &str, data: &[u8]) -> DumpStatus {
+        let headerless = if data.starts_with(b"NES\x1A") && data.len() > INES_HEADER_SIZE {
+            &data[INES_HEADER_SIZE..]
+        } else {
+            data
+        };
+        for contents in [headerless, data] {
+            if let Some(&index) = self.by_crc.get(&crc32(contents)) {
+                let rom = &self.roms[index];
+                return if rom.bad_dump {
+                    DumpStatus::BadDump(rom.game.clone())
+                } else {
+                    DumpStatus::Verified(rom.game.clone())
+                };
+            }
+        }
+        // Overdumps repeat or pad the data past its real size
+        let mut prefix_crcs: HashMap<usize, u32> = HashMap::new();
+        for rom in self.roms.iter().filter(|rom| rom.size < headerless.len()) {
+            let crc = *prefix_crcs
+                .entry(rom.size)
+                .or_insert_with(|| crc32(&headerless[..rom.size]));
+            if crc == rom.crc32 && !rom.bad_dump {
+                return DumpStatus::Overdump(rom.game.clone());
+            }
+        }
+        let stem = |name: &str| {
+            Path::new(name)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_lowercase())
+        };
+        let wanted = stem(file_name);
+        match self.roms.iter().find(|rom| stem(&rom.name) == wanted) {
+            Some(rom) if wanted.is_some() => DumpStatus::Modified(rom.game.clone()),
+            _ => DumpStatus::Unknown,
+        }
+    }
+}
+
+/// Value of `key="..."` in an XML tag, with the five predefined entities
+/// decoded.
+fn attribute(tag: &str, key: &str) -> Option<String> {
+    let pattern = format!(" {}=\"", key);
+    let start = tag.find(&pattern)? + pattern.len();
+    let value = &tag[start..start + tag[start..].find('"')?];
+    Some(
+        value
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verifies_against_a_no_intro_dat() {
+        let good = vec![0x11u8; 0x6000];
+        let bad = vec![0x22u8; 0x6000];
+        let xml = format!(
+            r#"<?xml version="1.0"?>
+<datafile>
+  <header><name>Nintendo - Nintendo Entertainment System (Headerless)</name></header>
+  <game name="Tom &amp; Jerry (USA)">
+    <description>Tom &amp; Jerry (USA)</description>
+    <rom name="Tom &amp; Jerry (USA).nes" size="24576" crc="{:08x}" status="verified"/>
+  </game>
+  <game name="Bad Game (Japan)">
+    <rom name="Bad Game (Japan).nes" size="24576" crc="{:08X}" status="baddump"/>
+  </game>
+</datafile>"#,
+            crc32(&good),
+            crc32(&bad)
+        );
+        let dat = RomDat::parse(&xml);
+        assert_eq!(dat.len(), 2);
+
+        let mut headered = b"NES\x1A\x01\x01\0\0\0\0\0\0\0\0\0\0".to_vec();
+        headered.extend_from_slice(&good);
+        let verified = dat.verify("whatever.nes", &headered);
+        assert_eq!(verified, DumpStatus::Verified("Tom & Jerry (USA)".into()));
+        assert!(verified.is_verified());
+        assert_eq!(
+            dat.verify("x.nes", &bad),
+            DumpStatus::BadDump("Bad Game (Japan)".into())
+        );
+
+        let mut over = good.clone();
+        over.extend_from_slice(&good);
+        assert_eq!(dat.verify("x.nes", &over).tag(), "overdump");
+
+        let mut hacked = good.clone();
+        hacked[0x100] = 0xEA;
+        assert_eq!(
+            dat.verify("Tom & Jerry (USA).nes", &hacked).to_string(),
+            "modified copy of Tom & Jerry (USA)"
+        );
+        assert_eq!(dat.verify("other.nes", &hacked), DumpStatus::Unknown);
+    }
+}
@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Where the front-ends keep the recent-ROMs list.
+pub const HISTORY_PATH: &str = "recent_roms.dat";
+
+const MAX_RECENT: usize = 32;
+
+/// One ROM in the recent list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RomRecord {
+    /// Path the ROM was last launched from.
+    pub path: String,
+    /// CRC-32 of the PRG+CHR data, so a renamed ROM keeps its record.
+    pub crc32: u32,
+    /// Unix time of the last launch.
+    pub last_played: u64,
+    pub play_time_secs: u64,
+}
+
+/// Recently played ROMs, most recent first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RomHistory {
+    records: Vec<RomRecord>,
+}
+
+impl RomHistory {
+    /// Read the list; a missing or unreadable file gives an empty one.
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        std::fs::read(path)
+            .ok()
+            .and_then(|data| bincode::deserialize(&data).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::write(path, bincode::serialize(self)?)?;
+        Ok(())
+    }
+
+    pub fn records(&self) -> &[RomRecord] {
+        &self.records
+    }
+
+    /// Record of the ROM at `path`, for annotating a file listing.
+    pub fn find_path(&self, path: &str) -> Option<&RomRecord> {
+        self.records.iter().find(|record| record.path == path)
+    }
+
+    /// Add `play_time_secs` to the ROM's total and move it to the top.
+    /// Call with 0 at launch to mark it played.
+    pub fn record_session(&mut self, path: &str, crc32: u32, play_time_secs: u64, now: u64) {
+        let mut record = match self.records.iter().position(|r| r.crc32 == crc32) {
+            Some(index) => self.records.remove(index),
+            None => RomRecord {
+                path: String::new(),
+                crc32,
+                last_played: 0,
+                play_time_secs: 0,
+            },
+        };
+        record.path = path.to_string();
+        record.last_played = now;
+        record.play_time_secs += play_time_secs;
+        self.records.insert(0, record);
+        self.records.truncate(MAX_RECENT);
+    }
+
+    pub fn total_play_time_secs(&self) -> u64 {
+        self.records
+            .iter()
+            .map(|record| record.play_time_secs)
+            .sum()
+    }
+}
+
+/// Play time for listings, e.g. `2h 05m` or `4m 09s`.
+pub fn format_duration(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}h {:02}m", secs / 3600, secs / 60 % 60)
+    } else {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    }
+}
+
+/// Current Unix time in seconds.
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sessions_accumulate_per_rom_and_move_to_top() {
+        let mut history = RomHistory::default();
+        history.record_session("roms/a.nes", 0xAAAA, 60, 100);
+        history.record_session("roms/b.nes", 0xBBBB, 30, 200);
+        // Same ROM under a new name keeps its play time
+        history.record_session("roms/a (renamed).nes", 0xAAAA, 15, 300);
+
+        let records = history.records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].path, "roms/a (renamed).nes");
+        assert_eq!(records[0].play_time_secs, 75);
+        assert_eq!(records[0].last_played, 300);
+        assert_eq!(records[1].crc32, 0xBBBB);
+        assert_eq!(history.total_play_time_secs(), 105);
+        assert!(history.find_path("roms/a.nes").is_none());
+    }
+
+    #[test]
+    fn round_trips_through_a_file() {
+        let mut history = RomHistory::default();
+        history.record_session("roms/a.nes", 0x1234, 3725, 42);
+        let mut path = std::env::temp_dir();
+        path.push(format!("nes_rom_history_test_{}.dat", std::process::id()));
+        history.save(&path).unwrap();
+        let loaded = RomHistory::load(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(loaded, history);
+        assert_eq!(format_duration(3725), "1h 02m");
+        assert_eq!(format_duration(249), "4m 09s");
+        assert!(RomHistory::load("/nonexistent/recent_roms.dat")
+            .records()
+            .is_empty());
+    }
+}
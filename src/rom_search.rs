@@ -0,0 +1,221 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// File types offered by the ROM picker and name search.
+pub const ROM_EXTENSIONS: [&str; 4] = ["nes", "fds", "nsf", "unf"];
+
+/// Extra ROM directories, separated like `PATH`.
+pub const ROM_DIRS_ENV: &str = "NES_ROM_DIRS";
+
+pub const DEFAULT_ROM_DIR: &str = "roms";
+
+/// Where to look for ROMs: `extra` (from `--rom-dir`), then `NES_ROM_DIRS`,
+/// then `roms/`. A directory listed twice keeps its first place.
+pub fn rom_dirs(extra: &[String]) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = extra.iter().map(PathBuf::from).collect();
+    if let Some(paths) = std::env::var_os(ROM_DIRS_ENV) {
+        dirs.extend(std::env::split_paths(&paths));
+    }
+    dirs.push(PathBuf::from(DEFAULT_ROM_DIR));
+    let mut seen = HashSet::new();
+    dirs.retain(|dir| seen.insert(dir.clone()));
+    dirs
+}
+
+pub fn is_rom_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ROM_EXTENSIONS.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+}
+
+/// Every ROM under `dirs`, searched recursively, sorted by path. Hidden
+/// directories are skipped and missing ones ignored. Symlinked
+/// directories are followed, but each directory is read once, so a link
+/// back up the tree can't loop.
+pub fn find_roms(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    fn walk(dir: &Path, visited: &mut HashSet<PathBuf>, out: &mut Vec<PathBuf>) {
+        let Ok(real) = dir.canonicalize() else {
+            return;
+        };
+        if !visited.insert(real) {
+            return;
+        }
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if path.is_dir() && !hidden {
+                walk(&path, visited, out);
+            } else if path.is_file() && is_rom_file(&path) {
+                out.push(path);
+            }
+        }
+    }
+    let mut roms = Vec::new();
+    let mut visited = HashSet::new();
+    for dir in dirs {
+        walk(dir, &mut visited, &mut roms);
+    }
+    roms.sort();
+    roms.dedup();
+    roms
+}
+
+/// Title used for matching: the file stem without `(Japan)`/`[!]` tags,
+/// lowercased, punctuation turned into single spaces.
+pub fn rom_title(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy())
+        .unwrap_or_default();
+    let mut depth = 0;
+    let untagged: String = stem
+        .chars()
+        .filter(|&c| {
+            match c {
+                '(' | '[' => depth += 1,
+                ')' | ']' => depth = (depth - 1).max(0),
+                _ => return depth == 0,
+            }
+            false
+        })
+        .collect();
+    normalize(&untagged)
+}
+
+fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// How well `query` matches `title` (from `rom_title`); lower is better,
+/// `None` is no match. 0 exact, 1 prefix, 2 substring, 3 every word
+/// present, 4 letters in order.
+pub fn match_score(query: &str, title: &str) -> Option<u8> {
+    let query = normalize(query);
+    if query.is_empty() {
+        return None;
+    }
+    let squash = |s: &str| s.replace(' ', "");
+    let mut title_chars = squash(title).chars().collect::<Vec<_>>().into_iter();
+    if title == query {
+        Some(0)
+    } else if title.starts_with(&query) {
+        Some(1)
+    } else if squash(title).contains(&squash(&query)) {
+        Some(2)
+    } else if query.split(' ').all(|word| title.contains(word)) {
+        Some(3)
+    } else if squash(&query).chars().all(|c| title_chars.any(|t| t == c)) {
+        Some(4)
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RomLookup {
+    Found(PathBuf),
+    /// Several titles match; best first. The caller asks which one.
+    Ambiguous(Vec<PathBuf>),
+    NotFound,
+}
+
+/// Resolve a ROM argument: an existing file is used as is, otherwise it is
+/// matched against the titles of the ROMs under `dirs`. A single match or
+/// a single exact title wins outright.
+pub fn resolve_rom(query: &str, dirs: &[PathBuf]) -> RomLookup {
+    if Path::new(query).is_file() {
+        return RomLookup::Found(PathBuf::from(query));
+    }
+    let mut matches: Vec<(u8, PathBuf)> = find_roms(dirs)
+        .into_iter()
+        .filter_map(|path| Some((match_score(query, &rom_title(&path))?, path)))
+        .collect();
+    matches.sort();
+    let exact = matches.iter().filter(|(score, _)| *score == 0).count();
+    match matches.len() {
+        0 => RomLookup::NotFound,
+        1 => RomLookup::Found(matches.remove(0).1),
+        _ if exact == 1 => RomLookup::Found(matches.remove(0).1),
+        _ => RomLookup::Ambiguous(matches.into_iter().map(|(_, path)| path).collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_roms_recursively_and_matches_titles() {
+        let root = std::env::temp_dir().join(format!("nes_rom_search_{}", std::process::id()));
+        let nested = root.join("Nintendo").join("Platformers");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::create_dir_all(root.join(".cache")).unwrap();
+        for path in [
+            root.join("Mario Bros. (World).nes"),
+            nested.join("Super Mario Bros. 3 (USA) [!].NES"),
+            nested.join("Super Mario Bros. (Japan).nes"),
+            root.join("Zelda no Densetsu (Japan).fds"),
+            root.join("notes.txt"),
+            root.join(".cache").join("old.nes"),
+        ] {
+            std::fs::write(path, b"").unwrap();
+        }
+        let dirs = vec![root.clone()];
+
+        assert_eq!(find_roms(&dirs).len(), 4);
+        assert_eq!(
+            rom_title(Path::new("Super Mario Bros. 3 (USA) [!].nes")),
+            "super mario bros 3"
+        );
+        assert_eq!(
+            resolve_rom("zelda", &dirs),
+            RomLookup::Found(root.join("Zelda no Densetsu (Japan).fds"))
+        );
+        // An exact title wins over longer ones containing it
+        assert_eq!(
+            resolve_rom("super mario bros", &dirs),
+            RomLookup::Found(nested.join("Super Mario Bros. (Japan).nes"))
+        );
+        let RomLookup::Ambiguous(choices) = resolve_rom("mario", &dirs) else {
+            panic!("expected several matches");
+        };
+        assert_eq!(choices[0], root.join("Mario Bros. (World).nes"));
+        assert_eq!(choices.len(), 3);
+        assert_eq!(match_score("smb3", "super mario bros 3"), Some(4));
+        assert_eq!(resolve_rom("metroid", &dirs), RomLookup::NotFound);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_loops_are_walked_once() {
+        let root = std::env::temp_dir().join(format!("nes_rom_loop_{}", std::process::id()));
+        let nested = root.join("a");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(nested.join("game.nes"), b"").unwrap();
+        std::os::unix::fs::symlink(&root, nested.join("up")).unwrap();
+
+        let roms = find_roms(&[root.clone(), nested.clone()]);
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(roms, vec![nested.join("game.nes")]);
+    }
+
+    #[test]
+    fn rom_dirs_drops_repeats_anywhere_in_the_list() {
+        let extra = ["roms".to_string(), "games".to_string(), "roms".to_string()];
+        let dirs = rom_dirs(&extra);
+        assert_eq!(dirs[..2], [PathBuf::from("roms"), PathBuf::from("games")]);
+        assert_eq!(
+            dirs.iter().filter(|dir| *dir == Path::new("roms")).count(),
+            1
+        );
+    }
+}
@@ -0,0 +1,78 @@
+// Watch mode for homebrew work: polls the ROM (and any extra files, e.g. the
+// assembler's symbol output) and reports when they changed, so the frontend
+// can reload without a restart. A change is only reported once the files
+// have stopped changing between two polls, so a half-written ROM from a
+// running build is never loaded.
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Size and modification time; `None` while the file is missing.
+type Stamp = Option<(u64, SystemTime)>;
+
+pub struct RomWatcher {
+    paths: Vec<PathBuf>,
+    seen: Vec<Stamp>,
+    settling: bool,
+}
+
+impl RomWatcher {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        let seen = paths.iter().map(stamp).collect();
+        RomWatcher {
+            paths,
+            seen,
+            settling: false,
+        }
+    }
+
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Check the files; `true` once a change has settled.
+    pub fn poll(&mut self) -> bool {
+        let stamps: Vec<Stamp> = self.paths.iter().map(stamp).collect();
+        if stamps != self.seen {
+            self.seen = stamps;
+            self.settling = true;
+            return false;
+        }
+        if self.settling && stamps.iter().all(Option::is_some) {
+            self.settling = false;
+            return true;
+        }
+        false
+    }
+}
+
+fn stamp(path: &PathBuf) -> Stamp {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.len(), metadata.modified().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempRom;
+
+    #[test]
+    fn reports_a_change_once_it_settles() {
+        let rom = TempRom::new("watch", &[0u8; 16]);
+        let path = PathBuf::from(rom.path());
+        let mut watcher = RomWatcher::new(vec![path.clone()]);
+        assert!(!watcher.poll());
+
+        std::fs::write(&path, [0u8; 32]).unwrap();
+        assert!(!watcher.poll());
+        assert!(watcher.poll());
+        assert!(!watcher.poll());
+
+        // A missing file (build in progress) never triggers a reload
+        std::fs::remove_file(&path).unwrap();
+        assert!(!watcher.poll());
+        assert!(!watcher.poll());
+        std::fs::write(&path, [0u8; 48]).unwrap();
+        assert!(!watcher.poll());
+        assert!(watcher.poll());
+    }
+}
@@ -1,8 +1,9 @@
 use crate::apu::ApuState;
 use crate::cartridge::{CartridgeState, Mirroring, Mmc1State};
+use crate::rng::EmuRng;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct SaveState {
     // CPU state
     pub cpu_a: u8,
@@ -63,6 +64,51 @@ pub struct SaveState {
     pub bus_dmc_stall_cycles: u32,
     #[serde(default)]
     pub ppu_frame_complete: bool,
+    /// Downscaled RGB24 frame (`THUMBNAIL_WIDTH` x `THUMBNAIL_HEIGHT`),
+    /// empty for states saved before thumbnails existed.
+    #[serde(default)]
+    pub thumbnail: Vec<u8>,
+    #[serde(default)]
+    pub play_time_secs: u64,
+    /// Emulator RNG at save time; `None` for states from before it existed.
+    #[serde(default)]
+    pub rng: Option<EmuRng>,
+}
+
+pub const THUMBNAIL_WIDTH: usize = 64;
+pub const THUMBNAIL_HEIGHT: usize = 60;
+
+/// Shrink a 256x240 RGB24 frame 4x by averaging each 4x4 block.
+pub fn make_thumbnail(frame: &[u8]) -> Vec<u8> {
+    if frame.len() < 256 * 240 * 3 {
+        return Vec::new();
+    }
+    let mut thumbnail = Vec::with_capacity(THUMBNAIL_WIDTH * THUMBNAIL_HEIGHT * 3);
+    for ty in 0..THUMBNAIL_HEIGHT {
+        for tx in 0..THUMBNAIL_WIDTH {
+            let mut sum = [0u32; 3];
+            for y in ty * 4..ty * 4 + 4 {
+                for x in tx * 4..tx * 4 + 4 {
+                    let i = (y * 256 + x) * 3;
+                    for c in 0..3 {
+                        sum[c] += frame[i + c] as u32;
+                    }
+                }
+            }
+            thumbnail.extend(sum.iter().map(|&v| (v / 16) as u8));
+        }
+    }
+    thumbnail
+}
+
+/// What the state picker shows for an occupied slot.
+#[derive(Debug, Clone)]
+pub struct SlotInfo {
+    pub slot: u8,
+    /// Seconds since the Unix epoch when the state was written.
+    pub timestamp: u64,
+    pub play_time_secs: u64,
+    pub thumbnail: Vec<u8>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -143,6 +189,9 @@ impl From<LegacySaveState> for SaveState {
             bus_dma_in_progress: false,
             bus_dmc_stall_cycles: 0,
             ppu_frame_complete: false,
+            thumbnail: Vec::new(),
+            play_time_secs: 0,
+            rng: None,
         }
     }
 }
@@ -306,6 +355,8 @@ impl From<SaveStateV1> for SaveState {
                 mapper18: None,
                 mapper210: None,
                 vrc6: None,
+                unrom512: None,
+                gtrom: None,
             }),
             apu_frame_counter: v1.apu_frame_counter,
             apu_frame_interrupt: v1.apu_frame_interrupt,
@@ -316,6 +367,9 @@ impl From<SaveStateV1> for SaveState {
             bus_dma_in_progress: false,
             bus_dmc_stall_cycles: 0,
             ppu_frame_complete: false,
+            thumbnail: Vec::new(),
+            play_time_secs: 0,
+            rng: None,
         }
     }
 }
@@ -399,6 +453,197 @@ impl From<SaveStateV2> for SaveState {
             bus_dma_in_progress: false,
             bus_dmc_stall_cycles: 0,
             ppu_frame_complete: false,
+            thumbnail: Vec::new(),
+            play_time_secs: 0,
+            rng: None,
+        }
+    }
+}
+
+/// SaveState before thumbnails and play time were added.
+#[derive(Serialize, Deserialize)]
+struct SaveStateV3 {
+    cpu_a: u8,
+    cpu_x: u8,
+    cpu_y: u8,
+    cpu_pc: u16,
+    cpu_sp: u8,
+    cpu_status: u8,
+    cpu_cycles: u64,
+    ppu_control: u8,
+    ppu_mask: u8,
+    ppu_status: u8,
+    ppu_oam_addr: u8,
+    ppu_scroll_x: u8,
+    ppu_scroll_y: u8,
+    ppu_addr: u16,
+    ppu_data_buffer: u8,
+    ppu_w: bool,
+    ppu_t: u16,
+    ppu_v: u16,
+    ppu_x: u8,
+    ppu_scanline: i16,
+    ppu_cycle: u16,
+    ppu_frame: u64,
+    ppu_palette: [u8; 32],
+    ppu_nametable: Vec<u8>,
+    ppu_oam: Vec<u8>,
+    ram: Vec<u8>,
+    cartridge_prg_bank: u8,
+    cartridge_chr_bank: u8,
+    cartridge_state: Option<CartridgeState>,
+    apu_frame_counter: u8,
+    apu_frame_interrupt: bool,
+    apu_state: Option<ApuState>,
+    rom_filename: String,
+    timestamp: u64,
+    cpu_halted: bool,
+    bus_dma_cycles: u32,
+    bus_dma_in_progress: bool,
+    bus_dmc_stall_cycles: u32,
+    ppu_frame_complete: bool,
+}
+
+impl From<SaveStateV3> for SaveState {
+    fn from(v3: SaveStateV3) -> Self {
+        SaveState {
+            cpu_a: v3.cpu_a,
+            cpu_x: v3.cpu_x,
+            cpu_y: v3.cpu_y,
+            cpu_pc: v3.cpu_pc,
+            cpu_sp: v3.cpu_sp,
+            cpu_status: v3.cpu_status,
+            cpu_cycles: v3.cpu_cycles,
+            cpu_halted: v3.cpu_halted,
+            ppu_control: v3.ppu_control,
+            ppu_mask: v3.ppu_mask,
+            ppu_status: v3.ppu_status,
+            ppu_oam_addr: v3.ppu_oam_addr,
+            ppu_scroll_x: v3.ppu_scroll_x,
+            ppu_scroll_y: v3.ppu_scroll_y,
+            ppu_addr: v3.ppu_addr,
+            ppu_data_buffer: v3.ppu_data_buffer,
+            ppu_w: v3.ppu_w,
+            ppu_t: v3.ppu_t,
+            ppu_v: v3.ppu_v,
+            ppu_x: v3.ppu_x,
+            ppu_scanline: v3.ppu_scanline,
+            ppu_cycle: v3.ppu_cycle,
+            ppu_frame: v3.ppu_frame,
+            ppu_palette: v3.ppu_palette,
+            ppu_nametable: v3.ppu_nametable,
+            ppu_oam: v3.ppu_oam,
+            ram: v3.ram,
+            cartridge_prg_bank: v3.cartridge_prg_bank,
+            cartridge_chr_bank: v3.cartridge_chr_bank,
+            cartridge_state: v3.cartridge_state,
+            apu_frame_counter: v3.apu_frame_counter,
+            apu_frame_interrupt: v3.apu_frame_interrupt,
+            apu_state: v3.apu_state,
+            rom_filename: v3.rom_filename,
+            timestamp: v3.timestamp,
+            bus_dma_cycles: v3.bus_dma_cycles,
+            bus_dma_in_progress: v3.bus_dma_in_progress,
+            bus_dmc_stall_cycles: v3.bus_dmc_stall_cycles,
+            ppu_frame_complete: v3.ppu_frame_complete,
+            thumbnail: Vec::new(),
+            play_time_secs: 0,
+            rng: None,
+        }
+    }
+}
+
+/// SaveState before the RNG was recorded.
+#[derive(Serialize, Deserialize)]
+struct SaveStateV4 {
+    cpu_a: u8,
+    cpu_x: u8,
+    cpu_y: u8,
+    cpu_pc: u16,
+    cpu_sp: u8,
+    cpu_status: u8,
+    cpu_cycles: u64,
+    ppu_control: u8,
+    ppu_mask: u8,
+    ppu_status: u8,
+    ppu_oam_addr: u8,
+    ppu_scroll_x: u8,
+    ppu_scroll_y: u8,
+    ppu_addr: u16,
+    ppu_data_buffer: u8,
+    ppu_w: bool,
+    ppu_t: u16,
+    ppu_v: u16,
+    ppu_x: u8,
+    ppu_scanline: i16,
+    ppu_cycle: u16,
+    ppu_frame: u64,
+    ppu_palette: [u8; 32],
+    ppu_nametable: Vec<u8>,
+    ppu_oam: Vec<u8>,
+    ram: Vec<u8>,
+    cartridge_prg_bank: u8,
+    cartridge_chr_bank: u8,
+    cartridge_state: Option<CartridgeState>,
+    apu_frame_counter: u8,
+    apu_frame_interrupt: bool,
+    apu_state: Option<ApuState>,
+    rom_filename: String,
+    timestamp: u64,
+    cpu_halted: bool,
+    bus_dma_cycles: u32,
+    bus_dma_in_progress: bool,
+    bus_dmc_stall_cycles: u32,
+    ppu_frame_complete: bool,
+    thumbnail: Vec<u8>,
+    play_time_secs: u64,
+}
+
+impl From<SaveStateV4> for SaveState {
+    fn from(v4: SaveStateV4) -> Self {
+        SaveState {
+            cpu_a: v4.cpu_a,
+            cpu_x: v4.cpu_x,
+            cpu_y: v4.cpu_y,
+            cpu_pc: v4.cpu_pc,
+            cpu_sp: v4.cpu_sp,
+            cpu_status: v4.cpu_status,
+            cpu_cycles: v4.cpu_cycles,
+            ppu_control: v4.ppu_control,
+            ppu_mask: v4.ppu_mask,
+            ppu_status: v4.ppu_status,
+            ppu_oam_addr: v4.ppu_oam_addr,
+            ppu_scroll_x: v4.ppu_scroll_x,
+            ppu_scroll_y: v4.ppu_scroll_y,
+            ppu_addr: v4.ppu_addr,
+            ppu_data_buffer: v4.ppu_data_buffer,
+            ppu_w: v4.ppu_w,
+            ppu_t: v4.ppu_t,
+            ppu_v: v4.ppu_v,
+            ppu_x: v4.ppu_x,
+            ppu_scanline: v4.ppu_scanline,
+            ppu_cycle: v4.ppu_cycle,
+            ppu_frame: v4.ppu_frame,
+            ppu_palette: v4.ppu_palette,
+            ppu_nametable: v4.ppu_nametable,
+            ppu_oam: v4.ppu_oam,
+            ram: v4.ram,
+            cartridge_prg_bank: v4.cartridge_prg_bank,
+            cartridge_chr_bank: v4.cartridge_chr_bank,
+            cartridge_state: v4.cartridge_state,
+            apu_frame_counter: v4.apu_frame_counter,
+            apu_frame_interrupt: v4.apu_frame_interrupt,
+            apu_state: v4.apu_state,
+            rom_filename: v4.rom_filename,
+            timestamp: v4.timestamp,
+            cpu_halted: v4.cpu_halted,
+            bus_dma_cycles: v4.bus_dma_cycles,
+            bus_dma_in_progress: v4.bus_dma_in_progress,
+            bus_dmc_stall_cycles: v4.bus_dmc_stall_cycles,
+            ppu_frame_complete: v4.ppu_frame_complete,
+            thumbnail: v4.thumbnail,
+            play_time_secs: v4.play_time_secs,
+            rng: None,
         }
     }
 }
@@ -412,25 +657,54 @@ impl SaveState {
     }
 
     pub fn load_from_file(filename: &str) -> Result<SaveState, Box<dyn std::error::Error>> {
+        let (save_state, format) = Self::read_file(filename)?;
+        match format {
+            Some(format) => println!("Save state loaded from: {} ({} format)", filename, format),
+            None => println!("Save state loaded from: {}", filename),
+        }
+        Ok(save_state)
+    }
+
+    /// Decode a state file without logging; also returns the name of the
+    /// older format it was converted from, if any.
+    fn read_file(
+        filename: &str,
+    ) -> Result<(SaveState, Option<&'static str>), Box<dyn std::error::Error>> {
         let data = std::fs::read(filename)?;
         if let Ok(save_state) = bincode::deserialize::<SaveState>(&data) {
-            println!("Save state loaded from: {}", filename);
-            return Ok(save_state);
+            return Ok((save_state, None));
+        }
+
+        if let Ok(v4) = bincode::deserialize::<SaveStateV4>(&data) {
+            return Ok((v4.into(), Some("v4")));
+        }
+
+        if let Ok(v3) = bincode::deserialize::<SaveStateV3>(&data) {
+            return Ok((v3.into(), Some("v3")));
         }
 
         if let Ok(v2) = bincode::deserialize::<SaveStateV2>(&data) {
-            println!("Save state loaded from: {} (v2 format)", filename);
-            return Ok(v2.into());
+            return Ok((v2.into(), Some("v2")));
         }
 
         if let Ok(v1) = bincode::deserialize::<SaveStateV1>(&data) {
-            println!("Save state loaded from: {} (v1 format)", filename);
-            return Ok(v1.into());
+            return Ok((v1.into(), Some("v1")));
         }
 
         let legacy = bincode::deserialize::<LegacySaveState>(&data)?;
-        println!("Save state loaded from: {} (legacy format)", filename);
-        Ok(legacy.into())
+        Ok((legacy.into(), Some("legacy")))
+    }
+
+    /// Slot metadata for the state picker, or `None` if the file is missing
+    /// or unreadable.
+    pub fn read_slot_info(filename: &str, slot: u8) -> Option<SlotInfo> {
+        let (save_state, _) = Self::read_file(filename).ok()?;
+        Some(SlotInfo {
+            slot,
+            timestamp: save_state.timestamp,
+            play_time_secs: save_state.play_time_secs,
+            thumbnail: save_state.thumbnail,
+        })
     }
 }
 
@@ -602,10 +876,16 @@ mod tests {
             bus_dma_in_progress: true,
             bus_dmc_stall_cycles: 3,
             ppu_frame_complete: true,
+            thumbnail: vec![1, 2, 3],
+            play_time_secs: 90,
+            rng: Some(EmuRng::new(5)),
         };
 
         let encoded = bincode::serialize(&state).expect("serialize save state");
         let decoded: SaveState = bincode::deserialize(&encoded).expect("deserialize save state");
+        assert_eq!(decoded.thumbnail, [1, 2, 3]);
+        assert_eq!(decoded.play_time_secs, 90);
+        assert_eq!(decoded.rng.map(|rng| rng.seed()), Some(5));
 
         assert_eq!(decoded.cpu_cycles, 42_123);
         assert!(decoded.cpu_halted);
@@ -615,6 +895,60 @@ mod tests {
         assert!(decoded.ppu_frame_complete);
     }
 
+    #[test]
+    fn deserialize_v4_save_state_without_rng() {
+        let mut state: SaveState = LegacySaveState {
+            cpu_a: 0,
+            cpu_x: 0,
+            cpu_y: 0,
+            cpu_pc: 0x8000,
+            cpu_sp: 0xFD,
+            cpu_status: 0x24,
+            cpu_cycles: 0,
+            ppu_control: 0,
+            ppu_mask: 0,
+            ppu_status: 0,
+            ppu_oam_addr: 0,
+            ppu_scroll_x: 0,
+            ppu_scroll_y: 0,
+            ppu_addr: 0,
+            ppu_data_buffer: 0,
+            ppu_w: false,
+            ppu_t: 0,
+            ppu_v: 0,
+            ppu_x: 0,
+            ppu_scanline: 0,
+            ppu_cycle: 0,
+            ppu_frame: 0,
+            ppu_palette: [0; 32],
+            ppu_nametable: vec![0; 2048],
+            ppu_oam: vec![0; 256],
+            ram: vec![0; 0x800],
+            cartridge_prg_bank: 0,
+            cartridge_chr_bank: 0,
+            apu_frame_counter: 0,
+            apu_frame_interrupt: false,
+            rom_filename: "v4".to_string(),
+            timestamp: 77,
+        }
+        .into();
+        state.play_time_secs = 12;
+        // A v4 file is the current layout without the trailing `rng` option
+        let mut encoded = bincode::serialize(&state).expect("serialize save state");
+        assert_eq!(encoded.pop(), Some(0));
+
+        let mut path = std::env::temp_dir();
+        path.push(format!("nes_v4_state_{}.sav", std::process::id()));
+        std::fs::write(&path, encoded).expect("write v4 save");
+        let decoded = SaveState::read_file(path.to_str().expect("utf-8 path"));
+        let _ = std::fs::remove_file(path);
+
+        let (decoded, format) = decoded.expect("load v4 save");
+        assert_eq!(format, Some("v4"));
+        assert_eq!(decoded.play_time_secs, 12);
+        assert!(decoded.rng.is_none());
+    }
+
     #[test]
     fn deserialize_v1_save_state_without_mmc2() {
         use crate::cartridge::{Mirroring, Mmc1State};
@@ -705,4 +1039,215 @@ mod tests {
         assert!(cs.mmc1.is_some());
         assert!(cs.mmc2.is_none());
     }
+
+    #[test]
+    fn deserialize_v3_save_state_keeps_apu_state_without_thumbnail() {
+        let v3 = SaveStateV3 {
+            cpu_a: 0,
+            cpu_x: 0,
+            cpu_y: 0,
+            cpu_pc: 0x8000,
+            cpu_sp: 0xFD,
+            cpu_status: 0x24,
+            cpu_cycles: 10,
+            ppu_control: 0,
+            ppu_mask: 0,
+            ppu_status: 0,
+            ppu_oam_addr: 0,
+            ppu_scroll_x: 0,
+            ppu_scroll_y: 0,
+            ppu_addr: 0,
+            ppu_data_buffer: 0,
+            ppu_w: false,
+            ppu_t: 0,
+            ppu_v: 0,
+            ppu_x: 0,
+            ppu_scanline: 0,
+            ppu_cycle: 0,
+            ppu_frame: 0,
+            ppu_palette: [0; 32],
+            ppu_nametable: vec![0; 2048],
+            ppu_oam: vec![0; 256],
+            ram: vec![0; 0x800],
+            cartridge_prg_bank: 0,
+            cartridge_chr_bank: 0,
+            cartridge_state: None,
+            apu_frame_counter: 0,
+            apu_frame_interrupt: false,
+            apu_state: Some(crate::apu::Apu::new().snapshot_state()),
+            rom_filename: "v3".to_string(),
+            timestamp: 1234,
+            cpu_halted: false,
+            bus_dma_cycles: 5,
+            bus_dma_in_progress: true,
+            bus_dmc_stall_cycles: 0,
+            ppu_frame_complete: false,
+        };
+
+        let mut path = std::env::temp_dir();
+        path.push(format!("nes_v3_state_{}.sav", std::process::id()));
+        std::fs::write(&path, bincode::serialize(&v3).expect("serialize v3 save"))
+            .expect("write v3 save");
+        let decoded =
+            SaveState::load_from_file(path.to_str().expect("utf-8 path")).expect("load v3 save");
+        let info = SaveState::read_slot_info(path.to_str().expect("utf-8 path"), 2);
+        let _ = std::fs::remove_file(path);
+
+        assert!(decoded.apu_state.is_some());
+        assert_eq!(decoded.bus_dma_cycles, 5);
+        assert!(decoded.thumbnail.is_empty());
+        let info = info.expect("slot info");
+        assert_eq!(info.slot, 2);
+        assert_eq!(info.timestamp, 1234);
+        assert!(info.thumbnail.is_empty());
+    }
+
+    #[test]
+    fn thumbnail_averages_4x4_blocks() {
+        let mut frame = vec![0u8; 256 * 240 * 3];
+        // Top-left block: half its pixels white
+        for y in 0..2 {
+            for x in 0..4 {
+                let i = (y * 256 + x) * 3;
+                frame[i..i + 3].copy_from_slice(&[0xFF; 3]);
+            }
+        }
+        let thumbnail = make_thumbnail(&frame);
+        assert_eq!(thumbnail.len(), THUMBNAIL_WIDTH * THUMBNAIL_HEIGHT * 3);
+        assert_eq!(&thumbnail[..3], &[0x7F; 3]);
+        assert_eq!(&thumbnail[3..6], &[0; 3]);
+        assert!(make_thumbnail(&[]).is_empty());
+    }
+
+    fn nes_counting_in_ram() -> crate::Nes {
+        // MMC1, 32KB PRG: INC $10 / JMP $8000 from reset
+        let mut rom = vec![b'N', b'E', b'S', 0x1A, 2, 1, 0x10, 0];
+        rom.resize(16 + 0x8000 + 0x2000, 0);
+        rom[16..22].copy_from_slice(&[0xE6, 0x10, 0x4C, 0x00, 0x80, 0xEA]);
+        rom[16 + 0x7FFC..16 + 0x7FFE].copy_from_slice(&[0x00, 0x80]);
+        crate::test_support::nes_with_rom(&rom)
+    }
+
+    fn run_frames(nes: &mut crate::Nes, frames: usize) {
+        for _ in 0..frames {
+            while !nes.step() {}
+        }
+    }
+
+    #[test]
+    fn test_in_memory_snapshot_restores_exactly() {
+        let mut nes = nes_counting_in_ram();
+        run_frames(&mut nes, 3);
+        let mut snapshot = SaveState::default();
+        nes.capture_state_into(&mut snapshot);
+        run_frames(&mut nes, 2);
+        let expected = (nes.ram().to_vec(), nes.get_frame_buffer().to_vec());
+
+        nes.restore_state(&snapshot).unwrap();
+        assert_eq!(nes.ram(), &snapshot.ram[..]);
+        run_frames(&mut nes, 2);
+        assert_eq!(
+            (nes.ram().to_vec(), nes.get_frame_buffer().to_vec()),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_repeated_snapshots_reuse_buffers() {
+        let mut nes = nes_counting_in_ram();
+        run_frames(&mut nes, 1);
+        let mut snapshot = SaveState::default();
+        nes.capture_state_into(&mut snapshot);
+        let ram = snapshot.ram.as_ptr();
+        let prg_ram = snapshot.cartridge_state.as_ref().unwrap().prg_ram.as_ptr();
+
+        for _ in 0..10 {
+            run_frames(&mut nes, 1);
+            nes.capture_state_into(&mut snapshot);
+        }
+
+        assert_eq!(snapshot.ram.as_ptr(), ram);
+        assert_eq!(
+            snapshot.cartridge_state.as_ref().unwrap().prg_ram.as_ptr(),
+            prg_ram
+        );
+    }
+
+    // Wall-clock bound, so off by default; run with
+    // `cargo test --release -- --ignored snapshot_takes`
+    #[test]
+    #[ignore]
+    fn bench_snapshot_takes_under_a_millisecond() {
+        let mut nes = nes_counting_in_ram();
+        run_frames(&mut nes, 1);
+        let mut snapshot = SaveState::default();
+        nes.capture_state_into(&mut snapshot);
+
+        const SNAPSHOTS: u32 = 200;
+        let start = std::time::Instant::now();
+        for _ in 0..SNAPSHOTS {
+            nes.capture_state_into(&mut snapshot);
+        }
+        let per_snapshot = start.elapsed() / SNAPSHOTS;
+        assert!(
+            per_snapshot < std::time::Duration::from_millis(1),
+            "snapshot took {:?}",
+            per_snapshot
+        );
+    }
+
+    #[test]
+    fn test_state_loads_follow_the_sram_policy() {
+        use crate::sram::{get_backup_file_path, SramConflict, SramPolicy};
+
+        // Battery MMC1: mark the save valid ($60B7 = $5A), then INC $6000 in
+        // a loop so SRAM changes every iteration
+        let mut rom = vec![b'N', b'E', b'S', 0x1A, 2, 1, 0x12, 0];
+        rom.resize(16 + 0x8000 + 0x2000, 0);
+        rom[16..27].copy_from_slice(&[
+            0xA9, 0x5A, 0x8D, 0xB7, 0x60, 0xEE, 0x00, 0x60, 0x4C, 0x05, 0x80,
+        ]);
+        rom[16 + 0x7FFC..16 + 0x7FFE].copy_from_slice(&[0x00, 0x80]);
+        let rom = crate::test_support::TempRom::new("sram_policy", &rom);
+        let rom_path = rom.path();
+        let mut nes = crate::Nes::new();
+        nes.load_rom(rom_path).unwrap();
+        assert_eq!(nes.sram_policy(), SramPolicy::Ask);
+
+        run_frames(&mut nes, 1);
+        let old = nes.capture_state();
+        run_frames(&mut nes, 1);
+        let newer_sram = nes.prg_ram().unwrap().to_vec();
+        assert_ne!(
+            newer_sram[0],
+            old.cartridge_state.as_ref().unwrap().prg_ram[0]
+        );
+
+        // Ask: nothing is restored
+        let err = nes
+            .restore_state_with_sram(&old, SramPolicy::Ask)
+            .unwrap_err();
+        assert!(err.is::<SramConflict>());
+        assert_ne!(nes.capture_state().ppu_frame, old.ppu_frame);
+        assert_eq!(nes.prg_ram().unwrap(), &newer_sram[..]);
+
+        // Keep: the machine goes back, the save data does not
+        nes.restore_state_with_sram(&old, SramPolicy::Keep).unwrap();
+        assert_eq!(nes.capture_state().ppu_frame, old.ppu_frame);
+        assert_eq!(nes.prg_ram().unwrap(), &newer_sram[..]);
+
+        // State: the state's SRAM wins, the replaced one is backed up
+        nes.restore_state_with_sram(&old, SramPolicy::State)
+            .unwrap();
+        let backup = get_backup_file_path(rom_path);
+        assert_eq!(std::fs::read(&backup).unwrap(), newer_sram);
+        assert_eq!(
+            nes.prg_ram().unwrap(),
+            &old.cartridge_state.as_ref().unwrap().prg_ram[..]
+        );
+
+        // Identical SRAM is never a conflict
+        nes.restore_state_with_sram(&old, SramPolicy::Ask).unwrap();
+        std::fs::remove_file(backup).unwrap();
+    }
 }
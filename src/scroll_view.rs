@@ -0,0 +1,150 @@
+// Scroll debug view: tints every background pixel by the nametable it was
+// fetched from and marks the scanlines where the scroll position jumps, so
+// status bars, parallax splits and stray mid-frame $2005/$2006 writes show
+// up as bands. Built from the PPU's per-pixel provenance, the same record
+// the pixel inspector reads.
+use crate::hud_toast::draw_hud_label_rgb24;
+use crate::ppu::PixelProvenance;
+
+/// Logical nametables $2000, $2400, $2800, $2C00.
+const NAMETABLE_TINTS: [[u8; 3]; 4] = [
+    [0xE0, 0x40, 0x40],
+    [0x40, 0xC0, 0x40],
+    [0x40, 0x70, 0xF0],
+    [0xE0, 0xC0, 0x30],
+];
+const SPLIT_COLOR: [u8; 3] = [0xFF, 0xFF, 0xFF];
+const LABEL_COLOR: [u8; 3] = [0xFF, 0xFF, 0x80];
+const LABEL_SPACING: usize = 9;
+
+/// A scanline whose scroll differs from the line above it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScrollSplit {
+    pub line: usize,
+    /// Scroll of the screen's top-left corner in the 512x480 space of the
+    /// four logical nametables, as `$2005`/`$2000` would set it.
+    pub scroll_x: u16,
+    pub scroll_y: u16,
+}
+
+/// The scroll that places the background pixel fetched for screen (`x`,
+/// `y`) there; `None` when no background tile was fetched.
+pub fn pixel_scroll(x: usize, y: usize, pixel: &PixelProvenance) -> Option<(u16, u16)> {
+    let addr = pixel.nametable_addr?.wrapping_sub(0x2000) as usize;
+    let nametable = (addr >> 10) & 3;
+    let world_x = (nametable & 1) * 256 + (addr & 0x1F) * 8 + pixel.fine_x as usize;
+    let world_y = (nametable >> 1) * 240 + ((addr >> 5) & 0x1F) * 8 + pixel.fine_y as usize;
+    Some((
+        ((world_x + 512 - x % 512) % 512) as u16,
+        ((world_y + 480 - y % 480) % 480) as u16,
+    ))
+}
+
+/// Scanlines where the scroll changes, the first rendered line included.
+/// Each line is judged by its leftmost background pixel.
+pub fn scroll_splits(
+    provenance: impl Fn(usize, usize) -> Option<PixelProvenance>,
+) -> Vec<ScrollSplit> {
+    let mut splits = Vec::new();
+    let mut previous = None;
+    for line in 0..240 {
+        let scroll = (0..256).find_map(|x| pixel_scroll(x, line, &provenance(x, line)?));
+        if let Some((scroll_x, scroll_y)) = scroll {
+            if scroll != previous {
+                splits.push(ScrollSplit {
+                    line,
+                    scroll_x,
+                    scroll_y,
+                });
+            }
+        }
+        previous = scroll;
+    }
+    splits
+}
+
+/// Tint the picture by nametable (pixels with no background fetch are
+/// dimmed) and draw a dotted line with the new scroll at each split.
+pub fn draw_scroll_view_rgb24(
+    frame: &mut [u8],
+    width: usize,
+    height: usize,
+    provenance: impl Fn(usize, usize) -> Option<PixelProvenance>,
+) {
+    if width < 256 || height < 240 || frame.len() < width * height * 3 {
+        return;
+    }
+    for y in 0..240 {
+        for x in 0..256 {
+            let pixel = &mut frame[(y * width + x) * 3..][..3];
+            let nametable = provenance(x, y).and_then(|p| p.nametable_addr);
+            match nametable {
+                Some(addr) => {
+                    let tint = NAMETABLE_TINTS[((addr >> 10) & 3) as usize];
+                    for (channel, tint) in pixel.iter_mut().zip(tint) {
+                        *channel = ((*channel as u16 + tint as u16) / 2) as u8;
+                    }
+                }
+                None => pixel.iter_mut().for_each(|channel| *channel /= 4),
+            }
+        }
+    }
+
+    let mut next_label = 0;
+    for split in scroll_splits(&provenance) {
+        if split.line > 0 {
+            for x in (0..256).step_by(2) {
+                frame[(split.line * width + x) * 3..][..3].copy_from_slice(&SPLIT_COLOR);
+            }
+        }
+        // Splits on consecutive lines (wavy effects) get one label
+        if split.line >= next_label {
+            let text = format!("X={} Y={}", split.scroll_x, split.scroll_y);
+            let y = (split.line + 2).min(240 - 8);
+            draw_hud_label_rgb24(frame, width, height, 2, y, &text, LABEL_COLOR);
+            next_label = split.line + LABEL_SPACING;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_a_status_bar_split() {
+        // Lines 0-31 unscrolled in $2000; below, scrolled 100 px into $2400
+        // (coarse X 12, fine X 4 at the left edge)
+        let provenance = |x: usize, y: usize| {
+            let (world_x, world_y) = if y < 32 { (x, y) } else { (x + 100, y) };
+            let nametable = (world_x / 256) as u16;
+            let (cx, cy) = ((world_x % 256 / 8) as u16, (world_y / 8) as u16);
+            Some(PixelProvenance {
+                nametable_addr: Some(0x2000 + nametable * 0x400 + cy * 32 + cx),
+                fine_x: (world_x % 8) as u8,
+                fine_y: (world_y % 8) as u8,
+                ..PixelProvenance::default()
+            })
+        };
+        assert_eq!(
+            pixel_scroll(255, 40, &provenance(255, 40).unwrap()),
+            Some((100, 0))
+        );
+        assert_eq!(
+            scroll_splits(provenance),
+            [
+                ScrollSplit {
+                    line: 0,
+                    scroll_x: 0,
+                    scroll_y: 0
+                },
+                ScrollSplit {
+                    line: 32,
+                    scroll_x: 100,
+                    scroll_y: 0
+                },
+            ]
+        );
+        assert!(scroll_splits(|_, _| None).is_empty());
+    }
+}
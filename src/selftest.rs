@@ -0,0 +1,175 @@
+// Built-in input/video self-test: a tiny generated NROM program that draws a
+// checkerboard and turns the whole screen white while any controller button
+// is held, so the input -> CPU -> PPU -> window chain can be checked, and its
+// latency measured, without an external ROM.
+use std::time::{Duration, Instant};
+
+// 6502 program at $C000 (mirrored at $8000). Reset draws the checkerboard
+// and enables NMI; the NMI handler reads controller 1 and rewrites the first
+// two palette entries: white/white while a button is held, black/gray idle.
+#[rustfmt::skip]
+const PROGRAM: [u8; 0xB7] = [
+    // reset: SEI, CLD, LDX #$FF, TXS, PPU off
+    0x78, 0xD8, 0xA2, 0xFF, 0x9A, 0xA9, 0x00, 0x8D, 0x00, 0x20, 0x8D, 0x01, 0x20,
+    // wait for two vblanks
+    0x2C, 0x02, 0x20, 0x10, 0xFB, 0x2C, 0x02, 0x20, 0x10, 0xFB,
+    // palette: $3F00 <- PALETTE x8
+    0xA9, 0x3F, 0x8D, 0x06, 0x20, 0xA9, 0x00, 0x8D, 0x06, 0x20, 0xA2, 0x00,
+    0xBD, 0xB7, 0xC0, 0x8D, 0x07, 0x20, 0xE8, 0xE0, 0x20, 0xD0, 0xF5,
+    // nametable $2000: tile (col ^ row) & 1 for 30 rows of 32
+    0xA9, 0x20, 0x8D, 0x06, 0x20, 0xA9, 0x00, 0x8D, 0x06, 0x20, 0xA0, 0x00,
+    0xA2, 0x00, 0x84, 0x01, 0x8A, 0x45, 0x01, 0x29, 0x01, 0x8D, 0x07, 0x20,
+    0xE8, 0xE0, 0x20, 0xD0, 0xF1, 0xC8, 0xC0, 0x1E, 0xD0, 0xEA,
+    // attribute table: 64 zeros
+    0xA9, 0x00, 0xA2, 0x40, 0x8D, 0x07, 0x20, 0xCA, 0xD0, 0xFA,
+    // NMI on, scroll 0, background on; then spin
+    0xA9, 0x80, 0x8D, 0x00, 0x20, 0xA9, 0x00, 0x8D, 0x05, 0x20, 0x8D, 0x05,
+    0x20, 0xA9, 0x0A, 0x8D, 0x01, 0x20, 0x4C, 0x6C, 0xC0,
+    // nmi ($C06F): strobe and shift 8 buttons into $00
+    0xA9, 0x01, 0x8D, 0x16, 0x40, 0xA9, 0x00, 0x8D, 0x16, 0x40, 0x85, 0x00,
+    0xA2, 0x08, 0xAD, 0x16, 0x40, 0x4A, 0x26, 0x00, 0xCA, 0xD0, 0xF7,
+    // $3F00 <- $30,$30 when any button is held, else $0F,$2D
+    0xA9, 0x3F, 0x8D, 0x06, 0x20, 0xA9, 0x00, 0x8D, 0x06, 0x20, 0xA5, 0x00,
+    0xF0, 0x0B, 0xA9, 0x30, 0x8D, 0x07, 0x20, 0x8D, 0x07, 0x20, 0x4C, 0xA9,
+    0xC0, 0xA9, 0x0F, 0x8D, 0x07, 0x20, 0xA9, 0x2D, 0x8D, 0x07, 0x20,
+    // restore nametable select and scroll, RTI ($C0B6, also the IRQ handler)
+    0xA9, 0x80, 0x8D, 0x00, 0x20, 0xA9, 0x00, 0x8D, 0x05, 0x20, 0x8D, 0x05,
+    0x20, 0x40,
+];
+
+const PALETTE: [u8; 4] = [0x0F, 0x2D, 0x10, 0x30];
+const NMI_VECTOR: u16 = 0xC06F;
+const RESET_VECTOR: u16 = 0xC000;
+const IRQ_VECTOR: u16 = 0xC0B6;
+
+/// Presses older than this are dropped as never reaching the screen (a key
+/// the controller does not map, say).
+const PROBE_TIMEOUT_FRAMES: u32 = 120;
+
+/// The self-test program as an iNES image: 16KB PRG, 8KB CHR, mapper 0.
+pub fn selftest_rom() -> Vec<u8> {
+    let mut rom = vec![b'N', b'E', b'S', 0x1A, 1, 1];
+    rom.resize(16, 0);
+
+    let mut prg = vec![0u8; 0x4000];
+    prg[..PROGRAM.len()].copy_from_slice(&PROGRAM);
+    for i in 0..8 {
+        prg[PROGRAM.len() + i * 4..][..4].copy_from_slice(&PALETTE);
+    }
+    for (offset, vector) in [
+        (0x3FFA, NMI_VECTOR),
+        (0x3FFC, RESET_VECTOR),
+        (0x3FFE, IRQ_VECTOR),
+    ] {
+        prg[offset..offset + 2].copy_from_slice(&vector.to_le_bytes());
+    }
+    rom.extend_from_slice(&prg);
+
+    // Tile 0 is blank, tile 1 solid color 1
+    let mut chr = vec![0u8; 0x2000];
+    chr[16..24].fill(0xFF);
+    rom.extend_from_slice(&chr);
+    rom
+}
+
+/// Whether an RGB24 frame shows the flash (its top-left pixel is white).
+pub fn is_flash(frame: &[u8]) -> bool {
+    frame.len() >= 3 && frame[..3].iter().all(|&c| c >= 0xC0)
+}
+
+/// Press-to-flash time, in wall-clock time and presented frames.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Latency {
+    pub elapsed: Duration,
+    pub frames: u32,
+}
+
+/// Times button presses until the flash is first presented.
+#[derive(Debug, Default)]
+pub struct LatencyProbe {
+    pressed: Option<(Instant, u32)>,
+    lit: bool,
+}
+
+impl LatencyProbe {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A button went down at `now`; ignored while the screen is already lit.
+    pub fn press(&mut self, now: Instant) {
+        if !self.lit {
+            self.pressed = Some((now, 0));
+        }
+    }
+
+    /// Check a frame just presented at `now`; returns the latency when it is
+    /// the first flash after a press.
+    pub fn presented(&mut self, frame: &[u8], now: Instant) -> Option<Latency> {
+        self.lit = is_flash(frame);
+        let (pressed_at, frames) = self.pressed.take()?;
+        let frames = frames + 1;
+        if self.lit {
+            return Some(Latency {
+                elapsed: now.saturating_duration_since(pressed_at),
+                frames,
+            });
+        }
+        if frames < PROBE_TIMEOUT_FRAMES {
+            self.pressed = Some((pressed_at, frames));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boot_screen::builtin_rom_name;
+    use crate::Nes;
+
+    fn run_frames(nes: &mut Nes, frames: usize) {
+        for _ in 0..frames {
+            while !nes.step() {}
+        }
+    }
+
+    #[test]
+    fn flashes_while_a_button_is_held() {
+        let mut nes = Nes::new();
+        nes.load_rom_bytes(&builtin_rom_name("selftest"), &selftest_rom())
+            .unwrap();
+
+        run_frames(&mut nes, 5);
+        assert!(!is_flash(nes.get_frame_buffer()));
+        let frame = nes.get_frame_buffer();
+        assert_ne!(frame[..3], frame[8 * 3..8 * 3 + 3], "checkerboard drawn");
+
+        nes.set_controller(0x01);
+        let frames = (1..=4)
+            .find(|_| {
+                run_frames(&mut nes, 1);
+                is_flash(nes.get_frame_buffer())
+            })
+            .expect("screen flashes");
+        assert!(frames <= 2, "flash after {} frames", frames);
+
+        nes.set_controller(0);
+        run_frames(&mut nes, 3);
+        assert!(!is_flash(nes.get_frame_buffer()));
+
+        let start = Instant::now();
+        let mut probe = LatencyProbe::new();
+        probe.press(start);
+        assert_eq!(probe.presented(&[0, 0, 0], start), None);
+        let latency = probe.presented(&[255, 255, 255], start + Duration::from_millis(33));
+        assert_eq!(
+            latency,
+            Some(Latency {
+                elapsed: Duration::from_millis(33),
+                frames: 2
+            })
+        );
+        assert_eq!(probe.presented(&[255, 255, 255], start), None);
+    }
+}
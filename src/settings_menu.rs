@@ -0,0 +1,318 @@
+// In-game settings menu: video filter, aspect ratio, volume, accuracy
+// profile and the controller 1 keys, changed over the running game without a
+// restart. The menu only edits `Settings`; the frontend applies them.
+use crate::accuracy::AccuracyProfile;
+use crate::hud_toast::draw_hud_label_rgb24;
+use crate::input::ControllerState;
+use crate::output_scale::{AspectRatio, ScaleFilter};
+
+const PANEL_COLOR: [u8; 3] = [0x10, 0x10, 0x10];
+const TITLE_COLOR: [u8; 3] = [0xF8, 0xB8, 0x00];
+const TEXT_COLOR: [u8; 3] = [0xFF, 0xFF, 0xFF];
+const SELECTED_COLOR: [u8; 3] = [0x58, 0xD8, 0x54];
+const LINE_HEIGHT: usize = 9;
+const VOLUME_STEP: u8 = 10;
+
+/// Controller 1 buttons in menu order, with their labels.
+pub const BUTTONS: [(ControllerState, &str); 8] = [
+    (ControllerState::A, "A"),
+    (ControllerState::B, "B"),
+    (ControllerState::SELECT, "SELECT"),
+    (ControllerState::START, "START"),
+    (ControllerState::UP, "UP"),
+    (ControllerState::DOWN, "DOWN"),
+    (ControllerState::LEFT, "LEFT"),
+    (ControllerState::RIGHT, "RIGHT"),
+];
+
+/// SDL names of the keys the front-end takes as hotkeys before the pad
+/// sees them (keep in step with main.rs); buttons can't be bound to these.
+#[rustfmt::skip]
+pub const RESERVED_KEYS: &[&str] = &[
+    "R", "P", "B", "T", "G", "Q", "J", "O", "E", "D", "N", "I", "U", "H", "M", "K", "L",
+    // Turbo A/B and the Vs. System coin slots
+    "A", "S", "C", "V",
+    "`", "Backspace", "Tab", "Escape",
+    // State slots
+    "1", "2", "3", "4", "Keypad 1", "Keypad 2", "Keypad 3", "Keypad 4",
+    "F1", "F2", "F3", "F4", "F5", "F6", "F7", "F8", "F9", "F10", "F11", "F12",
+];
+
+/// Keyboard keys for the controller 1 buttons, by SDL key name and in
+/// `BUTTONS` order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBindings {
+    keys: [String; 8],
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings {
+            keys: ["Z", "X", "Space", "Return", "Up", "Down", "Left", "Right"].map(String::from),
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn button_for(&self, key: &str) -> Option<ControllerState> {
+        let index = self.keys.iter().position(|bound| bound == key)?;
+        Some(BUTTONS[index].0)
+    }
+
+    pub fn key_for(&self, index: usize) -> &str {
+        &self.keys[index]
+    }
+
+    /// Bind `key` to button `index`. A button already on that key takes
+    /// this one's old key, so no key drives two buttons. Hotkeys in
+    /// `RESERVED_KEYS` are refused and leave the bindings as they were.
+    pub fn bind(&mut self, index: usize, key: &str) -> bool {
+        if RESERVED_KEYS.contains(&key) {
+            return false;
+        }
+        if let Some(other) = self.keys.iter().position(|bound| bound == key) {
+            self.keys.swap(index, other);
+        } else {
+            self.keys[index] = key.to_string();
+        }
+        true
+    }
+}
+
+/// Everything the menu can change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Settings {
+    pub scale: ScaleFilter,
+    pub aspect: AspectRatio,
+    /// Output volume, 0-100.
+    pub volume: u8,
+    pub profile: AccuracyProfile,
+    pub bindings: KeyBindings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            scale: ScaleFilter::default(),
+            aspect: AspectRatio::default(),
+            volume: 100,
+            profile: AccuracyProfile::default(),
+            bindings: KeyBindings::default(),
+        }
+    }
+}
+
+/// A menu row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsItem {
+    Scale,
+    Aspect,
+    Volume,
+    Profile,
+    /// Key for `BUTTONS[i]`.
+    Button(usize),
+}
+
+impl SettingsItem {
+    const ROWS: usize = 4 + BUTTONS.len();
+
+    fn at(row: usize) -> Self {
+        match row {
+            0 => SettingsItem::Scale,
+            1 => SettingsItem::Aspect,
+            2 => SettingsItem::Volume,
+            3 => SettingsItem::Profile,
+            row => SettingsItem::Button(row - 4),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SettingsMenu {
+    pub selected: usize,
+    /// Waiting for the key to bind to the selected button.
+    capturing: bool,
+    /// Hotkey last pressed while capturing, which can't be bound.
+    refused: Option<String>,
+}
+
+impl SettingsMenu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn item(&self) -> SettingsItem {
+        SettingsItem::at(self.selected)
+    }
+
+    pub fn is_capturing(&self) -> bool {
+        self.capturing
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        self.selected = self
+            .selected
+            .saturating_add_signed(delta)
+            .min(SettingsItem::ROWS - 1);
+    }
+
+    /// Step the selected setting back or forward; returns the item changed.
+    pub fn adjust(&mut self, settings: &mut Settings, delta: isize) -> Option<SettingsItem> {
+        let item = self.item();
+        match item {
+            SettingsItem::Scale => settings.scale = step(&ScaleFilter::ALL, settings.scale, delta),
+            SettingsItem::Aspect => {
+                settings.aspect = step(&AspectRatio::ALL, settings.aspect, delta)
+            }
+            SettingsItem::Volume => {
+                let volume = if delta < 0 {
+                    settings.volume.saturating_sub(VOLUME_STEP)
+                } else {
+                    settings.volume.saturating_add(VOLUME_STEP).min(100)
+                };
+                if volume == settings.volume {
+                    return None;
+                }
+                settings.volume = volume;
+            }
+            SettingsItem::Profile => {
+                settings.profile = step(&AccuracyProfile::ALL, settings.profile, delta)
+            }
+            SettingsItem::Button(_) => return None,
+        }
+        Some(item)
+    }
+
+    /// Enter: step a setting forward, or wait for a key for a button.
+    pub fn activate(&mut self, settings: &mut Settings) -> Option<SettingsItem> {
+        if let SettingsItem::Button(_) = self.item() {
+            self.capturing = true;
+            return None;
+        }
+        self.adjust(settings, 1)
+    }
+
+    /// Bind the key pressed while capturing, or just stop on `None`. A
+    /// reserved hotkey is refused and the menu keeps waiting for a key.
+    pub fn capture(&mut self, settings: &mut Settings, key: Option<&str>) {
+        if let (SettingsItem::Button(index), Some(key)) = (self.item(), key) {
+            if !settings.bindings.bind(index, key) {
+                self.refused = Some(key.to_string());
+                return;
+            }
+        }
+        self.capturing = false;
+        self.refused = None;
+    }
+}
+
+/// The entry `delta` places from `current` in `all`, wrapping around.
+fn step<T: Copy + PartialEq>(all: &[T], current: T, delta: isize) -> T {
+    let index = all.iter().position(|&v| v == current).unwrap_or(0) as isize;
+    all[(index + delta).rem_euclid(all.len() as isize) as usize]
+}
+
+/// Dim the frame and draw the menu over it.
+pub fn draw_settings_menu_rgb24(
+    frame: &mut [u8],
+    width: usize,
+    height: usize,
+    menu: &SettingsMenu,
+    settings: &Settings,
+) {
+    let panel_h = (SettingsItem::ROWS + 2) * LINE_HEIGHT + 4;
+    if width < 256 || height < panel_h || frame.len() < width * height * 3 {
+        return;
+    }
+    for byte in frame[..width * height * 3].iter_mut() {
+        *byte /= 3;
+    }
+    frame[..panel_h * width * 3]
+        .chunks_exact_mut(3)
+        .for_each(|pixel| pixel.copy_from_slice(&PANEL_COLOR));
+
+    let label = |frame: &mut [u8], row: usize, text: &str, color: [u8; 3]| {
+        draw_hud_label_rgb24(frame, width, height, 2, 2 + row * LINE_HEIGHT, text, color);
+    };
+    label(frame, 0, "SETTINGS  ESC:CLOSE", TITLE_COLOR);
+    for row in 0..SettingsItem::ROWS {
+        let (name, value) = match SettingsItem::at(row) {
+            SettingsItem::Scale => ("VIDEO FILTER", settings.scale.name().to_string()),
+            SettingsItem::Aspect => ("ASPECT RATIO", settings.aspect.name().to_string()),
+            SettingsItem::Volume => ("VOLUME", format!("{}%", settings.volume)),
+            SettingsItem::Profile => ("ACCURACY", settings.profile.name().to_string()),
+            SettingsItem::Button(index) if menu.capturing && row == menu.selected => {
+                (BUTTONS[index].1, "PRESS A KEY".to_string())
+            }
+            SettingsItem::Button(index) => (
+                BUTTONS[index].1,
+                settings.bindings.key_for(index).to_string(),
+            ),
+        };
+        let color = if row == menu.selected {
+            SELECTED_COLOR
+        } else {
+            TEXT_COLOR
+        };
+        let text = format!("{:<13}{}", name, value.to_uppercase());
+        label(frame, row + 1, &text, color);
+    }
+    let hint = match (&menu.refused, menu.capturing) {
+        (Some(key), true) => format!("{} IS A HOTKEY, PICK ANOTHER", key.to_uppercase()),
+        (None, true) => "ESC: KEEP THE OLD KEY".to_string(),
+        (_, false) => "LEFT/RIGHT: CHANGE  ENTER: SET KEY".to_string(),
+    };
+    label(frame, SettingsItem::ROWS + 1, &hint, TITLE_COLOR);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_settings_and_rebinds_keys() {
+        let mut settings = Settings::default();
+        let mut menu = SettingsMenu::new();
+        assert_eq!(menu.adjust(&mut settings, -1), Some(SettingsItem::Scale));
+        assert_eq!(settings.scale, ScaleFilter::SharpBilinear);
+
+        menu.move_selection(2);
+        assert_eq!(menu.adjust(&mut settings, 1), None);
+        menu.adjust(&mut settings, -1);
+        assert_eq!(settings.volume, 90);
+
+        // Binding A to X swaps it with B
+        menu.move_selection(2);
+        assert_eq!(menu.item(), SettingsItem::Button(0));
+        menu.activate(&mut settings);
+        assert!(menu.is_capturing());
+        menu.capture(&mut settings, Some("X"));
+        assert!(!menu.is_capturing());
+        assert_eq!(settings.bindings.button_for("X"), Some(ControllerState::A));
+        assert_eq!(settings.bindings.button_for("Z"), Some(ControllerState::B));
+
+        // P cycles the colour filter before the pad would see it, so START
+        // can't take it; the menu keeps waiting for another key
+        menu.move_selection(3);
+        assert_eq!(menu.item(), SettingsItem::Button(3));
+        menu.activate(&mut settings);
+        menu.capture(&mut settings, Some("P"));
+        assert!(menu.is_capturing());
+        assert_eq!(settings.bindings.button_for("P"), None);
+        assert_eq!(settings.bindings.key_for(3), "Return");
+        menu.capture(&mut settings, Some("W"));
+        assert!(!menu.is_capturing());
+        assert_eq!(
+            settings.bindings.button_for("W"),
+            Some(ControllerState::START)
+        );
+        assert!(!settings.bindings.bind(0, "F5"));
+        menu.move_selection(100);
+        assert_eq!(menu.item(), SettingsItem::Button(7));
+
+        let mut frame = vec![0x90u8; 256 * 240 * 3];
+        draw_settings_menu_rgb24(&mut frame, 256, 240, &menu, &settings);
+        assert_eq!(frame[..3], PANEL_COLOR);
+        assert_eq!(frame[frame.len() - 3..], [0x30; 3]);
+    }
+}
@@ -0,0 +1,379 @@
+// Speedrun timer with RAM-driven autosplits. A splits file lists when the
+// timer starts, one line per split and, optionally, when it resets:
+//
+//     start when ram[$0770] == 1
+//     split World 1-2 when ram[$075C] changes
+//     split Bowser when ram[$0772] == 3
+//     reset when ram[$0770] == 0
+//
+// Triggers are input-script conditions, fired on the frame they become
+// true, or `changes` for any change of the byte. Time is counted in emulated
+// frames, so it is game time: pauses, fast-forward and slowdown of the host
+// don't change it. Events can be forwarded to a LiveSplit Server over TCP.
+use std::fmt;
+use std::io::Write;
+use std::net::TcpStream;
+use std::time::Duration;
+
+use crate::hud_toast::draw_hud_label_rgb24;
+use crate::input_script::RamTrigger;
+use crate::Nes;
+
+const PANEL_COLOR: [u8; 3] = [0x10, 0x10, 0x10];
+const TIME_COLOR: [u8; 3] = [0xFF, 0xFF, 0xFF];
+const DONE_COLOR: [u8; 3] = [0x58, 0xD8, 0x54];
+const PENDING_COLOR: [u8; 3] = [0x80, 0x80, 0x80];
+const PANEL_WIDTH: usize = 16 * 6 + 4;
+const LINE_HEIGHT: usize = 9;
+/// Split rows shown around the current one.
+const VISIBLE_SPLITS: usize = 5;
+
+#[derive(Debug, Clone)]
+pub struct Split {
+    pub name: String,
+    autosplit: RamTrigger,
+    /// Frames from the start when the split was reached.
+    pub time: Option<u64>,
+}
+
+/// What the timer did on a frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerEvent {
+    Started,
+    /// Split `index` was reached; the last one also finishes the run.
+    Split(usize),
+    Finished,
+    Reset,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SpeedrunTimer {
+    start: Option<RamTrigger>,
+    reset: Option<RamTrigger>,
+    splits: Vec<Split>,
+    frame: usize,
+    elapsed: u64,
+    running: bool,
+    finished: bool,
+}
+
+impl SpeedrunTimer {
+    /// Parse a splits file. Blank lines and `#` comments are skipped; errors
+    /// name the offending line. Without a `start` line the timer starts
+    /// with the first frame.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut timer = SpeedrunTimer::default();
+        for (i, line) in source.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = || format!("line {}: invalid split '{}'", i + 1, line);
+            let (head, condition) = line.split_once(" when ").ok_or_else(error)?;
+            let autosplit = RamTrigger::parse(condition).ok_or_else(error)?;
+            match head
+                .split_once(' ')
+                .map_or((head, ""), |(k, name)| (k, name.trim()))
+            {
+                ("start", "") => timer.start = Some(autosplit),
+                ("reset", "") => timer.reset = Some(autosplit),
+                ("split", name) if !name.is_empty() => timer.splits.push(Split {
+                    name: name.to_string(),
+                    autosplit,
+                    time: None,
+                }),
+                _ => return Err(error()),
+            }
+        }
+        if timer.splits.is_empty() {
+            return Err("no splits".to_string());
+        }
+        Ok(timer)
+    }
+
+    pub fn splits(&self) -> &[Split] {
+        &self.splits
+    }
+
+    /// Frames since the run started (the final time once finished).
+    pub fn elapsed(&self) -> u64 {
+        self.elapsed
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Index of the split being run for, if the run is under way.
+    pub fn current_split(&self) -> Option<usize> {
+        self.running
+            .then(|| self.splits.iter().position(|s| s.time.is_none()))
+            .flatten()
+    }
+
+    /// Run the autosplits against the machine at the start of an emulated
+    /// frame and advance the clock.
+    pub fn frame(&mut self, nes: &Nes) -> Option<TimerEvent> {
+        let frame = self.frame;
+        self.frame += 1;
+        // Every trigger sees every frame, so edges are never missed
+        let start = self.start.as_mut().map(|t| t.fired(frame, nes));
+        let reset = self.reset.as_mut().is_some_and(|t| t.fired(frame, nes));
+        let fired: Vec<bool> = self
+            .splits
+            .iter_mut()
+            .map(|s| s.autosplit.fired(frame, nes))
+            .collect();
+
+        if self.running {
+            self.elapsed += 1;
+        }
+        if reset && (self.running || self.finished) {
+            self.reset();
+            return Some(TimerEvent::Reset);
+        }
+        if !self.running && !self.finished && start.unwrap_or(true) {
+            self.running = true;
+            return Some(TimerEvent::Started);
+        }
+        self.split_if(|index| fired[index])
+    }
+
+    /// Split now, by hand; starts the run if it isn't running.
+    pub fn manual_split(&mut self) -> Option<TimerEvent> {
+        if self.finished {
+            return None;
+        }
+        if !self.running {
+            self.running = true;
+            return Some(TimerEvent::Started);
+        }
+        self.split_if(|_| true)
+    }
+
+    pub fn reset(&mut self) {
+        self.running = false;
+        self.finished = false;
+        self.elapsed = 0;
+        for split in &mut self.splits {
+            split.time = None;
+        }
+    }
+
+    fn split_if(&mut self, fired: impl Fn(usize) -> bool) -> Option<TimerEvent> {
+        let index = self.current_split()?;
+        if !fired(index) {
+            return None;
+        }
+        self.splits[index].time = Some(self.elapsed);
+        if index + 1 == self.splits.len() {
+            self.running = false;
+            self.finished = true;
+            return Some(TimerEvent::Finished);
+        }
+        Some(TimerEvent::Split(index))
+    }
+}
+
+/// `1:23.45`, or `1:02:03.45` past an hour.
+pub fn format_run_time(time: Duration) -> String {
+    let centis = time.as_millis() / 10;
+    let (hours, minutes) = (centis / 360_000, centis / 6000 % 60);
+    let (seconds, centis) = (centis / 100 % 60, centis % 100);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}.{:02}", hours, minutes, seconds, centis)
+    } else {
+        format!("{}:{:02}.{:02}", minutes, seconds, centis)
+    }
+}
+
+/// Draw the running time and the splits around the current one in the
+/// top-right corner. `frame_duration` converts frames to time.
+pub fn draw_speedrun_timer_rgb24(
+    frame: &mut [u8],
+    width: usize,
+    height: usize,
+    timer: &SpeedrunTimer,
+    frame_duration: Duration,
+) {
+    let rows = timer.splits.len().min(VISIBLE_SPLITS);
+    let panel_h = (rows + 1) * LINE_HEIGHT + 3;
+    if width < PANEL_WIDTH || height < panel_h || frame.len() < width * height * 3 {
+        return;
+    }
+    let left = width - PANEL_WIDTH;
+    for y in 0..panel_h {
+        frame[(y * width + left) * 3..(y + 1) * width * 3]
+            .chunks_exact_mut(3)
+            .for_each(|pixel| pixel.copy_from_slice(&PANEL_COLOR));
+    }
+
+    let time = |frames: u64| format_run_time(frame_duration * frames as u32);
+    let mut label = |row: usize, text: &str, color: [u8; 3]| {
+        draw_hud_label_rgb24(
+            frame,
+            width,
+            height,
+            left + 2,
+            2 + row * LINE_HEIGHT,
+            text,
+            color,
+        );
+    };
+    let total = time(timer.elapsed);
+    label(0, &format!("{:>16}", total), TIME_COLOR);
+
+    let current = timer
+        .current_split()
+        .or(timer.finished.then_some(timer.splits.len() - 1))
+        .unwrap_or(0);
+    let first = (current + 1).saturating_sub(VISIBLE_SPLITS);
+    for (row, split) in timer.splits.iter().skip(first).take(rows).enumerate() {
+        let (value, color) = match split.time {
+            Some(frames) => (time(frames), DONE_COLOR),
+            None => ("-".to_string(), PENDING_COLOR),
+        };
+        let name: String = split.name.chars().take(15 - value.len()).collect();
+        label(
+            row + 1,
+            &format!("{:<w$} {}", name, value, w = 15 - value.len()),
+            color,
+        );
+    }
+}
+
+/// A connection to LiveSplit Server (LiveSplit's TCP remote control,
+/// port 16834 by default). LiveSplit's game time is driven from the
+/// emulator's frame count so both show the same time.
+pub struct LiveSplitServer {
+    stream: TcpStream,
+    synced_second: u64,
+}
+
+impl LiveSplitServer {
+    pub fn connect(addr: &str) -> std::io::Result<Self> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        Ok(LiveSplitServer {
+            stream,
+            synced_second: 0,
+        })
+    }
+
+    /// Forward a timer event.
+    pub fn send_event(&mut self, event: TimerEvent, elapsed: Duration) -> std::io::Result<()> {
+        for command in live_split_commands(event, elapsed) {
+            self.send(&command)?;
+        }
+        Ok(())
+    }
+
+    /// Keep LiveSplit's game time in step, once a second of run time.
+    pub fn sync(&mut self, elapsed: Duration) -> std::io::Result<()> {
+        if elapsed.as_secs() != self.synced_second {
+            self.synced_second = elapsed.as_secs();
+            self.send(&format!("setgametime {}", LiveSplitTime(elapsed)))?;
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, command: &str) -> std::io::Result<()> {
+        self.stream.write_all(format!("{}\r\n", command).as_bytes())
+    }
+}
+
+/// LiveSplit Server commands for a timer event. Game time is paused and
+/// set explicitly, so it follows emulated frames.
+pub fn live_split_commands(event: TimerEvent, elapsed: Duration) -> Vec<String> {
+    let set_time = format!("setgametime {}", LiveSplitTime(elapsed));
+    match event {
+        TimerEvent::Started => vec![
+            "starttimer".to_string(),
+            "initgametime".to_string(),
+            "pausegametime".to_string(),
+        ],
+        TimerEvent::Split(_) | TimerEvent::Finished => vec![set_time, "split".to_string()],
+        TimerEvent::Reset => vec!["reset".to_string()],
+    }
+}
+
+/// A time in the `H:MM:SS.ff` form LiveSplit parses.
+struct LiveSplitTime(Duration);
+
+impl fmt::Display for LiveSplitTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let centis = self.0.as_millis() / 10;
+        write!(
+            f,
+            "{}:{:02}:{:02}.{:02}",
+            centis / 360_000,
+            centis / 6000 % 60,
+            centis / 100 % 60,
+            centis % 100
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_splits_files() {
+        let timer = SpeedrunTimer::parse(
+            "# SMB any%\nstart when ram[$0770] == 1\nsplit World 1-2 when ram[$075C] changes\n\nsplit Bowser when ram[$0772] == 3\nreset when ram[$0770] == 0\n",
+        )
+        .unwrap();
+        assert_eq!(timer.splits().len(), 2);
+        assert_eq!(timer.splits()[0].name, "World 1-2");
+        assert!(timer.start.is_some() && timer.reset.is_some());
+
+        assert_eq!(
+            SpeedrunTimer::parse("split when ram[$0770] == 1").unwrap_err(),
+            "line 1: invalid split 'split when ram[$0770] == 1'"
+        );
+        assert_eq!(
+            SpeedrunTimer::parse("start when frame > 10").unwrap_err(),
+            "no splits"
+        );
+    }
+
+    #[test]
+    fn autosplits_on_ram_changes() {
+        let mut nes = Nes::new();
+        let mut timer = SpeedrunTimer::parse(
+            "start when ram[$0010] == 1\nsplit Level when ram[$0011] changes\nsplit End when ram[$0012] >= 2\nreset when ram[$0010] == 0",
+        )
+        .unwrap();
+
+        assert_eq!(timer.frame(&nes), None);
+        nes.ram_mut()[0x10] = 1;
+        assert_eq!(timer.frame(&nes), Some(TimerEvent::Started));
+        for _ in 0..59 {
+            assert_eq!(timer.frame(&nes), None);
+        }
+        nes.ram_mut()[0x11] = 4;
+        assert_eq!(timer.frame(&nes), Some(TimerEvent::Split(0)));
+        assert_eq!(timer.splits()[0].time, Some(60));
+        nes.ram_mut()[0x12] = 2;
+        assert_eq!(timer.frame(&nes), Some(TimerEvent::Finished));
+        assert!(!timer.is_running());
+        assert_eq!(timer.frame(&nes), None);
+        assert_eq!(timer.elapsed(), 61);
+
+        nes.ram_mut()[0x10] = 0;
+        assert_eq!(timer.frame(&nes), Some(TimerEvent::Reset));
+        assert_eq!(timer.elapsed(), 0);
+        assert_eq!(timer.manual_split(), Some(TimerEvent::Started));
+    }
+
+    #[test]
+    fn formats_times_for_overlay_and_live_split() {
+        assert_eq!(format_run_time(Duration::from_millis(83_456)), "1:23.45");
+        assert_eq!(format_run_time(Duration::from_secs(3723)), "1:02:03.00");
+        assert_eq!(
+            live_split_commands(TimerEvent::Split(0), Duration::from_millis(61_500)),
+            ["setgametime 0:01:01.50", "split"]
+        );
+    }
+}
@@ -1,7 +1,86 @@
-use std::fs::{create_dir_all, File};
+use std::fmt;
+use std::fs::{create_dir_all, rename, File};
 use std::io::{Read, Result, Write};
 use std::path::{Path, PathBuf};
 
+/// What loading a save state does with battery SRAM when the state's copy
+/// differs from the cartridge's (the data the next `.sav` flush writes).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SramPolicy {
+    /// Refuse with `SramConflict` so the frontend can ask first.
+    #[default]
+    Ask,
+    /// Keep the current SRAM; everything else comes from the state.
+    Keep,
+    /// Take the state's SRAM, after writing the current one to
+    /// `<rom>.sav.bak`.
+    State,
+}
+
+impl SramPolicy {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "ask" => Some(SramPolicy::Ask),
+            "keep" => Some(SramPolicy::Keep),
+            "state" => Some(SramPolicy::State),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SramPolicy::Ask => "ask",
+            SramPolicy::Keep => "keep",
+            SramPolicy::State => "state",
+        }
+    }
+}
+
+/// When battery SRAM reaches `<rom>.sav`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SramFlush {
+    /// Only when the frontend calls `Nes::save_sram` (the SDL front-end
+    /// does every 30 seconds, on ROM swap and on exit), sparing SD cards.
+    #[default]
+    Buffered,
+    /// `Nes` writes the file itself within a second of the game changing
+    /// SRAM, so a crash loses at most that second.
+    WriteThrough,
+}
+
+impl SramFlush {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "buffered" => Some(SramFlush::Buffered),
+            "write-through" => Some(SramFlush::WriteThrough),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SramFlush::Buffered => "buffered",
+            SramFlush::WriteThrough => "write-through",
+        }
+    }
+}
+
+/// A state load stopped under `SramPolicy::Ask` because the state would
+/// replace different battery SRAM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SramConflict;
+
+impl fmt::Display for SramConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the save state's battery SRAM differs from the current save"
+        )
+    }
+}
+
+impl std::error::Error for SramConflict {}
+
 pub fn get_save_file_path(rom_path: &str) -> PathBuf {
     let path = Path::new(rom_path);
     let mut save_path = path.to_path_buf();
@@ -9,6 +88,13 @@ pub fn get_save_file_path(rom_path: &str) -> PathBuf {
     save_path
 }
 
+/// Where `SramPolicy::State` keeps the SRAM a state load replaced.
+pub fn get_backup_file_path(rom_path: &str) -> PathBuf {
+    let mut backup_path = get_save_file_path(rom_path);
+    backup_path.set_extension("sav.bak");
+    backup_path
+}
+
 pub fn load_sram(rom_path: &str) -> Result<Option<Vec<u8>>> {
     let save_path = get_save_file_path(rom_path);
 
@@ -31,9 +117,56 @@ pub fn save_sram(rom_path: &str, data: &[u8]) -> Result<()> {
         create_dir_all(parent)?;
     }
 
-    let mut file = File::create(&save_path)?;
+    // Write a temporary file and rename it over the save, so a crash
+    // mid-write leaves the old save rather than a truncated one
+    let mut temp_path = save_path.clone();
+    temp_path.set_extension("sav.tmp");
+    let mut file = File::create(&temp_path)?;
     file.write_all(data)?;
     file.sync_all()?;
+    rename(&temp_path, &save_path)?;
 
     Ok(())
 }
+
+pub fn save_sram_backup(rom_path: &str, data: &[u8]) -> Result<()> {
+    let backup_path = get_backup_file_path(rom_path);
+    let mut file = File::create(&backup_path)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempRom;
+    use crate::{Nes, SRAM_WRITE_THROUGH_FRAMES};
+
+    #[test]
+    fn write_through_saves_within_a_second() {
+        // MMC1 with battery-backed PRG-RAM
+        let mut data = vec![b'N', b'E', b'S', 0x1A, 2, 1, 0x12, 0];
+        data.resize(16, 0);
+        let mut prg = vec![0xEA; 0x8000];
+        // LDA #$42; STA $6000; JMP $8005
+        prg[0..8].copy_from_slice(&[0xA9, 0x42, 0x8D, 0x00, 0x60, 0x4C, 0x05, 0x80]);
+        prg[0x7FFC..0x7FFE].copy_from_slice(&[0x00, 0x80]);
+        data.extend_from_slice(&prg);
+        data.extend_from_slice(&[0; 0x2000]);
+        let rom = TempRom::new("sram_wt", &data);
+        let rom_path = rom.path();
+        save_sram(rom_path, &[0; 0x2000]).unwrap();
+
+        let mut nes = Nes::new();
+        nes.set_sram_flush(SramFlush::WriteThrough);
+        nes.load_rom(rom_path).unwrap();
+        let frame = |nes: &mut Nes| while !nes.step() {};
+        for _ in 0..SRAM_WRITE_THROUGH_FRAMES - 1 {
+            frame(&mut nes);
+        }
+        assert_eq!(load_sram(rom_path).unwrap().unwrap()[0], 0);
+        frame(&mut nes);
+        assert_eq!(load_sram(rom_path).unwrap().unwrap()[0], 0x42);
+    }
+}
@@ -0,0 +1,553 @@
+use crate::save_state::SaveState;
+use serde::ser::{self, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Fields that differ between any two saves and say nothing about the
+/// emulated machine.
+const IGNORED_FIELDS: [&str; 3] = ["timestamp", "thumbnail", "play_time_secs"];
+
+/// Longest run of values printed for a diverged range.
+const MAX_SHOWN_VALUES: usize = 8;
+
+/// One diverged field, or a run of adjacent diverged elements such as
+/// `ram[$0010..=$0013]`. Values are `-` where one side has no such field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    pub path: String,
+    pub left: String,
+    pub right: String,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} -> {}", self.path, self.left, self.right)
+    }
+}
+
+/// Every field where two save states disagree, in save-state field order.
+#[derive(Debug, Clone, Default)]
+pub struct StateDiff {
+    pub divergences: Vec<Divergence>,
+}
+
+impl StateDiff {
+    pub fn is_identical(&self) -> bool {
+        self.divergences.is_empty()
+    }
+
+    /// Top-level save-state fields that diverged (`ram`, `cpu_pc`,
+    /// `cartridge_state`, ...), without repeats.
+    pub fn components(&self) -> Vec<&str> {
+        let mut components: Vec<&str> = Vec::new();
+        for divergence in &self.divergences {
+            let end = divergence
+                .path
+                .find(['.', '['])
+                .unwrap_or(divergence.path.len());
+            let component = &divergence.path[..end];
+            if !components.contains(&component) {
+                components.push(component);
+            }
+        }
+        components
+    }
+}
+
+impl fmt::Display for StateDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_identical() {
+            return writeln!(f, "States are identical");
+        }
+        for divergence in &self.divergences {
+            writeln!(f, "{}", divergence)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare two save states field by field. Mapper state is walked down to
+/// individual registers and RAM/VRAM/OAM to individual bytes; adjacent
+/// differing bytes are reported as one range.
+pub fn diff_states(left: &SaveState, right: &SaveState) -> StateDiff {
+    let left = flatten(left);
+    let right = flatten(right);
+
+    let left_paths: HashMap<&str, &str> =
+        left.iter().map(|(p, v)| (p.as_str(), v.as_str())).collect();
+    let right_paths: HashMap<&str, &str> = right
+        .iter()
+        .map(|(p, v)| (p.as_str(), v.as_str()))
+        .collect();
+
+    let mut diverged = Vec::new();
+    for (path, value) in &left {
+        match right_paths.get(path.as_str()) {
+            Some(other) if other == value => {}
+            other => diverged.push((
+                path.clone(),
+                value.clone(),
+                other.unwrap_or(&"-").to_string(),
+            )),
+        }
+    }
+    for (path, value) in &right {
+        if !left_paths.contains_key(path.as_str()) {
+            diverged.push((path.clone(), "-".to_string(), value.clone()));
+        }
+    }
+    StateDiff {
+        divergences: merge_ranges(diverged),
+    }
+}
+
+/// `path[index]` split into path and index, for leaf elements only.
+fn split_index(path: &str) -> Option<(&str, usize)> {
+    let inner = path.strip_suffix(']')?;
+    let open = inner.rfind('[')?;
+    let index = usize::from_str_radix(inner[open + 1..].strip_prefix('$')?, 16).ok()?;
+    Some((&path[..open], index))
+}
+
+fn join_values(values: &[String]) -> String {
+    let mut text = values
+        .iter()
+        .take(MAX_SHOWN_VALUES)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if values.len() > MAX_SHOWN_VALUES {
+        text.push_str(" ...");
+    }
+    text
+}
+
+fn merge_ranges(diverged: Vec<(String, String, String)>) -> Vec<Divergence> {
+    // (base path, first index, last index, left values, right values)
+    type Run = (String, usize, usize, Vec<String>, Vec<String>);
+    fn finish(run: Run, out: &mut Vec<Divergence>) {
+        let (base, first, last, left, right) = run;
+        let path = if first == last {
+            format!("{}[${:04X}]", base, first)
+        } else {
+            format!("{}[${:04X}..=${:04X}]", base, first, last)
+        };
+        out.push(Divergence {
+            path,
+            left: join_values(&left),
+            right: join_values(&right),
+        });
+    }
+
+    let mut out = Vec::new();
+    let mut run: Option<Run> = None;
+    for (path, left, right) in diverged {
+        let Some((base, index)) = split_index(&path) else {
+            if let Some(run) = run.take() {
+                finish(run, &mut out);
+            }
+            out.push(Divergence { path, left, right });
+            continue;
+        };
+        match &mut run {
+            Some((run_base, _, last, lefts, rights)) if run_base == base && *last + 1 == index => {
+                *last = index;
+                lefts.push(left);
+                rights.push(right);
+            }
+            _ => {
+                if let Some(run) = run.take() {
+                    finish(run, &mut out);
+                }
+                run = Some((base.to_string(), index, index, vec![left], vec![right]));
+            }
+        }
+    }
+    if let Some(run) = run {
+        finish(run, &mut out);
+    }
+    out
+}
+
+/// Every leaf value of a save state as `(path, value)`, e.g.
+/// `("cartridge_state.mmc1.shift_register", "$10")`.
+fn flatten(state: &SaveState) -> Vec<(String, String)> {
+    let mut flattener = Flattener::default();
+    state
+        .serialize(&mut flattener)
+        .expect("save states flatten");
+    flattener.leaves.retain(|(path, _)| {
+        !IGNORED_FIELDS
+            .iter()
+            .any(|f| path == f || path.starts_with(&format!("{}[", f)))
+    });
+    flattener.leaves
+}
+
+#[derive(Debug)]
+struct FlattenError(String);
+
+impl fmt::Display for FlattenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for FlattenError {}
+
+impl ser::Error for FlattenError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        FlattenError(msg.to_string())
+    }
+}
+
+/// A serializer that records each leaf under its dotted field path, with
+/// sequence elements as `[$index]`.
+#[derive(Default)]
+struct Flattener {
+    path: Vec<String>,
+    leaves: Vec<(String, String)>,
+    /// Next element index of each open sequence, and whether it pushed an
+    /// enum variant segment that `end` has to pop.
+    sequences: Vec<(usize, bool)>,
+    map_key: Option<String>,
+}
+
+impl Flattener {
+    fn leaf(&mut self, value: String) -> Result<(), FlattenError> {
+        let mut path = String::new();
+        for segment in &self.path {
+            if !path.is_empty() && !segment.starts_with('[') {
+                path.push('.');
+            }
+            path.push_str(segment);
+        }
+        self.leaves.push((path, value));
+        Ok(())
+    }
+
+    fn open_sequence(&mut self, variant: Option<&str>) {
+        if let Some(variant) = variant {
+            self.path.push(variant.to_string());
+        }
+        self.sequences.push((0, variant.is_some()));
+    }
+
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), FlattenError> {
+        let index = self.sequences.last().map_or(0, |&(i, _)| i);
+        if let Some((next, _)) = self.sequences.last_mut() {
+            *next += 1;
+        }
+        self.path.push(format!("[${:04X}]", index));
+        value.serialize(&mut *self)?;
+        self.path.pop();
+        Ok(())
+    }
+
+    fn close_sequence(&mut self) -> Result<(), FlattenError> {
+        if let Some((_, true)) = self.sequences.pop() {
+            self.path.pop();
+        }
+        Ok(())
+    }
+
+    fn field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), FlattenError> {
+        self.path.push(key.to_string());
+        value.serialize(&mut *self)?;
+        self.path.pop();
+        Ok(())
+    }
+}
+
+impl ser::Serializer for &mut Flattener {
+    type Ok = ();
+    type Error = FlattenError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn serialize_bool(self, v: bool) -> Result<(), FlattenError> {
+        self.leaf(v.to_string())
+    }
+    fn serialize_i8(self, v: i8) -> Result<(), FlattenError> {
+        self.leaf(v.to_string())
+    }
+    fn serialize_i16(self, v: i16) -> Result<(), FlattenError> {
+        self.leaf(v.to_string())
+    }
+    fn serialize_i32(self, v: i32) -> Result<(), FlattenError> {
+        self.leaf(v.to_string())
+    }
+    fn serialize_i64(self, v: i64) -> Result<(), FlattenError> {
+        self.leaf(v.to_string())
+    }
+    fn serialize_u8(self, v: u8) -> Result<(), FlattenError> {
+        self.leaf(format!("${:02X}", v))
+    }
+    fn serialize_u16(self, v: u16) -> Result<(), FlattenError> {
+        self.leaf(format!("${:04X}", v))
+    }
+    fn serialize_u32(self, v: u32) -> Result<(), FlattenError> {
+        self.leaf(v.to_string())
+    }
+    fn serialize_u64(self, v: u64) -> Result<(), FlattenError> {
+        self.leaf(v.to_string())
+    }
+    fn serialize_f32(self, v: f32) -> Result<(), FlattenError> {
+        self.leaf(v.to_string())
+    }
+    fn serialize_f64(self, v: f64) -> Result<(), FlattenError> {
+        self.leaf(v.to_string())
+    }
+    fn serialize_char(self, v: char) -> Result<(), FlattenError> {
+        self.leaf(format!("{:?}", v))
+    }
+    fn serialize_str(self, v: &str) -> Result<(), FlattenError> {
+        self.leaf(format!("{:?}", v))
+    }
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), FlattenError> {
+        self.open_sequence(None);
+        for byte in v {
+            self.element(byte)?;
+        }
+        self.close_sequence()
+    }
+    fn serialize_none(self) -> Result<(), FlattenError> {
+        self.leaf("None".to_string())
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), FlattenError> {
+        value.serialize(self)
+    }
+    fn serialize_unit(self) -> Result<(), FlattenError> {
+        self.leaf("()".to_string())
+    }
+    fn serialize_unit_struct(self, name: &'static str) -> Result<(), FlattenError> {
+        self.leaf(name.to_string())
+    }
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<(), FlattenError> {
+        self.leaf(variant.to_string())
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), FlattenError> {
+        value.serialize(self)
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), FlattenError> {
+        self.field(variant, value)
+    }
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self, FlattenError> {
+        // Length changes (e.g. a different CHR-RAM size) show up as `len`
+        if let Some(len) = len {
+            self.path.push("len".to_string());
+            self.leaf(len.to_string())?;
+            self.path.pop();
+        }
+        self.open_sequence(None);
+        Ok(self)
+    }
+    fn serialize_tuple(self, _len: usize) -> Result<Self, FlattenError> {
+        self.open_sequence(None);
+        Ok(self)
+    }
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self, FlattenError> {
+        self.open_sequence(None);
+        Ok(self)
+    }
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, FlattenError> {
+        self.open_sequence(Some(variant));
+        Ok(self)
+    }
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self, FlattenError> {
+        Ok(self)
+    }
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self, FlattenError> {
+        Ok(self)
+    }
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self, FlattenError> {
+        self.path.push(variant.to_string());
+        Ok(self)
+    }
+}
+
+impl ser::SerializeSeq for &mut Flattener {
+    type Ok = ();
+    type Error = FlattenError;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), FlattenError> {
+        self.element(value)
+    }
+    fn end(self) -> Result<(), FlattenError> {
+        self.close_sequence()
+    }
+}
+
+impl ser::SerializeTuple for &mut Flattener {
+    type Ok = ();
+    type Error = FlattenError;
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), FlattenError> {
+        self.element(value)
+    }
+    fn end(self) -> Result<(), FlattenError> {
+        self.close_sequence()
+    }
+}
+
+impl ser::SerializeTupleStruct for &mut Flattener {
+    type Ok = ();
+    type Error = FlattenError;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), FlattenError> {
+        self.element(value)
+    }
+    fn end(self) -> Result<(), FlattenError> {
+        self.close_sequence()
+    }
+}
+
+impl ser::SerializeTupleVariant for &mut Flattener {
+    type Ok = ();
+    type Error = FlattenError;
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), FlattenError> {
+        self.element(value)
+    }
+    fn end(self) -> Result<(), FlattenError> {
+        self.close_sequence()
+    }
+}
+
+impl ser::SerializeMap for &mut Flattener {
+    type Ok = ();
+    type Error = FlattenError;
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), FlattenError> {
+        let mut flat = Flattener::default();
+        key.serialize(&mut flat)?;
+        let key = flat.leaves.into_iter().map(|(_, v)| v).collect::<Vec<_>>();
+        self.map_key = Some(format!("{{{}}}", key.join(",")));
+        Ok(())
+    }
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), FlattenError> {
+        self.path.push(self.map_key.take().unwrap_or_default());
+        value.serialize(&mut **self)?;
+        self.path.pop();
+        Ok(())
+    }
+    fn end(self) -> Result<(), FlattenError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for &mut Flattener {
+    type Ok = ();
+    type Error = FlattenError;
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), FlattenError> {
+        self.field(key, value)
+    }
+    fn end(self) -> Result<(), FlattenError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for &mut Flattener {
+    type Ok = ();
+    type Error = FlattenError;
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), FlattenError> {
+        self.field(key, value)
+    }
+    fn end(self) -> Result<(), FlattenError> {
+        self.path.pop();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::nes_with_rom;
+    use crate::Nes;
+
+    fn nes_with_mmc1() -> Nes {
+        let mut rom = vec![b'N', b'E', b'S', 0x1A, 2, 1, 0x10, 0];
+        rom.resize(16 + 0x8000 + 0x2000, 0);
+        nes_with_rom(&rom)
+    }
+
+    #[test]
+    fn reports_ram_ranges_registers_and_mapper_fields() {
+        let nes = nes_with_mmc1();
+        let before = nes.capture_state();
+        let mut after = before.clone();
+        after.timestamp += 100;
+        after.cpu_a ^= 0xFF;
+        after.ram[0x10..0x14].copy_from_slice(&[1, 2, 3, 4]);
+        after.ram[0x300] = 0x7F;
+        after.ppu_palette[3] = 0x30;
+        let mmc1 = after
+            .cartridge_state
+            .as_mut()
+            .and_then(|cs| cs.mmc1.as_mut())
+            .unwrap();
+        mmc1.shift_register ^= 0x01;
+
+        let diff = diff_states(&before, &after);
+        let paths: Vec<&str> = diff.divergences.iter().map(|d| d.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "cpu_a",
+                "ppu_palette[$0003]",
+                "ram[$0010..=$0013]",
+                "ram[$0300]",
+                "cartridge_state.mmc1.shift_register",
+            ]
+        );
+        assert_eq!(diff.divergences[2].right, "$01 $02 $03 $04");
+        assert_eq!(
+            diff.components(),
+            ["cpu_a", "ppu_palette", "ram", "cartridge_state"]
+        );
+        assert!(diff_states(&before, &before.clone()).is_identical());
+    }
+}
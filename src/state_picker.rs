@@ -0,0 +1,133 @@
+use crate::hud_toast::draw_hud_label_rgb24;
+use crate::save_state::{SlotInfo, THUMBNAIL_HEIGHT, THUMBNAIL_WIDTH};
+
+// Thumbnail origins for slots 1-4, laid out 2x2 in the 256x240 frame.
+const CELLS: [(usize, usize); 4] = [(56, 40), (136, 40), (56, 130), (136, 130)];
+const BORDER_COLOR: [u8; 3] = [0xF8, 0xB8, 0x00];
+const EMPTY_COLOR: [u8; 3] = [0x20, 0x20, 0x20];
+const LABEL_COLOR: [u8; 3] = [0xFF, 0xFF, 0xFF];
+
+/// Short play time label in the HUD font, e.g. `12M05S` or `3H07M`.
+pub fn format_play_time(secs: u64) -> String {
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}H{minutes:02}M")
+    } else {
+        format!("{minutes}M{seconds:02}S")
+    }
+}
+
+/// Dim the game frame and draw up to four save slots (thumbnail, slot
+/// number and play time) on top. `slots[i]` is slot `i + 1`.
+pub fn draw_state_picker_rgb24(
+    frame: &mut [u8],
+    width: usize,
+    height: usize,
+    slots: &[Option<SlotInfo>],
+) {
+    if width < 256 || height < 240 || frame.len() < width * height * 3 {
+        return;
+    }
+    for byte in frame[..width * height * 3].iter_mut() {
+        *byte /= 3;
+    }
+
+    for (i, &(x, y)) in CELLS.iter().enumerate() {
+        fill_rect(
+            frame,
+            width,
+            x - 1,
+            y - 1,
+            THUMBNAIL_WIDTH + 2,
+            THUMBNAIL_HEIGHT + 2,
+            BORDER_COLOR,
+        );
+        let slot = slots.get(i).and_then(|slot| slot.as_ref());
+        match slot {
+            Some(info) if info.thumbnail.len() == THUMBNAIL_WIDTH * THUMBNAIL_HEIGHT * 3 => {
+                for row in 0..THUMBNAIL_HEIGHT {
+                    let src = row * THUMBNAIL_WIDTH * 3;
+                    let dst = ((y + row) * width + x) * 3;
+                    frame[dst..dst + THUMBNAIL_WIDTH * 3]
+                        .copy_from_slice(&info.thumbnail[src..src + THUMBNAIL_WIDTH * 3]);
+                }
+            }
+            _ => fill_rect(
+                frame,
+                width,
+                x,
+                y,
+                THUMBNAIL_WIDTH,
+                THUMBNAIL_HEIGHT,
+                EMPTY_COLOR,
+            ),
+        }
+
+        let label = match slot {
+            Some(info) => format!("{} {}", i + 1, format_play_time(info.play_time_secs)),
+            None => format!("{} NONE", i + 1),
+        };
+        draw_hud_label_rgb24(
+            frame,
+            width,
+            height,
+            x,
+            y + THUMBNAIL_HEIGHT + 3,
+            &label,
+            LABEL_COLOR,
+        );
+    }
+}
+
+fn fill_rect(
+    frame: &mut [u8],
+    width: usize,
+    x: usize,
+    y: usize,
+    w: usize,
+    h: usize,
+    color: [u8; 3],
+) {
+    for py in y..y + h {
+        for px in x..x + w {
+            let idx = (py * width + px) * 3;
+            frame[idx..idx + 3].copy_from_slice(&color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_play_time() {
+        assert_eq!(format_play_time(65), "1M05S");
+        assert_eq!(format_play_time(3 * 3600 + 7 * 60 + 59), "3H07M");
+    }
+
+    #[test]
+    fn draws_thumbnail_for_occupied_slot() {
+        let mut frame = vec![0x90u8; 256 * 240 * 3];
+        let slots = [
+            None,
+            Some(SlotInfo {
+                slot: 2,
+                timestamp: 0,
+                play_time_secs: 30,
+                thumbnail: vec![0x42; THUMBNAIL_WIDTH * THUMBNAIL_HEIGHT * 3],
+            }),
+        ];
+        draw_state_picker_rgb24(&mut frame, 256, 240, &slots);
+
+        let pixel = |x: usize, y: usize| {
+            let i = (y * 256 + x) * 3;
+            [frame[i], frame[i + 1], frame[i + 2]]
+        };
+        let (x, y) = CELLS[1];
+        assert_eq!(pixel(x + 10, y + 10), [0x42; 3]);
+        assert_eq!(pixel(CELLS[0].0 + 10, CELLS[0].1 + 10), EMPTY_COLOR);
+        // The game frame behind the picker is dimmed
+        assert_eq!(pixel(0, 0), [0x30; 3]);
+    }
+}
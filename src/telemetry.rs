@@ -0,0 +1,147 @@
+// Problems worth reporting once rather than on every occurrence: JAM and
+// unknown opcodes the CPU stops on, and ROMs whose mapper is not emulated.
+// Each is aggregated by ROM and kind, with a count and the first few
+// addresses, and printed as a short summary when the emulator exits.
+use std::fmt::Write as _;
+
+use crate::cartridge::Cartridge;
+
+/// Distinct addresses kept per incident.
+const MAX_ADDRESSES: usize = 8;
+
+/// The twelve 6502 opcodes that lock up the CPU.
+const JAM_OPCODES: [u8; 12] = [
+    0x02, 0x12, 0x22, 0x32, 0x42, 0x52, 0x62, 0x72, 0x92, 0xB2, 0xD2, 0xF2,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IncidentKind {
+    Jam(u8),
+    /// An opcode the CPU has no implementation for.
+    UnknownOpcode(u8),
+    UnsupportedMapper(u8),
+}
+
+impl IncidentKind {
+    /// `Jam` or `UnknownOpcode` for an opcode the CPU halted on.
+    pub fn halting_opcode(opcode: u8) -> Self {
+        if JAM_OPCODES.contains(&opcode) {
+            IncidentKind::Jam(opcode)
+        } else {
+            IncidentKind::UnknownOpcode(opcode)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Incident {
+    /// File name of the ROM it happened in.
+    pub rom: String,
+    pub kind: IncidentKind,
+    pub count: u64,
+    /// CPU addresses it happened at, first ones first.
+    pub addresses: Vec<u16>,
+}
+
+impl Incident {
+    /// One line without the ROM name, e.g. `JAM $02 x3 at $C000 $C123` or
+    /// `unsupported mapper 6 (FFE F4xxx)`.
+    pub fn describe(&self) -> String {
+        let mut text = match self.kind {
+            IncidentKind::Jam(opcode) => format!("JAM ${:02X}", opcode),
+            IncidentKind::UnknownOpcode(opcode) => format!("unknown opcode ${:02X}", opcode),
+            IncidentKind::UnsupportedMapper(mapper) => match Cartridge::board_name(mapper) {
+                Some(board) => format!("unsupported mapper {} ({})", mapper, board),
+                None => format!("unsupported mapper {}", mapper),
+            },
+        };
+        if self.count > 1 {
+            let _ = write!(text, " x{}", self.count);
+        }
+        if !self.addresses.is_empty() {
+            text += " at";
+            for addr in &self.addresses {
+                let _ = write!(text, " ${:04X}", addr);
+            }
+        }
+        text
+    }
+}
+
+/// Incidents for a session, in the order first seen.
+#[derive(Debug, Clone, Default)]
+pub struct Telemetry {
+    incidents: Vec<Incident>,
+}
+
+impl Telemetry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, rom: &str, kind: IncidentKind, addr: Option<u16>) {
+        let index = match self
+            .incidents
+            .iter()
+            .position(|i| i.kind == kind && i.rom == rom)
+        {
+            Some(index) => index,
+            None => {
+                self.incidents.push(Incident {
+                    rom: rom.to_string(),
+                    kind,
+                    count: 0,
+                    addresses: Vec::new(),
+                });
+                self.incidents.len() - 1
+            }
+        };
+        let incident = &mut self.incidents[index];
+        incident.count += 1;
+        if let Some(addr) = addr {
+            if incident.addresses.len() < MAX_ADDRESSES && !incident.addresses.contains(&addr) {
+                incident.addresses.push(addr);
+            }
+        }
+    }
+
+    pub fn incidents(&self) -> &[Incident] {
+        &self.incidents
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.incidents.is_empty()
+    }
+
+    /// `rom: description` lines, one per incident.
+    pub fn report(&self) -> Vec<String> {
+        self.incidents
+            .iter()
+            .map(|i| format!("{}: {}", i.rom, i.describe()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregates_repeats() {
+        let mut telemetry = Telemetry::new();
+        for addr in [0xC000, 0xC123, 0xC000] {
+            telemetry.record("a.nes", IncidentKind::halting_opcode(0x02), Some(addr));
+        }
+        telemetry.record("b.nes", IncidentKind::UnsupportedMapper(6), None);
+        telemetry.record("b.nes", IncidentKind::halting_opcode(0x80), Some(0x8000));
+
+        assert_eq!(
+            telemetry.report(),
+            [
+                "a.nes: JAM $02 x3 at $C000 $C123",
+                "b.nes: unsupported mapper 6 (FFE F4xxx)",
+                "b.nes: unknown opcode $80 at $8000",
+            ]
+        );
+    }
+}
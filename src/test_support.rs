@@ -0,0 +1,53 @@
+// Fixtures shared by the unit tests: ROM images built in the test go
+// through the real loader from memory, with no trip through a temp file.
+// `TempRom` covers the tests that need the file itself.
+use std::path::PathBuf;
+
+use crate::bus::Bus;
+use crate::cartridge::Cartridge;
+use crate::Nes;
+
+/// Run `rom` through the real loader.
+pub(crate) fn load_rom_image(rom: &[u8]) -> std::io::Result<Cartridge> {
+    Cartridge::from_bytes(rom.to_vec())
+}
+
+/// A bus with `rom` plugged in.
+pub(crate) fn bus_with_rom(rom: &[u8]) -> Bus {
+    let mut bus = Bus::new();
+    bus.load_cartridge(load_rom_image(rom).unwrap());
+    bus
+}
+
+/// A console powered on with `rom`.
+pub(crate) fn nes_with_rom(rom: &[u8]) -> Nes {
+    let mut nes = Nes::new();
+    nes.load_rom_bytes("test.nes", rom).unwrap();
+    nes
+}
+
+/// `rom` written to a temp file, for tests that need a path: SRAM saved
+/// next to the ROM, file watching, tools that take a path. The file and
+/// its `.sav` are removed on drop.
+pub(crate) struct TempRom {
+    path: PathBuf,
+}
+
+impl TempRom {
+    pub(crate) fn new(name: &str, rom: &[u8]) -> Self {
+        let path = std::env::temp_dir().join(format!("nes_{}_{}.nes", name, std::process::id()));
+        std::fs::write(&path, rom).unwrap();
+        TempRom { path }
+    }
+
+    pub(crate) fn path(&self) -> &str {
+        self.path.to_str().unwrap()
+    }
+}
+
+impl Drop for TempRom {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(crate::sram::get_save_file_path(self.path()));
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
@@ -0,0 +1,90 @@
+// Window title text: the running game, its DAT verification status, the
+// measured frame rate and the save state slot last used, e.g.
+// `Zelda no Densetsu [verified] - 60 FPS - Slot 2`.
+use std::time::{Duration, Instant};
+
+use crate::rom_dat::DumpStatus;
+
+/// Real frames presented per second, counted over one-second windows.
+pub struct FpsCounter {
+    window_start: Instant,
+    frames: u32,
+    fps: Option<f64>,
+}
+
+impl FpsCounter {
+    pub fn new(now: Instant) -> Self {
+        FpsCounter {
+            window_start: now,
+            frames: 0,
+            fps: None,
+        }
+    }
+
+    /// Count a presented frame; `true` when a new reading is available.
+    pub fn frame(&mut self, now: Instant) -> bool {
+        self.frames += 1;
+        let elapsed = now.saturating_duration_since(self.window_start);
+        if elapsed < Duration::from_secs(1) {
+            return false;
+        }
+        self.fps = Some(self.frames as f64 / elapsed.as_secs_f64());
+        self.frames = 0;
+        self.window_start = now;
+        true
+    }
+
+    /// `None` until the first second has passed.
+    pub fn fps(&self) -> Option<f64> {
+        self.fps
+    }
+}
+
+pub fn window_title(
+    game: &str,
+    dump: Option<&DumpStatus>,
+    fps: Option<f64>,
+    slot: Option<u8>,
+) -> String {
+    let mut title = if game.is_empty() {
+        "NES Emulator".to_string()
+    } else {
+        game.to_string()
+    };
+    if let Some(dump) = dump.filter(|_| !game.is_empty()) {
+        title += &format!(" [{}]", dump.tag());
+    }
+    if let Some(fps) = fps {
+        title += &format!(" - {:.0} FPS", fps);
+    }
+    if let Some(slot) = slot {
+        title += &format!(" - Slot {}", slot);
+    }
+    title
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_fps_and_formats_the_title() {
+        let start = Instant::now();
+        let mut counter = FpsCounter::new(start);
+        for i in 1..60 {
+            assert!(!counter.frame(start + Duration::from_millis(i * 1000 / 60)));
+        }
+        assert!(counter.frame(start + Duration::from_secs(1)));
+        assert_eq!(counter.fps(), Some(60.0));
+
+        assert_eq!(window_title("", None, None, None), "NES Emulator");
+        assert_eq!(
+            window_title("ドラゴンクエスト", None, counter.fps(), Some(2)),
+            "ドラゴンクエスト - 60 FPS - Slot 2"
+        );
+        assert_eq!(
+            window_title("Zelda", Some(&DumpStatus::Unknown), None, None),
+            "Zelda [unknown dump]"
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Golden audio: renders register-scripted APU output and compares it with
+//! stored fingerprints, so a change that alters the sound fails here.
+//! Run with: cargo test --test apu_golden -- --nocapture
+//! Samples are quantized to 16 bits before hashing. RMS and zero crossings
+//! are printed with each mismatch as a rough loudness and pitch guide.
+
+use nes_emulator::apu::Apu;
+use nes_emulator::crc32::crc32;
+
+const GOLDEN: &str = "tests/apu_reference/golden.txt";
+const CPU_CLOCK: u64 = 1_789_773;
+
+/// A register write `at` milliseconds into the run.
+struct Write {
+    at: u64,
+    addr: u16,
+    value: u8,
+}
+
+const fn w(at: u64, addr: u16, value: u8) -> Write {
+    Write { at, addr, value }
+}
+
+/// `(name, length in ms, writes in time order)`
+const SCRIPTS: &[(&str, u64, &[Write])] = &[
+    (
+        // 50% duty, constant volume, sweeping up then cut by the sweep
+        "pulse_sweep",
+        1000,
+        &[
+            w(0, 0x4015, 0x01),
+            w(0, 0x4000, 0xBF),
+            w(0, 0x4001, 0xA3),
+            w(0, 0x4002, 0x00),
+            w(0, 0x4003, 0x02),
+        ],
+    ),
+    (
+        // 12.5% duty with a decaying envelope, retriggered; length counter
+        // stops the last note
+        "pulse_envelope_length",
+        1000,
+        &[
+            w(0, 0x4015, 0x02),
+            w(0, 0x4004, 0x04),
+            w(0, 0x4006, 0xFD),
+            w(0, 0x4007, 0xF8),
+            w(300, 0x4004, 0x42),
+            w(300, 0x4007, 0x19),
+            w(600, 0x4004, 0x0F),
+            w(600, 0x4007, 0x18),
+        ],
+    ),
+    (
+        // Linear counter cut-offs, an endless note and an ultrasonic period
+        "triangle_linear",
+        1000,
+        &[
+            w(0, 0x4015, 0x04),
+            w(0, 0x4008, 0x20),
+            w(0, 0x400A, 0xFD),
+            w(0, 0x400B, 0xF8),
+            w(250, 0x4008, 0x7F),
+            w(250, 0x400B, 0x00),
+            w(500, 0x4008, 0x80),
+            w(500, 0x400A, 0x7E),
+            w(500, 0x400B, 0x01),
+            w(800, 0x400A, 0x01),
+        ],
+    ),
+    (
+        // Long then short (93-step) noise, then an envelope
+        "noise_modes",
+        1000,
+        &[
+            w(0, 0x4015, 0x08),
+            w(0, 0x400C, 0x3A),
+            w(0, 0x400E, 0x04),
+            w(0, 0x400F, 0x08),
+            w(400, 0x400E, 0x84),
+            w(700, 0x400C, 0x03),
+            w(700, 0x400E, 0x0A),
+            w(700, 0x400F, 0x08),
+        ],
+    ),
+    (
+        // Sawtooth written straight to the DMC DAC
+        "dmc_dac",
+        250,
+        &[
+            w(0, 0x4011, 0x00),
+            w(20, 0x4011, 0x10),
+            w(40, 0x4011, 0x20),
+            w(60, 0x4011, 0x30),
+            w(80, 0x4011, 0x40),
+            w(100, 0x4011, 0x50),
+            w(120, 0x4011, 0x60),
+            w(140, 0x4011, 0x70),
+            w(160, 0x4011, 0x00),
+            w(180, 0x4011, 0x7F),
+            w(200, 0x4011, 0x00),
+        ],
+    ),
+    (
+        // Everything at once in 5-step mode, through the non-linear mixer
+        "mixed_five_step",
+        2000,
+        &[
+            w(0, 0x4017, 0x80),
+            w(0, 0x4015, 0x0F),
+            w(0, 0x4000, 0x7C),
+            w(0, 0x4002, 0xAB),
+            w(0, 0x4003, 0x01),
+            w(0, 0x4004, 0xB8),
+            w(0, 0x4006, 0x53),
+            w(0, 0x4007, 0x01),
+            w(0, 0x4008, 0xFF),
+            w(0, 0x400A, 0xA9),
+            w(0, 0x400B, 0x02),
+            w(0, 0x400C, 0x36),
+            w(0, 0x400E, 0x08),
+            w(0, 0x400F, 0x00),
+            w(0, 0x4011, 0x40),
+            w(500, 0x4002, 0x1C),
+            w(500, 0x4005, 0x8A),
+            w(1000, 0x4015, 0x05),
+            w(1500, 0x4015, 0x0F),
+            w(1500, 0x4003, 0x00),
+            w(1500, 0x400F, 0x00),
+        ],
+    ),
+];
+
+/// Checksum of the 16-bit samples, RMS, zero crossings.
+#[derive(Debug, PartialEq)]
+struct Fingerprint {
+    crc32: u32,
+    rms: f64,
+    crossings: usize,
+}
+
+impl Fingerprint {
+    fn of(samples: &[f32]) -> Self {
+        let bytes: Vec<u8> = samples
+            .iter()
+            .flat_map(|&s| ((s.clamp(-1.0, 1.0) * 32767.0).round() as i16).to_le_bytes())
+            .collect();
+        let power = samples.iter().map(|&s| s as f64 * s as f64).sum::<f64>();
+        Fingerprint {
+            crc32: crc32(&bytes),
+            rms: (power / samples.len().max(1) as f64).sqrt(),
+            crossings: samples
+                .windows(2)
+                .filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0))
+                .count(),
+        }
+    }
+
+    fn line(&self, name: &str) -> String {
+        format!(
+            "{} {:08X} {:.4} {}",
+            name, self.crc32, self.rms, self.crossings
+        )
+    }
+}
+
+fn render(length_ms: u64, writes: &[Write]) -> Vec<f32> {
+    let mut apu = Apu::new();
+    let mut samples = Vec::new();
+    let mut pending = writes.iter().peekable();
+    for cycle in 0..length_ms * CPU_CLOCK / 1000 {
+        while let Some(write) = pending.next_if(|write| write.at * CPU_CLOCK / 1000 <= cycle) {
+            apu.write_register(write.addr, write.value);
+        }
+        apu.step();
+        if cycle % 4096 == 0 {
+            samples.extend(apu.get_audio_buffer());
+        }
+    }
+    samples.extend(apu.get_audio_buffer());
+    samples
+}
+
+#[test]
+fn apu_output_matches_golden_fingerprints() {
+    let golden = std::fs::read_to_string(GOLDEN).expect("missing golden audio file");
+    // (name, CRC32, whole line)
+    let expected: Vec<(&str, u32, &str)> = golden
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let crc32 = fields
+                .get(1)
+                .and_then(|crc| u32::from_str_radix(crc, 16).ok())
+                .unwrap_or_else(|| panic!("bad golden line: {}", line));
+            (fields[0], crc32, line)
+        })
+        .collect();
+
+    let mut failures = Vec::new();
+    for &(name, length_ms, writes) in SCRIPTS {
+        let fingerprint = Fingerprint::of(&render(length_ms, writes));
+        let line = fingerprint.line(name);
+        match expected.iter().find(|(golden, _, _)| *golden == name) {
+            Some(&(_, crc32, _)) if crc32 == fingerprint.crc32 => println!("{}: match", name),
+            Some(&(_, _, stored)) => {
+                println!(
+                    "{}: differs\n  golden: {}\n  now:    {}",
+                    name, stored, line
+                );
+                failures.push(name);
+            }
+            None => {
+                println!("{}: no golden entry; add\n  {}", name, line);
+                failures.push(name);
+            }
+        }
+    }
+    assert!(failures.is_empty(), "audio differs: {:?}", failures);
+}
@@ -0,0 +1,65 @@
+//! Runs PPU timing test ROMs and compares frames with stored references.
+//! Run with: cargo test --test ppu_timing -- --nocapture
+//! Mismatches print a map of the differing 8x8 cells and write an overlay
+//! (reference in gray, differing pixels in red) to target/ppu_diff/.
+
+use nes_emulator::frame_diff::{diff_frames, load_ppm};
+use nes_emulator::png::write_rgb24_png;
+use nes_emulator::Nes;
+use std::path::Path;
+
+const MANIFEST: &str = "tests/ppu_reference/manifest.txt";
+const DIFF_DIR: &str = "target/ppu_diff";
+
+#[test]
+fn ppu_frames_match_references() {
+    let manifest = std::fs::read_to_string(MANIFEST).expect("missing PPU reference manifest");
+    let mut failures = Vec::new();
+
+    for line in manifest.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [rom, frame, reference] = fields[..] else {
+            panic!("bad manifest line: {}", line);
+        };
+        let frame: u32 = frame.parse().expect("bad frame number in manifest");
+        if !Path::new(rom).exists() || !Path::new(reference).exists() {
+            eprintln!("{}: ROM or reference not found, skipping", rom);
+            continue;
+        }
+
+        let (width, height, expected) = load_ppm(reference).unwrap();
+        let mut nes = Nes::new();
+        nes.load_rom(rom).unwrap();
+        for _ in 0..=frame {
+            while !nes.step() {}
+        }
+
+        let diff = diff_frames(width, height, nes.get_frame_buffer(), &expected);
+        if diff.is_match() {
+            println!("{} frame {}: match", rom, frame);
+            continue;
+        }
+        let (left, top, right, bottom) = diff.bounds().unwrap();
+        println!(
+            "{} frame {}: {} pixels differ in ({},{})-({},{})\n{}",
+            rom,
+            frame,
+            diff.mismatched_pixels(),
+            left,
+            top,
+            right,
+            bottom,
+            diff.text_map()
+        );
+        std::fs::create_dir_all(DIFF_DIR).unwrap();
+        let stem = Path::new(reference).file_stem().unwrap().to_string_lossy();
+        let overlay = format!("{}/{}.png", DIFF_DIR, stem);
+        write_rgb24_png(&overlay, width, height, &diff.overlay_rgb24(&expected)).unwrap();
+        failures.push(format!("{} frame {} (see {})", rom, frame, overlay));
+    }
+
+    assert!(failures.is_empty(), "frames differ: {:#?}", failures);
+}
@@ -0,0 +1,30 @@
+//! The core runs on a thread other than the one that built it, and can be
+//! shared behind a `Mutex`. `Send` itself is checked at compile time in
+//! lib.rs; this exercises it, with the threaded PPU's worker along for the
+//! ride.
+
+use nes_emulator::Nes;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn nes_moves_between_threads() {
+    let mut nes = Nes::new();
+    nes.set_threaded_rendering(true);
+    let nes = std::thread::spawn(move || {
+        for _ in 0..2 {
+            while !nes.step() {}
+        }
+        nes
+    })
+    .join()
+    .unwrap();
+    assert!(nes.clock().frame >= 2);
+
+    let shared = Arc::new(Mutex::new(nes));
+    let worker = {
+        let shared = Arc::clone(&shared);
+        std::thread::spawn(move || while !shared.lock().unwrap().step() {})
+    };
+    worker.join().unwrap();
+    assert!(shared.lock().unwrap().clock().frame >= 3);
+}